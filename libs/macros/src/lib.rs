@@ -33,9 +33,9 @@ pub fn traced(attrs: TokenStream, item: TokenStream) -> TokenStream {
     let fmt_after = format!("{}{{:?}} -> {{:?}}", name);
     let stmts = &fun.block.stmts;
     let block = quote! {{
-        log::trace!(target: #target, #fmt_before, (#(#args)*,));
+        tracing::trace!(target: #target, #fmt_before, (#(#args)*,));
         let result = { #(#stmts)* };
-        log::trace!(target: #target, #fmt_after, (#(#args)*,), result);
+        tracing::trace!(target: #target, #fmt_after, (#(#args)*,), result);
         result
     }
     };
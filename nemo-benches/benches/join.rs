@@ -0,0 +1,58 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use rand::prelude::*;
+use rand_pcg::Pcg64;
+
+use nemo_physical::columnar::{
+    column_types::vector::{ColumnScanVector, ColumnVector},
+    operations::{ColumnScanJoin, ColumnScanVectorJoin},
+    traits::columnscan::ColumnScanCell,
+};
+
+/// Create a sorted column of distinct values, each of which is contained with the given probability.
+fn sparse_column(rng: &mut Pcg64, max: u64, probability: f64) -> ColumnVector<u64> {
+    ColumnVector::new((0..max).filter(|_| rng.gen_bool(probability)).collect())
+}
+
+pub fn benchmark_join(c: &mut Criterion) {
+    let mut rng = Pcg64::seed_from_u64(21564);
+    let columns = [
+        sparse_column(&mut rng, 4_000_000, 0.5),
+        sparse_column(&mut rng, 4_000_000, 0.25),
+        sparse_column(&mut rng, 4_000_000, 0.75),
+    ];
+
+    let mut group = c.benchmark_group("join");
+    group.sample_size(50);
+
+    group.bench_function("join_generic", |b| {
+        b.iter(|| {
+            let scans: Vec<ColumnScanCell<u64>> = columns
+                .iter()
+                .map(|column| ColumnScanVector::new(column).into())
+                .collect();
+            let join = ColumnScanJoin::new(scans.iter().collect());
+            black_box(join.count())
+        })
+    });
+
+    group.bench_function("join_vector", |b| {
+        b.iter(|| {
+            let scans: Vec<ColumnScanCell<u64>> = columns
+                .iter()
+                .map(|column| ColumnScanVector::new(column).into())
+                .collect();
+            let join = ColumnScanVectorJoin::new(
+                scans
+                    .iter()
+                    .map(|scan| scan.vector_scan().expect("scan is a vector scan"))
+                    .collect(),
+            );
+            black_box(join.count())
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, benchmark_join);
+criterion_main!(benches);
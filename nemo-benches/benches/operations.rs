@@ -10,7 +10,7 @@ use nemo_physical::{
         PhysicalBuilderProxyEnum, PhysicalColumnBuilderProxy, PhysicalStringColumnBuilderProxy,
     },
     datatypes::{storage_value::VecT, DataTypeName},
-    dictionary::PrefixedStringDictionary,
+    management::database::Dict,
     table_reader::TableReader,
     tabular::{
         operations::{
@@ -28,11 +28,7 @@ use nemo::{
 };
 
 // NOTE: See TableStorage::load_from_disk
-fn load_trie(
-    source: &NativeDataSource,
-    arity: usize,
-    dict: &RefCell<PrefixedStringDictionary>,
-) -> Trie {
+fn load_trie(source: &NativeDataSource, arity: usize, dict: &RefCell<Dict>) -> Trie {
     match source {
         NativeDataSource::DsvFile(dsv_file) => {
             // Using fallback solution to treat everything as string for now (storing as u64 internally)
@@ -47,6 +43,7 @@ fn load_trie(
                         PhysicalStringColumnBuilderProxy::new(dict),
                     ),
                     DataTypeName::I64 => PhysicalBuilderProxyEnum::I64(Default::default()),
+                    DataTypeName::U128 => PhysicalBuilderProxyEnum::U128(Default::default()),
                     DataTypeName::U64 => PhysicalBuilderProxyEnum::U64(Default::default()),
                     DataTypeName::U32 => PhysicalBuilderProxyEnum::U32(Default::default()),
                     DataTypeName::Float => PhysicalBuilderProxyEnum::Float(Default::default()),
@@ -64,6 +61,7 @@ fn load_trie(
                 .map(|bp| match bp {
                     PhysicalBuilderProxyEnum::String(bp) => bp.finalize(),
                     PhysicalBuilderProxyEnum::I64(bp) => bp.finalize(),
+                    PhysicalBuilderProxyEnum::U128(bp) => bp.finalize(),
                     PhysicalBuilderProxyEnum::U64(bp) => bp.finalize(),
                     PhysicalBuilderProxyEnum::U32(bp) => bp.finalize(),
                     PhysicalBuilderProxyEnum::Float(bp) => bp.finalize(),
@@ -85,7 +83,7 @@ fn load_trie(
 }
 
 pub fn benchmark_join(c: &mut Criterion) {
-    let dict = RefCell::new(PrefixedStringDictionary::default());
+    let dict = RefCell::new(Dict::default());
 
     let table_a_arity = 3;
     let table_a = NativeDataSource::DsvFile(DsvFile::csv_file(
@@ -186,7 +184,7 @@ pub fn benchmark_join(c: &mut Criterion) {
 }
 
 fn benchmark_project(c: &mut Criterion) {
-    let dict = RefCell::new(PrefixedStringDictionary::default());
+    let dict = RefCell::new(Dict::default());
 
     let table_a_arity = 3;
     let table_a = NativeDataSource::DsvFile(DsvFile::csv_file(
@@ -285,7 +283,7 @@ fn benchmark_union(c: &mut Criterion) {
     const FILE_NAME: &str = "test-files/bench-data/aux-split/aux";
     const NUM_PARTS: usize = 10;
 
-    let dict = RefCell::new(PrefixedStringDictionary::default());
+    let dict = RefCell::new(Dict::default());
 
     let mut tries = Vec::<Trie>::new();
     let mut frames = Vec::<DataFrame>::new();
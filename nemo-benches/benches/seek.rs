@@ -72,6 +72,30 @@ pub fn benchmark_seek(c: &mut Criterion) {
             },
         )
     });
+    // Seeking forward by small distances, as done in joins,
+    // should not cost a search over all remaining runs
+    let seek_targets: Vec<usize> = data.iter().step_by(1000).copied().collect();
+    group_rle.bench_function("seek_vec_sequence", |b| {
+        b.iter_with_setup(
+            || test_column.iter(),
+            |mut gcs| {
+                for &target in &seek_targets {
+                    black_box(gcs.seek(target));
+                }
+            },
+        )
+    });
+
+    group_rle.bench_function("seek_rle_sequence", |b| {
+        b.iter_with_setup(
+            || rle_test_column.iter(),
+            |mut rcs| {
+                for &target in &seek_targets {
+                    black_box(rcs.seek(target));
+                }
+            },
+        )
+    });
     group_rle.finish();
 }
 
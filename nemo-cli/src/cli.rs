@@ -1,21 +1,26 @@
 //! Contains structures and functionality for the binary
 use std::path::PathBuf;
 
-use nemo::{error::Error, io::OutputFileManager};
+use nemo::{
+    error::Error,
+    io::{FileSplitting, OutputFileManager, OutputPolicy},
+};
 
 const DEFAULT_OUTPUT_DIRECTORY: &str = "results";
+const DEFAULT_ANONYMIZED_DIRECTORY: &str = "anonymized";
+const DEFAULT_REDUCED_DIRECTORY: &str = "reduced";
 
 /// Cli Arguments related to logging
-#[derive(clap::Args, Debug)]
+#[derive(clap::Args, Debug, Clone)]
 pub struct LoggingArgs {
     /// Sets the verbosity of logging if the flags -v and -q are not used
-    #[arg(long = "log", value_parser=clap::builder::PossibleValuesParser::new(["error", "warn", "info", "debug", "trace"]), group = "verbosity")]
+    #[arg(long = "log", value_parser=clap::builder::PossibleValuesParser::new(["error", "warn", "info", "debug", "trace"]), group = "verbosity", global = true)]
     log_level: Option<String>,
     /// Sets log verbosity (multiple times means more verbose)
-    #[arg(short, long, action = clap::builder::ArgAction::Count, group = "verbosity")]
+    #[arg(short, long, action = clap::builder::ArgAction::Count, group = "verbosity", global = true)]
     verbose: u8,
     /// Sets log verbosity to only log errors
-    #[arg(short, long, group = "verbosity")]
+    #[arg(short, long, group = "verbosity", global = true)]
     quiet: bool,
 }
 
@@ -29,6 +34,7 @@ impl LoggingArgs {
     ///  * `Warn` otherwise
     pub fn initialize_logging(&self) {
         let mut builder = env_logger::Builder::new();
+        builder.filter_level(log::LevelFilter::Warn);
         builder.parse_env("NMO_LOG");
         if let Some(ref level) = self.log_level {
             builder.parse_filters(level);
@@ -46,8 +52,126 @@ impl LoggingArgs {
     }
 }
 
+/// Cli arguments related to exporting traces via OpenTelemetry
+#[cfg(feature = "otlp")]
+#[derive(clap::Args, Debug, Clone)]
+pub struct TracingArgs {
+    /// Export traces of the reasoning process to the OTLP/HTTP collector at the given endpoint (e.g. http://localhost:4318)
+    #[arg(long = "otlp-endpoint", global = true)]
+    otlp_endpoint: Option<String>,
+}
+
+#[cfg(feature = "otlp")]
+impl TracingArgs {
+    /// Initialising the export of traces
+    ///
+    /// Does nothing if no endpoint has been given.
+    /// Log messages are still handled by the logger set up in [`LoggingArgs::initialize_logging`].
+    pub fn initialize_tracing(&self) {
+        use opentelemetry::KeyValue;
+        use opentelemetry_otlp::WithExportConfig;
+        use tracing_subscriber::layer::SubscriberExt;
+
+        let Some(endpoint) = &self.otlp_endpoint else {
+            return;
+        };
+
+        let tracer = opentelemetry_otlp::new_pipeline()
+            .tracing()
+            .with_exporter(
+                opentelemetry_otlp::new_exporter()
+                    .http()
+                    .with_endpoint(endpoint),
+            )
+            .with_trace_config(opentelemetry_sdk::trace::config().with_resource(
+                opentelemetry_sdk::Resource::new(vec![KeyValue::new("service.name", "nmo")]),
+            ))
+            .install_simple();
+
+        let tracer = match tracer {
+            Ok(tracer) => tracer,
+            Err(err) => {
+                log::warn!("Could not set up the export of traces: {err}");
+                return;
+            }
+        };
+
+        let subscriber =
+            tracing_subscriber::registry().with(tracing_opentelemetry::layer().with_tracer(tracer));
+        if let Err(err) = tracing::subscriber::set_global_default(subscriber) {
+            log::warn!("Could not set up the export of traces: {err}");
+        }
+    }
+
+    /// Send all remaining traces to the collector
+    pub fn finish_tracing(&self) {
+        if self.otlp_endpoint.is_some() {
+            opentelemetry::global::shutdown_tracer_provider();
+        }
+    }
+}
+
+/// Cli arguments related to continuous reasoning over Kafka topics
+#[cfg(feature = "kafka")]
+#[derive(clap::Args, Debug, Clone)]
+pub struct KafkaArgs {
+    /// Reason continuously over facts consumed from the Kafka brokers at the given address (e.g. localhost:9092)
+    #[arg(long = "kafka-brokers", requires = "kafka_inputs")]
+    pub brokers: Option<String>,
+    /// Feed an external source with the facts from a topic,
+    /// e.g. `edges=graph` for `@source edge[2]: external("graph") .`
+    #[arg(long = "kafka-input", value_name = "TOPIC=SOURCE", value_parser = parse_kafka_input, requires = "brokers")]
+    pub kafka_inputs: Vec<(String, String)>,
+    /// Publish the changes of the output predicates to this topic
+    #[arg(long = "kafka-output", requires = "brokers")]
+    pub output_topic: Option<String>,
+    /// Format of the payload of the consumed messages
+    #[arg(
+        long = "kafka-format",
+        value_parser = clap::builder::PossibleValuesParser::new(["json", "csv"]),
+        default_value = "json"
+    )]
+    pub format: String,
+    /// Consumer group used to consume the topics
+    #[arg(long = "kafka-group", default_value = "nemo")]
+    pub group_id: String,
+}
+
+#[cfg(feature = "kafka")]
+impl KafkaArgs {
+    /// Creates the Kafka stream described by the arguments, unless no brokers are given
+    pub fn stream(&self) -> Option<nemo::io::kafka::KafkaStream> {
+        use nemo::io::kafka::{KafkaStream, PayloadFormat};
+
+        let brokers = self.brokers.as_ref()?;
+
+        let format = match self.format.as_str() {
+            "csv" => PayloadFormat::Csv,
+            _ => PayloadFormat::Json,
+        };
+        let stream = self.kafka_inputs.iter().fold(
+            KafkaStream::new(brokers, &self.group_id).with_format(format),
+            |stream, (topic, source)| stream.with_input(topic, source),
+        );
+
+        Some(match &self.output_topic {
+            Some(topic) => stream.with_output(topic),
+            None => stream,
+        })
+    }
+}
+
+/// Parses a Kafka topic and the name of the external source it feeds
+#[cfg(feature = "kafka")]
+fn parse_kafka_input(argument: &str) -> Result<(String, String), String> {
+    argument
+        .split_once('=')
+        .map(|(topic, source)| (topic.to_string(), source.to_string()))
+        .ok_or_else(|| format!("expected TOPIC=SOURCE, found `{argument}`"))
+}
+
 /// Cli arguments related to file output
-#[derive(Debug, clap::Args)]
+#[derive(Debug, Clone, clap::Args)]
 pub struct OutputArgs {
     /// Save results to files. (Also see --output-dir)
     #[arg(short, long = "save-results")]
@@ -63,6 +187,20 @@ pub struct OutputArgs {
         requires = "save_results"
     )]
     overwrite: bool,
+    /// Append results to existing files in --output-dir. (Only relevant if --save-results is set.)
+    #[arg(
+        long = "append-results",
+        requires = "save_results",
+        conflicts_with_all = ["overwrite", "versioned"]
+    )]
+    append: bool,
+    /// Write results into a new subdirectory of --output-dir, named after the current time. (Only relevant if --save-results is set.)
+    #[arg(
+        long = "versioned-results",
+        requires = "save_results",
+        conflicts_with = "overwrite"
+    )]
+    versioned: bool,
     /// Gzip output files
     #[arg(
         short,
@@ -71,6 +209,29 @@ pub struct OutputArgs {
         requires = "save_results"
     )]
     gz: bool,
+    /// Split the results of each predicate into files with at most this many rows
+    #[arg(
+        long = "split-rows",
+        value_name = "ROWS",
+        requires = "save_results",
+        conflicts_with_all = ["split_size", "split_column"]
+    )]
+    split_rows: Option<usize>,
+    /// Split the results of each predicate into files of at most this many bytes (before compression)
+    #[arg(
+        long = "split-size",
+        value_name = "BYTES",
+        requires = "save_results",
+        conflicts_with = "split_column"
+    )]
+    split_size: Option<u64>,
+    /// Split the results of each predicate into one file per value of the column with this index (starting at 0)
+    #[arg(
+        long = "split-by-column",
+        value_name = "COLUMN",
+        requires = "save_results"
+    )]
+    split_column: Option<usize>,
 }
 
 impl OutputArgs {
@@ -94,24 +255,212 @@ impl OutputArgs {
             return Ok(None);
         }
 
-        Ok(Some(OutputFileManager::try_new(
-            self.output_directory,
-            self.overwrite,
-            self.gz,
-        )?))
+        let splitting = if let Some(rows) = self.split_rows {
+            FileSplitting::MaxRows(rows)
+        } else if let Some(bytes) = self.split_size {
+            FileSplitting::MaxBytes(bytes)
+        } else if let Some(column) = self.split_column {
+            FileSplitting::ByColumn(column)
+        } else {
+            FileSplitting::None
+        };
+
+        let policy = if self.overwrite {
+            OutputPolicy::Overwrite
+        } else if self.append {
+            OutputPolicy::Append
+        } else if self.versioned {
+            OutputPolicy::Versioned
+        } else {
+            OutputPolicy::FailIfExists
+        };
+
+        let output_manager =
+            OutputFileManager::try_new(self.output_directory, self.overwrite, self.gz)?
+                .with_splitting(splitting)
+                .with_policy(policy);
+        log::info!("Writing results to {:?}", output_manager.output_directory());
+
+        Ok(Some(output_manager))
     }
 }
 
-/// Nemo CLI
-#[derive(clap::Parser, Debug)]
-#[command(author, version, about)]
-pub struct CliApp {
-    /// Arguments related to logging
-    #[command(flatten)]
-    pub logging: LoggingArgs,
-    /// One or more rule program files
+/// Cli arguments of the command that anonymizes a program and its input files
+#[derive(clap::Args, Debug, Clone)]
+pub struct AnonymizeArgs {
+    /// Rule program file
+    #[arg(value_parser)]
+    pub rules: PathBuf,
+    /// Specify directory for input files.
+    #[arg(short = 'I', long = "input-dir")]
+    pub input_directory: Option<PathBuf>,
+    /// Specify directory for the anonymized program and the files read by its data sources.
+    #[arg(short = 'D', long = "output-dir", default_value = DEFAULT_ANONYMIZED_DIRECTORY)]
+    pub output_directory: PathBuf,
+    /// Overwrite existing files in --output-dir.
+    #[arg(short, long = "overwrite-results", default_value = "false")]
+    pub overwrite: bool,
+}
+
+/// Cli arguments of the command that reduces a program and its input files
+#[derive(clap::Args, Debug, Clone)]
+pub struct ReduceArgs {
+    /// Rule program file
+    #[arg(value_parser)]
+    pub rules: PathBuf,
+    /// Shell command that succeeds as long as the problem occurs, e.g. `nmo program.rls | grep wrong`.
+    /// It is run in a directory containing only the current candidate as program.rls and its input files.
+    #[arg(long = "check")]
+    pub check: String,
+    /// Specify directory for input files.
+    #[arg(short = 'I', long = "input-dir")]
+    pub input_directory: Option<PathBuf>,
+    /// Specify directory for the reduced program and the files read by its data sources.
+    #[arg(short = 'D', long = "output-dir", default_value = DEFAULT_REDUCED_DIRECTORY)]
+    pub output_directory: PathBuf,
+    /// Overwrite existing files in --output-dir.
+    #[arg(short, long = "overwrite-results", default_value = "false")]
+    pub overwrite: bool,
+}
+
+/// Cli arguments of the command that previews some facts of a predicate
+#[derive(clap::Args, Debug, Clone)]
+pub struct PeekArgs {
+    /// Rule program file
+    #[arg(value_parser)]
+    pub rules: PathBuf,
+    /// Predicate whose facts are shown
+    #[arg(long = "predicate")]
+    pub predicate: String,
+    /// Maximal number of facts that are shown
+    #[arg(long = "limit", default_value = "20")]
+    pub limit: usize,
+    /// Maximal number of rule applications before reasoning is stopped
+    #[arg(long = "max-steps", default_value = "100")]
+    pub max_steps: usize,
+    /// Specify directory for input files.
+    #[arg(short = 'I', long = "input-dir")]
+    pub input_directory: Option<PathBuf>,
+}
+
+/// Cli arguments of the command that compiles a program
+#[derive(clap::Args, Debug, Clone)]
+pub struct CompileArgs {
+    /// Rule program file
+    #[arg(value_parser)]
+    pub rules: PathBuf,
+    /// File for the compiled program, which can be given to nmo instead of the rule program file.
+    /// Defaults to the rule program file with the extension `nmoc`.
+    #[arg(short = 'o', long = "output")]
+    pub output: Option<PathBuf>,
+}
+
+/// Cli arguments of the command that checks a program without reasoning
+#[derive(clap::Args, Debug, Clone)]
+pub struct CheckArgs {
+    /// Rule program file
+    #[arg(value_parser)]
+    pub rules: PathBuf,
+    /// Read variables for `${NAME}` in the resources of data sources from this file,
+    /// in addition to the environment. Defaults to `.env` in the current directory, if it exists.
+    #[arg(long = "env-file", value_name = "FILE")]
+    pub env_file: Option<PathBuf>,
+}
+
+/// Cli arguments of the command that queries saved results
+#[derive(clap::Args, Debug, Clone)]
+pub struct QueryArgs {
+    /// Directory containing the results saved by an earlier run, e.g. with `--save-results`
+    #[arg(value_parser)]
+    pub database: PathBuf,
+    /// Query atom, whose variables are matched against the saved facts, e.g. `ancestor(alice, ?x)`.
+    /// Positions that are not of interest can be matched by the anonymous variable `_`.
+    pub query: String,
+    /// Format of the printed bindings of the variables of the query:
+    /// tab-separated values with a header row, or a JSON array of objects
+    #[arg(
+        long = "format",
+        value_parser = clap::builder::PossibleValuesParser::new(["tsv", "json"]),
+        default_value = "tsv"
+    )]
+    pub format: String,
+}
+
+/// Cli arguments of the command that checks whether a query has matches in saved results
+#[derive(clap::Args, Debug, Clone)]
+pub struct AssertArgs {
+    /// Directory containing the results saved by an earlier run, e.g. with `--save-results`
+    #[arg(value_parser)]
+    pub database: PathBuf,
+    /// Query atom, whose variables are matched against the saved facts, e.g. `violation(?x)`
+    pub query: String,
+    /// Fail if the query has matches, e.g. to check that no integrity constraint is violated
+    #[arg(
+        long = "expect-empty",
+        required_unless_present = "expect_non_empty",
+        conflicts_with = "expect_non_empty"
+    )]
+    pub expect_empty: bool,
+    /// Fail if the query has no matches
+    #[arg(long = "expect-non-empty")]
+    pub expect_non_empty: bool,
+}
+
+/// Cli arguments of the command that explains how a fact has been derived
+#[derive(clap::Args, Debug, Clone)]
+pub struct TraceArgs {
+    /// Rule program file
+    #[arg(value_parser)]
+    pub rules: PathBuf,
+    /// Fact to explain, e.g. `ancestor(alice, carol)`
+    pub fact: String,
+    /// Directory containing the results of all derived predicates saved by an earlier run
+    /// with `--write-all-idb-predicates`, which are used instead of reasoning again
+    #[arg(long = "results", value_name = "DIR")]
+    pub results: Option<PathBuf>,
+    /// Specify directory for input files.
+    #[arg(short = 'I', long = "input-dir")]
+    pub input_directory: Option<PathBuf>,
+}
+
+/// Commands of the CLI
+#[derive(clap::Subcommand, Debug, Clone)]
+pub enum CliCommand {
+    /// Reason over a program and report or save its results (the default if no command is given)
+    Run(Box<RunArgs>),
+    /// Parse and analyze a program without reasoning, reporting any errors and warnings
+    Check(CheckArgs),
+    /// Print the bindings of the variables of a query atom to facts in saved results, without reasoning again
+    Query(QueryArgs),
+    /// Check whether a query atom has matches in saved results,
+    /// and exit with a nonzero code if this is not as expected, e.g. to gate deployments in CI pipelines
+    Assert(AssertArgs),
+    /// Explain how a fact has been derived by showing the rule applications leading to it
+    Trace(TraceArgs),
+    /// Consistently rename the predicates, constants, IRIs and strings of a program and its input files,
+    /// e.g. to share a reproducible bug report without disclosing proprietary data
+    Anonymize(AnonymizeArgs),
+    /// Repeatedly remove statements of a program and facts of its input files while a check still succeeds,
+    /// e.g. to obtain a minimal program reproducing a bug
+    Reduce(ReduceArgs),
+    /// Apply only the rules needed to derive facts of a predicate, for a bounded number of steps,
+    /// and show some of its facts, e.g. to check rules without materializing all predicates
+    Peek(PeekArgs),
+    /// Validate and analyze a program once and store it together with the results of its analysis,
+    /// so that later runs on the compiled program start reasoning without analyzing it again
+    Compile(CompileArgs),
+}
+
+/// Cli arguments of the command that reasons over a program
+#[derive(clap::Args, Debug, Clone)]
+pub struct RunArgs {
+    /// One or more rule program files, or a program compiled by `nmo compile`
     #[arg(value_parser, required = true)]
     pub rules: Vec<PathBuf>,
+    /// Arguments related to continuous reasoning over Kafka topics
+    #[cfg(feature = "kafka")]
+    #[command(flatten)]
+    pub kafka: KafkaArgs,
     /// Arguments related to output
     #[command(flatten)]
     pub output: OutputArgs,
@@ -125,10 +474,176 @@ pub struct CliApp {
     /// Display detailed timing information
     #[arg(long = "detailed-timing", default_value = "false")]
     pub detailed_timing: bool,
-    /// Display detailed memory information
+    /// Display detailed memory information, broken down by predicate, kind of structure and column type
     #[arg(long = "detailed-memory", default_value = "false")]
     pub detailed_memory: bool,
+    /// Display the state of the tables of each predicate after reasoning
+    #[arg(long = "inspect", default_value = "false")]
+    pub inspect: bool,
+    /// Write a summary of the run in JSON format to this file.
+    #[arg(long = "report")]
+    pub report: Option<PathBuf>,
+    /// Write a log of all rule applications in CSV format to this file,
+    /// including a sample of the bindings that satisfied the body of the rule.
+    #[arg(long = "firing-log")]
+    pub firing_log: Option<PathBuf>,
+    /// Maximal number of bindings logged per rule application
+    #[arg(
+        long = "firing-log-sample",
+        default_value = "10",
+        requires = "firing_log"
+    )]
+    pub firing_log_sample: usize,
+    /// Write a report in CSV format to this file, which states for each output predicate
+    /// how many facts were given as input, how many were derived by rules
+    /// and how many derived facts were discarded as duplicates.
+    #[arg(long = "dedup-report")]
+    pub dedup_report: Option<PathBuf>,
+    /// Skip rule applications whose body has no matches,
+    /// as detected by approximate filters of the values in the first column of each table
+    #[arg(long = "key-filters", default_value = "false")]
+    pub key_filters: bool,
+    /// Maintain an inverted index of the words of all strings,
+    /// which speeds up full-text searches with MATCHES_TEXT
+    #[arg(long = "text-index", default_value = "false")]
+    pub text_index: bool,
+    /// Apply rules with existential variables once for every binding of the variables they share with the head,
+    /// instead of only if the head is not satisfied yet (Skolem chase)
+    #[arg(long = "skolem-chase", default_value = "false")]
+    pub skolem_chase: bool,
+    /// Only remove already known facts from every N-th application of each rule,
+    /// which uses more memory but saves time if few duplicates are derived
+    #[arg(long = "dedup-every", value_name = "N")]
+    pub dedup_every: Option<usize>,
+    /// Warn if a single rule application is estimated to produce more than this number of matches
+    #[arg(long = "max-estimated-matches", value_name = "MATCHES")]
+    pub max_estimated_matches: Option<u64>,
+    /// Stop reasoning after this number of rule applications, e.g. for programs that do not terminate.
+    /// The results are incomplete if reasoning is stopped.
+    #[arg(long = "max-iterations", visible_alias = "max-depth", value_name = "N")]
+    pub max_iterations: Option<usize>,
+    /// Display the estimated number of matches of each rule on the input facts instead of reasoning
+    #[arg(long = "explain", default_value = "false")]
+    pub explain: bool,
+    /// Treatment of rules with disjunctive heads:
+    /// reject them, derive only the first disjunct, or derive all disjuncts
+    #[arg(
+        long = "disjunctive-heads",
+        value_parser = clap::builder::PossibleValuesParser::new(["reject", "first", "split"]),
+        default_value = "reject"
+    )]
+    pub disjunctive_heads: String,
+    /// Treatment of predicate names: keep them as parsed (where `p` and `<p>` are the same predicate,
+    /// but differ from a prefixed name like `ex:p`) or resolve them against the base IRI of the program
+    #[arg(
+        long = "predicate-names",
+        value_parser = clap::builder::PossibleValuesParser::new(["keep", "absolutize"]),
+        default_value = "keep"
+    )]
+    pub predicate_names: String,
     /// Specify directory for input files.
     #[arg(short = 'I', long = "input-dir")]
     pub input_directory: Option<PathBuf>,
+    /// Resolve relative paths of input files against the directory of the rule file
+    /// or against the current directory. (Not relevant if --input-dir is set.)
+    #[arg(
+        long = "relative-to",
+        value_parser = clap::builder::PossibleValuesParser::new(["rules", "cwd"]),
+        default_value = "rules"
+    )]
+    pub relative_to: String,
+    /// Read variables for `${NAME}` in the resources of data sources from this file,
+    /// in addition to the environment. Defaults to `.env` in the current directory, if it exists.
+    #[arg(long = "env-file", value_name = "FILE")]
+    pub env_file: Option<PathBuf>,
+    /// Import an R2RML mapping (in Turtle), whose triples are derived for the predicate `triple`.
+    /// Tables are read from CSV files with a header row, e.g. `people.csv` for `rr:tableName "people"`.
+    #[arg(long = "r2rml", value_name = "MAPPING")]
+    pub r2rml: Option<PathBuf>,
+    /// Read the rule file as a problem in the Horn fragment of TPTP
+    /// and report whether its ground conjecture follows from its axioms
+    #[arg(long = "tptp")]
+    pub tptp: bool,
+    /// Read the rule file as a set of rules in the monotonic subset of Notation3 (N3),
+    /// whose triples are derived for the predicate `triple`
+    #[arg(long = "n3", conflicts_with = "tptp")]
+    pub n3: bool,
+    /// Load an OWL ontology (in RDF/XML or Turtle) into the predicate `triple`
+    /// and translate its DL-safe SWRL rules into rules over this predicate
+    #[arg(long = "ontology", value_name = "ONTOLOGY")]
+    pub ontology: Option<PathBuf>,
+    /// Obtain the facts of the external source with the given name from a gRPC service,
+    /// e.g. `graph=http://localhost:50051` for `@source edge[2]: external("graph") .`
+    #[cfg(feature = "grpc")]
+    #[arg(long = "grpc-source", value_name = "NAME=ENDPOINT", value_parser = parse_grpc_source)]
+    pub grpc_sources: Vec<(String, String)>,
+    /// Load previously saved results of derived predicates from this directory before reasoning.
+    /// (Only correct for programs without negation.)
+    #[arg(long = "warm-start")]
+    pub warm_start: Option<PathBuf>,
+    /// Only write facts that were not contained in the results loaded by `--warm-start`
+    #[arg(long = "new-facts-only", requires = "warm_start")]
+    pub new_facts_only: bool,
+    /// Reuse the results of derived predicates stored in this directory by an earlier run
+    /// if neither their rules nor their inputs have changed, and store the new results there.
+    /// (Not used for compiled programs.)
+    #[arg(
+        long = "consequence-cache",
+        value_name = "DIR",
+        conflicts_with = "warm_start"
+    )]
+    pub consequence_cache: Option<PathBuf>,
+    /// Store the encoded tables of input files in this directory,
+    /// and read them from there instead of parsing inputs whose content has not changed
+    #[arg(long = "input-cache", value_name = "DIR")]
+    pub input_cache: Option<PathBuf>,
+    /// Start with the dictionary stored in this file by `--export-dictionary`,
+    /// such that constants are encoded as in the run that stored it
+    #[arg(
+        long = "import-dictionary",
+        value_name = "FILE",
+        conflicts_with_all = ["warm_start", "consequence_cache"]
+    )]
+    pub import_dictionary: Option<PathBuf>,
+    /// Store the dictionary of constants in this file after reasoning
+    #[arg(long = "export-dictionary", value_name = "FILE")]
+    pub export_dictionary: Option<PathBuf>,
+    /// Keep running and reason again whenever the rule file, the R2RML mapping, the ontology
+    /// or a file in the input directory changes
+    #[arg(long = "watch", default_value = "false")]
+    pub watch: bool,
+}
+
+/// Nemo CLI
+#[derive(clap::Parser, Debug, Clone)]
+#[command(
+    author,
+    version,
+    about,
+    subcommand_negates_reqs = true,
+    args_conflicts_with_subcommands = true
+)]
+pub struct CliApp {
+    /// Command to run, which is `run` if no command is given
+    #[command(subcommand)]
+    pub command: Option<CliCommand>,
+    /// Arguments related to logging
+    #[command(flatten)]
+    pub logging: LoggingArgs,
+    /// Arguments related to exporting traces
+    #[cfg(feature = "otlp")]
+    #[command(flatten)]
+    pub tracing: TracingArgs,
+    /// Arguments of the `run` command, if no command is given
+    #[command(flatten)]
+    pub run: RunArgs,
+}
+
+/// Parses the name of an external source and the endpoint of the gRPC service providing its facts
+#[cfg(feature = "grpc")]
+fn parse_grpc_source(argument: &str) -> Result<(String, String), String> {
+    argument
+        .split_once('=')
+        .map(|(name, endpoint)| (name.to_string(), endpoint.to_string()))
+        .ok_or_else(|| format!("expected NAME=ENDPOINT, found `{argument}`"))
 }
@@ -19,21 +19,51 @@
 #![feature(is_sorted)]
 
 pub mod cli;
-
-use std::fs::read_to_string;
+pub mod query;
+pub mod reduce;
+pub mod report;
+pub mod watch;
+
+use std::{
+    fs::{read, read_to_string, File},
+    io::BufWriter,
+    path::{Path, PathBuf},
+};
 
 use clap::Parser;
-use cli::CliApp;
+use cli::{
+    AnonymizeArgs, AssertArgs, CheckArgs, CliCommand, CompileArgs, PeekArgs, QueryArgs, ReduceArgs,
+    RunArgs, TraceArgs,
+};
 use colored::Colorize;
 use nemo::{
     error::{Error, ReadingError},
-    execution::{DefaultExecutionEngine, ExecutionEngine},
-    io::{parser::parse_program, resource_providers::ResourceProviders, RecordWriter},
+    execution::{DefaultExecutionEngine, ExecutionEngine, KeepNames},
+    io::{
+        parser::{parse_atom, parse_program, parse_program_with_environment, DisjunctiveHeads},
+        r2rml::TRIPLE_PREDICATE,
+        resource_providers::{file::file_uri_from_path, ResourceProviders},
+        CompiledProgram, ConsequenceCache, Environment, InputCache, N3Rules, OutputFileManager,
+        R2rmlMapping, RecordWriter, RuleVariableOrders, SwrlRules, TptpProblem, WarmStart,
+    },
     meta::{timing::TimedDisplay, TimedCode},
-    model::OutputPredicateSelection,
+    model::{Identifier, OutputPredicateSelection, PredicateNames},
 };
+use query::QueryBindings;
+use reduce::{Component, Reducer};
+use report::RunReport;
+use watch::FileWatcher;
+
+/// File that variables for the resources of data sources are read from if no other file is given
+const DEFAULT_ENV_FILE: &str = ".env";
 
-fn print_finished_message(new_facts: usize, saving: bool) {
+/// Exit code of `nmo assert` if the assertion fails, which differs from the exit code used for errors
+const ASSERTION_FAILED_EXIT_CODE: i32 = 3;
+
+/// Name of the file containing the exported program in the output directory of `nmo anonymize` and `nmo reduce`
+const EXPORTED_PROGRAM_FILE: &str = "program.rls";
+
+fn print_finished_message(new_facts: usize, saving: bool, stopped_after: Option<usize>) {
     let overall_time = TimedCode::instance().total_system_time().as_millis();
     let reading_time = TimedCode::instance()
         .sub("Reading & Preprocessing")
@@ -68,12 +98,22 @@ fn print_finished_message(new_facts: usize, saving: bool) {
         .expect("Vector is not empty")
         + 2; // for the unit ms
 
-    println!(
-        "Reasoning completed in {}{}. Derived {} facts.",
-        overall_time.to_string().green().bold(),
-        "ms".green().bold(),
-        new_facts.to_string().green().bold(),
-    );
+    match stopped_after {
+        None => println!(
+            "Reasoning completed in {}{}. Derived {} facts.",
+            overall_time.to_string().green().bold(),
+            "ms".green().bold(),
+            new_facts.to_string().green().bold(),
+        ),
+        Some(steps) => println!(
+            "Reasoning {} after {steps} rule applications in {}{}. Derived {} facts, {}.",
+            "stopped".yellow().bold(),
+            overall_time.to_string().green().bold(),
+            "ms".green().bold(),
+            new_facts.to_string().green().bold(),
+            "the results are partial".yellow().bold(),
+        ),
+    }
 
     println!(
         "   {0: <14} {1:>max_string_len$}ms",
@@ -92,7 +132,9 @@ fn print_finished_message(new_facts: usize, saving: bool) {
     }
 }
 
-fn run(mut cli: CliApp) -> Result<(), Error> {
+fn run(mut cli: RunArgs) -> Result<(), Error> {
+    log::debug!("Rule files: {:?}", cli.rules);
+
     TimedCode::instance().start();
     TimedCode::instance().sub("Reading & Preprocessing").start();
 
@@ -103,12 +145,84 @@ fn run(mut cli: CliApp) -> Result<(), Error> {
     }
 
     let rules = cli.rules.pop().ok_or(Error::NoInput)?;
-    let rules_content = read_to_string(rules.clone()).map_err(|err| ReadingError::IOReading {
-        error: err,
-        filename: rules,
-    })?;
+    let (rules_content, variable_orders) = read_rules(&rules)?;
 
-    let mut program = parse_program(rules_content)?;
+    let disjunctive_heads = match cli.disjunctive_heads.as_str() {
+        "first" => DisjunctiveHeads::ChooseFirst,
+        "split" => DisjunctiveHeads::Split,
+        _ => DisjunctiveHeads::Reject,
+    };
+    let mapping = match &cli.r2rml {
+        Some(path) => {
+            let mapping_content = read_to_string(path).map_err(|err| ReadingError::IOReading {
+                error: err,
+                filename: path.clone(),
+            })?;
+            let base = path
+                .canonicalize()
+                .ok()
+                .map(|path| file_uri_from_path(&path));
+            Some(R2rmlMapping::parse(&mapping_content, base.as_deref())?)
+        }
+        None => None,
+    };
+    let problem = if cli.tptp {
+        Some(TptpProblem::parse(&rules_content)?)
+    } else {
+        None
+    };
+    let rules_content = match &problem {
+        Some(problem) => problem.program(),
+        None => rules_content,
+    };
+    let rules_content = if cli.n3 {
+        let base = rules
+            .canonicalize()
+            .ok()
+            .map(|path| file_uri_from_path(&path));
+        N3Rules::parse(&rules_content, base.as_deref())?.program()
+    } else {
+        rules_content
+    };
+    let rules_content = match &mapping {
+        Some(mapping) => mapping.program() + &rules_content,
+        None => rules_content,
+    };
+    let rules_content = match &cli.ontology {
+        Some(path) => {
+            let ontology_content = read_to_string(path).map_err(|err| ReadingError::IOReading {
+                error: err,
+                filename: path.clone(),
+            })?;
+            let swrl_rules = if path.extension().is_some_and(|extension| extension == "ttl") {
+                SwrlRules::parse_turtle(&ontology_content, None)?
+            } else {
+                SwrlRules::parse_rdf_xml(&ontology_content, None)?
+            };
+
+            let resource = path
+                .canonicalize()
+                .unwrap_or_else(|_| path.clone())
+                .display()
+                .to_string()
+                .replace('\\', "\\\\")
+                .replace('"', "\\\"");
+            format!(
+                "{rules_content}\n@source {TRIPLE_PREDICATE}[any, any, any]: load-rdf(\"{resource}\") .\n{}",
+                swrl_rules.program()
+            )
+        }
+        None => rules_content,
+    };
+
+    let predicate_names = match cli.predicate_names.as_str() {
+        "absolutize" => PredicateNames::Absolutize,
+        _ => PredicateNames::Keep,
+    };
+    let environment = read_environment(cli.env_file.as_deref())?;
+    let mut program =
+        parse_program_with_environment(rules_content, disjunctive_heads, environment)?
+            .normalize_predicate_names(predicate_names)?;
 
     log::info!("Rules parsed");
     log::trace!("{:?}", program);
@@ -123,20 +237,124 @@ fn run(mut cli: CliApp) -> Result<(), Error> {
         output_manager.prevent_accidental_overwrite(program.output_predicates())?;
     }
 
-    let mut engine: DefaultExecutionEngine = ExecutionEngine::initialize(
-        program,
-        ResourceProviders::with_base_path(cli.input_directory),
-    )?;
+    let base_path = input_base_path(&rules, cli.input_directory, &cli.relative_to);
+    let resource_providers = ResourceProviders::with_base_path(base_path);
+    let resource_providers = match &mapping {
+        Some(mapping) => mapping.register_sources(resource_providers),
+        None => resource_providers,
+    };
+    let resource_providers = match cli.input_cache {
+        Some(directory) => resource_providers.with_input_cache(InputCache::new(directory)),
+        None => resource_providers,
+    };
+    #[cfg(feature = "grpc")]
+    let resource_providers =
+        cli.grpc_sources
+            .iter()
+            .fold(resource_providers, |providers, (name, endpoint)| {
+                providers.with_external_source(
+                    name,
+                    nemo::io::resource_providers::grpc::GrpcSourceProvider::new(endpoint, name),
+                )
+            });
+
+    #[cfg(feature = "kafka")]
+    if let Some(stream) = cli.kafka.stream() {
+        log::info!("Reasoning continuously over Kafka topics ...");
+        let mut reasoner = nemo::execution::StreamingReasoner::new(program, resource_providers);
+        return stream.run(&mut reasoner);
+    }
+
+    if variable_orders.is_some() && cli.import_dictionary.is_some() {
+        log::warn!("Importing a dictionary is not supported for compiled programs and is skipped");
+    }
+
+    let warm_start = cli.warm_start.map(WarmStart::new);
+    let consequence_cache = cli.consequence_cache.map(ConsequenceCache::new);
+    let mut engine: DefaultExecutionEngine = match (variable_orders, &warm_start) {
+        (Some(variable_orders), warm_start) => ExecutionEngine::initialize_with_variable_orders(
+            program,
+            resource_providers,
+            variable_orders,
+            warm_start.as_ref(),
+        )?,
+        (None, Some(warm_start)) => {
+            ExecutionEngine::initialize_with_warm_start(program, resource_providers, warm_start)?
+        }
+        (None, None) => match (&consequence_cache, &cli.import_dictionary) {
+            (Some(consequence_cache), _) => ExecutionEngine::initialize_with_consequence_cache(
+                program,
+                resource_providers,
+                consequence_cache,
+            )?,
+            (None, Some(dictionary_file)) => ExecutionEngine::initialize_with_dictionary_file(
+                program,
+                resource_providers,
+                dictionary_file,
+            )?,
+            (None, None) => ExecutionEngine::initialize(program, resource_providers)?,
+        },
+    };
+
+    if cli.firing_log.is_some() {
+        engine.log_rule_firings(cli.firing_log_sample);
+    }
+
+    if cli.dedup_report.is_some() {
+        engine.count_duplicates();
+    }
+
+    if cli.key_filters {
+        engine.use_key_filters();
+    }
+
+    if cli.text_index {
+        engine.use_text_index();
+    }
+
+    if cli.skolem_chase {
+        engine.use_skolem_chase();
+    }
+
+    if let Some(period) = cli.dedup_every {
+        engine.delay_duplicate_elimination(period);
+    }
+
+    if let Some(max_matches) = cli.max_estimated_matches {
+        engine.warn_on_estimated_matches(max_matches);
+    }
+
+    if cli.explain {
+        for rule_index in 0..engine.program().rules().len() {
+            print!("{}", engine.explain(rule_index)?);
+        }
+
+        return Ok(());
+    }
 
     TimedCode::instance().sub("Reading & Preprocessing").stop();
     TimedCode::instance().sub("Reasoning").start();
 
     log::info!("Reasoning ... ");
 
-    engine.execute()?;
+    let complete = match cli.max_iterations {
+        Some(max_iterations) => engine.execute_until(|engine| engine.steps() >= max_iterations)?,
+        None => {
+            engine.execute()?;
+            true
+        }
+    };
 
     log::info!("Reasoning done");
 
+    if let Some(consequence_cache) = consequence_cache.as_ref().filter(|_| complete) {
+        let stored = engine.store_consequences(consequence_cache)?;
+        log::info!(
+            "Stored {stored} predicates in {:?}",
+            consequence_cache.directory()
+        );
+    }
+
     TimedCode::instance().sub("Reasoning").stop();
 
     if let Some(output_manager) = &output_manager {
@@ -148,26 +366,51 @@ fn run(mut cli: CliApp) -> Result<(), Error> {
         for predicate in engine.program().output_predicates() {
             let mut writer = output_manager.create_file_writer(&predicate)?;
 
-            let Some(record_iter) = engine.output_serialization(predicate)? else {
-                continue;
-            };
-            for record in record_iter {
-                writer.write_record(record)?;
+            if cli.new_facts_only {
+                if let Some(record_iter) = engine.new_facts_serialization(predicate)? {
+                    for record in record_iter {
+                        writer.write_record(record)?;
+                    }
+                }
+            } else if let Some(record_iter) = engine.output_serialization(predicate)? {
+                for record in record_iter {
+                    writer.write_record(record)?;
+                }
             }
+            writer.finish()?;
         }
+        output_manager.finalize()?;
 
         TimedCode::instance()
             .sub("Output & Final Materialization")
             .stop();
     }
 
+    if let Some(dictionary_file) = &cli.export_dictionary {
+        engine.export_dictionary(dictionary_file)?;
+        log::info!("Stored dictionary in {dictionary_file:?}");
+    }
+
     TimedCode::instance().stop();
 
     print_finished_message(
         engine.count_facts_of_derived_predicates(),
         output_manager.is_some(),
+        (!complete).then(|| engine.steps()),
     );
 
+    if let Some(query) = problem.as_ref().and_then(TptpProblem::query) {
+        if engine.query(&query)?.is_empty() {
+            if complete {
+                println!("The conjecture does not follow from the axioms.");
+            } else {
+                println!("The conjecture could not be derived before reasoning was stopped.");
+            }
+        } else {
+            println!("The conjecture follows from the axioms.");
+        }
+    }
+
     if cli.detailed_timing {
         println!(
             "\n{}",
@@ -183,21 +426,463 @@ fn run(mut cli: CliApp) -> Result<(), Error> {
     }
 
     if cli.detailed_memory {
-        println!("\n{}", engine.memory_usage());
+        print!("\n{}", engine.memory_report());
+    }
+
+    if cli.inspect {
+        print!("\n{}", engine.state());
+    }
+
+    if let Some(report) = cli.report {
+        RunReport::new(&engine, complete).write(report)?;
+    }
+
+    if let (Some(path), Some(firing_log)) = (cli.firing_log, engine.firing_log()) {
+        let file = File::create(&path).map_err(|error| Error::IOWriting {
+            error,
+            filename: path.to_string_lossy().to_string(),
+        })?;
+        firing_log.write_csv(BufWriter::new(file))?;
+    }
+
+    if let (Some(path), Some(dedup_report)) = (cli.dedup_report, engine.deduplication_report()) {
+        let file = File::create(&path).map_err(|error| Error::IOWriting {
+            error,
+            filename: path.to_string_lossy().to_string(),
+        })?;
+        dedup_report.write_csv(BufWriter::new(file))?;
     }
 
     Ok(())
 }
 
+/// Read a rule program file, which may contain a program compiled by `nmo compile`.
+///
+/// Returns the text of the program and, if it was compiled, the variable orders of its rules.
+fn read_rules(path: &Path) -> Result<(String, Option<Vec<RuleVariableOrders>>), Error> {
+    let reading_error = |error| ReadingError::IOReading {
+        error,
+        filename: path.to_path_buf(),
+    };
+    let bytes = read(path).map_err(reading_error)?;
+
+    if CompiledProgram::is_compiled_program(&bytes) {
+        log::info!("Reading compiled program ...");
+        let (program, variable_orders) = CompiledProgram::from_bytes(&bytes)?.into_parts();
+
+        return Ok((program, Some(variable_orders)));
+    }
+
+    let content = String::from_utf8(bytes)
+        .map_err(|err| reading_error(std::io::Error::new(std::io::ErrorKind::InvalidData, err)))?;
+
+    Ok((content, None))
+}
+
+/// Return the variables that can be used in the resources of data sources,
+/// which are read from the given `.env` file or from `.env` in the current directory, if it exists.
+fn read_environment(env_file: Option<&Path>) -> Result<Environment, Error> {
+    let default_file = Path::new(DEFAULT_ENV_FILE);
+
+    match env_file {
+        Some(path) => Environment::default().with_dotenv_file(path),
+        None if default_file.is_file() => Environment::default().with_dotenv_file(default_file),
+        None => Ok(Environment::default()),
+    }
+}
+
+/// Return the directory against which relative paths of input files are resolved:
+/// the given input directory, if any, or otherwise the directory of the rule file,
+/// unless paths should be resolved against the current directory.
+fn input_base_path(
+    rules: &Path,
+    input_directory: Option<PathBuf>,
+    relative_to: &str,
+) -> Option<PathBuf> {
+    match (input_directory, relative_to) {
+        (Some(directory), _) => Some(directory),
+        (None, "rules") => rules
+            .parent()
+            .filter(|directory| !directory.as_os_str().is_empty())
+            .map(Path::to_path_buf),
+        (None, _) => None,
+    }
+}
+
+/// Return the path of the exported program in the given output directory.
+///
+/// Returns an error if the file exists and may not be overwritten.
+fn exported_program_path(output_directory: &Path, overwrite: bool) -> Result<PathBuf, Error> {
+    let program_path = output_directory.join(EXPORTED_PROGRAM_FILE);
+    if !overwrite && program_path.exists() {
+        return Err(Error::IOExists {
+            error: std::io::ErrorKind::AlreadyExists.into(),
+            filename: program_path,
+        });
+    }
+
+    Ok(program_path)
+}
+
+/// Write an anonymized copy of a program and the facts of its data sources to the output directory.
+fn anonymize(args: AnonymizeArgs) -> Result<(), Error> {
+    let rules_content = read_to_string(&args.rules).map_err(|err| ReadingError::IOReading {
+        error: err,
+        filename: args.rules.clone(),
+    })?;
+    let program = parse_program(rules_content)?;
+
+    let output_manager =
+        OutputFileManager::try_new(args.output_directory.clone(), args.overwrite, false)?;
+    let program_path = exported_program_path(&args.output_directory, args.overwrite)?;
+
+    let mut engine: DefaultExecutionEngine = ExecutionEngine::initialize(
+        program.clone(),
+        ResourceProviders::with_base_path(args.input_directory),
+    )?;
+    let anonymized = engine.anonymize(&program)?;
+
+    for (predicate, anonymized_predicate) in &anonymized.sources {
+        let mut writer = output_manager.create_file_writer(anonymized_predicate)?;
+
+        if let Some(record_iter) = engine.output_serialization(predicate.clone())? {
+            for record in record_iter {
+                writer.write_record(record)?;
+            }
+        }
+        writer.finish()?;
+    }
+    output_manager.finalize()?;
+
+    std::fs::write(&program_path, anonymized.text()).map_err(|error| Error::IOWriting {
+        error,
+        filename: program_path.to_string_lossy().to_string(),
+    })?;
+
+    println!(
+        "Anonymized program written to {}, run it with `nmo -I {} {}`.",
+        program_path.display(),
+        args.output_directory.display(),
+        program_path.display()
+    );
+
+    Ok(())
+}
+
+/// Write a minimal part of a program and the facts of its data sources,
+/// for which the check given by the user still succeeds, to the output directory.
+fn reduce(args: ReduceArgs) -> Result<(), Error> {
+    let rules_content = read_to_string(&args.rules).map_err(|err| ReadingError::IOReading {
+        error: err,
+        filename: args.rules.clone(),
+    })?;
+    let program = parse_program(rules_content)?;
+
+    let output_manager =
+        OutputFileManager::try_new(args.output_directory.clone(), args.overwrite, false)?;
+    let program_path = exported_program_path(&args.output_directory, args.overwrite)?;
+
+    let mut engine: DefaultExecutionEngine = ExecutionEngine::initialize(
+        program.clone(),
+        ResourceProviders::with_base_path(args.input_directory),
+    )?;
+    let exported = engine.export(&program, &mut KeepNames)?;
+    output_manager.prevent_accidental_overwrite(
+        exported
+            .sources
+            .iter()
+            .map(|(_, predicate)| predicate.clone()),
+    )?;
+
+    let mut components = exported
+        .statements
+        .into_iter()
+        .map(Component::Statement)
+        .collect::<Vec<_>>();
+    let statement_count = components.len();
+    for (predicate, exported_predicate) in &exported.sources {
+        if let Some(record_iter) = engine.output_serialization(predicate.clone())? {
+            components.extend(
+                record_iter.map(|record| Component::Record(exported_predicate.clone(), record)),
+            );
+        }
+    }
+    let record_count = components.len() - statement_count;
+
+    let mut reducer = Reducer::new(
+        components,
+        exported
+            .sources
+            .into_iter()
+            .map(|(_, predicate)| predicate)
+            .collect(),
+        args.check,
+        EXPORTED_PROGRAM_FILE,
+    );
+    let reduced = reducer.reduce(&args.output_directory)?;
+    let reduced_statements = reduced
+        .iter()
+        .filter(|component| matches!(component, Component::Statement(_)))
+        .count();
+
+    println!(
+        "Reduced program with {reduced_statements} of {statement_count} statements and {} of {record_count} input facts written to {} after {} checks, run it with `nmo -I {} {}`.",
+        reduced.len() - reduced_statements,
+        program_path.display(),
+        reducer.check_count(),
+        args.output_directory.display(),
+        program_path.display()
+    );
+
+    Ok(())
+}
+
+/// Print some facts of a predicate, deriving them only with the rules that are relevant for the predicate.
+///
+/// Reasoning stops as soon as enough facts have been derived or the maximal number of steps is reached.
+fn peek(args: PeekArgs) -> Result<(), Error> {
+    let rules_content = read_to_string(&args.rules).map_err(|err| ReadingError::IOReading {
+        error: err,
+        filename: args.rules.clone(),
+    })?;
+    let predicate = Identifier::from(args.predicate);
+    let program = parse_program(rules_content)?.restrict_to(std::slice::from_ref(&predicate));
+
+    let mut engine: DefaultExecutionEngine = ExecutionEngine::initialize(
+        program,
+        ResourceProviders::with_base_path(args.input_directory),
+    )?;
+    let finished = engine.execute_until(|engine| {
+        engine.steps() >= args.max_steps
+            || engine.count_facts_of_predicate(&predicate).unwrap_or(0) >= args.limit
+    })?;
+
+    let mut count = 0;
+    if let Some(record_iter) = engine.output_serialization(predicate.clone())? {
+        for record in record_iter.take(args.limit) {
+            println!("{predicate}({})", record.join(", "));
+            count += 1;
+        }
+    }
+
+    if finished {
+        println!(
+            "Showing {count} facts of {predicate} derived in {} steps.",
+            engine.steps()
+        );
+    } else {
+        println!(
+            "Showing {count} facts of {predicate}, reasoning was stopped after {} steps.",
+            engine.steps()
+        );
+    }
+
+    Ok(())
+}
+
+/// Parse and analyze a program without reasoning.
+fn check(args: CheckArgs) -> Result<(), Error> {
+    let rules_content = read_to_string(&args.rules).map_err(|err| ReadingError::IOReading {
+        error: err,
+        filename: args.rules.clone(),
+    })?;
+    let environment = read_environment(args.env_file.as_deref())?;
+    let program =
+        parse_program_with_environment(rules_content, DisjunctiveHeads::default(), environment)?;
+
+    let engine: DefaultExecutionEngine =
+        ExecutionEngine::initialize(program, ResourceProviders::empty())?;
+
+    println!(
+        "{} {} contains {} rules over {} predicates, with {} warnings.",
+        "Valid:".green().bold(),
+        args.rules.display(),
+        engine.program().rules().len(),
+        engine.predicates().len(),
+        engine.warnings().len()
+    );
+    println!("{} {}", "Fragment:".bold(), engine.fragment());
+
+    Ok(())
+}
+
+/// Return the bindings of the variables of the given query atom to facts in the given saved results.
+fn saved_query_bindings(database: PathBuf, query: &str) -> Result<QueryBindings, Error> {
+    let atom = parse_atom(query)?;
+
+    let program =
+        WarmStart::new(database).program(&[(atom.predicate(), atom.term_trees().len())])?;
+    let mut engine: DefaultExecutionEngine =
+        ExecutionEngine::initialize(program, ResourceProviders::with_base_path(None))?;
+
+    Ok(QueryBindings::new(&atom, engine.query(&atom)?))
+}
+
+/// Print the bindings of the variables of a query atom to facts in saved results.
+fn query(args: QueryArgs) -> Result<(), Error> {
+    let bindings = saved_query_bindings(args.database, &args.query)?;
+
+    let mut stdout = std::io::stdout().lock();
+    match args.format.as_str() {
+        "json" => bindings.write_json(&mut stdout),
+        _ => bindings.write_tsv(&mut stdout),
+    }
+}
+
+/// Check whether a query atom has matches in saved results.
+///
+/// Returns whether the expectation holds. Otherwise, the bindings of the query are printed.
+fn assert(args: AssertArgs) -> Result<bool, Error> {
+    let bindings = saved_query_bindings(args.database, &args.query)?;
+    let matches = bindings.rows.len();
+
+    let holds = if args.expect_empty {
+        matches == 0
+    } else {
+        matches > 0
+    };
+
+    if holds {
+        println!(
+            "{} {} has {matches} matches.",
+            "Assertion holds:".green().bold(),
+            args.query
+        );
+    } else {
+        println!(
+            "{} {} has {matches} matches.",
+            "Assertion failed:".red().bold(),
+            args.query
+        );
+        bindings.write_tsv(&mut std::io::stdout().lock())?;
+    }
+
+    Ok(holds)
+}
+
+/// Explain how a fact has been derived, either from saved results or by reasoning again.
+fn trace(args: TraceArgs) -> Result<(), Error> {
+    let rules_content = read_to_string(&args.rules).map_err(|err| ReadingError::IOReading {
+        error: err,
+        filename: args.rules.clone(),
+    })?;
+    let program = parse_program(rules_content)?;
+    let fact = parse_atom(&args.fact)?;
+    let resource_providers = ResourceProviders::with_base_path(args.input_directory);
+
+    let mut engine: DefaultExecutionEngine = match args.results {
+        Some(results) => ExecutionEngine::initialize_with_warm_start(
+            program,
+            resource_providers,
+            &WarmStart::new(results),
+        )?,
+        None => {
+            let mut engine = ExecutionEngine::initialize(program, resource_providers)?;
+            engine.execute()?;
+            engine
+        }
+    };
+
+    match engine.derivation(&fact)? {
+        Some(derivation) => print!("{derivation}"),
+        None => println!("The fact {} does not hold.", args.fact),
+    }
+
+    Ok(())
+}
+
+/// Validate and analyze a program and write it together with the results of its analysis to a file.
+fn compile(args: CompileArgs) -> Result<(), Error> {
+    let rules_content = read_to_string(&args.rules).map_err(|err| ReadingError::IOReading {
+        error: err,
+        filename: args.rules.clone(),
+    })?;
+    let output = args
+        .output
+        .unwrap_or_else(|| args.rules.with_extension("nmoc"));
+
+    let compiled = CompiledProgram::compile(rules_content)?;
+    std::fs::write(&output, compiled.to_bytes()).map_err(|error| Error::IOWriting {
+        error,
+        filename: output.to_string_lossy().to_string(),
+    })?;
+
+    println!(
+        "Compiled program written to {}, run it with `nmo {}`.",
+        output.display(),
+        output.display()
+    );
+
+    Ok(())
+}
+
+/// Reason again whenever one of the input files changes.
+///
+/// Errors are reported without stopping, so that they can be fixed in the watched files.
+fn watch(cli: RunArgs) -> ! {
+    let paths = cli
+        .rules
+        .iter()
+        .chain(&cli.r2rml)
+        .chain(&cli.ontology)
+        .chain(&cli.input_directory)
+        .cloned()
+        .collect();
+    let mut watcher = FileWatcher::new(paths);
+
+    loop {
+        *TimedCode::instance() = TimedCode::new();
+
+        if let Err(err) = run(cli.clone()) {
+            log::error!("{} {err}", "error:".red().bold());
+        }
+
+        println!("Watching for changes ...");
+        watcher.wait_for_change();
+        println!();
+    }
+}
+
 fn main() {
     let cli = cli::CliApp::parse();
 
     cli.logging.initialize_logging();
+    #[cfg(feature = "otlp")]
+    cli.tracing.initialize_tracing();
     log::info!("Version: {}", clap::crate_version!());
-    log::debug!("Rule files: {:?}", cli.rules);
 
-    run(cli).unwrap_or_else(|err| {
+    #[cfg(feature = "otlp")]
+    let tracing = cli.tracing.clone();
+
+    let mut exit_code = 0;
+    let result = match cli.command.clone() {
+        Some(CliCommand::Run(args)) if args.watch => watch(*args),
+        Some(CliCommand::Run(args)) => run(*args),
+        Some(CliCommand::Check(args)) => check(args),
+        Some(CliCommand::Query(args)) => query(args),
+        Some(CliCommand::Assert(args)) => assert(args).map(|holds| {
+            if !holds {
+                exit_code = ASSERTION_FAILED_EXIT_CODE;
+            }
+        }),
+        Some(CliCommand::Trace(args)) => trace(args),
+        Some(CliCommand::Anonymize(args)) => anonymize(args),
+        Some(CliCommand::Reduce(args)) => reduce(args),
+        Some(CliCommand::Peek(args)) => peek(args),
+        Some(CliCommand::Compile(args)) => compile(args),
+        None if cli.run.watch => watch(cli.run),
+        None => run(cli.run),
+    };
+
+    #[cfg(feature = "otlp")]
+    tracing.finish_tracing();
+
+    result.unwrap_or_else(|err| {
         log::error!("{} {err}", "error:".red().bold());
         std::process::exit(1)
-    })
+    });
+
+    if exit_code != 0 {
+        std::process::exit(exit_code)
+    }
 }
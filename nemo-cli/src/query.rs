@@ -0,0 +1,102 @@
+//! Printing the answers of queries given on the command line
+use std::io::Write;
+
+use nemo::{
+    error::Error,
+    model::{
+        types::primitive_logical_value::PrimitiveLogicalValueT, Atom, Term, TermOperation, Variable,
+    },
+};
+
+/// Bindings of the named variables of a query atom, one for each matching fact
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QueryBindings {
+    /// Names of the variables, in the order of their first occurrence
+    pub variables: Vec<String>,
+    /// Values of the variables for each answer, without duplicates
+    pub rows: Vec<Vec<String>>,
+}
+
+impl QueryBindings {
+    /// Collect the bindings of the variables of the given query atom from the matching facts.
+    ///
+    /// Anonymous variables (`_`) are not part of the bindings.
+    pub fn new(atom: &Atom, facts: Vec<Vec<PrimitiveLogicalValueT>>) -> Self {
+        let mut variables = Vec::<&Variable>::new();
+        let mut positions = Vec::new();
+
+        for (position, term_tree) in atom.term_trees().iter().enumerate() {
+            if let TermOperation::Term(Term::Variable(variable)) = term_tree.operation() {
+                if !variable.is_anonymous() && !variables.contains(&variable) {
+                    variables.push(variable);
+                    positions.push(position);
+                }
+            }
+        }
+
+        let mut rows = facts
+            .into_iter()
+            .map(|fact| {
+                positions
+                    .iter()
+                    .map(|&position| fact[position].to_string())
+                    .collect()
+            })
+            .collect::<Vec<Vec<String>>>();
+        rows.sort();
+        rows.dedup();
+
+        Self {
+            variables: variables.iter().map(|variable| variable.name()).collect(),
+            rows,
+        }
+    }
+
+    /// Write the bindings as tab-separated values, starting with a header row of the variables.
+    ///
+    /// Tabs, line breaks and backslashes within values are escaped.
+    pub fn write_tsv(&self, writer: &mut impl Write) -> Result<(), Error> {
+        let escape = |value: &str| {
+            value
+                .replace('\\', "\\\\")
+                .replace('\t', "\\t")
+                .replace('\n', "\\n")
+                .replace('\r', "\\r")
+        };
+        let header = self
+            .variables
+            .iter()
+            .map(|variable| format!("?{variable}"))
+            .collect::<Vec<_>>();
+
+        writeln!(writer, "{}", header.join("\t"))?;
+        for row in &self.rows {
+            let values = row.iter().map(|value| escape(value)).collect::<Vec<_>>();
+            writeln!(writer, "{}", values.join("\t"))?;
+        }
+
+        Ok(())
+    }
+
+    /// Write the bindings as a JSON array, which contains an object for each answer
+    /// that maps the names of the variables to their values.
+    pub fn write_json(&self, writer: &mut impl Write) -> Result<(), Error> {
+        let answers = self
+            .rows
+            .iter()
+            .map(|row| {
+                self.variables
+                    .iter()
+                    .cloned()
+                    .zip(row.iter().cloned().map(serde_json::Value::String))
+                    .collect::<serde_json::Map<_, _>>()
+            })
+            .collect::<Vec<_>>();
+
+        serde_json::to_writer_pretty(&mut *writer, &answers)
+            .map_err(|error| Error::IO(error.into()))?;
+        writeln!(writer)?;
+
+        Ok(())
+    }
+}
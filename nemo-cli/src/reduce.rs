@@ -0,0 +1,190 @@
+//! Reduction of a program and its input files by delta debugging
+
+use std::{
+    collections::HashMap,
+    env::temp_dir,
+    fs::{remove_dir_all, write},
+    io::ErrorKind,
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+};
+
+use nemo::{
+    error::Error,
+    io::{OutputFileManager, RecordWriter},
+    model::Identifier,
+};
+
+/// Part of an exported program that may be removed while reducing it
+#[derive(Debug, Clone)]
+pub enum Component {
+    /// Statement of the program, e.g. a rule or a fact
+    Statement(String),
+    /// Fact of the file read by the data source of the given predicate
+    Record(Identifier, Vec<String>),
+}
+
+/// Searches for a minimal selection of the components of a program
+/// for which a user-supplied check still succeeds
+#[derive(Debug)]
+pub struct Reducer {
+    components: Vec<Component>,
+    sources: Vec<Identifier>,
+    check: String,
+    program_file: &'static str,
+    scratch_directory: PathBuf,
+    check_count: usize,
+}
+
+impl Reducer {
+    /// Create a reducer for the given components of a program,
+    /// whose data sources read the files of the given predicates.
+    ///
+    /// Each candidate is written to a file named `program_file` and the files of its data sources
+    /// in an otherwise empty directory, in which the shell command `check` is run afterwards.
+    /// A candidate is kept if the command succeeds.
+    pub fn new(
+        components: Vec<Component>,
+        sources: Vec<Identifier>,
+        check: String,
+        program_file: &'static str,
+    ) -> Self {
+        Self {
+            components,
+            sources,
+            check,
+            program_file,
+            scratch_directory: temp_dir().join(format!("nmo-reduce-{}", std::process::id())),
+            check_count: 0,
+        }
+    }
+
+    /// Number of times the check has been run so far
+    pub fn check_count(&self) -> usize {
+        self.check_count
+    }
+
+    /// Write the given components to the given directory.
+    fn write(&self, candidate: &[usize], directory: &Path) -> Result<(), Error> {
+        let output_manager = OutputFileManager::try_new(directory.to_path_buf(), true, false)?;
+
+        let mut program = String::new();
+        let mut records = HashMap::<&Identifier, Vec<&Vec<String>>>::new();
+        for &index in candidate {
+            match &self.components[index] {
+                Component::Statement(statement) => {
+                    program.push_str(statement);
+                    program.push('\n');
+                }
+                Component::Record(predicate, record) => {
+                    records.entry(predicate).or_default().push(record)
+                }
+            }
+        }
+
+        for predicate in &self.sources {
+            let mut writer = output_manager.create_file_writer(predicate)?;
+            for record in records.remove(predicate).unwrap_or_default() {
+                writer.write_record(record)?;
+            }
+            writer.finish()?;
+        }
+        output_manager.finalize()?;
+
+        let program_path = directory.join(self.program_file);
+        write(&program_path, program).map_err(|error| Error::IOWriting {
+            error,
+            filename: program_path.to_string_lossy().to_string(),
+        })
+    }
+
+    /// Remove the directory in which the check is run, including all files written by the check.
+    fn clear_scratch_directory(&self) -> Result<(), Error> {
+        match remove_dir_all(&self.scratch_directory) {
+            Err(error) if error.kind() != ErrorKind::NotFound => Err(error.into()),
+            _ => Ok(()),
+        }
+    }
+
+    /// Return whether the check succeeds for the given components.
+    ///
+    /// Files written by earlier checks are removed beforehand, so that they cannot influence the result.
+    fn check(&mut self, candidate: &[usize]) -> Result<bool, Error> {
+        self.clear_scratch_directory()?;
+        self.write(candidate, &self.scratch_directory)?;
+        self.check_count += 1;
+
+        let status = Command::new("sh")
+            .arg("-c")
+            .arg(&self.check)
+            .current_dir(&self.scratch_directory)
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()?;
+
+        Ok(status.success())
+    }
+
+    /// Compute a selection of components for which the check succeeds,
+    /// but fails after removing any single one of them, and write it to the given directory.
+    ///
+    /// Returns an error if the check does not succeed for the whole program.
+    pub fn reduce(&mut self, directory: &Path) -> Result<Vec<&Component>, Error> {
+        let mut current = (0..self.components.len()).collect::<Vec<_>>();
+        if !self.check(&current)? {
+            self.clear_scratch_directory()?;
+            return Err(Error::IO(std::io::Error::other(format!(
+                "The check `{}` does not succeed for the given program.",
+                self.check
+            ))));
+        }
+
+        // Delta debugging (ddmin): split the components into chunks
+        // and try to keep only one chunk or to remove one chunk, refining the chunks if neither succeeds.
+        let mut chunk_count = 2;
+        while current.len() >= 2 {
+            let chunk_size = current.len().div_ceil(chunk_count);
+            let chunks = current
+                .chunks(chunk_size)
+                .map(<[usize]>::to_vec)
+                .collect::<Vec<_>>();
+
+            let mut reduced = None;
+            for (index, chunk) in chunks.iter().enumerate() {
+                if chunks.len() > 2 && self.check(chunk)? {
+                    reduced = Some((chunk.clone(), 2));
+                    break;
+                }
+
+                let complement = chunks
+                    .iter()
+                    .enumerate()
+                    .filter(|&(other, _)| other != index)
+                    .flat_map(|(_, chunk)| chunk.iter().copied())
+                    .collect::<Vec<_>>();
+                if self.check(&complement)? {
+                    reduced = Some((complement, (chunk_count - 1).max(2)));
+                    break;
+                }
+            }
+
+            match reduced {
+                Some((candidate, count)) => {
+                    log::info!("Reduced to {} components", candidate.len());
+                    current = candidate;
+                    chunk_count = count;
+                }
+                None if chunk_count >= current.len() => break,
+                None => chunk_count = (2 * chunk_count).min(current.len()),
+            }
+        }
+
+        self.clear_scratch_directory()?;
+        self.write(&current, directory)?;
+
+        Ok(current
+            .into_iter()
+            .map(|index| &self.components[index])
+            .collect())
+    }
+}
@@ -0,0 +1,220 @@
+//! Machine-readable summary of a run of nmo
+use std::{fs::File, io::BufWriter, path::PathBuf};
+
+use nemo::{error::Error, execution::DefaultExecutionEngine, meta::TimedCode};
+use serde::Serialize;
+
+/// Time spent in the different phases of a run, in milliseconds
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct TimingReport {
+    /// Overall time
+    pub total: u128,
+    /// Time spent parsing and preparing the program
+    pub loading: u128,
+    /// Time spent reasoning (including loading the input tables)
+    pub reasoning: u128,
+    /// Time spent writing the results
+    pub output: u128,
+}
+
+impl TimingReport {
+    fn from_timed_code() -> Self {
+        // Each measurement locks the timing instance, so they must not be taken within one expression
+        let time = |name: &str| {
+            TimedCode::instance()
+                .sub(name)
+                .total_system_time()
+                .as_millis()
+        };
+        let total = TimedCode::instance().total_system_time().as_millis();
+        let loading = time("Reading & Preprocessing");
+        let reasoning = time("Reasoning");
+        let output = time("Output & Final Materialization");
+
+        Self {
+            total,
+            loading,
+            reasoning,
+            output,
+        }
+    }
+}
+
+/// Summary of the facts of a single predicate
+#[derive(Debug, Serialize)]
+pub struct PredicateReport {
+    /// Name of the predicate
+    pub name: String,
+    /// Arity of the predicate
+    pub arity: usize,
+    /// Whether the predicate occurs in the head of some rule
+    pub derived: bool,
+    /// Whether the facts of this predicate are part of the output
+    pub output: bool,
+    /// Number of facts
+    pub rows: usize,
+    /// Number of labelled nulls introduced by rules deriving facts of the predicate
+    pub nulls: usize,
+    /// Memory consumed by the tables of the predicate, in bytes
+    pub memory: u64,
+}
+
+/// Summary of the applications of a single rule
+#[derive(Debug, Serialize)]
+pub struct RuleReport {
+    /// Position of the rule in the normalized program evaluated by the engine
+    ///
+    /// This does not coincide with the position in the source program,
+    /// since rules are split and auxiliary rules are added during normalization,
+    /// e.g. for disjunctions, negated conjunctions, functions and aggregates.
+    pub index: usize,
+    /// Predicates of the rule in the form `head :- body`
+    pub rule: String,
+    /// Predicates occurring in the head of the rule
+    pub head: Vec<String>,
+    /// Number of times the rule was applied
+    pub applications: usize,
+    /// Number of new facts derived by the rule
+    pub derived_facts: usize,
+    /// Number of labelled nulls introduced for the existential variables of the rule
+    pub nulls: usize,
+    /// Overall time spent applying the rule, in milliseconds
+    pub time: u128,
+    /// Step in which the rule was applied for the last time
+    pub last_applied: Option<usize>,
+}
+
+/// Summary of reading a single input resource
+#[derive(Debug, Serialize)]
+pub struct InputReport {
+    /// The resource, e.g. a file name or an IRI
+    pub resource: String,
+    /// Size of the resource in bytes (before decompression), if it has been read completely
+    pub bytes: Option<u64>,
+    /// SHA-256 hash of the resource (before decompression) as hexadecimal number,
+    /// if it has been read completely
+    pub checksum: Option<String>,
+    /// Number of rows that have been loaded
+    pub accepted_rows: u64,
+    /// Number of rows that have been ignored, since they could not be parsed
+    pub rejected_rows: u64,
+}
+
+/// Machine-readable summary of a run of nmo
+#[derive(Debug, Serialize)]
+pub struct RunReport {
+    /// Version of nmo
+    pub version: String,
+    /// Timing of the different phases
+    pub timing: TimingReport,
+    /// Number of rule applications
+    pub steps: usize,
+    /// Whether reasoning finished, i.e. was not stopped after a maximal number of rule applications
+    pub complete: bool,
+    /// Highest memory consumption of the tables after any rule application, in bytes
+    pub peak_memory: u64,
+    /// Memory consumption of the tables at the end of the run, in bytes
+    pub memory: u64,
+    /// Number of facts of derived predicates
+    pub derived_facts: usize,
+    /// Number of labelled nulls introduced by all rules
+    pub nulls: usize,
+    /// Summary for each predicate, sorted by name
+    pub predicates: Vec<PredicateReport>,
+    /// Summary for each rule, in the order of the program
+    pub rules: Vec<RuleReport>,
+    /// Summary for each input resource that has been read, sorted by resource
+    pub inputs: Vec<InputReport>,
+    /// Warnings issued during the run
+    pub warnings: Vec<String>,
+}
+
+impl RunReport {
+    /// Create a [`RunReport`] from the state of the given engine after reasoning,
+    /// which was stopped early unless `complete` is set.
+    pub fn new(engine: &DefaultExecutionEngine, complete: bool) -> Self {
+        let state = engine.state();
+        let output_predicates = engine.program().output_predicates().collect::<Vec<_>>();
+
+        let predicates = state
+            .predicates
+            .iter()
+            .map(|predicate| PredicateReport {
+                name: predicate.predicate.name(),
+                arity: predicate.arity(),
+                derived: predicate.is_derived,
+                output: output_predicates.contains(&predicate.predicate),
+                rows: predicate.rows,
+                nulls: predicate.introduced_nulls,
+                memory: predicate.memory.as_u64(),
+            })
+            .collect();
+
+        let rules = engine
+            .program()
+            .rules()
+            .iter()
+            .zip(engine.rule_infos())
+            .enumerate()
+            .map(|(index, (rule, info))| RuleReport {
+                index,
+                rule: rule.predicate_outline(),
+                head: rule
+                    .head()
+                    .iter()
+                    .map(|atom| atom.predicate().name())
+                    .collect(),
+                applications: info.applications,
+                derived_facts: info.derived_facts,
+                nulls: info.introduced_nulls,
+                time: info.duration.as_millis(),
+                last_applied: (info.step_last_applied > 0).then_some(info.step_last_applied),
+            })
+            .collect();
+
+        let inputs = engine
+            .input_statistics()
+            .into_iter()
+            .map(|statistics| InputReport {
+                checksum: statistics.checksum_hex(),
+                resource: statistics.resource,
+                bytes: statistics.bytes,
+                accepted_rows: statistics.accepted_rows,
+                rejected_rows: statistics.rejected_rows,
+            })
+            .collect();
+
+        Self {
+            version: clap::crate_version!().to_string(),
+            timing: TimingReport::from_timed_code(),
+            steps: state.steps,
+            complete,
+            peak_memory: engine.peak_memory().as_u64(),
+            memory: state.memory().as_u64(),
+            derived_facts: engine.count_facts_of_derived_predicates(),
+            nulls: engine
+                .rule_infos()
+                .iter()
+                .map(|info| info.introduced_nulls)
+                .sum(),
+            predicates,
+            rules,
+            inputs,
+            warnings: engine.warnings().to_vec(),
+        }
+    }
+
+    /// Write the report as JSON into the given file.
+    pub fn write(&self, path: PathBuf) -> Result<(), Error> {
+        let io_error = |error| Error::IOWriting {
+            error,
+            filename: path.to_string_lossy().to_string(),
+        };
+
+        let file = File::create(&path).map_err(io_error)?;
+        serde_json::to_writer_pretty(BufWriter::new(file), self)
+            .map_err(|error| io_error(error.into()))?;
+
+        Ok(())
+    }
+}
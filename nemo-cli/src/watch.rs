@@ -0,0 +1,69 @@
+//! Polling of input files for watch mode
+
+use std::{
+    collections::BTreeMap,
+    fs::{metadata, read_dir},
+    path::PathBuf,
+    thread::sleep,
+    time::{Duration, SystemTime},
+};
+
+/// Time between two checks for modified files
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Detects changes to a set of files and to the files contained in a set of directories
+#[derive(Debug)]
+pub struct FileWatcher {
+    paths: Vec<PathBuf>,
+    snapshot: BTreeMap<PathBuf, Option<SystemTime>>,
+}
+
+impl FileWatcher {
+    /// Start watching the given files and directories
+    pub fn new(paths: Vec<PathBuf>) -> Self {
+        let mut watcher = Self {
+            paths,
+            snapshot: BTreeMap::new(),
+        };
+        watcher.snapshot = watcher.take_snapshot();
+
+        watcher
+    }
+
+    /// Collect the modification time of every watched file.
+    ///
+    /// Files that cannot be accessed (e.g. since they have been deleted) are recorded without a time.
+    fn take_snapshot(&self) -> BTreeMap<PathBuf, Option<SystemTime>> {
+        let mut snapshot = BTreeMap::new();
+
+        for path in &self.paths {
+            match read_dir(path) {
+                Ok(entries) => {
+                    for entry in entries.flatten() {
+                        let modified = entry.metadata().and_then(|m| m.modified()).ok();
+                        snapshot.insert(entry.path(), modified);
+                    }
+                }
+                Err(_) => {
+                    let modified = metadata(path).and_then(|m| m.modified()).ok();
+                    snapshot.insert(path.clone(), modified);
+                }
+            }
+        }
+
+        snapshot
+    }
+
+    /// Block until a watched file has been created, modified or removed
+    pub fn wait_for_change(&mut self) {
+        loop {
+            sleep(POLL_INTERVAL);
+
+            let snapshot = self.take_snapshot();
+            if snapshot != self.snapshot {
+                self.snapshot = snapshot;
+                return;
+            }
+        }
+    }
+}
@@ -1,4 +1,5 @@
-use assert_cmd::prelude::*; // Add methods on commands
+use assert_cmd::{cargo::cargo_bin, prelude::*}; // Add methods on commands
+use assert_fs::{prelude::*, TempDir};
 use predicates::prelude::*;
 use std::process::Command; // Run programs
 use test_log::test;
@@ -50,3 +51,1178 @@ fn cli_argument_parsing() -> Result<(), Box<dyn std::error::Error>> {
         .stderr(predicate::str::contains("'--log <LOG_LEVEL>'"));
     Ok(())
 }
+
+#[cfg_attr(miri, ignore)]
+#[test]
+fn warm_start() -> Result<(), Box<dyn std::error::Error>> {
+    let bin = "nmo";
+    let temp_dir = TempDir::new()?;
+
+    let rules = "path(?x, ?y) :- edge(?x, ?y) .\n\
+                 path(?x, ?z) :- path(?x, ?y), edge(?y, ?z) .\n";
+
+    let run = |program: &str, output: &str, warm_start: Option<&str>, new_facts_only: bool| {
+        let file = temp_dir.child(format!("{output}.rls"));
+        file.write_str(program).unwrap();
+
+        let mut cmd = Command::cargo_bin(bin).unwrap();
+        if let Some(previous) = warm_start {
+            cmd.arg("--warm-start").arg(temp_dir.child(previous).path());
+        }
+        if new_facts_only {
+            cmd.arg("--new-facts-only");
+        }
+        cmd.arg("-s")
+            .arg("-D")
+            .arg(temp_dir.child(output).path())
+            .arg(file.path());
+        cmd.assert().success();
+
+        let result =
+            std::fs::read_to_string(temp_dir.child(output).child("path.csv").path()).unwrap();
+        let mut lines: Vec<String> = result.lines().map(String::from).collect();
+        lines.sort();
+        lines
+    };
+
+    let initial = run(
+        &format!("edge(1, 2) . edge(2, 3) .\n{rules}"),
+        "initial",
+        None,
+        false,
+    );
+    assert_eq!(initial.len(), 3);
+
+    let extended = format!("edge(1, 2) . edge(2, 3) . edge(3, 4) .\n{rules}");
+    let warm = run(&extended, "warm", Some("initial"), false);
+    let cold = run(&extended, "cold", None, false);
+
+    assert_eq!(warm.len(), 6);
+    assert_eq!(warm, cold);
+
+    let new = run(&extended, "new", Some("initial"), true);
+    assert_eq!(new.len(), 3);
+    assert!(new
+        .iter()
+        .all(|line| line.ends_with(r#","""4""^^<http://www.w3.org/2001/XMLSchema#integer>""#)));
+    assert!(new.iter().all(|line| cold.contains(line)));
+
+    Ok(())
+}
+
+#[cfg_attr(miri, ignore)]
+#[test]
+fn consequence_cache() -> Result<(), Box<dyn std::error::Error>> {
+    let bin = "nmo";
+    let temp_dir = TempDir::new()?;
+    let cache = temp_dir.child("cache");
+
+    let facts = "edge(1, 2) . edge(2, 3) .\n\
+                 path(?x, ?y) :- edge(?x, ?y) .\n\
+                 path(?x, ?z) :- path(?x, ?y), edge(?y, ?z) .\n";
+
+    let run = |program: &str, output: &str| {
+        let file = temp_dir.child(format!("{output}.rls"));
+        file.write_str(program).unwrap();
+
+        let mut cmd = Command::cargo_bin(bin).unwrap();
+        cmd.arg("--consequence-cache")
+            .arg(cache.path())
+            .arg("-s")
+            .arg("-D")
+            .arg(temp_dir.child(output).path())
+            .arg(file.path());
+        cmd.assert().success();
+
+        let read = |predicate: &str| {
+            let result = std::fs::read_to_string(
+                temp_dir
+                    .child(output)
+                    .child(format!("{predicate}.csv"))
+                    .path(),
+            )
+            .unwrap();
+            let mut lines: Vec<String> = result.lines().map(String::from).collect();
+            lines.sort();
+            lines
+        };
+        (read("path"), read("start"))
+    };
+
+    let (initial_path, initial_start) =
+        run(&format!("{facts}start(?x) :- edge(?x, 2) .\n"), "initial");
+    assert_eq!(initial_path.len(), 3);
+    assert_eq!(initial_start.len(), 1);
+
+    let (edited_path, edited_start) =
+        run(&format!("{facts}start(?x) :- edge(2, ?x) .\n"), "edited");
+    assert_eq!(edited_path, initial_path);
+    assert_eq!(edited_start.len(), 1);
+    assert_ne!(edited_start, initial_start);
+
+    let cached_files = std::fs::read_dir(cache.path())?.count();
+    assert_eq!(cached_files, 2);
+
+    Ok(())
+}
+
+#[cfg_attr(miri, ignore)]
+#[test]
+fn input_cache() -> Result<(), Box<dyn std::error::Error>> {
+    let bin = "nmo";
+    let temp_dir = TempDir::new()?;
+    let cache = temp_dir.child("cache");
+
+    temp_dir.child("edge.csv").write_str("a,b\nb,c\na,b\n")?;
+    let program = temp_dir.child("program.rls");
+    program.write_str(
+        "@source edge[2]: load-csv(\"edge.csv\") .\n\
+         path(?x, ?y) :- edge(?x, ?y) .\n\
+         path(?x, ?z) :- path(?x, ?y), edge(?y, ?z) .\n",
+    )?;
+
+    let run = |output: &str| {
+        let mut cmd = Command::cargo_bin(bin).unwrap();
+        cmd.arg("--input-cache")
+            .arg(cache.path())
+            .arg("-I")
+            .arg(temp_dir.path())
+            .arg("-s")
+            .arg("-D")
+            .arg(temp_dir.child(output).path())
+            .arg(program.path());
+        cmd.assert().success();
+
+        let result =
+            std::fs::read_to_string(temp_dir.child(output).child("path.csv").path()).unwrap();
+        let mut lines: Vec<String> = result.lines().map(String::from).collect();
+        lines.sort();
+        lines
+    };
+
+    let initial = run("initial");
+    assert_eq!(initial, vec!["a,b", "a,c", "b,c"]);
+    assert_eq!(std::fs::read_dir(cache.path())?.count(), 1);
+
+    assert_eq!(run("cached"), initial);
+    assert_eq!(std::fs::read_dir(cache.path())?.count(), 1);
+
+    Ok(())
+}
+
+#[cfg_attr(miri, ignore)]
+#[test]
+fn run_report() -> Result<(), Box<dyn std::error::Error>> {
+    let bin = "nmo";
+    let temp_dir = TempDir::new()?;
+
+    let rules = temp_dir.child("rules.rls");
+    rules.write_str(
+        "edge(1, 2) . edge(2, 3) . edge(3, 4) .\n\
+         path(?x, ?y) :- edge(?x, ?y) .\n\
+         path(?x, ?z) :- path(?x, ?y), edge(?y, ?z) .\n",
+    )?;
+    let report = temp_dir.child("report.json");
+
+    let mut cmd = Command::cargo_bin(bin)?;
+    cmd.arg("--report").arg(report.path()).arg(rules.path());
+    cmd.assert().success();
+
+    let report: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(report.path())?)?;
+
+    assert!(report["steps"].as_u64().unwrap() > 0);
+    assert_eq!(report["complete"], true);
+    assert_eq!(report["derived_facts"], 6);
+    assert_eq!(report["nulls"], 0);
+    assert!(report["peak_memory"].as_u64().unwrap() >= report["memory"].as_u64().unwrap());
+
+    let predicates = report["predicates"].as_array().unwrap();
+    assert_eq!(predicates.len(), 2);
+    assert_eq!(predicates[1]["name"], "path");
+    assert_eq!(predicates[1]["rows"], 6);
+    assert_eq!(predicates[1]["derived"], true);
+
+    let rules = report["rules"].as_array().unwrap();
+    assert_eq!(rules.len(), 2);
+    let derived: u64 = rules
+        .iter()
+        .map(|rule| rule["derived_facts"].as_u64().unwrap())
+        .sum();
+    assert_eq!(derived, 6);
+    assert!(rules
+        .iter()
+        .all(|rule| rule["applications"].as_u64().unwrap() > 0));
+
+    Ok(())
+}
+
+#[cfg_attr(miri, ignore)]
+#[test]
+fn run_report_nulls() -> Result<(), Box<dyn std::error::Error>> {
+    let bin = "nmo";
+    let temp_dir = TempDir::new()?;
+
+    let rules = temp_dir.child("rules.rls");
+    rules.write_str(
+        "person(alice) . person(bob) . person(dave) . parent(alice, carol) .\n\
+         parent(?x, !y) :- person(?x) .\n\
+         ancestor(?y) :- parent(?x, ?y) .\n",
+    )?;
+    let report = temp_dir.child("report.json");
+
+    let mut cmd = Command::cargo_bin(bin)?;
+    cmd.arg("--report").arg(report.path()).arg(rules.path());
+    cmd.assert().success();
+
+    let report: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(report.path())?)?;
+
+    assert_eq!(report["nulls"], 2);
+
+    let predicate_nulls = |name: &str| {
+        report["predicates"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|predicate| predicate["name"] == name)
+            .unwrap()["nulls"]
+            .clone()
+    };
+    assert_eq!(predicate_nulls("parent"), 2);
+    assert_eq!(predicate_nulls("ancestor"), 0);
+
+    let rules = report["rules"].as_array().unwrap();
+    assert_eq!(rules[0]["nulls"], 2);
+    assert_eq!(rules[1]["nulls"], 0);
+
+    Ok(())
+}
+
+#[cfg_attr(miri, ignore)]
+#[test]
+fn max_iterations() -> Result<(), Box<dyn std::error::Error>> {
+    let bin = "nmo";
+    let temp_dir = TempDir::new()?;
+
+    let rules = temp_dir.child("rules.rls");
+    rules.write_str(
+        "@declare nat(integer) .\n\
+         nat(0) .\n\
+         nat(?x + 1) :- nat(?x) .\n",
+    )?;
+    let report = temp_dir.child("report.json");
+
+    let mut cmd = Command::cargo_bin(bin)?;
+    cmd.arg("--max-iterations")
+        .arg("10")
+        .arg("--report")
+        .arg(report.path())
+        .arg("-s")
+        .arg("-D")
+        .arg(temp_dir.child("results").path())
+        .arg(rules.path());
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "stopped after 10 rule applications",
+        ))
+        .stdout(predicate::str::contains("the results are partial"));
+
+    let report: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(report.path())?)?;
+    assert_eq!(report["steps"], 10);
+    assert_eq!(report["complete"], false);
+    assert_eq!(
+        std::fs::read_to_string(temp_dir.child("results").child("nat.csv").path())?
+            .lines()
+            .count(),
+        11
+    );
+
+    Ok(())
+}
+
+#[cfg_attr(miri, ignore)]
+#[test]
+fn firing_log() -> Result<(), Box<dyn std::error::Error>> {
+    let bin = "nmo";
+    let temp_dir = TempDir::new()?;
+
+    let rules = temp_dir.child("rules.rls");
+    rules.write_str(
+        "edge(1, 2) . edge(2, 3) . edge(3, 4) .\n\
+         path(?x, ?y) :- edge(?x, ?y) .\n\
+         path(?x, ?z) :- path(?x, ?y), edge(?y, ?z) .\n",
+    )?;
+    let log = temp_dir.child("firings.csv");
+
+    let mut cmd = Command::cargo_bin(bin)?;
+    cmd.arg("--firing-log")
+        .arg(log.path())
+        .arg("--firing-log-sample")
+        .arg("2")
+        .arg(rules.path());
+    cmd.assert().success();
+
+    log.assert(predicate::str::starts_with(
+        "rule,step,matches,derived,bindings\n\
+         0,1,3,3,?x=1 ?y=2\n\
+         0,1,3,3,?x=2 ?y=3\n",
+    ));
+    // The second rule is first applied to the two paths of length one
+    log.assert(predicate::str::contains("\n1,3,2,2,"));
+    log.assert(predicate::str::contains("?x=3 ?y=4").not());
+
+    let mut cmd = Command::cargo_bin(bin)?;
+    cmd.arg("--firing-log-sample").arg("2").arg(rules.path());
+    cmd.assert().failure();
+
+    Ok(())
+}
+
+#[cfg_attr(miri, ignore)]
+#[test]
+fn disjunctive_heads() -> Result<(), Box<dyn std::error::Error>> {
+    let bin = "nmo";
+    let temp_dir = TempDir::new()?;
+
+    let rules = temp_dir.child("rules.rls");
+    rules.write_str(
+        "node(a) . node(b) .\n\
+         red(?x) | green(?x) :- node(?x) .\n",
+    )?;
+
+    let mut cmd = Command::cargo_bin(bin)?;
+    cmd.arg(rules.path());
+    cmd.assert().failure().stderr(predicate::str::contains(
+        "Disjunctive rule heads are not supported",
+    ));
+
+    let run = |mode: &str| {
+        let mut cmd = Command::cargo_bin(bin).unwrap();
+        cmd.arg("--disjunctive-heads")
+            .arg(mode)
+            .arg("-s")
+            .arg("-D")
+            .arg(temp_dir.child(mode).path())
+            .arg(rules.path());
+        cmd.assert().success();
+
+        let count = |predicate: &str| {
+            std::fs::read_to_string(
+                temp_dir
+                    .child(mode)
+                    .child(format!("{predicate}.csv"))
+                    .path(),
+            )
+            .map(|result| result.lines().count())
+            .unwrap_or_default()
+        };
+        (count("red"), count("green"))
+    };
+
+    assert_eq!(run("first"), (2, 0));
+    assert_eq!(run("split"), (2, 2));
+
+    Ok(())
+}
+
+#[cfg_attr(miri, ignore)]
+#[test]
+fn r2rml_mapping() -> Result<(), Box<dyn std::error::Error>> {
+    let bin = "nmo";
+    let temp_dir = TempDir::new()?;
+
+    temp_dir
+        .child("people.csv")
+        .write_str("id,name,manager\n1,Alice,\n2,Bob,1\n")?;
+    let mapping = temp_dir.child("mapping.ttl");
+    mapping.write_str(
+        "@prefix rr: <http://www.w3.org/ns/r2rml#> .\n\
+         @prefix ex: <http://example.org/> .\n\
+         <#PersonMap> rr:logicalTable [ rr:tableName \"people\" ] ;\n\
+             rr:subjectMap [ rr:template \"http://example.org/person/{id}\" ; rr:class ex:Person ] ;\n\
+             rr:predicateObjectMap [ rr:predicate ex:name ; rr:objectMap [ rr:column \"name\" ] ] ;\n\
+             rr:predicateObjectMap [ rr:predicate ex:manager ;\n\
+                 rr:objectMap [ rr:template \"http://example.org/person/{manager}\" ] ] .\n",
+    )?;
+    let rules = temp_dir.child("rules.rls");
+    rules.write_str(
+        "managed(?x) :- triple(?x, <http://example.org/manager>, ?y) .\n\
+         @output triple .\n\
+         @output managed .\n",
+    )?;
+
+    let mut cmd = Command::cargo_bin(bin)?;
+    cmd.arg("--r2rml")
+        .arg(mapping.path())
+        .arg("-I")
+        .arg(temp_dir.path())
+        .arg("-s")
+        .arg("-D")
+        .arg(temp_dir.child("results").path())
+        .arg(rules.path());
+    cmd.assert().success();
+
+    let read = |predicate: &str| {
+        std::fs::read_to_string(
+            temp_dir
+                .child("results")
+                .child(format!("{predicate}.csv"))
+                .path(),
+        )
+        .unwrap()
+    };
+
+    assert_eq!(read("triple").lines().count(), 5);
+    assert_eq!(read("managed").trim(), "http://example.org/person/2");
+
+    Ok(())
+}
+
+#[cfg_attr(miri, ignore)]
+#[test]
+fn tptp_problem() -> Result<(), Box<dyn std::error::Error>> {
+    let bin = "nmo";
+    let temp_dir = TempDir::new()?;
+
+    let run = |conjecture: &str| {
+        let problem = temp_dir.child("problem.p");
+        problem
+            .write_str(&format!(
+                "cnf(a, axiom, edge(a, b)).\n\
+                 cnf(b, axiom, edge(b, c)).\n\
+                 cnf(c, axiom, path(X, Y) | ~edge(X, Y)).\n\
+                 cnf(d, axiom, path(X, Z) | ~path(X, Y) | ~edge(Y, Z)).\n\
+                 fof(goal, conjecture, {conjecture}).\n"
+            ))
+            .unwrap();
+
+        let mut cmd = Command::cargo_bin(bin).unwrap();
+        cmd.arg("--tptp").arg(problem.path());
+        cmd.assert()
+    };
+
+    run("path(a, c)").success().stdout(predicate::str::contains(
+        "The conjecture follows from the axioms.",
+    ));
+    run("path(c, a)").success().stdout(predicate::str::contains(
+        "The conjecture does not follow from the axioms.",
+    ));
+
+    Ok(())
+}
+
+#[cfg_attr(miri, ignore)]
+#[test]
+fn n3_rules() -> Result<(), Box<dyn std::error::Error>> {
+    let bin = "nmo";
+    let temp_dir = TempDir::new()?;
+
+    let rules = temp_dir.child("rules.n3");
+    rules.write_str(
+        "@prefix : <http://example.org/> .\n\
+         :a :edge :b . :b :edge :c .\n\
+         { ?x :edge ?y } => { ?x :path ?y } .\n\
+         { ?x :path ?y . ?y :edge ?z } => { ?x :path ?z } .\n",
+    )?;
+
+    let mut cmd = Command::cargo_bin(bin)?;
+    cmd.arg("--n3")
+        .arg("-s")
+        .arg("-D")
+        .arg(temp_dir.child("results").path())
+        .arg(rules.path());
+    cmd.assert().success();
+
+    let triples = std::fs::read_to_string(temp_dir.child("results").child("triple.csv").path())?;
+    assert_eq!(triples.lines().count(), 5);
+    assert!(triples.contains("http://example.org/a,http://example.org/path,http://example.org/c"));
+
+    rules.write_str("{ ?x :p ?y } => { ?x :q ?z } .\n")?;
+    let mut cmd = Command::cargo_bin(bin)?;
+    cmd.arg("--n3").arg(rules.path());
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("The prefix \"\" is not declared."));
+
+    Ok(())
+}
+
+#[cfg_attr(miri, ignore)]
+#[test]
+fn swrl_rules() -> Result<(), Box<dyn std::error::Error>> {
+    let bin = "nmo";
+    let temp_dir = TempDir::new()?;
+
+    let ontology = temp_dir.child("family.owl");
+    ontology.write_str(
+        r#"<?xml version="1.0"?>
+<rdf:RDF xmlns="http://example.org/family#"
+         xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#"
+         xmlns:swrl="http://www.w3.org/2003/11/swrl#">
+  <rdf:Description rdf:about="http://example.org/family#alice">
+    <hasParent rdf:resource="http://example.org/family#bob"/>
+  </rdf:Description>
+  <rdf:Description rdf:about="http://example.org/family#bob">
+    <hasBrother rdf:resource="http://example.org/family#carl"/>
+  </rdf:Description>
+  <swrl:Variable rdf:about="urn:swrl:var#x"/>
+  <swrl:Variable rdf:about="urn:swrl:var#y"/>
+  <swrl:Variable rdf:about="urn:swrl:var#z"/>
+  <swrl:Imp rdf:about="http://example.org/family#uncle">
+    <swrl:body rdf:parseType="Collection">
+      <swrl:IndividualPropertyAtom>
+        <swrl:propertyPredicate rdf:resource="http://example.org/family#hasParent"/>
+        <swrl:argument1 rdf:resource="urn:swrl:var#x"/>
+        <swrl:argument2 rdf:resource="urn:swrl:var#y"/>
+      </swrl:IndividualPropertyAtom>
+      <swrl:IndividualPropertyAtom>
+        <swrl:propertyPredicate rdf:resource="http://example.org/family#hasBrother"/>
+        <swrl:argument1 rdf:resource="urn:swrl:var#y"/>
+        <swrl:argument2 rdf:resource="urn:swrl:var#z"/>
+      </swrl:IndividualPropertyAtom>
+    </swrl:body>
+    <swrl:head rdf:parseType="Collection">
+      <swrl:IndividualPropertyAtom>
+        <swrl:propertyPredicate rdf:resource="http://example.org/family#hasUncle"/>
+        <swrl:argument1 rdf:resource="urn:swrl:var#x"/>
+        <swrl:argument2 rdf:resource="urn:swrl:var#z"/>
+      </swrl:IndividualPropertyAtom>
+    </swrl:head>
+  </swrl:Imp>
+</rdf:RDF>
+"#,
+    )?;
+    let rules = temp_dir.child("rules.rls");
+    rules.write_str(
+        "@prefix family: <http://example.org/family#> .\n\
+         uncle(?x, ?z) :- triple(?x, family:hasUncle, ?z) .\n\
+         @output uncle .\n",
+    )?;
+
+    let mut cmd = Command::cargo_bin(bin)?;
+    cmd.arg("--ontology")
+        .arg(ontology.path())
+        .arg("-s")
+        .arg("-D")
+        .arg(temp_dir.child("results").path())
+        .arg(rules.path());
+    cmd.assert().success();
+
+    assert_eq!(
+        std::fs::read_to_string(temp_dir.child("results").child("uncle.csv").path())?.trim(),
+        "http://example.org/family#alice,http://example.org/family#carl"
+    );
+
+    Ok(())
+}
+
+#[cfg_attr(miri, ignore)]
+#[test]
+fn anonymize() -> Result<(), Box<dyn std::error::Error>> {
+    let bin = "nmo";
+    let temp_dir = TempDir::new()?;
+
+    temp_dir
+        .child("staff.csv")
+        .write_str("alice,\"Alice Smith\",42\nbob,Bob,7\n")?;
+    let rules = temp_dir.child("rules.rls");
+    rules.write_str(
+        "@prefix acme: <http://acme.com/> .\n\
+         @source staff[any, string, integer]: load-csv(\"staff.csv\") .\n\
+         boss(acme:carol) .\n\
+         senior(?N, ?B) :- staff(?N, ?Name, ?Age), ?Age > 10, boss(?B) .\n\
+         @output senior .\n",
+    )?;
+
+    let mut cmd = Command::cargo_bin(bin)?;
+    cmd.arg("anonymize")
+        .arg("-I")
+        .arg(temp_dir.path())
+        .arg("-D")
+        .arg(temp_dir.child("anonymized").path())
+        .arg(rules.path());
+    cmd.assert().success();
+
+    let program = std::fs::read_to_string(temp_dir.child("anonymized").child("program.rls"))?;
+    let data = std::fs::read_to_string(temp_dir.child("anonymized").child("p1.csv"))?;
+    for name in ["staff", "senior", "acme", "alice", "Alice", "carol"] {
+        assert!(!program.contains(name) && !data.contains(name));
+    }
+    assert!(data.contains(",42"));
+
+    let mut cmd = Command::cargo_bin(bin)?;
+    cmd.arg("-I")
+        .arg(temp_dir.child("anonymized").path())
+        .arg("-s")
+        .arg("-D")
+        .arg(temp_dir.child("results").path())
+        .arg(temp_dir.child("anonymized").child("program.rls").path());
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Derived 1 facts"));
+
+    Ok(())
+}
+
+#[cfg_attr(miri, ignore)]
+#[test]
+fn dictionary_export_import() -> Result<(), Box<dyn std::error::Error>> {
+    let bin = "nmo";
+    let temp_dir = TempDir::new()?;
+    let dictionary = temp_dir.child("constants.dict");
+
+    let first = temp_dir.child("first.rls");
+    first.write_str("edge(a, b) . edge(b, c) .\n")?;
+    let mut cmd = Command::cargo_bin(bin)?;
+    cmd.arg("--export-dictionary")
+        .arg(dictionary.path())
+        .arg(first.path());
+    cmd.assert().success();
+    dictionary.assert(predicate::path::exists());
+
+    let second = temp_dir.child("second.rls");
+    second.write_str("edge(c, d) .\npath(?x, ?y) :- edge(?x, ?y) .\n")?;
+    let mut cmd = Command::cargo_bin(bin)?;
+    cmd.arg("--import-dictionary")
+        .arg(dictionary.path())
+        .arg("-s")
+        .arg("-D")
+        .arg(temp_dir.child("results").path())
+        .arg(second.path());
+    cmd.assert().success();
+    temp_dir.child("results").child("path.csv").assert("c,d\n");
+
+    let mut cmd = Command::cargo_bin(bin)?;
+    cmd.arg("--import-dictionary")
+        .arg(temp_dir.child("missing.dict").path())
+        .arg(second.path());
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("missing.dict"));
+
+    Ok(())
+}
+
+#[test]
+fn reduce() -> Result<(), Box<dyn std::error::Error>> {
+    let bin = "nmo";
+    let temp_dir = TempDir::new()?;
+
+    temp_dir
+        .child("edge.csv")
+        .write_str("a,b\nb,c\nc,d\nx,y\ny,z\n")?;
+    let rules = temp_dir.child("rules.rls");
+    rules.write_str(
+        "@source edge[any, any]: load-csv(\"edge.csv\") .\n\
+         node(a) .\n\
+         reach(?X, ?Y) :- edge(?X, ?Y) .\n\
+         reach(?X, ?Z) :- reach(?X, ?Y), edge(?Y, ?Z) .\n\
+         degree(?X, #count(?Y)) :- edge(?X, ?Y) .\n",
+    )?;
+    let check = format!(
+        "{} -s -D results program.rls && grep -q '^a,d$' results/reach.csv",
+        cargo_bin(bin).display()
+    );
+
+    let mut cmd = Command::cargo_bin(bin)?;
+    cmd.arg("reduce")
+        .arg("--check")
+        .arg(&check)
+        .arg("-I")
+        .arg(temp_dir.path())
+        .arg("-D")
+        .arg(temp_dir.child("reduced").path())
+        .arg(rules.path());
+    cmd.assert().success().stdout(predicate::str::contains(
+        "3 of 7 statements and 3 of 5 input facts",
+    ));
+
+    let program = std::fs::read_to_string(temp_dir.child("reduced").child("program.rls"))?;
+    assert_eq!(
+        program,
+        "@source edge[any, any]: load-csv(\"edge.csv\") .\n\
+         reach(?X, ?Y) :- edge(?X, ?Y) .\n\
+         reach(?X, ?Z) :- reach(?X, ?Y), edge(?Y, ?Z) .\n"
+    );
+    assert_eq!(
+        std::fs::read_to_string(temp_dir.child("reduced").child("edge.csv"))?,
+        "a,b\nb,c\nc,d\n"
+    );
+
+    let mut cmd = Command::cargo_bin(bin)?;
+    cmd.arg("reduce")
+        .arg("--check")
+        .arg("false")
+        .arg("-I")
+        .arg(temp_dir.path())
+        .arg("-D")
+        .arg(temp_dir.child("unreduced").path())
+        .arg(rules.path());
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("does not succeed"));
+
+    Ok(())
+}
+
+#[cfg_attr(miri, ignore)]
+#[test]
+fn peek() -> Result<(), Box<dyn std::error::Error>> {
+    let bin = "nmo";
+    let temp_dir = TempDir::new()?;
+
+    let rules = temp_dir.child("rules.rls");
+    rules.write_str(
+        "@declare nat(integer) .\n\
+         nat(0) .\n\
+         nat(?X + 1) :- nat(?X) .\n\
+         other(?X) :- nat(?X) .\n",
+    )?;
+
+    let mut cmd = Command::cargo_bin(bin)?;
+    cmd.arg("peek")
+        .arg("--predicate")
+        .arg("nat")
+        .arg("--limit")
+        .arg("3")
+        .arg(rules.path());
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("nat(0)\nnat(1)\nnat(2)\n"))
+        .stdout(predicate::str::contains("nat(3)").not())
+        .stdout(predicate::str::contains("other").not())
+        .stdout(predicate::str::contains("reasoning was stopped"));
+
+    let mut cmd = Command::cargo_bin(bin)?;
+    cmd.arg("peek")
+        .arg("--predicate")
+        .arg("other")
+        .arg("--max-steps")
+        .arg("5")
+        .arg(rules.path());
+    cmd.assert().success().stdout(predicate::str::contains(
+        "reasoning was stopped after 5 steps",
+    ));
+
+    Ok(())
+}
+
+#[cfg_attr(miri, ignore)]
+#[test]
+fn compile() -> Result<(), Box<dyn std::error::Error>> {
+    let bin = "nmo";
+    let temp_dir = TempDir::new()?;
+
+    let rules = temp_dir.child("rules.rls");
+    rules.write_str(
+        "edge(a, b) . edge(b, c) .\n\
+         path(?X, ?Y) :- edge(?X, ?Y) .\n\
+         path(?X, ?Z) :- path(?X, ?Y), edge(?Y, ?Z) .\n",
+    )?;
+    let compiled = temp_dir.child("program.nmoc");
+
+    let mut cmd = Command::cargo_bin(bin)?;
+    cmd.arg("compile")
+        .arg(rules.path())
+        .arg("-o")
+        .arg(compiled.path());
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Compiled program written to"));
+    compiled.assert(predicate::path::exists());
+
+    let mut cmd = Command::cargo_bin(bin)?;
+    cmd.arg(compiled.path());
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Reasoning completed"));
+
+    let invalid = temp_dir.child("invalid.rls");
+    invalid.write_str("p(?X) :- q(?X), q(?X, ?Y) .\n")?;
+
+    let mut cmd = Command::cargo_bin(bin)?;
+    cmd.arg("compile").arg(invalid.path());
+    cmd.assert().failure();
+    temp_dir
+        .child("invalid.nmoc")
+        .assert(predicate::path::missing());
+
+    Ok(())
+}
+
+#[cfg_attr(miri, ignore)]
+#[test]
+fn watch_mode() -> Result<(), Box<dyn std::error::Error>> {
+    use std::{
+        io::{BufRead, BufReader},
+        process::Stdio,
+        sync::mpsc::channel,
+        time::Duration,
+    };
+
+    let bin = "nmo";
+    let temp_dir = TempDir::new()?;
+    let rules = temp_dir.child("rules.rls");
+    rules.write_str(
+        "edge(a, b) .\n\
+         path(?X, ?Y) :- edge(?X, ?Y) .\n",
+    )?;
+
+    let mut child = Command::cargo_bin(bin)?
+        .arg("--watch")
+        .arg(rules.path())
+        .stdout(Stdio::piped())
+        .spawn()?;
+
+    let (sender, receiver) = channel();
+    let stdout = child.stdout.take().expect("stdout is piped");
+    std::thread::spawn(move || {
+        for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+            if sender.send(line).is_err() {
+                break;
+            }
+        }
+    });
+
+    let next_summary = || -> Option<String> {
+        let mut summary = None;
+        loop {
+            let line = receiver.recv_timeout(Duration::from_secs(60)).ok()?;
+            if line.starts_with("Reasoning completed") {
+                summary = Some(line);
+            } else if line.starts_with("Watching for changes") {
+                return summary;
+            }
+        }
+    };
+
+    let first = next_summary();
+    rules.write_str(
+        "edge(a, b) .\n\
+         edge(b, c) .\n\
+         path(?X, ?Y) :- edge(?X, ?Y) .\n\
+         path(?X, ?Z) :- path(?X, ?Y), edge(?Y, ?Z) .\n",
+    )?;
+    let second = next_summary();
+
+    child.kill()?;
+    child.wait()?;
+
+    assert!(first.expect("reasoning runs").contains("Derived 1 facts"));
+    assert!(second
+        .expect("reasoning runs again")
+        .contains("Derived 3 facts"));
+
+    Ok(())
+}
+
+#[cfg_attr(miri, ignore)]
+#[test]
+fn key_filters() -> Result<(), Box<dyn std::error::Error>> {
+    let bin = "nmo";
+    let temp_dir = TempDir::new()?;
+
+    let rules = temp_dir.child("rules.rls");
+    rules.write_str(
+        "edge(1, 2) . edge(2, 3) . edge(3, 4) . edge(10, 11) .\n\
+         start(1) . start(10) .\n\
+         stop(20, late) .\n\
+         reach(?x) :- start(?x) .\n\
+         reach(?y) :- reach(?x), edge(?x, ?y) .\n\
+         late(?x, ?n) :- reach(?x), stop(?x, ?n) .\n\
+         fork(?x, ?y, ?z) :- edge(?x, ?y), edge(?x, ?z) .\n",
+    )?;
+
+    let run = |output: &str, key_filters: bool| {
+        let mut cmd = Command::cargo_bin(bin).unwrap();
+        if key_filters {
+            cmd.arg("--key-filters");
+        }
+        cmd.arg("--write-all-idb-predicates")
+            .arg("-s")
+            .arg("-D")
+            .arg(temp_dir.child(output).path())
+            .arg(rules.path());
+        cmd.assert().success();
+
+        ["reach", "late", "fork"].map(|predicate| {
+            let result = std::fs::read_to_string(
+                temp_dir
+                    .child(output)
+                    .child(format!("{predicate}.csv"))
+                    .path(),
+            )
+            .unwrap_or_default();
+            let mut lines: Vec<String> = result.lines().map(String::from).collect();
+            lines.sort();
+            lines
+        })
+    };
+
+    let filtered = run("filtered", true);
+    assert_eq!(filtered.each_ref().map(Vec::len), [6, 0, 4]);
+    assert_eq!(filtered, run("unfiltered", false));
+
+    Ok(())
+}
+
+#[cfg_attr(miri, ignore)]
+#[test]
+fn estimated_matches() -> Result<(), Box<dyn std::error::Error>> {
+    let bin = "nmo";
+    let temp_dir = TempDir::new()?;
+
+    let rules = temp_dir.child("rules.rls");
+    rules.write_str(
+        "edge(1, 2) . edge(2, 3) . edge(3, 4) .\n\
+         pair(?x, ?y) :- edge(?x, ?a), edge(?y, ?b) .\n",
+    )?;
+
+    let mut cmd = Command::cargo_bin(bin)?;
+    cmd.arg("--explain").arg(rules.path());
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Rule 0 (pair): ~9 estimated matches",
+        ))
+        .stdout(predicate::str::contains("edge: 3 rows (3 new)"))
+        .stdout(predicate::str::contains("Reasoning completed").not());
+
+    let mut cmd = Command::cargo_bin(bin)?;
+    cmd.arg("--max-estimated-matches")
+        .arg("5")
+        .arg(rules.path());
+    cmd.assert().success().stderr(predicate::str::contains(
+        "Application of rule 0 in step 1 is estimated to produce 9 matches",
+    ));
+
+    Ok(())
+}
+
+#[cfg_attr(miri, ignore)]
+#[test]
+fn subcommands() -> Result<(), Box<dyn std::error::Error>> {
+    let bin = "nmo";
+    let temp_dir = TempDir::new()?;
+
+    let rules = temp_dir.child("rules.rls");
+    rules.write_str(
+        "parent(alice, bob) . parent(bob, carol) .\n\
+         ancestor(?x, ?y) :- parent(?x, ?y) .\n\
+         ancestor(?x, ?z) :- ancestor(?x, ?y), parent(?y, ?z) .\n",
+    )?;
+    let results = temp_dir.child("results");
+
+    let mut cmd = Command::cargo_bin(bin)?;
+    cmd.arg("check").arg(rules.path());
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("2 rules over 2 predicates"))
+        .stdout(predicate::str::contains("Fragment: Datalog"));
+
+    let mut cmd = Command::cargo_bin(bin)?;
+    cmd.arg("run")
+        .arg("--write-all-idb-predicates")
+        .arg("-s")
+        .arg("-D")
+        .arg(results.path())
+        .arg(rules.path());
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Reasoning completed"));
+
+    let mut cmd = Command::cargo_bin(bin)?;
+    cmd.arg("query")
+        .arg(results.path())
+        .arg("ancestor(alice, ?x)");
+    cmd.assert().success().stdout("?x\nbob\ncarol\n");
+
+    let mut cmd = Command::cargo_bin(bin)?;
+    cmd.arg("query")
+        .arg(results.path())
+        .arg("ancestor(?x, _)")
+        .arg("--format")
+        .arg("json");
+    let output = cmd.assert().success().get_output().stdout.clone();
+    assert_eq!(
+        serde_json::from_slice::<serde_json::Value>(&output)?,
+        serde_json::json!([{"x": "alice"}, {"x": "bob"}])
+    );
+
+    let mut cmd = Command::cargo_bin(bin)?;
+    cmd.arg("trace")
+        .arg(rules.path())
+        .arg("ancestor(alice, carol)")
+        .arg("--results")
+        .arg(results.path());
+    cmd.assert().success().stdout(
+        "ancestor(alice, carol) [rule 1]\n\
+         \x20 ancestor(alice, bob) [rule 0]\n\
+         \x20   parent(alice, bob) [given]\n\
+         \x20 parent(bob, carol) [given]\n",
+    );
+
+    let mut cmd = Command::cargo_bin(bin)?;
+    cmd.arg("check").arg(rules.path()).arg("-s");
+    cmd.assert().failure();
+
+    Ok(())
+}
+
+#[cfg_attr(miri, ignore)]
+#[test]
+fn assert_saved_results() -> Result<(), Box<dyn std::error::Error>> {
+    let bin = "nmo";
+    let temp_dir = TempDir::new()?;
+
+    let rules = temp_dir.child("rules.rls");
+    rules.write_str(
+        "person(alice) . person(bob) . age(alice, 31) .\n\
+         known(?x) :- age(?x, ?y) .\n\
+         violation(?x) :- person(?x), ~known(?x) .\n",
+    )?;
+    let results = temp_dir.child("results");
+
+    let mut cmd = Command::cargo_bin(bin)?;
+    cmd.arg("--write-all-idb-predicates")
+        .arg("-s")
+        .arg("-D")
+        .arg(results.path())
+        .arg(rules.path());
+    cmd.assert().success();
+
+    let assert = |query: &str, expectation: &str| {
+        let mut cmd = Command::cargo_bin(bin).unwrap();
+        cmd.arg("assert")
+            .arg(results.path())
+            .arg(query)
+            .arg(expectation);
+        cmd.assert()
+    };
+
+    assert("violation(?x)", "--expect-empty")
+        .code(3)
+        .stdout(predicate::str::contains("has 1 matches"))
+        .stdout(predicate::str::contains("?x\nbob\n"));
+    assert("violation(alice)", "--expect-empty").success();
+    assert("known(?x)", "--expect-non-empty").success();
+    assert("known(bob)", "--expect-non-empty").code(3);
+    assert("unknown(?x)", "--expect-empty").code(1);
+
+    Ok(())
+}
+
+#[cfg_attr(miri, ignore)]
+#[test]
+fn variables_in_resources() -> Result<(), Box<dyn std::error::Error>> {
+    let bin = "nmo";
+    let temp_dir = TempDir::new()?;
+
+    temp_dir
+        .child("staging")
+        .child("edges.csv")
+        .write_str("a,b\nb,c\n")?;
+    let rules = temp_dir.child("rules.rls");
+    rules.write_str(
+        "@source edge[2]: load-csv(\"${NEMO_CLI_TEST_STAGE}/edges.csv\") .\n\
+         node(?x) :- edge(?x, ?y) .\n",
+    )?;
+
+    let mut cmd = Command::cargo_bin(bin)?;
+    cmd.current_dir(temp_dir.path()).arg(rules.path());
+    cmd.assert().failure().stderr(predicate::str::contains(
+        "The variable \"NEMO_CLI_TEST_STAGE\" is not set",
+    ));
+
+    let mut cmd = Command::cargo_bin(bin)?;
+    cmd.current_dir(temp_dir.path())
+        .env("NEMO_CLI_TEST_STAGE", "staging")
+        .arg(rules.path());
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Derived 2 facts"));
+
+    temp_dir
+        .child(".env")
+        .write_str("NEMO_CLI_TEST_STAGE=staging\n")?;
+    let mut cmd = Command::cargo_bin(bin)?;
+    cmd.current_dir(temp_dir.path()).arg(rules.path());
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Derived 2 facts"));
+
+    Ok(())
+}
+
+#[cfg_attr(miri, ignore)]
+#[test]
+fn paths_relative_to_rules() -> Result<(), Box<dyn std::error::Error>> {
+    let bin = "nmo";
+    let temp_dir = TempDir::new()?;
+    let project = temp_dir.child("project");
+
+    project
+        .child("data")
+        .child("edges.csv")
+        .write_str("a,b\nb,c\n")?;
+    let rules = project.child("rules.rls");
+    rules.write_str(
+        "@source edge[2]: load-csv(\"data/edges.csv\") .\n\
+         node(?x) :- edge(?x, ?y) .\n",
+    )?;
+
+    let mut cmd = Command::cargo_bin(bin)?;
+    cmd.current_dir(temp_dir.path()).arg("project/rules.rls");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Derived 2 facts"));
+
+    let mut cmd = Command::cargo_bin(bin)?;
+    cmd.current_dir(temp_dir.path())
+        .arg("--relative-to")
+        .arg("cwd")
+        .arg("project/rules.rls");
+    cmd.assert().failure();
+
+    let mut cmd = Command::cargo_bin(bin)?;
+    cmd.current_dir(project.path())
+        .arg("--relative-to")
+        .arg("cwd")
+        .arg(rules.path());
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Derived 2 facts"));
+
+    Ok(())
+}
+
+#[cfg_attr(miri, ignore)]
+#[test]
+fn rdf_format_detection() -> Result<(), Box<dyn std::error::Error>> {
+    let bin = "nmo";
+    let temp_dir = TempDir::new()?;
+
+    temp_dir.child("people").write_str(
+        "@prefix ex: <http://example.org/> .\n\
+         ex:alice ex:knows ex:bob ;\n\
+         \x20   ex:knows ex:carol .\n",
+    )?;
+    temp_dir
+        .child("people.nt")
+        .write_str("@prefix ex: <http://example.org/> .\nex:bob ex:knows ex:dave .\n")?;
+    let rules = temp_dir.child("rules.rls");
+    rules.write_str(
+        "@source detected[3]: load-rdf(\"people\") .\n\
+         @source explicit[3]: load-rdf(\"people.nt\", format=\"turtle\") .\n\
+         knows(?x, ?y) :- detected(?x, ?p, ?y) .\n\
+         knows(?x, ?y) :- explicit(?x, ?p, ?y) .\n",
+    )?;
+
+    let mut cmd = Command::cargo_bin(bin)?;
+    cmd.arg(rules.path());
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Derived 3 facts"));
+
+    Ok(())
+}
@@ -6,7 +6,7 @@ use crate::error::ReadingError;
 
 use crate::{
     datatypes::{storage_value::VecT, Double, Float},
-    dictionary::Dictionary,
+    dictionary::{index_to_storage, Dictionary},
     management::database::Dict,
 };
 
@@ -95,7 +95,7 @@ impl ColumnBuilderProxy<PhysicalString> for PhysicalStringColumnBuilderProxy<'_>
     generic_trait_impl_without_add!(VecT::U64);
     fn add(&mut self, input: PhysicalString) -> Result<(), ReadingError> {
         self.commit();
-        self.value = Some(self.dict.borrow_mut().add(input.into()).try_into()?);
+        self.value = Some(index_to_storage(self.dict.borrow_mut().add(input.into()))?);
         Ok(())
     }
 }
@@ -116,6 +116,7 @@ pub struct PhysicalGenericColumnBuilderProxy<T> {
 
 physical_generic_trait_impl!(u64, VecT::U64);
 physical_generic_trait_impl!(i64, VecT::I64);
+physical_generic_trait_impl!(u128, VecT::U128);
 physical_generic_trait_impl!(u32, VecT::U32);
 physical_generic_trait_impl!(Float, VecT::Float);
 physical_generic_trait_impl!(Double, VecT::Double);
@@ -127,6 +128,8 @@ pub enum PhysicalBuilderProxyEnum<'a> {
     String(PhysicalStringColumnBuilderProxy<'a>),
     /// Proxy for I64 Type
     I64(PhysicalGenericColumnBuilderProxy<i64>),
+    /// Proxy for U128 Type
+    U128(PhysicalGenericColumnBuilderProxy<u128>),
     /// Proxy for U64 Type
     U64(PhysicalGenericColumnBuilderProxy<u64>),
     /// Proxy for U32 Type
@@ -143,6 +146,7 @@ impl PhysicalBuilderProxyEnum<'_> {
         match self {
             PhysicalBuilderProxyEnum::String(bp) => bp.finalize(),
             PhysicalBuilderProxyEnum::I64(bp) => bp.finalize(),
+            PhysicalBuilderProxyEnum::U128(bp) => bp.finalize(),
             PhysicalBuilderProxyEnum::U64(bp) => bp.finalize(),
             PhysicalBuilderProxyEnum::U32(bp) => bp.finalize(),
             PhysicalBuilderProxyEnum::Float(bp) => bp.finalize(),
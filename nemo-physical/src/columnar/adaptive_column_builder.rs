@@ -170,6 +170,8 @@ pub enum ColumnBuilderAdaptiveT {
     U64(ColumnBuilderAdaptive<u64>),
     /// Case i64
     I64(ColumnBuilderAdaptive<i64>),
+    /// Case u128
+    U128(ColumnBuilderAdaptive<u128>),
     /// Case Float
     Float(ColumnBuilderAdaptive<Float>),
     /// Case Double
@@ -196,6 +198,10 @@ impl ColumnBuilderAdaptiveT {
                 decision_threshold,
                 target_min_length_for_rle_elements,
             )),
+            StorageTypeName::U128 => Self::U128(ColumnBuilderAdaptive::new(
+                decision_threshold,
+                target_min_length_for_rle_elements,
+            )),
             StorageTypeName::Float => Self::Float(ColumnBuilderAdaptive::new(
                 decision_threshold,
                 target_min_length_for_rle_elements,
@@ -240,6 +246,16 @@ impl ColumnBuilderAdaptiveT {
                     );
                 }
             }
+            Self::U128(cb) => {
+                if let StorageValueT::U128(v) = value {
+                    cb.add(v);
+                } else {
+                    panic!(
+                        "value of type {} does not match AdaptiveColumn type U128",
+                        value.get_type()
+                    );
+                }
+            }
             Self::Float(cb) => {
                 if let StorageValueT::Float(v) = value {
                     cb.add(v);
@@ -269,6 +285,7 @@ impl ColumnBuilderAdaptiveT {
             Self::U32(cb) => cb.count(),
             Self::U64(cb) => cb.count(),
             Self::I64(cb) => cb.count(),
+            Self::U128(cb) => cb.count(),
             Self::Float(cb) => cb.count(),
             Self::Double(cb) => cb.count(),
         }
@@ -289,6 +306,9 @@ impl ColumnBuilderAdaptiveT {
                 c.finalize(),
                 interval_column.finalize(),
             )),
+            ColumnBuilderAdaptiveT::U128(c) => ColumnWithIntervalsT::U128(
+                ColumnWithIntervals::new(c.finalize(), interval_column.finalize()),
+            ),
             ColumnBuilderAdaptiveT::Float(c) => ColumnWithIntervalsT::Float(
                 ColumnWithIntervals::new(c.finalize(), interval_column.finalize()),
             ),
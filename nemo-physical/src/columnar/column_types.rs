@@ -6,5 +6,8 @@ pub mod vector;
 /// Module for defining [`ColumnRle`][rle::ColumnRle]
 pub mod rle;
 
+/// Module for defining [`ColumnRankSelect`][rank_select::ColumnRankSelect]
+pub mod rank_select;
+
 /// Module for defining [`ColumnWithIntervals`][interval::ColumnWithIntervals]
 pub mod interval;
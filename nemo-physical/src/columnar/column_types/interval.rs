@@ -1,5 +1,6 @@
 use bytesize::ByteSize;
 
+use crate::columnar::column_types::rank_select::ColumnRankSelect;
 use crate::datatypes::{RunLengthEncodable, StorageTypeName};
 use crate::generate_datatype_forwarder;
 use crate::management::ByteSized;
@@ -25,10 +26,28 @@ where
     T: ColumnDataType,
 {
     /// Constructs a new [`ColumnWithIntervals`] given data column and a column containing the intervals
+    ///
+    /// If the interval starts need less memory as a [`ColumnRankSelect`],
+    /// e.g. because the column consists of many small intervals, they are stored in this form.
     pub fn new(data: ColumnEnum<T>, int_starts: ColumnEnum<usize>) -> ColumnWithIntervals<T> {
+        let int_starts = Self::compact_int_starts(int_starts, data.len());
         ColumnWithIntervals { data, int_starts }
     }
 
+    /// Returns the interval starts as a [`ColumnRankSelect`] if this is smaller than their current representation.
+    fn compact_int_starts(int_starts: ColumnEnum<usize>, data_len: usize) -> ColumnEnum<usize> {
+        if matches!(int_starts, ColumnEnum::ColumnRankSelect(_))
+            || ColumnRankSelect::<usize>::estimated_size_bytes(data_len) >= int_starts.size_bytes()
+        {
+            return int_starts;
+        }
+
+        match ColumnRankSelect::from_increasing(int_starts.iter()) {
+            Some(column) => ColumnEnum::ColumnRankSelect(column),
+            None => int_starts,
+        }
+    }
+
     /// Return data column
     pub fn get_data_column(&self) -> &ColumnEnum<T> {
         &self.data
@@ -104,6 +123,8 @@ pub enum ColumnWithIntervalsT {
     U64(ColumnWithIntervals<u64>),
     /// Case i64
     I64(ColumnWithIntervals<i64>),
+    /// Case u128
+    U128(ColumnWithIntervals<u128>),
     /// Case Float
     Float(ColumnWithIntervals<Float>),
     /// Case Double
@@ -151,6 +172,13 @@ impl ColumnWithIntervalsT {
                     intervals.iter(),
                 )
             }
+            Self::U128(this) => {
+                let (data, intervals) = this.as_parts();
+                (
+                    ColumnScanT::U128(ColumnScanCell::new(data.iter())),
+                    intervals.iter(),
+                )
+            }
             Self::Float(this) => {
                 let (data, intervals) = this.as_parts();
                 (
@@ -174,6 +202,7 @@ impl ColumnWithIntervalsT {
             Self::U32(_) => StorageTypeName::U32,
             Self::U64(_) => StorageTypeName::U64,
             Self::I64(_) => StorageTypeName::I64,
+            Self::U128(_) => StorageTypeName::U128,
             Self::Float(_) => StorageTypeName::Float,
             Self::Double(_) => StorageTypeName::Double,
         }
@@ -252,4 +281,39 @@ mod test {
         assert_eq!(gic.int_bounds(2), 6..7);
         assert_eq!(gic.int_bounds(3), 7..9);
     }
+
+    #[test]
+    fn small_intervals_use_rank_select() {
+        let data: Vec<u64> = (0..1000).collect();
+        let int_starts: Vec<usize> = (0..1000).filter(|i| i % 3 != 1).collect();
+
+        let gic = ColumnWithIntervals::new(
+            ColumnEnum::ColumnVector(ColumnVector::new(data)),
+            ColumnEnum::ColumnVector(ColumnVector::new(int_starts.clone())),
+        );
+
+        assert!(matches!(
+            gic.get_int_column(),
+            ColumnEnum::ColumnRankSelect(_)
+        ));
+        assert_eq!(gic.int_len(), int_starts.len());
+        assert_eq!(gic.int_bounds(0), 0..2);
+        assert_eq!(gic.int_bounds(1), 2..3);
+        assert_eq!(gic.int_bounds(gic.int_len() - 1), 999..1000);
+        assert_eq!(gic.get_int_column().iter().collect::<Vec<_>>(), int_starts);
+    }
+
+    #[test]
+    fn large_intervals_keep_layout() {
+        let data: Vec<u64> = (0..1000).collect();
+        let int_starts: Vec<usize> = vec![0, 500];
+
+        let gic = ColumnWithIntervals::new(
+            ColumnEnum::ColumnVector(ColumnVector::new(data)),
+            ColumnEnum::ColumnVector(ColumnVector::new(int_starts)),
+        );
+
+        assert!(matches!(gic.get_int_column(), ColumnEnum::ColumnVector(_)));
+        assert_eq!(gic.int_bounds(1), 500..1000);
+    }
 }
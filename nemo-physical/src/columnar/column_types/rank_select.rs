@@ -0,0 +1,426 @@
+use std::{fmt::Debug, marker::PhantomData, mem::size_of, ops::Range};
+
+use bytesize::ByteSize;
+
+use crate::{
+    columnar::traits::{column::Column, columnscan::ColumnScan},
+    datatypes::ColumnDataType,
+    management::ByteSized,
+};
+
+/// Number of bits in a word of the bitvector
+const WORD_BITS: usize = u64::BITS as usize;
+/// Number of words of the bitvector that share a precomputed rank
+const BLOCK_WORDS: usize = 8;
+
+/// Implementation of [`Column`] for strictly increasing sequences of non-negative integers,
+/// which are stored as a bitvector where the bit at each value is set.
+///
+/// Prefix sums of the number of set bits are stored for each block of words,
+/// which allows answering rank and select queries while using only slightly more than one bit per position.
+/// This is a compact representation for the interval starts of trie layers with many small intervals.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ColumnRankSelect<T> {
+    words: Vec<u64>,
+    /// Number of set bits before each block of [`BLOCK_WORDS`] words
+    block_ranks: Vec<usize>,
+    len: usize,
+    _type: PhantomData<T>,
+}
+
+impl<T> ColumnRankSelect<T>
+where
+    T: ColumnDataType,
+{
+    /// Constructs a new [`ColumnRankSelect`] from the given values.
+    ///
+    /// Returns `None` if the values are not a strictly increasing sequence of non-negative integers.
+    pub fn from_increasing<I: IntoIterator<Item = T>>(values: I) -> Option<Self> {
+        let mut words: Vec<u64> = Vec::new();
+        let mut len = 0;
+        let mut previous: Option<usize> = None;
+
+        for value in values {
+            let position = value.floor_to_usize()?;
+            if T::try_from(position).ok() != Some(value)
+                || previous.is_some_and(|previous| previous >= position)
+            {
+                return None;
+            }
+
+            let word = position / WORD_BITS;
+            if words.len() <= word {
+                words.resize(word + 1, 0);
+            }
+            words[word] |= 1 << (position % WORD_BITS);
+
+            previous = Some(position);
+            len += 1;
+        }
+
+        words.shrink_to_fit();
+
+        let mut block_ranks = Vec::with_capacity(words.len().div_ceil(BLOCK_WORDS));
+        let mut rank = 0;
+        for block in words.chunks(BLOCK_WORDS) {
+            block_ranks.push(rank);
+            rank += block
+                .iter()
+                .map(|word| word.count_ones() as usize)
+                .sum::<usize>();
+        }
+
+        Some(Self {
+            words,
+            block_ranks,
+            len,
+            _type: PhantomData,
+        })
+    }
+
+    /// Returns the number of bytes a [`ColumnRankSelect`] needs
+    /// to store values that are smaller than `universe`.
+    pub fn estimated_size_bytes(universe: usize) -> ByteSize {
+        let words = universe.div_ceil(WORD_BITS);
+        let blocks = words.div_ceil(BLOCK_WORDS);
+
+        ByteSize::b(
+            size_of::<Self>() as u64
+                + words as u64 * size_of::<u64>() as u64
+                + blocks as u64 * size_of::<usize>() as u64,
+        )
+    }
+
+    /// Returns the number of values in the column that are smaller than `position`.
+    pub fn rank(&self, position: usize) -> usize {
+        let word = position / WORD_BITS;
+        if word >= self.words.len() {
+            return self.len;
+        }
+
+        let block = word / BLOCK_WORDS;
+        let preceding_words = self.words[block * BLOCK_WORDS..word]
+            .iter()
+            .map(|word| word.count_ones() as usize)
+            .sum::<usize>();
+        let mask = (1u64 << (position % WORD_BITS)) - 1;
+
+        self.block_ranks[block] + preceding_words + (self.words[word] & mask).count_ones() as usize
+    }
+
+    /// Returns the value with the given index as a position in the bitvector.
+    ///
+    /// # Panics
+    /// Panics if `index` is out of bounds.
+    pub fn select(&self, index: usize) -> usize {
+        assert!(index < self.len, "Index {index} is out of bounds.");
+
+        let block = self.block_ranks.partition_point(|&rank| rank <= index) - 1;
+        let mut remaining = index - self.block_ranks[block];
+
+        for (offset, &word) in self.words[block * BLOCK_WORDS..].iter().enumerate() {
+            let ones = word.count_ones() as usize;
+            if remaining < ones {
+                return (block * BLOCK_WORDS + offset) * WORD_BITS
+                    + select_in_word(word, remaining);
+            }
+            remaining -= ones;
+        }
+
+        unreachable!("the block ranks count every set bit")
+    }
+
+    /// Returns the smallest position of a value that is at least `position`.
+    fn next_position(&self, position: usize) -> Option<usize> {
+        let mut word = position / WORD_BITS;
+        let mut bits = self.words.get(word)? & (u64::MAX << (position % WORD_BITS));
+
+        loop {
+            if bits != 0 {
+                return Some(word * WORD_BITS + bits.trailing_zeros() as usize);
+            }
+
+            word += 1;
+            bits = *self.words.get(word)?;
+        }
+    }
+
+    /// Converts a position in the bitvector back into a value.
+    fn value(position: usize) -> T {
+        T::try_from(position)
+            .unwrap_or_else(|_| unreachable!("positions are obtained from values of the column"))
+    }
+}
+
+/// Returns the position of the set bit with the given index in `word`.
+fn select_in_word(mut word: u64, index: usize) -> usize {
+    for _ in 0..index {
+        word &= word - 1;
+    }
+
+    word.trailing_zeros() as usize
+}
+
+/// Returns the smallest position whose value is at least `value`,
+/// or `None` if there is no such position.
+fn lower_position<T: ColumnDataType>(value: T) -> Option<usize> {
+    if value <= T::zero() {
+        return Some(0);
+    }
+
+    let floor = value.floor_to_usize()?;
+    match T::try_from(floor) {
+        Ok(floor_value) if floor_value >= value => Some(floor),
+        _ => floor.checked_add(1),
+    }
+}
+
+impl<'a, T> Column<'a, T> for ColumnRankSelect<T>
+where
+    T: 'a + ColumnDataType,
+{
+    type Scan = ColumnScanRankSelect<'a, T>;
+
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    fn get(&self, index: usize) -> T {
+        Self::value(self.select(index))
+    }
+
+    fn iter(&'a self) -> Self::Scan {
+        ColumnScanRankSelect::new(self)
+    }
+}
+
+impl<T> ByteSized for ColumnRankSelect<T> {
+    fn size_bytes(&self) -> ByteSize {
+        // We cast everything to u64 separately to avoid overflows
+        ByteSize::b(
+            size_of::<Self>() as u64
+                + self.words.capacity() as u64 * size_of::<u64>() as u64
+                + self.block_ranks.capacity() as u64 * size_of::<usize>() as u64,
+        )
+    }
+}
+
+/// Implementation of [`ColumnScan`] for a [`ColumnRankSelect`].
+///
+/// Consecutive values are found by scanning the bitvector from the current position,
+/// so only `seek` and `narrow` need to use the rank and select structures.
+#[derive(Debug)]
+pub struct ColumnScanRankSelect<'a, T> {
+    column: &'a ColumnRankSelect<T>,
+    interval: Range<usize>,
+    pos: Option<usize>,
+    /// Position in the bitvector of the value at `pos`, if `pos` is in the interval
+    position: usize,
+}
+
+impl<'a, T> ColumnScanRankSelect<'a, T>
+where
+    T: 'a + ColumnDataType,
+{
+    /// Constructs a new [`ColumnScanRankSelect`] for a Column.
+    pub fn new(column: &'a ColumnRankSelect<T>) -> Self {
+        Self {
+            column,
+            interval: 0..column.len(),
+            pos: None,
+            position: 0,
+        }
+    }
+
+    /// Moves the scan to the given index, which must be within the interval.
+    fn move_to(&mut self, index: usize) -> T {
+        self.pos = Some(index);
+        self.position = self.column.select(index);
+
+        ColumnRankSelect::value(self.position)
+    }
+}
+
+impl<'a, T> Iterator for ColumnScanRankSelect<'a, T>
+where
+    T: 'a + ColumnDataType,
+{
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.pos {
+            None if self.interval.is_empty() => {
+                self.pos = Some(self.interval.start);
+                None
+            }
+            None => Some(self.move_to(self.interval.start)),
+            Some(pos) if pos + 1 < self.interval.end => {
+                self.pos = Some(pos + 1);
+                self.position = self
+                    .column
+                    .next_position(self.position + 1)
+                    .expect("the interval only contains indices of values in the column");
+
+                Some(ColumnRankSelect::value(self.position))
+            }
+            Some(pos) => {
+                self.pos = Some(pos.max(self.interval.end));
+                None
+            }
+        }
+    }
+}
+
+impl<'a, T> ColumnScan for ColumnScanRankSelect<'a, T>
+where
+    T: 'a + ColumnDataType,
+{
+    fn seek(&mut self, value: T) -> Option<T> {
+        let current = match self.pos {
+            None if self.interval.is_empty() => return None,
+            None => self.move_to(self.interval.start),
+            Some(pos) if pos < self.interval.end => ColumnRankSelect::value(self.position),
+            Some(_) => return None,
+        };
+
+        if current >= value {
+            return Some(current);
+        }
+
+        let index = lower_position(value).map_or(self.interval.end, |position| {
+            self.column.rank(position).min(self.interval.end)
+        });
+        if index >= self.interval.end {
+            self.pos = Some(self.interval.end);
+            return None;
+        }
+
+        Some(self.move_to(index))
+    }
+
+    fn current(&self) -> Option<T> {
+        self.pos().map(|_| ColumnRankSelect::value(self.position))
+    }
+
+    fn reset(&mut self) {
+        self.pos = None;
+    }
+
+    fn pos(&self) -> Option<usize> {
+        self.pos.filter(|&pos| pos < self.interval.end)
+    }
+
+    fn narrow(&mut self, interval: Range<usize>) {
+        assert!(
+            interval.end <= self.column.len(),
+            "Cannot narrow to an interval larger than the column."
+        );
+
+        self.interval = interval;
+        self.pos = None;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::columnar::traits::{column::Column, columnscan::ColumnScan};
+
+    use quickcheck_macros::quickcheck;
+    use test_log::test;
+
+    use super::{super::vector::ColumnVector, ColumnRankSelect};
+
+    fn get_test_column() -> ColumnRankSelect<usize> {
+        ColumnRankSelect::from_increasing(vec![0, 1, 3, 63, 64, 200, 513, 1000, 1001]).unwrap()
+    }
+
+    #[test]
+    fn from_increasing() {
+        assert!(ColumnRankSelect::<usize>::from_increasing(vec![0, 2, 2]).is_none());
+        assert!(ColumnRankSelect::<usize>::from_increasing(vec![3, 1]).is_none());
+        assert!(ColumnRankSelect::<i64>::from_increasing(vec![-1, 2]).is_none());
+        assert!(ColumnRankSelect::<usize>::from_increasing(vec![]).is_some_and(|c| c.is_empty()));
+    }
+
+    #[test]
+    fn rank_and_select() {
+        let column = get_test_column();
+
+        assert_eq!(column.len(), 9);
+        assert_eq!(
+            (0..column.len())
+                .map(|index| column.select(index))
+                .collect::<Vec<_>>(),
+            vec![0, 1, 3, 63, 64, 200, 513, 1000, 1001]
+        );
+
+        assert_eq!(column.rank(0), 0);
+        assert_eq!(column.rank(1), 1);
+        assert_eq!(column.rank(3), 2);
+        assert_eq!(column.rank(64), 4);
+        assert_eq!(column.rank(65), 5);
+        assert_eq!(column.rank(512), 6);
+        assert_eq!(column.rank(1001), 8);
+        assert_eq!(column.rank(1002), 9);
+        assert_eq!(column.rank(100_000), 9);
+    }
+
+    #[test]
+    fn seek_and_narrow() {
+        let column = get_test_column();
+        let mut iter = column.iter();
+
+        assert_eq!(iter.current(), None);
+        assert_eq!(iter.seek(2), Some(3));
+        assert_eq!(iter.pos(), Some(2));
+        assert_eq!(iter.seek(1), Some(3));
+        assert_eq!(iter.next(), Some(63));
+        assert_eq!(iter.seek(201), Some(513));
+        assert_eq!(iter.pos(), Some(6));
+        assert_eq!(iter.seek(1002), None);
+        assert_eq!(iter.pos(), None);
+        assert_eq!(iter.next(), None);
+
+        iter.narrow(2..5);
+        assert_eq!(iter.next(), Some(3));
+        assert_eq!(iter.seek(64), Some(64));
+        assert_eq!(iter.next(), None);
+
+        iter.narrow(3..6);
+        assert_eq!(iter.seek(513), None);
+        assert_eq!(iter.current(), None);
+
+        iter.narrow(4..4);
+        assert_eq!(iter.next(), None);
+        assert_eq!(iter.seek(0), None);
+    }
+
+    #[quickcheck]
+    #[cfg_attr(miri, ignore)]
+    fn scan_quickcheck_u64(gaps: Vec<u8>, targets: Vec<u64>) -> bool {
+        let values = gaps
+            .iter()
+            .scan(0u64, |value, &gap| {
+                *value += u64::from(gap) + 1;
+                Some(*value)
+            })
+            .collect::<Vec<_>>();
+        let mut sorted_targets = targets;
+        sorted_targets.sort();
+
+        let column = ColumnRankSelect::from_increasing(values.clone()).unwrap();
+        let reference = ColumnVector::new(values.clone());
+
+        if column.iter().collect::<Vec<_>>() != values {
+            return false;
+        }
+
+        let mut iter = column.iter();
+        let mut reference_iter = reference.iter();
+        sorted_targets.into_iter().all(|target| {
+            iter.seek(target) == reference_iter.seek(target)
+                && iter.pos() == reference_iter.pos()
+                && iter.next() == reference_iter.next()
+        })
+    }
+}
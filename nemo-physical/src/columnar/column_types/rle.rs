@@ -234,6 +234,34 @@ where
         (element_index, increment_index)
     }
 
+    /// Return the index of the last run in `runs` whose start value is smaller than the given value,
+    /// or the first run in `runs` if there is no such run.
+    ///
+    /// The start values of the runs serve as a sorted index of the column.
+    /// Since the target run is often close to the first run, e.g. when seeking during a join,
+    /// the search first gallops over this index and then uses a binary search,
+    /// which takes O(log d) steps if the target run is d runs after the first one.
+    fn find_run(&self, runs: Range<usize>, value: T) -> usize {
+        let start_values = &self.values[runs.clone()];
+
+        let mut lower = 0;
+        let mut upper = start_values.len();
+        let mut step = 1;
+        while lower + step < start_values.len() {
+            if start_values[lower + step] < value {
+                lower += step;
+                step *= 2;
+            } else {
+                upper = lower + step;
+                break;
+            }
+        }
+
+        let smaller_values = start_values[lower..upper].partition_point(|start| *start < value);
+
+        runs.start + lower + smaller_values.saturating_sub(1)
+    }
+
     fn get_internal(&self, element_index: usize, increment_index: usize) -> T {
         let value = self.values[element_index];
         let increment = self.increments[element_index];
@@ -457,16 +485,11 @@ where
             self.upper_bound_exclusive.0
         };
 
-        // it is possible that the last element of the matching_element_index - 1 -th element
-        // holds the same value as the matching element; in this case, we want to position the
-        // iterator on that element;
-        // hence we subtract 1 in any case
-        let bin_search_element_index = self.column.values
-            [self.element_index..upper_element_bound_for_search]
-            .binary_search(&value)
-            .unwrap_or_else(|err| err)
-            .saturating_sub(1)
-            + self.element_index;
+        // the run before the first run starting with a value that is at least as large as the given value
+        // may contain the value, so we continue from this run
+        let bin_search_element_index = self
+            .column
+            .find_run(self.element_index..upper_element_bound_for_search, value);
 
         let mut seek_element_index: usize;
         let mut seek_increment_index: usize;
@@ -500,9 +523,9 @@ where
             // We might end up here if the intervals ends right after the first element in a decrementing RLE block
             // or if an interval start in the given RLE block
             // (or if there is an interval of length zero anywhere in a decrementing RLE block, which means that it also starts in the decrementing RLE block)
-            // then the increment index is either 0 or will be captured by the default of current_increment_index, which is set to self.lower_bound_inclusive.1
-            // either way, it is safe to set seek_increment_index to 0 here
-            seek_element_index = bin_search_element_index;
+            // All values of a decrementing run are at most its start value, which is smaller than the given value,
+            // so we skip the whole run instead of scanning through it
+            seek_element_index = bin_search_element_index + 1;
             seek_increment_index = 0;
         }
 
@@ -558,6 +581,46 @@ where
             .column
             .get_element_and_increment_index_from_global_index(interval.end);
     }
+
+    fn next_batch(&mut self, out: &mut Vec<T>, n: usize) -> usize {
+        let mut count = 0;
+        let upper_pos = self.pos_for_element_and_increment_index_unchecked(
+            self.upper_bound_exclusive.0,
+            self.upper_bound_exclusive.1,
+        );
+
+        while count < n {
+            // Moving to the next run (or starting the scan) is left to `next`
+            let Some(mut value) = self.next() else {
+                break;
+            };
+            out.push(value);
+            count += 1;
+
+            // The remaining values of the current run are computed from its increment
+            let pos = self.pos_for_element_and_increment_index_unchecked(
+                self.element_index,
+                self.increment_index,
+            );
+            let run_end = self.column.end_indices[self.element_index]
+                .get()
+                .min(upper_pos);
+            let batch = (run_end - pos - 1).min(n - count);
+            if batch == 0 {
+                continue;
+            }
+
+            let increment = self.column.increments[self.element_index];
+            out.extend((1..=batch).map(|times| value.offset(increment, times)));
+            value = value.offset(increment, batch);
+
+            self.increment_index += batch;
+            self.current = Some(value);
+            count += batch;
+        }
+
+        count
+    }
 }
 
 #[cfg(test)]
@@ -571,7 +634,7 @@ mod test {
     use std::num::NonZeroUsize;
     use test_log::test;
 
-    use super::ColumnRle;
+    use super::{super::vector::ColumnVector, ColumnRle};
 
     fn get_control_data() -> Vec<u64> {
         vec![2, 5, 6, 7, 8, 42, 4, 7, 10, 13, 16]
@@ -841,6 +904,70 @@ mod test {
         iterated_col == raw_data
     }
 
+    #[test]
+    fn next_batch() {
+        let control_data = get_control_data();
+        let col = get_test_column_u64();
+
+        for batch_size in 1..=control_data.len() + 1 {
+            let mut batch_iter = col.iter();
+            let mut iter = col.iter();
+            let mut batches = Vec::new();
+
+            loop {
+                let count = batch_iter.next_batch(&mut batches, batch_size);
+                iter.by_ref().take(count).for_each(drop);
+                if count < batch_size {
+                    iter.next();
+                }
+
+                assert_eq!(batch_iter.current(), iter.current());
+                assert_eq!(batch_iter.pos(), iter.pos());
+
+                if count < batch_size {
+                    break;
+                }
+            }
+
+            assert_eq!(batches, control_data);
+            assert_eq!(batch_iter.next(), None);
+        }
+    }
+
+    #[test]
+    fn next_batch_on_narrowed_column() {
+        let col = get_test_column_u64();
+        let mut iter = col.iter();
+        iter.narrow(1..6);
+
+        let mut batch = Vec::new();
+        assert_eq!(iter.next_batch(&mut batch, 3), 3);
+        assert_eq!(batch, vec![5, 6, 7]);
+        assert_eq!(iter.current(), Some(7));
+        assert_eq!(iter.pos(), Some(3));
+
+        assert_eq!(iter.next(), Some(8));
+
+        batch.clear();
+        assert_eq!(iter.next_batch(&mut batch, 3), 1);
+        assert_eq!(batch, vec![42]);
+        assert_eq!(iter.current(), None);
+        assert_eq!(iter.next_batch(&mut batch, 3), 0);
+    }
+
+    #[quickcheck]
+    #[cfg_attr(miri, ignore)]
+    fn next_batch_quickcheck_u64(raw_data: Vec<u64>, batch_size: u8) -> bool {
+        let col = ColumnRle::new(raw_data.clone());
+        let batch_size = usize::from(batch_size) + 1;
+
+        let mut iter = col.iter();
+        let mut batches = Vec::new();
+        while iter.next_batch(&mut batches, batch_size) == batch_size {}
+
+        batches == raw_data
+    }
+
     #[test]
     fn seek_and_get_pos() {
         let seek_test_col = ColumnRle {
@@ -988,6 +1115,64 @@ mod test {
         assert_eq!(iter.pos(), Some(8));
     }
 
+    #[test]
+    fn find_run() {
+        // Steps larger than an `IntStep` force a new run for each value
+        let col = ColumnRle::new((0..1000u64).map(|value| value * 40000).collect());
+        assert_eq!(col.values.len(), 1000);
+
+        assert_eq!(col.find_run(0..1000, 0), 0);
+        assert_eq!(col.find_run(0..1000, 1), 0);
+        assert_eq!(col.find_run(0..1000, 40000), 0);
+        assert_eq!(col.find_run(0..1000, 40001), 1);
+        assert_eq!(col.find_run(0..1000, 999 * 40000 + 1), 999);
+        assert_eq!(col.find_run(0..1000, u64::MAX), 999);
+        assert_eq!(col.find_run(500..1000, 0), 500);
+        assert_eq!(col.find_run(500..1000, 700 * 40000 + 5), 700);
+        assert_eq!(col.find_run(500..501, 700 * 40000), 500);
+    }
+
+    #[test]
+    fn seek_skips_decrementing_run() {
+        // Imagine this as an interval column
+        // 50, 40
+        // 30, 35, 36, 37
+        let seek_test_col = ColumnRle {
+            values: vec![50u32, 35],
+            end_indices: vec![NonZeroUsize::new(3).unwrap(), NonZeroUsize::new(6).unwrap()],
+            increments: vec![(-10).into(), 1.into()],
+        };
+
+        let mut iter = seek_test_col.iter();
+        iter.narrow(2..6);
+
+        assert_eq!(iter.seek(31), Some(35));
+        assert_eq!(iter.pos(), Some(3));
+        assert_eq!(iter.seek(37), Some(37));
+        assert_eq!(iter.seek(38), None);
+
+        iter.narrow(2..6);
+        assert_eq!(iter.seek(30), Some(30));
+        assert_eq!(iter.pos(), Some(2));
+    }
+
+    #[quickcheck]
+    #[cfg_attr(miri, ignore)]
+    fn seek_sequence_quickcheck_u64(mut raw_data: Vec<u64>, mut targets: Vec<u64>) -> bool {
+        raw_data.sort_unstable();
+        targets.sort_unstable();
+
+        let vector_col = ColumnVector::new(raw_data.clone());
+        let rle_col = ColumnRle::new(raw_data);
+
+        let mut vector_scan = vector_col.iter();
+        let mut rle_scan = rle_col.iter();
+
+        targets.into_iter().all(|target| {
+            vector_scan.seek(target) == rle_scan.seek(target) && vector_scan.pos() == rle_scan.pos()
+        })
+    }
+
     #[quickcheck]
     #[cfg_attr(miri, ignore)]
     fn seek_quickcheck_u64(mut raw_data: Vec<u64>, target: u64) -> bool {
@@ -207,6 +207,22 @@ where
         self.pos = None;
         self.validate_interval();
     }
+
+    fn next_batch(&mut self, out: &mut Vec<T>, n: usize) -> usize {
+        if n == 0 {
+            return 0;
+        }
+
+        let start = self.pos.map_or(self.interval.start, |pos| pos + 1);
+        let end = start.saturating_add(n).min(self.interval.end).max(start);
+        out.extend_from_slice(&self.column.data[start..end]);
+
+        let count = end - start;
+        // If fewer than `n` values are left, `next` would have been called once more
+        self.pos = Some(if count == n { end - 1 } else { end });
+
+        count
+    }
 }
 
 #[cfg(test)]
@@ -351,6 +367,28 @@ mod test {
         assert_eq!(gcs.collect::<Vec<_>>(), vec![1, 2, 5]);
     }
 
+    #[test]
+    fn u64_next_batch() {
+        let test_column = get_test_column_large();
+        let mut gcs = ColumnScanVector::new(&test_column);
+        gcs.narrow(4..16);
+
+        let mut batch = Vec::new();
+        assert_eq!(gcs.next_batch(&mut batch, 5), 5);
+        assert_eq!(batch, vec![12, 14, 16, 18, 21]);
+        assert_eq!(gcs.current(), Some(21));
+        assert_eq!(gcs.pos(), Some(8));
+
+        assert_eq!(gcs.next(), Some(25));
+
+        batch.clear();
+        assert_eq!(gcs.next_batch(&mut batch, 10), 6);
+        assert_eq!(batch, vec![28, 29, 30, 35, 37, 39]);
+        assert_eq!(gcs.current(), None);
+        assert_eq!(gcs.next_batch(&mut batch, 10), 0);
+        assert_eq!(gcs.next(), None);
+    }
+
     #[test]
     fn u64_seek_interval() {
         let test_column = get_test_column_large();
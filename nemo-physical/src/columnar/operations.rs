@@ -4,6 +4,10 @@
 pub mod columnscan_join;
 pub use columnscan_join::ColumnScanJoin;
 
+/// Module for defining [`ColumnScanVectorJoin`]
+pub mod columnscan_vector_join;
+pub use columnscan_vector_join::ColumnScanVectorJoin;
+
 /// Module for defining [`ColumnScanReorder`]
 pub mod columnscan_reorder;
 pub use columnscan_reorder::ColumnScanReorder;
@@ -1,13 +1,68 @@
 use super::super::traits::columnscan::ColumnScan;
 use crate::{
-    columnar::traits::columnscan::ColumnScanCell, datatypes::ColumnDataType,
+    columnar::traits::columnscan::{ColumnScanCell, ColumnScanT},
+    datatypes::{random_bits::mix_bits, ColumnDataType, DataTypeName, StorageValueT},
     util::tagged_tree::TaggedTree,
 };
 use std::{
     fmt::{Debug, Display},
     ops::Range,
+    rc::Rc,
 };
 
+/// Constant that distinguishes pseudo-random numbers computed from a seed
+/// from hashes of the same value.
+const SEEDED_RANDOM_SALT: u64 = 0x2545_F491_4F6C_DD1D;
+
+/// Implementation of an [`ExternalFunction`], which maps the values of its arguments to a value.
+pub type ExternalFunctionImpl = Rc<dyn Fn(&[StorageValueT]) -> Option<StorageValueT>>;
+
+/// Function that is defined outside of the physical layer,
+/// which computes a value from the values of its arguments.
+///
+/// The function returns `None` if its result is undefined.
+#[derive(Clone)]
+pub struct ExternalFunction {
+    /// Name of the function
+    name: String,
+    /// Type of the values computed by the function
+    data_type: DataTypeName,
+    /// The function itself
+    function: ExternalFunctionImpl,
+}
+
+impl ExternalFunction {
+    /// Create a new [`ExternalFunction`].
+    pub fn new(name: String, data_type: DataTypeName, function: ExternalFunctionImpl) -> Self {
+        Self {
+            name,
+            data_type,
+            function,
+        }
+    }
+
+    /// Return the name of the function.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Return the type of the values computed by the function.
+    pub fn data_type(&self) -> DataTypeName {
+        self.data_type
+    }
+
+    /// Apply the function to the given arguments.
+    pub fn evaluate(&self, arguments: &[StorageValueT]) -> Option<StorageValueT> {
+        (self.function)(arguments)
+    }
+}
+
+impl Debug for ExternalFunction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.name, self.data_type)
+    }
+}
+
 /// Operation that can be exectued by a [`ColumnScanArithmetic`].
 #[derive(Clone)]
 pub enum ArithmeticOperation<T> {
@@ -15,6 +70,9 @@ pub enum ArithmeticOperation<T> {
     Constant(T),
     /// Value is read off the column scan with the given index.
     ColumnScan(usize),
+    /// Value is read off the column scan of possibly different type with the given index
+    /// and converted into the given type, which is the type of the whole operation.
+    ConvertedColumnScan(usize, DataTypeName),
     /// Value is the sum of the values of the given subtrees.
     Addition,
     /// Value is the difference between the value of the first subtree and the second.
@@ -23,6 +81,35 @@ pub enum ArithmeticOperation<T> {
     Multiplication,
     /// Value is the quotient of the value of the first subtree and the second.
     Division,
+    /// Value is a hash of the value of the given subtree (see [`crate::datatypes::RandomBits`]).
+    Hash,
+    /// Value is a random number, which is different for each evaluation (see [`crate::datatypes::RandomBits`]).
+    Random,
+    /// Value is a pseudo-random number that is determined by the value of the given subtree,
+    /// i.e. the seed (see [`crate::datatypes::RandomBits`]).
+    SeededRandom,
+    /// Value is the absolute value of the value of the given subtree.
+    Absolute,
+    /// Value is the value of the given subtree rounded to the nearest integer.
+    Round,
+    /// Value is the value of the given subtree rounded up.
+    Ceil,
+    /// Value is the value of the given subtree rounded down.
+    Floor,
+    /// Value is the remainder of the division of the value of the first subtree by the second.
+    Modulo,
+    /// Value is the value of the first subtree raised to the power of the second.
+    Power,
+    /// Value is the natural logarithm of the value of the given subtree.
+    Logarithm,
+    /// Value is the square root of the value of the given subtree.
+    SquareRoot,
+    /// Value is the largest of the values of the given subtrees.
+    Maximum,
+    /// Value is the smallest of the values of the given subtrees.
+    Minimum,
+    /// Value is the result of the given [`ExternalFunction`] applied to the values of the given subtrees.
+    Function(ExternalFunction),
 }
 
 impl<T> Debug for ArithmeticOperation<T>
@@ -33,10 +120,27 @@ where
         match self {
             Self::Constant(constant) => write!(f, "{:?}", constant),
             Self::ColumnScan(index) => write!(f, "Column({:?})", index),
+            Self::ConvertedColumnScan(index, data_type) => {
+                write!(f, "Column({:?}) as {}", index, data_type)
+            }
             Self::Addition => write!(f, "Addition"),
             Self::Subtraction => write!(f, "Subtraction"),
             Self::Multiplication => write!(f, "Multiplication"),
             Self::Division => write!(f, "Division"),
+            Self::Hash => write!(f, "Hash"),
+            Self::Random => write!(f, "Random"),
+            Self::SeededRandom => write!(f, "SeededRandom"),
+            Self::Absolute => write!(f, "Absolute"),
+            Self::Round => write!(f, "Round"),
+            Self::Ceil => write!(f, "Ceil"),
+            Self::Floor => write!(f, "Floor"),
+            Self::Modulo => write!(f, "Modulo"),
+            Self::Power => write!(f, "Power"),
+            Self::Logarithm => write!(f, "Logarithm"),
+            Self::SquareRoot => write!(f, "SquareRoot"),
+            Self::Maximum => write!(f, "Maximum"),
+            Self::Minimum => write!(f, "Minimum"),
+            Self::Function(function) => write!(f, "Function({})", function.name()),
         }
     }
 }
@@ -49,10 +153,27 @@ where
         match self {
             Self::Constant(constant) => write!(f, "{}", constant),
             Self::ColumnScan(index) => write!(f, "Column({})", index),
+            Self::ConvertedColumnScan(index, data_type) => {
+                write!(f, "Column({}) as {}", index, data_type)
+            }
             Self::Addition => write!(f, "Addition"),
             Self::Subtraction => write!(f, "Subtraction"),
             Self::Multiplication => write!(f, "Multiplication"),
             Self::Division => write!(f, "Division"),
+            Self::Hash => write!(f, "Hash"),
+            Self::Random => write!(f, "Random"),
+            Self::SeededRandom => write!(f, "SeededRandom"),
+            Self::Absolute => write!(f, "Absolute"),
+            Self::Round => write!(f, "Round"),
+            Self::Ceil => write!(f, "Ceil"),
+            Self::Floor => write!(f, "Floor"),
+            Self::Modulo => write!(f, "Modulo"),
+            Self::Power => write!(f, "Power"),
+            Self::Logarithm => write!(f, "Logarithm"),
+            Self::SquareRoot => write!(f, "SquareRoot"),
+            Self::Maximum => write!(f, "Maximum"),
+            Self::Minimum => write!(f, "Minimum"),
+            Self::Function(function) => write!(f, "Function({})", function.name()),
         }
     }
 }
@@ -90,6 +211,36 @@ impl<T> OperationTree<T> {
     }
 }
 
+impl<T> OperationTree<T> {
+    /// Return a list of all the indices of converted column scans used in this tree.
+    pub fn converted_input_indices(&self) -> Vec<&usize> {
+        self.leaves()
+            .into_iter()
+            .filter_map(|l| {
+                if let ArithmeticOperation::ConvertedColumnScan(index, _) = l {
+                    Some(index)
+                } else {
+                    None
+                }
+            })
+            .collect()
+    }
+
+    /// Return a list with mutable references to all indices of converted column scans used in this tree.
+    pub fn converted_input_indices_mut(&mut self) -> Vec<&mut usize> {
+        self.leaves_mut()
+            .into_iter()
+            .filter_map(|l| {
+                if let ArithmeticOperation::ConvertedColumnScan(index, _) = l {
+                    Some(index)
+                } else {
+                    None
+                }
+            })
+            .collect()
+    }
+}
+
 /// Cursor position of the scan
 #[derive(Debug, Eq, PartialEq)]
 enum CursorPosition {
@@ -108,6 +259,10 @@ where
     /// [`OperationTree`] refers to a [`ColumnScan`] by its position in this `Vec`.
     column_scans: Vec<&'a ColumnScanCell<'a, T>>,
 
+    /// List of subiterators of arbitrary type, whose values are converted into the type of this scan.
+    /// [`OperationTree`] refers to such a [`ColumnScan`] by its position in this `Vec`.
+    converted_column_scans: Vec<&'a ColumnScanT<'a>>,
+
     /// The current value.
     value: Option<T>,
 
@@ -123,9 +278,14 @@ where
     T: ColumnDataType,
 {
     /// Constructs a new [`ColumnScanArithmetic`].
-    pub fn new(column_scans: Vec<&'a ColumnScanCell<'a, T>>, operation: OperationTree<T>) -> Self {
+    pub fn new(
+        column_scans: Vec<&'a ColumnScanCell<'a, T>>,
+        converted_column_scans: Vec<&'a ColumnScanT<'a>>,
+        operation: OperationTree<T>,
+    ) -> Self {
         Self {
             column_scans,
+            converted_column_scans,
             operation,
             value: None,
             cursor: CursorPosition::Before,
@@ -135,6 +295,20 @@ where
     fn evaluate_recursive(&self, tree: &OperationTree<T>) -> Option<T> {
         match &tree.tag {
             ArithmeticOperation::ColumnScan(index) => self.column_scans[*index].current(),
+            ArithmeticOperation::ConvertedColumnScan(index, _) => {
+                match self.converted_column_scans[*index].current()? {
+                    StorageValueT::U32(value) => T::checked_from_integer(value.into()),
+                    StorageValueT::U64(value) => {
+                        T::checked_from_integer(i64::try_from(value).ok()?)
+                    }
+                    StorageValueT::I64(value) => T::checked_from_integer(value),
+                    StorageValueT::U128(value) => {
+                        T::checked_from_integer(i64::try_from(value).ok()?)
+                    }
+                    StorageValueT::Float(value) => T::checked_from_float(f32::from(value).into()),
+                    StorageValueT::Double(value) => T::checked_from_float(value.into()),
+                }
+            }
             ArithmeticOperation::Constant(constant) => Some(*constant),
             ArithmeticOperation::Addition => {
                 let mut result = T::zero();
@@ -166,6 +340,70 @@ where
 
                 Some(left / right)
             }
+            ArithmeticOperation::Hash => {
+                let value = self.evaluate_recursive(&tree.subtrees[0])?;
+
+                Some(T::from_random_bits(mix_bits(value.to_hash_bits())))
+            }
+            ArithmeticOperation::Random => Some(T::from_random_bits(rand::random())),
+            ArithmeticOperation::SeededRandom => {
+                let seed = self.evaluate_recursive(&tree.subtrees[0])?;
+
+                Some(T::from_random_bits(mix_bits(
+                    mix_bits(seed.to_hash_bits()) ^ SEEDED_RANDOM_SALT,
+                )))
+            }
+            ArithmeticOperation::Absolute => self
+                .evaluate_recursive(&tree.subtrees[0])?
+                .checked_absolute(),
+            ArithmeticOperation::Round => {
+                self.evaluate_recursive(&tree.subtrees[0])?.checked_round()
+            }
+            ArithmeticOperation::Ceil => self.evaluate_recursive(&tree.subtrees[0])?.checked_ceil(),
+            ArithmeticOperation::Floor => {
+                self.evaluate_recursive(&tree.subtrees[0])?.checked_floor()
+            }
+            ArithmeticOperation::Modulo => {
+                let left = self.evaluate_recursive(&tree.subtrees[0])?;
+                let right = self.evaluate_recursive(&tree.subtrees[1])?;
+
+                left.checked_modulo(right)
+            }
+            ArithmeticOperation::Power => {
+                let left = self.evaluate_recursive(&tree.subtrees[0])?;
+                let right = self.evaluate_recursive(&tree.subtrees[1])?;
+
+                left.checked_power(right)
+            }
+            ArithmeticOperation::Logarithm => self
+                .evaluate_recursive(&tree.subtrees[0])?
+                .checked_logarithm(),
+            ArithmeticOperation::SquareRoot => self
+                .evaluate_recursive(&tree.subtrees[0])?
+                .checked_square_root(),
+            ArithmeticOperation::Maximum => tree
+                .subtrees
+                .iter()
+                .map(|subtree| self.evaluate_recursive(subtree))
+                .collect::<Option<Vec<_>>>()?
+                .into_iter()
+                .max(),
+            ArithmeticOperation::Minimum => tree
+                .subtrees
+                .iter()
+                .map(|subtree| self.evaluate_recursive(subtree))
+                .collect::<Option<Vec<_>>>()?
+                .into_iter()
+                .min(),
+            ArithmeticOperation::Function(function) => {
+                let arguments = tree
+                    .subtrees
+                    .iter()
+                    .map(|subtree| self.evaluate_recursive(subtree)?.to_storage_value_t())
+                    .collect::<Option<Vec<_>>>()?;
+
+                T::from_storage_value_t(function.evaluate(&arguments)?)
+            }
         }
     }
 
@@ -236,13 +474,18 @@ where
 
 #[cfg(test)]
 mod test {
-    use crate::columnar::{
-        column_types::vector::{ColumnScanVector, ColumnVector},
-        operations::columnscan_arithmetic::OperationTree,
-        traits::columnscan::{ColumnScan, ColumnScanCell, ColumnScanEnum},
+    use crate::{
+        columnar::{
+            column_types::vector::{ColumnScanVector, ColumnVector},
+            operations::columnscan_arithmetic::OperationTree,
+            traits::columnscan::{ColumnScan, ColumnScanCell, ColumnScanEnum, ColumnScanT},
+        },
+        datatypes::{DataTypeName, Double, StorageValueT},
     };
 
-    use super::{ArithmeticOperation, ColumnScanArithmetic};
+    use super::{ArithmeticOperation, ColumnScanArithmetic, ExternalFunction};
+
+    use std::rc::Rc;
 
     use test_log::test;
 
@@ -286,7 +529,7 @@ mod test {
         );
 
         let scans = vec![&scan_a_cell, &scan_b_cell];
-        let mut arithmetic = ColumnScanArithmetic::new(scans, operation);
+        let mut arithmetic = ColumnScanArithmetic::new(scans, vec![], operation);
 
         assert_eq!(arithmetic.current(), None);
         assert_eq!(arithmetic.next(), Some(20));
@@ -296,4 +539,105 @@ mod test {
         assert_eq!(arithmetic.next(), None);
         assert_eq!(arithmetic.current(), None);
     }
+
+    #[test]
+    fn column_scan_arithmetic_random() {
+        let column: ColumnVector<i64> = ColumnVector::new(vec![42]);
+        let mut scan = ColumnScanEnum::ColumnScanVector(ColumnScanVector::new(&column));
+        scan.next();
+        let scan_cell = ColumnScanCell::new(scan);
+
+        let evaluate = |operation: ArithmeticOperation<i64>| {
+            let tree = OperationTree::tree(
+                operation,
+                vec![OperationTree::leaf(ArithmeticOperation::ColumnScan(0))],
+            );
+
+            ColumnScanArithmetic::new(vec![&scan_cell], vec![], tree)
+                .next()
+                .unwrap()
+        };
+
+        let hash = evaluate(ArithmeticOperation::Hash);
+        let seeded = evaluate(ArithmeticOperation::SeededRandom);
+
+        assert!(hash >= 0 && seeded >= 0);
+        assert_ne!(hash, seeded);
+        assert_eq!(hash, evaluate(ArithmeticOperation::Hash));
+        assert_eq!(seeded, evaluate(ArithmeticOperation::SeededRandom));
+
+        let random = ColumnScanArithmetic::<Double>::new(
+            vec![],
+            vec![],
+            OperationTree::leaf(ArithmeticOperation::Random),
+        )
+        .next()
+        .map(f64::from)
+        .unwrap();
+
+        assert!((0.0..1.0).contains(&random));
+    }
+
+    #[test]
+    fn column_scan_arithmetic_conversion() {
+        let column: ColumnVector<Double> = ColumnVector::new(vec![Double::new(-2.5).unwrap()]);
+        let mut scan = ColumnScanEnum::ColumnScanVector(ColumnScanVector::new(&column));
+        scan.next();
+        let scan_t = ColumnScanT::Double(ColumnScanCell::new(scan));
+
+        // abs(int(a)) + 1
+        let operation: OperationTree<i64> = OperationTree::tree(
+            ArithmeticOperation::Addition,
+            vec![
+                OperationTree::tree(
+                    ArithmeticOperation::Absolute,
+                    vec![OperationTree::leaf(
+                        ArithmeticOperation::ConvertedColumnScan(0, DataTypeName::I64),
+                    )],
+                ),
+                OperationTree::leaf(ArithmeticOperation::Constant(1)),
+            ],
+        );
+
+        let mut arithmetic = ColumnScanArithmetic::new(vec![], vec![&scan_t], operation);
+        assert_eq!(arithmetic.next(), Some(3));
+    }
+
+    #[test]
+    fn column_scan_arithmetic_function() {
+        let column: ColumnVector<i64> = ColumnVector::new(vec![-4, 7]);
+        let scan = ColumnScanEnum::ColumnScanVector(ColumnScanVector::new(&column));
+        let scan_cell = ColumnScanCell::new(scan);
+
+        // Only defined for even numbers
+        let half = ExternalFunction::new(
+            "half".to_string(),
+            DataTypeName::I64,
+            Rc::new(|arguments| match arguments {
+                [StorageValueT::I64(value)] if value % 2 == 0 => {
+                    Some(StorageValueT::I64(value / 2))
+                }
+                _ => None,
+            }),
+        );
+
+        // half(a) + 1
+        let operation: OperationTree<i64> = OperationTree::tree(
+            ArithmeticOperation::Addition,
+            vec![
+                OperationTree::tree(
+                    ArithmeticOperation::Function(half),
+                    vec![OperationTree::leaf(ArithmeticOperation::ColumnScan(0))],
+                ),
+                OperationTree::leaf(ArithmeticOperation::Constant(1)),
+            ],
+        );
+
+        let mut arithmetic = ColumnScanArithmetic::new(vec![&scan_cell], vec![], operation);
+        scan_cell.next();
+        assert_eq!(arithmetic.next(), Some(-1));
+        scan_cell.next();
+        arithmetic.reset();
+        assert_eq!(arithmetic.next(), None);
+    }
 }
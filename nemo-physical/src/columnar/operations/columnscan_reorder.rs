@@ -90,7 +90,7 @@ where
         }
 
         self.current_value = Some(next_value);
-        log::debug!("{self:#?}");
+        tracing::debug!("{self:#?}");
         self.current_value
     }
 }
@@ -79,6 +79,35 @@ where
     }
 }
 
+/// Function returning the sorted list of values that are related to a given value
+pub type RelatedValuesFunction<T> = Box<dyn Fn(T) -> Vec<T>>;
+
+/// Restriction of a value to the values related to the current value of another scan
+struct RelatedValues<T> {
+    /// Index of the sub scan whose current value is used
+    column: usize,
+    /// Function returning the related values
+    related: RelatedValuesFunction<T>,
+    /// Related values of the last value they have been computed for
+    cache: Option<(T, Vec<T>)>,
+}
+
+impl<T: Debug> Debug for RelatedValues<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RelatedValues")
+            .field("column", &self.column)
+            .field("cache", &self.cache)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Return the smallest of the given sorted values that is at least as large as `value`.
+fn next_value<T: Copy + Ord>(values: &[T], value: T) -> Option<T> {
+    values
+        .get(values.partition_point(|other| *other < value))
+        .copied()
+}
+
 #[derive(Debug)]
 enum ColumnScanStatus {
     /// Iterator is before the lower bound of the interval.
@@ -106,6 +135,10 @@ where
     upper_bounds: Vec<FilterBound<T>>,
     /// Values that are skipped in `scan_value`.
     avoid_values: Vec<FilterValue<T>>,
+    /// Sorted list of the only values that may be taken by `scan_value`, if restricted.
+    allowed_values: Option<Vec<T>>,
+    /// Restrictions of `scan_value` to the values related to the values of sub scans.
+    related_values: Vec<RelatedValues<T>>,
 
     /// Status of this scan.
     status: ColumnScanStatus,
@@ -128,10 +161,33 @@ where
             lower_bounds,
             upper_bounds,
             avoid_values,
+            allowed_values: None,
+            related_values: Vec::new(),
             status: ColumnScanStatus::Before,
         }
     }
 
+    /// Additionally restrict `scan_value` to the given values, which need to be sorted.
+    pub fn with_allowed_values(mut self, allowed_values: Vec<T>) -> Self {
+        debug_assert!(allowed_values.is_sorted());
+
+        self.allowed_values = Some(allowed_values);
+        self
+    }
+
+    /// Additionally restrict `scan_value` to the values that `related` returns
+    /// for the current value of the sub scan with index `column` in `scans_restriction`.
+    ///
+    /// The returned values need to be sorted.
+    pub fn with_related_values(mut self, column: usize, related: RelatedValuesFunction<T>) -> Self {
+        self.related_values.push(RelatedValues {
+            column,
+            related,
+            cache: None,
+        });
+        self
+    }
+
     fn get_value(&self, value: &FilterValue<T>) -> T {
         match value {
             FilterValue::Column(index) => self.scans_restriction[*index]
@@ -202,6 +258,58 @@ where
 
         false
     }
+
+    /// Advance `scan_value` until its current value is one of the allowed values,
+    /// is related to the current values of the respective sub scans
+    /// and is none of the values to avoid.
+    ///
+    /// Returns `false` if there is no such value.
+    fn skip_restricted_values(&mut self) -> bool {
+        'values: while let Some(current) = self.scan_value.current() {
+            if let Some(allowed_values) = &self.allowed_values {
+                let Some(next_allowed) = next_value(allowed_values, current) else {
+                    return false;
+                };
+
+                if next_allowed != current {
+                    self.scan_value.seek(next_allowed);
+                    continue;
+                }
+            }
+
+            for related_values in &mut self.related_values {
+                let reference = self.scans_restriction[related_values.column]
+                    .current()
+                    .expect("Related values are only computed for sub scans that have a value.");
+
+                if !matches!(related_values.cache, Some((value, _)) if value == reference) {
+                    related_values.cache = Some((reference, (related_values.related)(reference)));
+                }
+
+                let (_, values) = related_values
+                    .cache
+                    .as_ref()
+                    .expect("The cache has been filled above.");
+                let Some(next_related) = next_value(values, current) else {
+                    return false;
+                };
+
+                if next_related != current {
+                    self.scan_value.seek(next_related);
+                    continue 'values;
+                }
+            }
+
+            if !self.check_avoid_values() {
+                self.scan_value.next();
+                continue;
+            }
+
+            return true;
+        }
+
+        false
+    }
 }
 
 impl<'a, T> Iterator for ColumnScanRestrictValues<'a, T>
@@ -222,11 +330,7 @@ where
             ColumnScanStatus::After => return None,
         }
 
-        if !self.check_avoid_values() {
-            self.scan_value.next();
-        }
-
-        if self.check_upper_bounds() {
+        if self.skip_restricted_values() && self.check_upper_bounds() {
             self.current()
         } else {
             self.status = ColumnScanStatus::After;
@@ -253,11 +357,7 @@ where
             ColumnScanStatus::After => return None,
         }
 
-        if !self.check_avoid_values() {
-            self.scan_value.next();
-        }
-
-        if self.check_upper_bounds() {
+        if self.skip_restricted_values() && self.check_upper_bounds() {
             self.current()
         } else {
             self.status = ColumnScanStatus::After;
@@ -462,4 +562,73 @@ mod test {
         assert_eq!(restrict_scan.next(), None);
         assert_eq!(restrict_scan.current(), None);
     }
+
+    #[test]
+    fn restrict_related_values() {
+        let column_value = ColumnVector::new(vec![1u64, 2, 3, 5, 6, 9, 10]);
+        let column_reference = ColumnVector::new(vec![2u64, 5]);
+
+        let value_iter = ColumnScanCell::new(ColumnScanEnum::ColumnScanVector(column_value.iter()));
+        let reference_iter =
+            ColumnScanCell::new(ColumnScanEnum::ColumnScanVector(column_reference.iter()));
+        reference_iter.next();
+
+        // Values differing by at most one from the reference
+        let mut restrict_scan = ColumnScanRestrictValues::new(
+            &value_iter,
+            vec![&reference_iter],
+            vec![],
+            vec![],
+            vec![],
+        )
+        .with_related_values(0, Box::new(|value: u64| vec![value - 1, value, value + 1]));
+
+        assert_eq!(restrict_scan.next(), Some(1));
+        assert_eq!(restrict_scan.next(), Some(2));
+        assert_eq!(restrict_scan.next(), Some(3));
+        assert_eq!(restrict_scan.next(), None);
+
+        reference_iter.next();
+        let value_iter = ColumnScanCell::new(ColumnScanEnum::ColumnScanVector(column_value.iter()));
+        let mut restrict_scan = ColumnScanRestrictValues::new(
+            &value_iter,
+            vec![&reference_iter],
+            vec![],
+            vec![],
+            vec![],
+        )
+        .with_related_values(0, Box::new(|value: u64| vec![value - 1, value, value + 1]));
+
+        assert_eq!(restrict_scan.next(), Some(5));
+        assert_eq!(restrict_scan.next(), Some(6));
+        assert_eq!(restrict_scan.next(), None);
+    }
+
+    #[test]
+    fn restrict_allowed_values() {
+        let col = ColumnVector::new(vec![1u64, 2, 3, 4, 5, 6, 8]);
+        let col_iter = ColumnScanCell::new(ColumnScanEnum::ColumnScanVector(col.iter()));
+
+        let mut restrict_scan = ColumnScanRestrictValues::new(
+            &col_iter,
+            vec![],
+            vec![],
+            vec![FilterBound::Inclusive(FilterValue::Constant(7))],
+            vec![FilterValue::Constant(3), FilterValue::Constant(4)],
+        )
+        .with_allowed_values(vec![0, 2, 3, 4, 6, 8]);
+
+        assert_eq!(restrict_scan.next(), Some(2));
+        assert_eq!(restrict_scan.next(), Some(6));
+        assert_eq!(restrict_scan.next(), None);
+
+        let col_iter = ColumnScanCell::new(ColumnScanEnum::ColumnScanVector(col.iter()));
+        let mut restrict_scan =
+            ColumnScanRestrictValues::new(&col_iter, vec![], vec![], vec![], vec![])
+                .with_allowed_values(vec![0, 2, 3, 4, 6, 8]);
+
+        assert_eq!(restrict_scan.seek(5), Some(6));
+        assert_eq!(restrict_scan.next(), Some(8));
+        assert_eq!(restrict_scan.next(), None);
+    }
 }
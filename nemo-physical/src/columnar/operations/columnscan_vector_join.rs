@@ -0,0 +1,201 @@
+use crate::datatypes::ColumnDataType;
+
+use std::fmt::Debug;
+use std::ops::Range;
+
+use super::super::traits::columnscan::{ColumnScan, ColumnScanVectorRef};
+
+/// Implementation of [`ColumnScan`] for the result of joining a list of
+/// [`ColumnScanVector`][crate::columnar::column_types::vector::ColumnScanVector] objects.
+///
+/// This computes the same result as a [`ColumnScanJoin`][super::ColumnScanJoin],
+/// but calls its sub scans without dispatching over all kinds of [`ColumnScan`]s.
+/// It is used when joining materialized tries, whose column scans are all vector scans.
+#[derive(Debug)]
+pub struct ColumnScanVectorJoin<'a, T>
+where
+    T: 'a + ColumnDataType,
+{
+    /// List of subiterators to be joined
+    column_scans: Vec<ColumnScanVectorRef<'a, T>>,
+
+    /// Index of the scan which is currently being advanced
+    active_index: usize,
+
+    /// Current value of the [`ColumnScanVectorJoin`]; its also the value pointed to by each sub scan
+    current_value: Option<T>,
+}
+
+impl<'a, T> ColumnScanVectorJoin<'a, T>
+where
+    T: 'a + ColumnDataType,
+{
+    /// Constructs a new [`ColumnScanVectorJoin`].
+    pub fn new(column_scans: Vec<ColumnScanVectorRef<'a, T>>) -> Self {
+        ColumnScanVectorJoin {
+            column_scans,
+            active_index: 0,
+            current_value: None,
+        }
+    }
+}
+
+impl<'a, T> ColumnScanVectorJoin<'a, T>
+where
+    T: 'a + ColumnDataType,
+{
+    /// Sets each sub scan to the currently largest value until
+    /// either all sub scans point to the same value
+    /// or one sub scan reaches its end
+    #[inline]
+    fn next_loop(&mut self, mut current_max: T) -> Option<T> {
+        // Number of scans that point to the same value
+        let mut matched_scans: usize = 1;
+
+        loop {
+            // If all the sub scans point to the same value we found the next match
+            if matched_scans == self.column_scans.len() {
+                return Some(current_max);
+            }
+
+            // Select the next sub scan
+            self.active_index = (self.active_index + 1) % self.column_scans.len();
+
+            // If we reach the end of one sub scan then we have reached the end of the join
+            let active_value = self.column_scans[self.active_index].seek(current_max)?;
+
+            if active_value == current_max {
+                // If the values are equal we increment the counter
+                matched_scans += 1;
+            } else {
+                // If the value of the current scan does not equal `current_max` then it must be larger
+                current_max = active_value;
+                matched_scans = 1;
+            }
+        }
+    }
+}
+
+impl<'a, T> Iterator for ColumnScanVectorJoin<'a, T>
+where
+    T: 'a + ColumnDataType,
+{
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.current_value = self.column_scans[self.active_index]
+            .next()
+            .and_then(|next_active_value| self.next_loop(next_active_value));
+
+        self.current_value
+    }
+}
+
+impl<'a, T> ColumnScan for ColumnScanVectorJoin<'a, T>
+where
+    T: 'a + ColumnDataType,
+{
+    fn seek(&mut self, value: T) -> Option<T> {
+        self.current_value = self.column_scans[self.active_index]
+            .seek(value)
+            .and_then(|next_active_value| self.next_loop(next_active_value));
+
+        self.current_value
+    }
+
+    fn current(&self) -> Option<T> {
+        self.current_value
+    }
+
+    fn reset(&mut self) {
+        self.active_index = 0;
+        self.current_value = None;
+    }
+
+    fn pos(&self) -> Option<usize> {
+        unimplemented!("This functions is not implemented for column operators");
+    }
+    fn narrow(&mut self, _interval: Range<usize>) {
+        unimplemented!("This functions is not implemented for column operators");
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use test_log::test;
+
+    use super::ColumnScanVectorJoin;
+    use crate::columnar::{
+        column_types::{
+            rle::ColumnRle,
+            vector::{ColumnScanVector, ColumnVector},
+        },
+        operations::ColumnScanJoin,
+        traits::{
+            column::Column,
+            columnscan::{ColumnScan, ColumnScanCell, ColumnScanEnum},
+        },
+    };
+
+    #[test]
+    fn test_u64_vector_join() {
+        let vc1 = ColumnVector::new((0..100u64).map(|value| value * 2).collect());
+        let vc2 = ColumnVector::new((0..100u64).map(|value| value * 3).collect());
+        let vc3 = ColumnVector::new((0..100u64).map(|value| value * 5).collect());
+        let expected = (0..200u64).step_by(30).collect::<Vec<_>>();
+
+        let gcs1: ColumnScanCell<u64> = ColumnScanVector::new(&vc1).into();
+        let gcs2: ColumnScanCell<u64> = ColumnScanVector::new(&vc2).into();
+        let gcs3: ColumnScanCell<u64> = ColumnScanVector::new(&vc3).into();
+
+        let generic = ColumnScanJoin::new(vec![&gcs1, &gcs2, &gcs3]);
+        assert_eq!(generic.collect::<Vec<_>>(), expected);
+
+        gcs1.reset();
+        gcs2.reset();
+        gcs3.reset();
+
+        let scans = [&gcs1, &gcs2, &gcs3]
+            .into_iter()
+            .map(|scan| scan.vector_scan().unwrap())
+            .collect();
+        let mut omj = ColumnScanVectorJoin::new(scans);
+        assert_eq!(omj.next(), Some(0));
+        assert_eq!(omj.current(), Some(0));
+        assert_eq!(omj.by_ref().collect::<Vec<_>>(), expected[1..]);
+        assert_eq!(omj.current(), None);
+
+        gcs1.reset();
+        gcs2.reset();
+
+        let scans = [&gcs1, &gcs2]
+            .into_iter()
+            .map(|scan| scan.vector_scan().unwrap())
+            .collect();
+        let mut omj = ColumnScanVectorJoin::new(scans);
+        assert_eq!(omj.seek(7), Some(12));
+        assert_eq!(gcs1.current(), Some(12));
+        assert_eq!(gcs2.current(), Some(12));
+        assert_eq!(omj.next(), Some(18));
+        assert_eq!(omj.seek(190), Some(192));
+        assert_eq!(omj.next(), Some(198));
+        assert_eq!(omj.next(), None);
+    }
+
+    #[test]
+    fn test_vector_scan_ref() {
+        let vc = ColumnVector::new(vec![1u64, 2, 3]);
+        let rle = ColumnRle::new(vec![1u64, 2, 3]);
+
+        let gcs_vector: ColumnScanCell<u64> = ColumnScanVector::new(&vc).into();
+        let gcs_rle: ColumnScanCell<u64> = ColumnScanEnum::ColumnScanRle(rle.iter()).into();
+        assert!(gcs_rle.vector_scan().is_none());
+
+        let scan = gcs_vector.vector_scan().unwrap();
+        assert_eq!(scan.next(), Some(1));
+        assert_eq!(gcs_vector.current(), Some(1));
+        assert_eq!(gcs_vector.seek(3), Some(3));
+        assert_eq!(scan.current(), Some(3));
+        assert_eq!(scan.next(), None);
+    }
+}
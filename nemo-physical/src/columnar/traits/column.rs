@@ -7,7 +7,9 @@ use crate::{
 };
 use std::{fmt::Debug, mem::size_of};
 
-use super::super::column_types::{rle::ColumnRle, vector::ColumnVector};
+use super::super::column_types::{
+    rank_select::ColumnRankSelect, rle::ColumnRle, vector::ColumnVector,
+};
 use super::columnscan::{ColumnScan, ColumnScanEnum};
 
 /// Column of ordered values.
@@ -40,11 +42,14 @@ pub enum ColumnEnum<T: RunLengthEncodable> {
     ColumnVector(ColumnVector<T>),
     /// Case ColumnRle
     ColumnRle(ColumnRle<T>),
+    /// Case ColumnRankSelect
+    ColumnRankSelect(ColumnRankSelect<T>),
 }
 
 generate_forwarder!(forward_to_column;
                     ColumnVector,
-                    ColumnRle);
+                    ColumnRle,
+                    ColumnRankSelect);
 
 impl<'a, T> Column<'a, T> for ColumnEnum<T>
 where
@@ -68,6 +73,7 @@ where
         match self {
             Self::ColumnVector(col) => ColumnScanEnum::ColumnScanVector(col.iter()),
             Self::ColumnRle(col) => ColumnScanEnum::ColumnScanRle(col.iter()),
+            Self::ColumnRankSelect(col) => ColumnScanEnum::ColumnScanRankSelect(col.iter()),
         }
     }
 }
@@ -88,6 +94,8 @@ pub enum ColumnT {
     U64(ColumnEnum<u64>),
     /// Case `ColumnEnum<i64>`
     I64(ColumnEnum<i64>),
+    /// Case `ColumnEnum<u128>`
+    U128(ColumnEnum<u128>),
     /// Case `ColumnEnum<Float>`
     Float(ColumnEnum<Float>),
     /// Case `ColumnEnum<Double>`
@@ -1,7 +1,10 @@
-use super::super::column_types::{rle::ColumnScanRle, vector::ColumnScanVector};
+use super::super::column_types::{
+    rank_select::ColumnScanRankSelect, rle::ColumnScanRle, vector::ColumnScanVector,
+};
 use super::super::operations::{
     ColumnScanCastEnum, ColumnScanEqualColumn, ColumnScanFollow, ColumnScanJoin, ColumnScanMinus,
     ColumnScanPass, ColumnScanPrune, ColumnScanReorder, ColumnScanRestrictValues, ColumnScanUnion,
+    ColumnScanVectorJoin,
 };
 
 use crate::columnar::operations::{
@@ -11,7 +14,7 @@ use crate::{
     datatypes::{ColumnDataType, Double, Float, StorageValueT},
     generate_datatype_forwarder, generate_forwarder,
 };
-use std::{cell::UnsafeCell, fmt::Debug, ops::Range};
+use std::{cell::UnsafeCell, fmt::Debug, marker::PhantomData, ops::Range, ptr::NonNull};
 
 /// Iterator for a sorted interval of values
 pub trait ColumnScan: Debug + Iterator {
@@ -40,6 +43,24 @@ pub trait ColumnScan: Debug + Iterator {
     /// Restricts the iterator to the given `interval`.
     /// Resets the iterator just before the start of the interval.
     fn narrow(&mut self, interval: Range<usize>);
+
+    /// Append the next (at most) `n` values to `out` and return how many values were appended.
+    ///
+    /// This leaves the iterator in the same state as calling [`Iterator::next`]
+    /// until it returned `n` values or `None`,
+    /// but allows implementations to produce values without a call per value.
+    fn next_batch(&mut self, out: &mut Vec<Self::Item>, n: usize) -> usize {
+        let mut count = 0;
+        while count < n {
+            match self.next() {
+                Some(value) => out.push(value),
+                None => break,
+            }
+            count += 1;
+        }
+
+        count
+    }
 }
 
 /// Enum for [`ColumnScan`] of all supported types
@@ -52,8 +73,12 @@ where
     ColumnScanVector(ColumnScanVector<'a, T>),
     /// Case ColumnRleScan
     ColumnScanRle(ColumnScanRle<'a, T>),
+    /// Case ColumnScanRankSelect
+    ColumnScanRankSelect(ColumnScanRankSelect<'a, T>),
     /// Case ColumnScanJoin
     ColumnScanJoin(ColumnScanJoin<'a, T>),
+    /// Case ColumnScanVectorJoin
+    ColumnScanVectorJoin(ColumnScanVectorJoin<'a, T>),
     /// Case ColumnScanCast
     ColumnScanCast(ColumnScanCastEnum<'a, T>),
     /// Case ColumnScanReorder
@@ -104,6 +129,15 @@ where
     }
 }
 
+impl<'a, T> From<ColumnScanRankSelect<'a, T>> for ColumnScanEnum<'a, T>
+where
+    T: 'a + ColumnDataType,
+{
+    fn from(cs: ColumnScanRankSelect<'a, T>) -> Self {
+        Self::ColumnScanRankSelect(cs)
+    }
+}
+
 impl<'a, T> From<ColumnScanCastEnum<'a, T>> for ColumnScanEnum<'a, T>
 where
     T: 'a + ColumnDataType,
@@ -122,6 +156,15 @@ where
     }
 }
 
+impl<'a, T> From<ColumnScanVectorJoin<'a, T>> for ColumnScanEnum<'a, T>
+where
+    T: 'a + ColumnDataType,
+{
+    fn from(cs: ColumnScanVectorJoin<'a, T>) -> Self {
+        Self::ColumnScanVectorJoin(cs)
+    }
+}
+
 impl<'a, T> From<ColumnScanReorder<'a, T>> for ColumnScanEnum<'a, T>
 where
     T: 'a + ColumnDataType,
@@ -279,8 +322,10 @@ where
 generate_forwarder!(forward_to_columnscan;
     ColumnScanVector,
     ColumnScanRle,
+    ColumnScanRankSelect,
     ColumnScanCast,
     ColumnScanJoin,
+    ColumnScanVectorJoin,
     ColumnScanReorder,
     ColumnScanEqualColumn,
     ColumnScanRestrictValues,
@@ -330,6 +375,10 @@ where
     fn narrow(&mut self, interval: Range<usize>) {
         forward_to_columnscan!(self, narrow(interval))
     }
+
+    fn next_batch(&mut self, out: &mut Vec<Self::Item>, n: usize) -> usize {
+        forward_to_columnscan!(self, next_batch(out, n))
+    }
 }
 
 /// A wrapper around a cell type holding a `ColumnScanEnum`.
@@ -372,6 +421,17 @@ where
         unsafe { &mut *self.0.get() }.seek(value)
     }
 
+    /// Return a [`ColumnScanVectorRef`] to the underlying scan if it is a [`ColumnScanVector`].
+    pub fn vector_scan(&'a self) -> Option<ColumnScanVectorRef<'a, T>> {
+        match unsafe { &mut *self.0.get() } {
+            ColumnScanEnum::ColumnScanVector(scan) => Some(ColumnScanVectorRef {
+                scan: NonNull::from(scan),
+                _cell: PhantomData,
+            }),
+            _ => None,
+        }
+    }
+
     /// Forward `current` to the underlying [`ColumnScanEnum`].
     #[inline]
     pub fn current(&self) -> Option<<ColumnScanEnum<'a, T> as Iterator>::Item> {
@@ -396,6 +456,16 @@ where
         unsafe { &mut *self.0.get() }.narrow(interval)
     }
 
+    /// Forward `next_batch` to the underlying [`ColumnScanEnum`].
+    #[inline]
+    pub fn next_batch(
+        &self,
+        out: &mut Vec<<ColumnScanEnum<'a, T> as Iterator>::Item>,
+        n: usize,
+    ) -> usize {
+        unsafe { &mut *self.0.get() }.next_batch(out, n)
+    }
+
     /// Forward `pos_multiple` to the underlying [`ColumnScanEnum`].
     pub fn pos_multiple(&self) -> Option<Vec<usize>> {
         unsafe { &mut *self.0.get() }.pos_multiple()
@@ -450,6 +520,65 @@ where
     }
 }
 
+/// Reference to a [`ColumnScanVector`] contained in a [`ColumnScanCell`].
+///
+/// Calls through this reference go directly to the [`ColumnScanVector`],
+/// instead of dispatching over all variants of [`ColumnScanEnum`].
+/// Like the [`ColumnScanCell`] itself, it may be used to advance the scan while other references to the cell exist.
+pub struct ColumnScanVectorRef<'a, T>
+where
+    T: 'a + ColumnDataType,
+{
+    /// Scan contained in the cell
+    scan: NonNull<ColumnScanVector<'a, T>>,
+    /// Cell containing the scan
+    _cell: PhantomData<&'a ColumnScanCell<'a, T>>,
+}
+
+impl<'a, T> Clone for ColumnScanVectorRef<'a, T>
+where
+    T: 'a + ColumnDataType,
+{
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'a, T> Copy for ColumnScanVectorRef<'a, T> where T: 'a + ColumnDataType {}
+
+impl<T> Debug for ColumnScanVectorRef<'_, T>
+where
+    T: ColumnDataType,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let scan = unsafe { self.scan.as_ref() };
+        f.debug_tuple("ColumnScanVectorRef").field(scan).finish()
+    }
+}
+
+impl<'a, T> ColumnScanVectorRef<'a, T>
+where
+    T: 'a + ColumnDataType,
+{
+    /// Forward `next` to the underlying [`ColumnScanVector`].
+    #[inline]
+    pub fn next(&self) -> Option<T> {
+        unsafe { &mut *self.scan.as_ptr() }.next()
+    }
+
+    /// Forward `seek` to the underlying [`ColumnScanVector`].
+    #[inline]
+    pub fn seek(&self, value: T) -> Option<T> {
+        unsafe { &mut *self.scan.as_ptr() }.seek(value)
+    }
+
+    /// Forward `current` to the underlying [`ColumnScanVector`].
+    #[inline]
+    pub fn current(&self) -> Option<T> {
+        unsafe { self.scan.as_ref() }.current()
+    }
+}
+
 /// Enum for [`ColumnScan`] for underlying data type
 #[derive(Debug)]
 pub enum ColumnScanT<'a> {
@@ -459,6 +588,8 @@ pub enum ColumnScanT<'a> {
     U64(ColumnScanCell<'a, u64>),
     /// Case i64
     I64(ColumnScanCell<'a, i64>),
+    /// Case u128
+    U128(ColumnScanCell<'a, u128>),
     /// Case Float
     Float(ColumnScanCell<'a, Float>),
     /// Case Double
@@ -542,6 +673,10 @@ impl<'a> ColumnScan for ColumnScanT<'a> {
                 Self::Item::I64(val) => cs.seek(val).map(StorageValueT::I64),
                 _ => None,
             },
+            Self::U128(cs) => match value {
+                Self::Item::U128(val) => cs.seek(val).map(StorageValueT::U128),
+                _ => None,
+            },
             Self::Float(cs) => match value {
                 Self::Item::Float(val) => cs.seek(val).map(StorageValueT::Float),
                 _ => None,
@@ -5,7 +5,7 @@ pub mod storage_type_name;
 pub use storage_type_name::StorageTypeName;
 /// Module for defining [`StorageValueT`]
 pub mod storage_value;
-pub use storage_value::StorageValueT;
+pub use storage_value::{StorageValueConversion, StorageValueT};
 /// Module for defining [`DataTypeName`]
 pub mod data_type_name;
 pub use data_type_name::DataTypeName;
@@ -30,6 +30,12 @@ pub use field::Field;
 /// Module for defining [`FloorToUsize`]
 pub mod floor_to_usize;
 pub use floor_to_usize::FloorToUsize;
+/// Module for defining [`NumericFunctions`]
+pub mod numeric_functions;
+pub use numeric_functions::NumericFunctions;
+/// Module for defining [`RandomBits`]
+pub mod random_bits;
+pub use random_bits::RandomBits;
 
 /// Module for defining [`ColumnDataType`]
 pub mod column_data_type;
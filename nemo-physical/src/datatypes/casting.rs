@@ -49,6 +49,7 @@ implicit_cast_id!(u8);
 implicit_cast_id!(u16);
 implicit_cast_id!(u32);
 implicit_cast_id!(u64);
+implicit_cast_id!(u128);
 implicit_cast_id!(usize);
 implicit_cast_id!(i8);
 implicit_cast_id!(i16);
@@ -88,6 +89,9 @@ implicit_cast_small_to_large!(u16, usize);
 
 implicit_cast_small_to_large!(u32, u64);
 implicit_cast_small_to_large!(u32, i64);
+implicit_cast_small_to_large!(u32, u128);
+
+implicit_cast_small_to_large!(u64, u128);
 
 implicit_cast_small_to_large!(i8, i16);
 implicit_cast_small_to_large!(i8, i32);
@@ -127,6 +131,10 @@ implicit_cast_unsigned_large_to_small!(u64, u16);
 implicit_cast_unsigned_large_to_small!(u64, u8);
 implicit_cast_unsigned_large_to_small!(u64, i64);
 
+implicit_cast_unsigned_large_to_small!(u128, u64);
+implicit_cast_unsigned_large_to_small!(u128, u32);
+implicit_cast_unsigned_large_to_small!(u128, i64);
+
 implicit_cast_unsigned_large_to_small!(u32, u16);
 implicit_cast_unsigned_large_to_small!(u32, u8);
 
@@ -180,6 +188,7 @@ implicit_cast_signed_large_to_small!(i64, i16);
 implicit_cast_signed_large_to_small!(i64, i8);
 implicit_cast_signed_large_to_small!(i64, u32);
 implicit_cast_signed_large_to_small!(i64, u64);
+implicit_cast_signed_large_to_small!(i64, u128);
 
 implicit_cast_signed_large_to_small!(i32, i16);
 implicit_cast_signed_large_to_small!(i32, i8);
@@ -286,6 +295,7 @@ impl PartialOrd for StorageTypeName {
                 Self::U32 => Some(Ordering::Equal),
                 Self::U64 => Some(Ordering::Less),
                 Self::I64 => Some(Ordering::Less),
+                Self::U128 => None,
                 Self::Float => None,
                 Self::Double => None,
             },
@@ -293,6 +303,7 @@ impl PartialOrd for StorageTypeName {
                 Self::U32 => Some(Ordering::Greater),
                 Self::U64 => Some(Ordering::Equal),
                 Self::I64 => None,
+                Self::U128 => None,
                 Self::Float => None,
                 Self::Double => None,
             },
@@ -300,9 +311,14 @@ impl PartialOrd for StorageTypeName {
                 Self::U32 => Some(Ordering::Greater),
                 Self::U64 => None,
                 Self::I64 => Some(Ordering::Equal),
+                Self::U128 => None,
                 Self::Float => None,
                 Self::Double => None,
             },
+            Self::U128 => match other {
+                Self::U128 => Some(Ordering::Equal),
+                _ => None,
+            },
             Self::Float => match other {
                 Self::Float => Some(Ordering::Equal),
                 _ => None,
@@ -329,6 +345,7 @@ impl PartialOrd for DataTypeName {
                 Self::U32 => Some(Ordering::Equal),
                 Self::U64 => Some(Ordering::Less),
                 Self::I64 => Some(Ordering::Less),
+                Self::U128 => None,
                 Self::Float => None,
                 Self::Double => None,
             },
@@ -337,6 +354,7 @@ impl PartialOrd for DataTypeName {
                 Self::U32 => Some(Ordering::Greater),
                 Self::U64 => Some(Ordering::Equal),
                 Self::I64 => None,
+                Self::U128 => None,
                 Self::Float => None,
                 Self::Double => None,
             },
@@ -345,9 +363,15 @@ impl PartialOrd for DataTypeName {
                 Self::U32 => Some(Ordering::Greater),
                 Self::I64 => Some(Ordering::Equal),
                 Self::U64 => None,
+                Self::U128 => None,
                 Self::Float => None,
                 Self::Double => None,
             },
+            Self::U128 => match other {
+                Self::String => None, // TODO: should be: Some(Ordering::Less); needs changes on trie level...
+                Self::U128 => Some(Ordering::Equal),
+                _ => None,
+            },
             Self::Float => match other {
                 Self::String => None, // TODO: should be: Some(Ordering::Less); needs changes on trie level...
                 Self::Float => Some(Ordering::Equal),
@@ -376,6 +400,7 @@ impl PartialUpperBound for DataTypeName {
             DataTypeName::U32 => DataTypeName::U64,
             DataTypeName::U64 => DataTypeName::U64,
             DataTypeName::I64 => DataTypeName::I64,
+            DataTypeName::U128 => DataTypeName::U128,
             DataTypeName::Float => DataTypeName::Float,
             DataTypeName::Double => DataTypeName::Double,
         }
@@ -411,6 +436,7 @@ macro_rules! generate_cast_statements {
                 StorageTypeName::U64 => $cast_macro!(I64, U64, i64, u64),
                 _ => panic!("Unsupported cast."),
             },
+            StorageTypeName::U128 => panic!("Unsupported cast."),
             StorageTypeName::Float => panic!("Unsupported cast."),
             StorageTypeName::Double => panic!("Unsupported cast."),
         }
@@ -1,6 +1,6 @@
 use super::{
     casting::{ImplicitCastFrom, ImplicitCastInto},
-    Field, FloorToUsize, RunLengthEncodable,
+    Field, FloorToUsize, NumericFunctions, RandomBits, RunLengthEncodable, StorageValueConversion,
 };
 use num::{Bounded, CheckedMul};
 use std::fmt::Debug;
@@ -22,6 +22,9 @@ pub trait ColumnDataType:
     + ImplicitCastInto<i64>
     + Bounded
     + RunLengthEncodable
+    + RandomBits
+    + NumericFunctions
+    + StorageValueConversion
 {
 }
 
@@ -41,5 +44,8 @@ impl<T> ColumnDataType for T where
         + ImplicitCastInto<i64>
         + Bounded
         + RunLengthEncodable
+        + RandomBits
+        + NumericFunctions
+        + StorageValueConversion
 {
 }
@@ -16,6 +16,8 @@ pub enum DataTypeName {
     U64,
     /// Data type [`i64`]
     I64,
+    /// Data type [`u128`]
+    U128,
     /// Data type [`super::float::Float`]
     Float,
     /// Data type [`super::double::Double`]
@@ -30,6 +32,7 @@ impl DataTypeName {
             Self::U32 => StorageTypeName::U32,
             Self::U64 => StorageTypeName::U64,
             Self::I64 => StorageTypeName::I64,
+            Self::U128 => StorageTypeName::U128,
             Self::Float => StorageTypeName::Float,
             Self::Double => StorageTypeName::Double,
         }
@@ -43,6 +46,7 @@ impl Display for DataTypeName {
             Self::U32 => write!(f, "U32"),
             Self::U64 => write!(f, "U64"),
             Self::I64 => write!(f, "I64"),
+            Self::U128 => write!(f, "U128"),
             Self::Float => write!(f, "Float"),
             Self::Double => write!(f, "Double"),
         }
@@ -1,4 +1,5 @@
-use crate::dictionary::Dictionary;
+use crate::dictionary::{index_to_storage, Dictionary};
+use crate::error::ReadingError;
 use crate::management::database::Dict;
 
 use super::double::Double;
@@ -48,6 +49,8 @@ pub enum DataValueT {
     U64(u64),
     /// Case i64
     I64(i64),
+    /// Case u128
+    U128(u128),
     /// Case Float
     Float(Float),
     /// Case Double
@@ -62,6 +65,7 @@ impl DataValueT {
             Self::U32(_) => DataTypeName::U32,
             Self::U64(_) => DataTypeName::U64,
             Self::I64(_) => DataTypeName::I64,
+            Self::U128(_) => DataTypeName::U128,
             Self::Float(_) => DataTypeName::Float,
             Self::Double(_) => DataTypeName::Double,
         }
@@ -69,18 +73,32 @@ impl DataValueT {
 
     /// Get the appropriate [`StorageValueT`]` for the given [`DataValueT`].
     /// May change the given dictionary.
-    pub fn to_storage_value_mut(&self, dict: &mut Dict) -> StorageValueT {
-        match self {
+    ///
+    /// Returns an error if the dictionary has grown too large to represent the value.
+    pub fn try_to_storage_value_mut(&self, dict: &mut Dict) -> Result<StorageValueT, ReadingError> {
+        Ok(match self {
             Self::String(val) => {
                 // dictionary indices
-                StorageValueT::U64(dict.add(val.clone().into()).try_into().unwrap())
+                StorageValueT::U64(index_to_storage(dict.add(val.clone().into()))?)
             }
             Self::U32(val) => StorageValueT::U32(*val),
             Self::U64(val) => StorageValueT::U64(*val),
             Self::I64(val) => StorageValueT::I64(*val),
+            Self::U128(val) => StorageValueT::U128(*val),
             Self::Float(val) => StorageValueT::Float(*val),
             Self::Double(val) => StorageValueT::Double(*val),
-        }
+        })
+    }
+
+    /// Get the appropriate [`StorageValueT`]` for the given [`DataValueT`].
+    /// May change the given dictionary.
+    ///
+    /// # Panics
+    /// Panics if the dictionary has grown too large to represent the value,
+    /// see [`DataValueT::try_to_storage_value_mut`].
+    pub fn to_storage_value_mut(&self, dict: &mut Dict) -> StorageValueT {
+        self.try_to_storage_value_mut(dict)
+            .unwrap_or_else(|error| panic!("{error}"))
     }
 
     /// Get the appropriate [`StorageValueT`]` for the given [`DataValueT`]
@@ -88,11 +106,12 @@ impl DataValueT {
         match self {
             Self::String(val) => Some(StorageValueT::U64(
                 // dictionary indices
-                dict.index_of(val.into())?.try_into().unwrap(),
+                index_to_storage(dict.index_of(val.into())?).ok()?,
             )),
             Self::U32(val) => Some(StorageValueT::U32(*val)),
             Self::U64(val) => Some(StorageValueT::U64(*val)),
             Self::I64(val) => Some(StorageValueT::I64(*val)),
+            Self::U128(val) => Some(StorageValueT::U128(*val)),
             Self::Float(val) => Some(StorageValueT::Float(*val)),
             Self::Double(val) => Some(StorageValueT::Double(*val)),
         }
@@ -106,6 +125,7 @@ impl std::fmt::Display for DataValueT {
             Self::U32(val) => write!(f, "{val}"),
             Self::U64(val) => write!(f, "{val}"),
             Self::I64(val) => write!(f, "{val}"),
+            Self::U128(val) => write!(f, "{val}"),
             Self::Float(val) => write!(f, "{val}"),
             Self::Double(val) => write!(f, "{val}"),
         }
@@ -123,6 +143,8 @@ pub enum DataValueIteratorT<'a> {
     U64(Box<dyn Iterator<Item = u64> + 'a>),
     /// I64 Variant
     I64(Box<dyn Iterator<Item = i64> + 'a>),
+    /// U128 Variant
+    U128(Box<dyn Iterator<Item = u128> + 'a>),
     /// Float Variant
     Float(Box<dyn Iterator<Item = Float> + 'a>),
     /// Double Variant
@@ -1,5 +1,5 @@
 use super::run_length_encodable::FloatingStep;
-use super::{FloatIsNaN, FloorToUsize, RunLengthEncodable};
+use super::{FloatIsNaN, FloorToUsize, NumericFunctions, RandomBits, RunLengthEncodable};
 use crate::error::Error;
 use num::{Bounded, CheckedMul, FromPrimitive, One, Zero};
 use std::cmp::Ordering;
@@ -198,6 +198,62 @@ impl FloorToUsize for Float {
     }
 }
 
+impl NumericFunctions for Float {
+    fn checked_absolute(self) -> Option<Self> {
+        Self::new(self.0.abs()).ok()
+    }
+
+    fn checked_round(self) -> Option<Self> {
+        Self::new(self.0.round()).ok()
+    }
+
+    fn checked_ceil(self) -> Option<Self> {
+        Self::new(self.0.ceil()).ok()
+    }
+
+    fn checked_floor(self) -> Option<Self> {
+        Self::new(self.0.floor()).ok()
+    }
+
+    fn checked_modulo(self, divisor: Self) -> Option<Self> {
+        if divisor.0 == 0.0 {
+            return None;
+        }
+
+        Self::new(self.0.rem_euclid(divisor.0)).ok()
+    }
+
+    fn checked_power(self, exponent: Self) -> Option<Self> {
+        Self::new(self.0.powf(exponent.0)).ok()
+    }
+
+    fn checked_logarithm(self) -> Option<Self> {
+        Self::new(self.0.ln()).ok()
+    }
+
+    fn checked_square_root(self) -> Option<Self> {
+        Self::new(self.0.sqrt()).ok()
+    }
+
+    fn checked_from_integer(value: i64) -> Option<Self> {
+        Self::new(value as f32).ok()
+    }
+
+    fn checked_from_float(value: f64) -> Option<Self> {
+        Self::new(f32::from_f64(value)?).ok()
+    }
+}
+
+impl RandomBits for Float {
+    fn to_hash_bits(self) -> u64 {
+        u64::from(self.0.to_bits())
+    }
+
+    fn from_random_bits(bits: u64) -> Self {
+        Self((bits >> 40) as f32 / (1u64 << 24) as f32)
+    }
+}
+
 impl Bounded for Float {
     fn min_value() -> Self {
         Self(f32::MIN)
@@ -10,6 +10,12 @@ impl FloorToUsize for usize {
     }
 }
 
+impl FloorToUsize for u128 {
+    fn floor_to_usize(self) -> Option<usize> {
+        self.try_into().ok()
+    }
+}
+
 impl FloorToUsize for u64 {
     fn floor_to_usize(self) -> Option<usize> {
         self.try_into().ok()
@@ -0,0 +1,150 @@
+use num::FromPrimitive;
+
+/// Trait representing datatypes that support further numeric functions besides the field operations
+///
+/// Each function returns `None` if its result is undefined,
+/// e.g. because of an overflow, a division by zero or a result that is not a number.
+pub trait NumericFunctions: Sized {
+    /// Return the absolute value.
+    fn checked_absolute(self) -> Option<Self>;
+
+    /// Round to the nearest integer, where halfway cases are rounded away from zero.
+    fn checked_round(self) -> Option<Self>;
+
+    /// Round up to the nearest integer.
+    fn checked_ceil(self) -> Option<Self>;
+
+    /// Round down to the nearest integer.
+    fn checked_floor(self) -> Option<Self>;
+
+    /// Return the non-negative remainder of the division by the given divisor.
+    fn checked_modulo(self, divisor: Self) -> Option<Self>;
+
+    /// Raise to the given power.
+    fn checked_power(self, exponent: Self) -> Option<Self>;
+
+    /// Return the natural logarithm, which is rounded down for integer types.
+    fn checked_logarithm(self) -> Option<Self>;
+
+    /// Return the square root, which is rounded down for integer types.
+    fn checked_square_root(self) -> Option<Self>;
+
+    /// Convert the given integer into this type.
+    fn checked_from_integer(value: i64) -> Option<Self>;
+
+    /// Convert the given floating point value into this type,
+    /// where integer types round towards zero.
+    fn checked_from_float(value: f64) -> Option<Self>;
+}
+
+macro_rules! impl_numeric_functions_integer {
+    ($type:ty, $absolute:expr, $square_root:expr) => {
+        impl NumericFunctions for $type {
+            fn checked_absolute(self) -> Option<Self> {
+                $absolute(self)
+            }
+
+            fn checked_round(self) -> Option<Self> {
+                Some(self)
+            }
+
+            fn checked_ceil(self) -> Option<Self> {
+                Some(self)
+            }
+
+            fn checked_floor(self) -> Option<Self> {
+                Some(self)
+            }
+
+            fn checked_modulo(self, divisor: Self) -> Option<Self> {
+                self.checked_rem_euclid(divisor)
+            }
+
+            fn checked_power(self, exponent: Self) -> Option<Self> {
+                self.checked_pow(u32::try_from(exponent).ok()?)
+            }
+
+            fn checked_logarithm(self) -> Option<Self> {
+                if self <= 0 {
+                    return None;
+                }
+
+                Self::from_f64((self as f64).ln().floor())
+            }
+
+            fn checked_square_root(self) -> Option<Self> {
+                $square_root(self)
+            }
+
+            fn checked_from_integer(value: i64) -> Option<Self> {
+                Self::from_i64(value)
+            }
+
+            fn checked_from_float(value: f64) -> Option<Self> {
+                Self::from_f64(value)
+            }
+        }
+    };
+}
+
+macro_rules! impl_numeric_functions_signed {
+    ($($type:ty),*) => {
+        $(
+            impl_numeric_functions_integer!($type, <$type>::checked_abs, <$type>::checked_isqrt);
+        )*
+    };
+}
+
+macro_rules! impl_numeric_functions_unsigned {
+    ($($type:ty),*) => {
+        $(
+            impl_numeric_functions_integer!($type, Some, |value: $type| Some(value.isqrt()));
+        )*
+    };
+}
+
+impl_numeric_functions_signed!(i8, i16, i32, i64);
+impl_numeric_functions_unsigned!(u8, u16, u32, u64, u128, usize);
+
+#[cfg(test)]
+mod test {
+    use super::NumericFunctions;
+    use crate::datatypes::Double;
+
+    #[test]
+    fn numeric_functions_integer() {
+        assert_eq!((-7i64).checked_absolute(), Some(7));
+        assert_eq!(i64::MIN.checked_absolute(), None);
+        assert_eq!((-7i64).checked_modulo(3), Some(2));
+        assert_eq!(7i64.checked_modulo(0), None);
+        assert_eq!(2i64.checked_power(10), Some(1024));
+        assert_eq!(2i64.checked_power(64), None);
+        assert_eq!(2i64.checked_power(-1), None);
+        assert_eq!(17i64.checked_square_root(), Some(4));
+        assert_eq!((-1i64).checked_square_root(), None);
+        assert_eq!(8i64.checked_logarithm(), Some(2));
+        assert_eq!(0i64.checked_logarithm(), None);
+        assert_eq!(i64::checked_from_float(-2.7), Some(-2));
+        assert_eq!(i64::checked_from_float(f64::NAN), None);
+        assert_eq!(i64::checked_from_float(1e20), None);
+        assert_eq!(u32::checked_from_integer(-1), None);
+    }
+
+    #[test]
+    fn numeric_functions_float() {
+        let double = |value: f64| Double::new(value).unwrap();
+
+        assert_eq!(double(-2.5).checked_round(), Some(double(-3.0)));
+        assert_eq!(double(-2.5).checked_ceil(), Some(double(-2.0)));
+        assert_eq!(double(-2.5).checked_floor(), Some(double(-3.0)));
+        assert_eq!(double(-2.5).checked_absolute(), Some(double(2.5)));
+        assert_eq!(double(-7.5).checked_modulo(double(2.0)), Some(double(0.5)));
+        assert_eq!(double(1.0).checked_modulo(double(0.0)), None);
+        assert_eq!(double(2.0).checked_power(double(-1.0)), Some(double(0.5)));
+        assert_eq!(double(-8.0).checked_power(double(0.5)), None);
+        assert_eq!(double(-1.0).checked_square_root(), None);
+        assert_eq!(double(-1.0).checked_logarithm(), None);
+        assert_eq!(Double::checked_from_integer(3), Some(double(3.0)));
+        assert_eq!(Double::checked_from_float(f64::NAN), None);
+    }
+}
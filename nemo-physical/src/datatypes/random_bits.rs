@@ -0,0 +1,146 @@
+/// Trait representing datatypes whose values can be hashed into
+/// and constructed from uniformly distributed random bits
+pub trait RandomBits {
+    /// Return bits that uniquely identify this value, to be used as input to a hash function
+    fn to_hash_bits(self) -> u64;
+
+    /// Construct a value from uniformly distributed random bits.
+    ///
+    /// Integer types result in non-negative values,
+    /// whereas floating point types result in values from the interval [0, 1).
+    fn from_random_bits(bits: u64) -> Self;
+}
+
+/// Mix the given bits, such that similar inputs result in very different outputs.
+///
+/// This is the finalizer of the SplitMix64 generator,
+/// which does not depend on the platform or on the state of the program.
+pub fn mix_bits(bits: u64) -> u64 {
+    let mut result = bits.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    result = (result ^ (result >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    result = (result ^ (result >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    result ^ (result >> 31)
+}
+
+impl RandomBits for u64 {
+    fn to_hash_bits(self) -> u64 {
+        self
+    }
+
+    fn from_random_bits(bits: u64) -> Self {
+        bits
+    }
+}
+
+impl RandomBits for u128 {
+    #[allow(clippy::cast_possible_truncation)]
+    fn to_hash_bits(self) -> u64 {
+        (self as u64) ^ ((self >> 64) as u64)
+    }
+
+    fn from_random_bits(bits: u64) -> Self {
+        Self::from(bits) << 64 | Self::from(mix_bits(bits))
+    }
+}
+
+impl RandomBits for usize {
+    fn to_hash_bits(self) -> u64 {
+        self as u64
+    }
+
+    fn from_random_bits(bits: u64) -> Self {
+        usize::try_from(bits >> (u64::BITS - usize::BITS)).expect("shifted value fits into usize")
+    }
+}
+
+impl RandomBits for u32 {
+    fn to_hash_bits(self) -> u64 {
+        self as u64
+    }
+
+    fn from_random_bits(bits: u64) -> Self {
+        (bits >> 32) as u32
+    }
+}
+
+impl RandomBits for u16 {
+    fn to_hash_bits(self) -> u64 {
+        self as u64
+    }
+
+    fn from_random_bits(bits: u64) -> Self {
+        (bits >> 48) as u16
+    }
+}
+
+impl RandomBits for u8 {
+    fn to_hash_bits(self) -> u64 {
+        self as u64
+    }
+
+    fn from_random_bits(bits: u64) -> Self {
+        (bits >> 56) as u8
+    }
+}
+
+impl RandomBits for i64 {
+    fn to_hash_bits(self) -> u64 {
+        self as u64
+    }
+
+    fn from_random_bits(bits: u64) -> Self {
+        i64::try_from(bits >> 1).expect("shifted value fits into i64")
+    }
+}
+
+impl RandomBits for i32 {
+    fn to_hash_bits(self) -> u64 {
+        self as u64
+    }
+
+    fn from_random_bits(bits: u64) -> Self {
+        (bits >> 33) as i32
+    }
+}
+
+impl RandomBits for i16 {
+    fn to_hash_bits(self) -> u64 {
+        self as u64
+    }
+
+    fn from_random_bits(bits: u64) -> Self {
+        (bits >> 49) as i16
+    }
+}
+
+impl RandomBits for i8 {
+    fn to_hash_bits(self) -> u64 {
+        self as u64
+    }
+
+    fn from_random_bits(bits: u64) -> Self {
+        (bits >> 57) as i8
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{mix_bits, RandomBits};
+    use crate::datatypes::{Double, Float};
+
+    #[test]
+    fn random_bits_ranges() {
+        for input in [0u64, 1, 42, u64::MAX] {
+            let bits = mix_bits(input);
+
+            assert!(i64::from_random_bits(bits) >= 0);
+            assert!(i8::from_random_bits(bits) >= 0);
+            assert!((0.0..1.0).contains(&f64::from(Double::from_random_bits(bits))));
+            assert!((0.0..1.0).contains(&f32::from(Float::from_random_bits(bits))));
+        }
+
+        assert_eq!(i64::from_random_bits(u64::MAX), i64::MAX);
+        assert_eq!(u8::from_random_bits(u64::MAX), u8::MAX);
+        assert_ne!(mix_bits(1), mix_bits(2));
+    }
+}
@@ -109,6 +109,32 @@ impl RunLengthEncodable for u64 {
     }
 }
 
+impl RunLengthEncodable for u128 {
+    type Step = IntStep;
+
+    fn diff_step(prev: Self, curr: Self) -> Option<Self::Step> {
+        let step = if curr >= prev {
+            i16::try_from(curr - prev).ok()?
+        } else {
+            -i16::try_from(prev - curr).ok()?
+        };
+
+        Some(IntStep(step))
+    }
+
+    fn zero_step() -> Self::Step {
+        IntStep(0)
+    }
+
+    fn get_step_increment(step: Self::Step) -> Option<Self> {
+        Self::try_from(step.0).ok()
+    }
+
+    fn offset(self, inc: Self::Step, times: usize) -> Self {
+        Self::Step::offset_unsigned(self, inc, times)
+    }
+}
+
 impl RunLengthEncodable for usize {
     type Step = IntStep;
 
@@ -9,6 +9,8 @@ pub enum StorageTypeName {
     U64,
     /// Data type [`i64`]
     I64,
+    /// Data type [`u128`]
+    U128,
     /// Data type [`super::float::Float`]
     Float,
     /// Data type [`super::double::Double`]
@@ -21,6 +23,7 @@ impl Display for StorageTypeName {
             StorageTypeName::U32 => write!(f, "U32"),
             StorageTypeName::U64 => write!(f, "U64"),
             StorageTypeName::I64 => write!(f, "I64"),
+            StorageTypeName::U128 => write!(f, "U128"),
             StorageTypeName::Float => write!(f, "Float"),
             StorageTypeName::Double => write!(f, "Double"),
         }
@@ -18,6 +18,8 @@ pub enum StorageValueT {
     U64(u64),
     /// Case i64
     I64(i64),
+    /// Case u128
+    U128(u128),
     /// Case Float
     Float(Float),
     /// Case Double
@@ -31,6 +33,7 @@ impl StorageValueT {
             StorageValueT::U32(val) => (*other).try_into().map(|otherval| val.cmp(&otherval)).ok(),
             StorageValueT::U64(val) => (*other).try_into().map(|otherval| val.cmp(&otherval)).ok(),
             StorageValueT::I64(val) => (*other).try_into().map(|otherval| val.cmp(&otherval)).ok(),
+            StorageValueT::U128(val) => (*other).try_into().map(|otherval| val.cmp(&otherval)).ok(),
             StorageValueT::Float(val) => {
                 (*other).try_into().map(|otherval| val.cmp(&otherval)).ok()
             }
@@ -46,6 +49,7 @@ impl StorageValueT {
             Self::U32(_) => StorageTypeName::U32,
             Self::U64(_) => StorageTypeName::U64,
             Self::I64(_) => StorageTypeName::I64,
+            Self::U128(_) => StorageTypeName::U128,
             Self::Float(_) => StorageTypeName::Float,
             Self::Double(_) => StorageTypeName::Double,
         }
@@ -70,15 +74,67 @@ macro_rules! storage_value_try_into {
 storage_value_try_into!(U32 => u32);
 storage_value_try_into!(U64 => u64);
 storage_value_try_into!(I64 => i64);
+storage_value_try_into!(U128 => u128);
 storage_value_try_into!(Float => Float);
 storage_value_try_into!(Double => Double);
 
+/// Trait representing datatypes whose values can be exchanged as a [`StorageValueT`]
+pub trait StorageValueConversion: Sized {
+    /// Return the [`StorageValueT`] representing this value, if there is one.
+    fn to_storage_value_t(self) -> Option<StorageValueT>;
+
+    /// Return the value represented by the given [`StorageValueT`],
+    /// if it can be represented by this type.
+    fn from_storage_value_t(value: StorageValueT) -> Option<Self>;
+}
+
+macro_rules! storage_value_conversion {
+    ($variant:ident => $type:ty) => {
+        impl StorageValueConversion for $type {
+            fn to_storage_value_t(self) -> Option<StorageValueT> {
+                Some(StorageValueT::$variant(self))
+            }
+
+            fn from_storage_value_t(value: StorageValueT) -> Option<Self> {
+                value.try_into().ok()
+            }
+        }
+    };
+    ($variant:ident($repr:ty) => $type:ty) => {
+        impl StorageValueConversion for $type {
+            fn to_storage_value_t(self) -> Option<StorageValueT> {
+                <$repr>::try_from(self)
+                    .ok()
+                    .map(StorageValueT::$variant)
+            }
+
+            fn from_storage_value_t(value: StorageValueT) -> Option<Self> {
+                <$repr>::try_from(value)
+                    .ok()
+                    .and_then(|value| Self::try_from(value).ok())
+            }
+        }
+    };
+}
+
+storage_value_conversion!(U32 => u32);
+storage_value_conversion!(U64 => u64);
+storage_value_conversion!(I64 => i64);
+storage_value_conversion!(U128 => u128);
+storage_value_conversion!(Float => Float);
+storage_value_conversion!(Double => Double);
+storage_value_conversion!(U64(u64) => usize);
+storage_value_conversion!(U32(u32) => u8);
+storage_value_conversion!(I64(i64) => i32);
+storage_value_conversion!(I64(i64) => i8);
+
 impl std::fmt::Display for StorageValueT {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Self::U32(val) => write!(f, "{val}"),
             Self::U64(val) => write!(f, "{val}"),
             Self::I64(val) => write!(f, "{val}"),
+            Self::U128(val) => write!(f, "{val}"),
             Self::Float(val) => write!(f, "{val}"),
             Self::Double(val) => write!(f, "{val}"),
         }
@@ -94,6 +150,8 @@ pub enum VecT {
     U64(Vec<u64>),
     /// Case `Vec<i64>`
     I64(Vec<i64>),
+    /// Case `Vec<u128>`
+    U128(Vec<u128>),
     /// Case `Vec<Float>`
     Float(Vec<Float>),
     /// Case `Vec<Double>`
@@ -115,6 +173,7 @@ impl VecT {
             StorageTypeName::U32 => Self::U32(Vec::new()),
             StorageTypeName::U64 => Self::U64(Vec::new()),
             StorageTypeName::I64 => Self::I64(Vec::new()),
+            StorageTypeName::U128 => Self::U128(Vec::new()),
             StorageTypeName::Float => Self::Float(Vec::new()),
             StorageTypeName::Double => Self::Double(Vec::new()),
         }
@@ -126,6 +185,7 @@ impl VecT {
             Self::U32(_) => StorageTypeName::U32,
             Self::U64(_) => StorageTypeName::U64,
             Self::I64(_) => StorageTypeName::I64,
+            Self::U128(_) => StorageTypeName::U128,
             Self::Float(_) => StorageTypeName::Float,
             Self::Double(_) => StorageTypeName::Double,
         }
@@ -142,6 +202,7 @@ impl VecT {
             VecT::U32(vec) => vec.get(index).copied().map(StorageValueT::U32),
             VecT::U64(vec) => vec.get(index).copied().map(StorageValueT::U64),
             VecT::I64(vec) => vec.get(index).copied().map(StorageValueT::I64),
+            VecT::U128(vec) => vec.get(index).copied().map(StorageValueT::U128),
             VecT::Float(vec) => vec.get(index).copied().map(StorageValueT::Float),
             VecT::Double(vec) => vec.get(index).copied().map(StorageValueT::Double),
         }
@@ -166,6 +227,9 @@ impl VecT {
                     "expecting VecT::I64 and StorageValueT::I64, but StorageValueT does not match",
                 ))
             }
+            VecT::U128(vec) => vec.push(value.try_into().expect(
+                "expecting VecT::U128 and StorageValueT::U128, but StorageValueT does not match",
+            )),
             VecT::Float(vec) => vec.push(value.try_into().expect(
                 "expecting VecT::Float and StorageValueT::Float, but StorageValueT does not match",
             )),
@@ -197,6 +261,9 @@ impl VecT {
             VecT::I64(vec) => vec
                 .get(idx_a)
                 .and_then(|&val_a| vec.get(idx_b).map(|val_b| val_a.cmp(val_b))),
+            VecT::U128(vec) => vec
+                .get(idx_a)
+                .and_then(|&val_a| vec.get(idx_b).map(|val_b| val_a.cmp(val_b))),
             VecT::Float(vec) => vec
                 .get(idx_a)
                 .and_then(|&val_a| vec.get(idx_b).map(|val_b| val_a.cmp(val_b))),
@@ -216,6 +283,8 @@ pub enum StorageValueIteratorT<'a> {
     U64(Box<dyn Iterator<Item = u64> + 'a>),
     /// I64 Variant
     I64(Box<dyn Iterator<Item = i64> + 'a>),
+    /// U128 Variant
+    U128(Box<dyn Iterator<Item = u128> + 'a>),
     /// Float Variant
     Float(Box<dyn Iterator<Item = Float> + 'a>),
     /// Double Variant
@@ -3,6 +3,8 @@
 
 use std::fmt::Debug;
 
+use crate::error::ReadingError;
+
 /// Module to define a [PrefixedStringDictionary]
 /// This will provide a more memory-efficient storage of [String] values if they share equivalent prefixes (such as IRIs)
 /// The prefixes of the [String] will be stored as a Triestructure.
@@ -11,10 +13,54 @@ pub use prefixed_string_dictionary::PrefixedStringDictionary;
 /// Module to define a simple [StringDictionary]
 pub mod string_dictionary;
 pub use string_dictionary::StringDictionary;
+/// Module to define a [DictionaryOrder]
+/// This provides an index that orders the entries of a dictionary by their strings
+pub mod dictionary_order;
+pub use dictionary_order::DictionaryOrder;
+/// Module to define a [DomainDictionary]
+/// This stores the entries of each datatype domain in a separate dictionary
+pub mod domain_dictionary;
+pub use domain_dictionary::DomainDictionary;
+/// Module to define a [LayeredDictionary]
+/// This extends a frozen dictionary that can be shared between threads without copying it
+pub mod layered_dictionary;
+pub use layered_dictionary::LayeredDictionary;
+/// Module to define a [TextIndex]
+/// This provides an inverted index from the words of the entries of a dictionary to their indices
+pub mod text_index;
+pub use text_index::{TextIndex, TextQuery};
+/// Module to define a [LengthIndex]
+/// This provides an index of the entries of a dictionary by the length of their strings to find similar entries
+pub mod edit_distance;
+pub use edit_distance::{edit_distance_at_most, LengthIndex};
+/// Module to read and write the entries of a dictionary from and to files
+pub mod dictionary_file;
 /// Module mapping physical types into logical types into Strings
 pub mod value_serializer;
 pub use value_serializer::ValueSerializer;
 
+/// Smallest value of a column of type [`u64`] that represents a null.
+///
+/// Indices of dictionary entries are represented by the values below.
+pub const FIRST_NULL: u64 = 1 << 63;
+
+/// Return the value of a column of type [`u64`] that represents the dictionary entry with the given index.
+///
+/// Returns an error if the index cannot be distinguished from the values representing nulls.
+///
+/// Dictionary indices are not moved to wider columns (e.g. of type [`u128`]) when this happens.
+/// An index that does not fit would require a dictionary with 2^63 entries,
+/// or, for a [`DomainDictionary`], 2^61 entries of one domain,
+/// which cannot be held in memory.
+/// Supporting dictionary indices in other column types would, however, affect all operations on dictionary values,
+/// so such indices are reported as an error instead.
+pub fn index_to_storage(index: usize) -> Result<u64, ReadingError> {
+    u64::try_from(index)
+        .ok()
+        .filter(|&value| value < FIRST_NULL)
+        .ok_or(ReadingError::DictionaryOverflow(index))
+}
+
 /// This Dictionary Trait defines dictionaries, which keep ownership of the inserted elements.
 pub trait Dictionary: Debug + Default + Clone {
     /// Construct a new and empty [`Dictionary`]
@@ -36,4 +82,93 @@ pub trait Dictionary: Debug + Default + Clone {
     fn len(&self) -> usize;
     /// Returns whether the dictionary is empty.
     fn is_empty(&self) -> bool;
+    /// Returns the indices of all entries in the dictionary in increasing order.
+    /// Note that these need not be consecutive.
+    fn indices(&self) -> Box<dyn Iterator<Item = usize> + '_> {
+        Box::new(0..self.len())
+    }
+    /// Returns whether the dictionary contains an entry with the given `index`,
+    /// without constructing the entry.
+    fn contains_index(&self, index: usize) -> bool {
+        index < self.len()
+    }
+    /// Returns a dictionary that associates the result of applying `function` to each entry with the index of that entry,
+    /// so that tables using this dictionary represent the mapped values without being changed.
+    /// Entries contained in every new dictionary are kept.
+    /// Note that `function` has to map different entries to different strings.
+    fn map_entries<F>(&self, mut function: F) -> Self
+    where
+        Self: Sized,
+        F: FnMut(&str) -> String,
+    {
+        let mut result = Self::new();
+
+        for index in self.indices() {
+            if result.entry(index).is_some() {
+                continue;
+            }
+
+            let entry = self
+                .entry(index)
+                .expect("The dictionary contains an entry for each of its indices.");
+            let mapped_index = result.add(function(&entry));
+
+            debug_assert_eq!(
+                mapped_index, index,
+                "The entries of a dictionary have to be mapped to different strings."
+            );
+        }
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::error::ReadingError;
+
+    use super::{
+        index_to_storage, Dictionary, DomainDictionary, PrefixedStringDictionary, StringDictionary,
+        FIRST_NULL,
+    };
+
+    #[test]
+    fn index_overflow() {
+        assert_eq!(index_to_storage(42).unwrap(), 42);
+
+        let last_index = usize::try_from(FIRST_NULL - 1).unwrap();
+        assert_eq!(index_to_storage(last_index).unwrap(), FIRST_NULL - 1);
+        assert!(matches!(
+            index_to_storage(last_index + 1),
+            Err(ReadingError::DictionaryOverflow(index)) if index == last_index + 1
+        ));
+    }
+
+    fn mapped_entries<Dict: Dictionary>() {
+        let mut dict = Dict::new();
+        for entry in ["http://example.org/a", "http://example.org/b", "c"] {
+            dict.add(entry.to_string());
+        }
+
+        let mapped = dict.map_entries(|entry| entry.replace("example.org", "example.com"));
+
+        assert_eq!(mapped.len(), dict.len());
+        assert_eq!(
+            mapped.index_of("http://example.com/a"),
+            dict.index_of("http://example.org/a")
+        );
+        assert_eq!(mapped.index_of("http://example.org/a"), None);
+        assert_eq!(
+            mapped.entry(dict.index_of("http://example.org/b").unwrap()),
+            Some("http://example.com/b".to_string())
+        );
+        assert_eq!(mapped.index_of("c"), dict.index_of("c"));
+    }
+
+    #[test]
+    fn map_entries() {
+        mapped_entries::<StringDictionary>();
+        mapped_entries::<PrefixedStringDictionary>();
+        mapped_entries::<DomainDictionary>();
+    }
 }
@@ -0,0 +1,237 @@
+//! Files storing the entries of a dictionary together with their indices.
+//!
+//! A dictionary file lists all entries of a [`Dictionary`] in the order of their indices.
+//! Reading it into an empty dictionary thus reproduces the indices of the dictionary it was written from,
+//! so that several related runs can assign the same indices to the same strings.
+//! Since indices need not be consecutive, each entry starts with the gap to the index of its predecessor.
+//! Since entries often share prefixes with their predecessor (e.g. IRIs of the same namespace),
+//! each entry is then stored as the length of this shared prefix followed by the remaining bytes.
+
+use std::{
+    fs::File,
+    io::{BufReader, BufWriter, Error, ErrorKind, Read, Result, Write},
+    path::Path,
+};
+
+use super::Dictionary;
+
+/// Bytes at the beginning of every dictionary file.
+const MAGIC: &[u8] = b"NMOD";
+
+/// Version of the format of dictionary files.
+const FORMAT_VERSION: u32 = 2;
+
+fn invalid_data(message: &str) -> Error {
+    Error::new(ErrorKind::InvalidData, message)
+}
+
+/// Write `value` using as many bytes as needed for its 7-bit groups.
+fn write_varint(writer: &mut impl Write, mut value: u64) -> Result<()> {
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 {
+            return writer.write_all(&[byte]);
+        }
+        writer.write_all(&[byte | 0x80])?;
+    }
+}
+
+fn read_varint(reader: &mut impl Read) -> Result<u64> {
+    let mut value = 0u64;
+    for shift in (0..64).step_by(7) {
+        let mut byte = [0];
+        reader.read_exact(&mut byte)?;
+        value |= u64::from(byte[0] & 0x7f) << shift;
+        if byte[0] & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+
+    Err(invalid_data("varint exceeds 64 bits"))
+}
+
+fn read_length(reader: &mut impl Read) -> Result<usize> {
+    usize::try_from(read_varint(reader)?).map_err(|_| invalid_data("length exceeds usize"))
+}
+
+/// Return the number of bytes at the start of `entry` that it shares with `previous`,
+/// not splitting any character.
+fn shared_prefix_length(previous: &str, entry: &str) -> usize {
+    previous
+        .char_indices()
+        .zip(entry.chars())
+        .find(|((_, left), right)| left != right)
+        .map_or(previous.len().min(entry.len()), |((index, _), _)| index)
+}
+
+/// Write all entries of the given dictionary into a dictionary file at the given path.
+///
+/// The file is written under a temporary name first,
+/// such that concurrent runs never read an incomplete dictionary file.
+pub fn write_dictionary<D: Dictionary>(path: &Path, dict: &D) -> Result<()> {
+    let temporary_path = path.with_extension(format!("tmp{}", std::process::id()));
+    let mut writer = BufWriter::new(File::create(&temporary_path)?);
+
+    writer.write_all(MAGIC)?;
+    writer.write_all(&FORMAT_VERSION.to_le_bytes())?;
+    write_varint(&mut writer, dict.len() as u64)?;
+
+    let mut previous = String::new();
+    let mut next_index = 0;
+    for index in dict.indices() {
+        let entry = dict
+            .entry(index)
+            .expect("The dictionary contains an entry for each of its indices.");
+        let shared = shared_prefix_length(&previous, &entry);
+
+        write_varint(&mut writer, (index - next_index) as u64)?;
+        write_varint(&mut writer, shared as u64)?;
+        write_varint(&mut writer, (entry.len() - shared) as u64)?;
+        writer.write_all(&entry.as_bytes()[shared..])?;
+
+        previous = entry;
+        next_index = index + 1;
+    }
+
+    writer.flush()?;
+    drop(writer);
+
+    std::fs::rename(&temporary_path, path).inspect_err(|_| {
+        let _ = std::fs::remove_file(&temporary_path);
+    })
+}
+
+/// Add the entries stored in the dictionary file at the given path to the given dictionary,
+/// and return their number.
+///
+/// Returns an error of kind [`ErrorKind::InvalidData`] if the file is not a dictionary file
+/// or if the dictionary assigns some entry a different index than the one stored in the file,
+/// e.g. because it already contained other entries.
+pub fn read_dictionary<D: Dictionary>(path: &Path, dict: &mut D) -> Result<usize> {
+    let mut reader = BufReader::new(File::open(path)?);
+
+    let mut magic = [0; 4];
+    reader.read_exact(&mut magic)?;
+    let mut version = [0; 4];
+    reader.read_exact(&mut version)?;
+    if magic != MAGIC || u32::from_le_bytes(version) != FORMAT_VERSION {
+        return Err(invalid_data("not a dictionary file of this version"));
+    }
+
+    let entry_count = read_length(&mut reader)?;
+    let mut previous = Vec::new();
+    let mut next_index = 0usize;
+    for _ in 0..entry_count {
+        let index = next_index
+            .checked_add(read_length(&mut reader)?)
+            .ok_or_else(|| invalid_data("index exceeds usize"))?;
+        let shared = read_length(&mut reader)?;
+        let length = read_varint(&mut reader)?;
+        if shared > previous.len() {
+            return Err(invalid_data("invalid shared prefix"));
+        }
+
+        let mut bytes = previous[..shared].to_vec();
+        if (&mut reader).take(length).read_to_end(&mut bytes)? as u64 != length {
+            return Err(ErrorKind::UnexpectedEof.into());
+        }
+        let entry = String::from_utf8(bytes).map_err(|_| invalid_data("invalid entry"))?;
+        previous.clear();
+        previous.extend_from_slice(entry.as_bytes());
+
+        if dict.add(entry) != index {
+            return Err(invalid_data(
+                "the dictionary assigns different indices than the dictionary file",
+            ));
+        }
+        next_index = index + 1;
+    }
+
+    Ok(entry_count)
+}
+
+#[cfg(test)]
+mod test {
+    use std::io::ErrorKind;
+
+    use crate::dictionary::{
+        Dictionary, DomainDictionary, PrefixedStringDictionary, StringDictionary,
+    };
+
+    use super::{read_dictionary, shared_prefix_length, write_dictionary};
+
+    #[test]
+    fn shared_prefixes() {
+        assert_eq!(shared_prefix_length("", "abc"), 0);
+        assert_eq!(shared_prefix_length("abc", "abd"), 2);
+        assert_eq!(shared_prefix_length("abc", "ab"), 2);
+        assert_eq!(shared_prefix_length("ab", "abc"), 2);
+        assert_eq!(shared_prefix_length("aä", "aö"), 1);
+    }
+
+    #[test]
+    fn read_written_dictionary() {
+        let path =
+            std::env::temp_dir().join(format!("nemo-dictionary-file-{}", std::process::id()));
+
+        let mut dict = PrefixedStringDictionary::default();
+        for entry in [
+            "http://example.org/b",
+            "http://example.org/a",
+            "ä",
+            "äö",
+            "",
+            "c",
+        ] {
+            dict.add(entry.to_string());
+        }
+        write_dictionary(&path, &dict).unwrap();
+
+        let mut read = PrefixedStringDictionary::default();
+        assert_eq!(read_dictionary(&path, &mut read).unwrap(), dict.len());
+        assert_eq!(read.len(), dict.len());
+        for index in dict.indices() {
+            assert_eq!(read.entry(index), dict.entry(index));
+        }
+
+        // Reading twice keeps the indices
+        assert!(read_dictionary(&path, &mut read).is_ok());
+        assert_eq!(read.len(), dict.len());
+
+        let mut other = StringDictionary::default();
+        other.add("x".to_string());
+        assert_eq!(
+            read_dictionary(&path, &mut other).unwrap_err().kind(),
+            ErrorKind::InvalidData
+        );
+
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(
+            read_dictionary(&path, &mut read).unwrap_err().kind(),
+            ErrorKind::NotFound
+        );
+    }
+
+    #[test]
+    fn read_written_domain_dictionary() {
+        let path = std::env::temp_dir().join(format!(
+            "nemo-domain-dictionary-file-{}",
+            std::process::id()
+        ));
+
+        let mut dict = DomainDictionary::<StringDictionary>::default();
+        for entry in ["CONSTANT:a", "STRING:b", "INTEGER:1", "CONSTANT:c"] {
+            dict.add(entry.to_string());
+        }
+        write_dictionary(&path, &dict).unwrap();
+
+        let mut read = DomainDictionary::<StringDictionary>::default();
+        assert_eq!(read_dictionary(&path, &mut read).unwrap(), dict.len());
+        for index in dict.indices() {
+            assert_eq!(read.entry(index), dict.entry(index));
+        }
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
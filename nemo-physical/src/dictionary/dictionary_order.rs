@@ -0,0 +1,244 @@
+//! Auxiliary index that orders the entries of a dictionary by their strings.
+
+use std::{
+    cmp::Ordering,
+    collections::HashMap,
+    mem::size_of,
+    ops::{Bound, Range},
+};
+
+use bytesize::ByteSize;
+
+use crate::management::ByteSized;
+
+use super::Dictionary;
+
+/// Sorted index over the entries of a [`Dictionary`].
+///
+/// Dictionaries assign indices in the order in which strings are added,
+/// so comparing indices does not compare the strings they represent.
+/// A [`DictionaryOrder`] assigns each index the rank of its string among all entries,
+/// such that comparisons and range restrictions can be evaluated on encoded values
+/// without retrieving the strings from the dictionary.
+///
+/// Since dictionaries only grow, the index can be brought up to date with [`DictionaryOrder::update`],
+/// which only has to sort the entries added since the last update.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DictionaryOrder {
+    /// Indices of the entries sorted by their strings
+    sorted: Vec<usize>,
+    /// Position of each index in `sorted`
+    ranks: HashMap<usize, usize>,
+}
+
+impl DictionaryOrder {
+    /// Create a [`DictionaryOrder`] for all entries of the given dictionary.
+    pub fn new<D: Dictionary>(dict: &D) -> Self {
+        let mut result = Self::default();
+        result.update(dict);
+        result
+    }
+
+    /// Add the entries that have been added to the dictionary since the last update.
+    pub fn update<D: Dictionary>(&mut self, dict: &D) {
+        if self.ranks.len() >= dict.len() {
+            return;
+        }
+
+        let entry = |index: usize| {
+            dict.entry(index)
+                .expect("The dictionary contains an entry for each of its indices.")
+        };
+
+        let mut added = dict
+            .indices()
+            .filter(|index| !self.ranks.contains_key(index))
+            .map(|index| (entry(index), index))
+            .collect::<Vec<_>>();
+        added.sort_unstable();
+
+        let mut sorted = Vec::with_capacity(dict.len());
+        let mut existing = self
+            .sorted
+            .iter()
+            .map(|&index| (entry(index), index))
+            .peekable();
+        for (added_entry, added_index) in added {
+            while let Some((_, index)) = existing.next_if(|(entry, _)| *entry < added_entry) {
+                sorted.push(index);
+            }
+            sorted.push(added_index);
+        }
+        sorted.extend(existing.map(|(_, index)| index));
+
+        self.ranks = sorted
+            .iter()
+            .enumerate()
+            .map(|(rank, &index)| (index, rank))
+            .collect();
+        self.sorted = sorted;
+    }
+
+    /// Return the number of dictionary entries covered by this index.
+    pub fn len(&self) -> usize {
+        self.ranks.len()
+    }
+
+    /// Return whether this index does not cover any entries.
+    pub fn is_empty(&self) -> bool {
+        self.ranks.is_empty()
+    }
+
+    /// Return the position of the entry with the given index in the sorted order of all entries,
+    /// or `None` if the index is not covered by this [`DictionaryOrder`].
+    pub fn rank(&self, index: usize) -> Option<usize> {
+        self.ranks.get(&index).copied()
+    }
+
+    /// Return the index of the entry at the given position in the sorted order of all entries.
+    pub fn index_at(&self, rank: usize) -> Option<usize> {
+        self.sorted.get(rank).copied()
+    }
+
+    /// Compare the strings of the entries with the given indices,
+    /// or return `None` if one of the indices is not covered by this [`DictionaryOrder`].
+    pub fn compare(&self, left: usize, right: usize) -> Option<Ordering> {
+        Some(self.rank(left)?.cmp(&self.rank(right)?))
+    }
+
+    /// Return the ranks of the entries whose strings lie within the given bounds.
+    ///
+    /// An index is part of the range iff its [rank][DictionaryOrder::rank] is contained in the result.
+    pub fn rank_range<D: Dictionary>(
+        &self,
+        dict: &D,
+        lower: Bound<&str>,
+        upper: Bound<&str>,
+    ) -> Range<usize> {
+        // Number of entries whose strings are smaller than (or, if `inclusive`, at most) `value`
+        let count_below = |value: &str, inclusive: bool| {
+            self.sorted.partition_point(|&index| {
+                let entry = dict
+                    .entry(index)
+                    .expect("The index only contains entries of the dictionary.");
+                if inclusive {
+                    entry.as_str() <= value
+                } else {
+                    entry.as_str() < value
+                }
+            })
+        };
+
+        let start = match lower {
+            Bound::Included(value) => count_below(value, false),
+            Bound::Excluded(value) => count_below(value, true),
+            Bound::Unbounded => 0,
+        };
+        let end = match upper {
+            Bound::Included(value) => count_below(value, true),
+            Bound::Excluded(value) => count_below(value, false),
+            Bound::Unbounded => self.sorted.len(),
+        };
+
+        start..end.max(start)
+    }
+
+    /// Return the indices of the entries whose strings lie within the given bounds,
+    /// in the order of their strings.
+    pub fn indices_in_range<D: Dictionary>(
+        &self,
+        dict: &D,
+        lower: Bound<&str>,
+        upper: Bound<&str>,
+    ) -> &[usize] {
+        &self.sorted[self.rank_range(dict, lower, upper)]
+    }
+}
+
+impl ByteSized for DictionaryOrder {
+    fn size_bytes(&self) -> ByteSize {
+        ByteSize::b(
+            (size_of::<Self>()
+                + self.sorted.capacity() * size_of::<usize>()
+                + self.ranks.capacity() * (size_of::<(usize, usize)>() + 1)) as u64,
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::ops::Bound;
+
+    use crate::dictionary::{Dictionary, StringDictionary};
+
+    use super::DictionaryOrder;
+
+    fn create_dict(entries: &[&str]) -> StringDictionary {
+        let mut dict = StringDictionary::default();
+        for entry in entries {
+            dict.add(entry.to_string());
+        }
+        dict
+    }
+
+    #[test]
+    fn ranks_follow_strings() {
+        let dict = create_dict(&["c", "a", "http://b", "b"]);
+        let order = DictionaryOrder::new(&dict);
+
+        assert_eq!(order.len(), 4);
+        assert_eq!(order.rank(0), Some(2));
+        assert_eq!(order.rank(1), Some(0));
+        assert_eq!(order.rank(2), Some(3));
+        assert_eq!(order.rank(3), Some(1));
+        assert_eq!(order.rank(4), None);
+        assert_eq!(order.index_at(0), Some(1));
+        assert!(order.compare(1, 0).unwrap().is_lt());
+    }
+
+    #[test]
+    fn update_merges_new_entries() {
+        let mut dict = create_dict(&["d", "b"]);
+        let mut order = DictionaryOrder::new(&dict);
+
+        for entry in ["e", "a", "c"] {
+            dict.add(entry.to_string());
+        }
+        order.update(&dict);
+
+        assert_eq!(order, DictionaryOrder::new(&dict));
+        assert_eq!(
+            (0..order.len())
+                .map(|rank| dict.entry(order.index_at(rank).unwrap()).unwrap())
+                .collect::<Vec<_>>(),
+            vec!["a", "b", "c", "d", "e"]
+        );
+    }
+
+    #[test]
+    fn ranges() {
+        let dict = create_dict(&["d", "b", "e", "a", "c"]);
+        let order = DictionaryOrder::new(&dict);
+
+        assert_eq!(
+            order.rank_range(&dict, Bound::Included("b"), Bound::Excluded("d")),
+            1..3
+        );
+        assert_eq!(
+            order.rank_range(&dict, Bound::Excluded("b"), Bound::Included("d")),
+            2..4
+        );
+        assert_eq!(
+            order.rank_range(&dict, Bound::Included("bb"), Bound::Unbounded),
+            2..5
+        );
+        assert_eq!(
+            order.rank_range(&dict, Bound::Included("x"), Bound::Included("a")),
+            5..5
+        );
+        assert_eq!(
+            order.indices_in_range(&dict, Bound::Unbounded, Bound::Included("b")),
+            &[3, 1]
+        );
+    }
+}
@@ -0,0 +1,296 @@
+//! Dictionary that stores the entries of each datatype domain in a separate dictionary.
+
+use bytesize::ByteSize;
+
+use crate::management::ByteSized;
+
+use super::{Dictionary, LayeredDictionary};
+
+/// Prefix of dictionary entries that represent IRIs and other abstract constants
+pub const IRI_PREFIX: &str = "CONSTANT:";
+/// Prefix of dictionary entries that represent plain strings
+pub const STRING_PREFIX: &str = "STRING:";
+/// Prefix of dictionary entries that represent language tagged strings
+pub const LANGUAGE_STRING_PREFIX: &str = "LANGUAGE_STRING:";
+
+/// Number of bits by which the domain of an entry is shifted within its index.
+///
+/// The two bits below the most significant bit encode the domain,
+/// such that indices stay below [`FIRST_NULL`][super::FIRST_NULL] on 64-bit platforms.
+const DOMAIN_SHIFT: u32 = usize::BITS - 3;
+/// Mask selecting the position of an entry within the dictionary of its domain
+const LOCAL_MASK: usize = (1 << DOMAIN_SHIFT) - 1;
+
+/// Datatype domain of a dictionary entry
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum DictionaryDomain {
+    /// Entries that do not belong to any of the other domains
+    Other = 0,
+    /// IRIs and other abstract constants
+    Iri = 1,
+    /// Plain strings
+    String = 2,
+    /// Language tagged strings
+    LanguageString = 3,
+}
+
+impl DictionaryDomain {
+    /// All domains, in the order of their tags
+    pub const ALL: [DictionaryDomain; 4] = [
+        DictionaryDomain::Other,
+        DictionaryDomain::Iri,
+        DictionaryDomain::String,
+        DictionaryDomain::LanguageString,
+    ];
+
+    /// Return the prefix shared by all entries of this domain.
+    pub fn prefix(self) -> &'static str {
+        match self {
+            DictionaryDomain::Other => "",
+            DictionaryDomain::Iri => IRI_PREFIX,
+            DictionaryDomain::String => STRING_PREFIX,
+            DictionaryDomain::LanguageString => LANGUAGE_STRING_PREFIX,
+        }
+    }
+
+    /// Return the domain of the given entry together with the part of the entry following the domain's prefix.
+    pub fn split(entry: &str) -> (Self, &str) {
+        for domain in [
+            DictionaryDomain::Iri,
+            DictionaryDomain::String,
+            DictionaryDomain::LanguageString,
+        ] {
+            if let Some(rest) = entry.strip_prefix(domain.prefix()) {
+                return (domain, rest);
+            }
+        }
+
+        (DictionaryDomain::Other, entry)
+    }
+
+    /// Return the domain of the entry with the given index of a [`DomainDictionary`],
+    /// or `None` if the index does not encode any domain.
+    pub fn of_index(index: usize) -> Option<Self> {
+        Self::ALL.get(index >> DOMAIN_SHIFT).copied()
+    }
+
+    fn tag(self, local: usize) -> usize {
+        assert!(
+            local <= LOCAL_MASK,
+            "the dictionary of a domain exceeds its index space"
+        );
+        ((self as usize) << DOMAIN_SHIFT) | local
+    }
+}
+
+/// [`Dictionary`] that keeps the entries of each [`DictionaryDomain`] in a dictionary of their own.
+///
+/// Entries are stored without the prefix of their domain,
+/// which is instead encoded in the bits below the most significant bit of their index.
+/// Indices are thus no longer consecutive, but the domain of an entry can be read off its index,
+/// and the strings of each domain are stored (and compressed) together.
+/// The dictionaries used for each domain can be chosen independently.
+#[derive(Debug, Clone, Default)]
+pub struct DomainDictionary<IriDict = super::PrefixedStringDictionary, StringDict = IriDict> {
+    /// Entries of [`DictionaryDomain::Other`], which often contain IRIs as well (e.g. datatypes)
+    others: IriDict,
+    /// Entries of [`DictionaryDomain::Iri`]
+    iris: IriDict,
+    /// Entries of [`DictionaryDomain::String`]
+    strings: StringDict,
+    /// Entries of [`DictionaryDomain::LanguageString`]
+    language_strings: StringDict,
+}
+
+impl<IriDict: Dictionary, StringDict: Dictionary> DomainDictionary<IriDict, StringDict> {
+    /// Return the number of entries of the given domain.
+    pub fn domain_len(&self, domain: DictionaryDomain) -> usize {
+        match domain {
+            DictionaryDomain::Other => self.others.len(),
+            DictionaryDomain::Iri => self.iris.len(),
+            DictionaryDomain::String => self.strings.len(),
+            DictionaryDomain::LanguageString => self.language_strings.len(),
+        }
+    }
+
+    fn domain_add(&mut self, domain: DictionaryDomain, entry: String) -> usize {
+        match domain {
+            DictionaryDomain::Other => self.others.add(entry),
+            DictionaryDomain::Iri => self.iris.add(entry),
+            DictionaryDomain::String => self.strings.add(entry),
+            DictionaryDomain::LanguageString => self.language_strings.add(entry),
+        }
+    }
+
+    fn domain_index_of(&self, domain: DictionaryDomain, entry: &str) -> Option<usize> {
+        match domain {
+            DictionaryDomain::Other => self.others.index_of(entry),
+            DictionaryDomain::Iri => self.iris.index_of(entry),
+            DictionaryDomain::String => self.strings.index_of(entry),
+            DictionaryDomain::LanguageString => self.language_strings.index_of(entry),
+        }
+    }
+
+    fn domain_entry(&self, domain: DictionaryDomain, local: usize) -> Option<String> {
+        match domain {
+            DictionaryDomain::Other => self.others.entry(local),
+            DictionaryDomain::Iri => self.iris.entry(local),
+            DictionaryDomain::String => self.strings.entry(local),
+            DictionaryDomain::LanguageString => self.language_strings.entry(local),
+        }
+    }
+}
+
+impl<IriDict: Dictionary, StringDict: Dictionary>
+    DomainDictionary<LayeredDictionary<IriDict>, LayeredDictionary<StringDict>>
+{
+    /// Return a dictionary with the same entries,
+    /// whose clones share all of these entries, see [`LayeredDictionary::frozen`].
+    pub fn frozen(&self) -> Self {
+        Self {
+            others: self.others.frozen(),
+            iris: self.iris.frozen(),
+            strings: self.strings.frozen(),
+            language_strings: self.language_strings.frozen(),
+        }
+    }
+}
+
+impl<IriDict: Dictionary, StringDict: Dictionary> Dictionary
+    for DomainDictionary<IriDict, StringDict>
+{
+    fn add(&mut self, mut entry: String) -> usize {
+        let (domain, _) = DictionaryDomain::split(&entry);
+        let local = self.domain_add(domain, entry.split_off(domain.prefix().len()));
+
+        domain.tag(local)
+    }
+
+    fn index_of(&self, entry: &str) -> Option<usize> {
+        let (domain, rest) = DictionaryDomain::split(entry);
+        self.domain_index_of(domain, rest)
+            .map(|local| domain.tag(local))
+    }
+
+    fn entry(&self, index: usize) -> Option<String> {
+        let domain = DictionaryDomain::of_index(index)?;
+        self.domain_entry(domain, index & LOCAL_MASK)
+            .map(|entry| format!("{}{entry}", domain.prefix()))
+    }
+
+    fn len(&self) -> usize {
+        DictionaryDomain::ALL
+            .iter()
+            .map(|&domain| self.domain_len(domain))
+            .sum()
+    }
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn indices(&self) -> Box<dyn Iterator<Item = usize> + '_> {
+        Box::new(DictionaryDomain::ALL.into_iter().flat_map(move |domain| {
+            (0..self.domain_len(domain)).map(move |local| domain.tag(local))
+        }))
+    }
+
+    fn contains_index(&self, index: usize) -> bool {
+        DictionaryDomain::of_index(index)
+            .is_some_and(|domain| index & LOCAL_MASK < self.domain_len(domain))
+    }
+}
+
+impl<IriDict: ByteSized, StringDict: ByteSized> ByteSized
+    for DomainDictionary<IriDict, StringDict>
+{
+    fn size_bytes(&self) -> ByteSize {
+        self.others.size_bytes()
+            + self.iris.size_bytes()
+            + self.strings.size_bytes()
+            + self.language_strings.size_bytes()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::dictionary::{Dictionary, PrefixedStringDictionary, StringDictionary};
+
+    use super::{DictionaryDomain, DomainDictionary};
+
+    #[test]
+    fn split_domains() {
+        assert_eq!(
+            DictionaryDomain::split("CONSTANT:http://example.org/a"),
+            (DictionaryDomain::Iri, "http://example.org/a")
+        );
+        assert_eq!(
+            DictionaryDomain::split("STRING:"),
+            (DictionaryDomain::String, "")
+        );
+        assert_eq!(
+            DictionaryDomain::split("LANGUAGE_STRING:hello@en"),
+            (DictionaryDomain::LanguageString, "hello@en")
+        );
+        assert_eq!(
+            DictionaryDomain::split("DOUBLE:1.5"),
+            (DictionaryDomain::Other, "DOUBLE:1.5")
+        );
+    }
+
+    #[test]
+    fn tagged_indices() {
+        let mut dict = DomainDictionary::<StringDictionary>::default();
+
+        let iri = dict.add("CONSTANT:a".to_string());
+        let string = dict.add("STRING:a".to_string());
+        let other = dict.add("INTEGER:a".to_string());
+        let second_iri = dict.add("CONSTANT:b".to_string());
+
+        assert_eq!(dict.add("CONSTANT:a".to_string()), iri);
+        assert_eq!(DictionaryDomain::of_index(iri), Some(DictionaryDomain::Iri));
+        assert_eq!(
+            DictionaryDomain::of_index(string),
+            Some(DictionaryDomain::String)
+        );
+        assert_eq!(
+            DictionaryDomain::of_index(other),
+            Some(DictionaryDomain::Other)
+        );
+        assert_eq!(second_iri, iri + 1);
+        assert!(u64::try_from(string).unwrap() < crate::dictionary::FIRST_NULL);
+
+        assert_eq!(dict.len(), 4);
+        assert_eq!(dict.domain_len(DictionaryDomain::Iri), 2);
+        assert_eq!(dict.index_of("STRING:a"), Some(string));
+        assert_eq!(dict.index_of("LANGUAGE_STRING:a"), None);
+        assert_eq!(dict.entry(second_iri), Some("CONSTANT:b".to_string()));
+        assert_eq!(dict.entry(other), Some("INTEGER:a".to_string()));
+        assert_eq!(dict.entry(string + 1), None);
+        assert!(dict.contains_index(second_iri));
+        assert!(!dict.contains_index(string + 1));
+        assert!(!dict.contains_index(usize::try_from(crate::dictionary::FIRST_NULL).unwrap()));
+
+        assert_eq!(
+            dict.indices().collect::<Vec<_>>(),
+            vec![other, iri, second_iri, string]
+        );
+    }
+
+    #[test]
+    fn mapped_entries_keep_domains() {
+        let mut dict = DomainDictionary::<PrefixedStringDictionary, StringDictionary>::default();
+        let indices = ["CONSTANT:a", "STRING:b", "LANGUAGE_STRING:c@en", "DOUBLE:1"]
+            .map(|entry| dict.add(entry.to_string()));
+
+        let mapped = dict.map_entries(|entry| entry.replace(':', ":x"));
+
+        assert_eq!(mapped.len(), dict.len());
+        for index in indices {
+            assert_eq!(
+                mapped.entry(index),
+                dict.entry(index).map(|entry| entry.replace(':', ":x"))
+            );
+        }
+    }
+}
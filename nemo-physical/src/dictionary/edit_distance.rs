@@ -0,0 +1,220 @@
+//! Index of dictionary entries by the length of their strings, used to find entries within a given edit distance.
+
+use std::{
+    collections::{BTreeMap, HashSet},
+    mem::size_of,
+};
+
+use bytesize::ByteSize;
+
+use crate::management::ByteSized;
+
+use super::{text_index::searched_text, Dictionary};
+
+/// Return whether the Levenshtein distance between the given strings,
+/// i.e. the number of character insertions, deletions and substitutions
+/// needed to turn one into the other, is at most `max_distance`.
+///
+/// Only the diagonal band of width `2 * max_distance + 1` of the usual dynamic programming table is computed,
+/// and the computation stops as soon as every entry of a row exceeds `max_distance`.
+pub fn edit_distance_at_most(left: &str, right: &str, max_distance: usize) -> bool {
+    let left = left.chars().collect::<Vec<_>>();
+    let right = right.chars().collect::<Vec<_>>();
+
+    if left.len().abs_diff(right.len()) > max_distance {
+        return false;
+    }
+
+    // Distances exceeding `max_distance` are all represented by `exceeded`
+    let exceeded = max_distance + 1;
+    let mut previous = (0..=right.len())
+        .map(|distance| distance.min(exceeded))
+        .collect::<Vec<_>>();
+    let mut current = vec![exceeded; right.len() + 1];
+
+    for (row, left_char) in left.iter().enumerate() {
+        let row = row + 1;
+        let first = row.saturating_sub(max_distance).max(1);
+        let last = (row + max_distance).min(right.len());
+
+        current.fill(exceeded);
+        current[0] = row.min(exceeded);
+
+        let mut row_minimum = current[0];
+        for column in first..=last {
+            let substitution = previous[column - 1] + usize::from(*left_char != right[column - 1]);
+            let distance = substitution
+                .min(previous[column] + 1)
+                .min(current[column - 1] + 1)
+                .min(exceeded);
+
+            current[column] = distance;
+            row_minimum = row_minimum.min(distance);
+        }
+
+        if row_minimum > max_distance {
+            return false;
+        }
+
+        std::mem::swap(&mut previous, &mut current);
+    }
+
+    previous[right.len()] <= max_distance
+}
+
+/// Index of the entries of a [`Dictionary`] by the number of characters of their strings.
+///
+/// Since strings within edit distance `k` of a string with `n` characters
+/// have between `n - k` and `n + k` characters,
+/// only the entries of these lengths need to be verified when looking for similar strings.
+/// Like for the [`TextIndex`][super::TextIndex], only IRIs, strings and language tagged strings are indexed,
+/// and entries that were added to the dictionary since the index was created are added with [`LengthIndex::update`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LengthIndex {
+    /// Indices of the entries with each number of characters
+    buckets: BTreeMap<usize, Vec<usize>>,
+    /// Indices of the entries covered by this index
+    covered: HashSet<usize>,
+}
+
+impl LengthIndex {
+    /// Create a [`LengthIndex`] for all entries of the given dictionary.
+    pub fn new<D: Dictionary>(dict: &D) -> Self {
+        let mut result = Self::default();
+        result.update(dict);
+        result
+    }
+
+    /// Add the entries that have been added to the dictionary since the last update.
+    pub fn update<D: Dictionary>(&mut self, dict: &D) {
+        if self.covered.len() >= dict.len() {
+            return;
+        }
+
+        let added = dict
+            .indices()
+            .filter(|index| !self.covered.contains(index))
+            .collect::<Vec<_>>();
+
+        for index in added {
+            self.covered.insert(index);
+
+            let entry = dict
+                .entry(index)
+                .expect("The dictionary contains an entry for each of its indices.");
+            if let Some(text) = searched_text(&entry) {
+                self.buckets
+                    .entry(text.chars().count())
+                    .or_default()
+                    .push(index);
+            }
+        }
+    }
+
+    /// Return the number of dictionary entries covered by this index.
+    pub fn len(&self) -> usize {
+        self.covered.len()
+    }
+
+    /// Return whether this index does not cover any entries.
+    pub fn is_empty(&self) -> bool {
+        self.covered.is_empty()
+    }
+
+    /// Return the indices of the covered entries of the given dictionary
+    /// whose strings are within edit distance `max_distance` of the given text, in increasing order.
+    pub fn similar_indices<D: Dictionary>(
+        &self,
+        dict: &D,
+        text: &str,
+        max_distance: usize,
+    ) -> Vec<usize> {
+        let length = text.chars().count();
+        let lengths = length.saturating_sub(max_distance)..=length.saturating_add(max_distance);
+
+        let mut result = self
+            .buckets
+            .range(lengths)
+            .flat_map(|(_, indices)| indices.iter().copied())
+            .filter(|&index| {
+                dict.entry(index).is_some_and(|entry| {
+                    searched_text(&entry)
+                        .is_some_and(|other| edit_distance_at_most(text, other, max_distance))
+                })
+            })
+            .collect::<Vec<_>>();
+        result.sort_unstable();
+
+        result
+    }
+}
+
+impl ByteSized for LengthIndex {
+    fn size_bytes(&self) -> ByteSize {
+        let size_buckets = self
+            .buckets
+            .values()
+            .map(|indices| {
+                2 * size_of::<usize>()
+                    + size_of::<Vec<usize>>()
+                    + indices.capacity() * size_of::<usize>()
+            })
+            .sum::<usize>();
+
+        ByteSize::b(
+            (size_of::<Self>() + size_buckets + self.covered.capacity() * size_of::<usize>())
+                as u64,
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::dictionary::{
+        domain_dictionary::{IRI_PREFIX, LANGUAGE_STRING_PREFIX, STRING_PREFIX},
+        Dictionary, DomainDictionary, StringDictionary,
+    };
+
+    use super::{edit_distance_at_most, LengthIndex};
+
+    fn edit_distance(left: &str, right: &str) -> usize {
+        (0..)
+            .find(|&distance| edit_distance_at_most(left, right, distance))
+            .unwrap()
+    }
+
+    #[test]
+    fn edit_distances() {
+        assert_eq!(edit_distance("", ""), 0);
+        assert_eq!(edit_distance("", "abc"), 3);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("flaw", "lawn"), 2);
+        assert_eq!(edit_distance("Müller", "Mueller"), 2);
+        assert_eq!(edit_distance("abcdef", "badcfe"), 4);
+        assert_eq!(edit_distance("intention", "execution"), 5);
+
+        assert!(!edit_distance_at_most("kitten", "sitting", 2));
+        assert!(!edit_distance_at_most("a", "abcd", 2));
+    }
+
+    #[test]
+    fn similar_indices() {
+        let mut dict = DomainDictionary::<StringDictionary>::default();
+
+        let berlin = dict.add(format!("{STRING_PREFIX}Berlin"));
+        let berlyn = dict.add(format!("{LANGUAGE_STRING_PREFIX}Berlyn@de"));
+        dict.add(format!("{STRING_PREFIX}Bern"));
+        dict.add("INTEGER:Berlin".to_string());
+
+        let mut index = LengthIndex::new(&dict);
+        let berlin_iri = dict.add(format!("{IRI_PREFIX}Berlin"));
+        index.update(&dict);
+        assert_eq!(index.len(), dict.len());
+
+        let mut expected = vec![berlin, berlyn, berlin_iri];
+        expected.sort_unstable();
+        assert_eq!(index.similar_indices(&dict, "Berlin", 1), expected);
+        assert_eq!(index.similar_indices(&dict, "Berlin", 0).len(), 2);
+        assert_eq!(index.similar_indices(&dict, "Bernd", 1).len(), 1);
+    }
+}
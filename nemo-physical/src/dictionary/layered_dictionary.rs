@@ -0,0 +1,161 @@
+//! Dictionary that extends a frozen dictionary, which may be shared with other dictionaries.
+
+use std::sync::Arc;
+
+use bytesize::ByteSize;
+
+use crate::management::ByteSized;
+
+use super::Dictionary;
+
+/// [`Dictionary`] whose entries extend the entries of an immutable base dictionary.
+///
+/// The base is shared via [`Arc`], so cloning a [`LayeredDictionary`] only copies the entries added on top of it.
+/// This allows several dictionaries (e.g. of different threads) to start from the same entries
+/// without copying them, while each of them can add further entries independently.
+/// Indices of the base are kept, and entries added later are numbered consecutively after them.
+///
+/// The dictionary type needs to assign consecutive indices starting at 0.
+#[derive(Debug, Clone)]
+pub struct LayeredDictionary<D> {
+    /// Frozen entries shared with other dictionaries
+    base: Arc<D>,
+    /// Entries added on top of the base
+    ///
+    /// Since `local` starts as a new dictionary, it contains the entries every new dictionary contains.
+    /// Those are contained in the base as well, and are hence never looked up in `local`.
+    local: D,
+    /// Number of entries contained in every new dictionary of type `D`
+    reserved: usize,
+}
+
+impl<D: Dictionary> Default for LayeredDictionary<D> {
+    fn default() -> Self {
+        let local = D::default();
+
+        Self {
+            base: Arc::new(local.clone()),
+            reserved: local.len(),
+            local,
+        }
+    }
+}
+
+impl<D: Dictionary> LayeredDictionary<D> {
+    /// Translate an index of the local dictionary into an index of this dictionary.
+    fn global_index(&self, local: usize) -> usize {
+        self.base.len() + local - self.reserved
+    }
+
+    /// Return a dictionary with the same entries, whose base contains all of these entries.
+    ///
+    /// Clones of the result share all of their current entries,
+    /// so a dictionary should be frozen before it is handed out to several users.
+    /// This copies the base only if entries have been added on top of it.
+    pub fn frozen(&self) -> Self {
+        if self.local.len() == self.reserved {
+            return self.clone();
+        }
+
+        let mut base = D::clone(&self.base);
+        for local in self.reserved..self.local.len() {
+            let entry = self
+                .local
+                .entry(local)
+                .expect("The dictionary contains an entry for each of its indices.");
+            base.add(entry);
+        }
+
+        Self {
+            base: Arc::new(base),
+            local: D::default(),
+            reserved: self.reserved,
+        }
+    }
+}
+
+impl<D: Dictionary> Dictionary for LayeredDictionary<D> {
+    fn add(&mut self, entry: String) -> usize {
+        if let Some(index) = self.base.index_of(&entry) {
+            return index;
+        }
+
+        let local = self.local.add(entry);
+        self.global_index(local)
+    }
+
+    fn index_of(&self, entry: &str) -> Option<usize> {
+        self.base.index_of(entry).or_else(|| {
+            self.local
+                .index_of(entry)
+                .map(|local| self.global_index(local))
+        })
+    }
+
+    fn entry(&self, index: usize) -> Option<String> {
+        if index < self.base.len() {
+            self.base.entry(index)
+        } else {
+            self.local.entry(index - self.base.len() + self.reserved)
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.base.len() + self.local.len() - self.reserved
+    }
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<D: ByteSized> ByteSized for LayeredDictionary<D> {
+    fn size_bytes(&self) -> ByteSize {
+        self.base.size_bytes() + self.local.size_bytes()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use crate::dictionary::{Dictionary, PrefixedStringDictionary, StringDictionary};
+
+    use super::LayeredDictionary;
+
+    fn layered_entries<D: Dictionary>() {
+        let mut dict = LayeredDictionary::<D>::default();
+        let a = dict.add("http://example.org/a".to_string());
+        let b = dict.add("b".to_string());
+
+        let frozen = dict.frozen();
+        assert_eq!(frozen.local.len(), frozen.reserved);
+        assert_eq!(frozen.index_of("b"), Some(b));
+
+        let mut left = frozen.clone();
+        let mut right = frozen.clone();
+        assert!(Arc::ptr_eq(&left.base, &right.base));
+
+        let c = left.add("c".to_string());
+        assert_eq!(right.add("http://example.org/d".to_string()), c);
+        assert_eq!(left.add("http://example.org/a".to_string()), a);
+
+        assert_eq!(left.len(), dict.len() + 1);
+        assert_eq!(left.entry(c), Some("c".to_string()));
+        assert_eq!(right.entry(c), Some("http://example.org/d".to_string()));
+        assert_eq!(right.index_of("c"), None);
+        assert_eq!(right.entry(a), Some("http://example.org/a".to_string()));
+        assert_eq!(right.entry(c + 1), None);
+
+        let refrozen = left.frozen();
+        assert_eq!(refrozen.len(), left.len());
+        assert_eq!(refrozen.index_of("c"), Some(c));
+        assert_eq!(refrozen.entry(a), Some("http://example.org/a".to_string()));
+    }
+
+    #[test]
+    fn shared_base() {
+        layered_entries::<StringDictionary>();
+        layered_entries::<PrefixedStringDictionary>();
+    }
+}
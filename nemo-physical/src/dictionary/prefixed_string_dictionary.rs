@@ -1,393 +1,141 @@
-use std::{
-    cell::RefCell,
-    collections::HashMap,
-    fmt::Display,
-    hash::{Hash, Hasher},
-    rc::{Rc, Weak},
-};
+use std::{collections::HashMap, mem::size_of};
 
-use super::Dictionary;
+use bytesize::ByteSize;
 
-/// Represents a node, which is either a [TrieNode::Root], or some non-special [TrieNode::Node]
-enum TrieNode {
-    Root {
-        prefix: String,
-        children: Vec<Rc<RefCell<TrieNode>>>,
-        hash: HashMap<String, usize>,
-    },
-    Node {
-        prefix: String,
-        children: Vec<Rc<RefCell<TrieNode>>>,
-        parent: Weak<RefCell<TrieNode>>,
-        hash: HashMap<String, usize>,
-    },
-}
+use crate::management::ByteSized;
 
-impl std::fmt::Debug for TrieNode {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            TrieNode::Root {
-                prefix,
-                children,
-                hash,
-            } => f
-                .debug_struct("TrieNode::Root")
-                .field("prefix", prefix)
-                .field("children", children)
-                .field("hash", hash)
-                .finish(),
-            TrieNode::Node {
-                prefix,
-                children,
-                parent,
-                hash,
-            } => f
-                .debug_struct("TrieNode::Node")
-                .field("prefix", prefix)
-                .field("children", children)
-                .field("hash", hash)
-                .field(
-                    "parent(prefix)",
-                    &parent
-                        .upgrade()
-                        .expect("should be valid")
-                        .as_ref()
-                        .borrow()
-                        .prefix(),
-                )
-                .finish(),
-        }
-    }
-}
-
-impl Default for TrieNode {
-    fn default() -> Self {
-        Self::Root {
-            prefix: "".to_string(),
-            children: Vec::new(),
-            hash: HashMap::new(),
-        }
-    }
-}
-
-impl Hash for TrieNode {
-    fn hash<H: Hasher>(&self, state: &mut H) {
-        self.prefix().hash(state);
-        match self {
-            TrieNode::Root {
-                prefix: _,
-                children: _,
-                hash: _,
-            } => {
-                "".hash(state);
-            }
-            TrieNode::Node {
-                prefix: _,
-                children: _,
-                hash: _,
-                parent,
-            } => {
-                parent
-                    .upgrade()
-                    .expect("should be valid")
-                    .as_ref()
-                    .borrow()
-                    .hash(state);
-            }
-        }
-    }
-}
+use super::Dictionary;
 
-impl PartialEq for TrieNode {
-    fn eq(&self, other: &Self) -> bool {
-        match self {
-            TrieNode::Root {
-                prefix: left,
-                children: _,
-                hash: _,
-            } => match other {
-                TrieNode::Root {
-                    prefix: right,
-                    children: _,
-                    hash: _,
-                } => left.eq(right),
-                TrieNode::Node {
-                    prefix: _,
-                    children: _,
-                    parent: _,
-                    hash: _,
-                } => false,
-            },
-            TrieNode::Node {
-                prefix: left,
-                children: _,
-                parent: parent_left,
-                hash: _,
-            } => match other {
-                TrieNode::Root {
-                    prefix: _,
-                    children: _,
-                    hash: _,
-                } => false,
-                TrieNode::Node {
-                    prefix: right,
-                    children: _,
-                    parent: parent_right,
-                    hash: _,
-                } => {
-                    left.eq(right)
-                        && parent_left
-                            .upgrade()
-                            .expect("should be valid")
-                            .as_ref()
-                            .borrow()
-                            .eq(&*parent_right
-                                .upgrade()
-                                .expect("should be valid")
-                                .as_ref()
-                                .borrow())
-                }
-            },
-        }
-    }
+/// Return the number of bytes used by a map from strings to indices.
+fn string_map_size(map: &HashMap<String, usize>) -> u64 {
+    let size_keys: usize = map.keys().map(String::capacity).sum();
+    (map.capacity() * (size_of::<(String, usize)>() + 1) + size_keys) as u64
 }
 
-impl Eq for TrieNode {}
-
-impl Display for TrieNode {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            TrieNode::Root {
-                prefix: _,
-                children: _,
-                hash: _,
-            } => {}
-            TrieNode::Node {
-                prefix: _,
-                children: _,
-                hash: _,
-                parent,
-            } => {
-                write!(
-                    f,
-                    "{}",
-                    parent.upgrade().expect("should be valid").as_ref().borrow()
-                )?;
-            }
-        }
-
-        write!(f, "{}", self.prefix())
-    }
+/// Position of a [TrieNode] within the nodes of a [PrefixedStringDictionary]
+type NodeIndex = usize;
+
+/// Position of the root node, which represents the empty prefix
+const ROOT: NodeIndex = 0;
+
+/// Represents a node of the prefix tree, i.e. a chain of prefixes ending in `prefix`
+///
+/// Nodes refer to each other by their position in the dictionary,
+/// so the tree does not need any shared ownership or interior mutability.
+#[derive(Clone, Debug, Default)]
+struct TrieNode {
+    /// Last prefix of the chain represented by this node
+    prefix: String,
+    /// Parent of this node, which is `None` only for the root
+    parent: Option<NodeIndex>,
+    /// Children of this node, indexed by their prefix
+    children: HashMap<String, NodeIndex>,
 }
 
 impl TrieNode {
     /// create a [TrieNode] with a given `parent` as well as a `prefix` [String]
-    fn create_node(parent: Rc<RefCell<TrieNode>>, prefix: String) -> Self {
-        Self::Node {
+    fn create_node(parent: NodeIndex, prefix: String) -> Self {
+        Self {
             prefix,
-            children: Vec::new(),
-            parent: Rc::downgrade(&parent),
-            hash: HashMap::new(),
+            parent: Some(parent),
+            children: HashMap::new(),
         }
     }
 
-    /// add another child to the list of children of this node
-    /// Note that it does not check whether some child already exists
-    fn add_node(&mut self, child: Rc<RefCell<TrieNode>>) {
-        let (children, hash) = match self {
-            Self::Node {
-                children,
-                prefix: _,
-                parent: _,
-                hash,
-            } => (children, hash),
-            Self::Root {
-                children,
-                prefix: _,
-                hash,
-            } => (children, hash),
-        };
-        children.push(Rc::clone(&child));
-        hash.insert(child.borrow_mut().prefix().to_string(), children.len() - 1);
-    }
-
-    /// Returns the children of the given [TrieNode]
-    fn children(&self) -> &Vec<Rc<RefCell<TrieNode>>> {
-        match self {
-            Self::Node {
-                children,
-                prefix: _,
-                parent: _,
-                hash: _,
-            } => children,
-            Self::Root {
-                children,
-                prefix: _,
-                hash: _,
-            } => children,
-        }
-    }
-
-    /// Returns the hash of the given [TrieNode]
-    fn prefix_hash(&self) -> &HashMap<String, usize> {
-        match self {
-            Self::Node {
-                children: _,
-                prefix: _,
-                parent: _,
-                hash,
-            } => hash,
-            Self::Root {
-                children: _,
-                prefix: _,
-                hash,
-            } => hash,
-        }
-    }
-
-    /// Provides the prefix of the given [TrieNode]
-    fn prefix(&self) -> &str {
-        match self {
-            Self::Node {
-                children: _,
-                prefix,
-                parent: _,
-                hash: _,
-            } => prefix,
-            Self::Root {
-                children: _,
-                prefix,
-                hash: _,
-            } => prefix,
-        }
-    }
-
-    /// Given a prefix, checks whether the prefix occurs in the children. Returns that child if that is the case.
-    fn matching_child(&self, search_value: &str) -> Option<Rc<RefCell<TrieNode>>> {
-        self.prefix_hash()
-            .get(search_value)
-            .map(|&idx| Rc::clone(&self.children()[idx]))
-    }
-
-    /// Given a list of prefixes `search_list` and a `node`, searches for a matching path down the sub-tree with respect to the given list.
-    /// It will return the last matching node, together with the remainder of the (not-matched) prefixes
-    fn find_last_match<'a>(
-        node: Rc<RefCell<TrieNode>>,
-        search_list: &'a [&'a str],
-    ) -> (Rc<RefCell<TrieNode>>, &'a [&'a str]) {
-        let mut cur_node = node;
-        for (pos, element) in search_list.iter().enumerate() {
-            let mut helper = Rc::clone(&cur_node);
-            if let Some(child) = cur_node.as_ref().borrow().matching_child(element) {
-                helper = Rc::clone(&child);
-            } else {
-                return (helper, &search_list[pos..]);
-            }
-            cur_node = Rc::clone(&helper);
-        }
-        (cur_node, &[])
+    /// Returns the number of bytes used by this node
+    fn size_bytes(&self) -> u64 {
+        (size_of::<TrieNode>() + self.prefix.capacity()) as u64 + string_map_size(&self.children)
     }
 }
 
-/// Newtype to represent a pair of a [TreeNode] (i.e. a path in the Tree) and a [String]
-#[derive(Clone, Debug)]
-struct TrieNodeStringPair(Rc<RefCell<TrieNode>>, Rc<String>);
-
-impl Hash for TrieNodeStringPair {
-    fn hash<H: Hasher>(&self, state: &mut H) {
-        self.0.as_ref().borrow().hash(state);
-        self.1.hash(state);
-    }
-}
-
-impl PartialEq for TrieNodeStringPair {
-    fn eq(&self, other: &Self) -> bool {
-        self.1.eq(&other.1) && self.0.eq(&other.0)
-    }
-}
-
-impl Eq for TrieNodeStringPair {}
-
 /// The [PrefixedStringDictionary] allows to store (and own) a couple of prefixed [String]s.
 /// Prefixes will be stored in a Triestructure and each chain of prefixes is therefore only stored once.
 #[derive(Clone, Debug)]
 pub struct PrefixedStringDictionary {
-    ordering: Vec<TrieNodeStringPair>,
+    /// Node of the chain of prefixes and the remaining suffix of each entry
+    ordering: Vec<(NodeIndex, String)>,
     mapping: HashMap<String, usize>,
-    store: Rc<RefCell<TrieNode>>,
+    /// Nodes of the prefix tree, starting with its root
+    nodes: Vec<TrieNode>,
 }
 
 impl Default for PrefixedStringDictionary {
     /// Initialise a Default Prefixedstringdictionary
     /// It contains the empty string as the first element (position 0)
     fn default() -> Self {
-        let store = Rc::new(RefCell::new(TrieNode::default()));
-
         Self {
-            ordering: vec![TrieNodeStringPair(
-                Rc::clone(&store.to_owned()),
-                Rc::new("".to_string()),
-            )],
+            ordering: vec![(ROOT, "".to_string())],
             mapping: HashMap::from([("".to_string(), 0)]),
-            store,
+            nodes: vec![TrieNode::default()],
         }
     }
 }
 
+impl PrefixedStringDictionary {
+    /// Given a list of prefixes `search_list`, searches for a matching path down the tree with respect to the given list,
+    /// adding nodes for the prefixes that do not match yet.
+    /// It will return the node representing the whole list.
+    fn insert_prefixes(&mut self, search_list: &[&str]) -> NodeIndex {
+        let mut cur_node = ROOT;
+        for element in search_list {
+            cur_node = match self.nodes[cur_node].children.get(*element) {
+                Some(&child) => child,
+                None => {
+                    let child = self.nodes.len();
+                    self.nodes
+                        .push(TrieNode::create_node(cur_node, element.to_string()));
+                    self.nodes[cur_node]
+                        .children
+                        .insert(element.to_string(), child);
+                    tracing::trace!("{element:?} ({search_list:?}): new_node: {child:?}");
+                    child
+                }
+            };
+        }
+        cur_node
+    }
+
+    /// Returns the chain of prefixes represented by the given node
+    fn prefix_chain(&self, node: NodeIndex) -> String {
+        let mut prefixes = Vec::new();
+        let mut cur_node = Some(node);
+        while let Some(index) = cur_node {
+            prefixes.push(self.nodes[index].prefix.as_str());
+            cur_node = self.nodes[index].parent;
+        }
+
+        prefixes.into_iter().rev().collect()
+    }
+}
+
 impl Dictionary for PrefixedStringDictionary {
     fn add(&mut self, entry: String) -> usize {
-        log::trace!("add {entry:?} to {self:?}");
-        *self.mapping.entry(entry.clone()).or_insert_with(|| {
-            let prefixes: Vec<&str> = Prefixer::new(entry.as_str()).collect();
-            log::trace!("prefixes: {prefixes:?}");
-            let (real_prefixes, real_entry) = prefixes.split_at(prefixes.len() - 1);
-            log::trace!("reals: {real_prefixes:?}, {real_entry:?}");
-            let (mut cur_node, remaining_prefixes) =
-                TrieNode::find_last_match(self.store.to_owned(), real_prefixes);
-            log::trace!("cur_node: {cur_node:?}, remaining: {remaining_prefixes:?}");
-            for element in remaining_prefixes {
-                let new_node = Rc::new(RefCell::new(TrieNode::create_node(
-                    Rc::clone(&cur_node),
-                    element.to_string(),
-                )));
-                log::trace!("{element:?} ({remaining_prefixes:?}): new_node: {new_node:?}");
-                cur_node
-                    .as_ref()
-                    .borrow_mut()
-                    .add_node(Rc::clone(&new_node));
-                cur_node = Rc::clone(&new_node);
-                log::trace!("{element:?} ({remaining_prefixes:?}): cur_node: {cur_node:?}");
-            }
-            let entry_string = Rc::new(real_entry[0].to_string());
-            log::trace!("entry_string: {entry_string:?}");
-            log::trace!(
-                "pair: {:?}",
-                TrieNodeStringPair(Rc::clone(&cur_node), Rc::clone(&entry_string))
-            );
-            let value = self.ordering.len();
-            self.ordering.push(TrieNodeStringPair(
-                Rc::clone(&cur_node),
-                Rc::clone(&entry_string),
-            ));
-            log::trace!("ordering: {:?}, value: {value:?}", self.ordering);
-            value
-        })
+        tracing::trace!("add {entry:?}");
+        if let Some(&value) = self.mapping.get(&entry) {
+            return value;
+        }
+
+        let prefixes: Vec<&str> = Prefixer::new(entry.as_str()).collect();
+        tracing::trace!("prefixes: {prefixes:?}");
+        let (real_prefixes, real_entry) = prefixes.split_at(prefixes.len() - 1);
+        tracing::trace!("reals: {real_prefixes:?}, {real_entry:?}");
+        let node = self.insert_prefixes(real_prefixes);
+
+        let value = self.ordering.len();
+        self.ordering.push((node, real_entry[0].to_string()));
+        tracing::trace!("node: {node:?}, value: {value:?}");
+        self.mapping.insert(entry, value);
+        value
     }
 
     fn index_of(&self, entry: &str) -> Option<usize> {
-        self.mapping.get(&entry.to_string()).cloned()
+        self.mapping.get(entry).copied()
     }
 
     fn entry(&self, index: usize) -> Option<String> {
-        if index < self.ordering.len() {
-            Some(format!(
-                "{}{}",
-                self.ordering[index].0.as_ref().borrow(),
-                self.ordering[index].1.as_ref(),
-            ))
-        } else {
-            None
-        }
+        self.ordering
+            .get(index)
+            .map(|(node, suffix)| format!("{}{suffix}", self.prefix_chain(*node)))
     }
 
     fn len(&self) -> usize {
@@ -399,6 +147,28 @@ impl Dictionary for PrefixedStringDictionary {
     }
 }
 
+impl ByteSized for PrefixedStringDictionary {
+    fn size_bytes(&self) -> ByteSize {
+        let size_ordering = (self.ordering.capacity() * size_of::<(NodeIndex, String)>()) as u64;
+        let size_entries: u64 = self
+            .ordering
+            .iter()
+            .map(|(_, suffix)| suffix.capacity() as u64)
+            .sum();
+        let size_nodes = ((self.nodes.capacity() - self.nodes.len()) * size_of::<TrieNode>())
+            as u64
+            + self.nodes.iter().map(TrieNode::size_bytes).sum::<u64>();
+
+        ByteSize::b(
+            size_of::<Self>() as u64
+                + size_ordering
+                + size_entries
+                + string_map_size(&self.mapping)
+                + size_nodes,
+        )
+    }
+}
+
 /// The [Prefixer] allows to split a given [&str] into its prefixes.
 /// It is an [Iterator] an will iterate over the prefixes in the order they occur in the stringslice.
 struct Prefixer<'a> {
@@ -436,9 +206,9 @@ impl<'a> Iterator for Prefixer<'a> {
 mod test {
     use std::borrow::Borrow;
 
-    use crate::dictionary::Dictionary;
+    use crate::{dictionary::Dictionary, management::ByteSized};
 
-    use super::PrefixedStringDictionary;
+    use super::{PrefixedStringDictionary, ROOT};
 
     use test_log::test;
 
@@ -507,10 +277,10 @@ mod test {
     fn properties() {
         let mut dict = create_dict();
         // no prefixes, so no children
-        assert!(dict.store.as_ref().borrow().children().is_empty());
+        assert!(dict.nodes[ROOT].children.is_empty());
         dict.add("https://wikidata.org/entity/Q42".to_string());
         // now we need some children
-        assert!(!dict.store.as_ref().borrow().children().is_empty());
+        assert!(!dict.nodes[ROOT].children.is_empty());
         assert_eq!(
             dict.entry(7),
             Some("https://wikidata.org/entity/Q42".to_string())
@@ -521,10 +291,10 @@ mod test {
     fn iri() {
         let mut dict = create_dict();
         // no prefixes, so no children
-        assert!(dict.store.as_ref().borrow().children().is_empty());
+        assert!(dict.nodes[ROOT].children.is_empty());
         dict.add("https://wikidata.org/entity/Q42".to_string());
         // now we need some children
-        assert!(!dict.store.as_ref().borrow().children().is_empty());
+        assert!(!dict.nodes[ROOT].children.is_empty());
         assert_eq!(
             dict.entry(7),
             Some("https://wikidata.org/entity/Q42".to_string())
@@ -553,4 +323,20 @@ mod test {
             assert_eq!(dict.index_of(result), Some(id + 1));
         }
     }
+
+    #[test]
+    fn size_grows_with_entries() {
+        let mut dict = PrefixedStringDictionary::default();
+        let empty = dict.size_bytes();
+
+        dict.add("https://example.org/entity/Q42".to_string());
+        let one = dict.size_bytes();
+        assert!(one > empty);
+
+        // Shared prefixes are stored once
+        dict.add("https://example.org/entity/Q43".to_string());
+        let two = dict.size_bytes();
+        assert!(two > one);
+        assert!(two.0 - one.0 < one.0 - empty.0);
+    }
 }
@@ -1,12 +1,15 @@
 use super::Dictionary;
+use crate::management::ByteSized;
+use bytesize::ByteSize;
 use std::collections::HashMap;
-use std::rc::Rc;
+use std::mem::size_of;
+use std::sync::Arc;
 
 /// Offers a simple way to store multiple [String] objects, associate them to a [usize] and manage ownership for them
 #[derive(Clone, Debug, Default)]
 pub struct StringDictionary {
-    store: Vec<Rc<String>>,
-    mapping: HashMap<Rc<String>, usize>,
+    store: Vec<Arc<String>>,
+    mapping: HashMap<Arc<String>, usize>,
 }
 
 impl Dictionary for StringDictionary {
@@ -15,7 +18,7 @@ impl Dictionary for StringDictionary {
             Some(idx) => *idx,
             None => {
                 let len = self.store.len();
-                self.store.push(Rc::new(entry));
+                self.store.push(Arc::new(entry));
                 self.mapping.insert(self.store[len].clone(), len);
                 len
             }
@@ -29,7 +32,7 @@ impl Dictionary for StringDictionary {
     fn entry(&self, index: usize) -> Option<String> {
         self.store
             .get(index)
-            .map(|entry| -> String { Rc::clone(entry).to_string() })
+            .map(|entry| -> String { Arc::clone(entry).to_string() })
     }
 
     fn len(&self) -> usize {
@@ -41,6 +44,22 @@ impl Dictionary for StringDictionary {
     }
 }
 
+impl ByteSized for StringDictionary {
+    fn size_bytes(&self) -> ByteSize {
+        // Each entry is shared by the store and the mapping
+        let size_entries: u64 = self
+            .store
+            .iter()
+            .map(|entry| (2 * size_of::<usize>() + size_of::<String>() + entry.capacity()) as u64)
+            .sum();
+        let size_store = (self.store.capacity() * size_of::<Arc<String>>()) as u64;
+        let size_mapping =
+            (self.mapping.capacity() * (size_of::<(Arc<String>, usize)>() + 1)) as u64;
+
+        ByteSize::b(size_of::<Self>() as u64 + size_entries + size_store + size_mapping)
+    }
+}
+
 #[cfg(test)]
 mod test {
     use std::borrow::Borrow;
@@ -0,0 +1,301 @@
+//! Inverted index over the words of dictionary entries, used for full-text search.
+
+use std::{
+    collections::{BTreeMap, HashSet},
+    mem::size_of,
+};
+
+use bytesize::ByteSize;
+
+use crate::management::ByteSized;
+
+use super::{domain_dictionary::DictionaryDomain, Dictionary};
+
+/// Split a text into its words, i.e. maximal sequences of alphanumeric characters,
+/// which are compared case-insensitively and are thus returned in lowercase.
+pub fn tokenize(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+}
+
+/// Return the part of a dictionary entry that is searched,
+/// or `None` if entries of its domain are not searched.
+///
+/// The domain prefix is removed from IRIs and strings,
+/// and the language tag is removed from language tagged strings.
+pub(crate) fn searched_text(entry: &str) -> Option<&str> {
+    match DictionaryDomain::split(entry) {
+        (DictionaryDomain::Iri | DictionaryDomain::String, text) => Some(text),
+        (DictionaryDomain::LanguageString, text) => {
+            Some(text.rsplit_once('@').map_or(text, |(value, _tag)| value))
+        }
+        (DictionaryDomain::Other, _) => None,
+    }
+}
+
+/// Word of a [`TextQuery`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct QueryWord {
+    /// The word in lowercase
+    word: String,
+    /// Whether the word only needs to be a prefix of a word of the text
+    prefix: bool,
+}
+
+impl QueryWord {
+    fn matches(&self, word: &str) -> bool {
+        if self.prefix {
+            word.starts_with(&self.word)
+        } else {
+            word == self.word
+        }
+    }
+}
+
+/// Query of a full-text search, e.g. `"quantum comput*"`.
+///
+/// A text matches the query if it contains each word of the query,
+/// ignoring case and punctuation.
+/// Query words ending with `*` match all words that start with them.
+/// A query without words does not match any text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextQuery {
+    words: Vec<QueryWord>,
+}
+
+impl TextQuery {
+    /// Parse a [`TextQuery`].
+    pub fn new(query: &str) -> Self {
+        let words = query
+            .split_whitespace()
+            .flat_map(|part| {
+                let prefix = part.ends_with('*');
+                let mut words = tokenize(part).collect::<Vec<_>>();
+                let last = words.pop();
+
+                words
+                    .into_iter()
+                    .map(|word| QueryWord {
+                        word,
+                        prefix: false,
+                    })
+                    .chain(last.map(|word| QueryWord { word, prefix }))
+            })
+            .collect();
+
+        Self { words }
+    }
+
+    /// Return whether the given text matches this query.
+    pub fn matches(&self, text: &str) -> bool {
+        let text_words = tokenize(text).collect::<Vec<_>>();
+
+        !self.words.is_empty()
+            && self.words.iter().all(|query_word| {
+                text_words
+                    .iter()
+                    .any(|text_word| query_word.matches(text_word))
+            })
+    }
+
+    /// Return the indices of the entries of the given dictionary that match this query,
+    /// in increasing order, by checking every entry.
+    ///
+    /// Only IRIs, strings and language tagged strings are searched.
+    pub fn matching_indices<D: Dictionary>(&self, dict: &D) -> Vec<usize> {
+        dict.indices()
+            .filter(|&index| {
+                dict.entry(index).is_some_and(|entry| {
+                    searched_text(&entry).is_some_and(|text| self.matches(text))
+                })
+            })
+            .collect()
+    }
+}
+
+/// Inverted index from the words of the entries of a [`Dictionary`] to the indices of these entries.
+///
+/// The index allows to find the entries matching a [`TextQuery`]
+/// without looking at every entry of the dictionary.
+/// Like for the [`DictionaryOrder`][super::DictionaryOrder], entries that were added
+/// to the dictionary since the index was created are added with [`TextIndex::update`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TextIndex {
+    /// Indices of the entries containing each word, in increasing order
+    postings: BTreeMap<String, Vec<usize>>,
+    /// Indices of the entries covered by this index
+    covered: HashSet<usize>,
+}
+
+impl TextIndex {
+    /// Create a [`TextIndex`] for all entries of the given dictionary.
+    pub fn new<D: Dictionary>(dict: &D) -> Self {
+        let mut result = Self::default();
+        result.update(dict);
+        result
+    }
+
+    /// Add the entries that have been added to the dictionary since the last update.
+    pub fn update<D: Dictionary>(&mut self, dict: &D) {
+        if self.covered.len() >= dict.len() {
+            return;
+        }
+
+        let added = dict
+            .indices()
+            .filter(|index| !self.covered.contains(index))
+            .collect::<Vec<_>>();
+
+        let mut changed = HashSet::new();
+        for index in added {
+            self.covered.insert(index);
+
+            let entry = dict
+                .entry(index)
+                .expect("The dictionary contains an entry for each of its indices.");
+            let Some(text) = searched_text(&entry) else {
+                continue;
+            };
+
+            for word in tokenize(text) {
+                let postings = self.postings.entry(word.clone()).or_default();
+                if postings.last() != Some(&index) {
+                    postings.push(index);
+                    changed.insert(word);
+                }
+            }
+        }
+
+        // Entries added to one domain may have smaller indices than the covered entries of another domain
+        for word in changed {
+            if let Some(postings) = self.postings.get_mut(&word) {
+                postings.sort_unstable();
+                postings.dedup();
+            }
+        }
+    }
+
+    /// Return the number of dictionary entries covered by this index.
+    pub fn len(&self) -> usize {
+        self.covered.len()
+    }
+
+    /// Return whether this index does not cover any entries.
+    pub fn is_empty(&self) -> bool {
+        self.covered.is_empty()
+    }
+
+    /// Return the indices of the entries containing a word that matches the given query word.
+    fn word_matches(&self, query_word: &QueryWord) -> Vec<usize> {
+        if !query_word.prefix {
+            return self
+                .postings
+                .get(&query_word.word)
+                .cloned()
+                .unwrap_or_default();
+        }
+
+        let mut result = self
+            .postings
+            .range(query_word.word.clone()..)
+            .take_while(|(word, _)| word.starts_with(&query_word.word))
+            .flat_map(|(_, postings)| postings.iter().copied())
+            .collect::<Vec<_>>();
+        result.sort_unstable();
+        result.dedup();
+
+        result
+    }
+
+    /// Return the indices of the covered entries that match the given query, in increasing order.
+    pub fn matching_indices(&self, query: &TextQuery) -> Vec<usize> {
+        let mut words = query.words.iter();
+        let Some(first) = words.next() else {
+            return Vec::new();
+        };
+
+        let mut result = self.word_matches(first);
+        for query_word in words {
+            if result.is_empty() {
+                break;
+            }
+
+            let matches = self.word_matches(query_word);
+            result.retain(|index| matches.binary_search(index).is_ok());
+        }
+
+        result
+    }
+}
+
+impl ByteSized for TextIndex {
+    fn size_bytes(&self) -> ByteSize {
+        let size_postings = self
+            .postings
+            .iter()
+            .map(|(word, postings)| {
+                size_of::<String>()
+                    + word.capacity()
+                    + size_of::<Vec<usize>>()
+                    + postings.capacity() * size_of::<usize>()
+            })
+            .sum::<usize>();
+
+        ByteSize::b(
+            (size_of::<Self>() + size_postings + self.covered.capacity() * size_of::<usize>())
+                as u64,
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::dictionary::{
+        domain_dictionary::{IRI_PREFIX, LANGUAGE_STRING_PREFIX, STRING_PREFIX},
+        Dictionary, DomainDictionary, StringDictionary,
+    };
+
+    use super::{TextIndex, TextQuery};
+
+    #[test]
+    fn queries() {
+        let query = TextQuery::new("Quantum comput*");
+
+        assert!(query.matches("An introduction to quantum computing."));
+        assert!(query.matches("COMPUTERS, quantum"));
+        assert!(!query.matches("quantum mechanics"));
+        assert!(!query.matches("quantumcomputing"));
+        assert!(!TextQuery::new("").matches("anything"));
+        assert!(TextQuery::new("e-mail").matches("E-Mail address"));
+    }
+
+    #[test]
+    fn index_matches_scan() {
+        let mut dict = DomainDictionary::<StringDictionary>::default();
+
+        let computing = dict.add(format!("{STRING_PREFIX}Quantum computing"));
+        let computer = dict.add(format!("{LANGUAGE_STRING_PREFIX}The quantum computer@en"));
+        dict.add(format!("{STRING_PREFIX}Classical computing"));
+        dict.add("INTEGER:42".to_string());
+
+        let mut index = TextIndex::new(&dict);
+        let mechanics = dict.add(format!("{IRI_PREFIX}http://example.org/Quantum_mechanics"));
+        index.update(&dict);
+        assert_eq!(index.len(), dict.len());
+
+        for (query, expected) in [
+            ("quantum comput*", vec![computing, computer]),
+            ("http example", vec![mechanics]),
+            ("quantum", vec![mechanics, computing, computer]),
+            ("en", vec![]),
+            ("42", vec![]),
+            ("quantum classical", vec![]),
+        ] {
+            let query = TextQuery::new(query);
+
+            assert_eq!(index.matching_indices(&query), expected);
+            assert_eq!(query.matching_indices(&dict), expected);
+        }
+    }
+}
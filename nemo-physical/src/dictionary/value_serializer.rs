@@ -73,6 +73,12 @@ where
                     "DataType and Storage Type are incompatible. This should never happen!"
                 ),
             },
+            DataTypeName::U128 => match value {
+                StorageValueT::U128(val) => DataValueT::U128(val),
+                _ => unreachable!(
+                    "DataType and Storage Type are incompatible. This should never happen!"
+                ),
+            },
             DataTypeName::Float => match value {
                 StorageValueT::Float(val) => DataValueT::Float(val), // TODO: do we allow nulls here? if yes, how do we distinguish them?
                 _ => unreachable!(
@@ -27,6 +27,9 @@ pub enum ReadingError {
     /// Error which occurs when trying to Parse from an Int
     #[error(transparent)]
     FromInt(#[from] std::num::TryFromIntError),
+    /// Error when the dictionary contains more entries than can be represented in a column
+    #[error("Dictionary index {0} exceeds the range of values available for constants in columns of type U64.")]
+    DictionaryOverflow(usize),
     /// Errors on reading a file
     #[error("Failed to read \"{filename}\": {error}.")]
     IOReading {
@@ -45,6 +48,23 @@ pub enum ReadingError {
         /// Resource which was not provided
         resource: Resource,
     },
+    /// Error when no provider is registered for an external source
+    #[error(r#"No provider is registered for the external source "{0}""#)]
+    ExternalSourceNotProvided(String),
+    /// Error when no import format is registered under the name given by a source
+    #[error(r#"No import format is registered under the name "{0}""#)]
+    FormatNotRegistered(String),
+    /// Error when no import format is registered for the extension of a resource
+    #[error(r#"No import format is registered for the extension of resource "{0}""#)]
+    FormatNotDetected(Resource),
+    /// Error when reading a format requires a feature that Nemo was compiled without
+    #[error(r#"Reading {format} files requires Nemo to be compiled with the "{feature}" feature"#)]
+    FeatureNotEnabled {
+        /// Format which could not be read
+        format: &'static str,
+        /// Name of the missing feature
+        feature: &'static str,
+    },
     /// A provided resource is not a valid local file:// URI
     #[error(r#"Resource "{0}" is not a valid local file:// URI"#)]
     InvalidFileUri(Resource),
@@ -5,6 +5,13 @@
 pub mod database;
 pub use database::DatabaseInstance;
 
+/// Module for detailed reports of memory consumption
+pub mod memory_report;
+pub use memory_report::{MemoryFootprint, MemoryReport, StructureKind};
+
+/// Module for reading and writing cache files of tables
+pub mod table_cache;
+
 /// Module for defining [`Sized`]
 pub mod bytesized;
 pub use bytesized::ByteSized;
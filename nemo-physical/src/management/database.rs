@@ -1,22 +1,32 @@
 use std::cell::{Ref, RefCell};
+use std::cmp::Ordering;
 use std::collections::{HashMap, HashSet};
 use std::fmt::{Debug, Display};
+use std::path::PathBuf;
+use std::rc::Rc;
+use std::sync::Arc;
 
 use bytesize::ByteSize;
 
 use crate::builder_proxy::{PhysicalBuilderProxyEnum, PhysicalStringColumnBuilderProxy};
+use crate::columnar::operations::columnscan_restrict_values::FilterValue;
 use crate::datatypes::data_value::DataValueIteratorT;
 use crate::datatypes::storage_value::{StorageValueIteratorT, VecT};
 use crate::datatypes::{DataTypeName, DataValueT, StorageValueT};
+use crate::dictionary::text_index::searched_text;
 use crate::dictionary::value_serializer::{
     serialize_constant_with_dict, TrieSerializer, ValueSerializer,
 };
+use crate::dictionary::{
+    Dictionary, DictionaryOrder, LengthIndex, TextIndex, TextQuery, FIRST_NULL,
+};
 use crate::table_reader::TableReader;
-use crate::tabular::operations::materialize::materialize_up_to;
+use crate::tabular::operations::materialize::{materialize_first_row, materialize_up_to};
 use crate::tabular::operations::project_reorder::project_and_reorder;
 use crate::tabular::operations::triescan_minus::TrieScanSubtract;
 use crate::tabular::operations::triescan_project::ProjectReordering;
-use crate::tabular::operations::TrieScanPrune;
+use crate::tabular::operations::triescan_select::SimilarValuesFunction;
+use crate::tabular::operations::{TrieScanDistinctPrefix, TrieScanPrune, ValueAssignment};
 use crate::tabular::table_types::trie::TrieRecords;
 use crate::tabular::traits::table::Table;
 use crate::tabular::traits::trie_scan::TrieScan;
@@ -39,16 +49,22 @@ use crate::{
 use super::execution_plan::{ExecutionOperation, ExecutionTree};
 use super::{
     execution_plan::{ExecutionNodeRef, ExecutionResult},
+    memory_report::{MemoryFootprint, StructureKind},
+    table_cache,
     type_analysis::{TypeTree, TypeTreeNode},
     ByteSized, ExecutionPlan,
 };
 
 #[cfg(feature = "no-prefixed-string-dictionary")]
 /// Dictionary Implementation used in the current configuration
-pub type Dict = crate::dictionary::StringDictionary;
+pub type Dict = crate::dictionary::DomainDictionary<
+    crate::dictionary::LayeredDictionary<crate::dictionary::StringDictionary>,
+>;
 #[cfg(not(feature = "no-prefixed-string-dictionary"))]
 /// Dictionary Implementation used in the current configuration
-pub type Dict = crate::dictionary::PrefixedStringDictionary;
+pub type Dict = crate::dictionary::DomainDictionary<
+    crate::dictionary::LayeredDictionary<crate::dictionary::PrefixedStringDictionary>,
+>;
 
 /// Type that represents a reordering of the columns of a table.
 /// It is given in form of a permutation which encodes the transformation
@@ -85,10 +101,20 @@ impl TableId {
 pub enum TableSource {
     /// Table read by any reader implementation
     FileReader(Box<dyn TableReader>),
+    /// Table read by any reader implementation, which provides all but the last column.
+    /// Duplicate rows are kept once, followed by the number of their occurrences in the last column.
+    CountedFileReader(Box<dyn TableReader>),
     /// Table is stored as facts in an rls file
     /// TODO: To not invoke the parser twice I just put the parsed "row-table" here.
     /// Does not seem quite right
     RLS(Vec<Vec<DataValueT>>),
+    /// Table has already been loaded (possibly by another [`DatabaseInstance`])
+    /// and is shared between instances.
+    /// The trie must have been built using the same dictionary as the instance it is added to.
+    Trie(Arc<Trie>),
+    /// Table is read from the cache file at the given path, see [`table_cache`][super::table_cache].
+    /// If there is no such file, the table is loaded from the given source and then written to the cache file.
+    Cached(PathBuf, Box<TableSource>),
 }
 
 impl Display for TableSource {
@@ -96,7 +122,12 @@ impl Display for TableSource {
         match self {
             // TODO: maybe do not use the debug impl of the reader but I'm not sure if it should enforce display...; maybe have a method on it returning the file name or so?
             TableSource::FileReader(reader) => write!(f, "TableReader implementation: {reader:?}"),
+            TableSource::CountedFileReader(reader) => {
+                write!(f, "TableReader implementation with counts: {reader:?}")
+            }
             TableSource::RLS(_) => write!(f, "Rule file"),
+            TableSource::Trie(_) => write!(f, "Shared table"),
+            TableSource::Cached(path, source) => write!(f, "{source} (cached in {path:?})"),
         }
     }
 }
@@ -105,60 +136,151 @@ impl Display for TableSource {
 #[derive(Debug)]
 pub enum TableStorage {
     /// Table is stored as a [`Trie`] in memory.
-    InMemory(Trie),
+    InMemory(Arc<Trie>),
     /// Table is stored on disk.
     OnDisk(TableSchema, Vec<TableSource>),
 }
 
 impl TableStorage {
+    /// Read the columns of the given types from a [`TableReader`].
+    fn read_columns(
+        reader: Box<dyn TableReader>,
+        column_types: &[DataTypeName],
+        dict: &RefCell<Dict>,
+    ) -> Result<Vec<VecT>, ReadingError> {
+        let mut builder_proxies: Vec<PhysicalBuilderProxyEnum> = column_types
+            .iter()
+            .map(|data_type| match data_type {
+                DataTypeName::String => {
+                    PhysicalBuilderProxyEnum::String(PhysicalStringColumnBuilderProxy::new(dict))
+                }
+                DataTypeName::I64 => PhysicalBuilderProxyEnum::I64(Default::default()),
+                DataTypeName::U128 => PhysicalBuilderProxyEnum::U128(Default::default()),
+                DataTypeName::U64 => PhysicalBuilderProxyEnum::U64(Default::default()),
+                DataTypeName::U32 => PhysicalBuilderProxyEnum::U32(Default::default()),
+                DataTypeName::Float => PhysicalBuilderProxyEnum::Float(Default::default()),
+                DataTypeName::Double => PhysicalBuilderProxyEnum::Double(Default::default()),
+            })
+            .collect();
+
+        reader.read_into_builder_proxies(&mut builder_proxies)?;
+
+        Ok(builder_proxies
+            .into_iter()
+            .map(|bp| bp.finalize())
+            .collect())
+    }
+
+    /// Keep each distinct row of the given columns once
+    /// and add a column containing the number of its occurrences.
+    fn count_rows(cols: Vec<VecT>, count_type: DataTypeName) -> Result<Vec<VecT>, ReadingError> {
+        let row_count = cols.first().map_or(0, VecT::len);
+        let compare_rows = |&left: &usize, &right: &usize| {
+            cols.iter()
+                .map(|col| {
+                    col.get(left)
+                        .expect("index is within the column")
+                        .compare(&col.get(right).expect("index is within the column"))
+                        .expect("values of the same column have the same type")
+                })
+                .find(|ordering| ordering.is_ne())
+                .unwrap_or(Ordering::Equal)
+        };
+
+        let mut rows = (0..row_count).collect::<Vec<_>>();
+        rows.sort_by(compare_rows);
+
+        let mut result = cols
+            .iter()
+            .map(|col| VecT::new(col.get_type()))
+            .collect::<Vec<_>>();
+        let mut counts = VecT::new(count_type.to_storage_type_name());
+
+        for group in rows.chunk_by(|left, right| compare_rows(left, right).is_eq()) {
+            for (col, result_col) in cols.iter().zip(result.iter_mut()) {
+                result_col.push(col.get(group[0]).expect("index is within the column"));
+            }
+
+            let count = group.len();
+            counts.push(match count_type {
+                DataTypeName::I64 => StorageValueT::I64(count.try_into()?),
+                DataTypeName::U64 => StorageValueT::U64(count.try_into()?),
+                DataTypeName::U32 => StorageValueT::U32(count.try_into()?),
+                _ => {
+                    return Err(ReadingError::TypeConversionError(
+                        count.to_string(),
+                        count_type.to_string(),
+                    ))
+                }
+            });
+        }
+
+        result.push(counts);
+        Ok(result)
+    }
+
     /// Load table from a given on-disk source
     fn load_from_disk(
         source: TableSource,
         schema: &TableSchema,
-        dict: &mut RefCell<Dict>,
-    ) -> Result<Trie, ReadingError> {
+        dict: &RefCell<Dict>,
+    ) -> Result<Arc<Trie>, ReadingError> {
         {
-            log::info!("Loading source {source}");
+            let _span = tracing::info_span!("load_table", source = %source).entered();
+            tracing::info!("Loading source {source}");
 
             let trie = match source {
                 TableSource::FileReader(reader) => {
-                    let mut builder_proxies: Vec<PhysicalBuilderProxyEnum> = schema
-                        .iter()
-                        .map(|data_type| match data_type {
-                            DataTypeName::String => PhysicalBuilderProxyEnum::String(
-                                PhysicalStringColumnBuilderProxy::new(dict),
-                            ),
-                            DataTypeName::I64 => PhysicalBuilderProxyEnum::I64(Default::default()),
-                            DataTypeName::U64 => PhysicalBuilderProxyEnum::U64(Default::default()),
-                            DataTypeName::U32 => PhysicalBuilderProxyEnum::U32(Default::default()),
-                            DataTypeName::Float => {
-                                PhysicalBuilderProxyEnum::Float(Default::default())
-                            }
-                            DataTypeName::Double => {
-                                PhysicalBuilderProxyEnum::Double(Default::default())
-                            }
-                        })
-                        .collect();
-
-                    reader.read_into_builder_proxies(&mut builder_proxies)?;
+                    let column_types = schema.iter().copied().collect::<Vec<_>>();
+                    let is_sorted = reader.is_sorted();
+                    let col_table = Self::read_columns(reader, &column_types, dict)?;
 
-                    let col_table: Vec<VecT> = builder_proxies
-                        .into_iter()
-                        .map(|bp| bp.finalize())
-                        .collect();
-                    Trie::from_cols(col_table)
+                    if is_sorted && Trie::cols_are_sorted(&col_table) {
+                        Arc::new(Trie::from_sorted_cols(col_table))
+                    } else {
+                        Arc::new(Trie::from_cols(col_table))
+                    }
+                }
+                TableSource::CountedFileReader(reader) => {
+                    let mut column_types = schema.iter().copied().collect::<Vec<_>>();
+                    let count_type = column_types
+                        .pop()
+                        .expect("counted tables contain a column for the counts");
+                    let col_table = Self::read_columns(reader, &column_types, dict)?;
+                    Arc::new(Trie::from_cols(Self::count_rows(col_table, count_type)?))
                 }
                 TableSource::RLS(table_rows) => {
-                    let rows: Vec<Vec<StorageValueT>> = table_rows
+                    let rows = table_rows
                         .iter()
                         .map(|row| {
                             row.iter()
-                                .cloned()
-                                .map(|val| val.to_storage_value_mut(dict.get_mut()))
-                                .collect()
+                                .map(|val| val.try_to_storage_value_mut(&mut dict.borrow_mut()))
+                                .collect::<Result<Vec<_>, _>>()
                         })
-                        .collect();
-                    Trie::from_rows(&rows)
+                        .collect::<Result<Vec<_>, _>>()?;
+                    Arc::new(Trie::from_rows(&rows))
+                }
+                TableSource::Trie(trie) => trie,
+                TableSource::Cached(path, source) => {
+                    match table_cache::read_table(&path, schema, dict) {
+                        Ok(trie) => {
+                            tracing::info!("Read cached table from {path:?}");
+                            Arc::new(trie)
+                        }
+                        Err(error) => {
+                            if error.kind() != std::io::ErrorKind::NotFound {
+                                tracing::warn!("Ignoring cached table {path:?}: {error}");
+                            }
+
+                            let trie = Self::load_from_disk(*source, schema, dict)?;
+                            if let Err(error) = table_cache::write_table(&path, &trie, schema, dict)
+                            {
+                                tracing::warn!("Failed to cache table in {path:?}: {error}");
+                            }
+
+                            trie
+                        }
+                    }
                 }
             };
 
@@ -167,10 +289,7 @@ impl TableStorage {
     }
 
     /// Function that makes sure that underlying table is available in memory.
-    pub fn into_memory<'a>(
-        &'a mut self,
-        dict: &mut RefCell<Dict>,
-    ) -> Result<&'a Trie, ReadingError> {
+    pub fn into_memory<'a>(&'a mut self, dict: &RefCell<Dict>) -> Result<&'a Trie, ReadingError> {
         match self {
             TableStorage::InMemory(_) => {}
             TableStorage::OnDisk(schema, sources) => {
@@ -180,7 +299,7 @@ impl TableStorage {
                     // If the trie results form multiple sources
                     // we load each source indivdually and then compute the union over all tries
 
-                    let mut loaded_tries = Vec::<Trie>::with_capacity(sources.len());
+                    let mut loaded_tries = Vec::<Arc<Trie>>::with_capacity(sources.len());
                     for source in sources.drain(0..) {
                         loaded_tries.push(Self::load_from_disk(source, schema, dict)?);
                     }
@@ -192,7 +311,7 @@ impl TableStorage {
                     let union_iter =
                         TrieScanEnum::TrieScanUnion(TrieScanUnion::new(loaded_tries_iters));
 
-                    materialize(&mut TrieScanPrune::new(union_iter)).unwrap()
+                    Arc::new(materialize(&mut TrieScanPrune::new(union_iter)).unwrap())
                 };
 
                 *self = TableStorage::InMemory(new_trie);
@@ -207,6 +326,12 @@ impl TableStorage {
     /// Return a reference to the stored trie.
     /// Returns `None` if trie is not in memory.
     pub fn get_trie(&self) -> Option<&Trie> {
+        self.get_shared_trie().map(Arc::as_ref)
+    }
+
+    /// Return a shared handle to the stored trie.
+    /// Returns `None` if trie is not in memory.
+    pub fn get_shared_trie(&self) -> Option<&Arc<Trie>> {
         if let TableStorage::InMemory(trie) = self {
             Some(trie)
         } else {
@@ -394,6 +519,39 @@ impl OrderedReferenceManager {
         Some(())
     }
 
+    /// Create a copy of this manager that shares all of its tries.
+    ///
+    /// Tables that are still stored on disk are loaded into memory first.
+    pub fn snapshot(&mut self, dict: &RefCell<Dict>) -> Result<Self, ReadingError> {
+        let mut map = HashMap::with_capacity(self.map.len());
+
+        for (id, status) in &mut self.map {
+            let status = match status {
+                TableStatus::Present(ordered_tries) => {
+                    let mut shared_tries = HashMap::with_capacity(ordered_tries.len());
+                    for (order, storage) in ordered_tries {
+                        storage.into_memory(dict)?;
+                        let trie = storage
+                            .get_shared_trie()
+                            .expect("Trie has been loaded into memory above.");
+
+                        shared_tries
+                            .insert(order.clone(), TableStorage::InMemory(Arc::clone(trie)));
+                    }
+
+                    TableStatus::Present(shared_tries)
+                }
+                TableStatus::Reference(reference_id, permutation) => {
+                    TableStatus::Reference(*reference_id, permutation.clone())
+                }
+            };
+
+            map.insert(*id, status);
+        }
+
+        Ok(Self { map })
+    }
+
     /// Return the number of rows contained in this table.
     ///
     /// TODO: Currently only counting of in-memory facts is supported, see <https://github.com/knowsys/nemo/issues/335>
@@ -426,7 +584,7 @@ impl ByteSized for OrderedReferenceManager {
 
 /// Struct that contains useful information about a trie
 /// as well as the actual owner of the trie.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 struct TableInfo {
     /// The name of the table.
     pub name: String,
@@ -450,7 +608,15 @@ pub struct DatabaseInstance {
     table_infos: HashMap<TableId, TableInfo>,
 
     /// Dictionary which stores the strings associates with abstract constants
-    dict_constants: RefCell<Dict>,
+    /// May be shared with other instances.
+    dict_constants: Rc<RefCell<Dict>>,
+    /// Index ordering the entries of `dict_constants` by their strings, if enabled
+    dict_order: Option<RefCell<DictionaryOrder>>,
+    /// Inverted index of the words of the entries of `dict_constants`, if enabled
+    dict_text_index: Option<RefCell<TextIndex>>,
+    /// Index of the entries of `dict_constants` by the length of their strings,
+    /// which is only filled once similar constants are looked up
+    dict_length_index: Rc<RefCell<LengthIndex>>,
 
     /// Lowest unused null value.
     current_null: u64,
@@ -479,17 +645,189 @@ impl Default for DatabaseInstance {
 impl DatabaseInstance {
     /// Create new [`DatabaseInstance`]
     pub fn new() -> Self {
-        let current_null = 1 << 63; // TODO: Think about a robust null representation method
+        Self::with_dictionary(Rc::new(RefCell::new(Dict::default())))
+    }
+
+    /// Create new [`DatabaseInstance`] which uses the given dictionary for abstract constants.
+    ///
+    /// This allows several instances to share the same dictionary,
+    /// and hence also to exchange tries via [`TableSource::Trie`].
+    pub fn with_dictionary(dict_constants: Rc<RefCell<Dict>>) -> Self {
+        let current_null = FIRST_NULL; // TODO: Think about a robust null representation method
 
         Self {
             storage_handler: OrderedReferenceManager::default(),
             table_infos: HashMap::new(),
-            dict_constants: RefCell::new(Dict::default()),
+            dict_constants,
+            dict_order: None,
+            dict_text_index: None,
+            dict_length_index: Rc::new(RefCell::new(LengthIndex::default())),
             current_null,
             current_id: TableId::default(),
         }
     }
 
+    /// Maintain a [`DictionaryOrder`] for the dictionary of abstract constants,
+    /// which allows to compare and restrict constants by their strings without decoding them.
+    pub fn with_dictionary_order(mut self) -> Self {
+        self.dict_order = Some(RefCell::new(DictionaryOrder::default()));
+        self
+    }
+
+    /// Return the [`DictionaryOrder`] of the dictionary of abstract constants,
+    /// updated to cover all of its current entries,
+    /// or `None` if it has not been enabled via [`DatabaseInstance::with_dictionary_order`].
+    pub fn dictionary_order(&self) -> Option<Ref<'_, DictionaryOrder>> {
+        let order = self.dict_order.as_ref()?;
+        order.borrow_mut().update(&*self.get_dict_constants());
+
+        Some(order.borrow())
+    }
+
+    /// Compare the strings represented by the given abstract constants using the [`DictionaryOrder`].
+    ///
+    /// Returns `None` if the order is not enabled or one of the values does not represent a dictionary entry.
+    pub fn compare_constants(&self, left: u64, right: u64) -> Option<Ordering> {
+        let order = self.dictionary_order()?;
+        order.compare(usize::try_from(left).ok()?, usize::try_from(right).ok()?)
+    }
+
+    /// Maintain a [`TextIndex`] for the dictionary of abstract constants,
+    /// which allows to find the constants matching a [`TextQuery`] without checking every entry.
+    pub fn enable_text_index(&mut self) {
+        self.dict_text_index
+            .get_or_insert_with(|| RefCell::new(TextIndex::default()));
+    }
+
+    /// Return whether a [`TextIndex`] is maintained, see [`DatabaseInstance::enable_text_index`].
+    pub fn uses_text_index(&self) -> bool {
+        self.dict_text_index.is_some()
+    }
+
+    /// Return the abstract constants whose strings match the given [`TextQuery`], in increasing order.
+    ///
+    /// Uses the [`TextIndex`] if it is enabled and checks every entry of the dictionary otherwise.
+    pub fn text_matches(&self, query: &TextQuery) -> Vec<u64> {
+        let dict = self.get_dict_constants();
+
+        let indices = match &self.dict_text_index {
+            Some(index) => {
+                index.borrow_mut().update(&*dict);
+                index.borrow().matching_indices(query)
+            }
+            None => query.matching_indices(&*dict),
+        };
+
+        indices.into_iter().map(|index| index as u64).collect()
+    }
+
+    /// Return the abstract constants whose strings are within edit distance `max_distance` of the given text,
+    /// in increasing order.
+    pub fn similar_constants(&self, text: &str, max_distance: usize) -> Vec<u64> {
+        let dict = self.get_dict_constants();
+        self.dict_length_index.borrow_mut().update(&*dict);
+
+        self.dict_length_index
+            .borrow()
+            .similar_indices(&*dict, text, max_distance)
+            .into_iter()
+            .map(|index| index as u64)
+            .collect()
+    }
+
+    /// Return a [`SimilarValuesFunction`] looking up the abstract constants
+    /// that are similar to a given abstract constant.
+    ///
+    /// The [`LengthIndex`] is brought up to date before, as the dictionary can not change while scanning tries.
+    fn similar_values_function(&self) -> SimilarValuesFunction {
+        self.dict_length_index
+            .borrow_mut()
+            .update(&*self.get_dict_constants());
+
+        let dict = Rc::clone(&self.dict_constants);
+        let length_index = Rc::clone(&self.dict_length_index);
+
+        Rc::new(move |reference, max_distance| {
+            let dict = dict.borrow();
+            let Some(entry) = usize::try_from(reference)
+                .ok()
+                .and_then(|reference| dict.entry(reference))
+            else {
+                return Vec::new();
+            };
+            let Some(text) = searched_text(&entry) else {
+                return Vec::new();
+            };
+
+            length_index
+                .borrow()
+                .similar_indices(&*dict, text, max_distance)
+                .into_iter()
+                .map(|index| index as u64)
+                .collect()
+        })
+    }
+
+    /// Replace the [`TextQuery`]s and the constants that values need to be similar to
+    /// in the given [`ValueAssignment`]s
+    /// by restricting their columns to the abstract constants matching all of them.
+    fn resolve_text_queries(
+        &self,
+        assignments: &HashMap<usize, ValueAssignment>,
+    ) -> HashMap<usize, ValueAssignment> {
+        assignments
+            .iter()
+            .map(|(&column, assignment)| {
+                let mut assignment = assignment.clone();
+
+                for query in std::mem::take(&mut assignment.text_queries) {
+                    let matches = self.text_matches(&query);
+
+                    assignment.allowed_values = Some(match assignment.allowed_values {
+                        Some(allowed) => allowed
+                            .into_iter()
+                            .filter(|value| match value {
+                                DataValueT::U64(index) => matches.binary_search(index).is_ok(),
+                                _ => false,
+                            })
+                            .collect(),
+                        None => matches.into_iter().map(DataValueT::U64).collect(),
+                    });
+                }
+
+                let (constants, columns) = std::mem::take(&mut assignment.similar_values)
+                    .into_iter()
+                    .partition::<Vec<_>, _>(|(value, _)| value.column_index().is_none());
+                assignment.similar_values = columns;
+
+                for (value, max_distance) in constants {
+                    let matches = match value {
+                        FilterValue::Constant(DataValueT::String(value)) => {
+                            let value: String = value.into();
+                            searched_text(&value)
+                                .map(|text| self.similar_constants(text, max_distance))
+                                .unwrap_or_default()
+                        }
+                        _ => Vec::new(),
+                    };
+
+                    assignment.allowed_values = Some(match assignment.allowed_values {
+                        Some(allowed) => allowed
+                            .into_iter()
+                            .filter(|value| match value {
+                                DataValueT::U64(index) => matches.binary_search(index).is_ok(),
+                                _ => false,
+                            })
+                            .collect(),
+                        None => matches.into_iter().map(DataValueT::U64).collect(),
+                    });
+                }
+
+                (column, assignment)
+            })
+            .collect()
+    }
+
     /// Return the number of rows for a given table.
     ///
     /// TODO: Currently only counting of in-memory facts is supported, see <https://github.com/knowsys/nemo/issues/335>
@@ -497,6 +835,18 @@ impl DatabaseInstance {
         self.storage_handler.count_rows(table_id)
     }
 
+    /// Return the lowest null value that has not been used yet.
+    pub fn next_null(&self) -> u64 {
+        self.current_null
+    }
+
+    /// Make sure that newly created nulls are at least as large as the given value.
+    ///
+    /// This is needed when adding tables containing nulls that were created by another instance.
+    pub fn reserve_nulls(&mut self, next_null: u64) {
+        self.current_null = self.current_null.max(next_null);
+    }
+
     /// Return the current number of tables.
     pub fn num_tables(&self) -> usize {
         self.table_infos.len()
@@ -520,11 +870,42 @@ impl DatabaseInstance {
         self.table_schema(id).arity()
     }
 
+    /// Create a copy of this instance that shares its tries and its dictionary.
+    ///
+    /// Tables that are still stored on disk are loaded into memory first.
+    /// Since tries are never modified, tables added to or deleted from either instance
+    /// afterwards do not affect the other one.
+    pub fn snapshot(&mut self) -> Result<Self, ReadingError> {
+        let storage_handler = self.storage_handler.snapshot(&self.dict_constants)?;
+
+        Ok(Self {
+            storage_handler,
+            table_infos: self.table_infos.clone(),
+            dict_constants: Rc::clone(&self.dict_constants),
+            dict_order: self
+                .dict_order
+                .as_ref()
+                .map(|order| RefCell::new(order.borrow().clone())),
+            dict_text_index: self
+                .dict_text_index
+                .as_ref()
+                .map(|index| RefCell::new(index.borrow().clone())),
+            dict_length_index: Rc::clone(&self.dict_length_index),
+            current_null: self.current_null,
+            current_id: self.current_id,
+        })
+    }
+
     /// Returns a reference to the dictionary used for associating abstract constants with strings.
     pub fn get_dict_constants(&self) -> Ref<'_, Dict> {
         self.dict_constants.borrow()
     }
 
+    /// Returns a shared handle to the dictionary used for associating abstract constants with strings.
+    pub fn shared_dict_constants(&self) -> Rc<RefCell<Dict>> {
+        Rc::clone(&self.dict_constants)
+    }
+
     /// Register a new table under a given name and schema.
     /// Returns the [`TableId`] with which the new table can be addressed.
     pub fn register_table(&mut self, name: &str, schema: TableSchema) -> TableId {
@@ -537,7 +918,7 @@ impl DatabaseInstance {
     /// Add a new trie.
     pub fn add_trie(&mut self, id: TableId, order: ColumnOrder, trie: Trie) {
         self.storage_handler
-            .add_present(id, order, TableStorage::InMemory(trie));
+            .add_present(id, order, TableStorage::InMemory(Arc::new(trie)));
     }
 
     /// Register table and add a new trie.
@@ -656,6 +1037,8 @@ impl DatabaseInstance {
         id: TableId,
         order: &ColumnOrder,
     ) -> Result<(), ReadingError> {
+        let _span = tracing::debug_span!("make_available", table = %self.table_name(id)).entered();
+
         let arity = self.table_arity(id);
         let available_orders = self
             .storage_handler
@@ -670,7 +1053,7 @@ impl DatabaseInstance {
             .storage_handler
             .table_storage_mut(id, &closest_order.clone())
             .expect("Call to search_closest_ordered should give us an existing order")
-            .into_memory(&mut self.dict_constants)?;
+            .into_memory(&self.dict_constants)?;
 
         if !reorder.is_identity() {
             TimedCode::instance()
@@ -763,6 +1146,17 @@ impl DatabaseInstance {
                 self.get_iterator_node(execution_tree.root(), type_tree, computation_results)?;
             let cut_bottom = execution_tree.cut_bottom();
 
+            if execution_tree.first_match_only() {
+                return Ok(iter_opt.and_then(|iter| {
+                    let mut trie_scan = TrieScanPrune::new(iter);
+                    let prefix_length = trie_scan.column_types().len().checked_sub(cut_bottom)?;
+                    materialize_first_row(&mut TrieScanDistinctPrefix::new(
+                        &mut trie_scan,
+                        prefix_length,
+                    ))
+                }));
+            }
+
             Ok(iter_opt
                 .and_then(|iter| materialize_up_to(&mut TrieScanPrune::new(iter), cut_bottom)))
         }
@@ -782,7 +1176,9 @@ impl DatabaseInstance {
         let mut removed_temp_ids = HashSet::<usize>::new();
 
         for (tree_id, mut execution_tree) in execution_trees {
-            log::info!("Execution step: {}", execution_tree.name());
+            let _span =
+                tracing::info_span!("execution_tree", name = %execution_tree.name()).entered();
+            tracing::info!("Execution step: {}", execution_tree.name());
 
             execution_tree.satisfy_leapfrog_triejoin();
 
@@ -790,7 +1186,7 @@ impl DatabaseInstance {
                 execution_tree = simplified_tree;
             } else {
                 removed_temp_ids.insert(tree_id);
-                log::info!("Result is empty. No computation was required.");
+                tracing::info!("Result is empty. No computation was required.");
 
                 continue;
             }
@@ -826,7 +1222,7 @@ impl DatabaseInstance {
                 // Add new trie to the appropriate place
                 match execution_tree.result() {
                     ExecutionResult::Temporary => {
-                        log::info!(
+                        tracing::info!(
                             "Saved temporary table: {} entries ({})",
                             new_trie.row_num(),
                             new_trie.size_bytes()
@@ -842,7 +1238,7 @@ impl DatabaseInstance {
                     ExecutionResult::Permanent(order, name) => {
                         let new_id = self.register_table(name, schema);
 
-                        log::info!(
+                        tracing::info!(
                             "Saved permanent table {new_id} - {name} with {} entries ({})",
                             new_trie.row_num(),
                             new_trie.size_bytes()
@@ -856,7 +1252,7 @@ impl DatabaseInstance {
                     }
                 }
             } else {
-                log::info!("Trie does not contain any elements");
+                tracing::info!("Trie does not contain any elements");
 
                 computation_results.insert(tree_id, ComputationResult::Empty);
             }
@@ -892,7 +1288,33 @@ impl DatabaseInstance {
         self.storage_handler
             .table_storage_mut(id, order)
             .expect("Function assumes that there is a table with the given id and order.")
-            .into_memory(&mut self.dict_constants)
+            .into_memory(&self.dict_constants)
+    }
+
+    /// Return a reference to a trie with the given id and order.
+    /// Returns `None` if no table under the given id and order exists
+    /// or if the trie is not available in memory.
+    pub fn get_trie_if_loaded(&self, id: TableId, order: &ColumnOrder) -> Option<&Trie> {
+        self.storage_handler.table_storage(id, order)?.get_trie()
+    }
+
+    /// Return a shared handle to a trie identified by its id and order,
+    /// which can be added to another instance using the same dictionary via [`TableSource::Trie`].
+    /// If the trie is not available in memory, this function will load it.
+    /// Panics if no table under the given id exists.
+    pub fn get_shared_trie_or_load(
+        &mut self,
+        id: TableId,
+        order: &ColumnOrder,
+    ) -> Result<Arc<Trie>, ReadingError> {
+        self.make_available_in_memory(id, order)?;
+
+        Ok(Arc::clone(
+            self.storage_handler
+                .table_storage(id, order)
+                .and_then(TableStorage::get_shared_trie)
+                .expect("Trie has been loaded into memory above."),
+        ))
     }
 
     /// Returns an iterator that provides serialized fields for each row in the specified table.
@@ -970,6 +1392,9 @@ impl DatabaseInstance {
                 StorageValueIteratorT::U32(iter) => to_data_column_iter!(U32, iter, idx),
                 StorageValueIteratorT::U64(iter) => to_data_column_iter!(U64, iter, idx),
                 StorageValueIteratorT::I64(iter) => to_data_column_iter!(I64, iter, idx),
+                StorageValueIteratorT::U128(iter) => {
+                    to_data_column_iter_no_string!(U128, iter, idx)
+                }
                 StorageValueIteratorT::Float(iter) => {
                     to_data_column_iter_no_string!(Float, iter, idx)
                 }
@@ -1153,10 +1578,13 @@ impl DatabaseInstance {
                 )?;
 
                 if let Some(subiterator) = subiterator_opt {
-                    let restrict_scan = TrieScanRestrictValues::new(
+                    let assignments = self.resolve_text_queries(assignments);
+                    let similar_values = self.similar_values_function();
+                    let restrict_scan = TrieScanRestrictValues::with_similar_values(
                         &mut self.dict_constants.borrow_mut(),
                         subiterator,
-                        assignments,
+                        &assignments,
+                        similar_values,
                     );
                     Ok(Some(TrieScanEnum::TrieScanRestrictValues(restrict_scan)))
                 } else {
@@ -1263,6 +1691,50 @@ impl DatabaseInstance {
 
         status.size_bytes()
     }
+
+    /// Return the [`MemoryFootprint`] of the table with the given id, in all of its orders.
+    ///
+    /// # Panics
+    /// Panics if there is no table with the given id.
+    pub fn memory_footprint(&self, id: TableId) -> MemoryFootprint {
+        let status = self
+            .storage_handler
+            .map
+            .get(&id)
+            .expect("Function assumes that there is a table with the given id.");
+
+        let mut result = MemoryFootprint::default();
+        if let TableStatus::Present(storages) = status {
+            for trie in storages.values().filter_map(TableStorage::get_trie) {
+                result.add(&MemoryFootprint::of_trie(trie));
+            }
+        }
+
+        result
+    }
+
+    /// Return the [`MemoryFootprint`] of the dictionary, which may be shared with other instances,
+    /// including its [`DictionaryOrder`] and [`TextIndex`] if enabled and its [`LengthIndex`].
+    pub fn dictionary_footprint(&self) -> MemoryFootprint {
+        let size_order = self
+            .dict_order
+            .as_ref()
+            .map_or(ByteSize(0), |order| order.borrow().size_bytes());
+        let size_text_index = self
+            .dict_text_index
+            .as_ref()
+            .map_or(ByteSize(0), |index| index.borrow().size_bytes());
+        let size_length_index = self.dict_length_index.borrow().size_bytes();
+
+        MemoryFootprint::new(
+            StructureKind::Dictionary,
+            None,
+            self.get_dict_constants().size_bytes()
+                + size_order
+                + size_text_index
+                + size_length_index,
+        )
+    }
 }
 
 impl ByteSized for DatabaseInstance {
@@ -1275,7 +1747,8 @@ impl ByteSized for DatabaseInstance {
 mod test {
     use crate::{
         columnar::traits::column::Column,
-        datatypes::{DataTypeName, StorageValueT},
+        datatypes::{storage_value::VecT, DataTypeName, StorageValueT},
+        dictionary::{Dictionary, TextQuery, FIRST_NULL},
         management::{
             database::{ColumnOrder, TableId},
             ByteSized, ExecutionPlan,
@@ -1288,6 +1761,8 @@ mod test {
         util::{make_column_with_intervals_t, mapping::permutation::Permutation},
     };
 
+    use std::cmp::Ordering;
+
     use super::{DatabaseInstance, OrderedReferenceManager, TableStorage};
 
     #[test]
@@ -1339,6 +1814,99 @@ mod test {
         assert!(instance.size_bytes() < last_size);
     }
 
+    #[test]
+    fn snapshot_shares_tries() {
+        let column_a = make_column_with_intervals_t(&[1, 2, 3], &[0]);
+        let column_b = make_column_with_intervals_t(&[1, 2, 3, 4], &[0]);
+
+        let mut schema = TableSchema::new();
+        schema.add_entry(DataTypeName::U64);
+
+        let mut instance = DatabaseInstance::new();
+        let trie_a_id = instance.register_add_trie(
+            "A",
+            schema.clone(),
+            ColumnOrder::default(),
+            Trie::new(vec![column_a]),
+        );
+
+        let mut snapshot = instance.snapshot().unwrap();
+        assert!(std::ptr::eq(
+            instance.get_trie(trie_a_id, &ColumnOrder::default()),
+            snapshot.get_trie(trie_a_id, &ColumnOrder::default())
+        ));
+
+        let trie_b_id = snapshot.register_add_trie(
+            "B",
+            schema,
+            ColumnOrder::default(),
+            Trie::new(vec![column_b]),
+        );
+        snapshot.delete(trie_a_id);
+
+        assert_eq!(instance.num_tables(), 1);
+        assert_eq!(instance.count_rows(&trie_a_id), 3);
+        assert_eq!(snapshot.num_tables(), 1);
+        assert_eq!(snapshot.count_rows(&trie_b_id), 4);
+        assert_ne!(trie_a_id, trie_b_id);
+    }
+
+    #[test]
+    fn dictionary_order() {
+        let instance = DatabaseInstance::new();
+        assert!(instance.dictionary_order().is_none());
+
+        let instance = DatabaseInstance::new().with_dictionary_order();
+        let dict = instance.shared_dict_constants();
+        let b = dict.borrow_mut().add("b".to_string()) as u64;
+        let a = dict.borrow_mut().add("a".to_string()) as u64;
+
+        assert_eq!(instance.compare_constants(a, b), Some(Ordering::Less));
+
+        let c = dict.borrow_mut().add("c".to_string()) as u64;
+        assert_eq!(instance.compare_constants(c, b), Some(Ordering::Greater));
+        assert_eq!(instance.compare_constants(c, FIRST_NULL), None);
+        assert_eq!(
+            instance.dictionary_order().unwrap().len(),
+            dict.borrow().len()
+        );
+    }
+
+    #[test]
+    fn text_matches() {
+        let mut instance = DatabaseInstance::new();
+        let dict = instance.shared_dict_constants();
+        let apple = dict.borrow_mut().add("STRING:Green apple".to_string()) as u64;
+        dict.borrow_mut().add("STRING:Pear".to_string());
+
+        let query = TextQuery::new("app*");
+        assert_eq!(instance.text_matches(&query), vec![apple]);
+
+        instance.enable_text_index();
+        assert_eq!(instance.text_matches(&query), vec![apple]);
+
+        let applause = dict.borrow_mut().add("CONSTANT:Applause".to_string()) as u64;
+        let mut expected = vec![apple, applause];
+        expected.sort_unstable();
+        assert_eq!(instance.text_matches(&query), expected);
+    }
+
+    #[test]
+    fn similar_constants() {
+        let instance = DatabaseInstance::new();
+        let dict = instance.shared_dict_constants();
+        let smith = dict.borrow_mut().add("STRING:Smith".to_string()) as u64;
+        dict.borrow_mut().add("STRING:Jones".to_string());
+
+        assert_eq!(instance.similar_constants("Smyth", 1), vec![smith]);
+
+        let smithe = dict.borrow_mut().add("CONSTANT:Smithe".to_string()) as u64;
+        let mut expected = vec![smith, smithe];
+        expected.sort_unstable();
+        assert_eq!(instance.similar_constants("Smith", 1), expected);
+        assert!(instance.similar_constants("Smith", 0).len() == 1);
+    }
+
     fn test_casting_execution_plan() -> (ExecutionPlan, usize) {
         // ExecutionPlan:
         // Union
@@ -1581,4 +2149,21 @@ mod test {
             &expected_order
         );
     }
+
+    #[test]
+    fn count_rows() {
+        let cols = vec![
+            VecT::U64(vec![2, 1, 2, 1, 2]),
+            VecT::I64(vec![-1, 5, -1, 4, -1]),
+        ];
+
+        assert_eq!(
+            TableStorage::count_rows(cols, DataTypeName::I64).unwrap(),
+            vec![
+                VecT::U64(vec![1, 1, 2]),
+                VecT::I64(vec![4, 5, -1]),
+                VecT::I64(vec![1, 1, 3]),
+            ]
+        );
+    }
 }
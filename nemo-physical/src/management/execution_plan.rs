@@ -161,6 +161,8 @@ struct ExecutionOutNode {
     name: String,
     /// Amount of layers that will not be considered in the final output.
     cut_bottom_layers: usize,
+    /// Whether only the first row of the result is needed.
+    first_match_only: bool,
 }
 
 /// A DAG representing instructions for generating new tables.
@@ -311,6 +313,7 @@ impl ExecutionPlan {
         result: ExecutionResult,
         name: &str,
         cut_bottom_layers: usize,
+        first_match_only: bool,
     ) -> usize {
         let id = node.id();
 
@@ -319,6 +322,7 @@ impl ExecutionPlan {
             result,
             name: String::from(name),
             cut_bottom_layers,
+            first_match_only,
         });
 
         id
@@ -327,7 +331,7 @@ impl ExecutionPlan {
     /// Designate a [`ExecutionNode`] as an "output" node that will produce a temporary table.
     /// Returns an id which will later be associated with the result of the computation.
     pub fn write_temporary(&mut self, node: ExecutionNodeRef, tree_name: &str) -> usize {
-        self.push_out_node(node, ExecutionResult::Temporary, tree_name, 0)
+        self.push_out_node(node, ExecutionResult::Temporary, tree_name, 0, false)
     }
 
     /// Designate a [`ExecutionNode`] as an "output" node that will produce a temporary table.
@@ -339,7 +343,20 @@ impl ExecutionPlan {
         tree_name: &str,
         cut: usize,
     ) -> usize {
-        self.push_out_node(node, ExecutionResult::Temporary, tree_name, cut)
+        self.push_out_node(node, ExecutionResult::Temporary, tree_name, cut, false)
+    }
+
+    /// Designate a [`ExecutionNode`] as an "output" node that will produce a temporary table
+    /// containing only the first row of the result, which makes it possible to stop the computation early.
+    /// Returns an id which will later be associated with the result of the computation.
+    /// The parameter `cut` indicates how many of the last layers will not be needed.
+    pub fn write_temporary_first_match(
+        &mut self,
+        node: ExecutionNodeRef,
+        tree_name: &str,
+        cut: usize,
+    ) -> usize {
+        self.push_out_node(node, ExecutionResult::Temporary, tree_name, cut, true)
     }
 
     /// Designate a [`ExecutionNode`] as an "output" node that will produce a permament table (in its default order).
@@ -367,6 +384,7 @@ impl ExecutionPlan {
             ExecutionResult::Permanent(order, String::from(table_name)),
             tree_name,
             0,
+            false,
         )
     }
 
@@ -469,6 +487,7 @@ impl ExecutionPlan {
                 result: out_node.result.clone(),
                 name: out_node.name.clone(),
                 cut_bottom_layers: out_node.cut_bottom_layers,
+                first_match_only: out_node.first_match_only,
             });
 
             result.push((id, ExecutionTree::new(subtree)));
@@ -516,6 +535,11 @@ impl ExecutionTree {
         self.0.out_nodes[0].cut_bottom_layers
     }
 
+    /// Whether only the first row of the result is needed
+    pub fn first_match_only(&self) -> bool {
+        self.0.out_nodes[0].first_match_only
+    }
+
     fn ascii_tree_recursive(node: ExecutionNodeRef) -> Tree {
         let node_rc = node.get_rc();
         let node_operation = &node_rc.borrow().operation;
@@ -789,6 +813,7 @@ impl ExecutionTree {
             result: self.result().clone(),
             name: String::from(self.name()),
             cut_bottom_layers: self.cut_bottom(),
+            first_match_only: self.first_match_only(),
         });
 
         Some(ExecutionTree::new(simplified_tree))
@@ -869,10 +894,16 @@ impl ExecutionTree {
 
                 Self::satisfy_leapfrog_recurisve(subnode.clone(), permutation);
             }
-            ExecutionOperation::SelectEqual(subnode, _classes) => {
-                // TODO: A few other changes are needed to make this a bit simpler.
-                // Will update it then
-                assert!(permutation.is_identity());
+            ExecutionOperation::SelectEqual(subnode, classes) => {
+                // Selecting equal columns does not change the arity,
+                // so the same reordering is applied to the columns of the subnode
+                for class in classes.iter_mut() {
+                    for column in class.iter_mut() {
+                        *column = permutation.get(*column);
+                    }
+                    class.sort();
+                }
+
                 Self::satisfy_leapfrog_recurisve(subnode.clone(), permutation);
             }
             ExecutionOperation::AppendColumns(subnode, _instructions) => {
@@ -1151,4 +1182,42 @@ mod test {
 
         compare_leapfrog_trees(&test_tree, &expected_orders, &expected_bindings);
     }
+
+    #[test]
+    fn test_satisfy_leapfrog_select_equal() {
+        let mut test_plan = ExecutionPlan::default();
+        let mut current_id = TableId::default();
+
+        let node_a = test_plan.fetch_existing(current_id.increment()); // X Y
+        let node_b = test_plan.fetch_existing(current_id.increment()); // Y X Y'
+        let node_b_select = test_plan.select_equal(node_b, vec![vec![0, 2]]);
+
+        // Order: X Y Y'
+        let join_bindings = JoinBindings::new(vec![vec![0, 1], vec![1, 0, 2]]);
+
+        let node_join = test_plan.join(vec![node_a, node_b_select], join_bindings);
+        test_plan.write_temporary(node_join, "Test");
+
+        let mut test_tree = ExecutionTree::new(test_plan);
+        test_tree.satisfy_leapfrog_triejoin();
+
+        let mut current_id = TableId::default();
+        let mut expected_orders = HashMap::<TableId, ColumnOrder>::new();
+        expected_orders.insert(current_id.increment(), ColumnOrder::from_vector(vec![0, 1]));
+        expected_orders.insert(
+            current_id.increment(),
+            ColumnOrder::from_vector(vec![1, 0, 2]),
+        );
+
+        let mut expected_bindings = HashMap::<usize, JoinBindings>::new();
+        expected_bindings.insert(3, JoinBindings::new(vec![vec![0, 1], vec![0, 1, 2]]));
+
+        compare_leapfrog_trees(&test_tree, &expected_orders, &expected_bindings);
+
+        let operation = &test_tree.0.nodes[2].0.as_ref().borrow().operation;
+        match operation {
+            ExecutionOperation::SelectEqual(_, classes) => assert_eq!(classes, &vec![vec![1, 2]]),
+            _ => panic!("Expected a select operation"),
+        }
+    }
 }
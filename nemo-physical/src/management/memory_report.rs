@@ -0,0 +1,320 @@
+//! Detailed reports of the memory consumed by the physical structures of a database.
+//!
+//! In contrast to [`ByteSized`], which only returns the total size of a structure,
+//! a [`MemoryFootprint`] breaks this size down by the [`StructureKind`]s it consists of
+//! and by the [`StorageTypeName`] of the columns,
+//! which helps deciding e.g. whether run-length encoding pays off for some data.
+//! A [`MemoryReport`] arranges named footprints in a tree, e.g. one node per predicate.
+
+use std::{collections::BTreeMap, fmt::Display};
+
+use bytesize::ByteSize;
+
+use crate::{
+    columnar::{column_types::interval::ColumnWithIntervalsT, traits::column::ColumnEnum},
+    datatypes::{RunLengthEncodable, StorageTypeName},
+    tabular::table_types::trie::Trie,
+};
+
+use super::ByteSized;
+
+/// Kinds of physical structures whose memory is reported separately
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum StructureKind {
+    /// Data of columns that store each value
+    VectorColumn,
+    /// Data of run-length encoded columns
+    RleColumn,
+    /// Data of columns stored as bitvectors with rank and select support
+    RankSelectColumn,
+    /// Columns storing where the intervals of a layer of a trie start
+    IntervalStarts,
+    /// Dictionaries storing the strings of abstract constants
+    Dictionary,
+}
+
+impl Display for StructureKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StructureKind::VectorColumn => write!(f, "vector columns"),
+            StructureKind::RleColumn => write!(f, "RLE columns"),
+            StructureKind::RankSelectColumn => write!(f, "rank/select columns"),
+            StructureKind::IntervalStarts => write!(f, "interval starts"),
+            StructureKind::Dictionary => write!(f, "dictionary"),
+        }
+    }
+}
+
+/// Memory consumed by some physical structures,
+/// broken down by the kind of the structures and by the types of the columns.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MemoryFootprint {
+    by_kind: BTreeMap<StructureKind, ByteSize>,
+    /// Memory of each column type, in the order in which the types were added
+    by_column_type: Vec<(StorageTypeName, ByteSize)>,
+}
+
+impl MemoryFootprint {
+    /// Create a [`MemoryFootprint`] of a single structure of the given kind,
+    /// which belongs to a column of the given type, if any.
+    pub fn new(
+        kind: StructureKind,
+        column_type: Option<StorageTypeName>,
+        memory: ByteSize,
+    ) -> Self {
+        let mut result = Self::default();
+        result.by_kind.insert(kind, memory);
+        if let Some(column_type) = column_type {
+            result.by_column_type.push((column_type, memory));
+        }
+
+        result
+    }
+
+    /// Add the memory of the given footprint to this one.
+    pub fn add(&mut self, other: &MemoryFootprint) {
+        for (kind, memory) in &other.by_kind {
+            *self.by_kind.entry(*kind).or_default() += *memory;
+        }
+        for &(column_type, memory) in &other.by_column_type {
+            match self
+                .by_column_type
+                .iter_mut()
+                .find(|(other_type, _)| *other_type == column_type)
+            {
+                Some((_, total)) => *total += memory,
+                None => self.by_column_type.push((column_type, memory)),
+            }
+        }
+    }
+
+    /// Return the total memory.
+    pub fn total(&self) -> ByteSize {
+        self.by_kind
+            .values()
+            .fold(ByteSize(0), |total, memory| total + *memory)
+    }
+
+    /// Return the memory consumed by structures of the given kind.
+    pub fn kind(&self, kind: StructureKind) -> ByteSize {
+        self.by_kind.get(&kind).copied().unwrap_or_default()
+    }
+
+    /// Return the memory consumed by the data of columns of the given type.
+    ///
+    /// This does not include the interval starts of the columns.
+    pub fn column_type(&self, column_type: StorageTypeName) -> ByteSize {
+        self.by_column_type
+            .iter()
+            .find(|(other_type, _)| *other_type == column_type)
+            .map(|(_, memory)| *memory)
+            .unwrap_or_default()
+    }
+
+    /// Return the memory by kind of structure.
+    pub fn by_kind(&self) -> &BTreeMap<StructureKind, ByteSize> {
+        &self.by_kind
+    }
+
+    /// Return the memory consumed by the data of columns by their type.
+    pub fn by_column_type(&self) -> &[(StorageTypeName, ByteSize)] {
+        &self.by_column_type
+    }
+
+    fn column<T: RunLengthEncodable>(
+        column: &ColumnEnum<T>,
+        column_type: Option<StorageTypeName>,
+        data: bool,
+    ) -> Self {
+        let kind = match (data, column) {
+            (false, _) => StructureKind::IntervalStarts,
+            (true, ColumnEnum::ColumnVector(_)) => StructureKind::VectorColumn,
+            (true, ColumnEnum::ColumnRle(_)) => StructureKind::RleColumn,
+            (true, ColumnEnum::ColumnRankSelect(_)) => StructureKind::RankSelectColumn,
+        };
+
+        Self::new(kind, column_type, column.size_bytes())
+    }
+
+    /// Compute the [`MemoryFootprint`] of a column of a trie.
+    pub fn of_column(column: &ColumnWithIntervalsT) -> Self {
+        macro_rules! footprint {
+            ($column:ident) => {{
+                let (data, intervals) = $column.as_parts();
+                let mut result = Self::column(data, Some(column.get_type()), true);
+                result.add(&Self::column(intervals, None, false));
+                result
+            }};
+        }
+
+        match column {
+            ColumnWithIntervalsT::U32(column) => footprint!(column),
+            ColumnWithIntervalsT::U64(column) => footprint!(column),
+            ColumnWithIntervalsT::I64(column) => footprint!(column),
+            ColumnWithIntervalsT::U128(column) => footprint!(column),
+            ColumnWithIntervalsT::Float(column) => footprint!(column),
+            ColumnWithIntervalsT::Double(column) => footprint!(column),
+        }
+    }
+
+    /// Compute the [`MemoryFootprint`] of a trie.
+    pub fn of_trie(trie: &Trie) -> Self {
+        let mut result = Self::default();
+        for column in trie.columns() {
+            result.add(&Self::of_column(column));
+        }
+
+        result
+    }
+}
+
+impl Display for MemoryFootprint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.total())?;
+
+        let kinds = self
+            .by_kind
+            .iter()
+            .filter(|(_, memory)| memory.0 > 0)
+            .map(|(kind, memory)| format!("{kind} {memory}"))
+            .collect::<Vec<_>>();
+        if !kinds.is_empty() {
+            write!(f, ": {}", kinds.join(", "))?;
+        }
+
+        Ok(())
+    }
+}
+
+/// A tree of named [`MemoryFootprint`]s, where the footprint of each node includes those of its children.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MemoryReport {
+    name: String,
+    footprint: MemoryFootprint,
+    children: Vec<MemoryReport>,
+}
+
+impl MemoryReport {
+    /// Create a new [`MemoryReport`] without children.
+    pub fn new(name: &str, footprint: MemoryFootprint) -> Self {
+        Self {
+            name: name.to_string(),
+            footprint,
+            children: Vec::new(),
+        }
+    }
+
+    /// Create a new [`MemoryReport`] that only accounts for the memory of its children.
+    pub fn new_block(name: &str) -> Self {
+        Self::new(name, MemoryFootprint::default())
+    }
+
+    /// Add a child to this report.
+    pub fn add_child(&mut self, child: MemoryReport) {
+        self.footprint.add(&child.footprint);
+        self.children.push(child);
+    }
+
+    /// Return the name of this report.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Return the footprint of this report, including that of all its children.
+    pub fn footprint(&self) -> &MemoryFootprint {
+        &self.footprint
+    }
+
+    /// Return the children of this report.
+    pub fn children(&self) -> &[MemoryReport] {
+        &self.children
+    }
+
+    /// Return the child with the given name, if any.
+    pub fn child(&self, name: &str) -> Option<&MemoryReport> {
+        self.children.iter().find(|child| child.name == name)
+    }
+
+    /// Return an [`ascii_tree::Tree`] representation,
+    /// where the children of each node are sorted by decreasing memory.
+    pub fn ascii_tree(&self) -> ascii_tree::Tree {
+        let node = format!("{} ({})", self.name, self.footprint);
+
+        if self.children.is_empty() {
+            ascii_tree::Tree::Leaf(vec![node])
+        } else {
+            let mut children = self.children.iter().collect::<Vec<_>>();
+            children.sort_by_key(|child| std::cmp::Reverse(child.footprint.total()));
+
+            ascii_tree::Tree::Node(node, children.into_iter().map(Self::ascii_tree).collect())
+        }
+    }
+}
+
+impl Display for MemoryReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        ascii_tree::write_tree(f, &self.ascii_tree())?;
+
+        let column_types = self
+            .footprint
+            .by_column_type
+            .iter()
+            .map(|(column_type, memory)| format!("{column_type} {memory}"))
+            .collect::<Vec<_>>();
+        if !column_types.is_empty() {
+            writeln!(f, "Column data by type: {}", column_types.join(", "))?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use bytesize::ByteSize;
+
+    use crate::{
+        datatypes::{StorageTypeName, StorageValueT},
+        management::ByteSized,
+        tabular::{table_types::trie::Trie, traits::table::Table},
+    };
+
+    use super::{MemoryFootprint, MemoryReport, StructureKind};
+
+    #[test]
+    fn trie_footprint() {
+        let rows = (0..100u64)
+            .zip(0..100i64)
+            .map(|(unsigned, signed)| {
+                vec![
+                    StorageValueT::U64(unsigned / 10),
+                    StorageValueT::I64(signed * 7),
+                ]
+            })
+            .collect::<Vec<_>>();
+        let trie = Trie::from_rows(&rows);
+
+        let footprint = MemoryFootprint::of_trie(&trie);
+        assert!(footprint.kind(StructureKind::IntervalStarts).0 > 0);
+        assert_eq!(footprint.kind(StructureKind::Dictionary), ByteSize(0));
+        assert_eq!(
+            footprint.column_type(StorageTypeName::U64)
+                + footprint.column_type(StorageTypeName::I64),
+            footprint.kind(StructureKind::VectorColumn) + footprint.kind(StructureKind::RleColumn)
+        );
+        assert!(footprint.total() <= trie.size_bytes());
+
+        let mut report = MemoryReport::new_block("Tables");
+        report.add_child(MemoryReport::new("p", footprint.clone()));
+        report.add_child(MemoryReport::new("q", footprint.clone()));
+        assert_eq!(
+            report.footprint().total(),
+            footprint.total() + footprint.total()
+        );
+        assert_eq!(report.child("q").unwrap().footprint(), &footprint);
+
+        let printed = report.to_string();
+        assert!(printed.starts_with(&format!(" Tables ({}", report.footprint().total())));
+        assert!(printed.contains("Column data by type: U64"));
+    }
+}
@@ -0,0 +1,368 @@
+//! Cache files storing the tries of tables loaded from input files.
+//!
+//! A cache file contains the columns of a [`Trie`] together with the dictionary entries
+//! of the values in columns of type [`DataTypeName::String`].
+//! Since dictionary indices differ between runs, the values of such columns are stored as positions in the list of entries,
+//! which is sorted by the indices of the dictionary that built the trie.
+//! When the cache file is read, the entries are added to the current dictionary.
+//! If this assigns increasing indices to them, which is the case whenever none of them was known before,
+//! the columns are used as stored; otherwise the trie is sorted again.
+
+use std::{
+    cell::RefCell,
+    collections::{BTreeSet, HashMap},
+    fs::File,
+    io::{BufReader, BufWriter, Error, ErrorKind, Read, Result, Write},
+    path::Path,
+};
+
+use crate::{
+    columnar::{
+        adaptive_column_builder::ColumnBuilderAdaptive,
+        column_types::interval::{ColumnWithIntervals, ColumnWithIntervalsT},
+        traits::{column::Column, columnbuilder::ColumnBuilder},
+    },
+    datatypes::{DataTypeName, Double, Float},
+    dictionary::{index_to_storage, Dictionary, FIRST_NULL},
+    tabular::{
+        table_types::trie::Trie,
+        traits::{table::Table, table_schema::TableSchema},
+    },
+};
+
+use super::database::Dict;
+
+/// Bytes at the beginning of every cache file.
+const MAGIC: &[u8] = b"NMOT";
+
+/// Version of the format of cache files.
+const FORMAT_VERSION: u32 = 1;
+
+fn invalid_data(message: &str) -> Error {
+    Error::new(ErrorKind::InvalidData, message)
+}
+
+fn type_tag(data_type: DataTypeName) -> u8 {
+    match data_type {
+        DataTypeName::String => 0,
+        DataTypeName::U32 => 1,
+        DataTypeName::U64 => 2,
+        DataTypeName::I64 => 3,
+        DataTypeName::Float => 4,
+        DataTypeName::Double => 5,
+        DataTypeName::U128 => 6,
+    }
+}
+
+fn write_u64(writer: &mut impl Write, value: u64) -> Result<()> {
+    writer.write_all(&value.to_le_bytes())
+}
+
+fn write_usize(writer: &mut impl Write, value: usize) -> Result<()> {
+    write_u64(writer, value as u64)
+}
+
+fn read_u32(reader: &mut impl Read) -> Result<u32> {
+    let mut bytes = [0; 4];
+    reader.read_exact(&mut bytes)?;
+    Ok(u32::from_le_bytes(bytes))
+}
+
+fn read_u64(reader: &mut impl Read) -> Result<u64> {
+    let mut bytes = [0; 8];
+    reader.read_exact(&mut bytes)?;
+    Ok(u64::from_le_bytes(bytes))
+}
+
+fn read_i64(reader: &mut impl Read) -> Result<i64> {
+    let mut bytes = [0; 8];
+    reader.read_exact(&mut bytes)?;
+    Ok(i64::from_le_bytes(bytes))
+}
+
+fn read_u128(reader: &mut impl Read) -> Result<u128> {
+    let mut bytes = [0; 16];
+    reader.read_exact(&mut bytes)?;
+    Ok(u128::from_le_bytes(bytes))
+}
+
+fn read_usize(reader: &mut impl Read) -> Result<usize> {
+    usize::try_from(read_u64(reader)?).map_err(|_| invalid_data("length exceeds usize"))
+}
+
+/// Write the given trie, whose columns have the types of the given schema, into a cache file at the given path.
+///
+/// The file is written under a temporary name first,
+/// such that concurrent runs never read an incomplete cache file.
+pub fn write_table(
+    path: &Path,
+    trie: &Trie,
+    schema: &TableSchema,
+    dict: &RefCell<Dict>,
+) -> Result<()> {
+    if trie.get_types() != &schema.get_storage_types() {
+        return Err(invalid_data("trie does not match schema"));
+    }
+
+    let string_columns = schema
+        .iter()
+        .zip(trie.columns())
+        .filter(|(data_type, _)| **data_type == DataTypeName::String)
+        .filter_map(|(_, column)| column.as_u64())
+        .collect::<Vec<_>>();
+
+    let indices = string_columns
+        .iter()
+        .flat_map(|column| column.get_data_column().iter())
+        .filter(|&value| value < FIRST_NULL)
+        .collect::<BTreeSet<u64>>();
+    let positions = indices
+        .iter()
+        .enumerate()
+        .map(|(position, &index)| (index, position as u64))
+        .collect::<HashMap<_, _>>();
+
+    let temporary_path = path.with_extension(format!("tmp{}", std::process::id()));
+    let mut writer = BufWriter::new(File::create(&temporary_path)?);
+
+    writer.write_all(MAGIC)?;
+    writer.write_all(&FORMAT_VERSION.to_le_bytes())?;
+
+    write_usize(&mut writer, schema.arity())?;
+    for data_type in schema.iter() {
+        writer.write_all(&[type_tag(*data_type)])?;
+    }
+
+    write_usize(&mut writer, indices.len())?;
+    {
+        let dict = dict.borrow();
+        for &index in &indices {
+            let entry = usize::try_from(index)
+                .ok()
+                .and_then(|index| dict.entry(index))
+                .ok_or_else(|| invalid_data("trie contains an unknown dictionary index"))?;
+            write_usize(&mut writer, entry.len())?;
+            writer.write_all(entry.as_bytes())?;
+        }
+    }
+
+    for (data_type, column) in schema.iter().zip(trie.columns()) {
+        macro_rules! write_column {
+            ($column:ident, $value:ident => $bytes:expr) => {{
+                let (data, intervals) = $column.as_parts();
+
+                write_usize(&mut writer, data.len())?;
+                for $value in data.iter() {
+                    writer.write_all(&$bytes)?;
+                }
+
+                write_usize(&mut writer, intervals.len())?;
+                for start in intervals.iter() {
+                    write_usize(&mut writer, start)?;
+                }
+            }};
+        }
+
+        match column {
+            ColumnWithIntervalsT::U32(column) => {
+                write_column!(column, value => value.to_le_bytes())
+            }
+            ColumnWithIntervalsT::U64(column) if *data_type == DataTypeName::String => {
+                write_column!(column, value => positions.get(&value).copied().unwrap_or(value).to_le_bytes())
+            }
+            ColumnWithIntervalsT::U64(column) => {
+                write_column!(column, value => value.to_le_bytes())
+            }
+            ColumnWithIntervalsT::I64(column) => {
+                write_column!(column, value => value.to_le_bytes())
+            }
+            ColumnWithIntervalsT::U128(column) => {
+                write_column!(column, value => value.to_le_bytes())
+            }
+            ColumnWithIntervalsT::Float(column) => {
+                write_column!(column, value => f32::from(value).to_le_bytes())
+            }
+            ColumnWithIntervalsT::Double(column) => {
+                write_column!(column, value => f64::from(value).to_le_bytes())
+            }
+        }
+    }
+
+    writer.flush()?;
+    drop(writer);
+
+    std::fs::rename(&temporary_path, path).inspect_err(|_| {
+        let _ = std::fs::remove_file(&temporary_path);
+    })
+}
+
+/// Read the trie stored in the cache file at the given path,
+/// adding the dictionary entries it uses to the given dictionary.
+///
+/// Returns an error of kind [`ErrorKind::NotFound`] if there is no cache file
+/// and of kind [`ErrorKind::InvalidData`] if the file does not contain a table of the given schema.
+pub fn read_table(path: &Path, schema: &TableSchema, dict: &RefCell<Dict>) -> Result<Trie> {
+    let mut reader = BufReader::new(File::open(path)?);
+
+    let mut magic = [0; 4];
+    reader.read_exact(&mut magic)?;
+    if magic != MAGIC || read_u32(&mut reader)? != FORMAT_VERSION {
+        return Err(invalid_data("not a table cache file of this version"));
+    }
+
+    if read_usize(&mut reader)? != schema.arity() {
+        return Err(invalid_data("arity does not match schema"));
+    }
+    for data_type in schema.iter() {
+        let mut tag = [0];
+        reader.read_exact(&mut tag)?;
+        if tag[0] != type_tag(*data_type) {
+            return Err(invalid_data("column types do not match schema"));
+        }
+    }
+
+    let entry_count = read_usize(&mut reader)?;
+    let mut indices = Vec::with_capacity(entry_count.min(1 << 20));
+    {
+        let mut dict = dict.borrow_mut();
+        for _ in 0..entry_count {
+            let length = read_u64(&mut reader)?;
+            let mut bytes = Vec::new();
+            if (&mut reader).take(length).read_to_end(&mut bytes)? as u64 != length {
+                return Err(ErrorKind::UnexpectedEof.into());
+            }
+            let entry = String::from_utf8(bytes).map_err(|_| invalid_data("invalid entry"))?;
+            let index = index_to_storage(dict.add(entry))
+                .map_err(|error| Error::other(error.to_string()))?;
+            indices.push(index);
+        }
+    }
+    let sorted = indices.windows(2).all(|pair| pair[0] < pair[1]);
+
+    let mut columns = Vec::with_capacity(schema.arity());
+    for data_type in schema.iter() {
+        macro_rules! read_column {
+            ($variant:ident, $reader:ident => $value:expr) => {{
+                let length = read_usize(&mut reader)?;
+                let mut data = ColumnBuilderAdaptive::default();
+                for _ in 0..length {
+                    let $reader = &mut reader;
+                    data.add($value);
+                }
+
+                let length = read_usize(&mut reader)?;
+                let mut intervals = ColumnBuilderAdaptive::<usize>::default();
+                for _ in 0..length {
+                    intervals.add(read_usize(&mut reader)?);
+                }
+
+                ColumnWithIntervalsT::$variant(ColumnWithIntervals::new(
+                    data.finalize(),
+                    intervals.finalize(),
+                ))
+            }};
+        }
+
+        let column = match data_type {
+            DataTypeName::String => read_column!(U64, reader => {
+                let value = read_u64(reader)?;
+                if value < FIRST_NULL {
+                    *usize::try_from(value)
+                        .ok()
+                        .and_then(|position| indices.get(position))
+                        .ok_or_else(|| invalid_data("unknown dictionary entry"))?
+                } else {
+                    value
+                }
+            }),
+            DataTypeName::U32 => read_column!(U32, reader => read_u32(reader)?),
+            DataTypeName::U64 => read_column!(U64, reader => read_u64(reader)?),
+            DataTypeName::I64 => read_column!(I64, reader => read_i64(reader)?),
+            DataTypeName::U128 => read_column!(U128, reader => read_u128(reader)?),
+            DataTypeName::Float => read_column!(Float, reader => {
+                Float::new(f32::from_bits(read_u32(reader)?))
+                    .map_err(|_| invalid_data("invalid float"))?
+            }),
+            DataTypeName::Double => read_column!(Double, reader => {
+                Double::new(f64::from_bits(read_u64(reader)?))
+                    .map_err(|_| invalid_data("invalid double"))?
+            }),
+        };
+
+        columns.push(column);
+    }
+
+    let trie = Trie::new(columns);
+
+    if sorted {
+        Ok(trie)
+    } else {
+        Ok(Trie::from_cols(trie.as_column_vector()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{cell::RefCell, io::ErrorKind};
+
+    use crate::{
+        datatypes::{DataTypeName, StorageValueT},
+        dictionary::Dictionary,
+        management::database::Dict,
+        tabular::{
+            table_types::trie::Trie,
+            traits::{table::Table, table_schema::TableSchema},
+        },
+    };
+
+    use super::{read_table, write_table};
+
+    fn trie_of(dict: &RefCell<Dict>, rows: &[(&str, i64)]) -> Trie {
+        let rows = rows
+            .iter()
+            .map(|(string, integer)| {
+                let index = dict.borrow_mut().add(string.to_string());
+                vec![
+                    StorageValueT::U64(index as u64),
+                    StorageValueT::I64(*integer),
+                ]
+            })
+            .collect::<Vec<_>>();
+
+        Trie::from_rows(&rows)
+    }
+
+    #[test]
+    fn read_written_table() {
+        let path = std::env::temp_dir().join(format!("nemo-table-cache-{}", std::process::id()));
+        let schema = TableSchema::from_vec(vec![DataTypeName::String, DataTypeName::I64]);
+        let rows = [("b", 2), ("a", 1), ("c", -3), ("a", 4)];
+
+        let dict = RefCell::new(Dict::default());
+        let trie = trie_of(&dict, &rows);
+        write_table(&path, &trie, &schema, &dict).unwrap();
+
+        // A fresh dictionary assigns the same indices
+        let fresh_dict = RefCell::new(Dict::default());
+        assert_eq!(read_table(&path, &schema, &fresh_dict).unwrap(), trie);
+
+        // Entries known before are assigned different indices, which requires sorting again
+        let other_dict = RefCell::new(Dict::default());
+        other_dict.borrow_mut().add("c".to_string());
+        other_dict.borrow_mut().add("x".to_string());
+        let expected = trie_of(&other_dict, &rows);
+        assert_eq!(read_table(&path, &schema, &other_dict).unwrap(), expected);
+
+        let wrong_schema = TableSchema::from_vec(vec![DataTypeName::String, DataTypeName::U64]);
+        assert_eq!(
+            read_table(&path, &wrong_schema, &dict).unwrap_err().kind(),
+            ErrorKind::InvalidData
+        );
+
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(
+            read_table(&path, &schema, &dict).unwrap_err().kind(),
+            ErrorKind::NotFound
+        );
+    }
+}
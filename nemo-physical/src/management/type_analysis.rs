@@ -313,10 +313,29 @@ impl TypeTree {
                                                 .get_entry(*column_index)
                                                 .partial_upper_bound()
                                         }
+                                        ArithmeticOperation::ConvertedColumnScan(_, data_type) => {
+                                            data_type.partial_upper_bound()
+                                        }
+                                        ArithmeticOperation::Function(function) => {
+                                            function.data_type().partial_upper_bound()
+                                        }
+                                        ArithmeticOperation::Random => continue,
                                         ArithmeticOperation::Addition
                                         | ArithmeticOperation::Subtraction
                                         | ArithmeticOperation::Multiplication
-                                        | ArithmeticOperation::Division => {
+                                        | ArithmeticOperation::Division
+                                        | ArithmeticOperation::Hash
+                                        | ArithmeticOperation::SeededRandom
+                                        | ArithmeticOperation::Absolute
+                                        | ArithmeticOperation::Round
+                                        | ArithmeticOperation::Ceil
+                                        | ArithmeticOperation::Floor
+                                        | ArithmeticOperation::Modulo
+                                        | ArithmeticOperation::Power
+                                        | ArithmeticOperation::Logarithm
+                                        | ArithmeticOperation::SquareRoot
+                                        | ArithmeticOperation::Maximum
+                                        | ArithmeticOperation::Minimum => {
                                             unreachable!("Not a leaf node")
                                         }
                                     };
@@ -330,7 +349,10 @@ impl TypeTree {
                                     }
                                 }
 
-                                new_schema.add_entry(operation_type.expect("operation_type will be assigned because the the operation tree must contain at least one leaf node."));
+                                // Trees without constants or input columns only compute random numbers,
+                                // which are represented as floating point values
+                                new_schema
+                                    .add_entry(operation_type.unwrap_or(DataTypeName::Double));
                             }
                         }
                     }
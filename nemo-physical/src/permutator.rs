@@ -312,7 +312,7 @@ mod test {
         vec_f64: Vec<f64>,
         vec_f32: Vec<f32>,
     ) -> bool {
-        log::debug!(
+        tracing::debug!(
             "used values:\nvec_u64: {:?}\nvec_i64: {:?}\nvec_f64: {:?}\n vec_f32: {:?}",
             vec_u64,
             vec_i64,
@@ -455,7 +455,7 @@ mod test {
     #[quickcheck]
     #[cfg_attr(miri, ignore)]
     fn from_rnd_column(vec: Vec<u32>) -> bool {
-        log::debug!("used vector: {:?}", vec);
+        tracing::debug!("used vector: {:?}", vec);
 
         let mut builder = ColumnBuilderAdaptive::<u32>::default();
         let mut vec_cpy = vec.clone();
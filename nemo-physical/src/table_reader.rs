@@ -23,4 +23,14 @@ pub trait TableReader: std::fmt::Debug {
         self: Box<Self>,
         builder_proxies: &'b mut Vec<PhysicalBuilderProxyEnum<'a>>,
     ) -> Result<(), ReadingError>;
+
+    /// Return whether the rows read by this reader are known to be sorted,
+    /// e.g. since they stem from a table that has been exported before.
+    ///
+    /// This allows building the table without sorting its rows.
+    /// Since the physical representation of values depends on the dictionary,
+    /// the order is still verified after reading, and the rows are sorted if necessary.
+    fn is_sorted(&self) -> bool {
+        false
+    }
 }
@@ -34,6 +34,10 @@ pub use triescan_join::TrieScanJoin;
 /// Module for defining append functionality
 pub mod triescan_append;
 
+/// Module for defining [`TrieScanDistinctPrefix`]
+pub mod triescan_distinct_prefix;
+pub use triescan_distinct_prefix::TrieScanDistinctPrefix;
+
 /// Module for defining [`TrieScanNulls`]
 pub mod triescan_nulls;
 pub use triescan_nulls::TrieScanNulls;
@@ -4,17 +4,37 @@ use crate::{
         traits::columnbuilder::ColumnBuilder,
     },
     datatypes::StorageTypeName,
-    tabular::{table_types::trie::Trie, traits::trie_scan::TrieScan},
+    tabular::{
+        operations::TrieScanDistinctPrefix, table_types::trie::Trie, traits::trie_scan::TrieScan,
+    },
 };
 
 /// Given a TrieScan iterator, materialize its content into a trie
 /// For the last `cut` layers only checks the existence of a value and does not materialize it fully.
 pub fn materialize_up_to(trie_scan: &mut impl TrieScan, cut: usize) -> Option<Trie> {
+    let prefix_length = trie_scan.column_types().len().checked_sub(cut)?;
+    materialize(&mut TrieScanDistinctPrefix::new(trie_scan, prefix_length))
+}
+
+/// Given a TrieScan iterator, materialize its content into a trie
+pub fn materialize(trie_scan: &mut impl TrieScan) -> Option<Trie> {
+    materialize_rows(trie_scan, false)
+}
+
+/// Given a TrieScan iterator, materialize only its first row into a trie,
+/// without advancing the iterator any further.
+/// Returns `None` if the iterator is empty.
+pub fn materialize_first_row(trie_scan: &mut impl TrieScan) -> Option<Trie> {
+    materialize_rows(trie_scan, true)
+}
+
+/// Materialize the content of a TrieScan iterator, stopping after the first row if `first_row_only` is set.
+fn materialize_rows(trie_scan: &mut impl TrieScan, first_row_only: bool) -> Option<Trie> {
     if trie_scan.column_types().is_empty() {
         return None;
     }
 
-    let num_columns = trie_scan.column_types().len() - cut;
+    let num_columns = trie_scan.column_types().len();
 
     let mut data_column_builders: Vec<_> = trie_scan
         .column_types()
@@ -31,6 +51,7 @@ pub fn materialize_up_to(trie_scan: &mut impl TrieScan, cut: usize) -> Option<Tr
                 StorageTypeName::U32 => init_builder_for_datatype!(U32),
                 StorageTypeName::U64 => init_builder_for_datatype!(U64),
                 StorageTypeName::I64 => init_builder_for_datatype!(I64),
+                StorageTypeName::U128 => init_builder_for_datatype!(U128),
                 StorageTypeName::Float => init_builder_for_datatype!(Float),
                 StorageTypeName::Double => init_builder_for_datatype!(Double),
             }
@@ -52,6 +73,10 @@ pub fn materialize_up_to(trie_scan: &mut impl TrieScan, cut: usize) -> Option<Tr
             data_column_builders[i].add(trie_scan.current(i));
         }
 
+        if first_row_only {
+            break;
+        }
+
         if let Some(next_changed_layer) = trie_scan.advance_on_layer(num_columns - 1) {
             changed_layer = next_changed_layer;
         } else {
@@ -67,21 +92,17 @@ pub fn materialize_up_to(trie_scan: &mut impl TrieScan, cut: usize) -> Option<Tr
     Some(columns.collect())
 }
 
-/// Given a TrieScan iterator, materialize its content into a trie
-pub fn materialize(trie_scan: &mut impl TrieScan) -> Option<Trie> {
-    materialize_up_to(trie_scan, 0)
-}
-
 /// Tests whether an iterator is empty by materializing it until the first element
 pub fn scan_is_empty(trie_scan: &mut impl TrieScan) -> bool {
-    trie_scan
-        .advance_on_layer(trie_scan.column_types().len())
-        .is_some()
+    match trie_scan.column_types().len().checked_sub(1) {
+        Some(last_layer) => trie_scan.advance_on_layer(last_layer).is_none(),
+        None => true,
+    }
 }
 
 #[cfg(test)]
 mod test {
-    use super::materialize;
+    use super::{materialize, materialize_first_row, scan_is_empty};
     use crate::columnar::traits::column::Column;
     use crate::tabular::operations::{JoinBindings, TrieScanJoin, TrieScanPrune};
     use crate::tabular::table_types::trie::{Trie, TrieScanGeneric};
@@ -226,5 +247,41 @@ mod test {
         );
     }
 
-    // TODO: Tets scan_is_empty
+    #[test]
+    fn first_row() {
+        let column_fst = make_column_with_intervals_t(&[1, 2], &[0]);
+        let column_snd = make_column_with_intervals_t(&[3, 4, 5], &[0, 2]);
+
+        let trie = Trie::new(vec![column_fst, column_snd]);
+        let trie_iter = TrieScanEnum::TrieScanGeneric(TrieScanGeneric::new(&trie));
+        let mut trie_scan = TrieScanPrune::new(trie_iter);
+
+        let first_row = materialize_first_row(&mut trie_scan).unwrap();
+        let first_row_fst = first_row.get_column(0).as_u64().unwrap();
+        let first_row_snd = first_row.get_column(1).as_u64().unwrap();
+
+        assert_eq!(
+            first_row_fst.get_data_column().iter().collect::<Vec<u64>>(),
+            vec![1]
+        );
+        assert_eq!(
+            first_row_snd.get_data_column().iter().collect::<Vec<u64>>(),
+            vec![3]
+        );
+
+        let empty_trie = Trie::new(vec![make_column_with_intervals_t(&[], &[0])]);
+        let empty_iter = TrieScanEnum::TrieScanGeneric(TrieScanGeneric::new(&empty_trie));
+        assert!(materialize_first_row(&mut TrieScanPrune::new(empty_iter)).is_none());
+    }
+
+    #[test]
+    fn is_empty() {
+        let trie = Trie::new(vec![make_column_with_intervals_t(&[1, 2], &[0])]);
+        let trie_iter = TrieScanEnum::TrieScanGeneric(TrieScanGeneric::new(&trie));
+        assert!(!scan_is_empty(&mut TrieScanPrune::new(trie_iter)));
+
+        let empty_trie = Trie::new(vec![make_column_with_intervals_t(&[], &[0])]);
+        let empty_iter = TrieScanEnum::TrieScanGeneric(TrieScanGeneric::new(&empty_trie));
+        assert!(scan_is_empty(&mut TrieScanPrune::new(empty_iter)));
+    }
 }
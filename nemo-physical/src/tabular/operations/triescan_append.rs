@@ -104,6 +104,127 @@ impl OperationTreeT {
 
                 OperationTree::<T>::tree(ArithmeticOperation::Division, subtrees)
             }
+            ArithmeticOperation::Hash => {
+                let subtrees = tree
+                    .subtrees
+                    .into_iter()
+                    .map(|t| Self::translate_recursive(t, translate_function))
+                    .collect();
+
+                OperationTree::<T>::tree(ArithmeticOperation::Hash, subtrees)
+            }
+            ArithmeticOperation::Random => OperationTree::<T>::leaf(ArithmeticOperation::Random),
+            ArithmeticOperation::ConvertedColumnScan(index, data_type) => OperationTree::<T>::leaf(
+                ArithmeticOperation::ConvertedColumnScan(index, data_type),
+            ),
+            ArithmeticOperation::SeededRandom => {
+                let subtrees = tree
+                    .subtrees
+                    .into_iter()
+                    .map(|t| Self::translate_recursive(t, translate_function))
+                    .collect();
+
+                OperationTree::<T>::tree(ArithmeticOperation::SeededRandom, subtrees)
+            }
+            ArithmeticOperation::Absolute => {
+                let subtrees = tree
+                    .subtrees
+                    .into_iter()
+                    .map(|t| Self::translate_recursive(t, translate_function))
+                    .collect();
+
+                OperationTree::<T>::tree(ArithmeticOperation::Absolute, subtrees)
+            }
+            ArithmeticOperation::Round => {
+                let subtrees = tree
+                    .subtrees
+                    .into_iter()
+                    .map(|t| Self::translate_recursive(t, translate_function))
+                    .collect();
+
+                OperationTree::<T>::tree(ArithmeticOperation::Round, subtrees)
+            }
+            ArithmeticOperation::Ceil => {
+                let subtrees = tree
+                    .subtrees
+                    .into_iter()
+                    .map(|t| Self::translate_recursive(t, translate_function))
+                    .collect();
+
+                OperationTree::<T>::tree(ArithmeticOperation::Ceil, subtrees)
+            }
+            ArithmeticOperation::Floor => {
+                let subtrees = tree
+                    .subtrees
+                    .into_iter()
+                    .map(|t| Self::translate_recursive(t, translate_function))
+                    .collect();
+
+                OperationTree::<T>::tree(ArithmeticOperation::Floor, subtrees)
+            }
+            ArithmeticOperation::Modulo => {
+                let subtrees = tree
+                    .subtrees
+                    .into_iter()
+                    .map(|t| Self::translate_recursive(t, translate_function))
+                    .collect();
+
+                OperationTree::<T>::tree(ArithmeticOperation::Modulo, subtrees)
+            }
+            ArithmeticOperation::Power => {
+                let subtrees = tree
+                    .subtrees
+                    .into_iter()
+                    .map(|t| Self::translate_recursive(t, translate_function))
+                    .collect();
+
+                OperationTree::<T>::tree(ArithmeticOperation::Power, subtrees)
+            }
+            ArithmeticOperation::Logarithm => {
+                let subtrees = tree
+                    .subtrees
+                    .into_iter()
+                    .map(|t| Self::translate_recursive(t, translate_function))
+                    .collect();
+
+                OperationTree::<T>::tree(ArithmeticOperation::Logarithm, subtrees)
+            }
+            ArithmeticOperation::SquareRoot => {
+                let subtrees = tree
+                    .subtrees
+                    .into_iter()
+                    .map(|t| Self::translate_recursive(t, translate_function))
+                    .collect();
+
+                OperationTree::<T>::tree(ArithmeticOperation::SquareRoot, subtrees)
+            }
+            ArithmeticOperation::Maximum => {
+                let subtrees = tree
+                    .subtrees
+                    .into_iter()
+                    .map(|t| Self::translate_recursive(t, translate_function))
+                    .collect();
+
+                OperationTree::<T>::tree(ArithmeticOperation::Maximum, subtrees)
+            }
+            ArithmeticOperation::Minimum => {
+                let subtrees = tree
+                    .subtrees
+                    .into_iter()
+                    .map(|t| Self::translate_recursive(t, translate_function))
+                    .collect();
+
+                OperationTree::<T>::tree(ArithmeticOperation::Minimum, subtrees)
+            }
+            ArithmeticOperation::Function(function) => {
+                let subtrees = tree
+                    .subtrees
+                    .into_iter()
+                    .map(|t| Self::translate_recursive(t, translate_function))
+                    .collect();
+
+                OperationTree::<T>::tree(ArithmeticOperation::Function(function), subtrees)
+            }
         }
     }
 
@@ -118,19 +239,27 @@ impl OperationTreeT {
     }
 
     /// Return the [`StorageTypeName`] of the evaluation result of this tree.
+    ///
+    /// Returns `None` if the tree neither contains constants nor reads from columns,
+    /// e.g. if it only computes a random number.
     pub fn storage_type(
         &self,
         input_types: &[StorageTypeName],
         dict: &mut Dict,
-    ) -> StorageTypeName {
-        let first_leaf_node = self.leaves()[0];
-        match first_leaf_node {
+    ) -> Option<StorageTypeName> {
+        self.leaves().into_iter().find_map(|leaf| match leaf {
             ArithmeticOperation::Constant(constant) => {
-                constant.to_storage_value_mut(dict).get_type()
+                Some(constant.to_storage_value_mut(dict).get_type())
             }
-            ArithmeticOperation::ColumnScan(index) => input_types[*index],
-            _ => unreachable!("Not a leave."),
-        }
+            ArithmeticOperation::ColumnScan(index) => Some(input_types[*index]),
+            ArithmeticOperation::ConvertedColumnScan(_, data_type) => {
+                Some(data_type.to_storage_type_name())
+            }
+            ArithmeticOperation::Function(function) => {
+                Some(function.data_type().to_storage_type_name())
+            }
+            _ => None,
+        })
     }
 }
 
@@ -225,6 +354,7 @@ pub fn trie_append(
                         StorageTypeName::U32 => append_column_for_datatype!(U32, u32),
                         StorageTypeName::U64 => append_column_for_datatype!(U64, u64),
                         StorageTypeName::I64 => append_column_for_datatype!(I64, i64),
+                        StorageTypeName::U128 => append_column_for_datatype!(U128, u128),
                         StorageTypeName::Float => {
                             append_column_for_datatype!(Float, Float)
                         }
@@ -260,6 +390,9 @@ pub fn trie_append(
                         StorageValueT::U32(value) => append_columns_for_datatype!(value, U32, u32),
                         StorageValueT::U64(value) => append_columns_for_datatype!(value, U64, u64),
                         StorageValueT::I64(value) => append_columns_for_datatype!(value, I64, i64),
+                        StorageValueT::U128(value) => {
+                            append_columns_for_datatype!(value, U128, u128)
+                        }
                         StorageValueT::Float(value) => {
                             append_columns_for_datatype!(value, Float, Float)
                         }
@@ -376,8 +509,10 @@ impl<'a> TrieScanAppend<'a> {
         src_types: &[StorageTypeName],
         dict: &mut Dict,
     ) {
-        let src_type = operation_tree.storage_type(src_types, dict);
         let dst_type = self.target_types[self.column_scans.len()];
+        let src_type = operation_tree
+            .storage_type(src_types, dict)
+            .unwrap_or(dst_type);
 
         // TODO: Cast the types if there are not equal.
         assert!(src_type == dst_type);
@@ -403,6 +538,20 @@ impl<'a> TrieScanAppend<'a> {
                     column_map.insert(*src_index, column_map.len());
                 }
 
+                let mut converted_column_map = HashMap::<usize, usize>::new();
+                let mut converted_input_scans = Vec::new();
+
+                for src_index in operation_tree.converted_input_indices().into_iter() {
+                    if converted_column_map.contains_key(&src_index) {
+                        continue;
+                    }
+
+                    let base_scan = unsafe { &*self.trie_scan.get_scan(*src_index).unwrap().get() };
+                    converted_input_scans.push(base_scan);
+
+                    converted_column_map.insert(*src_index, converted_column_map.len());
+                }
+
                 let translate_type = |t: DataValueT| {
                     if let StorageValueT::$variant(value) = t
                         .to_storage_value(dict)
@@ -425,8 +574,14 @@ impl<'a> TrieScanAppend<'a> {
                         .expect("The construction of this map insures that this value is present.");
                 }
 
+                for index in operation_tree.converted_input_indices_mut() {
+                    *index = *converted_column_map
+                        .get(index)
+                        .expect("The construction of this map insures that this value is present.");
+                }
+
                 let new_scan = ColumnScanCell::new(ColumnScanEnum::ColumnScanArithmetic(
-                    ColumnScanArithmetic::new(input_scans, operation_tree),
+                    ColumnScanArithmetic::new(input_scans, converted_input_scans, operation_tree),
                 ));
 
                 self.column_scans
@@ -438,6 +593,7 @@ impl<'a> TrieScanAppend<'a> {
             StorageTypeName::U32 => input_for_datatype!(U32, u32),
             StorageTypeName::U64 => input_for_datatype!(U64, u64),
             StorageTypeName::I64 => input_for_datatype!(I64, i64),
+            StorageTypeName::U128 => input_for_datatype!(U128, u128),
             StorageTypeName::Float => input_for_datatype!(Float, f32),
             StorageTypeName::Double => input_for_datatype!(Double, f64),
         }
@@ -466,6 +622,7 @@ impl<'a> TrieScanAppend<'a> {
             StorageTypeName::U32 => append_pass_for_datatype!(U32),
             StorageTypeName::U64 => append_pass_for_datatype!(U64),
             StorageTypeName::I64 => append_pass_for_datatype!(I64),
+            StorageTypeName::U128 => append_pass_for_datatype!(U128),
             StorageTypeName::Float => append_pass_for_datatype!(Float),
             StorageTypeName::Double => append_pass_for_datatype!(Double),
         };
@@ -516,6 +673,7 @@ impl<'a> TrieScanAppend<'a> {
             StorageTypeName::U32 => append_repeat_for_datatype!(U32),
             StorageTypeName::U64 => append_repeat_for_datatype!(U64),
             StorageTypeName::I64 => append_repeat_for_datatype!(I64),
+            StorageTypeName::U128 => append_repeat_for_datatype!(U128),
             StorageTypeName::Float => append_repeat_for_datatype!(Float),
             StorageTypeName::Double => append_repeat_for_datatype!(Double),
         }
@@ -535,6 +693,7 @@ impl<'a> TrieScanAppend<'a> {
             StorageValueT::U32(value) => append_constant_for_datatype!(U32, value),
             StorageValueT::U64(value) => append_constant_for_datatype!(U64, value),
             StorageValueT::I64(value) => append_constant_for_datatype!(I64, value),
+            StorageValueT::U128(value) => append_constant_for_datatype!(U128, value),
             StorageValueT::Float(value) => {
                 append_constant_for_datatype!(Float, value)
             }
@@ -0,0 +1,159 @@
+use crate::{
+    datatypes::{StorageTypeName, StorageValueT},
+    tabular::traits::trie_scan::TrieScan,
+};
+
+/// [`TrieScan`] which only enumerates the distinct prefixes of the first layers of another [`TrieScan`].
+///
+/// Since the layers below the prefix are hidden, the input trie scan is never advanced on them.
+/// For a [`crate::tabular::operations::TrieScanPrune`], they are only visited
+/// to check whether a prefix can be completed to a full tuple, which stops at the first such tuple.
+/// This is useful for existence checks and for projecting away the last columns of a trie scan
+/// without iterating over all of its tuples.
+#[derive(Debug)]
+pub struct TrieScanDistinctPrefix<T: TrieScan> {
+    /// Trie scan whose prefixes are enumerated
+    trie_scan: T,
+    /// Types of the layers belonging to the prefix
+    target_types: Vec<StorageTypeName>,
+}
+
+impl<T: TrieScan> TrieScanDistinctPrefix<T> {
+    /// Create new [`TrieScanDistinctPrefix`] object,
+    /// which enumerates the distinct prefixes of length `prefix_length` of the given trie scan.
+    ///
+    /// # Panics
+    /// If `prefix_length` is larger than the number of layers of the trie scan.
+    pub fn new(trie_scan: T, prefix_length: usize) -> Self {
+        assert!(prefix_length <= trie_scan.column_types().len());
+        let target_types = trie_scan.column_types()[..prefix_length].to_vec();
+
+        Self {
+            trie_scan,
+            target_types,
+        }
+    }
+
+    /// Return the underlying trie scan.
+    pub fn into_inner(self) -> T {
+        self.trie_scan
+    }
+}
+
+impl<T: TrieScan> TrieScan for TrieScanDistinctPrefix<T> {
+    fn advance_on_layer(&mut self, layer: usize) -> Option<usize> {
+        assert!(layer < self.target_types.len());
+        self.trie_scan.advance_on_layer(layer)
+    }
+
+    fn current(&mut self, layer: usize) -> StorageValueT {
+        assert!(layer < self.target_types.len());
+        self.trie_scan.current(layer)
+    }
+
+    fn column_types(&self) -> &[StorageTypeName] {
+        &self.target_types
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use super::TrieScanDistinctPrefix;
+    use crate::columnar::operations::columnscan_restrict_values::{FilterBound, FilterValue};
+    use crate::datatypes::{DataValueT, StorageValueT};
+    use crate::management::database::Dict;
+    use crate::tabular::operations::{
+        materialize::materialize,
+        TrieScanPrune, TrieScanRestrictValues, ValueAssignment,
+    };
+    use crate::tabular::table_types::trie::{Trie, TrieScanGeneric};
+    use crate::tabular::traits::{
+        partial_trie_scan::TrieScanEnum, table::Table, trie_scan::TrieScan,
+    };
+    use crate::util::test_util::make_column_with_intervals_t;
+    use test_log::test;
+
+    fn collect_prefixes(scan: &mut impl TrieScan) -> Vec<Vec<u64>> {
+        let arity = scan.column_types().len();
+        let mut result = Vec::new();
+        let mut current = vec![0; arity];
+
+        while let Some(changed_layer) = scan.advance_on_layer(arity - 1) {
+            for (layer, value) in current.iter_mut().enumerate().skip(changed_layer) {
+                let StorageValueT::U64(next) = scan.current(layer) else {
+                    panic!("type should be u64");
+                };
+                *value = next;
+            }
+            result.push(current.clone());
+        }
+
+        result
+    }
+
+    /// Trie containing the tuples
+    /// (1, 4, 7), (1, 4, 8), (1, 5, 7), (2, 4, 9), (2, 6, 7), (2, 6, 8)
+    fn create_example_trie() -> Trie {
+        let column_fst = make_column_with_intervals_t(&[1, 2], &[0]);
+        let column_snd = make_column_with_intervals_t(&[4, 5, 4, 6], &[0, 2]);
+        let column_trd = make_column_with_intervals_t(&[7, 8, 7, 9, 7, 8], &[0, 2, 3, 4]);
+
+        Trie::new(vec![column_fst, column_snd, column_trd])
+    }
+
+    #[test]
+    fn distinct_prefixes() {
+        let trie = create_example_trie();
+        let scan = TrieScanPrune::new(TrieScanEnum::TrieScanGeneric(TrieScanGeneric::new(&trie)));
+
+        let mut prefixes = TrieScanDistinctPrefix::new(scan, 2);
+        assert_eq!(
+            collect_prefixes(&mut prefixes),
+            vec![vec![1, 4], vec![1, 5], vec![2, 4], vec![2, 6]]
+        );
+
+        let trie = create_example_trie();
+        let scan = TrieScanPrune::new(TrieScanEnum::TrieScanGeneric(TrieScanGeneric::new(&trie)));
+        let mut prefixes = TrieScanDistinctPrefix::new(scan, 1);
+        let materialized = materialize(&mut prefixes).unwrap();
+        assert_eq!(materialized.row_num(), 2);
+    }
+
+    #[test]
+    fn prefixes_without_complete_tuples_are_skipped() {
+        let trie = create_example_trie();
+        let scan = TrieScanEnum::TrieScanGeneric(TrieScanGeneric::new(&trie));
+
+        let mut dict = Dict::default();
+        let assignment = |value: u64| ValueAssignment {
+            lower_bounds: vec![FilterBound::Inclusive(FilterValue::Constant(
+                DataValueT::U64(value),
+            ))],
+            upper_bounds: vec![FilterBound::Inclusive(FilterValue::Constant(
+                DataValueT::U64(value),
+            ))],
+            avoid_values: vec![],
+            allowed_values: None,
+            text_queries: vec![],
+            similar_values: vec![],
+        };
+        let scan = TrieScanEnum::TrieScanRestrictValues(TrieScanRestrictValues::new(
+            &mut dict,
+            scan,
+            &HashMap::from([(2, assignment(8))]),
+        ));
+
+        let mut prefixes = TrieScanDistinctPrefix::new(TrieScanPrune::new(scan), 2);
+        assert_eq!(collect_prefixes(&mut prefixes), vec![vec![1, 4], vec![2, 6]]);
+
+        let scan = TrieScanEnum::TrieScanRestrictValues(TrieScanRestrictValues::new(
+            &mut dict,
+            TrieScanEnum::TrieScanGeneric(TrieScanGeneric::new(&trie)),
+            &HashMap::from([(2, assignment(3))]),
+        ));
+        let mut prefixes = TrieScanDistinctPrefix::new(TrieScanPrune::new(scan), 1);
+        assert!(prefixes.advance_on_layer(0).is_none());
+    }
+}
@@ -1,6 +1,6 @@
 use crate::{
     columnar::{
-        operations::{ColumnScanJoin, ColumnScanPass},
+        operations::{ColumnScanJoin, ColumnScanPass, ColumnScanVectorJoin},
         traits::columnscan::{ColumnScan, ColumnScanCell, ColumnScanEnum, ColumnScanT},
     },
     datatypes::{Double, Float, StorageTypeName},
@@ -228,9 +228,15 @@ impl<'a> TrieScanJoin<'a> {
                             }
                         }
 
-                        merge_joins.push(UnsafeCell::new(ColumnScanT::$variant(ColumnScanCell::new(
-                            ColumnScanEnum::ColumnScanJoin(ColumnScanJoin::new(scans)),
-                        ))))
+                        // Joins over materialized tries, e.g. of (dictionary encoded) u64 values, are the most common case,
+                        // so if all sub scans are vector scans, they are joined without dispatching over the kind of scan
+                        let vector_scans = scans.iter().map(|scan| scan.vector_scan()).collect::<Option<Vec<_>>>();
+                        let join_scan = match vector_scans {
+                            Some(vector_scans) => ColumnScanEnum::ColumnScanVectorJoin(ColumnScanVectorJoin::new(vector_scans)),
+                            None => ColumnScanEnum::ColumnScanJoin(ColumnScanJoin::new(scans)),
+                        };
+
+                        merge_joins.push(UnsafeCell::new(ColumnScanT::$variant(ColumnScanCell::new(join_scan))))
                     } else {
                         // If we have only one column then no join is necessary and we use a [`ColumnScanPass`]
                         unsafe {
@@ -255,6 +261,7 @@ impl<'a> TrieScanJoin<'a> {
                 StorageTypeName::U32 => merge_join_for_datatype!(U32, u32),
                 StorageTypeName::U64 => merge_join_for_datatype!(U64, u64),
                 StorageTypeName::I64 => merge_join_for_datatype!(I64, i64),
+                StorageTypeName::U128 => merge_join_for_datatype!(U128, u128),
                 StorageTypeName::Float => merge_join_for_datatype!(Float, Float),
                 StorageTypeName::Double => merge_join_for_datatype!(Double, Double),
             }
@@ -338,10 +345,12 @@ mod test {
         columnbuilder::ColumnBuilder,
         columnscan::ColumnScanT,
     };
+    use crate::datatypes::{storage_value::VecT, StorageValueT};
     use crate::tabular::operations::triescan_join::JoinBindings;
     use crate::tabular::operations::{materialize, TrieScanPrune};
     use crate::tabular::table_types::trie::{Trie, TrieScanGeneric};
     use crate::tabular::traits::partial_trie_scan::{PartialTrieScan, TrieScanEnum};
+    use crate::tabular::traits::table::Table;
 
     use crate::util::test_util::make_column_with_intervals_t;
     use test_log::test;
@@ -772,4 +781,70 @@ mod test {
         assert_eq!(join_next(&mut my_join_iter), Some(4));
         assert_eq!(join_next(&mut my_join_iter), None);
     }
+
+    #[test]
+    fn test_u128_join() {
+        let large = u128::from(u64::MAX) + 1;
+        let trie_a = Trie::from_cols(vec![
+            VecT::U128(vec![1, large, large + 2]),
+            VecT::U64(vec![1, 2, 3]),
+        ]);
+        let trie_b = Trie::from_cols(vec![VecT::U128(vec![large + 2, 3, large])]);
+
+        let join_iter = TrieScanJoin::new(
+            vec![
+                TrieScanEnum::TrieScanGeneric(TrieScanGeneric::new(&trie_a)),
+                TrieScanEnum::TrieScanGeneric(TrieScanGeneric::new(&trie_b)),
+            ],
+            &JoinBindings::new(vec![vec![0, 1], vec![0]]),
+        );
+
+        let join_trie = materialize(&mut TrieScanPrune::new(TrieScanEnum::TrieScanJoin(
+            join_iter,
+        )))
+        .unwrap();
+
+        assert_eq!(
+            join_trie.get_column(0).iter().collect::<Vec<_>>(),
+            vec![StorageValueT::U128(large), StorageValueT::U128(large + 2)]
+        );
+        assert_eq!(
+            join_trie.get_column(1).iter().collect::<Vec<_>>(),
+            vec![StorageValueT::U64(2), StorageValueT::U64(3)]
+        );
+    }
+
+    #[test]
+    fn test_vector_join() {
+        // Sparse columns are stored as vectors, so their scans are joined without dispatching over the kind of scan
+        let trie_a = Trie::from_cols(vec![VecT::U64(
+            (0..1000u64).map(|value| value * value).collect(),
+        )]);
+        let trie_b = Trie::from_cols(vec![VecT::U64(
+            (0..1000u64).map(|value| 4 * value * value).collect(),
+        )]);
+
+        let join_iter = TrieScanJoin::new(
+            vec![
+                TrieScanEnum::TrieScanGeneric(TrieScanGeneric::new(&trie_a)),
+                TrieScanEnum::TrieScanGeneric(TrieScanGeneric::new(&trie_b)),
+            ],
+            &JoinBindings::new(vec![vec![0], vec![0]]),
+        );
+
+        let merge_join = unsafe { &*join_iter.merge_joins[0].get() };
+        assert!(format!("{merge_join:?}").contains("ColumnScanVectorJoin"));
+
+        let join_trie = materialize(&mut TrieScanPrune::new(TrieScanEnum::TrieScanJoin(
+            join_iter,
+        )))
+        .unwrap();
+
+        assert_eq!(
+            join_trie.get_column(0).iter().collect::<Vec<_>>(),
+            (0..500u64)
+                .map(|value| StorageValueT::U64(4 * value * value))
+                .collect::<Vec<_>>()
+        );
+    }
 }
@@ -154,6 +154,7 @@ impl<'a> TrieScanSubtract<'a> {
                 StorageTypeName::U32 => subtract_for_datatype!(U32, u32),
                 StorageTypeName::U64 => subtract_for_datatype!(U64, u64),
                 StorageTypeName::I64 => subtract_for_datatype!(I64, i64),
+                StorageTypeName::U128 => subtract_for_datatype!(U128, u128),
                 StorageTypeName::Float => subtract_for_datatype!(Float, Float),
                 StorageTypeName::Double => subtract_for_datatype!(Double, Double),
             }
@@ -330,6 +331,7 @@ impl<'a> TrieScanMinus<'a> {
                 StorageTypeName::U32 => init_scans_for_datatype!(U32),
                 StorageTypeName::U64 => init_scans_for_datatype!(U64),
                 StorageTypeName::I64 => init_scans_for_datatype!(I64),
+                StorageTypeName::U128 => init_scans_for_datatype!(U128),
                 StorageTypeName::Float => init_scans_for_datatype!(Float),
                 StorageTypeName::Double => init_scans_for_datatype!(Double),
             };
@@ -27,7 +27,7 @@ fn expand_range(column: &ColumnWithIntervalsT, range: Range<usize>) -> Range<usi
         column.int_bounds(range.end).start
     };
 
-    log::debug!("{range:?} {start}..{end}");
+    tracing::debug!("{range:?} {start}..{end}");
 
     start..end
 }
@@ -48,6 +48,7 @@ fn shrink_position(column: &ColumnWithIntervalsT, pos: usize) -> usize {
         ColumnWithIntervalsT::U32(col) => shrink_position_t(col, pos),
         ColumnWithIntervalsT::U64(col) => shrink_position_t(col, pos),
         ColumnWithIntervalsT::I64(col) => shrink_position_t(col, pos),
+        ColumnWithIntervalsT::U128(col) => shrink_position_t(col, pos),
         ColumnWithIntervalsT::Float(col) => shrink_position_t(col, pos),
         ColumnWithIntervalsT::Double(col) => shrink_position_t(col, pos),
     }
@@ -115,6 +116,7 @@ impl<'a> TrieScanProject<'a> {
                 StorageTypeName::U32 => init_scans_for_datatype!(U32),
                 StorageTypeName::U64 => init_scans_for_datatype!(U64),
                 StorageTypeName::I64 => init_scans_for_datatype!(I64),
+                StorageTypeName::U128 => init_scans_for_datatype!(U128),
                 StorageTypeName::Float => init_scans_for_datatype!(Float),
                 StorageTypeName::Double => init_scans_for_datatype!(Double),
             }
@@ -177,7 +179,7 @@ impl<'a> PartialTrieScan<'a> for TrieScanProject<'a> {
                         .pos_multiple()
                         .expect("Should not call down when not on an element");
 
-                    log::debug!("CURRENT_CURSORS before filtering {:?}", current_cursors);
+                    tracing::debug!("CURRENT_CURSORS before filtering {:?}", current_cursors);
 
                     // keep only cursor positions for the comparison layer that are in line with the positions in the current layer
                     // we check this by "shrinking" the cursor positions from the comparison layer
@@ -201,8 +203,8 @@ impl<'a> PartialTrieScan<'a> for TrieScanProject<'a> {
                     };
 
 
-                    log::debug!("CURRENT_CURSORS after filtering {:?}", current_cursors);
-                    log::debug!("PICKED_COLUMNS {:?} {:?} {:?}", layer_for_comparison, next_layer, self.layer_to_input);
+                    tracing::debug!("CURRENT_CURSORS after filtering {:?}", current_cursors);
+                    tracing::debug!("PICKED_COLUMNS {:?} {:?} {:?}", layer_for_comparison, next_layer, self.layer_to_input);
 
                     if self.layer_to_input[layer_for_comparison] < self.layer_to_input[next_layer] {
                         let mut ranges = Vec::<Range<usize>>::new();
@@ -212,7 +214,7 @@ impl<'a> PartialTrieScan<'a> for TrieScanProject<'a> {
                             for expand_index in (self.layer_to_input[layer_for_comparison] + 1)
                                 ..=self.layer_to_input[next_layer]
                             {
-                                log::debug!("EXPAND_INDEX {:?}", expand_index);
+                                tracing::debug!("EXPAND_INDEX {:?}", expand_index);
                                 range = expand_range(self.trie.get_column(expand_index), range);
                             }
 
@@ -228,7 +230,7 @@ impl<'a> PartialTrieScan<'a> for TrieScanProject<'a> {
                                 ..=(self.layer_to_input[layer_for_comparison]))
                                 .rev()
                             {
-                                log::debug!("VALUE {value:?}");
+                                tracing::debug!("VALUE {value:?}");
 
                                 value = shrink_position(self.trie.get_column(shrink_index), value);
                             }
@@ -248,7 +250,7 @@ impl<'a> PartialTrieScan<'a> for TrieScanProject<'a> {
                     }
                 };
 
-                log::debug!("NEXT_RANGES {next_layer:?} {next_ranges:?}");
+                tracing::debug!("NEXT_RANGES {next_layer:?} {next_ranges:?}");
 
                 self.reorder_scans[next_layer]
                     .get_mut()
@@ -260,6 +262,7 @@ impl<'a> PartialTrieScan<'a> for TrieScanProject<'a> {
             ColumnWithIntervalsT::U32(_) => down_for_datatype!(U32),
             ColumnWithIntervalsT::U64(_) => down_for_datatype!(U64),
             ColumnWithIntervalsT::I64(_) => down_for_datatype!(I64),
+            ColumnWithIntervalsT::U128(_) => down_for_datatype!(U128),
             ColumnWithIntervalsT::Float(_) => down_for_datatype!(Float),
             ColumnWithIntervalsT::Double(_) => down_for_datatype!(Double),
         }
@@ -431,6 +431,7 @@ impl<'a> TrieScanPrune<'a> {
                 StorageTypeName::U32 => create_column_scan_for_storage_type!(U32, u32),
                 StorageTypeName::U64 => create_column_scan_for_storage_type!(U64, u64),
                 StorageTypeName::I64 => create_column_scan_for_storage_type!(I64, i64),
+                StorageTypeName::U128 => create_column_scan_for_storage_type!(U128, u128),
                 StorageTypeName::Float => create_column_scan_for_storage_type!(Float, Float),
                 StorageTypeName::Double => create_column_scan_for_storage_type!(Double, Double),
             };
@@ -613,6 +614,9 @@ mod test {
                             DataValueT::U64(layer_1_equality),
                         ))],
                         avoid_values: vec![],
+                        allowed_values: None,
+                        text_queries: vec![],
+                        similar_values: vec![],
                     },
                 ),
                 (
@@ -625,6 +629,9 @@ mod test {
                             DataValueT::U64(layer_3_equality),
                         ))],
                         avoid_values: vec![],
+                        allowed_values: None,
+                        text_queries: vec![],
+                        similar_values: vec![],
                     },
                 ),
             ]),
@@ -6,11 +6,16 @@ use crate::{
         },
         traits::columnscan::{ColumnScan, ColumnScanCell, ColumnScanEnum, ColumnScanT},
     },
-    datatypes::{DataValueT, Double, Float, StorageTypeName, StorageValueT},
+    datatypes::{
+        casting::{ImplicitCastFrom, ImplicitCastInto},
+        DataValueT, Double, Float, StorageTypeName, StorageValueT,
+    },
+    dictionary::TextQuery,
     management::database::Dict,
     tabular::traits::partial_trie_scan::{PartialTrieScan, TrieScanEnum},
 };
 use std::fmt::Debug;
+use std::rc::Rc;
 use std::{cell::UnsafeCell, collections::HashMap};
 
 /// [`SelectEqualClasses`] contains a vectors that indicate which column indices should be forced to the same value
@@ -68,6 +73,7 @@ impl<'a> TrieScanSelectEqual<'a> {
                 StorageTypeName::U32 => init_scans_for_datatype!(U32),
                 StorageTypeName::U64 => init_scans_for_datatype!(U64),
                 StorageTypeName::I64 => init_scans_for_datatype!(I64),
+                StorageTypeName::U128 => init_scans_for_datatype!(U128),
                 StorageTypeName::Float => init_scans_for_datatype!(Float),
                 StorageTypeName::Double => init_scans_for_datatype!(Double),
             };
@@ -115,6 +121,7 @@ impl<'a> TrieScanSelectEqual<'a> {
                     StorageTypeName::U32 => init_scans_for_datatype!(U32),
                     StorageTypeName::U64 => init_scans_for_datatype!(U64),
                     StorageTypeName::I64 => init_scans_for_datatype!(I64),
+                    StorageTypeName::U128 => init_scans_for_datatype!(U128),
                     StorageTypeName::Float => init_scans_for_datatype!(Float),
                     StorageTypeName::Double => init_scans_for_datatype!(Double),
                 }
@@ -191,14 +198,71 @@ pub struct ValueAssignment {
     pub upper_bounds: Vec<FilterBound<DataValueT>>,
     /// List of values that the column must avoid.
     pub avoid_values: Vec<FilterValue<DataValueT>>,
+    /// List of the only values that the column may take, if restricted.
+    pub allowed_values: Option<Vec<DataValueT>>,
+    /// List of queries that the strings represented by the column must match.
+    ///
+    /// These are resolved to `allowed_values` by the [`DatabaseInstance`][crate::management::DatabaseInstance]
+    /// right before the restriction is applied, when all strings of the restricted table are in the dictionary.
+    pub text_queries: Vec<TextQuery>,
+    /// List of values together with the maximal edit distance
+    /// that the strings represented by the column may have from their strings.
+    ///
+    /// Constants are resolved to `allowed_values` like `text_queries`,
+    /// while the strings similar to the values of other columns are looked up while scanning the trie,
+    /// see [`TrieScanRestrictValues::with_similar_values`].
+    pub similar_values: Vec<(FilterValue<DataValueT>, usize)>,
+}
+
+/// Function returning the sorted dictionary indices of the strings
+/// within the given edit distance of the string with the given index
+pub type SimilarValuesFunction = Rc<dyn Fn(u64, usize) -> Vec<u64>>;
+
+impl ValueAssignment {
+    /// Create a [`ValueAssignment`] which restricts a column to a single value.
+    pub fn equal(value: DataValueT) -> Self {
+        let value = FilterValue::Constant(value);
+
+        Self {
+            lower_bounds: vec![FilterBound::Inclusive(value.clone())],
+            upper_bounds: vec![FilterBound::Inclusive(value)],
+            avoid_values: Vec::new(),
+            allowed_values: None,
+            text_queries: Vec::new(),
+            similar_values: Vec::new(),
+        }
+    }
 }
 
 impl<'a> TrieScanRestrictValues<'a> {
     /// Construct new TrieScanRestrictValues object.
+    ///
+    /// The assignments must not require the strings of a column to be similar to those of another column,
+    /// see [`TrieScanRestrictValues::with_similar_values`].
     pub fn new(
         dict: &mut Dict,
         base_trie: TrieScanEnum<'a>,
         assignments: &HashMap<usize, ValueAssignment>,
+    ) -> Self {
+        Self::new_internal(dict, base_trie, assignments, None)
+    }
+
+    /// Construct new TrieScanRestrictValues object,
+    /// which uses the given function to look up the strings that are similar to the values of other columns.
+    pub fn with_similar_values(
+        dict: &mut Dict,
+        base_trie: TrieScanEnum<'a>,
+        assignments: &HashMap<usize, ValueAssignment>,
+        similar_values: SimilarValuesFunction,
+    ) -> Self {
+        Self::new_internal(dict, base_trie, assignments, Some(similar_values))
+    }
+
+    fn new_internal(
+        dict: &mut Dict,
+        base_trie: TrieScanEnum<'a>,
+        assignments: &HashMap<usize, ValueAssignment>,
+        similar_values: Option<SimilarValuesFunction>,
     ) -> Self {
         let column_types = base_trie.get_types();
         let arity = column_types.len();
@@ -228,6 +292,7 @@ impl<'a> TrieScanRestrictValues<'a> {
                 StorageTypeName::U32 => init_scans_for_datatype!(U32),
                 StorageTypeName::U64 => init_scans_for_datatype!(U64),
                 StorageTypeName::I64 => init_scans_for_datatype!(I64),
+                StorageTypeName::U128 => init_scans_for_datatype!(U128),
                 StorageTypeName::Float => init_scans_for_datatype!(Float),
                 StorageTypeName::Double => init_scans_for_datatype!(Double),
             }
@@ -264,6 +329,11 @@ impl<'a> TrieScanRestrictValues<'a> {
         }
 
         for (column_idx_value, assignment) in assignments {
+            debug_assert!(
+                assignment.text_queries.is_empty(),
+                "text queries have to be resolved to allowed values"
+            );
+
             macro_rules! init_scans_for_datatype {
                 ($variant:ident, $type:ty) => {{
                     let scan_value = if let ColumnScanT::$variant(scan) =
@@ -291,12 +361,25 @@ impl<'a> TrieScanRestrictValues<'a> {
                         .iter()
                         .map(|v| translate_filter_value!($variant, v, dict))
                         .collect();
+                    let mut similar_columns: Vec<(FilterValue<$type>, usize)> = assignment
+                        .similar_values
+                        .iter()
+                        .map(|(value, max_distance)| {
+                            debug_assert!(
+                                value.column_index().is_some(),
+                                "similar constants have to be resolved to allowed values"
+                            );
+
+                            (translate_filter_value!($variant, value, dict), *max_distance)
+                        })
+                        .collect();
 
                     for value in lower_bounds
                         .iter_mut()
                         .map(|b| b.value_mut())
                         .chain(upper_bounds.iter_mut().map(|b| b.value_mut()))
                         .chain(avoid_values.iter_mut())
+                        .chain(similar_columns.iter_mut().map(|(value, _)| value))
                     {
                         if let Some(column_index) = value.column_index_mut() {
                             let map_len = column_map.len();
@@ -322,14 +405,63 @@ impl<'a> TrieScanRestrictValues<'a> {
                         }
                     }
 
+                    let mut restrict_scan = ColumnScanRestrictValues::new(
+                        scan_value,
+                        scans_restriction,
+                        lower_bounds,
+                        upper_bounds,
+                        avoid_values,
+                    );
+
+                    if let Some(allowed_values) = &assignment.allowed_values {
+                        let mut allowed_values: Vec<$type> = allowed_values
+                            .iter()
+                            .map(|value| {
+                                if let StorageValueT::$variant(value_typed) =
+                                    value.to_storage_value_mut(dict)
+                                {
+                                    value_typed
+                                } else {
+                                    panic!(
+                                        "Expected a column scan of type {}",
+                                        stringify!($variant)
+                                    );
+                                }
+                            })
+                            .collect();
+                        allowed_values.sort_unstable();
+
+                        restrict_scan = restrict_scan.with_allowed_values(allowed_values);
+                    }
+
+                    for (value, max_distance) in similar_columns {
+                        let column = value
+                            .column_index()
+                            .expect("Only similarities to columns remain.");
+                        let similar_values = Rc::clone(similar_values.as_ref().expect(
+                            "Similarities to columns require a function looking up similar values.",
+                        ));
+
+                        restrict_scan = restrict_scan.with_related_values(
+                            column,
+                            Box::new(move |reference: $type| {
+                                let Ok(reference) = ImplicitCastInto::<u64>::cast_into(reference)
+                                else {
+                                    return Vec::new();
+                                };
+
+                                similar_values(reference, max_distance)
+                                    .into_iter()
+                                    .filter_map(|value| {
+                                        <$type as ImplicitCastFrom<u64>>::cast_from(value).ok()
+                                    })
+                                    .collect()
+                            }),
+                        );
+                    }
+
                     let next_scan = ColumnScanCell::new(ColumnScanEnum::ColumnScanRestrictValues(
-                        ColumnScanRestrictValues::new(
-                            scan_value,
-                            scans_restriction,
-                            lower_bounds,
-                            upper_bounds,
-                            avoid_values,
-                        ),
+                        restrict_scan,
                     ));
 
                     select_scans[*column_idx_value] =
@@ -341,6 +473,7 @@ impl<'a> TrieScanRestrictValues<'a> {
                 StorageTypeName::U32 => init_scans_for_datatype!(U32, u32),
                 StorageTypeName::U64 => init_scans_for_datatype!(U64, u64),
                 StorageTypeName::I64 => init_scans_for_datatype!(I64, i64),
+                StorageTypeName::U128 => init_scans_for_datatype!(U128, u128),
                 StorageTypeName::Float => init_scans_for_datatype!(Float, Float),
                 StorageTypeName::Double => init_scans_for_datatype!(Double, Double),
             }
@@ -517,6 +650,9 @@ mod test {
                             DataValueT::U64(4),
                         ))],
                         avoid_values: vec![],
+                        allowed_values: None,
+                        text_queries: vec![],
+                        similar_values: vec![],
                     },
                 ),
                 (
@@ -529,6 +665,9 @@ mod test {
                             DataValueT::U64(7),
                         ))],
                         avoid_values: vec![],
+                        allowed_values: None,
+                        text_queries: vec![],
+                        similar_values: vec![],
                     },
                 ),
             ]),
@@ -598,6 +737,9 @@ mod test {
                     lower_bounds: vec![],
                     upper_bounds: vec![FilterBound::Exclusive(FilterValue::Column(0))],
                     avoid_values: vec![],
+                    allowed_values: None,
+                    text_queries: vec![],
+                    similar_values: vec![],
                 },
             )]),
         );
@@ -651,6 +793,9 @@ mod test {
                     lower_bounds: vec![],
                     upper_bounds: vec![],
                     avoid_values: vec![FilterValue::Column(0)],
+                    allowed_values: None,
+                    text_queries: vec![],
+                    similar_values: vec![],
                 },
             )]),
         );
@@ -66,6 +66,7 @@ impl<'a> TrieScanUnion<'a> {
                 StorageTypeName::U32 => init_scans_for_datatype!(U32, u32),
                 StorageTypeName::U64 => init_scans_for_datatype!(U64, u64),
                 StorageTypeName::I64 => init_scans_for_datatype!(I64, i64),
+                StorageTypeName::U128 => init_scans_for_datatype!(U128, u128),
                 StorageTypeName::Float => init_scans_for_datatype!(Float, Float),
                 StorageTypeName::Double => init_scans_for_datatype!(Double, Double),
             };
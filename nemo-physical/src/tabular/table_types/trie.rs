@@ -1,4 +1,5 @@
 use std::cell::UnsafeCell;
+use std::cmp::Ordering;
 use std::mem::size_of;
 use std::ops::Deref;
 use std::{debug_assert, iter};
@@ -90,6 +91,136 @@ impl Trie {
         Self { types, columns }
     }
 
+    /// Construct a new Trie from columns of equal length whose rows are already sorted lexicographically,
+    /// e.g. since they have been read from a previously exported table.
+    ///
+    /// In contrast to [`Table::from_cols`], this skips sorting the rows,
+    /// which dominates the time of building large tries.
+    /// Duplicate rows are still removed.
+    /// Use [`Trie::cols_are_sorted`] to check whether columns of unknown origin may be passed here.
+    pub fn from_sorted_cols(cols: Vec<VecT>) -> Self {
+        debug_assert!(Self::cols_are_sorted(&cols));
+
+        if cols.first().is_none_or(VecT::is_empty) {
+            return Self::from_cols(cols);
+        }
+
+        let column_len = cols[0].len();
+        let sorted_cols = cols
+            .into_iter()
+            .map(|col| {
+                let column_type = col.get_type();
+                let values = (0..col.len()).map(move |index| {
+                    col.get(index)
+                        .expect("index is smaller than the length of the column")
+                });
+
+                (column_type, values)
+            })
+            .collect();
+
+        Self::condense_sorted_cols(sorted_cols, column_len)
+    }
+
+    /// Return whether the given columns have equal length
+    /// and their rows are sorted lexicographically, possibly containing duplicates.
+    pub fn cols_are_sorted(cols: &[VecT]) -> bool {
+        let Some(column_len) = cols.first().map(VecT::len) else {
+            return true;
+        };
+        if cols.iter().any(|col| col.len() != column_len) {
+            return false;
+        }
+
+        (1..column_len).all(|row| {
+            cols.iter()
+                .map(|col| col.compare_idx(row - 1, row))
+                .find(|ordering| *ordering != Some(Ordering::Equal))
+                .is_none_or(|ordering| ordering == Some(Ordering::Less))
+        })
+    }
+
+    /// Build a trie from columns of equal length whose rows are sorted lexicographically,
+    /// given as the type and an iterator over the values of each column.
+    /// Consecutive duplicate rows are removed.
+    ///
+    /// The columns must not be empty.
+    fn condense_sorted_cols<Values>(
+        sorted_cols: Vec<(StorageTypeName, Values)>,
+        column_len: usize,
+    ) -> Self
+    where
+        Values: Iterator<Item = StorageValueT>,
+    {
+        // NOTE: we talk about "condensed" and "uncondensed" in the following
+        // "uncondensed" refers to the input version of the column vectors (and their indices), i.e. they have the same length and no duplicates have been removed
+        // "condensed" refers to the "cleaned up" version of the columns where duplicates have been removed (largely) and a trie structure is resembled
+        // we name our variables accordingly to clarify which version a column vector or index corresponds to
+        let mut last_uncondensed_interval_starts: Vec<usize> = vec![0];
+        let mut condensed_data_builders: Vec<ColumnBuilderAdaptiveT> = vec![];
+        let mut condensed_interval_starts_builders: Vec<ColumnBuilderAdaptive<usize>> = vec![];
+
+        for (column_type, mut sorted_col) in sorted_cols {
+            let mut current_uncondensed_interval_starts = vec![0];
+            let mut current_condensed_data: ColumnBuilderAdaptiveT =
+                ColumnBuilderAdaptiveT::new(column_type, Default::default(), Default::default());
+            let mut current_condensed_interval_starts_builder: ColumnBuilderAdaptive<usize> =
+                ColumnBuilderAdaptive::default();
+
+            let mut uncondensed_interval_ends =
+                last_uncondensed_interval_starts.iter().skip(1).copied();
+            let mut uncondensed_interval_end =
+                uncondensed_interval_ends.next().unwrap_or(column_len);
+
+            let mut current_val = sorted_col
+                .next()
+                .expect("callers ensure that the columns are not empty");
+            current_condensed_data.add(current_val);
+            current_condensed_interval_starts_builder.add(0);
+
+            for (next_val, uncondensed_col_index) in sorted_col.zip(1..) {
+                if next_val != current_val || uncondensed_col_index >= uncondensed_interval_end {
+                    current_uncondensed_interval_starts.push(uncondensed_col_index);
+                    current_val = next_val;
+                    current_condensed_data.add(current_val);
+                }
+
+                // if the second condition above is true, we need to adjust additional interval information
+                if uncondensed_col_index >= uncondensed_interval_end {
+                    current_condensed_interval_starts_builder
+                        .add(current_condensed_data.count() - 1);
+                    uncondensed_interval_end =
+                        uncondensed_interval_ends.next().unwrap_or(column_len);
+                }
+            }
+
+            last_uncondensed_interval_starts = current_uncondensed_interval_starts;
+            condensed_data_builders.push(current_condensed_data);
+            condensed_interval_starts_builders.push(current_condensed_interval_starts_builder);
+        }
+
+        macro_rules! build_interval_column {
+            ($col_builder:ident, $interval_builder:ident; $($variant:ident);+) => {
+                match $col_builder {
+                    $(ColumnBuilderAdaptiveT::$variant(data_col) => ColumnWithIntervalsT::$variant(
+                        ColumnWithIntervals::new(
+                            data_col.finalize(),
+                            $interval_builder.finalize(),
+                        ),
+                    )),+
+                }
+            }
+        }
+
+        Self::new(
+            condensed_data_builders
+                .into_iter()
+                .zip(condensed_interval_starts_builders)
+                .map(|(col, iv)| build_interval_column!(col, iv; U32; U64; I64; U128; Float; Double))
+                .collect(),
+        )
+    }
+
     /// Return reference to all columns.
     pub fn columns(&self) -> &Vec<ColumnWithIntervalsT> {
         &self.columns
@@ -158,6 +289,7 @@ impl Trie {
             StorageTypeName::U32 => last_column_for_datatype!(U32),
             StorageTypeName::U64 => last_column_for_datatype!(U64),
             StorageTypeName::I64 => last_column_for_datatype!(I64),
+            StorageTypeName::U128 => last_column_for_datatype!(U128),
             StorageTypeName::Float => last_column_for_datatype!(Float),
             StorageTypeName::Double => last_column_for_datatype!(Double),
         };
@@ -206,6 +338,7 @@ impl Trie {
                 StorageTypeName::U32 => push_column_for_datatype!(U32),
                 StorageTypeName::U64 => push_column_for_datatype!(U64),
                 StorageTypeName::I64 => push_column_for_datatype!(I64),
+                StorageTypeName::U128 => push_column_for_datatype!(U128),
                 StorageTypeName::Float => push_column_for_datatype!(Float),
                 StorageTypeName::Double => push_column_for_datatype!(Double),
             };
@@ -280,6 +413,7 @@ impl Trie {
             ColumnWithIntervalsT::U32(c) => build_iter!(U32, c),
             ColumnWithIntervalsT::U64(c) => build_iter!(U64, c),
             ColumnWithIntervalsT::I64(c) => build_iter!(I64, c),
+            ColumnWithIntervalsT::U128(c) => build_iter!(U128, c),
             ColumnWithIntervalsT::Float(c) => build_iter!(Float, c),
             ColumnWithIntervalsT::Double(c) => build_iter!(Double, c),
         }
@@ -344,7 +478,7 @@ impl Table for Trie {
                     .map(|v| {
                         let empty_data_col = ColumnBuilderAdaptiveT::new(v.get_type(), Default::default(), Default::default());
                         let empty_interval_col = ColumnBuilderAdaptive::<usize>::default();
-                        build_interval_column!(empty_data_col, empty_interval_col; U32; U64; I64; Float; Double)
+                        build_interval_column!(empty_data_col, empty_interval_col; U32; U64; I64; U128; Float; Double)
                     })
                     .collect(),
             );
@@ -357,83 +491,16 @@ impl Table for Trie {
         let sorted_cols: Vec<_> = cols
             .into_iter()
             .map(|col| {
-                permutator
+                let column_type = col.get_type();
+                let sorted_col = permutator
                     .permute_streaming(col)
-                    .expect("length matches since permutator is constructed from these vectores")
+                    .expect("length matches since permutator is constructed from these vectores");
+
+                (column_type, sorted_col)
             })
             .collect();
 
-        // NOTE: we talk about "condensed" and "uncondensed" in the following
-        // "uncondensed" refers to the input version of the column vectors (and their indices), i.e. they have the same length and no duplicates have been removed
-        // "condensed" refers to the "cleaned up" version of the columns where duplicates have been removed (largely) and a trie structure is resembled
-        // we name our variables accordingly to clarify which version a column vector or index corresponds to
-        let mut last_uncondensed_interval_starts: Vec<usize> = vec![0];
-        let mut condensed_data_builders: Vec<ColumnBuilderAdaptiveT> = vec![];
-        let mut condensed_interval_starts_builders: Vec<ColumnBuilderAdaptive<usize>> = vec![];
-
-        for mut sorted_col in sorted_cols.into_iter() {
-            debug_assert_eq!(sorted_col.len(), column_len);
-            let mut current_uncondensed_interval_starts = vec![0];
-            let mut current_condensed_data: ColumnBuilderAdaptiveT = ColumnBuilderAdaptiveT::new(
-                sorted_col.get_type(),
-                Default::default(),
-                Default::default(),
-            );
-            let mut current_condensed_interval_starts_builder: ColumnBuilderAdaptive<usize> =
-                ColumnBuilderAdaptive::default();
-
-            let mut uncondensed_interval_ends =
-                last_uncondensed_interval_starts.iter().skip(1).copied();
-            let mut uncondensed_interval_end =
-                uncondensed_interval_ends.next().unwrap_or(column_len);
-
-            let mut current_val = sorted_col
-                .next()
-                .expect("we return early if the first column (and thus all) are empty");
-            current_condensed_data.add(current_val);
-            current_condensed_interval_starts_builder.add(0);
-
-            for (next_val, uncondensed_col_index) in sorted_col.zip(1..) {
-                if next_val != current_val || uncondensed_col_index >= uncondensed_interval_end {
-                    current_uncondensed_interval_starts.push(uncondensed_col_index);
-                    current_val = next_val;
-                    current_condensed_data.add(current_val);
-                }
-
-                // if the second condition above is true, we need to adjust additional interval information
-                if uncondensed_col_index >= uncondensed_interval_end {
-                    current_condensed_interval_starts_builder
-                        .add(current_condensed_data.count() - 1);
-                    uncondensed_interval_end =
-                        uncondensed_interval_ends.next().unwrap_or(column_len);
-                }
-            }
-
-            last_uncondensed_interval_starts = current_uncondensed_interval_starts;
-            condensed_data_builders.push(current_condensed_data);
-            condensed_interval_starts_builders.push(current_condensed_interval_starts_builder);
-        }
-
-        macro_rules! build_interval_column {
-            ($col_builder:ident, $interval_builder:ident; $($variant:ident);+) => {
-                match $col_builder {
-                    $(ColumnBuilderAdaptiveT::$variant(data_col) => ColumnWithIntervalsT::$variant(
-                        ColumnWithIntervals::new(
-                            data_col.finalize(),
-                            $interval_builder.finalize(),
-                        ),
-                    )),+
-                }
-            }
-        }
-
-        Self::new(
-            condensed_data_builders
-                .into_iter()
-                .zip(condensed_interval_starts_builders)
-                .map(|(col, iv)| build_interval_column!(col, iv; U32; U64; I64; Float; Double))
-                .collect(),
-        )
+        Self::condense_sorted_cols(sorted_cols, column_len)
     }
 
     fn from_rows(rows: &[Vec<StorageValueT>]) -> Self {
@@ -491,7 +558,7 @@ impl<'a> TrieScanGeneric<'a> {
     /// Construct a new trie iterator but converts each column to the given types.
     pub fn new_cast(trie: &'a Trie, column_types: Vec<StorageTypeName>) -> Self {
         debug_assert!(trie.get_types().len() == column_types.len());
-        log::trace!("TrieScanGeneric: casting to {:?}", column_types);
+        tracing::trace!("TrieScanGeneric: casting to {:?}", column_types);
 
         let mut layers = Vec::<UnsafeCell<ColumnScanT<'a>>>::new();
 
@@ -693,6 +760,25 @@ mod test {
         assert_eq!(expected_trie, constructed_trie);
     }
 
+    #[test]
+    fn construct_trie_from_sorted_cols() {
+        let cols = get_test_table_as_cols();
+        assert!(!Trie::cols_are_sorted(&cols));
+
+        // sorted version of the test table, where the row `1 2 8` occurs twice
+        let sorted_cols = vec![
+            VecT::U64(vec![1, 1, 1, 1, 2, 2]),
+            VecT::U64(vec![2, 2, 2, 3, 3, 6]),
+            VecT::U64(vec![7, 8, 8, 8, 9, 9]),
+        ];
+        assert!(Trie::cols_are_sorted(&sorted_cols));
+        assert_eq!(Trie::from_sorted_cols(sorted_cols), get_test_table_as_trie());
+
+        let empty_cols = vec![VecT::U64(vec![]), VecT::U64(vec![])];
+        assert!(Trie::cols_are_sorted(&empty_cols));
+        assert_eq!(Trie::from_sorted_cols(empty_cols).row_num(), 0);
+    }
+
     #[test]
     fn construct_trie_from_rows() {
         let rows = get_test_table_as_rows();
@@ -836,6 +922,7 @@ mod test {
             StorageValueIteratorT::U32(i) => VecT::U32(i.collect()),
             StorageValueIteratorT::U64(i) => VecT::U64(i.collect()),
             StorageValueIteratorT::I64(i) => VecT::I64(i.collect()),
+            StorageValueIteratorT::U128(i) => VecT::U128(i.collect()),
             StorageValueIteratorT::Float(i) => VecT::Float(i.collect()),
             StorageValueIteratorT::Double(i) => VecT::Double(i.collect()),
         }
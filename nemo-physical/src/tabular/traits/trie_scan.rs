@@ -20,3 +20,17 @@ pub trait TrieScan {
     /// Returns the number of layers of the current trie scan
     fn column_types(&self) -> &[StorageTypeName];
 }
+
+impl<T: TrieScan + ?Sized> TrieScan for &mut T {
+    fn advance_on_layer(&mut self, layer: usize) -> Option<usize> {
+        (**self).advance_on_layer(layer)
+    }
+
+    fn current(&mut self, layer: usize) -> StorageValueT {
+        (**self).current(layer)
+    }
+
+    fn column_types(&self) -> &[StorageTypeName] {
+        (**self).column_types()
+    }
+}
@@ -89,6 +89,7 @@ macro_rules! generate_datatype_forwarder {
                                     U32,
                                     U64,
                                     I64,
+                                    U128,
                                     Float,
                                     Double);
     }
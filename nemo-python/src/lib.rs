@@ -1,4 +1,7 @@
-use std::{collections::HashSet, fs::read_to_string};
+use std::{
+    collections::{HashMap, HashSet},
+    fs::read_to_string,
+};
 
 use nemo::{
     datatypes::Double,
@@ -104,6 +107,9 @@ impl NemoResults {
                     PrimitiveLogicalValueT::Float64(d) => {
                         f64::from(Double::from(d)).into_py(slf.py())
                     }
+                    PrimitiveLogicalValueT::DateTime(t) => t.to_string().into_py(slf.py()),
+                    PrimitiveLogicalValueT::Uuid(u) => u.to_string().into_py(slf.py()),
+                    PrimitiveLogicalValueT::IpAddress(a) => a.to_string().into_py(slf.py()),
                 })
                 .collect(),
         )
@@ -133,21 +139,35 @@ impl NemoEngine {
         output_manager: &PyCell<NemoOutputManager>,
     ) -> PyResult<()> {
         let identifier = predicate.into();
-        let mut writer = output_manager
-            .borrow()
-            .0
-            .create_file_writer(&identifier)
-            .py_res()?;
+        let output_manager = &output_manager.borrow().0;
+        let mut writer = output_manager.create_file_writer(&identifier).py_res()?;
 
         if let Some(record_iter) = self.0.output_serialization(identifier).py_res()? {
             for record in record_iter {
                 writer.write_record(record).py_res()?;
             }
         }
+        writer.finish().py_res()?;
+        output_manager.finalize().py_res()?;
 
         Ok(())
     }
 
+    fn apply_rule_once(
+        mut slf: PyRefMut<'_, Self>,
+        rule_index: usize,
+    ) -> PyResult<HashMap<String, Py<NemoResults>>> {
+        let derived = slf.0.apply_rule_once(rule_index).py_res()?;
+
+        derived
+            .into_iter()
+            .map(|(predicate, rows)| {
+                let results = NemoResults(Box::new(rows.into_iter()));
+                Ok((predicate.name(), Py::new(slf.py(), results)?))
+            })
+            .collect()
+    }
+
     fn result(mut slf: PyRefMut<'_, Self>, predicate: String) -> PyResult<Py<NemoResults>> {
         let iter = slf.0.table_scan(predicate.into()).py_res()?;
         let results = NemoResults(Box::new(
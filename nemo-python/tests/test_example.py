@@ -60,5 +60,27 @@ class TestExample(unittest.TestCase):
                 self.assertEqual(results, self.expected_serialized_result)
 
 
+class TestApplyRuleOnce(unittest.TestCase):
+    def test_apply_rule_once(self):
+        rules = """
+        edge(1, 2) .
+        edge(2, 3) .
+
+        path(?x, ?y) :- edge(?x, ?y) .
+        path(?x, ?z) :- path(?x, ?y), edge(?y, ?z) .
+        """
+
+        engine = NemoEngine(load_string(rules))
+
+        derived = engine.apply_rule_once(0)
+        self.assertEqual(list(derived.keys()), ["path"])
+        self.assertEqual(sorted(derived["path"]), [[1, 2], [2, 3]])
+
+        derived = engine.apply_rule_once(1)
+        self.assertEqual(list(derived["path"]), [[1, 3]])
+
+        self.assertEqual(engine.apply_rule_once(1), {})
+
+
 if __name__ == "__main__":
     unittest.main(verbosity=2)
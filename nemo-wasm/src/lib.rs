@@ -330,6 +330,9 @@ impl NemoResults {
                     PrimitiveLogicalValueT::String(s) => JsValue::from(String::from(s)),
                     PrimitiveLogicalValueT::Integer(i) => JsValue::from(i64::from(i)),
                     PrimitiveLogicalValueT::Float64(d) => JsValue::from(f64::from(Double::from(d))),
+                    PrimitiveLogicalValueT::DateTime(t) => JsValue::from(t.to_string()),
+                    PrimitiveLogicalValueT::Uuid(u) => JsValue::from(u.to_string()),
+                    PrimitiveLogicalValueT::IpAddress(a) => JsValue::from(a.to_string()),
                 })
                 .collect();
 
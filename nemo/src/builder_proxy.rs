@@ -19,7 +19,10 @@ use nemo_physical::{
 use crate::{
     error::ReadingError,
     io::parser::{parse_bare_name, span_from_str},
-    model::types::primitive_logical_value::{LogicalFloat64, LogicalInteger, LogicalString},
+    model::types::primitive_logical_value::{
+        LogicalDateTime, LogicalFloat64, LogicalInteger, LogicalIpAddress, LogicalString,
+        LogicalUuid,
+    },
 };
 
 use super::model::Term;
@@ -48,6 +51,12 @@ pub enum LogicalColumnBuilderProxyT<'a, 'b> {
     Integer(LogicalIntegerColumnBuilderProxy<'b>),
     /// Float64 variant
     Float64(LogicalFloat64ColumnBuilderProxy<'b>),
+    /// DateTime variant
+    DateTime(LogicalDateTimeColumnBuilderProxy<'b>),
+    /// Uuid variant
+    Uuid(LogicalUuidColumnBuilderProxy<'b>),
+    /// IpAddress variant
+    IpAddress(LogicalIpAddressColumnBuilderProxy<'b>),
 }
 
 impl<'a, 'b, T> ColumnBuilderProxy<T> for LogicalColumnBuilderProxyT<'a, 'b>
@@ -56,6 +65,9 @@ where
     LogicalStringColumnBuilderProxy<'a, 'b>: ColumnBuilderProxy<T>,
     LogicalIntegerColumnBuilderProxy<'b>: ColumnBuilderProxy<T>,
     LogicalFloat64ColumnBuilderProxy<'b>: ColumnBuilderProxy<T>,
+    LogicalDateTimeColumnBuilderProxy<'b>: ColumnBuilderProxy<T>,
+    LogicalUuidColumnBuilderProxy<'b>: ColumnBuilderProxy<T>,
+    LogicalIpAddressColumnBuilderProxy<'b>: ColumnBuilderProxy<T>,
 {
     fn commit(&mut self) {
         match self {
@@ -71,6 +83,15 @@ where
             Self::Float64(lcbp) => {
                 <LogicalFloat64ColumnBuilderProxy as ColumnBuilderProxy<T>>::commit(lcbp)
             }
+            Self::DateTime(lcbp) => {
+                <LogicalDateTimeColumnBuilderProxy as ColumnBuilderProxy<T>>::commit(lcbp)
+            }
+            Self::Uuid(lcbp) => {
+                <LogicalUuidColumnBuilderProxy as ColumnBuilderProxy<T>>::commit(lcbp)
+            }
+            Self::IpAddress(lcbp) => {
+                <LogicalIpAddressColumnBuilderProxy as ColumnBuilderProxy<T>>::commit(lcbp)
+            }
         }
     }
 
@@ -88,6 +109,15 @@ where
             Self::Float64(lcbp) => {
                 <LogicalFloat64ColumnBuilderProxy as ColumnBuilderProxy<T>>::forget(lcbp)
             }
+            Self::DateTime(lcbp) => {
+                <LogicalDateTimeColumnBuilderProxy as ColumnBuilderProxy<T>>::forget(lcbp)
+            }
+            Self::Uuid(lcbp) => {
+                <LogicalUuidColumnBuilderProxy as ColumnBuilderProxy<T>>::forget(lcbp)
+            }
+            Self::IpAddress(lcbp) => {
+                <LogicalIpAddressColumnBuilderProxy as ColumnBuilderProxy<T>>::forget(lcbp)
+            }
         }
     }
 
@@ -97,6 +127,9 @@ where
             Self::String(lcbp) => lcbp.add(input),
             Self::Integer(lcbp) => lcbp.add(input),
             Self::Float64(lcbp) => lcbp.add(input),
+            Self::DateTime(lcbp) => lcbp.add(input),
+            Self::Uuid(lcbp) => lcbp.add(input),
+            Self::IpAddress(lcbp) => lcbp.add(input),
         }
     }
 }
@@ -250,7 +283,119 @@ where
     }
 }
 
-fn parse_rdf_term_from_string(input: String) -> Term {
+/// Logical [`ColumnBuilderProxy`] to add DateTime
+#[derive(Debug)]
+pub struct LogicalDateTimeColumnBuilderProxy<'b> {
+    inner: &'b mut PhysicalGenericColumnBuilderProxy<i64>,
+}
+
+impl<'a, 'b> LogicalDateTimeColumnBuilderProxy<'b> {
+    /// Create new LogicalDateTimeColumnBuilderProxy from PhysicalI64ColumnBuilderProxy (wrapped in enum)
+    pub fn new(physical_builder_proxy: &'b mut PhysicalBuilderProxyEnum<'a>) -> Self {
+        match physical_builder_proxy {
+            PhysicalBuilderProxyEnum::I64(inner) => Self { inner },
+            _ => unreachable!("If the database representation of the logical types is correct, we never reach this branch.")
+        }
+    }
+
+    /// wrap LogicalDateTimeColumnBuilderProxy into GenericLogicalParser
+    pub fn into_parser<Intermediate>(self) -> GenericLogicalParser<Intermediate, Self>
+    where
+        Self: ColumnBuilderProxy<Intermediate>,
+    {
+        GenericLogicalParser::new(self)
+    }
+}
+
+impl<T> ColumnBuilderProxy<T> for LogicalDateTimeColumnBuilderProxy<'_>
+where
+    LogicalDateTime: TryFrom<T>,
+    ReadingError: From<<LogicalDateTime as TryFrom<T>>::Error>,
+{
+    logical_generic_trait_impl!();
+
+    fn add(&mut self, input: T) -> Result<(), ReadingError> {
+        <Self as ColumnBuilderProxy<T>>::commit(self);
+        self.inner.add(LogicalDateTime::try_from(input)?.into())
+    }
+}
+
+/// Logical [`ColumnBuilderProxy`] to add Uuid
+#[derive(Debug)]
+pub struct LogicalUuidColumnBuilderProxy<'b> {
+    inner: &'b mut PhysicalGenericColumnBuilderProxy<u128>,
+}
+
+impl<'a, 'b> LogicalUuidColumnBuilderProxy<'b> {
+    /// Create new LogicalUuidColumnBuilderProxy from PhysicalU128ColumnBuilderProxy (wrapped in enum)
+    pub fn new(physical_builder_proxy: &'b mut PhysicalBuilderProxyEnum<'a>) -> Self {
+        match physical_builder_proxy {
+            PhysicalBuilderProxyEnum::U128(inner) => Self { inner },
+            _ => unreachable!("If the database representation of the logical types is correct, we never reach this branch.")
+        }
+    }
+
+    /// wrap LogicalUuidColumnBuilderProxy into GenericLogicalParser
+    pub fn into_parser<Intermediate>(self) -> GenericLogicalParser<Intermediate, Self>
+    where
+        Self: ColumnBuilderProxy<Intermediate>,
+    {
+        GenericLogicalParser::new(self)
+    }
+}
+
+impl<T> ColumnBuilderProxy<T> for LogicalUuidColumnBuilderProxy<'_>
+where
+    LogicalUuid: TryFrom<T>,
+    ReadingError: From<<LogicalUuid as TryFrom<T>>::Error>,
+{
+    logical_generic_trait_impl!();
+
+    fn add(&mut self, input: T) -> Result<(), ReadingError> {
+        <Self as ColumnBuilderProxy<T>>::commit(self);
+        self.inner.add(LogicalUuid::try_from(input)?.into())
+    }
+}
+
+/// Logical [`ColumnBuilderProxy`] to add IpAddress
+#[derive(Debug)]
+pub struct LogicalIpAddressColumnBuilderProxy<'b> {
+    inner: &'b mut PhysicalGenericColumnBuilderProxy<u128>,
+}
+
+impl<'a, 'b> LogicalIpAddressColumnBuilderProxy<'b> {
+    /// Create new LogicalIpAddressColumnBuilderProxy from PhysicalU128ColumnBuilderProxy (wrapped in enum)
+    pub fn new(physical_builder_proxy: &'b mut PhysicalBuilderProxyEnum<'a>) -> Self {
+        match physical_builder_proxy {
+            PhysicalBuilderProxyEnum::U128(inner) => Self { inner },
+            _ => unreachable!("If the database representation of the logical types is correct, we never reach this branch.")
+        }
+    }
+
+    /// wrap LogicalIpAddressColumnBuilderProxy into GenericLogicalParser
+    pub fn into_parser<Intermediate>(self) -> GenericLogicalParser<Intermediate, Self>
+    where
+        Self: ColumnBuilderProxy<Intermediate>,
+    {
+        GenericLogicalParser::new(self)
+    }
+}
+
+impl<T> ColumnBuilderProxy<T> for LogicalIpAddressColumnBuilderProxy<'_>
+where
+    LogicalIpAddress: TryFrom<T>,
+    ReadingError: From<<LogicalIpAddress as TryFrom<T>>::Error>,
+{
+    logical_generic_trait_impl!();
+
+    fn add(&mut self, input: T) -> Result<(), ReadingError> {
+        <Self as ColumnBuilderProxy<T>>::commit(self);
+        self.inner.add(LogicalIpAddress::try_from(input)?.into())
+    }
+}
+
+/// Parse a field of a DSV file or a similar format into a [`Term`].
+pub(crate) fn parse_rdf_term_from_string(input: String) -> Term {
     const BASE: &str = "a:";
 
     let trimmed = input.trim();
@@ -390,11 +535,48 @@ where
     }
 }
 
+impl<T> ColumnBuilderProxy<String> for GenericLogicalParser<LogicalDateTime, T>
+where
+    T: ColumnBuilderProxy<LogicalDateTime>,
+{
+    logical_generic_trait_impl!();
+
+    fn add(&mut self, input: String) -> Result<(), ReadingError> {
+        <Self as ColumnBuilderProxy<String>>::commit(self);
+        self.inner.add(input.trim().parse()?)
+    }
+}
+
+impl<T> ColumnBuilderProxy<String> for GenericLogicalParser<LogicalUuid, T>
+where
+    T: ColumnBuilderProxy<LogicalUuid>,
+{
+    logical_generic_trait_impl!();
+
+    fn add(&mut self, input: String) -> Result<(), ReadingError> {
+        <Self as ColumnBuilderProxy<String>>::commit(self);
+        self.inner.add(input.trim().parse()?)
+    }
+}
+
+impl<T> ColumnBuilderProxy<String> for GenericLogicalParser<LogicalIpAddress, T>
+where
+    T: ColumnBuilderProxy<LogicalIpAddress>,
+{
+    logical_generic_trait_impl!();
+
+    fn add(&mut self, input: String) -> Result<(), ReadingError> {
+        <Self as ColumnBuilderProxy<String>>::commit(self);
+        self.inner.add(input.trim().parse()?)
+    }
+}
+
 #[cfg(test)]
 mod test {
     use nemo_physical::{
         datatypes::storage_value::VecT,
-        dictionary::{Dictionary, PrefixedStringDictionary},
+        dictionary::Dictionary,
+        management::database::Dict,
     };
     use test_log::test;
 
@@ -556,7 +738,7 @@ mod test {
         })
         .unwrap();
 
-        let mut dict = std::cell::RefCell::new(PrefixedStringDictionary::default());
+        let mut dict = std::cell::RefCell::new(Dict::default());
 
         let physical_builder_for_any_column = PhysicalStringColumnBuilderProxy::new(&dict);
         let physical_builder_for_string_column = PhysicalStringColumnBuilderProxy::new(&dict);
@@ -5,8 +5,18 @@ use std::path::PathBuf;
 use thiserror::Error;
 
 use crate::{
-    execution::selection_strategy::strategy::SelectionStrategyError, io::parser::LocatedParseError,
-    model::chase_model::RuleTranslationError, model::types::error::TypeError,
+    datatypes::StorageTypeName,
+    execution::{
+        selection_strategy::strategy::SelectionStrategyError, ExportError, FunctionError,
+        HypotheticalError, QueryError, TopDownError,
+    },
+    io::{
+        compiled_program::CompiledProgramError, environment::InterpolationError, n3::N3Error,
+        parser::LocatedParseError, r2rml::R2rmlError, swrl::SwrlError, tptp::TptpError,
+    },
+    model::chase_model::{AggregateError, RuleTranslationError},
+    model::types::{error::TypeError, primitive_types::PrimitiveType},
+    model::{ArityError, CompositionError, ProgramBuildError},
     program_analysis::analysis::RuleAnalysisError,
 };
 
@@ -28,15 +38,92 @@ pub enum Error {
     /// Error if no input rule files are specified
     #[error("No inputs were specified")]
     NoInput,
+    /// Error if a rule is referenced by an index that does not exist
+    #[error("The program has no rule with index {0}")]
+    UnknownRule(usize),
+    /// Error if a predicate has more facts than allowed by its `@limit` directive
+    #[error("The predicate {predicate} has {count} facts, exceeding its limit of {limit} facts")]
+    PredicateLimitExceeded {
+        /// The predicate
+        predicate: String,
+        /// Number of facts of the predicate
+        count: usize,
+        /// Maximal number of facts of the predicate
+        limit: usize,
+    },
+    /// Error if the output files of a predicate are split by a column it does not have
+    #[error("The output files of predicate {predicate} cannot be split by column {column}, since it only has {arity} columns")]
+    OutputSplitColumn {
+        /// The predicate
+        predicate: String,
+        /// Index of the column, starting at 0
+        column: usize,
+        /// Arity of the predicate
+        arity: usize,
+    },
+    /// Error if the columns of a table added to a predicate have different lengths
+    #[error("The columns of the table added to predicate {0} have different lengths")]
+    ColumnLengthMismatch(String),
+    /// Error if a column of a table added to a predicate does not match the type of the predicate
+    #[error("Column {column} of the table added to predicate {predicate} contains values of type {found}, but its type requires values of type {expected}")]
+    ColumnTypeMismatch {
+        /// The predicate
+        predicate: String,
+        /// Index of the column, starting at 0
+        column: usize,
+        /// Storage type required by the predicate
+        expected: StorageTypeName,
+        /// Storage type of the column
+        found: StorageTypeName,
+    },
+    /// Error if a column of a table added to a predicate contains a value that is not the id of a dictionary entry
+    #[error("Column {column} of the table added to predicate {predicate} contains {id}, which is not the id of an entry of the dictionary")]
+    UnknownDictionaryId {
+        /// The predicate
+        predicate: String,
+        /// Index of the column, starting at 0
+        column: usize,
+        /// The value of the column
+        id: u64,
+    },
+    /// Error if the dictionary id of a value of a type is requested, whose values are not stored in the dictionary
+    #[error("Values of type {0} are not stored in the dictionary")]
+    NoDictionaryType(PrimitiveType),
+    /// Error if there are no saved results of a predicate
+    #[error("No saved results of predicate {predicate} found in {directory:?}")]
+    MissingSavedResults {
+        /// The predicate
+        predicate: String,
+        /// Directory containing the saved results
+        directory: PathBuf,
+    },
     /// Error if the user asked for an unimplemented feature
     #[error("Multiple file support is not yet implemented")]
     MultipleFilesNotImplemented,
+    /// Query errors
+    #[error(transparent)]
+    QueryError(#[from] QueryError),
+    /// Errors concerning hypothetical changes of facts
+    #[error(transparent)]
+    HypotheticalError(#[from] HypotheticalError),
+    /// Errors concerning top-down evaluation of queries
+    #[error(transparent)]
+    TopDownError(#[from] TopDownError),
+    /// Errors concerning variables in resources
+    #[error(transparent)]
+    InterpolationError(#[from] InterpolationError),
+    /// Errors concerning user-defined functions
+    #[error(transparent)]
+    FunctionError(#[from] FunctionError),
     /// Rule analysis errors
     #[error(transparent)]
     RuleAnalysisError(#[from] RuleAnalysisError),
     /// Rule translation errors
     #[error(transparent)]
     RuleTranslationError(#[from] RuleTranslationError),
+    /// Aggregate errors
+    #[error(transparent)]
+    AggregateError(#[from] AggregateError),
     /// Parse errors
     #[error(transparent)]
     ParseError(#[from] LocatedParseError),
@@ -65,6 +152,37 @@ pub enum Error {
     /// CSV serialization/deserialization error
     #[error(transparent)]
     CsvError(#[from] csv::Error),
+    /// Error when consuming or producing Kafka messages
+    #[cfg(feature = "kafka")]
+    #[error(transparent)]
+    KafkaError(#[from] rdkafka::error::KafkaError),
+    /// Error when composing programs
+    #[error(transparent)]
+    CompositionError(#[from] CompositionError),
+    /// Error when building a program
+    #[error(transparent)]
+    ProgramBuildError(#[from] ProgramBuildError),
+    /// Error when predicates are used with different arities
+    #[error(transparent)]
+    ArityError(#[from] ArityError),
+    /// Error when importing an R2RML mapping
+    #[error(transparent)]
+    R2rmlError(#[from] R2rmlError),
+    /// Error when importing a TPTP problem
+    #[error(transparent)]
+    TptpError(#[from] TptpError),
+    /// Error when importing N3 rules
+    #[error(transparent)]
+    N3Error(#[from] N3Error),
+    /// Error when importing the SWRL rules of an ontology
+    #[error(transparent)]
+    SwrlError(#[from] SwrlError),
+    /// Error when exporting a program
+    #[error(transparent)]
+    ExportError(#[from] ExportError),
+    /// Error when reading a compiled program
+    #[error(transparent)]
+    CompiledProgramError(#[from] CompiledProgramError),
     /// Error in the physical layer
     #[error(transparent)]
     PhysicalError(#[from] nemo_physical::error::Error),
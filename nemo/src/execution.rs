@@ -3,21 +3,71 @@
 pub mod execution_engine;
 pub use execution_engine::ExecutionEngine;
 
+pub mod engine_state;
+pub use engine_state::{EngineState, PredicateState};
+
 use self::selection_strategy::{
     dependency_graph::graph_positive::GraphConstructorPositive,
-    strategy_graph::StrategyDependencyGraph, strategy_round_robin::StrategyRoundRobin,
+    strategy_datalog_first::StrategyDatalogFirst, strategy_graph::StrategyDependencyGraph,
+    strategy_priority::StrategyPriority, strategy_round_robin::StrategyRoundRobin,
     strategy_stratified_negation::StrategyStratifiedNegation,
 };
 
+pub mod aggregate_execution;
+
+pub mod anonymization;
+pub use anonymization::Anonymizer;
+
+pub mod export;
+pub use export::{ExportError, ExportedProgram, KeepNames, Renaming};
+
+pub mod deduplication;
+pub use deduplication::{DeduplicationReport, PredicateDeduplication};
+
+pub mod derivation;
+pub use derivation::Derivation;
+
+pub mod explanation;
+pub use explanation::{AtomExplanation, RuleExplanation};
+
+pub mod firing_log;
+pub use firing_log::{FiringLog, RuleFiring};
+
+pub mod function_registry;
+pub use function_registry::{FunctionError, FunctionRegistry};
+
+pub mod hypothetical;
+pub use hypothetical::{HypotheticalError, HypotheticalOutcome, HypotheticalTransaction};
+
 pub mod planning;
 
+pub use nemo_physical::management::memory_report::{MemoryFootprint, MemoryReport, StructureKind};
+
+pub mod query;
+pub use query::{PreparedQuery, QueryError};
+
 pub mod rule_execution;
+pub use rule_execution::ChaseVariant;
 
 pub mod selection_strategy;
 
+pub mod session;
+pub use session::Session;
+
+pub mod streaming;
+pub use streaming::{Delta, StreamingReasoner};
+
+pub mod top_down;
+pub use top_down::TopDownError;
+
+#[cfg(test)]
+mod test_util;
+
 /// The default strategy that will be used for reasoning
 pub type DefaultExecutionStrategy = StrategyStratifiedNegation<
-    StrategyDependencyGraph<GraphConstructorPositive, StrategyRoundRobin>,
+    StrategyPriority<
+        StrategyDependencyGraph<GraphConstructorPositive, StrategyDatalogFirst<StrategyRoundRobin>>,
+    >,
 >;
 
 /// Shorthand for an execution engine using the default strategy
@@ -0,0 +1,148 @@
+//! This module contains functionality for applying a rule that computes an aggregate.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::{
+    error::Error,
+    model::{
+        chase_model::{ChaseAggregate, ChaseRule},
+        types::error::TypeError,
+        Identifier, PrimitiveType, Term,
+    },
+    program_analysis::analysis::RuleAnalysis,
+    table_manager::TableManager,
+};
+
+use super::execution_engine::RuleInfo;
+
+/// Object responsible for executing a rule that computes an aggregate.
+///
+/// Such a rule has a single body atom containing the group of each aggregated value,
+/// followed by the aggregated values themselves (see [`ChaseAggregate`]).
+/// Since aggregates might be part of recursion,
+/// the facts of the aggregated predicate are replaced whenever the aggregate changes.
+#[derive(Debug)]
+pub struct AggregateExecution {
+    aggregate: ChaseAggregate,
+
+    input_predicate: Identifier,
+    input_types: Vec<PrimitiveType>,
+
+    output_predicate: Identifier,
+    output_types: Vec<PrimitiveType>,
+}
+
+impl AggregateExecution {
+    /// Create new [`AggregateExecution`].
+    pub fn initialize(rule: &ChaseRule, analysis: &RuleAnalysis) -> Self {
+        let aggregate = *rule
+            .aggregate()
+            .expect("Function assumes that the rule computes an aggregate.");
+
+        let types = |predicate: &Identifier| {
+            analysis
+                .predicate_types
+                .get(predicate)
+                .cloned()
+                .expect("All predicates should have types by now.")
+        };
+
+        let input_predicate = rule.positive_body()[0].predicate();
+        let output_predicate = rule.head()[0].predicate();
+
+        Self {
+            aggregate,
+            input_types: types(&input_predicate),
+            input_predicate,
+            output_types: types(&output_predicate),
+            output_predicate,
+        }
+    }
+
+    /// Execute the current rule.
+    /// Returns the predicates which received new elements.
+    pub fn execute(
+        &self,
+        table_manager: &mut TableManager,
+        rule_info: &RuleInfo,
+        step_number: usize,
+    ) -> Result<Vec<Identifier>, Error> {
+        match table_manager.last_step(self.input_predicate.clone()) {
+            Some(last_step) if last_step >= rule_info.step_last_applied => {}
+            _ => return Ok(Vec::new()),
+        }
+
+        let result = self.compute_aggregate(table_manager)?;
+        if result == self.current_facts(table_manager)? {
+            return Ok(Vec::new());
+        }
+
+        tracing::info!(
+            "Aggregate of {} changed, now containing {} facts",
+            self.output_predicate,
+            result.len()
+        );
+
+        let rows = result
+            .into_iter()
+            .map(|row| {
+                row.into_iter()
+                    .zip(self.output_types.iter())
+                    .map(|(term, logical_type)| logical_type.ground_term_to_data_value_t(term))
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(TypeError::from)?;
+
+        table_manager.replace_predicate(self.output_predicate.clone(), step_number, rows)?;
+
+        Ok(vec![self.output_predicate.clone()])
+    }
+
+    /// Return the facts of the aggregated predicate, computed from the current input facts.
+    fn compute_aggregate(
+        &self,
+        table_manager: &mut TableManager,
+    ) -> Result<BTreeSet<Vec<Term>>, Error> {
+        let Some(input_id) = table_manager.combine_predicate(self.input_predicate.clone())? else {
+            return Ok(BTreeSet::new());
+        };
+
+        let group_length = self.output_types.len() - 1;
+        let mut groups = BTreeMap::<Vec<Term>, Vec<Vec<Term>>>::new();
+
+        for row in table_manager.table_rows(input_id, &self.input_types)? {
+            let mut group: Vec<Term> = row.into_iter().map(Term::from).collect();
+            let values = group.split_off(group_length);
+
+            groups.entry(group).or_default().push(values);
+        }
+
+        let mut result = BTreeSet::new();
+        for (mut group, values) in groups {
+            if let Some(value) = self.aggregate.operation().aggregate(&values)? {
+                group.insert(self.aggregate.position(), value);
+                result.insert(group);
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// Return the facts that are currently stored for the aggregated predicate.
+    fn current_facts(
+        &self,
+        table_manager: &mut TableManager,
+    ) -> Result<BTreeSet<Vec<Term>>, Error> {
+        let Some(output_id) = table_manager.combine_predicate(self.output_predicate.clone())?
+        else {
+            return Ok(BTreeSet::new());
+        };
+
+        Ok(table_manager
+            .table_rows(output_id, &self.output_types)?
+            .into_iter()
+            .map(|row| row.into_iter().map(Term::from).collect())
+            .collect())
+    }
+}
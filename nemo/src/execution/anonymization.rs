@@ -0,0 +1,226 @@
+//! Replacing the names occurring in a program and its facts by meaningless ones,
+//! e.g. to share a reproducible bug report without disclosing proprietary data.
+//!
+//! The [`Anonymizer`] maps each name consistently, so that the anonymized program
+//! has the same structure as the original one and derives the same facts up to renaming.
+
+use std::collections::HashMap;
+
+use nemo_physical::{
+    datatypes::data_value::PhysicalString, dictionary::value_serializer::NULL_PREFIX,
+};
+
+use crate::model::{
+    types::primitive_logical_value::LOGICAL_NULL_PREFIX, Identifier, RdfLiteral, Term, Variable,
+    XSD_STRING,
+};
+
+use super::export::Renaming;
+
+/// Namespace of the IRIs replacing the IRIs of a program.
+const ANONYMOUS_NAMESPACE: &str = "https://example.org/";
+
+/// Namespace of XSD datatypes, whose values are kept unless they are strings.
+const XSD_NAMESPACE: &str = "http://www.w3.org/2001/XMLSchema#";
+
+/// Consistently replaces the names of predicates, variables, constants, IRIs and strings by meaningless ones.
+///
+/// Numbers and other values of XSD datatypes as well as language tags are kept,
+/// since they rarely disclose anything but may determine the results of a program.
+/// The kind of each name is preserved, e.g. IRIs are replaced by IRIs and blank nodes by blank nodes.
+#[derive(Debug, Default)]
+pub struct Anonymizer {
+    predicates: HashMap<Identifier, Identifier>,
+    variables: HashMap<Identifier, Identifier>,
+    constants: HashMap<String, String>,
+    strings: HashMap<String, String>,
+}
+
+impl Anonymizer {
+    /// Return the anonymized version of the given variable.
+    ///
+    /// Anonymous variables are kept.
+    pub fn variable(&mut self, variable: &Variable) -> Variable {
+        if variable.is_anonymous() {
+            return variable.clone();
+        }
+
+        match variable {
+            Variable::Universal(name) => Variable::Universal(self.variable_name(name)),
+            Variable::Existential(name) => Variable::Existential(self.variable_name(name)),
+        }
+    }
+
+    fn variable_name(&mut self, name: &Identifier) -> Identifier {
+        let count = self.variables.len();
+
+        self.variables
+            .entry(name.clone())
+            .or_insert_with(|| Identifier(format!("X{}", count + 1)))
+            .clone()
+    }
+
+    fn constant(&mut self, constant: &str) -> String {
+        if constant.starts_with(LOGICAL_NULL_PREFIX) {
+            return constant.to_string();
+        }
+
+        let count = self.constants.len();
+
+        self.constants
+            .entry(constant.to_string())
+            .or_insert_with(|| {
+                if constant.starts_with("_:") {
+                    format!("_:b{}", count + 1)
+                } else if constant.contains(':') {
+                    format!("{ANONYMOUS_NAMESPACE}c{}", count + 1)
+                } else {
+                    format!("c{}", count + 1)
+                }
+            })
+            .clone()
+    }
+
+    fn string(&mut self, value: &str) -> String {
+        let count = self.strings.len();
+
+        self.strings
+            .entry(value.to_string())
+            .or_insert_with(|| format!("s{}", count + 1))
+            .clone()
+    }
+
+    /// Return the anonymized version of an entry of the dictionary of an [`ExecutionEngine`][super::ExecutionEngine].
+    pub fn dictionary_entry(&mut self, entry: &str) -> String {
+        if entry.starts_with(NULL_PREFIX) {
+            return entry.to_string();
+        }
+
+        let term = Term::from(PhysicalString::from(entry.to_string()));
+
+        PhysicalString::try_from(self.term(&term))
+            .expect("Dictionary entries represent ground terms.")
+            .into()
+    }
+}
+
+impl Renaming for Anonymizer {
+    fn predicate(&mut self, predicate: &Identifier) -> Identifier {
+        let count = self.predicates.len();
+
+        self.predicates
+            .entry(predicate.clone())
+            .or_insert_with(|| Identifier(format!("p{}", count + 1)))
+            .clone()
+    }
+
+    fn term(&mut self, term: &Term) -> Term {
+        match term {
+            Term::Constant(Identifier(constant)) => {
+                Term::Constant(Identifier(self.constant(constant)))
+            }
+            Term::Variable(variable) => Term::Variable(self.variable(variable)),
+            Term::NumericLiteral(_) => term.clone(),
+            Term::StringLiteral(value) => Term::StringLiteral(self.string(value)),
+            Term::RdfLiteral(RdfLiteral::LanguageString { value, tag }) => {
+                Term::RdfLiteral(RdfLiteral::LanguageString {
+                    value: self.string(value),
+                    tag: tag.clone(),
+                })
+            }
+            Term::RdfLiteral(RdfLiteral::DatatypeValue { datatype, .. })
+                if datatype.starts_with(XSD_NAMESPACE) && datatype != XSD_STRING =>
+            {
+                term.clone()
+            }
+            Term::RdfLiteral(RdfLiteral::DatatypeValue { value, datatype }) => {
+                let datatype = if datatype == XSD_STRING {
+                    datatype.clone()
+                } else {
+                    self.constant(datatype)
+                };
+
+                Term::RdfLiteral(RdfLiteral::DatatypeValue {
+                    value: self.string(value),
+                    datatype,
+                })
+            }
+            Term::Aggregate(aggregate) => {
+                let mut aggregate = aggregate.clone();
+                for name in &mut aggregate.variable_identifiers {
+                    *name = self.variable_name(name);
+                }
+
+                Term::Aggregate(aggregate)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use crate::{
+        execution::export::export_program,
+        io::parser::parse_program,
+        model::{Identifier, PrimitiveType},
+    };
+
+    use super::Anonymizer;
+
+    #[test]
+    fn anonymize_program() {
+        let program = parse_program(
+            "@source employee[any, string]: load-csv(\"staff.csv\") .\n\
+             manager(<http://acme.com/alice>, \"Alice\"@en) .\n\
+             senior(?Name, !Id) :- employee(?Name, ?Role), ~intern(?Name), ?Role != \"trainee\" .\n\
+             bonus(?Name, ?Salary * 2) :- salary(?Name, ?Salary), ABS(?Salary - 100) > 5 .\n\
+             @output senior .\n",
+        )
+        .unwrap();
+        let types = HashMap::from([(
+            Identifier("employee".to_string()),
+            vec![PrimitiveType::Any, PrimitiveType::String],
+        )]);
+
+        let anonymized = export_program(&program, &types, &mut Anonymizer::default()).unwrap();
+
+        assert_eq!(
+            anonymized.text(),
+            "@source p1[any, string]: load-csv(\"p1.csv\") .\n\
+             p2(<https://example.org/c1>, \"s1\"@en) .\n\
+             p3(?X1, !X2) :- p1(?X1, ?X3), ~p4(?X1), ?X3 != \"s2\" .\n\
+             p5(?X1, (?X4 * 2)) :- p6(?X1, ?X4), ABS((?X4 - 100)) > 5 .\n\
+             @output p3 .\n"
+        );
+        assert_eq!(
+            anonymized.sources,
+            vec![(
+                Identifier("employee".to_string()),
+                Identifier("p1".to_string())
+            )]
+        );
+        assert!(parse_program(anonymized.text()).is_ok());
+    }
+
+    #[test]
+    fn anonymize_dictionary_entries() {
+        let mut anonymizer = Anonymizer::default();
+        let program = parse_program("p(<http://acme.com/alice>, \"Alice\") .").unwrap();
+        let anonymized = export_program(&program, &HashMap::new(), &mut anonymizer).unwrap();
+
+        assert_eq!(
+            anonymized.statements,
+            vec!["p1(<https://example.org/c1>, \"s1\") ."]
+        );
+        assert_eq!(
+            anonymizer.dictionary_entry("CONSTANT:http://acme.com/alice"),
+            "CONSTANT:https://example.org/c1"
+        );
+        assert_eq!(anonymizer.dictionary_entry("STRING:Alice"), "STRING:s1");
+        assert_eq!(anonymizer.dictionary_entry("STRING:Bob"), "STRING:s2");
+        assert_eq!(anonymizer.dictionary_entry("INTEGER:42"), "INTEGER:42");
+        assert_eq!(anonymizer.dictionary_entry("NULL:3"), "NULL:3");
+    }
+}
@@ -0,0 +1,60 @@
+//! Report of the facts of the output predicates
+//! and of the duplicates that were suppressed while deriving them.
+
+use std::io::Write;
+
+use crate::{error::Error, model::Identifier};
+
+/// Origin of the facts of a single predicate and the number of suppressed duplicates.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PredicateDeduplication {
+    /// The predicate
+    pub predicate: Identifier,
+    /// Number of facts given in the program or loaded from its sources
+    pub from_facts: usize,
+    /// Number of facts derived by rules
+    pub from_rules: usize,
+    /// Number of facts derived by rules that were discarded, since they were already known
+    pub duplicates: usize,
+}
+
+/// Report of the facts of each output predicate,
+/// see [`crate::execution::ExecutionEngine::count_duplicates`].
+///
+/// Facts derived several times in a single rule application are only counted once,
+/// and facts for which the head of an existential rule is already satisfied are not derived at all.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DeduplicationReport {
+    predicates: Vec<PredicateDeduplication>,
+}
+
+impl DeduplicationReport {
+    /// Create a new [`DeduplicationReport`].
+    pub fn new(predicates: Vec<PredicateDeduplication>) -> Self {
+        Self { predicates }
+    }
+
+    /// Return the entries of the report, sorted by predicate name.
+    pub fn predicates(&self) -> &[PredicateDeduplication] {
+        &self.predicates
+    }
+
+    /// Write the report as a CSV table with the columns
+    /// `predicate`, `from_facts`, `from_rules` and `duplicates`.
+    pub fn write_csv<W: Write>(&self, writer: W) -> Result<(), Error> {
+        let mut writer = csv::Writer::from_writer(writer);
+        writer.write_record(["predicate", "from_facts", "from_rules", "duplicates"])?;
+
+        for entry in &self.predicates {
+            writer.write_record([
+                entry.predicate.name(),
+                entry.from_facts.to_string(),
+                entry.from_rules.to_string(),
+                entry.duplicates.to_string(),
+            ])?;
+        }
+
+        writer.flush()?;
+        Ok(())
+    }
+}
@@ -0,0 +1,439 @@
+//! Derivations explaining how a fact follows from the input facts and the rules of a program.
+//!
+//! Derivations are reconstructed from the tables of an [`ExecutionEngine`],
+//! i.e. after reasoning or after loading previously saved results.
+//! For a fact of a derived predicate, the rules deriving the predicate are searched for
+//! a match of their body whose facts can in turn be derived,
+//! where facts that are currently being derived are not used again to avoid cycles.
+
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
+    fmt::Display,
+    rc::Rc,
+};
+
+use crate::{
+    error::Error,
+    model::{
+        chase_model::{ChaseAtom, ChaseRule},
+        Atom, Identifier, PrimitiveType, Term, TermOperation,
+    },
+    program_analysis::analysis::RuleAnalysis,
+};
+
+use super::{
+    query::QueryError,
+    selection_strategy::strategy::RuleSelectionStrategy,
+    top_down::{
+        canonical_term, extend, substitute, Binding, Row, Subgoal, TopDownError, TopDownRule,
+    },
+    ExecutionEngine,
+};
+
+/// Derivation of a fact, see [`ExecutionEngine::derivation`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Derivation {
+    /// Predicate of the fact
+    pub predicate: Identifier,
+    /// Values of the terms of the fact
+    pub terms: Vec<Term>,
+    /// Index of the rule that derived the fact,
+    /// or `None` if the fact was given, e.g. as an input fact or by previously saved results
+    pub rule: Option<usize>,
+    /// Derivations of the facts matching the positive body of the rule
+    pub premises: Vec<Derivation>,
+}
+
+impl Derivation {
+    /// Create the [`Derivation`] of a given fact.
+    fn given(predicate: Identifier, terms: Vec<Term>) -> Self {
+        Self {
+            predicate,
+            terms,
+            rule: None,
+            premises: Vec::new(),
+        }
+    }
+
+    fn fmt_indented(&self, f: &mut std::fmt::Formatter<'_>, depth: usize) -> std::fmt::Result {
+        let terms = self
+            .terms
+            .iter()
+            .map(Term::to_string)
+            .collect::<Vec<_>>()
+            .join(", ");
+        let origin = match self.rule {
+            Some(rule) => format!("rule {rule}"),
+            None => "given".to_string(),
+        };
+
+        writeln!(
+            f,
+            "{:indent$}{}({terms}) [{origin}]",
+            "",
+            self.predicate,
+            indent = 2 * depth
+        )?;
+
+        for premise in &self.premises {
+            premise.fmt_indented(f, depth + 1)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Display for Derivation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.fmt_indented(f, 0)
+    }
+}
+
+/// Searches derivations of facts, see the module documentation.
+#[derive(Debug)]
+pub(super) struct DerivationSearch {
+    /// Prepared rules together with their index in the program
+    rules: Vec<(usize, Rc<TopDownRule>)>,
+    /// For each predicate, the positions in `rules` of the rules that have it in their head
+    predicate_rules: HashMap<Identifier, Vec<usize>>,
+    /// Predicates that are derived by rules which cannot be traced
+    untraceable: HashSet<Identifier>,
+    /// Derivations found so far
+    derivations: BTreeMap<(Identifier, Row), Derivation>,
+    /// Facts whose derivation is currently searched
+    visiting: BTreeSet<(Identifier, Row)>,
+}
+
+impl DerivationSearch {
+    /// Create a new [`DerivationSearch`] for the given rules.
+    pub(super) fn new(
+        rules: &[ChaseRule],
+        rule_analysis: &[RuleAnalysis],
+        predicate_types: &HashMap<Identifier, Vec<PrimitiveType>>,
+    ) -> Self {
+        let mut result = Self {
+            rules: Vec::new(),
+            predicate_rules: HashMap::new(),
+            untraceable: HashSet::new(),
+            derivations: BTreeMap::new(),
+            visiting: BTreeSet::new(),
+        };
+
+        for (rule_index, (rule, analysis)) in rules.iter().zip(rule_analysis).enumerate() {
+            if !TopDownRule::can_prepare(rule) {
+                result
+                    .untraceable
+                    .extend(analysis.head_predicates.iter().cloned());
+                continue;
+            }
+
+            if let Some(prepared) = TopDownRule::new(rule, analysis, predicate_types) {
+                for predicate in &analysis.head_predicates {
+                    result
+                        .predicate_rules
+                        .entry(predicate.clone())
+                        .or_default()
+                        .push(result.rules.len());
+                }
+                result.rules.push((rule_index, Rc::new(prepared)));
+            }
+        }
+
+        result
+    }
+
+    /// Return a derivation of the given fact, whose predicate has the given types,
+    /// or `None` if the fact does not hold.
+    pub(super) fn derive_fact<Strategy: RuleSelectionStrategy>(
+        &mut self,
+        engine: &mut ExecutionEngine<Strategy>,
+        fact: &Atom,
+        types: &[PrimitiveType],
+    ) -> Result<Option<Derivation>, Error> {
+        let mut row = Row::new();
+        for (term_tree, logical_type) in fact.term_trees().iter().zip(types) {
+            let term = match term_tree.operation() {
+                TermOperation::Term(term) if !matches!(term, Term::Variable(_)) => term,
+                _ => return Err(QueryError::UnsupportedTerm(format!("{term_tree:?}")).into()),
+            };
+
+            match canonical_term(term.clone(), *logical_type) {
+                Some(term) => row.push(term),
+                None => return Ok(None),
+            }
+        }
+
+        if !Self::holds(engine, &fact.predicate(), &row)? {
+            return Ok(None);
+        }
+
+        let predicate = fact.predicate();
+        Ok(Some(
+            match self.derive_or_given(engine, &predicate, row.clone())? {
+                Some(derivation) => derivation,
+                None => Derivation::given(predicate, row),
+            },
+        ))
+    }
+
+    /// Return a derivation of the given fact, which is known to hold,
+    /// where facts that are not derived by a rule are given if they are input facts.
+    ///
+    /// Returns `None` if no derivation has been found.
+    fn derive_or_given<Strategy: RuleSelectionStrategy>(
+        &mut self,
+        engine: &mut ExecutionEngine<Strategy>,
+        predicate: &Identifier,
+        row: Row,
+    ) -> Result<Option<Derivation>, Error> {
+        if let Some(derivation) = self.derive(engine, predicate.clone(), row.clone())? {
+            return Ok(Some(derivation));
+        }
+
+        let atom = Subgoal::new(predicate.clone(), &row, &Binding::new()).atom();
+        if !engine.query_inputs(&atom)?.is_empty() {
+            return Ok(Some(Derivation::given(predicate.clone(), row)));
+        }
+
+        if self.untraceable.contains(predicate) {
+            return Err(TopDownError::UntraceableRule(predicate.clone()).into());
+        }
+
+        Ok(None)
+    }
+
+    /// Return whether the engine contains the given fact.
+    fn holds<Strategy: RuleSelectionStrategy>(
+        engine: &mut ExecutionEngine<Strategy>,
+        predicate: &Identifier,
+        row: &[Term],
+    ) -> Result<bool, Error> {
+        let atom = Subgoal::new(predicate.clone(), row, &Binding::new()).atom();
+
+        Ok(!engine.query(&atom)?.is_empty())
+    }
+
+    /// Return all facts of the engine that match the given atom under the given binding.
+    fn matches<Strategy: RuleSelectionStrategy>(
+        engine: &mut ExecutionEngine<Strategy>,
+        atom: &ChaseAtom,
+        binding: &Binding,
+    ) -> Result<Vec<Row>, Error> {
+        let subgoal = Subgoal::new(atom.predicate(), atom.terms(), binding);
+
+        Ok(engine
+            .query(&subgoal.atom())?
+            .into_iter()
+            .map(|row| row.into_iter().map(Term::from).collect())
+            .collect())
+    }
+
+    /// Return a derivation of the given fact, which is known to hold,
+    /// or `None` if the fact is not derived by a rule
+    /// or all derivations would depend on facts that are currently derived.
+    fn derive<Strategy: RuleSelectionStrategy>(
+        &mut self,
+        engine: &mut ExecutionEngine<Strategy>,
+        predicate: Identifier,
+        row: Row,
+    ) -> Result<Option<Derivation>, Error> {
+        let key = (predicate, row);
+        if let Some(derivation) = self.derivations.get(&key) {
+            return Ok(Some(derivation.clone()));
+        }
+        if !self.visiting.insert(key.clone()) {
+            return Ok(None);
+        }
+
+        let result = self.derive_with_rules(engine, &key.0, &key.1);
+        self.visiting.remove(&key);
+
+        let derivation = result?;
+        if let Some(derivation) = &derivation {
+            self.derivations.insert(key, derivation.clone());
+        }
+
+        Ok(derivation)
+    }
+
+    /// Search a rule application that derives the given fact.
+    fn derive_with_rules<Strategy: RuleSelectionStrategy>(
+        &mut self,
+        engine: &mut ExecutionEngine<Strategy>,
+        predicate: &Identifier,
+        row: &Row,
+    ) -> Result<Option<Derivation>, Error> {
+        let positions = self
+            .predicate_rules
+            .get(predicate)
+            .cloned()
+            .unwrap_or_default();
+
+        for position in positions {
+            let (rule_index, rule) = self.rules[position].clone();
+
+            for head in rule
+                .head
+                .iter()
+                .filter(|atom| &atom.predicate() == predicate)
+            {
+                let Some(binding) = extend(head.terms(), row, &Binding::new()) else {
+                    continue;
+                };
+
+                for binding in Self::body_matches(engine, &rule, binding)? {
+                    if let Some(premises) = self.derive_premises(engine, &rule, &binding)? {
+                        return Ok(Some(Derivation {
+                            predicate: predicate.clone(),
+                            terms: row.clone(),
+                            rule: Some(rule_index),
+                            premises,
+                        }));
+                    }
+                }
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Return all extensions of the given binding that satisfy the body of the given rule.
+    fn body_matches<Strategy: RuleSelectionStrategy>(
+        engine: &mut ExecutionEngine<Strategy>,
+        rule: &TopDownRule,
+        binding: Binding,
+    ) -> Result<Vec<Binding>, Error> {
+        let mut bindings = vec![binding];
+
+        for atom in &rule.positive_body {
+            let mut extended_bindings = Vec::new();
+            for binding in &bindings {
+                for row in Self::matches(engine, atom, binding)? {
+                    extended_bindings.extend(extend(atom.terms(), &row, binding));
+                }
+            }
+
+            bindings = extended_bindings;
+        }
+
+        bindings.retain(|binding| rule.filters.iter().all(|filter| filter.holds(binding)));
+
+        let mut result = Vec::new();
+        'bindings: for binding in bindings {
+            for atom in &rule.negative_body {
+                if Self::matches(engine, atom, &binding)?
+                    .iter()
+                    .any(|row| extend(atom.terms(), row, &binding).is_some())
+                {
+                    continue 'bindings;
+                }
+            }
+
+            result.push(binding);
+        }
+
+        Ok(result)
+    }
+
+    /// Return derivations of all facts matched by the positive body of the given rule under the given binding,
+    /// or `None` if one of them cannot be derived.
+    fn derive_premises<Strategy: RuleSelectionStrategy>(
+        &mut self,
+        engine: &mut ExecutionEngine<Strategy>,
+        rule: &TopDownRule,
+        binding: &Binding,
+    ) -> Result<Option<Vec<Derivation>>, Error> {
+        let mut premises = Vec::new();
+
+        for atom in &rule.positive_body {
+            let row = atom
+                .terms()
+                .iter()
+                .map(|term| substitute(term, binding))
+                .collect::<Row>();
+
+            match self.derive_or_given(engine, &atom.predicate(), row)? {
+                Some(premise) => premises.push(premise),
+                None => return Ok(None),
+            }
+        }
+
+        Ok(Some(premises))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        error::Error,
+        execution::{test_util::materialized_engine, TopDownError},
+        io::parser::parse_atom,
+    };
+
+    #[test]
+    fn recursive_derivation() {
+        let mut engine = materialized_engine(
+            "edge(a, b) . edge(b, c) . edge(c, a) .\n\
+             reach(?x, ?y) :- edge(?x, ?y) .\n\
+             reach(?x, ?z) :- reach(?x, ?y), edge(?y, ?z) .\n",
+        );
+
+        let derivation = engine
+            .derivation(&parse_atom("reach(a, a)").unwrap())
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            derivation.to_string(),
+            "reach(a, a) [rule 1]\n\
+             \x20 reach(a, c) [rule 1]\n\
+             \x20   reach(a, b) [rule 0]\n\
+             \x20     edge(a, b) [given]\n\
+             \x20   edge(b, c) [given]\n\
+             \x20 edge(c, a) [given]\n"
+        );
+
+        assert!(engine
+            .derivation(&parse_atom("reach(a, d)").unwrap())
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn negation_and_given_facts() {
+        let mut engine = materialized_engine(
+            "node(a) . node(b) . edge(a, b) . source(b) .\n\
+             source(?x) :- node(?x), ~target(?x) .\n\
+             target(?y) :- edge(?x, ?y) .\n",
+        );
+
+        let derivation = engine
+            .derivation(&parse_atom("source(a)").unwrap())
+            .unwrap()
+            .unwrap();
+        assert_eq!(derivation.rule, Some(0));
+        assert_eq!(derivation.premises.len(), 1);
+
+        let derivation = engine
+            .derivation(&parse_atom("source(b)").unwrap())
+            .unwrap()
+            .unwrap();
+        assert_eq!(derivation.rule, None);
+    }
+
+    #[test]
+    fn aggregates_are_untraceable() {
+        let mut engine = materialized_engine(
+            "@declare count(any, integer) .\n\
+             edge(a, b) . edge(a, c) .\n\
+             count(?x, #count(?y)) :- edge(?x, ?y) .\n",
+        );
+
+        assert!(matches!(
+            engine.derivation(&parse_atom("count(a, 2)").unwrap()),
+            Err(Error::TopDownError(TopDownError::UntraceableRule(_)))
+        ));
+        assert!(engine
+            .derivation(&parse_atom("count(a, ?x)").unwrap())
+            .is_err());
+    }
+}
@@ -0,0 +1,246 @@
+//! Structured information about the current state of an [`ExecutionEngine`][super::ExecutionEngine],
+//! e.g. for monitoring the progress of the chase.
+
+use std::fmt::Display;
+
+use bytesize::ByteSize;
+
+use crate::{
+    model::{Identifier, PrimitiveType},
+    table_manager::SubtableInfo,
+};
+
+/// State of the tables associated with a single predicate.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PredicateState {
+    /// The predicate.
+    pub predicate: Identifier,
+    /// Logical types of the columns.
+    pub types: Vec<PrimitiveType>,
+    /// Whether the predicate occurs in the head of some rule.
+    pub is_derived: bool,
+    /// Number of facts of this predicate.
+    ///
+    /// As for [`ExecutionEngine::count_facts_of_predicate`][super::ExecutionEngine::count_facts_of_predicate],
+    /// only in-memory facts are counted.
+    pub rows: usize,
+    /// Number of labelled nulls introduced by rules deriving facts of this predicate.
+    ///
+    /// Nulls occurring in several head atoms of a rule are counted for each of their predicates.
+    pub introduced_nulls: usize,
+    /// Memory consumed by all tables of this predicate.
+    pub memory: ByteSize,
+    /// The tables stored for this predicate, sorted by the steps they cover.
+    pub subtables: Vec<SubtableInfo>,
+}
+
+impl PredicateState {
+    /// Return the arity of the predicate.
+    pub fn arity(&self) -> usize {
+        self.types.len()
+    }
+}
+
+/// State of an [`ExecutionEngine`][super::ExecutionEngine].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EngineState {
+    /// Number of rule applications that have been performed.
+    pub steps: usize,
+    /// For each rule (in the order of the program),
+    /// the step in which it was last applied or `None` if it has not been applied yet.
+    pub rule_last_applied: Vec<Option<usize>>,
+    /// State of each predicate, sorted by name.
+    pub predicates: Vec<PredicateState>,
+}
+
+impl EngineState {
+    /// Return the state of the given predicate.
+    pub fn predicate(&self, predicate: &Identifier) -> Option<&PredicateState> {
+        self.predicates
+            .iter()
+            .find(|state| &state.predicate == predicate)
+    }
+
+    /// Return the overall memory consumed by the tables of all predicates.
+    pub fn memory(&self) -> ByteSize {
+        self.predicates
+            .iter()
+            .fold(ByteSize(0), |acc, state| acc + state.memory)
+    }
+}
+
+impl Display for EngineState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let applied_rules = self
+            .rule_last_applied
+            .iter()
+            .filter(|step| step.is_some())
+            .count();
+
+        writeln!(
+            f,
+            "Steps: {} ({} of {} rules applied), Memory: {}",
+            self.steps,
+            applied_rules,
+            self.rule_last_applied.len(),
+            self.memory()
+        )?;
+
+        for state in &self.predicates {
+            let types = state
+                .types
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(", ");
+
+            writeln!(
+                f,
+                "{}[{types}] ({}): {} rows, {}",
+                state.predicate,
+                if state.is_derived { "derived" } else { "input" },
+                state.rows,
+                state.memory
+            )?;
+
+            for subtable in &state.subtables {
+                let steps = if subtable.is_combined() {
+                    format!("Steps {}-{}", subtable.first_step, subtable.last_step)
+                } else {
+                    format!("Step {}", subtable.first_step)
+                };
+
+                writeln!(
+                    f,
+                    "    {steps}: {} rows, {}",
+                    subtable.rows, subtable.memory
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        error::Error,
+        execution::{DefaultExecutionEngine, ExecutionEngine},
+        io::{parser::parse_program, resource_providers::ResourceProviders},
+        model::{Identifier, PrimitiveType},
+    };
+
+    #[test]
+    fn state_after_execution() {
+        let program = parse_program(
+            "edge(1, 2) . edge(2, 3) . edge(3, 4) .\n\
+             path(?x, ?y) :- edge(?x, ?y) .\n\
+             path(?x, ?z) :- path(?x, ?y), edge(?y, ?z) .\n",
+        )
+        .unwrap();
+        let mut engine: DefaultExecutionEngine =
+            ExecutionEngine::initialize(program, ResourceProviders::empty()).unwrap();
+
+        let edge = Identifier("edge".to_string());
+        let path = Identifier("path".to_string());
+
+        assert_eq!(engine.predicates(), vec![edge.clone(), path.clone()]);
+        assert_eq!(engine.steps(), 0);
+        assert!(engine.state().rule_last_applied.iter().all(Option::is_none));
+
+        engine.execute().unwrap();
+        let state = engine.state();
+
+        assert!(state.steps > 0);
+        assert!(state.rule_last_applied.iter().all(Option::is_some));
+
+        let edge_state = state.predicate(&edge).unwrap();
+        assert!(!edge_state.is_derived);
+        assert_eq!(edge_state.arity(), 2);
+        assert_eq!(edge_state.rows, 3);
+        assert_eq!(edge_state.subtables.len(), 1);
+        assert_eq!(edge_state.subtables[0].first_step, 0);
+
+        let path_state = state.predicate(&path).unwrap();
+        assert!(path_state.is_derived);
+        assert_eq!(path_state.types, vec![PrimitiveType::Any; 2]);
+        assert_eq!(path_state.rows, 6);
+        assert!(path_state
+            .subtables
+            .iter()
+            .all(|subtable| subtable.first_step > 0 && subtable.last_step <= state.steps));
+    }
+
+    #[test]
+    fn execution_stopped_early() {
+        let program = parse_program(
+            "@declare nat(integer) .\n\
+             nat(0) .\n\
+             nat(?x + 1) :- nat(?x) .\n",
+        )
+        .unwrap();
+        let mut engine: DefaultExecutionEngine =
+            ExecutionEngine::initialize(program, ResourceProviders::empty()).unwrap();
+
+        let nat = Identifier("nat".to_string());
+        let finished = engine
+            .execute_until(|engine| engine.count_facts_of_predicate(&nat).unwrap_or(0) >= 5)
+            .unwrap();
+
+        assert!(!finished);
+        assert_eq!(engine.count_facts_of_predicate(&nat), Some(5));
+        assert_eq!(engine.steps(), 4);
+    }
+
+    #[test]
+    fn predicate_limit_exceeded() {
+        let program = parse_program(
+            "@declare nat(integer) .\n\
+             @limit nat 100 .\n\
+             nat(0) .\n\
+             nat(?x + 1) :- nat(?x) .\n",
+        )
+        .unwrap();
+        let mut engine: DefaultExecutionEngine =
+            ExecutionEngine::initialize(program, ResourceProviders::empty()).unwrap();
+
+        assert!(matches!(
+            engine.execute(),
+            Err(Error::PredicateLimitExceeded {
+                count: 101,
+                limit: 100,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn introduced_nulls() {
+        let program = parse_program(
+            "person(alice) . person(bob) . person(dave) . parent(alice, carol) .\n\
+             parent(?x, !y) :- person(?x) .\n\
+             ancestor(?y) :- parent(?x, ?y) .\n",
+        )
+        .unwrap();
+        let mut engine: DefaultExecutionEngine =
+            ExecutionEngine::initialize(program, ResourceProviders::empty()).unwrap();
+        engine.execute().unwrap();
+
+        // Only bob and dave have no parent yet
+        let introduced_nulls = engine
+            .rule_infos()
+            .iter()
+            .map(|info| info.introduced_nulls)
+            .collect::<Vec<_>>();
+        assert_eq!(introduced_nulls, vec![2, 0]);
+
+        let state = engine.state();
+        let parent = state.predicate(&Identifier("parent".to_string())).unwrap();
+        let ancestor = state
+            .predicate(&Identifier("ancestor".to_string()))
+            .unwrap();
+        assert_eq!(parent.introduced_nulls, 2);
+        assert_eq!(ancestor.introduced_nulls, 0);
+    }
+}
@@ -1,25 +1,76 @@
 //! Functionality which handles the execution of a program
 
-use std::collections::HashMap;
+use std::{
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+    path::Path,
+    rc::Rc,
+    sync::Arc,
+    time::Duration,
+};
 
-use nemo_physical::{datatypes::DataValueT, management::database::TableSource, meta::TimedCode};
+use bytesize::ByteSize;
+use nemo_physical::{
+    datatypes::{
+        storage_value::{StorageValueT, VecT},
+        DataTypeName, DataValueT,
+    },
+    dictionary::{
+        dictionary_file::{read_dictionary, write_dictionary},
+        Dictionary,
+    },
+    management::{
+        database::{Dict, TableId, TableSource},
+        MemoryReport,
+    },
+    meta::TimedCode,
+    tabular::{table_types::trie::Trie, traits::table::Table},
+};
 
 use crate::{
-    error::Error,
-    io::{input_manager::InputManager, resource_providers::ResourceProviders},
+    error::{Error, ReadingError},
+    io::{
+        consequence_cache::consequence_hashes,
+        formats::ExternalSourceReader,
+        input_manager::InputManager,
+        resource_providers::{external::ExternalSourceProvider, ResourceProviders},
+        ConsequenceCache, ResourceStatistics, WarmStart,
+    },
     model::{
-        chase_model::ChaseProgram,
+        chase_model::{ChaseAtom, ChaseProgram, ChaseRule},
         types::{
+            error::TypeError,
             primitive_logical_value::{PrimitiveLogicalValueIteratorT, PrimitiveLogicalValueT},
             primitive_types::PrimitiveType,
         },
-        Identifier, Program, TermOperation,
+        Atom, DsvFile, ExternalSource, Identifier, NativeDataSource, Program, Term, TermOperation,
+        Variable,
     },
-    program_analysis::analysis::ProgramAnalysis,
-    table_manager::{MemoryUsage, TableManager},
+    program_analysis::{
+        analysis::{ProgramAnalysis, RuleAnalysis, RuleVariableOrders},
+        fragment::Fragment,
+    },
+    table_manager::{MemoryUsage, PlanResults, RuleBindings, SubtableExecutionPlan, TableManager},
 };
 
-use super::{rule_execution::RuleExecution, selection_strategy::strategy::RuleSelectionStrategy};
+use super::{
+    aggregate_execution::AggregateExecution,
+    anonymization::Anonymizer,
+    deduplication::{DeduplicationReport, PredicateDeduplication},
+    derivation::{Derivation, DerivationSearch},
+    engine_state::{EngineState, PredicateState},
+    explanation::{AtomExplanation, RuleExplanation},
+    export::{export_program, ExportedProgram, Renaming},
+    firing_log::{FiringLog, RuleFiring},
+    function_registry::FunctionRegistry,
+    hypothetical::{HypotheticalOutcome, HypotheticalTransaction},
+    query::{PreparedQuery, QueryCache, QueryError, QueryKey, QueryPlan},
+    rule_execution::{ChaseVariant, RuleExecution},
+    selection_strategy::strategy::RuleSelectionStrategy,
+    session::Session,
+    streaming::Delta,
+    top_down::TopDownEvaluator,
+};
 
 // Number of tables that are periodically combined into one.
 const MAX_FRAGMENTATION: usize = 8;
@@ -29,6 +80,16 @@ const MAX_FRAGMENTATION: usize = 8;
 pub struct RuleInfo {
     /// The execution step this rule was last applied in.
     pub step_last_applied: usize,
+    /// Number of times this rule has been applied.
+    pub applications: usize,
+    /// Number of new facts derived by this rule.
+    ///
+    /// As for [`ExecutionEngine::count_facts_of_predicate`], only in-memory facts are counted.
+    pub derived_facts: usize,
+    /// Overall time spent applying this rule.
+    pub duration: Duration,
+    /// Number of labelled nulls introduced for the existential variables of this rule.
+    pub introduced_nulls: usize,
 }
 
 impl RuleInfo {
@@ -36,8 +97,99 @@ impl RuleInfo {
     pub fn new() -> Self {
         Self {
             step_last_applied: 0,
+            applications: 0,
+            derived_facts: 0,
+            duration: Duration::ZERO,
+            introduced_nulls: 0,
+        }
+    }
+}
+
+/// Object responsible for applying a single rule of the program.
+#[derive(Debug)]
+enum RuleApplication {
+    /// Application of a "normal" rule
+    Rule(RuleExecution),
+    /// Application of a rule that computes an aggregate
+    Aggregate(AggregateExecution),
+}
+
+impl RuleApplication {
+    fn initialize(
+        rule: &ChaseRule,
+        analysis: &RuleAnalysis,
+        functions: &FunctionRegistry,
+        chase_variant: ChaseVariant,
+    ) -> Self {
+        if rule.aggregate().is_some() {
+            Self::Aggregate(AggregateExecution::initialize(rule, analysis))
+        } else {
+            Self::Rule(RuleExecution::initialize(
+                rule,
+                analysis,
+                functions,
+                chase_variant,
+            ))
         }
     }
+
+    fn execute(
+        &self,
+        table_manager: &mut TableManager,
+        rule_info: &RuleInfo,
+        step_number: usize,
+    ) -> Result<Vec<Identifier>, Error> {
+        match self {
+            Self::Rule(execution) => execution.execute(table_manager, rule_info, step_number),
+            Self::Aggregate(execution) => execution.execute(table_manager, rule_info, step_number),
+        }
+    }
+
+    /// Like [`RuleApplication::execute`], but also returns the matches of the rule body
+    /// and the number of derived facts if requested by the given plan,
+    /// which are only available for "normal" rules.
+    fn execute_recording(
+        &self,
+        subtable_execution_plan: SubtableExecutionPlan,
+        table_manager: &mut TableManager,
+        rule_info: &RuleInfo,
+        step_number: usize,
+    ) -> Result<PlanResults, Error> {
+        match self {
+            Self::Rule(execution) => execution.execute_recording(
+                subtable_execution_plan,
+                table_manager,
+                rule_info,
+                step_number,
+            ),
+            Self::Aggregate(execution) => Ok(PlanResults {
+                updated_predicates: execution.execute(table_manager, rule_info, step_number)?,
+                ..Default::default()
+            }),
+        }
+    }
+
+    /// Return the estimated number of matches of the rule body,
+    /// or `None` if the rule computes an aggregate.
+    fn estimate_matches(
+        &self,
+        table_manager: &mut TableManager,
+        rule_info: &RuleInfo,
+        step_number: usize,
+    ) -> Result<Option<f64>, Error> {
+        match self {
+            Self::Rule(execution) => execution
+                .estimate_matches(table_manager, rule_info, step_number)
+                .map(Some),
+            Self::Aggregate(_) => Ok(None),
+        }
+    }
+
+    /// Return whether the application replaces the facts of its head predicate,
+    /// instead of adding new ones.
+    fn replaces_facts(&self) -> bool {
+        matches!(self, Self::Aggregate(_))
+    }
 }
 
 /// Object which handles the evaluation of the program.
@@ -48,15 +200,44 @@ pub struct ExecutionEngine<RuleSelectionStrategy> {
 
     rule_strategy: RuleSelectionStrategy,
 
-    #[allow(dead_code)]
     input_manager: InputManager,
     table_manager: TableManager,
 
     predicate_fragmentation: HashMap<Identifier, usize>,
     predicate_last_union: HashMap<Identifier, usize>,
+    predicate_nulls: HashMap<Identifier, usize>,
 
     rule_infos: Vec<RuleInfo>,
     current_step: usize,
+    peak_memory: ByteSize,
+
+    warnings: Vec<String>,
+
+    functions: FunctionRegistry,
+
+    firing_log: Option<FiringLog>,
+    /// Number of derived facts of each predicate that were discarded, since they were already known
+    duplicate_counts: Option<HashMap<Identifier, usize>>,
+
+    max_estimated_matches: Option<u64>,
+    /// Number of applications of each rule, of which only the last one removes the facts that were already known
+    duplicate_elimination_period: usize,
+    /// Steps whose derived facts were stored without removing duplicates, together with the updated predicates
+    unchecked_steps: HashMap<usize, Vec<Identifier>>,
+    warned_rules: HashSet<usize>,
+
+    pub(super) query_cache: QueryCache,
+
+    /// Hashes identifying the facts of derived predicates in a [`ConsequenceCache`]
+    consequence_hashes: Option<HashMap<Identifier, u64>>,
+    /// Rules that are not applied, since all predicates in their head were loaded from a [`ConsequenceCache`]
+    cached_rules: HashSet<usize>,
+    /// Facts of derived predicates loaded from a [`WarmStart`], kept separately from the predicates
+    previous_tables: HashMap<Identifier, TableId>,
+    /// Tables given to [`ExecutionEngine::add_table`] that were added like facts derived in some step,
+    /// which are added to the input facts when the derived facts are discarded
+    added_tables: Vec<(Identifier, Arc<Trie>)>,
+    chase_variant: ChaseVariant,
 }
 
 impl<Strategy: RuleSelectionStrategy> ExecutionEngine<Strategy> {
@@ -65,18 +246,192 @@ impl<Strategy: RuleSelectionStrategy> ExecutionEngine<Strategy> {
         program: Program,
         resource_providers: ResourceProviders,
     ) -> Result<Self, Error> {
+        Self::initialize_internal(program, resource_providers, None, None, None, None, None)
+    }
+
+    /// Initialize [`ExecutionEngine`] whose dictionary starts with the entries of the given dictionary file,
+    /// e.g. one written by [`ExecutionEngine::export_dictionary`] in an earlier run,
+    /// such that all constants of that run are encoded by the same values.
+    pub fn initialize_with_dictionary_file(
+        program: Program,
+        resource_providers: ResourceProviders,
+        dictionary_file: &Path,
+    ) -> Result<Self, Error> {
+        let mut dict = Dict::default();
+        read_dictionary(dictionary_file, &mut dict).map_err(|error| ReadingError::IOReading {
+            error,
+            filename: dictionary_file.to_path_buf(),
+        })?;
+
+        Self::initialize_internal(
+            program,
+            resource_providers,
+            None,
+            None,
+            None,
+            None,
+            Some(Rc::new(RefCell::new(dict))),
+        )
+    }
+
+    /// Initialize [`ExecutionEngine`], pre-populating derived predicates
+    /// with the results of a previous run found in the given [`WarmStart`] directory.
+    ///
+    /// This is only sound for monotone programs, i.e. programs without negation,
+    /// as previously derived facts are never retracted.
+    /// Moreover, nulls of the previous run are exported as blank nodes `_:N`,
+    /// which are loaded again as ordinary constants.
+    /// For programs with existential rules, they hence no longer behave like nulls,
+    /// e.g. facts containing them are kept even if the restricted chase would not have introduced them,
+    /// and they are exported as constants by this run.
+    pub fn initialize_with_warm_start(
+        program: Program,
+        resource_providers: ResourceProviders,
+        warm_start: &WarmStart,
+    ) -> Result<Self, Error> {
+        Self::initialize_internal(
+            program,
+            resource_providers,
+            Some(warm_start),
+            None,
+            None,
+            None,
+            None,
+        )
+    }
+
+    /// Initialize [`ExecutionEngine`], loading the facts of derived predicates from the given [`ConsequenceCache`]
+    /// if neither the rules deriving them nor the inputs they depend on have changed since they were cached.
+    ///
+    /// The rules whose head predicates are all loaded from the cache are not applied.
+    /// After reasoning, the cache can be updated with [`ExecutionEngine::store_consequences`].
+    pub fn initialize_with_consequence_cache(
+        program: Program,
+        resource_providers: ResourceProviders,
+        consequence_cache: &ConsequenceCache,
+    ) -> Result<Self, Error> {
+        Self::initialize_internal(
+            program,
+            resource_providers,
+            None,
+            None,
+            None,
+            Some(consequence_cache),
+            None,
+        )
+    }
+
+    /// Initialize [`ExecutionEngine`], reusing the given variable orders of the rules instead of computing them,
+    /// e.g. those of a [`CompiledProgram`][crate::io::CompiledProgram].
+    /// Derived predicates are pre-populated from the given [`WarmStart`] directory, if any.
+    ///
+    /// Returns an error if the variable orders do not belong to the program.
+    pub fn initialize_with_variable_orders(
+        program: Program,
+        resource_providers: ResourceProviders,
+        variable_orders: Vec<RuleVariableOrders>,
+        warm_start: Option<&WarmStart>,
+    ) -> Result<Self, Error> {
+        Self::initialize_internal(
+            program,
+            resource_providers,
+            warm_start,
+            None,
+            Some(variable_orders),
+            None,
+            None,
+        )
+    }
+
+    /// Initialize [`ExecutionEngine`] which reasons over the tables of the given [`Session`].
+    ///
+    /// The dictionary and the tables of the session are shared with the new engine,
+    /// while all tables derived by the engine are kept separately.
+    pub fn initialize_in_session(
+        program: Program,
+        resource_providers: ResourceProviders,
+        session: &Session,
+    ) -> Result<Self, Error> {
+        Self::initialize_internal(
+            program,
+            resource_providers,
+            None,
+            Some(session),
+            None,
+            None,
+            None,
+        )
+    }
+
+    fn initialize_internal(
+        program: Program,
+        resource_providers: ResourceProviders,
+        warm_start: Option<&WarmStart>,
+        session: Option<&Session>,
+        variable_orders: Option<Vec<RuleVariableOrders>>,
+        consequence_cache: Option<&ConsequenceCache>,
+        dictionary: Option<Rc<RefCell<Dict>>>,
+    ) -> Result<Self, Error> {
+        let _span = tracing::info_span!("initialize").entered();
+
+        program.check_arities()?;
         let mut program: ChaseProgram = program.try_into()?;
 
+        if let Some(session) = session {
+            session.declare_predicates(&mut program);
+        }
+
         program.check_for_unsupported_features()?;
         program.normalize();
 
-        let analysis = program.analyze()?;
+        let analysis = program.analyze_with_variable_orders(variable_orders)?;
+        tracing::info!("The program consists of {}", analysis.fragment);
+
+        let warnings = warm_start
+            .map(|warm_start| Self::warm_start_warnings(&program, &analysis, warm_start))
+            .unwrap_or_default();
+        for warning in &warnings {
+            tracing::warn!("{warning}");
+        }
 
         let input_manager = InputManager::new(resource_providers);
 
-        let mut table_manager = TableManager::new();
+        let consequence_hashes =
+            consequence_cache.map(|_| consequence_hashes(&program, &analysis, &input_manager));
+        let cached_sources = match (consequence_cache, &consequence_hashes) {
+            (Some(cache), Some(hashes)) => Self::cached_sources(cache, hashes, &analysis),
+            _ => HashMap::new(),
+        };
+        let cached_rules = analysis
+            .rule_analysis
+            .iter()
+            .enumerate()
+            .filter(|(_, rule_analysis)| {
+                rule_analysis
+                    .head_predicates
+                    .iter()
+                    .all(|predicate| cached_sources.contains_key(predicate))
+            })
+            .map(|(index, _)| index)
+            .collect();
+
+        let mut table_manager = match session {
+            Some(session) => session.table_manager(),
+            None => match dictionary {
+                Some(dictionary) => TableManager::with_dictionary(dictionary),
+                None => TableManager::new(),
+            },
+        };
         Self::register_all_predicates(&mut table_manager, &analysis);
-        Self::add_sources(&mut table_manager, &input_manager, &program, &analysis)?;
+        let previous_tables = Self::add_sources(
+            &mut table_manager,
+            &input_manager,
+            &program,
+            &analysis,
+            warm_start,
+            session,
+            cached_sources,
+        )?;
 
         let mut rule_infos = Vec::<RuleInfo>::new();
         program
@@ -89,6 +444,8 @@ impl<Strategy: RuleSelectionStrategy> ExecutionEngine<Strategy> {
             analysis.rule_analysis.iter().collect(),
         )?;
 
+        let functions = FunctionRegistry::new(table_manager.shared_dict());
+
         Ok(Self {
             program,
             analysis,
@@ -97,8 +454,24 @@ impl<Strategy: RuleSelectionStrategy> ExecutionEngine<Strategy> {
             table_manager,
             predicate_fragmentation: HashMap::new(),
             predicate_last_union: HashMap::new(),
+            predicate_nulls: HashMap::new(),
             rule_infos,
             current_step: 1,
+            peak_memory: ByteSize(0),
+            warnings,
+            functions,
+            firing_log: None,
+            duplicate_counts: None,
+            max_estimated_matches: None,
+            duplicate_elimination_period: 1,
+            unchecked_steps: HashMap::new(),
+            warned_rules: HashSet::new(),
+            query_cache: QueryCache::default(),
+            consequence_hashes,
+            cached_rules,
+            previous_tables,
+            added_tables: Vec::new(),
+            chase_variant: ChaseVariant::default(),
         })
     }
 
@@ -120,9 +493,24 @@ impl<Strategy: RuleSelectionStrategy> ExecutionEngine<Strategy> {
         input_manager: &InputManager,
         program: &ChaseProgram,
         analysis: &ProgramAnalysis,
-    ) -> Result<(), Error> {
+        warm_start: Option<&WarmStart>,
+        session: Option<&Session>,
+        cached_sources: HashMap<Identifier, DsvFile>,
+    ) -> Result<HashMap<Identifier, TableId>, Error> {
         let mut predicate_to_sources = HashMap::<Identifier, Vec<TableSource>>::new();
 
+        // Add the tables shared by the session
+        if let Some(session) = session {
+            for (predicate, _) in &analysis.all_predicates {
+                if let Some(trie) = session.shared_trie(predicate) {
+                    predicate_to_sources
+                        .entry(predicate.clone())
+                        .or_default()
+                        .push(TableSource::Trie(trie));
+                }
+            }
+        }
+
         // Add all the data source declarations
         for source_declaration in program.sources() {
             let logical_types = analysis
@@ -131,8 +519,22 @@ impl<Strategy: RuleSelectionStrategy> ExecutionEngine<Strategy> {
                 .cloned()
                 .expect("All predicates should have types by now.");
 
-            let table_source = input_manager
-                .load_native_table_source(source_declaration.source.clone(), logical_types)?;
+            let table_source = if source_declaration.is_bag() {
+                input_manager.load_counted_native_table_source(
+                    source_declaration.source.clone(),
+                    logical_types.clone(),
+                )?
+            } else {
+                input_manager.load_native_table_source(
+                    source_declaration.source.clone(),
+                    logical_types.clone(),
+                )?
+            };
+            let table_source = input_manager.cached_table_source(
+                table_source,
+                source_declaration,
+                &logical_types,
+            )?;
 
             predicate_to_sources
                 .entry(source_declaration.predicate.clone())
@@ -140,6 +542,35 @@ impl<Strategy: RuleSelectionStrategy> ExecutionEngine<Strategy> {
                 .push(table_source)
         }
 
+        // Add the results of a previous run for derived predicates
+        let previous_tables = match warm_start {
+            Some(warm_start) => Self::add_warm_start_sources(
+                table_manager,
+                &mut predicate_to_sources,
+                input_manager,
+                analysis,
+                warm_start,
+            )?,
+            None => HashMap::new(),
+        };
+
+        // Add the cached facts of derived predicates
+        for (predicate, dsv_file) in cached_sources {
+            let logical_types = analysis
+                .predicate_types
+                .get(&predicate)
+                .cloned()
+                .expect("All predicates should have types by now.");
+
+            let table_source = input_manager
+                .load_native_table_source(NativeDataSource::DsvFile(dsv_file), logical_types)?;
+
+            predicate_to_sources
+                .entry(predicate)
+                .or_default()
+                .push(table_source);
+        }
+
         // Add all the facts contained in the rule file as a source
         let mut predicate_to_rows = HashMap::<Identifier, Vec<Vec<DataValueT>>>::new();
 
@@ -178,181 +609,1369 @@ impl<Strategy: RuleSelectionStrategy> ExecutionEngine<Strategy> {
             table_manager.add_edb(predicate, sources);
         }
 
-        Ok(())
+        Ok(previous_tables)
     }
 
-    /// Executes the program.
-    pub fn execute(&mut self) -> Result<(), Error> {
-        TimedCode::instance().sub("Reasoning/Rules").start();
-        TimedCode::instance().sub("Reasoning/Execution").start();
+    fn warm_start_warnings(
+        program: &ChaseProgram,
+        analysis: &ProgramAnalysis,
+        warm_start: &WarmStart,
+    ) -> Vec<String> {
+        let mut warnings = Vec::new();
 
-        let rule_execution: Vec<RuleExecution> = self
-            .program
+        if program
             .rules()
             .iter()
-            .zip(self.analysis.rule_analysis.iter())
-            .map(|(r, a)| RuleExecution::initialize(r, a))
-            .collect();
+            .any(|rule| !rule.negative_body().is_empty())
+        {
+            warnings.push(format!(
+                "Warm-starting from {:?} may produce incorrect results, since the program uses negation",
+                warm_start.directory()
+            ));
+        }
 
-        let mut new_derivations: Option<bool> = None;
+        if analysis
+            .rule_analysis
+            .iter()
+            .any(|analysis| analysis.is_existential)
+        {
+            warnings.push(format!(
+                "Warm-starting from {:?}: previously derived nulls are treated as constants",
+                warm_start.directory()
+            ));
+        }
 
-        while let Some(current_rule_index) = self.rule_strategy.next_rule(new_derivations) {
-            let timing_string = format!("Reasoning/Rules/Rule {current_rule_index}");
+        warnings
+    }
 
-            TimedCode::instance().sub(&timing_string).start();
-            log::info!(
-                "<<< {0}: APPLYING RULE {current_rule_index} >>>",
-                self.current_step
-            );
+    /// Return data sources for all derived predicates whose facts are contained in the given [`ConsequenceCache`].
+    fn cached_sources(
+        consequence_cache: &ConsequenceCache,
+        consequence_hashes: &HashMap<Identifier, u64>,
+        analysis: &ProgramAnalysis,
+    ) -> HashMap<Identifier, DsvFile> {
+        let mut result = HashMap::new();
 
-            let current_info = &mut self.rule_infos[current_rule_index];
-            let current_execution = &rule_execution[current_rule_index];
+        for (predicate, hash) in consequence_hashes {
+            let types = analysis
+                .predicate_types
+                .get(predicate)
+                .expect("All predicates should have types by now.");
 
-            let updated_predicates = current_execution.execute(
-                &mut self.table_manager,
-                current_info,
-                self.current_step,
-            )?;
+            if let Some(dsv_file) = consequence_cache.data_source(predicate, *hash, types) {
+                tracing::info!("Loading predicate {predicate} from {:?}", dsv_file.resource);
+                result.insert(predicate.clone(), dsv_file);
+            }
+        }
 
-            new_derivations = Some(!updated_predicates.is_empty());
+        result
+    }
 
-            current_info.step_last_applied = self.current_step;
+    /// Add the facts of derived predicates saved in the given [`WarmStart`] as sources.
+    ///
+    /// Returns a separate table with these facts for each such predicate,
+    /// see [`ExecutionEngine::new_facts_serialization`].
+    fn add_warm_start_sources(
+        table_manager: &mut TableManager,
+        predicate_to_sources: &mut HashMap<Identifier, Vec<TableSource>>,
+        input_manager: &InputManager,
+        analysis: &ProgramAnalysis,
+        warm_start: &WarmStart,
+    ) -> Result<HashMap<Identifier, TableId>, Error> {
+        let mut previous_tables = HashMap::new();
 
-            let rule_duration = TimedCode::instance().sub(&timing_string).stop();
-            log::info!("Rule duration: {} ms", rule_duration.as_millis());
+        for predicate in &analysis.derived_predicates {
+            let logical_types = analysis
+                .predicate_types
+                .get(predicate)
+                .cloned()
+                .expect("All predicates should have types by now.");
 
-            // We prevent fragmentation by periodically collecting single-step tables into larger ones
-            for updated_pred in updated_predicates {
-                let counter = self
-                    .predicate_fragmentation
-                    .entry(updated_pred.clone())
-                    .or_insert(0);
-                *counter += 1;
+            let Some(dsv_file) = warm_start.data_source(predicate, &logical_types) else {
+                continue;
+            };
 
-                if *counter == MAX_FRAGMENTATION {
-                    let start =
-                        if let Some(last_union) = self.predicate_last_union.get(&updated_pred) {
-                            last_union + 1
-                        } else {
-                            0
-                        };
+            tracing::info!(
+                "Warm-starting predicate {predicate} from {:?}",
+                dsv_file.resource
+            );
 
-                    let range = start..(self.current_step + 1);
+            let previous_source = input_manager.load_native_table_source(
+                NativeDataSource::DsvFile(dsv_file.clone()),
+                logical_types.clone(),
+            )?;
+            previous_tables.insert(
+                predicate.clone(),
+                table_manager.add_unassociated_table(
+                    predicate,
+                    "previous state",
+                    vec![previous_source],
+                ),
+            );
 
-                    self.table_manager
-                        .combine_tables(updated_pred.clone(), range)?;
+            let table_source = input_manager
+                .load_native_table_source(NativeDataSource::DsvFile(dsv_file), logical_types)?;
 
-                    self.predicate_last_union
-                        .insert(updated_pred, self.current_step);
+            predicate_to_sources
+                .entry(predicate.clone())
+                .or_default()
+                .push(table_source);
+        }
 
-                    *counter = 0;
-                }
-            }
+        Ok(previous_tables)
+    }
 
-            self.current_step += 1;
-        }
+    /// Register a function with the given name and number of arguments,
+    /// which can then be called from the rules of the program (see [`FunctionRegistry`]).
+    ///
+    /// The function is applied to values of the type of the term it is used in
+    /// and its result is converted into this type.
+    /// If it returns `None`, no fact is derived.
+    pub fn register_function<F>(
+        &mut self,
+        name: &str,
+        arity: usize,
+        function: F,
+    ) -> Result<(), Error>
+    where
+        F: Fn(&[PrimitiveLogicalValueT]) -> Option<PrimitiveLogicalValueT> + 'static,
+    {
+        Ok(self.functions.register(name, arity, Rc::new(function))?)
+    }
 
-        TimedCode::instance().sub("Reasoning/Rules").stop();
-        TimedCode::instance().sub("Reasoning/Execution").stop();
-        Ok(())
+    /// Record every following rule application in a [`FiringLog`],
+    /// together with up to `sample_size` of the bindings that satisfied the body of the rule.
+    ///
+    /// Since the bindings have to be kept in full, this slows down reasoning.
+    pub fn log_rule_firings(&mut self, sample_size: usize) {
+        self.firing_log = Some(FiringLog::new(sample_size));
     }
 
-    /// Get a reference to the loaded program.
-    pub fn program(&self) -> &ChaseProgram {
-        &self.program
+    /// Return the log of rule applications, if enabled with [`ExecutionEngine::log_rule_firings`].
+    pub fn firing_log(&self) -> Option<&FiringLog> {
+        self.firing_log.as_ref()
     }
 
-    /// Get a list of column iterators for the predicate
-    pub fn get_predicate_column_iterators(
-        &mut self,
-        predicate: Identifier,
-    ) -> Result<Option<Vec<PrimitiveLogicalValueIteratorT>>, Error> {
-        let Some(table_id) = self.table_manager.combine_predicate(predicate.clone())? else {
-            return Ok(None);
-        };
+    /// Count the derived facts that are discarded in every following rule application,
+    /// since they were already known, for the [`DeduplicationReport`].
+    ///
+    /// Counting requires keeping all facts derived by a rule application until they are counted.
+    pub fn count_duplicates(&mut self) {
+        self.duplicate_counts.get_or_insert_with(HashMap::new);
+    }
 
-        let predicate_types: &Vec<PrimitiveType> = self
-            .analysis
-            .predicate_types
-            .get(&predicate)
-            .expect("All predicates should have types by now.");
+    /// Return a [`DeduplicationReport`] of the output predicates,
+    /// if duplicates are counted (see [`ExecutionEngine::count_duplicates`]).
+    pub fn deduplication_report(&self) -> Option<DeduplicationReport> {
+        let duplicate_counts = self.duplicate_counts.as_ref()?;
 
-        let iterators = self.table_manager.table_column_iters(table_id)?;
+        let mut predicates = self.program.output_predicates().collect::<Vec<_>>();
+        predicates.sort_by_key(Identifier::name);
 
-        let logically_mapped_iters: Vec<PrimitiveLogicalValueIteratorT> = iterators
+        let entries = predicates
             .into_iter()
-            .zip(predicate_types.iter())
-            .map(|(iter, lt)| lt.primitive_logical_value_iterator(iter))
+            .map(|predicate| {
+                let rows = self.count_facts_of_predicate(&predicate).unwrap_or(0);
+                let from_facts = self.table_manager.input_count_rows(&predicate).unwrap_or(0);
+
+                PredicateDeduplication {
+                    from_facts,
+                    from_rules: rows.saturating_sub(from_facts),
+                    duplicates: duplicate_counts.get(&predicate).copied().unwrap_or(0),
+                    predicate,
+                }
+            })
             .collect();
 
-        Ok(Some(logically_mapped_iters))
+        Some(DeduplicationReport::new(entries))
     }
 
-    /// Creates an [`Iterator`] over the resulting facts of a predicate.
-    // TODO: we probably want to return a list of column iterators over logical values
-    pub fn table_scan(
-        &mut self,
-        predicate: Identifier,
-    ) -> Result<Option<impl Iterator<Item = Vec<PrimitiveLogicalValueT>> + '_>, Error> {
-        let Some(logically_mapped_iters) = self.get_predicate_column_iterators(predicate)? else {
-            return Ok(None);
-        };
+    /// Maintain approximate filters of the values in the first column of each table
+    /// and skip rule applications if the filters show that the body has no matches,
+    /// since atoms with the same variable in their first position have no value in common.
+    ///
+    /// Computing the filters takes additional time and memory,
+    /// which pays off for programs with many rule applications that do not derive anything.
+    pub fn use_key_filters(&mut self) {
+        self.table_manager.enable_key_filters();
+    }
 
-        struct CombinedIters<'a>(Vec<PrimitiveLogicalValueIteratorT<'a>>);
+    /// Maintain an inverted index of the words of all strings,
+    /// which is used to evaluate full-text searches like `MATCHES_TEXT(?X, "quantum comput*")`
+    /// without checking every string.
+    ///
+    /// Without the index, each evaluation of a full-text search checks all strings,
+    /// which is faster for programs that search rarely.
+    pub fn use_text_index(&mut self) {
+        self.table_manager.enable_text_index();
+    }
 
-        impl<'a> Iterator for CombinedIters<'a> {
-            type Item = Vec<PrimitiveLogicalValueT>;
+    /// Apply rules with existential variables in the head once for every binding of their frontier variables
+    /// in every following rule application, instead of only if their head is not already satisfied,
+    /// see [`ChaseVariant::Skolem`].
+    ///
+    /// This avoids checking whether the head is satisfied, but may introduce more nulls,
+    /// and should therefore be called before executing the program.
+    pub fn use_skolem_chase(&mut self) {
+        self.chase_variant = ChaseVariant::Skolem;
+    }
 
-            fn next(&mut self) -> Option<Self::Item> {
-                let res: Self::Item = self.0.iter_mut().filter_map(|iter| iter.next()).collect();
-                (!res.is_empty()).then_some(res)
-            }
+    /// Estimate the number of matches of the rule body before every following rule application
+    /// and warn if it exceeds the given number,
+    /// e.g. to recognize rules that would run for a very long time.
+    ///
+    /// Every rule is warned about at most once.
+    /// The warnings are logged and can be obtained from [`ExecutionEngine::warnings`].
+    pub fn warn_on_estimated_matches(&mut self, max_matches: u64) {
+        self.max_estimated_matches = Some(max_matches);
+    }
+
+    /// Only remove the facts that were already known from the results of every `period`-th application of each rule,
+    /// trading memory for speed on programs where removing duplicates dominates,
+    /// but only few duplicates are derived.
+    ///
+    /// The other applications store all derived facts, such that the tables of a predicate may overlap
+    /// and [`ExecutionEngine::count_facts_of_predicate`] may count some facts more than once.
+    /// A rule still removes duplicates if it uses facts stored by such an application,
+    /// which prevents known facts from being derived over and over again.
+    pub fn delay_duplicate_elimination(&mut self, period: usize) {
+        self.duplicate_elimination_period = period.max(1);
+    }
+
+    /// Explain the next application of the rule with the given index,
+    /// including the estimated number of matches of its body.
+    ///
+    /// The index refers to the rules of [`ExecutionEngine::program`].
+    /// The estimate is computed from sketches of the tables of the body atoms,
+    /// without evaluating the rule.
+    pub fn explain(&mut self, rule_index: usize) -> Result<RuleExplanation, Error> {
+        let rule = self
+            .program
+            .rules()
+            .get(rule_index)
+            .ok_or(Error::UnknownRule(rule_index))?;
+        let rule_info = &self.rule_infos[rule_index];
+
+        let mut body = Vec::new();
+        for atom in rule.positive_body() {
+            let predicate = atom.predicate();
+            let mut count_rows = |steps| -> Result<usize, Error> {
+                Ok(self
+                    .table_manager
+                    .statistics(&predicate, &steps)?
+                    .map_or(0, |statistics| statistics.rows))
+            };
+
+            body.push(AtomExplanation {
+                rows: count_rows(0..self.current_step)?,
+                new_rows: count_rows(rule_info.step_last_applied..self.current_step)?,
+                predicate,
+            });
         }
 
-        let combined_iters = CombinedIters(logically_mapped_iters);
+        let application = RuleApplication::initialize(
+            rule,
+            &self.analysis.rule_analysis[rule_index],
+            &self.functions,
+            self.chase_variant,
+        );
+        let estimated_matches =
+            application.estimate_matches(&mut self.table_manager, rule_info, self.current_step)?;
+
+        Ok(RuleExplanation {
+            rule: rule_index,
+            head: rule.head().iter().map(|atom| atom.predicate()).collect(),
+            body,
+            estimated_matches,
+        })
+    }
 
-        Ok(Some(combined_iters))
+    /// Executes the program.
+    pub fn execute(&mut self) -> Result<(), Error> {
+        self.execute_until(|_| false).map(|_| ())
     }
 
-    /// Creates an [`Iterator`] over the resulting facts of a predicate.
-    pub fn output_serialization(
-        &mut self,
-        predicate: Identifier,
-    ) -> Result<Option<impl Iterator<Item = Vec<String>> + '_>, Error> {
-        let Some(table_id) = self.table_manager.combine_predicate(predicate.clone())? else {
-            return Ok(None);
-        };
+    /// Executes the program until `stop` returns `true`, which is checked after each rule application,
+    /// e.g. to bound the number of [steps][ExecutionEngine::steps] for programs that do not terminate.
+    ///
+    /// Returns `false` if the execution was stopped by `stop`.
+    pub fn execute_until<F>(&mut self, mut stop: F) -> Result<bool, Error>
+    where
+        F: FnMut(&Self) -> bool,
+    {
+        let _span = tracing::info_span!("reasoning").entered();
 
-        let predicate_types: &Vec<PrimitiveType> = self
-            .analysis
-            .predicate_types
-            .get(&predicate)
-            .expect("All predicates should have types by now.");
+        self.functions.check_program(&self.program)?;
 
-        let iterators = self.table_manager.table_column_iters(table_id)?;
+        TimedCode::instance().sub("Reasoning/Rules").start();
+        TimedCode::instance().sub("Reasoning/Execution").start();
 
-        let logically_mapped_iters: Vec<Box<dyn Iterator<Item = String>>> = iterators
-            .into_iter()
-            .zip(predicate_types.iter())
-            .map(|(iter, lt)| lt.serialize_output(iter))
+        let rule_execution: Vec<RuleApplication> = self
+            .program
+            .rules()
+            .iter()
+            .zip(self.analysis.rule_analysis.iter())
+            .map(|(r, a)| RuleApplication::initialize(r, a, &self.functions, self.chase_variant))
             .collect();
 
-        struct CombinedIters<'a>(Vec<Box<dyn Iterator<Item = String> + 'a>>);
+        let mut new_derivations: Option<bool> = None;
+        let mut run = || {
+            while let Some(current_rule_index) = self.rule_strategy.next_rule(new_derivations) {
+                if self.cached_rules.contains(&current_rule_index) {
+                    new_derivations = Some(false);
+                    continue;
+                }
 
-        impl<'a> Iterator for CombinedIters<'a> {
-            type Item = Vec<String>;
+                let updated_predicates =
+                    self.apply_rule(&rule_execution[current_rule_index], current_rule_index)?;
+                self.finish_step(&updated_predicates)?;
 
-            fn next(&mut self) -> Option<Self::Item> {
-                let res: Self::Item = self.0.iter_mut().filter_map(|iter| iter.next()).collect();
-                (!res.is_empty()).then_some(res)
+                if stop(self) {
+                    return Ok(false);
+                }
+
+                new_derivations = Some(!updated_predicates.is_empty());
             }
-        }
 
-        let combined_iters = CombinedIters(logically_mapped_iters);
+            Ok(true)
+        };
+        // Reasoning may be aborted by an error, e.g. if a predicate exceeds its limit
+        let result = run();
+
+        TimedCode::instance().sub("Reasoning/Rules").stop();
+        TimedCode::instance().sub("Reasoning/Execution").stop();
+        result
+    }
+
+    /// Apply the rule with the given index once, independently of the rule selection strategy,
+    /// and return the facts that it derived for each predicate of its head.
+    ///
+    /// The index refers to the rules of [`ExecutionEngine::program`].
+    /// This allows stepping through the evaluation of a program, e.g. for debugging rules.
+    /// Since rules are evaluated semi-naively, a rule only derives facts from facts
+    /// that are new since its last application.
+    pub fn apply_rule_once(
+        &mut self,
+        rule_index: usize,
+    ) -> Result<HashMap<Identifier, Vec<Vec<PrimitiveLogicalValueT>>>, Error> {
+        let rule = self
+            .program
+            .rules()
+            .get(rule_index)
+            .ok_or(Error::UnknownRule(rule_index))?;
+
+        self.functions.check_program(&self.program)?;
+        let application = RuleApplication::initialize(
+            rule,
+            &self.analysis.rule_analysis[rule_index],
+            &self.functions,
+            self.chase_variant,
+        );
+
+        let step = self.current_step;
+        let updated_predicates = self.apply_rule(&application, rule_index)?;
+
+        let mut result = HashMap::new();
+        for predicate in &updated_predicates {
+            let types = self
+                .analysis
+                .predicate_types
+                .get(predicate)
+                .expect("All predicates should have types by now.");
+            let rows = self
+                .table_manager
+                .subtable_rows(predicate.clone(), step, types)?
+                .unwrap_or_default();
+
+            result.insert(predicate.clone(), rows);
+        }
+
+        self.finish_step(&updated_predicates)?;
+
+        Ok(result)
+    }
+
+    /// Apply a single rule in the current step and update the statistics of the engine.
+    /// Returns the predicates for which new facts were derived.
+    ///
+    /// The step has to be completed with [`ExecutionEngine::finish_step`].
+    fn apply_rule(
+        &mut self,
+        current_execution: &RuleApplication,
+        rule_index: usize,
+    ) -> Result<Vec<Identifier>, Error> {
+        let timing_string = format!("Reasoning/Rules/Rule {rule_index}");
+        let _rule_span = tracing::info_span!(
+            "rule_application",
+            rule = rule_index,
+            step = self.current_step
+        )
+        .entered();
+
+        TimedCode::instance().sub(&timing_string).start();
+        tracing::info!("<<< {0}: APPLYING RULE {rule_index} >>>", self.current_step);
+
+        if let Some(max_matches) = self.max_estimated_matches {
+            self.check_estimated_matches(current_execution, rule_index, max_matches)?;
+        }
+
+        let eliminate_duplicates = self.eliminates_duplicates(rule_index);
+        let first_null = self.table_manager.next_null();
+        let current_info = &mut self.rule_infos[rule_index];
+        let results = if self.firing_log.is_some()
+            || self.duplicate_counts.is_some()
+            || !eliminate_duplicates
+        {
+            let mut subtable_execution_plan = if self.firing_log.is_some() {
+                SubtableExecutionPlan::recording_bindings()
+            } else {
+                SubtableExecutionPlan::default()
+            };
+            if self.duplicate_counts.is_some() {
+                subtable_execution_plan = subtable_execution_plan.counting_derived_facts();
+            }
+            if !eliminate_duplicates {
+                subtable_execution_plan = subtable_execution_plan.keeping_duplicates();
+            }
+
+            current_execution.execute_recording(
+                subtable_execution_plan,
+                &mut self.table_manager,
+                current_info,
+                self.current_step,
+            )?
+        } else {
+            PlanResults {
+                updated_predicates: current_execution.execute(
+                    &mut self.table_manager,
+                    current_info,
+                    self.current_step,
+                )?,
+                ..Default::default()
+            }
+        };
+        let updated_predicates = results.updated_predicates;
+
+        current_info.step_last_applied = self.current_step;
+        if !eliminate_duplicates && !updated_predicates.is_empty() {
+            self.unchecked_steps
+                .insert(self.current_step, updated_predicates.clone());
+        }
+
+        let rule_duration = TimedCode::instance().sub(&timing_string).stop();
+        tracing::info!("Rule duration: {} ms", rule_duration.as_millis());
+
+        let derived_facts = updated_predicates
+            .iter()
+            .map(|updated_pred| {
+                self.table_manager
+                    .subtable_count_rows(updated_pred.clone(), self.current_step)
+                    .unwrap_or(0)
+            })
+            .sum::<usize>();
+
+        let introduced_nulls = (self.table_manager.next_null() - first_null) as usize;
+
+        let current_info = &mut self.rule_infos[rule_index];
+        current_info.applications += 1;
+        current_info.duration += rule_duration;
+        current_info.derived_facts += derived_facts;
+        current_info.introduced_nulls += introduced_nulls;
+
+        if introduced_nulls > 0 {
+            self.count_introduced_nulls(rule_index, &updated_predicates, introduced_nulls);
+        }
+
+        if let Some(duplicate_counts) = &mut self.duplicate_counts {
+            for (predicate, derived) in results.derived_facts {
+                let new_facts = self
+                    .table_manager
+                    .subtable_count_rows(predicate.clone(), self.current_step)
+                    .unwrap_or(0);
+                *duplicate_counts.entry(predicate).or_default() +=
+                    derived.saturating_sub(new_facts);
+            }
+        }
+
+        if self.firing_log.is_some() {
+            self.log_rule_firing(rule_index, derived_facts, results.bindings)?;
+        }
+
+        self.peak_memory = self
+            .peak_memory
+            .max(self.table_manager.memory_consumption());
+
+        // Replaced predicates consist of a single table again
+        if current_execution.replaces_facts() {
+            for updated_pred in &updated_predicates {
+                self.predicate_fragmentation.remove(updated_pred);
+                self.predicate_last_union.remove(updated_pred);
+            }
+        }
+
+        Ok(updated_predicates)
+    }
+
+    /// Return whether the next application of the rule with the given index
+    /// should remove the facts that were already known from its results,
+    /// see [`ExecutionEngine::delay_duplicate_elimination`].
+    fn eliminates_duplicates(&self, rule_index: usize) -> bool {
+        let rule_info = &self.rule_infos[rule_index];
+        if (rule_info.applications + 1).is_multiple_of(self.duplicate_elimination_period) {
+            return true;
+        }
+
+        let body_predicates = &self.analysis.rule_analysis[rule_index].positive_body_predicates;
+        self.unchecked_steps.iter().any(|(&step, predicates)| {
+            step >= rule_info.step_last_applied
+                && predicates
+                    .iter()
+                    .any(|predicate| body_predicates.contains(predicate))
+        })
+    }
+
+    /// Attribute the given number of nulls introduced by the rule with the given index
+    /// to each of the updated predicates that contain existential variables in the head of the rule.
+    fn count_introduced_nulls(
+        &mut self,
+        rule_index: usize,
+        updated_predicates: &[Identifier],
+        introduced_nulls: usize,
+    ) {
+        let rule = &self.program.rules()[rule_index];
+
+        for predicate in updated_predicates {
+            let is_existential = rule.head().iter().any(|atom| {
+                &atom.predicate() == predicate && atom.existential_variables().next().is_some()
+            });
+
+            if is_existential {
+                *self.predicate_nulls.entry(predicate.clone()).or_default() += introduced_nulls;
+            }
+        }
+    }
+
+    /// Issue a warning if the estimated number of matches of the rule with the given index
+    /// exceeds the given number and the rule has not been warned about before.
+    fn check_estimated_matches(
+        &mut self,
+        current_execution: &RuleApplication,
+        rule_index: usize,
+        max_matches: u64,
+    ) -> Result<(), Error> {
+        if self.warned_rules.contains(&rule_index) {
+            return Ok(());
+        }
+
+        let estimate = current_execution.estimate_matches(
+            &mut self.table_manager,
+            &self.rule_infos[rule_index],
+            self.current_step,
+        )?;
+
+        if let Some(estimate) = estimate.filter(|&estimate| estimate > max_matches as f64) {
+            let warning = format!(
+                "Application of rule {rule_index} in step {} is estimated to produce {estimate:.0} matches, which exceeds the limit of {max_matches}",
+                self.current_step
+            );
+            tracing::warn!("{warning}");
+
+            self.warnings.push(warning);
+            self.warned_rules.insert(rule_index);
+        }
+
+        Ok(())
+    }
+
+    /// Add the application of the rule with the given index in the current step to the [`FiringLog`]
+    /// and delete the table containing the matches of its body.
+    fn log_rule_firing(
+        &mut self,
+        rule_index: usize,
+        derived_facts: usize,
+        bindings: Option<RuleBindings>,
+    ) -> Result<(), Error> {
+        let Some(firing_log) = &mut self.firing_log else {
+            return Ok(());
+        };
+
+        let mut firing = RuleFiring {
+            rule: rule_index,
+            step: self.current_step,
+            matches: 0,
+            derived_facts,
+            variables: Vec::new(),
+            bindings: Vec::new(),
+        };
+
+        if let Some(bindings) = bindings {
+            let variable_types = &self.analysis.rule_analysis[rule_index].variable_types;
+            let types = bindings
+                .variables
+                .iter()
+                .map(|variable| {
+                    *variable_types
+                        .get(variable)
+                        .expect("Every variable must be assigned to a type")
+                })
+                .collect::<Vec<_>>();
+
+            // Values computed for the head are not part of the bindings
+            let constructors = self.program.rules()[rule_index].constructors();
+            let body_columns = bindings
+                .variables
+                .iter()
+                .map(|variable| !constructors.contains_key(variable))
+                .collect::<Vec<_>>();
+
+            firing.matches = self.table_manager.table_count_rows(bindings.table);
+            firing.bindings = self
+                .table_manager
+                .table_rows_limited(bindings.table, &types, firing_log.sample_size())?
+                .into_iter()
+                .map(|row| {
+                    row.into_iter()
+                        .zip(&body_columns)
+                        .filter_map(|(value, &keep)| keep.then_some(value))
+                        .collect()
+                })
+                .collect();
+            firing.variables = bindings
+                .variables
+                .into_iter()
+                .zip(&body_columns)
+                .filter_map(|(variable, &keep)| keep.then_some(variable))
+                .collect();
+
+            self.table_manager.delete_table(bindings.table);
+        }
+
+        firing_log.push(firing);
+
+        Ok(())
+    }
+
+    /// Complete the current step after applying a rule that derived facts for the given predicates.
+    ///
+    /// Returns an error if one of these predicates has more facts than allowed by its `@limit` directive.
+    fn finish_step(&mut self, updated_predicates: &[Identifier]) -> Result<(), Error> {
+        for predicate in updated_predicates {
+            let Some(&limit) = self.program.predicate_limits().get(predicate) else {
+                continue;
+            };
+
+            let count = self.count_facts_of_predicate(predicate).unwrap_or(0);
+            if count > limit {
+                return Err(Error::PredicateLimitExceeded {
+                    predicate: predicate.name(),
+                    count,
+                    limit,
+                });
+            }
+        }
+
+        // We prevent fragmentation by periodically collecting single-step tables into larger ones
+        for updated_pred in updated_predicates.iter().cloned() {
+            let counter = self
+                .predicate_fragmentation
+                .entry(updated_pred.clone())
+                .or_insert(0);
+            *counter += 1;
+
+            if *counter == MAX_FRAGMENTATION {
+                let start = if let Some(last_union) = self.predicate_last_union.get(&updated_pred) {
+                    last_union + 1
+                } else {
+                    0
+                };
+
+                let range = start..(self.current_step + 1);
+
+                self.table_manager
+                    .combine_tables(updated_pred.clone(), range)?;
+
+                self.predicate_last_union
+                    .insert(updated_pred, self.current_step);
+
+                *counter = 0;
+            }
+        }
+
+        self.current_step += 1;
+
+        Ok(())
+    }
+
+    /// Get a reference to the loaded program.
+    pub fn program(&self) -> &ChaseProgram {
+        &self.program
+    }
+
+    /// Return the most restrictive [`Fragment`] of existential rules that contains the loaded program.
+    pub fn fragment(&self) -> Fragment {
+        self.analysis.fragment
+    }
+
+    /// Return the logical types of all predicates of the program.
+    pub(crate) fn predicate_types(&self) -> &HashMap<Identifier, Vec<PrimitiveType>> {
+        &self.analysis.predicate_types
+    }
+
+    /// Return a mutable reference to the [`TableManager`] of this engine.
+    pub(crate) fn table_manager_mut(&mut self) -> &mut TableManager {
+        &mut self.table_manager
+    }
+
+    /// Get a list of column iterators for the predicate
+    pub fn get_predicate_column_iterators(
+        &mut self,
+        predicate: Identifier,
+    ) -> Result<Option<Vec<PrimitiveLogicalValueIteratorT>>, Error> {
+        let Some(table_id) = self.table_manager.combine_predicate(predicate.clone())? else {
+            return Ok(None);
+        };
+
+        let predicate_types: &Vec<PrimitiveType> = self
+            .analysis
+            .predicate_types
+            .get(&predicate)
+            .expect("All predicates should have types by now.");
+
+        let iterators = self.table_manager.table_column_iters(table_id)?;
+
+        let logically_mapped_iters: Vec<PrimitiveLogicalValueIteratorT> = iterators
+            .into_iter()
+            .zip(predicate_types.iter())
+            .map(|(iter, lt)| lt.primitive_logical_value_iterator(iter))
+            .collect();
+
+        Ok(Some(logically_mapped_iters))
+    }
+
+    /// Creates an [`Iterator`] over the resulting facts of a predicate.
+    // TODO: we probably want to return a list of column iterators over logical values
+    pub fn table_scan(
+        &mut self,
+        predicate: Identifier,
+    ) -> Result<Option<impl Iterator<Item = Vec<PrimitiveLogicalValueT>> + '_>, Error> {
+        let Some(logically_mapped_iters) = self.get_predicate_column_iterators(predicate)? else {
+            return Ok(None);
+        };
+
+        struct CombinedIters<'a>(Vec<PrimitiveLogicalValueIteratorT<'a>>);
+
+        impl<'a> Iterator for CombinedIters<'a> {
+            type Item = Vec<PrimitiveLogicalValueT>;
+
+            fn next(&mut self) -> Option<Self::Item> {
+                let res: Self::Item = self.0.iter_mut().filter_map(|iter| iter.next()).collect();
+                (!res.is_empty()).then_some(res)
+            }
+        }
+
+        let combined_iters = CombinedIters(logically_mapped_iters);
 
         Ok(Some(combined_iters))
     }
 
+    /// Return all facts that match the given query atom.
+    ///
+    /// The query may contain constants and universal variables,
+    /// where repeated variables have to be bound to the same value.
+    /// Plans of queries and results of queries with few answers are cached
+    /// until the tables of the engine change.
+    pub fn query(&mut self, atom: &Atom) -> Result<Vec<Vec<PrimitiveLogicalValueT>>, Error> {
+        let key = QueryKey::from_atom(atom)?;
+        let types = self.query_types(key.predicate(), key.arity())?;
+
+        if let Some(rows) = self.query_cache.result(&key, self.current_step) {
+            return Ok(rows.clone());
+        }
+
+        let plan = self.query_cache.plan(&key, &types).clone();
+        let rows = self.evaluate_query(key.predicate().clone(), plan, &types)?;
+
+        self.query_cache.store_result(key, self.current_step, &rows);
+
+        Ok(rows)
+    }
+
+    /// Prepare a query that can be executed repeatedly with different values for the given parameters,
+    /// which have to be variables occurring in the query atom.
+    ///
+    /// See [`ExecutionEngine::execute_prepared`].
+    pub fn prepare(&self, atom: &Atom, parameters: &[Variable]) -> Result<PreparedQuery, Error> {
+        let types = self.query_types(&atom.predicate(), atom.term_trees().len())?;
+
+        Ok(PreparedQuery::new(atom, parameters, types)?)
+    }
+
+    /// Return all facts that match the given prepared query,
+    /// where the parameters of the query are bound to the given values (in the order of the parameters).
+    pub fn execute_prepared(
+        &mut self,
+        query: &PreparedQuery,
+        values: &[Term],
+    ) -> Result<Vec<Vec<PrimitiveLogicalValueT>>, Error> {
+        let plan = query.bind(values)?;
+
+        self.evaluate_query(query.predicate().clone(), plan, query.types())
+    }
+
+    /// Return the types of the given predicate, making sure that it can be queried with the given arity.
+    pub(super) fn query_types(
+        &self,
+        predicate: &Identifier,
+        arity: usize,
+    ) -> Result<Vec<PrimitiveType>, QueryError> {
+        let types = self
+            .analysis
+            .predicate_types
+            .get(predicate)
+            .ok_or_else(|| QueryError::UnknownPredicate(predicate.clone()))?;
+
+        if types.len() != arity {
+            return Err(QueryError::ArityMismatch {
+                predicate: predicate.clone(),
+                arity: types.len(),
+                used: arity,
+            });
+        }
+
+        Ok(types.clone())
+    }
+
+    /// Add facts to the given predicate, whose values are given column by column,
+    /// which avoids converting the values of each fact separately.
+    ///
+    /// Each column must contain values of the storage type of the corresponding logical type of the predicate,
+    /// i.e. [`VecT::I64`] for integers and datetimes, [`VecT::Double`] for floats,
+    /// [`VecT::U128`] for UUIDs and IP addresses, and [`VecT::U64`] for all other values,
+    /// which are given by their ids in the dictionary of the engine (see [`ExecutionEngine::dictionary_id`]).
+    /// Returns an error if such a column contains a value that is not the id of a dictionary entry.
+    ///
+    /// If neither the predicate nor the predicates derived from it are used by a negated atom or an aggregate,
+    /// the facts are added like facts derived in a new step,
+    /// so the next call to [`ExecutionEngine::execute`] only applies the rules to the new facts and their consequences.
+    /// Otherwise, all facts derived so far are discarded and computed again by the next call to [`ExecutionEngine::execute`].
+    /// In both cases, the facts are kept as input facts of the predicate.
+    pub fn add_table(&mut self, predicate: Identifier, columns: Vec<VecT>) -> Result<(), Error> {
+        let types = self.query_types(&predicate, columns.len())?;
+
+        for (column, (values, logical_type)) in columns.iter().zip(types).enumerate() {
+            let expected = DataTypeName::from(logical_type).to_storage_type_name();
+            if values.get_type() != expected {
+                return Err(Error::ColumnTypeMismatch {
+                    predicate: predicate.name(),
+                    column,
+                    expected,
+                    found: values.get_type(),
+                });
+            }
+        }
+
+        let row_count = columns.first().map_or(0, VecT::len);
+        if columns.iter().any(|values| values.len() != row_count) {
+            return Err(Error::ColumnLengthMismatch(predicate.name()));
+        }
+
+        let dict = self.table_manager.shared_dict();
+        for (column, values) in columns.iter().enumerate() {
+            let VecT::U64(ids) = values else {
+                continue;
+            };
+
+            // Values that do not belong to a dictionary entry would be read back as unknown constants or nulls
+            if let Some(&id) = ids.iter().find(|&&id| {
+                !usize::try_from(id).is_ok_and(|index| dict.borrow().contains_index(index))
+            }) {
+                return Err(Error::UnknownDictionaryId {
+                    predicate: predicate.name(),
+                    column,
+                    id,
+                });
+            }
+        }
+
+        if row_count == 0 {
+            return Ok(());
+        }
+
+        // Rules whose facts were loaded from a consequence cache have to be applied to the new facts as well
+        self.cached_rules.clear();
+
+        let trie = Trie::from_cols(columns);
+
+        if self.current_step == 1 {
+            // No facts have been derived yet
+            return self.table_manager.add_input_trie(predicate, trie);
+        }
+
+        if self.is_monotone_in(&HashSet::from([predicate.clone()])) {
+            let trie = Arc::new(trie);
+            if self.add_new_facts(predicate.clone(), vec![TableSource::Trie(trie.clone())])? {
+                self.added_tables.push((predicate, trie));
+            }
+
+            return Ok(());
+        }
+
+        self.reset_to_inputs()?;
+        self.table_manager.add_input_trie(predicate, trie)
+    }
+
+    /// Return the id of the given ground term in the dictionary of the engine,
+    /// which is added to the dictionary if necessary.
+    ///
+    /// The id represents the term in a [`VecT::U64`] column of the given logical type,
+    /// which can be added to a predicate with [`ExecutionEngine::add_table`].
+    /// Returns an error if values of the logical type are not stored in the dictionary.
+    pub fn dictionary_id(&mut self, term: Term, logical_type: PrimitiveType) -> Result<u64, Error> {
+        let value = logical_type
+            .ground_term_to_data_value_t(term)
+            .map_err(TypeError::from)?;
+
+        match value.try_to_storage_value_mut(&mut self.table_manager.shared_dict().borrow_mut())? {
+            StorageValueT::U64(id) => Ok(id),
+            _ => Err(Error::NoDictionaryType(logical_type)),
+        }
+    }
+
+    /// Discard all facts derived so far,
+    /// such that the next execution starts over from the input facts of each predicate.
+    pub(super) fn reset_to_inputs(&mut self) -> Result<(), Error> {
+        self.table_manager.remove_derived_tables();
+
+        for (predicate, trie) in std::mem::take(&mut self.added_tables) {
+            self.table_manager
+                .add_input_trie(predicate, Arc::unwrap_or_clone(trie))?;
+        }
+
+        self.rule_strategy = Strategy::new(
+            self.program.rules().iter().collect(),
+            self.analysis.rule_analysis.iter().collect(),
+        )?;
+        self.rule_infos = vec![RuleInfo::new(); self.program.rules().len()];
+        self.predicate_fragmentation.clear();
+        self.predicate_last_union.clear();
+        self.predicate_nulls.clear();
+        self.unchecked_steps.clear();
+        self.current_step = 1;
+        self.query_cache = QueryCache::default();
+        // Facts loaded from a consequence cache are kept as input facts,
+        // but may have to be extended by applying their rules to the new inputs
+        self.cached_rules.clear();
+
+        Ok(())
+    }
+
+    /// Return whether adding facts to the given predicates can only add consequences of the program,
+    /// i.e. whether neither the new facts nor the facts derived from them
+    /// are used by a negated atom or an aggregate.
+    pub(super) fn is_monotone_in(&self, predicates: &HashSet<Identifier>) -> bool {
+        let mut affected = predicates.clone();
+
+        loop {
+            let mut changed = false;
+
+            for rule in self.program.rules() {
+                let uses_affected = |atoms: &Vec<ChaseAtom>| {
+                    atoms
+                        .iter()
+                        .any(|atom| affected.contains(&atom.predicate()))
+                };
+
+                if uses_affected(rule.negative_body()) {
+                    return false;
+                }
+
+                if !uses_affected(rule.positive_body()) {
+                    continue;
+                }
+
+                if rule.aggregate().is_some() {
+                    return false;
+                }
+
+                for atom in rule.head() {
+                    changed |= affected.insert(atom.predicate());
+                }
+            }
+
+            if !changed {
+                return true;
+            }
+        }
+    }
+
+    /// Add the facts of an external source, which are obtained from the given provider,
+    /// to the given predicate, keeping all facts derived so far.
+    ///
+    /// The new facts are added like facts derived in a new step,
+    /// so the next call to [`ExecutionEngine::execute`] continues the semi-naive evaluation
+    /// by applying the rules to the new facts and their consequences only.
+    /// This is only correct if the program [is monotone][ExecutionEngine::is_monotone_in] in the predicate.
+    ///
+    /// Returns `false` if the source contains no facts that the predicate does not already contain.
+    pub(super) fn add_source_facts(
+        &mut self,
+        predicate: Identifier,
+        external_source: &ExternalSource,
+        provider: Rc<dyn ExternalSourceProvider>,
+    ) -> Result<bool, Error> {
+        let logical_types = self
+            .analysis
+            .predicate_types
+            .get(&predicate)
+            .cloned()
+            .expect("All predicates should have types by now.");
+        let reader = ExternalSourceReader::new(external_source, provider, logical_types);
+
+        self.add_new_facts(predicate, vec![TableSource::FileReader(Box::new(reader))])
+    }
+
+    /// Add the facts loaded from the given sources to the given predicate
+    /// like facts derived in a new step, see [`ExecutionEngine::add_source_facts`].
+    ///
+    /// Returns `false` if the sources contain no facts that the predicate does not already contain.
+    fn add_new_facts(
+        &mut self,
+        predicate: Identifier,
+        sources: Vec<TableSource>,
+    ) -> Result<bool, Error> {
+        if !self
+            .table_manager
+            .add_new_facts(predicate.clone(), self.current_step, sources)?
+        {
+            return Ok(false);
+        }
+
+        self.finish_step(&[predicate])?;
+
+        // Rules are selected again, but only derive facts from the facts that are new since their last application
+        self.rule_strategy = Strategy::new(
+            self.program.rules().iter().collect(),
+            self.analysis.rule_analysis.iter().collect(),
+        )?;
+
+        Ok(true)
+    }
+
+    /// Return the step in which the next facts will be derived.
+    pub(super) fn current_step(&self) -> usize {
+        self.current_step
+    }
+
+    /// Return the input facts of the given predicate.
+    pub(super) fn input_values(
+        &mut self,
+        predicate: &Identifier,
+    ) -> Result<Vec<Vec<DataValueT>>, Error> {
+        self.table_manager.input_values(predicate.clone())
+    }
+
+    /// Replace the input facts of the given predicate.
+    ///
+    /// This may only be called after [`ExecutionEngine::reset_to_inputs`].
+    pub(super) fn replace_input_values(
+        &mut self,
+        predicate: &Identifier,
+        rows: Vec<Vec<DataValueT>>,
+    ) -> Result<(), Error> {
+        self.table_manager
+            .replace_input_values(predicate.clone(), rows)
+    }
+
+    /// Return all input facts that match the given query atom (see [`ExecutionEngine::query`]),
+    /// ignoring all facts derived so far.
+    pub(super) fn query_inputs(
+        &mut self,
+        atom: &Atom,
+    ) -> Result<Vec<Vec<PrimitiveLogicalValueT>>, Error> {
+        let key = QueryKey::from_atom(atom)?;
+        let types = self.query_types(key.predicate(), key.arity())?;
+        let plan = self.query_cache.plan(&key, &types).clone();
+
+        if plan.is_empty {
+            return Ok(Vec::new());
+        }
+
+        let Some(table_id) = self.table_manager.input_table(key.predicate().clone()) else {
+            return Ok(Vec::new());
+        };
+
+        self.select_query_rows(table_id, plan, &types)
+    }
+
+    /// Return all facts that match the given query atom (see [`ExecutionEngine::query`]),
+    /// which are computed by goal-directed proof search instead of applying the rules of the program.
+    ///
+    /// Only the rules and input facts relevant for the query are considered,
+    /// which makes this suitable for point queries over large inputs,
+    /// for which materializing all consequences of the program would be wasteful.
+    /// The rules deriving the queried predicate may not contain
+    /// existential variables, aggregates or functions in their heads.
+    pub fn query_top_down(
+        &mut self,
+        atom: &Atom,
+    ) -> Result<Vec<Vec<PrimitiveLogicalValueT>>, Error> {
+        let key = QueryKey::from_atom(atom)?;
+        let types = self.query_types(key.predicate(), key.arity())?;
+
+        let mut evaluator = TopDownEvaluator::new(
+            self.program.rules(),
+            &self.analysis.rule_analysis,
+            &self.analysis.predicate_types,
+        );
+        evaluator.answer(self, atom, &types)
+    }
+
+    /// Return a derivation of the given fact from the input facts by the rules of the program,
+    /// or `None` if the fact does not hold.
+    ///
+    /// The derivation is reconstructed from the current tables of the engine,
+    /// so this is usually called after reasoning or after loading saved results with [`WarmStart`].
+    /// Facts derived by rules with aggregates or functions in their heads cannot be traced.
+    pub fn derivation(&mut self, fact: &Atom) -> Result<Option<Derivation>, Error> {
+        let types = self.query_types(&fact.predicate(), fact.term_trees().len())?;
+
+        let mut search = DerivationSearch::new(
+            self.program.rules(),
+            &self.analysis.rule_analysis,
+            &self.analysis.predicate_types,
+        );
+        search.derive_fact(self, fact, &types)
+    }
+
+    /// Compute the facts of the given predicate that satisfy the restrictions of the given [`QueryPlan`].
+    fn evaluate_query(
+        &mut self,
+        predicate: Identifier,
+        plan: QueryPlan,
+        types: &[PrimitiveType],
+    ) -> Result<Vec<Vec<PrimitiveLogicalValueT>>, Error> {
+        if plan.is_empty {
+            return Ok(Vec::new());
+        }
+
+        let Some(table_id) = self.table_manager.combine_predicate(predicate)? else {
+            return Ok(Vec::new());
+        };
+
+        self.select_query_rows(table_id, plan, types)
+    }
+
+    /// Compute the rows of the given table that satisfy the restrictions of the given [`QueryPlan`].
+    fn select_query_rows(
+        &mut self,
+        table_id: TableId,
+        plan: QueryPlan,
+        types: &[PrimitiveType],
+    ) -> Result<Vec<Vec<PrimitiveLogicalValueT>>, Error> {
+        if plan.is_trivial() {
+            return self.table_manager.table_rows(table_id, types);
+        }
+
+        let Some(result_id) =
+            self.table_manager
+                .select_rows(table_id, plan.equal_classes, plan.assignments)?
+        else {
+            return Ok(Vec::new());
+        };
+
+        let rows = self.table_manager.table_rows(result_id, types)?;
+        self.table_manager.delete_table(result_id);
+
+        Ok(rows)
+    }
+
+    /// Store the facts of all derived predicates that can be cached in the given [`ConsequenceCache`],
+    /// such that later runs of an edited program can reuse the facts of predicates that are not affected by the edit.
+    /// Returns the number of predicates whose facts were stored.
+    ///
+    /// This should only be called after the program has been fully executed.
+    pub fn store_consequences(
+        &mut self,
+        consequence_cache: &ConsequenceCache,
+    ) -> Result<usize, Error> {
+        let consequence_hashes = match &self.consequence_hashes {
+            Some(hashes) => hashes.clone(),
+            None => consequence_hashes(&self.program, &self.analysis, &self.input_manager),
+        };
+
+        let mut stored = 0;
+        for (predicate, hash) in consequence_hashes {
+            if consequence_cache.contains(&predicate, hash) {
+                continue;
+            }
+
+            match self.output_serialization(predicate.clone())? {
+                Some(records) => consequence_cache.store(&predicate, hash, records)?,
+                None => consequence_cache.store(&predicate, hash, std::iter::empty())?,
+            }
+
+            stored += 1;
+        }
+
+        Ok(stored)
+    }
+
+    /// Creates an [`Iterator`] over the resulting facts of a predicate.
+    pub fn output_serialization(
+        &mut self,
+        predicate: Identifier,
+    ) -> Result<Option<impl Iterator<Item = Vec<String>> + '_>, Error> {
+        let Some(table_id) = self.table_manager.combine_predicate(predicate.clone())? else {
+            return Ok(None);
+        };
+
+        self.table_serialization(predicate, table_id)
+    }
+
+    /// Creates an [`Iterator`] over the resulting facts of a predicate that were not contained
+    /// in the results of the previous run this engine was warm-started from.
+    ///
+    /// The new facts are computed as the difference of the tries of the predicate and of its previous facts.
+    /// If no facts of the predicate were loaded from a [`WarmStart`],
+    /// this yields the same facts as [`ExecutionEngine::output_serialization`].
+    pub fn new_facts_serialization(
+        &mut self,
+        predicate: Identifier,
+    ) -> Result<Option<impl Iterator<Item = Vec<String>> + '_>, Error> {
+        let Some(table_id) = self.table_manager.combine_predicate(predicate.clone())? else {
+            return Ok(None);
+        };
+
+        let table_id = match self.previous_tables.get(&predicate) {
+            Some(&previous_id) => {
+                match self.table_manager.subtract_table(table_id, previous_id)? {
+                    Some(new_id) => new_id,
+                    None => return Ok(None),
+                }
+            }
+            None => table_id,
+        };
+
+        self.table_serialization(predicate, table_id)
+    }
+
+    /// Creates an [`Iterator`] over the facts of a predicate that have been added in the given step or later,
+    /// see [`ExecutionEngine::current_step`].
+    pub(super) fn facts_since_serialization(
+        &mut self,
+        predicate: Identifier,
+        step: usize,
+    ) -> Result<Option<impl Iterator<Item = Vec<String>> + '_>, Error> {
+        let range = step..self.current_step;
+        if self
+            .table_manager
+            .tables_in_range(predicate.clone(), &range)
+            .is_empty()
+        {
+            return Ok(None);
+        }
+
+        let Some(table_id) = self
+            .table_manager
+            .combine_tables(predicate.clone(), range)?
+        else {
+            return Ok(None);
+        };
+
+        self.table_serialization(predicate, table_id)
+    }
+
+    fn table_serialization(
+        &mut self,
+        predicate: Identifier,
+        table_id: TableId,
+    ) -> Result<Option<impl Iterator<Item = Vec<String>> + '_>, Error> {
+        let predicate_types: &Vec<PrimitiveType> = self
+            .analysis
+            .predicate_types
+            .get(&predicate)
+            .expect("All predicates should have types by now.");
+
+        let iterators = self.table_manager.table_column_iters(table_id)?;
+
+        let logically_mapped_iters: Vec<Box<dyn Iterator<Item = String>>> = iterators
+            .into_iter()
+            .zip(predicate_types.iter())
+            .map(|(iter, lt)| lt.serialize_output(iter))
+            .collect();
+
+        struct CombinedIters<'a>(Vec<Box<dyn Iterator<Item = String> + 'a>>);
+
+        impl<'a> Iterator for CombinedIters<'a> {
+            type Item = Vec<String>;
+
+            fn next(&mut self) -> Option<Self::Item> {
+                let res: Self::Item = self.0.iter_mut().filter_map(|iter| iter.next()).collect();
+                (!res.is_empty()).then_some(res)
+            }
+        }
+
+        let combined_iters = CombinedIters(logically_mapped_iters);
+
+        Ok(Some(combined_iters))
+    }
+
+    /// Export the given program, which has to be the program this engine was initialized with,
+    /// replacing its names using the given [`Renaming`], see [`export_program`].
+    ///
+    /// The facts of the data sources are loaded, so that [`ExecutionEngine::output_serialization`]
+    /// yields the contents of the files read by the data sources of the exported program.
+    pub fn export<R: Renaming>(
+        &mut self,
+        program: &Program,
+        renaming: &mut R,
+    ) -> Result<ExportedProgram, Error> {
+        let exported = export_program(program, &self.analysis.predicate_types, renaming)?;
+
+        for (predicate, _) in &exported.sources {
+            if let Some(table_id) = self.table_manager.combine_predicate(predicate.clone())? {
+                self.table_manager.table_column_iters(table_id)?;
+            }
+        }
+
+        Ok(exported)
+    }
+
+    /// Write the entries of the dictionary into a dictionary file at the given path,
+    /// from which a later run can be initialized with [`ExecutionEngine::initialize_with_dictionary_file`].
+    ///
+    /// Since data sources are read lazily, this should be called after all of them have been loaded,
+    /// e.g. after reasoning.
+    pub fn export_dictionary(&self, path: &Path) -> Result<(), Error> {
+        write_dictionary(path, &*self.table_manager.shared_dict().borrow()).map_err(|error| {
+            Error::IOWriting {
+                error,
+                filename: path.to_string_lossy().to_string(),
+            }
+        })
+    }
+
+    /// Replace all names in the given program, which has to be the program this engine was initialized with,
+    /// and in the facts of its data sources by meaningless ones, see [`Anonymizer`].
+    ///
+    /// Afterwards, [`ExecutionEngine::output_serialization`] yields the anonymized facts of each predicate,
+    /// e.g. to write the files read by the data sources of the anonymized program.
+    pub fn anonymize(&mut self, program: &Program) -> Result<ExportedProgram, Error> {
+        let mut anonymizer = Anonymizer::default();
+        // Data sources are read lazily, so their facts have to be loaded before the dictionary is replaced.
+        let anonymized = self.export(program, &mut anonymizer)?;
+
+        let dict = self.table_manager.shared_dict();
+        let anonymized_dict = dict
+            .borrow()
+            .map_entries(|entry| anonymizer.dictionary_entry(entry));
+        *dict.borrow_mut() = anonymized_dict;
+
+        self.query_cache = QueryCache::default();
+
+        Ok(anonymized)
+    }
+
     /// Counts the facts of a single predicate.
     ///
     /// TODO: Currently only counting of in-memory facts is supported, see <https://github.com/knowsys/nemo/issues/335>
@@ -379,4 +1998,647 @@ impl<Strategy: RuleSelectionStrategy> ExecutionEngine<Strategy> {
     pub fn memory_usage(&self) -> MemoryUsage {
         self.table_manager.memory_usage()
     }
+
+    /// Return a [`MemoryReport`] of the memory consumed by the tables of each predicate and by the dictionary,
+    /// broken down by the kind of the physical structures and by the types of the columns.
+    pub fn memory_report(&self) -> MemoryReport {
+        self.table_manager.memory_report()
+    }
+
+    /// Return all predicates of the program, sorted by name.
+    pub fn predicates(&self) -> Vec<Identifier> {
+        let mut result: Vec<Identifier> = self
+            .analysis
+            .all_predicates
+            .iter()
+            .map(|(predicate, _)| predicate.clone())
+            .collect();
+        result.sort_by_key(Identifier::name);
+
+        result
+    }
+
+    /// Return the number of rule applications that have been performed so far.
+    pub fn steps(&self) -> usize {
+        self.current_step - 1
+    }
+
+    /// Return the current state of the tables associated with the given predicate.
+    /// Returns `None` if the predicate does not occur in the program.
+    pub fn predicate_state(&self, predicate: &Identifier) -> Option<PredicateState> {
+        let types = self.analysis.predicate_types.get(predicate)?.clone();
+        let subtables = self.table_manager.subtables(predicate)?;
+
+        Some(PredicateState {
+            predicate: predicate.clone(),
+            types,
+            is_derived: self.analysis.derived_predicates.contains(predicate),
+            rows: self.count_facts_of_predicate(predicate).unwrap_or(0),
+            introduced_nulls: self.predicate_nulls.get(predicate).copied().unwrap_or(0),
+            memory: subtables
+                .iter()
+                .fold(ByteSize(0), |acc, subtable| acc + subtable.memory),
+            subtables,
+        })
+    }
+
+    /// Return the variable orders of the rules of the program, in the order of the program.
+    pub fn variable_orders(&self) -> Vec<RuleVariableOrders> {
+        self.analysis.variable_orders()
+    }
+
+    /// Return information about each rule of the program, in the order of the program.
+    pub fn rule_infos(&self) -> &[RuleInfo] {
+        &self.rule_infos
+    }
+
+    /// Return the largest amount of memory consumed by the tables of the chase
+    /// after any of the rule applications performed so far.
+    pub fn peak_memory(&self) -> ByteSize {
+        self.peak_memory
+            .max(self.table_manager.memory_consumption())
+    }
+
+    /// Return the warnings that were issued while preparing or executing the program.
+    pub fn warnings(&self) -> &[String] {
+        &self.warnings
+    }
+
+    /// Return the statistics of the input resources that have been read so far,
+    /// i.e. their size, checksum and the number of accepted and rejected rows.
+    pub fn input_statistics(&self) -> Vec<ResourceStatistics> {
+        self.input_manager.input_statistics()
+    }
+
+    /// Return the current state of the engine.
+    pub fn state(&self) -> EngineState {
+        EngineState {
+            steps: self.steps(),
+            rule_last_applied: self
+                .rule_infos
+                .iter()
+                .map(|info| (info.step_last_applied > 0).then_some(info.step_last_applied))
+                .collect(),
+            predicates: self
+                .predicates()
+                .iter()
+                .filter_map(|predicate| self.predicate_state(predicate))
+                .collect(),
+        }
+    }
+}
+
+impl<Strategy: RuleSelectionStrategy + Clone> ExecutionEngine<Strategy> {
+    /// Create a copy of the engine in its current state, which can be used
+    /// to continue reasoning independently of this engine.
+    ///
+    /// The copy shares all tables computed so far as well as the dictionary with this engine,
+    /// so that only tables derived afterwards take up additional memory.
+    /// Input tables that have not been loaded yet are loaded before creating the copy.
+    pub fn snapshot(&mut self) -> Result<Self, Error> {
+        let _span = tracing::info_span!("snapshot").entered();
+
+        Ok(Self {
+            program: self.program.clone(),
+            analysis: self.analysis.clone(),
+            rule_strategy: self.rule_strategy.clone(),
+            input_manager: self.input_manager.clone(),
+            table_manager: self.table_manager.snapshot()?,
+            predicate_fragmentation: self.predicate_fragmentation.clone(),
+            predicate_last_union: self.predicate_last_union.clone(),
+            predicate_nulls: self.predicate_nulls.clone(),
+            rule_infos: self.rule_infos.clone(),
+            current_step: self.current_step,
+            peak_memory: self.peak_memory,
+            warnings: self.warnings.clone(),
+            functions: self.functions.clone(),
+            firing_log: self.firing_log.clone(),
+            duplicate_counts: self.duplicate_counts.clone(),
+            max_estimated_matches: self.max_estimated_matches,
+            duplicate_elimination_period: self.duplicate_elimination_period,
+            unchecked_steps: self.unchecked_steps.clone(),
+            warned_rules: self.warned_rules.clone(),
+            query_cache: self.query_cache.clone(),
+            consequence_hashes: self.consequence_hashes.clone(),
+            cached_rules: self.cached_rules.clone(),
+            previous_tables: self.previous_tables.clone(),
+            added_tables: self.added_tables.clone(),
+            chase_variant: self.chase_variant,
+        })
+    }
+
+    /// Compute the consequences of hypothetical changes to the input facts in isolation,
+    /// i.e. without affecting this engine.
+    ///
+    /// The given function adds and removes facts and may inspect their consequences
+    /// using the [`HypotheticalTransaction`], e.g.
+    /// `engine.hypothetical(|tx| { tx.add_fact(&fact)?; tx.query(&atom) })`.
+    /// The changes are evaluated on a [snapshot][ExecutionEngine::snapshot] of this engine,
+    /// which should have been executed before.
+    /// Returns the result of the function together with the changes of the output predicates.
+    pub fn hypothetical<F, T>(&mut self, function: F) -> Result<HypotheticalOutcome<T>, Error>
+    where
+        F: FnOnce(&mut HypotheticalTransaction<Strategy>) -> Result<T, Error>,
+    {
+        let _span = tracing::info_span!("hypothetical").entered();
+
+        let mut transaction = HypotheticalTransaction::new(self.snapshot()?);
+        let result = function(&mut transaction)?;
+        let mut engine = transaction.finish()?;
+
+        let mut deltas = Vec::new();
+        for predicate in self.program.output_predicates().collect::<Vec<_>>() {
+            let previous = self
+                .output_serialization(predicate.clone())?
+                .map(|records| records.collect::<HashSet<_>>())
+                .unwrap_or_default();
+            let current = engine
+                .output_serialization(predicate.clone())?
+                .map(|records| records.collect::<HashSet<_>>())
+                .unwrap_or_default();
+
+            deltas.extend(Delta::between(predicate, &previous, &current));
+        }
+
+        Ok(HypotheticalOutcome { result, deltas })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use assert_fs::TempDir;
+
+    use nemo_physical::dictionary::{Dictionary, FIRST_NULL};
+
+    use crate::{
+        datatypes::{storage_value::VecT, StorageTypeName},
+        error::Error,
+        execution::{
+            selection_strategy::{
+                dependency_graph::graph_positive::GraphConstructorPositive,
+                strategy::SelectionStrategyError, strategy_graph::StrategyDependencyGraph,
+                strategy_priority::StrategyPriority, strategy_round_robin::StrategyRoundRobin,
+                strategy_stratified_negation::StrategyStratifiedNegation,
+            },
+            test_util::sorted_strings,
+            DefaultExecutionEngine, ExecutionEngine, StructureKind,
+        },
+        io::{
+            parser::{parse_atom, parse_program},
+            resource_providers::ResourceProviders,
+            ConsequenceCache,
+        },
+        model::{types::primitive_types::PrimitiveType, Identifier, NumericLiteral, Term},
+    };
+
+    use super::RuleInfo;
+
+    #[test]
+    fn deduplication_report() {
+        let program = parse_program(
+            "edge(a, b) . edge(b, c) . edge(a, c) . path(a, b) .\n\
+             path(?x, ?y) :- edge(?x, ?y) .\n\
+             path(?x, ?z) :- path(?x, ?y), edge(?y, ?z) .\n\
+             @output path .\n",
+        )
+        .unwrap();
+
+        let mut engine: DefaultExecutionEngine =
+            ExecutionEngine::initialize(program, ResourceProviders::empty()).unwrap();
+        assert!(engine.deduplication_report().is_none());
+
+        engine.count_duplicates();
+        engine.execute().unwrap();
+
+        let report = engine.deduplication_report().unwrap();
+        let [path] = report.predicates() else {
+            panic!("Only the output predicate should be reported.");
+        };
+
+        assert_eq!(path.predicate, Identifier("path".to_string()));
+        assert_eq!(path.from_facts, 1);
+        assert_eq!(path.from_rules, 2);
+        // `path(a, b)` is derived from an edge and `path(a, c)` from an edge and a path
+        assert_eq!(path.duplicates, 2);
+    }
+
+    #[test]
+    fn snapshot_is_independent() {
+        let program = parse_program(
+            "edge(a, b) . edge(b, c) . edge(c, d) .\n\
+             reach(?x, ?y) :- edge(?x, ?y) .\n\
+             reach(?x, ?z) :- reach(?x, ?y), edge(?y, ?z) .\n",
+        )
+        .unwrap();
+        let reach = Identifier("reach".to_string());
+
+        let mut engine: DefaultExecutionEngine =
+            ExecutionEngine::initialize(program, ResourceProviders::empty()).unwrap();
+        let mut initial = engine.snapshot().unwrap();
+
+        engine.execute_until(|engine| engine.steps() == 1).unwrap();
+        let direct = engine.count_facts_of_predicate(&reach);
+        assert_eq!(direct, Some(3));
+
+        let mut partial = engine.snapshot().unwrap();
+        partial.execute().unwrap();
+        assert_eq!(partial.count_facts_of_predicate(&reach), Some(6));
+        assert_eq!(engine.count_facts_of_predicate(&reach), direct);
+        assert_eq!(engine.steps(), 1);
+
+        engine.execute().unwrap();
+        initial.execute().unwrap();
+        assert_eq!(engine.count_facts_of_predicate(&reach), Some(6));
+        assert_eq!(initial.count_facts_of_predicate(&reach), Some(6));
+        assert_eq!(initial.steps(), engine.steps());
+    }
+
+    #[test]
+    fn skolem_chase_triggers_each_frontier_once() {
+        let program = || {
+            parse_program(
+                "friend(a, b) . friend(a, c) . friend(b, d) . parent(b, e) .\n\
+                 knows(?x, ?y) :- friend(?x, ?y) .\n\
+                 parent(?x, !p) :- knows(?x, ?y) .\n",
+            )
+            .unwrap()
+        };
+        let parents = |engine: &mut DefaultExecutionEngine| {
+            engine
+                .output_serialization(Identifier("parent".to_string()))
+                .unwrap()
+                .unwrap()
+                .count()
+        };
+
+        let mut restricted: DefaultExecutionEngine =
+            ExecutionEngine::initialize(program(), ResourceProviders::empty()).unwrap();
+        restricted.execute().unwrap();
+        // `b` already has a parent
+        assert_eq!(parents(&mut restricted), 2);
+
+        let mut skolem: DefaultExecutionEngine =
+            ExecutionEngine::initialize(program(), ResourceProviders::empty()).unwrap();
+        skolem.use_skolem_chase();
+        skolem.execute().unwrap();
+        // `a` knows two persons, but only receives one parent
+        assert_eq!(parents(&mut skolem), 3);
+        assert_eq!(
+            skolem
+                .rule_infos()
+                .iter()
+                .map(|info| info.introduced_nulls)
+                .sum::<usize>(),
+            2
+        );
+    }
+
+    #[test]
+    fn datalog_rules_before_existential_rules() {
+        let program = || {
+            parse_program(
+                "person(a) .\n\
+                 hasParent(?x, !p) :- person(?x) .\n\
+                 person(?y) :- hasParent(?x, ?y) .\n\
+                 hasParent(?x, ?x) :- person(?x) .\n",
+            )
+            .unwrap()
+        };
+        let nulls = |infos: &[RuleInfo]| {
+            infos
+                .iter()
+                .map(|info| info.introduced_nulls)
+                .sum::<usize>()
+        };
+
+        let mut engine: DefaultExecutionEngine =
+            ExecutionEngine::initialize(program(), ResourceProviders::empty()).unwrap();
+        engine.execute().unwrap();
+        // `hasParent(a, a)` is derived before the existential rule is applied
+        assert_eq!(nulls(engine.rule_infos()), 0);
+        assert_eq!(
+            engine.count_facts_of_predicate(&Identifier("hasParent".to_string())),
+            Some(1)
+        );
+
+        let mut round_robin: ExecutionEngine<
+            StrategyStratifiedNegation<
+                StrategyPriority<
+                    StrategyDependencyGraph<GraphConstructorPositive, StrategyRoundRobin>,
+                >,
+            >,
+        > = ExecutionEngine::initialize(program(), ResourceProviders::empty()).unwrap();
+        round_robin.execute().unwrap();
+        assert_eq!(nulls(round_robin.rule_infos()), 1);
+    }
+
+    #[test]
+    fn delayed_duplicate_elimination() {
+        let program = || {
+            parse_program(
+                "edge(a, b) . edge(b, c) . edge(c, a) . edge(c, d) .\n\
+                 path(?x, ?y) :- edge(?x, ?y) .\n\
+                 path(?x, ?z) :- path(?x, ?y), edge(?y, ?z) .\n\
+                 reach(?y) :- path(a, ?y) .\n",
+            )
+            .unwrap()
+        };
+        let results = |engine: &mut DefaultExecutionEngine, predicate: &str| {
+            engine
+                .output_serialization(Identifier(predicate.to_string()))
+                .unwrap()
+                .unwrap()
+                .collect::<std::collections::HashSet<_>>()
+        };
+
+        let mut immediate: DefaultExecutionEngine =
+            ExecutionEngine::initialize(program(), ResourceProviders::empty()).unwrap();
+        immediate.execute().unwrap();
+
+        let mut delayed: DefaultExecutionEngine =
+            ExecutionEngine::initialize(program(), ResourceProviders::empty()).unwrap();
+        delayed.delay_duplicate_elimination(3);
+        delayed.execute().unwrap();
+
+        assert!(!delayed.unchecked_steps.is_empty());
+        for predicate in ["path", "reach"] {
+            assert_eq!(
+                results(&mut immediate, predicate),
+                results(&mut delayed, predicate)
+            );
+        }
+        assert_eq!(results(&mut delayed, "path").len(), 12);
+    }
+
+    #[test]
+    fn add_table_from_columns() {
+        let program = parse_program(
+            "@declare edge(integer, integer) .\n\
+             edge(1, 2) .\n\
+             path(?x, ?y) :- edge(?x, ?y) .\n\
+             path(?x, ?z) :- path(?x, ?y), edge(?y, ?z) .\n",
+        )
+        .unwrap();
+        let edge = Identifier("edge".to_string());
+        let paths = |engine: &mut DefaultExecutionEngine| {
+            engine
+                .output_serialization(Identifier("path".to_string()))
+                .unwrap()
+                .unwrap()
+                .count()
+        };
+
+        let mut engine: DefaultExecutionEngine =
+            ExecutionEngine::initialize(program, ResourceProviders::empty()).unwrap();
+        engine.execute().unwrap();
+        assert_eq!(paths(&mut engine), 1);
+
+        assert!(matches!(
+            engine.add_table(edge.clone(), vec![VecT::I64(vec![2]), VecT::U64(vec![3])]),
+            Err(Error::ColumnTypeMismatch { column: 1, .. })
+        ));
+        assert!(matches!(
+            engine.add_table(
+                edge.clone(),
+                vec![VecT::I64(vec![2, 3]), VecT::I64(vec![3])]
+            ),
+            Err(Error::ColumnLengthMismatch(_))
+        ));
+
+        engine
+            .add_table(
+                edge.clone(),
+                vec![VecT::I64(vec![3, 2, 3]), VecT::I64(vec![4, 3, 4])],
+            )
+            .unwrap();
+        engine.execute().unwrap();
+        assert_eq!(engine.count_facts_of_predicate(&edge), Some(3));
+        assert_eq!(paths(&mut engine), 6);
+    }
+
+    #[test]
+    fn add_table_with_dictionary_ids() {
+        let program = parse_program(
+            "edge(a, b) .\n\
+             path(?x, ?y) :- edge(?x, ?y) .\n\
+             path(?x, ?z) :- path(?x, ?y), edge(?y, ?z) .\n",
+        )
+        .unwrap();
+        let edge = Identifier("edge".to_string());
+        let path = Identifier("path".to_string());
+
+        let mut engine: DefaultExecutionEngine =
+            ExecutionEngine::initialize(program, ResourceProviders::empty()).unwrap();
+        engine.execute().unwrap();
+        let applied_rules = engine.current_step();
+
+        let mut id = |name: &str| {
+            engine
+                .dictionary_id(Term::Constant(name.to_string().into()), PrimitiveType::Any)
+                .unwrap()
+        };
+        let (a, b, c) = (id("a"), id("b"), id("c"));
+        assert_eq!(id("a"), a);
+
+        assert!(matches!(
+            engine.dictionary_id(
+                Term::NumericLiteral(NumericLiteral::Integer(1)),
+                PrimitiveType::Integer
+            ),
+            Err(Error::NoDictionaryType(PrimitiveType::Integer))
+        ));
+        for unknown in [c + 1, FIRST_NULL] {
+            assert!(matches!(
+                engine.add_table(edge.clone(), vec![VecT::U64(vec![a]), VecT::U64(vec![unknown])]),
+                Err(Error::UnknownDictionaryId { column: 1, id, .. }) if id == unknown
+            ));
+        }
+
+        engine
+            .add_table(
+                edge.clone(),
+                vec![VecT::U64(vec![b, a]), VecT::U64(vec![c, b])],
+            )
+            .unwrap();
+        // The program is monotone, so the derived facts are kept
+        assert!(engine.current_step() > applied_rules);
+        engine.execute().unwrap();
+        assert_eq!(engine.count_facts_of_predicate(&path), Some(3));
+
+        assert_eq!(
+            sorted_strings(engine.query(&parse_atom("path(a, ?x)").unwrap()).unwrap()),
+            vec![vec!["a", "b"], vec!["a", "c"]]
+        );
+
+        // The added facts are kept as input facts when the derived facts are discarded
+        engine.reset_to_inputs().unwrap();
+        engine.execute().unwrap();
+        assert_eq!(engine.count_facts_of_predicate(&path), Some(3));
+    }
+
+    #[test]
+    fn non_stratified_program_reports_cycle() {
+        let program = parse_program(
+            "base(a) .\n\
+             a(?x) :- base(?x), ~b(?x) .\n\
+             c(?x) :- a(?x) .\n\
+             b(?x) :- c(?x) .\n\
+             d(?x) :- base(?x) .\n",
+        )
+        .unwrap();
+
+        let Err(Error::SelectionStrategyError(SelectionStrategyError::NonStratifiedProgram(cycle))) =
+            DefaultExecutionEngine::initialize(program, ResourceProviders::empty())
+        else {
+            panic!("the program should not be stratified");
+        };
+
+        let steps = cycle
+            .steps
+            .iter()
+            .map(|step| (step.rule_index, step.predicate.to_string(), step.negated))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            steps,
+            vec![
+                (2, "b".to_string(), true),
+                (0, "a".to_string(), false),
+                (1, "c".to_string(), false)
+            ]
+        );
+        assert_eq!(cycle.steps[1].rule_predicates, "a :- base, ~b");
+        assert!(cycle
+            .to_string()
+            .ends_with("Consider splitting b into a separate predicate for the facts that do not depend on a, and negating that predicate in rule 0 instead."));
+    }
+
+    #[test]
+    fn consequence_cache_skips_unchanged_rules() {
+        let directory = TempDir::new().unwrap();
+        let cache = ConsequenceCache::new(directory.path().to_path_buf());
+        let reach = Identifier("reach".to_string());
+        let labeled = Identifier("labeled".to_string());
+
+        let run = |labeled_rule: &str| {
+            let program = parse_program(format!(
+                "edge(a, b) . edge(b, c) . edge(c, d) .\n\
+                 reach(?x, ?y) :- edge(?x, ?y) .\n\
+                 reach(?x, ?z) :- reach(?x, ?y), edge(?y, ?z) .\n\
+                 {labeled_rule}\n"
+            ))
+            .unwrap();
+
+            let mut engine: DefaultExecutionEngine =
+                ExecutionEngine::initialize_with_consequence_cache(
+                    program,
+                    ResourceProviders::default(),
+                    &cache,
+                )
+                .unwrap();
+            engine.execute().unwrap();
+            let stored = engine.store_consequences(&cache).unwrap();
+
+            (engine, stored)
+        };
+
+        let (initial, stored) = run("labeled(?x) :- reach(?x, d) .");
+        assert_eq!(stored, 2);
+        assert_eq!(initial.count_facts_of_predicate(&labeled), Some(3));
+
+        let (edited, stored) = run("labeled(?x) :- reach(a, ?x) .");
+        assert_eq!(stored, 1);
+        assert_eq!(edited.count_facts_of_predicate(&reach), Some(6));
+        assert_eq!(edited.count_facts_of_predicate(&labeled), Some(3));
+
+        for (rule, info) in edited.program().rules().iter().zip(edited.rule_infos()) {
+            let derives_reach = rule.head().iter().any(|atom| atom.predicate() == reach);
+            assert_eq!(info.applications == 0, derives_reach);
+        }
+    }
+
+    #[test]
+    fn memory_report_per_predicate() {
+        let program = parse_program(
+            "@declare value(any, integer) .\n\
+             value(a, 1) . value(b, 2) . value(c, 3) .\n\
+             unused(a) .\n",
+        )
+        .unwrap();
+        let mut engine: DefaultExecutionEngine =
+            ExecutionEngine::initialize(program, ResourceProviders::empty()).unwrap();
+        let value = Identifier("value".to_string());
+        assert_eq!(
+            engine
+                .output_serialization(value.clone())
+                .unwrap()
+                .unwrap()
+                .count(),
+            3
+        );
+
+        let report = engine.memory_report();
+        let tables = report.child("Tables").unwrap();
+        let value_footprint = tables.child("value").unwrap().footprint();
+        assert!(value_footprint.column_type(StorageTypeName::U64).0 > 0);
+        assert!(value_footprint.column_type(StorageTypeName::I64).0 > 0);
+        assert_eq!(value_footprint.kind(StructureKind::Dictionary).0, 0);
+
+        // Tables that have not been loaded do not consume memory
+        assert_eq!(tables.child("unused").unwrap().footprint().total().0, 0);
+
+        let dictionary = report.child("Dictionary").unwrap().footprint();
+        assert!(dictionary.kind(StructureKind::Dictionary).0 > 0);
+        assert_eq!(
+            report.footprint().total(),
+            tables.footprint().total() + dictionary.total()
+        );
+        assert!(report.to_string().contains("value ("));
+    }
+
+    #[test]
+    fn dictionary_file_keeps_indices() {
+        let directory = TempDir::new().unwrap();
+        let path = directory.path().join("constants.dict");
+        let value = Identifier("value".to_string());
+
+        let load = |engine: &mut DefaultExecutionEngine| {
+            engine
+                .output_serialization(value.clone())
+                .unwrap()
+                .unwrap()
+                .count()
+        };
+
+        let program = parse_program("value(b) . value(a) .").unwrap();
+        let mut engine: DefaultExecutionEngine =
+            ExecutionEngine::initialize(program, ResourceProviders::empty()).unwrap();
+        assert_eq!(load(&mut engine), 2);
+        engine.export_dictionary(&path).unwrap();
+
+        let program = parse_program("value(c) . value(a) . value(b) .").unwrap();
+        let mut imported: DefaultExecutionEngine =
+            ExecutionEngine::initialize_with_dictionary_file(
+                program,
+                ResourceProviders::empty(),
+                &path,
+            )
+            .unwrap();
+        assert_eq!(load(&mut imported), 3);
+
+        let dict = engine.table_manager.shared_dict();
+        let dict = dict.borrow();
+        let imported_dict = imported.table_manager.shared_dict();
+        let imported_dict = imported_dict.borrow();
+        assert!(imported_dict.len() > dict.len());
+        for index in dict.indices() {
+            assert_eq!(imported_dict.entry(index), dict.entry(index));
+        }
+
+        assert!(DefaultExecutionEngine::initialize_with_dictionary_file(
+            parse_program("value(a) .").unwrap(),
+            ResourceProviders::empty(),
+            &directory.path().join("missing.dict"),
+        )
+        .is_err());
+    }
 }
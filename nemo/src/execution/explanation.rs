@@ -0,0 +1,107 @@
+//! Explanation of the next application of a rule,
+//! including an estimate of the number of matches of its body.
+
+use std::fmt::Display;
+
+use crate::model::Identifier;
+
+/// State of the table of a single atom in the positive body of a rule.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AtomExplanation {
+    /// Predicate of the atom
+    pub predicate: Identifier,
+    /// Number of facts of the predicate
+    pub rows: usize,
+    /// Number of facts of the predicate that were derived since the last application of the rule
+    pub new_rows: usize,
+}
+
+/// Explanation of the next application of a rule,
+/// see [`crate::execution::ExecutionEngine::explain`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct RuleExplanation {
+    /// Index of the rule
+    pub rule: usize,
+    /// Predicates of the head of the rule
+    pub head: Vec<Identifier>,
+    /// Tables of the atoms of the positive body of the rule
+    pub body: Vec<AtomExplanation>,
+    /// Estimated number of matches of the rule body that involve at least one new fact,
+    /// or `None` if the rule computes an aggregate
+    pub estimated_matches: Option<f64>,
+}
+
+impl Display for RuleExplanation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let head = self
+            .head
+            .iter()
+            .map(Identifier::to_string)
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        match self.estimated_matches {
+            Some(estimate) => writeln!(
+                f,
+                "Rule {} ({head}): ~{estimate:.0} estimated matches",
+                self.rule
+            )?,
+            None => writeln!(f, "Rule {} ({head}): aggregate", self.rule)?,
+        }
+
+        for atom in &self.body {
+            writeln!(
+                f,
+                "  {}: {} rows ({} new)",
+                atom.predicate, atom.rows, atom.new_rows
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        execution::{DefaultExecutionEngine, ExecutionEngine},
+        io::{parser::parse_program, resource_providers::ResourceProviders},
+        model::Identifier,
+    };
+
+    #[test]
+    fn estimated_matches() {
+        let facts = (0..100)
+            .map(|value| format!("edge({value}, {}) .\n", value % 10))
+            .collect::<String>();
+        let program = parse_program(format!(
+            "{facts}\
+             pair(?x, ?y) :- edge(?x, ?a), edge(?y, ?b) .\n\
+             path(?x, ?z) :- edge(?x, ?y), edge(?y, ?z) .\n"
+        ))
+        .unwrap();
+        let mut engine: DefaultExecutionEngine =
+            ExecutionEngine::initialize(program, ResourceProviders::empty()).unwrap();
+
+        let pair = engine.explain(0).unwrap();
+        assert_eq!(pair.head, vec![Identifier("pair".to_string())]);
+        assert_eq!(pair.body.len(), 2);
+        assert!(pair
+            .body
+            .iter()
+            .all(|atom| atom.rows == 100 && atom.new_rows == 100));
+        let estimate = pair.estimated_matches.unwrap();
+        assert!((estimate / 10_000.0 - 1.0).abs() < 0.01);
+
+        // Each of the ten values in the second column occurs once in the first column
+        let estimate = engine.explain(1).unwrap().estimated_matches.unwrap();
+        assert!((estimate / 100.0 - 1.0).abs() < 0.2);
+
+        engine.warn_on_estimated_matches(5000);
+        engine.execute().unwrap();
+
+        assert_eq!(engine.warnings().len(), 1);
+        assert!(engine.warnings()[0].starts_with("Application of rule 0"));
+        assert_eq!(engine.explain(0).unwrap().estimated_matches, Some(0.0));
+    }
+}
@@ -0,0 +1,401 @@
+//! Writing a program as a self-contained rule file,
+//! whose data sources are replaced by CSV files containing their facts.
+//!
+//! The names in the exported program may be replaced using a [`Renaming`],
+//! e.g. by an [`Anonymizer`][super::Anonymizer].
+
+use std::{
+    collections::{HashMap, HashSet},
+    path::PathBuf,
+};
+
+use thiserror::Error;
+
+use crate::model::{
+    Atom, Filter, FilterOperation, Identifier, Literal, PrimitiveType, Program, RdfLiteral, Rule,
+    Term, TermOperation, TermTree, Variable,
+};
+
+/// Errors that can occur while exporting a program.
+#[derive(Error, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportError {
+    /// The program uses a feature that cannot be exported.
+    #[error("Programs using {0} cannot be exported yet.")]
+    Unsupported(&'static str),
+}
+
+/// Replaces the names occurring in an exported program.
+pub trait Renaming {
+    /// Return the name of the given predicate in the exported program.
+    fn predicate(&mut self, predicate: &Identifier) -> Identifier;
+
+    /// Return the given term as it occurs in the exported program.
+    fn term(&mut self, term: &Term) -> Term;
+}
+
+/// [`Renaming`] that keeps all names.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct KeepNames;
+
+impl Renaming for KeepNames {
+    fn predicate(&mut self, predicate: &Identifier) -> Identifier {
+        predicate.clone()
+    }
+
+    fn term(&mut self, term: &Term) -> Term {
+        term.clone()
+    }
+}
+
+/// A program exported by [`export_program`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExportedProgram {
+    /// The statements of the exported program, each of which fits on a single line.
+    ///
+    /// These are the data sources, the type declarations, the limits, the facts, the rules
+    /// and the output directives of the program, in this order.
+    pub statements: Vec<String>,
+    /// Each predicate with a data source together with its name in the exported program.
+    ///
+    /// The exported program reads the facts of such a predicate from a CSV file named after its new name,
+    /// see [`Identifier::sanitised_file_name`].
+    pub sources: Vec<(Identifier, Identifier)>,
+}
+
+impl ExportedProgram {
+    /// Return the text of the exported program.
+    pub fn text(&self) -> String {
+        self.statements
+            .iter()
+            .map(|statement| format!("{statement}\n"))
+            .collect()
+    }
+}
+
+/// Write the given program as a list of statements, whose names are replaced by the given [`Renaming`].
+///
+/// Instead of its data sources, the exported program reads the facts of each predicate with a data source
+/// from a CSV file, using the given types of its columns.
+/// Comments, prefixes and the base of the program are omitted.
+pub fn export_program<R: Renaming>(
+    program: &Program,
+    predicate_types: &HashMap<Identifier, Vec<PrimitiveType>>,
+    renaming: &mut R,
+) -> Result<ExportedProgram, ExportError> {
+    if program.semiring().is_some() {
+        return Err(ExportError::Unsupported("semiring annotations"));
+    }
+    if !program.temporal_predicates().is_empty() {
+        return Err(ExportError::Unsupported("temporal predicates"));
+    }
+    if program.sources().any(|source| source.is_bag()) {
+        return Err(ExportError::Unsupported("bag sources"));
+    }
+
+    let mut writer = ProgramWriter { renaming };
+    let mut statements = Vec::new();
+
+    let mut sources = Vec::new();
+    let mut seen = HashSet::new();
+    for source in program.sources() {
+        if !seen.insert(source.predicate.clone()) {
+            continue;
+        }
+
+        let predicate = writer.renaming.predicate(&source.predicate);
+        let types = predicate_types
+            .get(&source.predicate)
+            .map(|types| type_list(types))
+            .unwrap_or_default();
+        statements.push(format!(
+            "@source {}[{types}]: load-csv(\"{}.csv\") .",
+            name(&predicate),
+            predicate.sanitised_file_name(PathBuf::new()).display()
+        ));
+
+        sources.push((source.predicate.clone(), predicate));
+    }
+
+    let mut declarations = program
+        .parsed_predicate_declarations()
+        .into_iter()
+        .collect::<Vec<_>>();
+    declarations.sort_by(|(left, _), (right, _)| left.cmp(right));
+    for (predicate, types) in declarations {
+        statements.push(format!(
+            "@declare {}({}) .",
+            name(&writer.renaming.predicate(&predicate)),
+            type_list(&types)
+        ));
+    }
+
+    let mut limits = program.predicate_limits().iter().collect::<Vec<_>>();
+    limits.sort();
+    for (predicate, limit) in limits {
+        statements.push(format!(
+            "@limit {} {limit} .",
+            name(&writer.renaming.predicate(predicate))
+        ));
+    }
+
+    for fact in program.facts() {
+        statements.push(format!("{} .", writer.atom(&fact.0)));
+    }
+
+    for rule in program.rules() {
+        statements.push(writer.rule(rule));
+    }
+
+    let mut outputs = program
+        .output_predicates()
+        .map(|predicate| writer.renaming.predicate(&predicate))
+        .collect::<Vec<_>>();
+    outputs.sort();
+    for predicate in outputs {
+        statements.push(format!("@output {} .", name(&predicate)));
+    }
+
+    Ok(ExportedProgram {
+        statements,
+        sources,
+    })
+}
+
+/// Return the given types separated by commas.
+fn type_list(types: &[PrimitiveType]) -> String {
+    types
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Return the given name of a predicate or constant in the syntax of rule files.
+fn name(identifier: &Identifier) -> String {
+    let name = identifier.as_str();
+    let is_bare = name.starts_with(|c: char| c.is_ascii_alphabetic())
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | ' '));
+
+    if is_bare || name.starts_with("_:") {
+        name.to_string()
+    } else {
+        format!("<{name}>")
+    }
+}
+
+/// Return the given value as a string literal in the syntax of rule files.
+fn quoted(value: &str) -> String {
+    let mut result = String::from('"');
+    for c in value.chars() {
+        match c {
+            '"' => result.push_str("\\\""),
+            '\\' => result.push_str("\\\\"),
+            '\n' => result.push_str("\\n"),
+            '\r' => result.push_str("\\r"),
+            '\t' => result.push_str("\\t"),
+            c => result.push(c),
+        }
+    }
+    result.push('"');
+
+    result
+}
+
+/// Writes the components of a program, replacing their names using a [`Renaming`].
+struct ProgramWriter<'a, R> {
+    renaming: &'a mut R,
+}
+
+impl<R: Renaming> ProgramWriter<'_, R> {
+    fn rule(&mut self, rule: &Rule) -> String {
+        let priority = if rule.priority() != 0 {
+            format!("@priority {} ", rule.priority())
+        } else {
+            String::new()
+        };
+
+        let head = rule
+            .head()
+            .iter()
+            .map(|atom| self.atom(atom))
+            .collect::<Vec<_>>();
+        let bodies = std::iter::once(rule)
+            .chain(rule.alternatives())
+            .map(|disjunct| self.body(disjunct))
+            .collect::<Vec<_>>();
+
+        format!("{priority}{} :- {} .", head.join(", "), bodies.join(" ; "))
+    }
+
+    fn body(&mut self, rule: &Rule) -> String {
+        let mut expressions = Vec::new();
+
+        for literal in rule.body() {
+            expressions.push(match literal {
+                Literal::Positive(atom) => self.atom(atom),
+                Literal::Negative(atom) => format!("~{}", self.atom(atom)),
+            });
+        }
+        for conjunction in rule.negated_conjunctions() {
+            let atoms = conjunction
+                .iter()
+                .map(|atom| self.atom(atom))
+                .collect::<Vec<_>>();
+            expressions.push(format!("~({})", atoms.join(", ")));
+        }
+        for filter in rule.filters() {
+            expressions.push(self.filter(filter, rule.computed_variables()));
+        }
+
+        expressions.join(", ")
+    }
+
+    fn filter(&mut self, filter: &Filter, computed_variables: &[(Variable, TermTree)]) -> String {
+        // Function terms in filters are bound to computed variables, which are not part of the syntax.
+        let lhs = match computed_variables
+            .iter()
+            .find(|(variable, _)| *variable == filter.lhs)
+        {
+            Some((_, term_tree)) => self.term_tree(term_tree),
+            None => self.term(&Term::Variable(filter.lhs.clone())),
+        };
+        let operation = match filter.operation {
+            FilterOperation::MatchesText => {
+                return format!("MATCHES_TEXT({lhs}, {})", self.term(&filter.rhs))
+            }
+            FilterOperation::EditDistanceAtMost(max_distance) => {
+                return format!(
+                    "EDIT_DISTANCE({lhs}, {}) <= {max_distance}",
+                    self.term(&filter.rhs)
+                )
+            }
+            FilterOperation::Equals => "=",
+            FilterOperation::Unequals => "!=",
+            FilterOperation::LessThan => "<",
+            FilterOperation::GreaterThan => ">",
+            FilterOperation::LessThanEq => "<=",
+            FilterOperation::GreaterThanEq => ">=",
+        };
+
+        format!("{lhs} {operation} {}", self.term(&filter.rhs))
+    }
+
+    fn atom(&mut self, atom: &Atom) -> String {
+        let predicate = self.renaming.predicate(&atom.predicate());
+        let terms = atom
+            .term_trees()
+            .iter()
+            .map(|term_tree| self.term_tree(term_tree))
+            .collect::<Vec<_>>();
+
+        format!("{}({})", name(&predicate), terms.join(", "))
+    }
+
+    fn term_tree(&mut self, term_tree: &TermTree) -> String {
+        let subtrees = term_tree
+            .0
+            .subtrees
+            .iter()
+            .map(|subtree| self.term_tree(&TermTree(subtree.clone())))
+            .collect::<Vec<_>>();
+
+        let operator = match term_tree.operation() {
+            TermOperation::Term(term) => return self.term(term),
+            // Names of functions are kept, since they refer to functions provided by the engine.
+            TermOperation::Function(name) => return format!("{name}({})", subtrees.join(", ")),
+            TermOperation::Addition => " + ",
+            TermOperation::Subtraction => " - ",
+            TermOperation::Multiplication => " * ",
+            TermOperation::Division => " / ",
+        };
+
+        format!("({})", subtrees.join(operator))
+    }
+
+    fn term(&mut self, term: &Term) -> String {
+        match self.renaming.term(term) {
+            Term::Constant(constant) => name(&constant),
+            Term::Variable(variable) if variable.is_anonymous() => "_".to_string(),
+            Term::Variable(Variable::Universal(name)) => format!("?{name}"),
+            Term::Variable(Variable::Existential(name)) => format!("!{name}"),
+            Term::Aggregate(aggregate) => {
+                let variables = aggregate
+                    .variable_identifiers
+                    .iter()
+                    .map(|name| format!("?{name}"))
+                    .collect::<Vec<_>>();
+
+                format!(
+                    "#{}({})",
+                    aggregate.aggregate_identifier,
+                    variables.join(", ")
+                )
+            }
+            Term::StringLiteral(value) => quoted(&value),
+            Term::RdfLiteral(RdfLiteral::LanguageString { value, tag }) => {
+                format!("{}@{tag}", quoted(&value))
+            }
+            Term::RdfLiteral(RdfLiteral::DatatypeValue { value, datatype }) => {
+                format!("{}^^<{datatype}>", quoted(&value))
+            }
+            Term::NumericLiteral(literal) => literal.to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use crate::{
+        io::parser::parse_program,
+        model::{Identifier, PrimitiveType},
+    };
+
+    use super::{export_program, ExportError, KeepNames};
+
+    #[test]
+    fn export_statements() {
+        let program = parse_program(
+            "@prefix acme: <http://acme.com/> .\n\
+             @source employee[any, string]: load-csv(\"staff.csv\") .\n\
+             @limit senior 1_000 .\n\
+             manager(acme:alice, \"Alice \\\"A\\\" Smith\"@en) .\n\
+             @priority 2 senior(?Name, !Id) :- employee(?Name, ?Role), ~(intern(?Name), trainee(?Role)) .\n\
+             total(?Name, #sum(?Salary)) :- salary(?Name, ?Salary), ?Salary >= 0 .\n",
+        )
+        .unwrap();
+        let types = HashMap::from([(
+            Identifier("employee".to_string()),
+            vec![PrimitiveType::Any, PrimitiveType::String],
+        )]);
+
+        let exported = export_program(&program, &types, &mut KeepNames).unwrap();
+
+        assert_eq!(
+            exported.statements,
+            vec![
+                "@source employee[any, string]: load-csv(\"employee.csv\") .",
+                "@limit senior 1000 .",
+                "manager(<http://acme.com/alice>, \"Alice \\\"A\\\" Smith\"@en) .",
+                "@priority 2 senior(?Name, !Id) :- employee(?Name, ?Role), ~(intern(?Name), trainee(?Role)) .",
+                "total(?Name, #sum(?Salary)) :- salary(?Name, ?Salary), ?Salary >= 0 .",
+                "@output senior .",
+                "@output total .",
+            ]
+        );
+        assert!(parse_program(exported.text()).is_ok());
+    }
+
+    #[test]
+    fn unsupported_programs() {
+        let program = parse_program("@semiring min-plus .\n3 :: p(a) .").unwrap();
+
+        assert_eq!(
+            export_program(&program, &HashMap::new(), &mut KeepNames),
+            Err(ExportError::Unsupported("semiring annotations"))
+        );
+    }
+}
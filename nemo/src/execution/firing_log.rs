@@ -0,0 +1,100 @@
+//! Log of the rule applications performed during reasoning,
+//! including a sample of the bindings that satisfied the body of the rule.
+
+use std::io::Write;
+
+use crate::{
+    error::Error,
+    model::{types::primitive_logical_value::PrimitiveLogicalValueT, Variable},
+};
+
+/// Record of a single application of a rule.
+#[derive(Debug, Clone)]
+pub struct RuleFiring {
+    /// Index of the applied rule
+    pub rule: usize,
+    /// Execution step in which the rule was applied
+    pub step: usize,
+    /// Number of distinct matches of the rule body
+    pub matches: usize,
+    /// Number of new facts derived by the application
+    pub derived_facts: usize,
+    /// Variables of the rule body, which correspond to the entries of each binding
+    pub variables: Vec<Variable>,
+    /// Sample of the matches of the rule body
+    pub bindings: Vec<Vec<PrimitiveLogicalValueT>>,
+}
+
+/// Log of all rule applications, see [`crate::execution::ExecutionEngine::log_rule_firings`].
+///
+/// For every application, the first matches of the rule body (in the order of the computed table)
+/// are kept, up to a configurable number.
+/// Applications of rules that compute an aggregate are recorded without bindings.
+#[derive(Debug, Clone)]
+pub struct FiringLog {
+    sample_size: usize,
+    firings: Vec<RuleFiring>,
+}
+
+impl FiringLog {
+    /// Create a new [`FiringLog`] keeping at most `sample_size` bindings per rule application.
+    pub fn new(sample_size: usize) -> Self {
+        Self {
+            sample_size,
+            firings: Vec::new(),
+        }
+    }
+
+    /// Return the maximal number of bindings kept per rule application.
+    pub fn sample_size(&self) -> usize {
+        self.sample_size
+    }
+
+    /// Return the recorded rule applications in the order they were performed.
+    pub fn firings(&self) -> &[RuleFiring] {
+        &self.firings
+    }
+
+    /// Record a rule application.
+    pub(crate) fn push(&mut self, firing: RuleFiring) {
+        self.firings.push(firing);
+    }
+
+    /// Write the log as a CSV table with the columns
+    /// `rule`, `step`, `matches`, `derived` and `bindings`.
+    ///
+    /// There is one row for every sampled binding, which is given as `?X=a ?Y=b`.
+    /// Applications without bindings are represented by a single row with an empty binding.
+    pub fn write_csv<W: Write>(&self, writer: W) -> Result<(), Error> {
+        let mut writer = csv::Writer::from_writer(writer);
+        writer.write_record(["rule", "step", "matches", "derived", "bindings"])?;
+
+        for firing in &self.firings {
+            let counts = [
+                firing.rule.to_string(),
+                firing.step.to_string(),
+                firing.matches.to_string(),
+                firing.derived_facts.to_string(),
+            ];
+
+            if firing.bindings.is_empty() {
+                writer.write_record(counts.iter().map(String::as_str).chain([""]))?;
+            }
+
+            for binding in &firing.bindings {
+                let binding = firing
+                    .variables
+                    .iter()
+                    .zip(binding)
+                    .map(|(variable, value)| format!("?{variable}={value}"))
+                    .collect::<Vec<_>>()
+                    .join(" ");
+
+                writer.write_record(counts.iter().map(String::as_str).chain([binding.as_str()]))?;
+            }
+        }
+
+        writer.flush()?;
+        Ok(())
+    }
+}
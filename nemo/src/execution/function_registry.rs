@@ -0,0 +1,306 @@
+//! Functions that are defined by users of the library and can be called from rules.
+//!
+//! A function registered with an [`ExecutionEngine`][super::ExecutionEngine]
+//! can be used in rules just like the built-in functions, e.g. `p(?X, myfn(?X)) :- q(?X) .`.
+//! It is evaluated on the values of the type of the term it is used in,
+//! i.e. its arguments and its result have the same type.
+
+use std::{cell::RefCell, collections::HashMap, fmt::Debug, rc::Rc};
+
+use nemo_physical::{
+    columnar::operations::columnscan_arithmetic::ExternalFunction, datatypes::StorageValueT,
+    dictionary::value_serializer::serialize_constant_with_dict, management::database::Dict,
+};
+use thiserror::Error;
+
+use crate::model::{
+    chase_model::ChaseProgram,
+    types::primitive_logical_value::{LogicalString, PrimitiveLogicalValueT},
+    BuiltinFunction, Identifier, PrimitiveType, Term,
+};
+
+/// Function defined by a user of the library.
+///
+/// Returns `None` if the result is undefined for the given arguments,
+/// in which case no fact is derived.
+pub type UserFunction = Rc<dyn Fn(&[PrimitiveLogicalValueT]) -> Option<PrimitiveLogicalValueT>>;
+
+/// Errors that can occur when registering or calling user-defined functions.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum FunctionError {
+    /// The name of the function is already used by a built-in function.
+    #[error("The function \"{0}\" is built in and cannot be redefined.")]
+    BuiltinFunction(String),
+    /// A rule uses a function that is neither built in nor registered.
+    #[error("Unknown function \"{0}\".")]
+    UnknownFunction(String),
+    /// A rule calls a function with the wrong number of arguments.
+    #[error("The function \"{name}\" expects {arity} arguments, but {used} were given.")]
+    InvalidArity {
+        /// The name of the function
+        name: String,
+        /// The number of arguments of the function
+        arity: usize,
+        /// The number of arguments used in the rule
+        used: usize,
+    },
+}
+
+/// Function registered in a [`FunctionRegistry`].
+#[derive(Clone)]
+struct RegisteredFunction {
+    arity: usize,
+    function: UserFunction,
+}
+
+/// Collection of the user-defined functions that may be called from rules.
+///
+/// Values of type [`PrimitiveType::Any`] and [`PrimitiveType::String`]
+/// are translated using the dictionary of the engine the functions are registered with.
+#[derive(Clone)]
+pub struct FunctionRegistry {
+    functions: HashMap<String, RegisteredFunction>,
+    dict: Rc<RefCell<Dict>>,
+}
+
+impl Debug for FunctionRegistry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_map()
+            .entries(
+                self.functions
+                    .iter()
+                    .map(|(name, registered)| (name, registered.arity)),
+            )
+            .finish()
+    }
+}
+
+impl FunctionRegistry {
+    /// Create a new empty [`FunctionRegistry`], which translates values using the given dictionary.
+    pub(crate) fn new(dict: Rc<RefCell<Dict>>) -> Self {
+        Self {
+            functions: HashMap::new(),
+            dict,
+        }
+    }
+
+    /// Register a function with the given name and number of arguments.
+    ///
+    /// A previously registered function with the same name is replaced.
+    pub fn register(
+        &mut self,
+        name: &str,
+        arity: usize,
+        function: UserFunction,
+    ) -> Result<(), FunctionError> {
+        if BuiltinFunction::from_identifier(&Identifier(name.to_string())).is_some() {
+            return Err(FunctionError::BuiltinFunction(name.to_string()));
+        }
+
+        self.functions
+            .insert(name.to_string(), RegisteredFunction { arity, function });
+
+        Ok(())
+    }
+
+    /// Return whether a function with the given name is registered.
+    pub fn contains(&self, name: &str) -> bool {
+        self.functions.contains_key(name)
+    }
+
+    /// Check that every function used in the given program is either built in or registered
+    /// and is called with the right number of arguments.
+    pub fn check_program(&self, program: &ChaseProgram) -> Result<(), FunctionError> {
+        for rule in program.rules() {
+            for tree in rule.constructors().values() {
+                for (identifier, arguments) in tree.functions() {
+                    if BuiltinFunction::from_identifier(identifier).is_some() {
+                        continue;
+                    }
+
+                    let name = identifier.name();
+                    let registered = self
+                        .functions
+                        .get(&name)
+                        .ok_or_else(|| FunctionError::UnknownFunction(name.clone()))?;
+
+                    if registered.arity != arguments.len() {
+                        return Err(FunctionError::InvalidArity {
+                            name,
+                            arity: registered.arity,
+                            used: arguments.len(),
+                        });
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Return an [`ExternalFunction`], which evaluates the registered function with the given name
+    /// on physical values, whose logical type is given.
+    pub(crate) fn external_function(
+        &self,
+        name: &str,
+        logical_type: PrimitiveType,
+    ) -> ExternalFunction {
+        let function = Rc::clone(
+            &self
+                .functions
+                .get(name)
+                .expect("Unknown functions are rejected before execution.")
+                .function,
+        );
+
+        let dict = Rc::clone(&self.dict);
+
+        ExternalFunction::new(
+            name.to_string(),
+            logical_type.into(),
+            Rc::new(move |arguments| {
+                let arguments = arguments
+                    .iter()
+                    .map(|value| storage_to_logical_value(*value, logical_type, &dict))
+                    .collect::<Option<Vec<_>>>()?;
+
+                let result = Term::from(function(&arguments)?);
+                let data_value = logical_type.ground_term_to_data_value_t(result).ok()?;
+
+                Some(data_value.to_storage_value_mut(&mut dict.borrow_mut()))
+            }),
+        )
+    }
+}
+
+/// Translate a physical value of the given logical type into a [`PrimitiveLogicalValueT`].
+fn storage_to_logical_value(
+    value: StorageValueT,
+    logical_type: PrimitiveType,
+    dict: &RefCell<Dict>,
+) -> Option<PrimitiveLogicalValueT> {
+    match (logical_type, value) {
+        (PrimitiveType::Integer, StorageValueT::I64(value)) => {
+            Some(PrimitiveLogicalValueT::Integer(value.into()))
+        }
+        (PrimitiveType::Float64, StorageValueT::Double(value)) => {
+            Some(PrimitiveLogicalValueT::Float64(value.into()))
+        }
+        (PrimitiveType::DateTime, StorageValueT::I64(value)) => {
+            Some(PrimitiveLogicalValueT::DateTime(value.into()))
+        }
+        (PrimitiveType::Uuid, StorageValueT::U128(value)) => {
+            Some(PrimitiveLogicalValueT::Uuid(value.into()))
+        }
+        (PrimitiveType::IpAddress, StorageValueT::U128(value)) => {
+            Some(PrimitiveLogicalValueT::IpAddress(value.into()))
+        }
+        (PrimitiveType::Any, StorageValueT::U64(value)) => {
+            let string = serialize_constant_with_dict(value, dict.borrow());
+            Some(PrimitiveLogicalValueT::Any(Term::from(string)))
+        }
+        (PrimitiveType::String, StorageValueT::U64(value)) => {
+            let string = serialize_constant_with_dict(value, dict.borrow());
+            Some(PrimitiveLogicalValueT::String(LogicalString::from(string)))
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        error::Error,
+        execution::{test_util::initialized_engine, DefaultExecutionEngine},
+        model::{types::primitive_logical_value::PrimitiveLogicalValueT, Identifier, Term},
+    };
+
+    use super::FunctionError;
+
+    fn results(engine: &mut DefaultExecutionEngine, predicate: &str) -> Vec<Vec<String>> {
+        let mut rows: Vec<Vec<String>> = engine
+            .output_serialization(Identifier(predicate.to_string()))
+            .unwrap()
+            .map(|iter| iter.collect())
+            .unwrap_or_default();
+        rows.sort();
+        rows
+    }
+
+    #[test]
+    fn user_defined_functions() {
+        let mut engine = initialized_engine(
+            "@declare value(integer) .\n\
+             @declare collatz(integer, integer) .\n\
+             value(3) . value(4) . value(-1) .\n\
+             name(alice) . name(bob) .\n\
+             collatz(?X, collatz(?X) + 1) :- value(?X) .\n\
+             greeting(?X, greet(?X)) :- name(?X) .\n\
+             large(?X) :- value(?X), collatz(?X) > 5 .\n",
+        );
+
+        engine
+            .register_function("collatz", 1, |arguments| match &arguments[0] {
+                PrimitiveLogicalValueT::Integer(value) => {
+                    let value = i64::from(*value);
+                    match value {
+                        value if value <= 0 => None,
+                        value if value % 2 == 0 => Some(value / 2),
+                        value => Some(3 * value + 1),
+                    }
+                    .map(|result| PrimitiveLogicalValueT::Integer(result.into()))
+                }
+                _ => None,
+            })
+            .unwrap();
+        engine
+            .register_function("greet", 1, |arguments| {
+                Some(PrimitiveLogicalValueT::Any(Term::StringLiteral(format!(
+                    "Hello {}",
+                    arguments[0]
+                ))))
+            })
+            .unwrap();
+
+        engine.execute().unwrap();
+
+        assert_eq!(
+            results(&mut engine, "collatz"),
+            vec![
+                vec!["3".to_string(), "11".to_string()],
+                vec!["4".to_string(), "3".to_string()]
+            ]
+        );
+        assert_eq!(
+            results(&mut engine, "greeting"),
+            vec![
+                vec!["alice".to_string(), r#""Hello alice""#.to_string()],
+                vec!["bob".to_string(), r#""Hello bob""#.to_string()]
+            ]
+        );
+        assert_eq!(results(&mut engine, "large"), vec![vec!["3".to_string()]]);
+    }
+
+    #[test]
+    fn invalid_user_defined_functions() {
+        let mut unknown = initialized_engine("p(?X, myfn(?X)) :- q(?X) . q(a) .");
+        assert!(matches!(
+            unknown.execute(),
+            Err(Error::FunctionError(FunctionError::UnknownFunction(_)))
+        ));
+
+        let mut arity = initialized_engine("p(?X, myfn(?X)) :- q(?X) . q(a) .");
+        arity
+            .register_function("myfn", 2, |arguments| Some(arguments[0].clone()))
+            .unwrap();
+        assert!(matches!(
+            arity.execute(),
+            Err(Error::FunctionError(FunctionError::InvalidArity { .. }))
+        ));
+
+        assert!(matches!(
+            arity.register_function("HASH", 1, |_| None),
+            Err(Error::FunctionError(FunctionError::BuiltinFunction(_)))
+        ));
+    }
+}
@@ -0,0 +1,241 @@
+//! Computing the consequences of hypothetical changes to the input facts of an [`ExecutionEngine`],
+//! e.g. to answer "what if" questions in decision support.
+
+use std::collections::{HashMap, HashSet};
+
+use nemo_physical::datatypes::DataValueT;
+use thiserror::Error;
+
+use crate::{
+    error::Error,
+    model::{
+        types::{error::TypeError, primitive_logical_value::PrimitiveLogicalValueT},
+        Atom, Identifier, Term, TermOperation,
+    },
+};
+
+use super::{selection_strategy::strategy::RuleSelectionStrategy, Delta, ExecutionEngine};
+
+/// Errors that can occur while changing facts hypothetically.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum HypotheticalError {
+    /// A hypothetical fact contains a term that is not a constant.
+    #[error("Hypothetical facts may only contain constants, found \"{0}\".")]
+    NonGroundTerm(String),
+}
+
+/// Result of [`ExecutionEngine::hypothetical`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct HypotheticalOutcome<T> {
+    /// The value returned by the hypothetical computation
+    pub result: T,
+    /// Changes of the output predicates caused by the hypothetical changes,
+    /// compared to the facts of the engine
+    pub deltas: Vec<Delta>,
+}
+
+/// Hypothetical changes to the input facts of an [`ExecutionEngine`],
+/// which are evaluated on a snapshot of the engine without affecting the engine itself.
+///
+/// See [`ExecutionEngine::hypothetical`].
+#[derive(Debug)]
+pub struct HypotheticalTransaction<Strategy> {
+    engine: ExecutionEngine<Strategy>,
+    added: HashMap<Identifier, Vec<Vec<DataValueT>>>,
+    removed: HashMap<Identifier, Vec<Vec<DataValueT>>>,
+}
+
+impl<Strategy: RuleSelectionStrategy> HypotheticalTransaction<Strategy> {
+    /// Create a new [`HypotheticalTransaction`] whose changes are applied to the given engine.
+    pub(super) fn new(engine: ExecutionEngine<Strategy>) -> Self {
+        Self {
+            engine,
+            added: HashMap::new(),
+            removed: HashMap::new(),
+        }
+    }
+
+    /// Hypothetically add the given fact, which may only contain constants.
+    pub fn add_fact(&mut self, fact: &Atom) -> Result<(), Error> {
+        let row = self.fact_values(fact)?;
+        let predicate = fact.predicate();
+
+        if let Some(removed) = self.removed.get_mut(&predicate) {
+            removed.retain(|removed_row| removed_row != &row);
+        }
+        self.added.entry(predicate).or_default().push(row);
+
+        Ok(())
+    }
+
+    /// Hypothetically remove the given fact, which may only contain constants.
+    ///
+    /// Only input facts can be removed, i.e. facts that were loaded from a source or given in the program.
+    /// Derived facts disappear once none of their derivations remain.
+    pub fn remove_fact(&mut self, fact: &Atom) -> Result<(), Error> {
+        let row = self.fact_values(fact)?;
+        let predicate = fact.predicate();
+
+        if let Some(added) = self.added.get_mut(&predicate) {
+            added.retain(|added_row| added_row != &row);
+        }
+        self.removed.entry(predicate).or_default().push(row);
+
+        Ok(())
+    }
+
+    /// Answer a query (see [`ExecutionEngine::query`]) taking into account
+    /// all hypothetical changes made so far.
+    pub fn query(&mut self, atom: &Atom) -> Result<Vec<Vec<PrimitiveLogicalValueT>>, Error> {
+        self.update()?;
+        self.engine.query(atom)
+    }
+
+    /// Return the number of facts of the given predicate,
+    /// taking into account all hypothetical changes made so far.
+    pub fn count_facts_of_predicate(&mut self, predicate: &Identifier) -> Result<usize, Error> {
+        self.update()?;
+        Ok(self.engine.count_facts_of_predicate(predicate).unwrap_or(0))
+    }
+
+    /// Convert the terms of the given fact into the types of its predicate.
+    fn fact_values(&self, fact: &Atom) -> Result<Vec<DataValueT>, Error> {
+        let types = self
+            .engine
+            .query_types(&fact.predicate(), fact.term_trees().len())?;
+
+        fact.term_trees()
+            .iter()
+            .zip(types)
+            .map(|(term_tree, logical_type)| match term_tree.operation() {
+                TermOperation::Term(Term::Variable(_) | Term::Aggregate(_)) => {
+                    Err(HypotheticalError::NonGroundTerm(format!("{term_tree:?}")).into())
+                }
+                TermOperation::Term(term) => logical_type
+                    .ground_term_to_data_value_t(term.clone())
+                    .map_err(|error| TypeError::from(error).into()),
+                _ => Err(HypotheticalError::NonGroundTerm(format!("{term_tree:?}")).into()),
+            })
+            .collect()
+    }
+
+    /// Recompute the consequences of the input facts, if they have been changed since the last update.
+    ///
+    /// The input tables of the engine are reused, while all derived facts are computed again.
+    fn update(&mut self) -> Result<(), Error> {
+        if self.added.is_empty() && self.removed.is_empty() {
+            return Ok(());
+        }
+
+        self.engine.reset_to_inputs()?;
+
+        let predicates = self
+            .added
+            .keys()
+            .chain(self.removed.keys())
+            .cloned()
+            .collect::<HashSet<_>>();
+        for predicate in predicates {
+            let removed = self.removed.remove(&predicate).unwrap_or_default();
+            let added = self.added.remove(&predicate).unwrap_or_default();
+
+            let mut rows = self.engine.input_values(&predicate)?;
+            rows.retain(|row| !removed.contains(row));
+            for row in added {
+                if !rows.contains(&row) {
+                    rows.push(row);
+                }
+            }
+
+            self.engine.replace_input_values(&predicate, rows)?;
+        }
+
+        self.engine.execute()
+    }
+
+    /// Apply all remaining changes and return the resulting engine.
+    pub(super) fn finish(mut self) -> Result<ExecutionEngine<Strategy>, Error> {
+        self.update()?;
+        Ok(self.engine)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        execution::{test_util::materialized_engine, Delta},
+        io::parser::parse_atom,
+        model::Identifier,
+    };
+
+    fn fact(terms: &[&str]) -> Vec<String> {
+        terms.iter().map(|term| term.to_string()).collect()
+    }
+
+    #[test]
+    fn hypothetical_additions_and_removals() {
+        let mut engine = materialized_engine(
+            "edge(a, b) . edge(b, c) .\n\
+             reach(?x, ?y) :- edge(?x, ?y) .\n\
+             reach(?x, ?z) :- reach(?x, ?y), edge(?y, ?z) .\n\
+             @output reach .\n",
+        );
+        let reach = Identifier("reach".to_string());
+
+        let outcome = engine
+            .hypothetical(|tx| {
+                tx.add_fact(&parse_atom("edge(c, d)").unwrap())?;
+                tx.remove_fact(&parse_atom("edge(a, b)").unwrap())?;
+                tx.query(&parse_atom("reach(b, ?x)").unwrap())
+            })
+            .unwrap();
+
+        assert_eq!(outcome.result.len(), 2);
+        assert_eq!(
+            outcome.deltas,
+            vec![Delta {
+                predicate: reach.clone(),
+                added: vec![fact(&["b", "d"]), fact(&["c", "d"])],
+                removed: vec![fact(&["a", "b"]), fact(&["a", "c"])],
+            }]
+        );
+
+        // The engine itself is not affected
+        assert_eq!(engine.count_facts_of_predicate(&reach), Some(3));
+        assert!(engine
+            .hypothetical(|tx| tx.count_facts_of_predicate(&reach))
+            .unwrap()
+            .deltas
+            .is_empty());
+    }
+
+    #[test]
+    fn hypothetical_negation() {
+        let mut engine = materialized_engine(
+            "node(a) . node(b) . blocked(a) .\n\
+             open(?x) :- node(?x), ~blocked(?x) .\n\
+             @output open .\n",
+        );
+
+        let outcome = engine
+            .hypothetical(|tx| {
+                tx.remove_fact(&parse_atom("blocked(a)").unwrap())?;
+                tx.add_fact(&parse_atom("blocked(b)").unwrap())?;
+                tx.count_facts_of_predicate(&Identifier("open".to_string()))
+            })
+            .unwrap();
+
+        assert_eq!(outcome.result, 1);
+        assert_eq!(outcome.deltas[0].added, vec![fact(&["a"])]);
+        assert_eq!(outcome.deltas[0].removed, vec![fact(&["b"])]);
+    }
+
+    #[test]
+    fn non_ground_fact() {
+        let mut engine = materialized_engine("edge(a, b) .");
+
+        assert!(engine
+            .hypothetical(|tx| tx.add_fact(&parse_atom("edge(?x, b)").unwrap()))
+            .is_err());
+    }
+}
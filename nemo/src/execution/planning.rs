@@ -6,6 +6,8 @@ pub mod plan_head_datalog;
 
 pub mod plan_head_restricted;
 
+pub mod plan_head_skolem;
+
 pub mod strategy_head;
 pub use strategy_head::HeadStrategy;
 
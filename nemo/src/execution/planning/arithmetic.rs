@@ -10,7 +10,8 @@ use nemo_physical::{
 };
 
 use crate::{
-    model::{PrimitiveType, Term, TermOperation, TermTree, Variable},
+    execution::function_registry::FunctionRegistry,
+    model::{BuiltinFunction, PrimitiveType, Term, TermOperation, TermTree, Variable},
     program_analysis::variable_order::VariableOrder,
 };
 
@@ -18,6 +19,7 @@ fn termtree_to_operationtree(
     tree: &TaggedTree<TermOperation>,
     order: &VariableOrder,
     logical_type: &PrimitiveType,
+    functions: &FunctionRegistry,
 ) -> OperationTreeT {
     match &tree.tag {
         TermOperation::Term(term) => {
@@ -39,31 +41,83 @@ fn termtree_to_operationtree(
             ArithmeticOperation::Addition,
             tree.subtrees
                 .iter()
-                .map(|t| termtree_to_operationtree(t, order, logical_type))
+                .map(|t| termtree_to_operationtree(t, order, logical_type, functions))
                 .collect(),
         ),
         TermOperation::Subtraction => OperationTreeT::tree(
             ArithmeticOperation::Subtraction,
             tree.subtrees
                 .iter()
-                .map(|t| termtree_to_operationtree(t, order, logical_type))
+                .map(|t| termtree_to_operationtree(t, order, logical_type, functions))
                 .collect(),
         ),
         TermOperation::Multiplication => OperationTreeT::tree(
             ArithmeticOperation::Multiplication,
             tree.subtrees
                 .iter()
-                .map(|t| termtree_to_operationtree(t, order, logical_type))
+                .map(|t| termtree_to_operationtree(t, order, logical_type, functions))
                 .collect(),
         ),
         TermOperation::Division => OperationTreeT::tree(
             ArithmeticOperation::Division,
             tree.subtrees
                 .iter()
-                .map(|t| termtree_to_operationtree(t, order, logical_type))
+                .map(|t| termtree_to_operationtree(t, order, logical_type, functions))
                 .collect(),
         ),
-        TermOperation::Function(_) => todo!("function terms are not implemented yet."),
+        TermOperation::Function(identifier) => {
+            let Some(builtin) = BuiltinFunction::from_identifier(identifier) else {
+                return OperationTreeT::tree(
+                    ArithmeticOperation::Function(
+                        functions.external_function(&identifier.name(), *logical_type),
+                    ),
+                    tree.subtrees
+                        .iter()
+                        .map(|t| termtree_to_operationtree(t, order, logical_type, functions))
+                        .collect(),
+                );
+            };
+
+            let operation = match builtin {
+                BuiltinFunction::Integer | BuiltinFunction::Double => {
+                    let Some(TermOperation::Term(Term::Variable(variable))) =
+                        tree.subtrees.first().map(|subtree| &subtree.tag)
+                    else {
+                        unreachable!(
+                            "The parser ensures that conversions are applied to variables."
+                        )
+                    };
+
+                    return OperationTreeT::leaf(ArithmeticOperation::ConvertedColumnScan(
+                        *order
+                            .get(variable)
+                            .expect("Variable order must contain an entry for every variable."),
+                        (*logical_type).into(),
+                    ));
+                }
+                BuiltinFunction::Hash => ArithmeticOperation::Hash,
+                BuiltinFunction::Random => ArithmeticOperation::Random,
+                BuiltinFunction::SeededRandom => ArithmeticOperation::SeededRandom,
+                BuiltinFunction::Round => ArithmeticOperation::Round,
+                BuiltinFunction::Ceil => ArithmeticOperation::Ceil,
+                BuiltinFunction::Floor => ArithmeticOperation::Floor,
+                BuiltinFunction::Absolute => ArithmeticOperation::Absolute,
+                BuiltinFunction::Modulo => ArithmeticOperation::Modulo,
+                BuiltinFunction::Power => ArithmeticOperation::Power,
+                BuiltinFunction::Logarithm => ArithmeticOperation::Logarithm,
+                BuiltinFunction::SquareRoot => ArithmeticOperation::SquareRoot,
+                BuiltinFunction::Maximum => ArithmeticOperation::Maximum,
+                BuiltinFunction::Minimum => ArithmeticOperation::Minimum,
+            };
+
+            OperationTreeT::tree(
+                operation,
+                tree.subtrees
+                    .iter()
+                    .map(|t| termtree_to_operationtree(t, order, logical_type, functions))
+                    .collect(),
+            )
+        }
     }
 }
 
@@ -74,6 +128,7 @@ pub(super) fn generate_node_arithmetic(
     first_unused_index: usize,
     constructors: &HashMap<Variable, TermTree>,
     types: &HashMap<Variable, PrimitiveType>,
+    functions: &FunctionRegistry,
 ) -> (ExecutionNodeRef, VariableOrder) {
     let mut instructions = vec![vec![]; variable_order.len() + 1];
     let constructor_instructions = &mut instructions[first_unused_index];
@@ -88,6 +143,7 @@ pub(super) fn generate_node_arithmetic(
             types
                 .get(variable)
                 .expect("Every variable must be assigned to a type"),
+            functions,
         )));
     }
 
@@ -111,6 +111,7 @@ impl NegationGenerator {
                     &info.restricted_variable_order,
                     &self.filters,
                     &self.variable_types,
+                    &table_manager.get_dict(),
                 );
 
                 let node_filtered = plan.plan_mut().select_value(node_union, assignments);
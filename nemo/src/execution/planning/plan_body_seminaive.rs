@@ -5,7 +5,8 @@ use std::collections::{HashMap, HashSet};
 use nemo_physical::management::execution_plan::ExecutionNodeRef;
 
 use crate::{
-    execution::execution_engine::RuleInfo,
+    error::Error,
+    execution::{execution_engine::RuleInfo, function_registry::FunctionRegistry},
     model::{chase_model::ChaseRule, Term, TermTree, Variable},
     program_analysis::{analysis::RuleAnalysis, variable_order::VariableOrder},
     table_manager::{SubtableExecutionPlan, TableManager},
@@ -20,17 +21,26 @@ use super::{
 #[derive(Debug)]
 pub struct SeminaiveStrategy {
     used_variables: HashSet<Variable>,
+    /// Whether a single match of the body suffices to derive all facts of the head,
+    /// since it contains neither variables from the body nor existential variables
+    first_match_only: bool,
     constructors: HashMap<Variable, TermTree>,
     join_generator: SeminaiveJoinGenerator,
     negation_generator: Option<NegationGenerator>,
+    functions: FunctionRegistry,
 }
 
 impl SeminaiveStrategy {
     /// Create new [`SeminaiveStrategy`] object.
-    pub fn initialize(rule: &ChaseRule, analysis: &RuleAnalysis) -> Self {
+    pub fn initialize(
+        rule: &ChaseRule,
+        analysis: &RuleAnalysis,
+        functions: &FunctionRegistry,
+    ) -> Self {
         let constructors = rule.constructors().clone();
 
         let used_variables = Self::get_used_variables(&analysis.head_variables, &constructors);
+        let first_match_only = used_variables.is_empty() && !analysis.is_existential;
 
         let join_generator = SeminaiveJoinGenerator {
             atoms: rule.positive_body().clone(),
@@ -50,9 +60,11 @@ impl SeminaiveStrategy {
 
         Self {
             used_variables,
+            first_match_only,
             constructors,
             join_generator,
             negation_generator,
+            functions: functions.clone(),
         }
     }
 
@@ -81,6 +93,32 @@ impl SeminaiveStrategy {
 }
 
 impl BodyStrategy for SeminaiveStrategy {
+    fn may_have_matches(
+        &self,
+        table_manager: &mut TableManager,
+        rule_info: &RuleInfo,
+        step_number: usize,
+    ) -> bool {
+        self.join_generator.may_have_matches(
+            table_manager,
+            rule_info.step_last_applied,
+            step_number,
+        )
+    }
+
+    fn estimate_matches(
+        &self,
+        table_manager: &mut TableManager,
+        rule_info: &RuleInfo,
+        step_number: usize,
+    ) -> Result<f64, Error> {
+        self.join_generator.estimate_matches(
+            table_manager,
+            rule_info.step_last_applied,
+            step_number,
+        )
+    }
+
     fn add_plan_body(
         &self,
         table_manager: &TableManager,
@@ -117,9 +155,21 @@ impl BodyStrategy for SeminaiveStrategy {
             last_used,
             &self.constructors,
             types,
+            &self.functions,
         );
 
-        current_plan.add_temporary_table_cut(node_seminaive.clone(), "Body Join", cut);
+        if current_plan.records_bindings() {
+            // The bindings are needed in full, so no layers can be cut
+            current_plan.add_bindings_table(
+                node_seminaive.clone(),
+                "Body Join",
+                variable_order.as_ordered_list(),
+            );
+        } else if self.first_match_only {
+            current_plan.add_temporary_table_first_match(node_seminaive.clone(), "Body Join", cut);
+        } else {
+            current_plan.add_temporary_table_cut(node_seminaive.clone(), "Body Join", cut);
+        }
 
         node_seminaive
     }
@@ -86,6 +86,25 @@ impl HeadStrategy for DatalogStrategy {
 
             let new_tables_union = current_plan.plan_mut().union(project_append_nodes);
 
+            if current_plan.counts_derived_facts() {
+                current_plan.add_derived_facts_table(
+                    new_tables_union.clone(),
+                    "Derived Facts (Datalog)",
+                    predicate.clone(),
+                );
+            }
+
+            if !current_plan.eliminates_duplicates() {
+                current_plan.add_permanent_table(
+                    new_tables_union,
+                    "Head Union (Datalog)",
+                    &head_table_name,
+                    SubtableIdentifier::new(predicate.clone(), step),
+                );
+
+                continue;
+            }
+
             let old_subtables = table_manager.tables_in_range(predicate.clone(), &(0..step));
             let old_table_nodes: Vec<ExecutionNodeRef> = old_subtables
                 .into_iter()
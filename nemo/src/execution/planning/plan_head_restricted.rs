@@ -1,13 +1,10 @@
 //! Module defining the strategies used to
 //! derive the new facts for a rule application with existential variables in the head.
 
-use std::collections::{HashMap, HashSet};
+use std::collections::HashSet;
 
 use nemo_physical::{
-    management::{
-        database::{ColumnOrder, TableId},
-        execution_plan::ExecutionNodeRef,
-    },
+    management::execution_plan::ExecutionNodeRef,
     tabular::operations::triescan_project::ProjectReordering,
 };
 
@@ -19,9 +16,7 @@ use crate::{
 };
 
 use super::{
-    plan_util::{
-        atom_binding, cut_last_layers, head_instruction_from_atom, subplan_union, HeadInstruction,
-    },
+    plan_util::{cut_last_layers, subplan_union, ExistentialHeadGenerator},
     HeadStrategy, SeminaiveJoinGenerator,
 };
 
@@ -29,9 +24,7 @@ use super::{
 #[derive(Debug)]
 pub struct RestrictedChaseStrategy {
     join_generator: SeminaiveJoinGenerator,
-
-    predicate_to_instructions: HashMap<Identifier, Vec<HeadInstruction>>,
-    predicate_to_full_existential: HashMap<Identifier, bool>,
+    head_generator: ExistentialHeadGenerator,
 
     aux_head_order: VariableOrder,
     aux_predicate: Identifier,
@@ -44,25 +37,7 @@ pub struct RestrictedChaseStrategy {
 impl RestrictedChaseStrategy {
     /// Create a new [`RestrictedChaseStrategy`] object.
     pub fn initialize(rule: &ChaseRule, analysis: &RuleAnalysis) -> Self {
-        let mut predicate_to_instructions = HashMap::<Identifier, Vec<HeadInstruction>>::new();
-        let mut predicate_to_full_existential = HashMap::<Identifier, bool>::new();
-
-        for head_atom in rule.head() {
-            let is_existential = head_atom
-                .terms()
-                .iter()
-                .any(|t| matches!(t, Term::Variable(Variable::Existential(_))));
-
-            let instructions = predicate_to_instructions
-                .entry(head_atom.predicate())
-                .or_default();
-            instructions.push(head_instruction_from_atom(head_atom, analysis));
-
-            let is_full_existential = predicate_to_full_existential
-                .entry(head_atom.predicate())
-                .or_insert(true);
-            *is_full_existential &= is_existential;
-        }
+        let head_generator = ExistentialHeadGenerator::initialize(rule, analysis);
 
         let head_join_atoms = analysis.existential_aux_rule.positive_body().clone();
         let head_join_filters = analysis.existential_aux_rule.positive_filters().clone();
@@ -91,8 +66,7 @@ impl RestrictedChaseStrategy {
 
         RestrictedChaseStrategy {
             join_generator,
-            predicate_to_instructions,
-            predicate_to_full_existential,
+            head_generator,
             analysis: analysis.clone(),
             aux_predicate,
             aux_head_order,
@@ -136,7 +110,7 @@ impl HeadStrategy for RestrictedChaseStrategy {
         //     If it contains an existential varianormalize_atom_veble: Project from "Unsatisfied Matches Nulls" and append constants when needed
         //     If it does not contain an existential: Project from "Unsatisfied Matches Nulls", append constants and perform duplicate elimation
 
-        log::info!(
+        tracing::info!(
             "Existential Head Join Variable Order: {:?}",
             self.analysis.existential_aux_order
         );
@@ -260,88 +234,14 @@ impl HeadStrategy for RestrictedChaseStrategy {
             "Head (Restricted): Unsat. Matches",
         );
 
-        let variables_unsatisfied_matches_nulls = append_existential_at_the_end(
+        // 6. For each head atom project from "Unsatisfied Matches Nulls"
+        self.head_generator.add_plan_head(
+            table_manager,
+            current_plan,
+            node_unsatisfied_matches_nulls,
             self.aux_head_order.clone(),
-            &self.analysis.head_variables,
+            &self.analysis,
+            step,
         );
-
-        // 6. For each head atom project from "Unsatisfied Matches Nulls"
-        for (predicate, head_instructions) in self.predicate_to_instructions.iter() {
-            let mut final_head_nodes =
-                Vec::<ExecutionNodeRef>::with_capacity(head_instructions.len());
-
-            for head_instruction in head_instructions {
-                let head_binding = atom_binding(
-                    &head_instruction.reduced_atom,
-                    &variables_unsatisfied_matches_nulls,
-                );
-                let head_reordering = ProjectReordering::from_vector(
-                    head_binding.clone(),
-                    variables_unsatisfied_matches_nulls.len(),
-                );
-
-                let project_node = current_plan
-                    .plan_mut()
-                    .project(node_unsatisfied_matches_nulls.clone(), head_reordering);
-                let append_node = current_plan
-                    .plan_mut()
-                    .append_columns(project_node, head_instruction.append_instructions.clone());
-
-                final_head_nodes.push(append_node);
-            }
-
-            let new_tables_union = current_plan.plan_mut().union(final_head_nodes);
-
-            // We just pick the default order
-            // TODO: Is there a better pick?
-            let result_order = ColumnOrder::default();
-            let result_table_name =
-                table_manager.generate_table_name(predicate.clone(), &result_order, step);
-            let result_subtable_id = SubtableIdentifier::new(predicate.clone(), step);
-
-            if *self.predicate_to_full_existential.get(predicate).unwrap() {
-                // Since every new entry will contain a fresh null no duplcate elimination is needed
-                current_plan.add_permanent_table(
-                    new_tables_union,
-                    "Head (Restricted): Result Project",
-                    &result_table_name,
-                    result_subtable_id,
-                );
-            } else {
-                // Duplicate elimination for atoms thats do not contain existential variables
-                // Same as in plan_head_datalog
-                let old_tables: Vec<TableId> =
-                    table_manager.tables_in_range(predicate.clone(), &(0..step));
-                let old_table_nodes: Vec<ExecutionNodeRef> = old_tables
-                    .into_iter()
-                    .map(|id| current_plan.plan_mut().fetch_existing(id))
-                    .collect();
-                let old_table_union = current_plan.plan_mut().union(old_table_nodes);
-
-                let remove_duplicate_node = current_plan
-                    .plan_mut()
-                    .minus(new_tables_union, old_table_union);
-
-                current_plan.add_permanent_table(
-                    remove_duplicate_node,
-                    "Head (Restricted): Result Project",
-                    &result_table_name,
-                    result_subtable_id,
-                );
-            }
-        }
-    }
-}
-
-fn append_existential_at_the_end(
-    mut order: VariableOrder,
-    variables: &HashSet<Variable>,
-) -> VariableOrder {
-    for variable in variables {
-        if matches!(variable, Variable::Existential(_)) {
-            order.push(variable.clone());
-        }
     }
-
-    order
 }
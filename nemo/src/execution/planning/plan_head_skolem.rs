@@ -0,0 +1,129 @@
+//! Module defining the strategy used to
+//! derive the new facts for a rule application with existential variables in the head
+//! in the Skolem chase.
+
+use nemo_physical::{
+    management::execution_plan::ExecutionNodeRef,
+    tabular::operations::triescan_project::ProjectReordering,
+};
+
+use crate::{
+    execution::execution_engine::RuleInfo,
+    model::{chase_model::ChaseRule, Identifier, Term},
+    program_analysis::{analysis::RuleAnalysis, variable_order::VariableOrder},
+    table_manager::{SubtableExecutionPlan, SubtableIdentifier, TableManager},
+};
+
+use super::{
+    plan_util::{subplan_union, ExistentialHeadGenerator},
+    HeadStrategy,
+};
+
+/// Strategy for the Skolem chase.
+///
+/// Every binding of the frontier variables, i.e. the variables that occur in the body and the head,
+/// triggers the rule at most once, no matter whether the head is already satisfied.
+/// This corresponds to replacing the existential variables by Skolem terms over the frontier,
+/// such that applying the rule to the same frontier again would only produce equivalent nulls.
+#[derive(Debug)]
+pub struct SkolemChaseStrategy {
+    head_generator: ExistentialHeadGenerator,
+
+    frontier_order: VariableOrder,
+    frontier_predicate: Identifier,
+
+    analysis: RuleAnalysis,
+}
+
+impl SkolemChaseStrategy {
+    /// Create a new [`SkolemChaseStrategy`] object.
+    pub fn initialize(rule: &ChaseRule, analysis: &RuleAnalysis) -> Self {
+        let head_generator = ExistentialHeadGenerator::initialize(rule, analysis);
+
+        // The frontier bindings that already triggered the rule are stored
+        // in the tables of the auxiliary predicate used by the restricted chase,
+        // whose head consists of the frontier variables
+        let frontier_atom = &analysis.existential_aux_rule.head()[0];
+        let mut frontier_order = VariableOrder::new();
+        for term in frontier_atom.terms() {
+            if let Term::Variable(variable) = term {
+                frontier_order.push(variable.clone());
+            } else {
+                unreachable!("This atom should only conist of variables");
+            }
+        }
+
+        SkolemChaseStrategy {
+            head_generator,
+            frontier_order,
+            frontier_predicate: frontier_atom.predicate(),
+            analysis: analysis.clone(),
+        }
+    }
+}
+
+impl HeadStrategy for SkolemChaseStrategy {
+    fn add_plan_head(
+        &self,
+        table_manager: &TableManager,
+        current_plan: &mut SubtableExecutionPlan,
+        node_matches: ExecutionNodeRef,
+        _rule_info: &RuleInfo,
+        body_join_order: VariableOrder,
+        step: usize,
+    ) {
+        // 1. Project the matches to the frontier variables
+
+        let variables_matches = body_join_order.as_ordered_list();
+        let variables_frontier = self.frontier_order.as_ordered_list();
+        let matches_frontier_reordering =
+            ProjectReordering::from_transformation(&variables_matches, &variables_frontier);
+
+        let node_matches_frontier = current_plan
+            .plan_mut()
+            .project(node_matches, matches_frontier_reordering);
+
+        // 2. Remove the frontier bindings that triggered the rule in a previous application
+
+        let node_triggered_frontier = subplan_union(
+            current_plan.plan_mut(),
+            table_manager,
+            self.frontier_predicate.clone(),
+            &(0..step),
+        );
+        let node_new_frontier = current_plan
+            .plan_mut()
+            .minus(node_matches_frontier, node_triggered_frontier);
+
+        // 3. Remember the new frontier bindings, which trigger the rule in this application
+
+        current_plan.add_permanent_table(
+            node_new_frontier.clone(),
+            "Head (Skolem): Triggered Frontier",
+            "Skolem Chase Helper Table",
+            SubtableIdentifier::new(self.frontier_predicate.clone(), step),
+        );
+
+        // 4. Append a fresh null for each existential variable
+
+        let node_new_frontier_nulls = current_plan
+            .plan_mut()
+            .append_nulls(node_new_frontier, self.analysis.num_existential);
+
+        current_plan.add_temporary_table(
+            node_new_frontier_nulls.clone(),
+            "Head (Skolem): New Frontier",
+        );
+
+        // 5. For each head atom project from the new frontier bindings with nulls
+
+        self.head_generator.add_plan_head(
+            table_manager,
+            current_plan,
+            node_new_frontier_nulls,
+            self.frontier_order.clone(),
+            &self.analysis,
+            step,
+        );
+    }
+}
@@ -7,22 +7,25 @@ use std::{
 
 use nemo_physical::{
     columnar::operations::columnscan_restrict_values::{FilterBound, FilterValue},
-    datatypes::DataValueT,
+    datatypes::{DataValueT, StorageValueT},
+    dictionary::TextQuery,
     management::{
-        database::{ColumnOrder, TableId},
+        database::{ColumnOrder, Dict, TableId},
         execution_plan::{ExecutionNodeRef, ExecutionPlan},
     },
     tabular::operations::{
-        triescan_append::AppendInstruction, triescan_select::SelectEqualClasses, ValueAssignment,
+        triescan_append::AppendInstruction, triescan_project::ProjectReordering,
+        triescan_select::SelectEqualClasses, ValueAssignment,
     },
 };
 
 use crate::{
     model::{
-        chase_model::ChaseAtom, Filter, FilterOperation, Identifier, PrimitiveType, Term, Variable,
+        chase_model::{ChaseAtom, ChaseRule},
+        Filter, FilterOperation, Identifier, PrimitiveType, Term, Variable,
     },
     program_analysis::{analysis::RuleAnalysis, variable_order::VariableOrder},
-    table_manager::TableManager,
+    table_manager::{SubtableExecutionPlan, SubtableIdentifier, TableManager},
 };
 
 /// This function replaces each variable in the atom with its position in the variable ordering
@@ -38,11 +41,108 @@ pub(super) fn atom_binding(atom: &ChaseAtom, variable_order: &VariableOrder) ->
     }).collect()
 }
 
+/// Return the positions of the atom whose variables are required to be equal by the filters,
+/// e.g. `[[0, 1]]` for the atom `edge(x, y)` and the filter `y = x`,
+/// which is the result of normalizing the atom `edge(x, x)`.
+///
+/// Restricting the table of the atom to these rows before joining it with other atoms
+/// avoids considering rows that are discarded by the filters after the join.
+pub(super) fn atom_equal_classes(atom: &ChaseAtom, filters: &[Filter]) -> SelectEqualClasses {
+    let position = |variable: &Variable| {
+        atom.terms()
+            .iter()
+            .position(|term| matches!(term, Term::Variable(current) if current == variable))
+    };
+
+    let mut classes = Vec::<Vec<usize>>::new();
+    for filter in filters {
+        if filter.operation != FilterOperation::Equals {
+            continue;
+        }
+        let Term::Variable(right_variable) = &filter.rhs else {
+            continue;
+        };
+        let (Some(left), Some(right)) = (position(&filter.lhs), position(right_variable)) else {
+            continue;
+        };
+
+        let left_class = classes.iter().position(|class| class.contains(&left));
+        let right_class = classes.iter().position(|class| class.contains(&right));
+
+        match (left_class, right_class) {
+            (Some(left_class), Some(right_class)) if left_class == right_class => {}
+            (Some(left_class), Some(right_class)) => {
+                let other_class = classes.remove(left_class.max(right_class));
+                classes[left_class.min(right_class)].extend(other_class);
+            }
+            (Some(left_class), None) => classes[left_class].push(right),
+            (None, Some(right_class)) => classes[right_class].push(left),
+            (None, None) => classes.push(vec![left, right]),
+        }
+    }
+
+    for class in &mut classes {
+        class.sort();
+    }
+
+    classes
+}
+
+/// Return the value that the given filter compares its variable with, if it is a constant.
+fn filter_constant(
+    filter: &Filter,
+    variable_types: &HashMap<Variable, PrimitiveType>,
+) -> Option<DataValueT> {
+    if let Term::Variable(_) = filter.rhs {
+        return None;
+    }
+
+    let value = variable_types
+        .get(&filter.lhs)
+        .expect("Each variable should have been assigned a type.")
+        .ground_term_to_data_value_t(filter.rhs.clone())
+        .expect("Trying to convert a ground type into an invalid logical type. Should have been prevented by the type checker.");
+
+    Some(value)
+}
+
+/// Resolve a constant to the value stored in the tables, i.e. replace strings by their index in the dictionary.
+/// Returns `None` if the constant does not occur in the dictionary,
+/// in which case no table that is available in memory contains it.
+fn resolve_constant(dict: &Dict, value: &DataValueT) -> Option<DataValueT> {
+    match value {
+        DataValueT::String(_) => match value.to_storage_value(dict)? {
+            StorageValueT::U64(index) => Some(DataValueT::U64(index)),
+            _ => unreachable!("Strings are stored as dictionary indices."),
+        },
+        _ => Some(value.clone()),
+    }
+}
+
+/// Return whether one of the given filters requires a variable to be equal to a constant
+/// which does not occur in the dictionary,
+/// so that the filters can not be satisfied by values from tables that are available in memory.
+pub(super) fn has_unknown_constant(
+    filters: &[Filter],
+    variable_types: &HashMap<Variable, PrimitiveType>,
+    dict: &Dict,
+) -> bool {
+    filters.iter().any(|filter| {
+        filter.operation == FilterOperation::Equals
+            && filter_constant(filter, variable_types)
+                .is_some_and(|value| resolve_constant(dict, &value).is_none())
+    })
+}
+
 /// Calculate helper structures that define the filters that need to be applied.
+///
+/// Constants are resolved to their index in the dictionary if possible,
+/// so that they need not be looked up while executing the plan.
 pub(super) fn compute_filters(
     variable_order: &VariableOrder,
     filters: &[Filter],
     variable_types: &HashMap<Variable, PrimitiveType>,
+    dict: &Dict,
 ) -> (SelectEqualClasses, HashMap<usize, ValueAssignment>) {
     let mut filter_assignments = HashMap::<usize, ValueAssignment>::new();
     let mut filter_classes = Vec::<HashSet<&Variable>>::new();
@@ -76,7 +176,8 @@ pub(super) fn compute_filters(
 
                                 filter_classes.remove(li);
                             }
-                            std::cmp::Ordering::Equal => todo!(),
+                            // Both variables are already known to be equal
+                            std::cmp::Ordering::Equal => {}
                             std::cmp::Ordering::Greater => {
                                 let other_set = filter_classes[ri].clone();
                                 filter_classes[li].extend(other_set);
@@ -129,33 +230,43 @@ pub(super) fn compute_filters(
                         (column_idx_right, column_idx_left, filter.operation.flip())
                     };
 
-                let current_assignment = filter_assignments.entry(column_idx_value).or_default();
-
                 add_restriction(
                     &operation,
                     FilterValue::Column(column_idx_bound),
-                    &mut current_assignment.lower_bounds,
-                    &mut current_assignment.upper_bounds,
-                    &mut current_assignment.avoid_values,
+                    filter_assignments.entry(column_idx_value).or_default(),
                 );
             }
-            _ => {
+            Term::StringLiteral(query) if filter.operation == FilterOperation::MatchesText => {
                 let column_idx_value = *variable_order
                     .get(&filter.lhs)
                     .expect("Loop iteration is skipped for unknown variables.");
-                let right_value = variable_types
-                    .get(&filter.lhs)
-                    .expect("Each variable should have been assigned a type.")
-                    .ground_term_to_data_value_t(filter.rhs.clone()).expect("Trying to convert a ground type into an invalid logical type. Should have been prevented by the type checker.");
 
-                let current_assignment = filter_assignments.entry(column_idx_value).or_default();
+                // The matching strings are looked up when the plan is executed,
+                // since tables loaded while executing the plan might add new strings
+                filter_assignments
+                    .entry(column_idx_value)
+                    .or_default()
+                    .text_queries
+                    .push(TextQuery::new(query));
+            }
+            _ => {
+                let column_idx_value = *variable_order
+                    .get(&filter.lhs)
+                    .expect("Loop iteration is skipped for unknown variables.");
+                let right_value = filter_constant(filter, variable_types)
+                    .expect("Variables are handled in the other match arm.");
+                // Constants which are not in the dictionary yet might still occur in tables
+                // that are loaded while executing the plan.
+                // Strings similar to a constant are looked up by its string when the plan is executed.
+                let right_value = match filter.operation {
+                    FilterOperation::EditDistanceAtMost(_) => right_value,
+                    _ => resolve_constant(dict, &right_value).unwrap_or(right_value),
+                };
 
                 add_restriction(
                     &filter.operation,
                     FilterValue::Constant(right_value),
-                    &mut current_assignment.lower_bounds,
-                    &mut current_assignment.upper_bounds,
-                    &mut current_assignment.avoid_values,
+                    filter_assignments.entry(column_idx_value).or_default(),
                 );
             }
         }
@@ -179,20 +290,28 @@ pub(super) fn compute_filters(
 fn add_restriction(
     operation: &FilterOperation,
     value: FilterValue<DataValueT>,
-    lower_bounds: &mut Vec<FilterBound<DataValueT>>,
-    upper_bounds: &mut Vec<FilterBound<DataValueT>>,
-    avoid_values: &mut Vec<FilterValue<DataValueT>>,
+    assignment: &mut ValueAssignment,
 ) {
     match operation {
         FilterOperation::Equals => {
-            lower_bounds.push(FilterBound::Inclusive(value.clone()));
-            upper_bounds.push(FilterBound::Inclusive(value))
+            assignment
+                .lower_bounds
+                .push(FilterBound::Inclusive(value.clone()));
+            assignment.upper_bounds.push(FilterBound::Inclusive(value))
+        }
+        FilterOperation::LessThan => assignment.upper_bounds.push(FilterBound::Exclusive(value)),
+        FilterOperation::GreaterThan => assignment.lower_bounds.push(FilterBound::Exclusive(value)),
+        FilterOperation::LessThanEq => assignment.upper_bounds.push(FilterBound::Inclusive(value)),
+        FilterOperation::GreaterThanEq => {
+            assignment.lower_bounds.push(FilterBound::Inclusive(value))
+        }
+        FilterOperation::Unequals => assignment.avoid_values.push(value),
+        FilterOperation::MatchesText => {
+            unreachable!("Text searches are not expressed by bounds.")
+        }
+        FilterOperation::EditDistanceAtMost(max_distance) => {
+            assignment.similar_values.push((value, *max_distance))
         }
-        FilterOperation::LessThan => upper_bounds.push(FilterBound::Exclusive(value)),
-        FilterOperation::GreaterThan => lower_bounds.push(FilterBound::Exclusive(value)),
-        FilterOperation::LessThanEq => upper_bounds.push(FilterBound::Inclusive(value)),
-        FilterOperation::GreaterThanEq => lower_bounds.push(FilterBound::Inclusive(value)),
-        FilterOperation::Unequals => avoid_values.push(value),
     }
 }
 
@@ -320,3 +439,146 @@ pub(super) fn cut_last_layers(
 
     (last_index + 1, variable_order.len() - last_index - 1)
 }
+
+/// Generator for the part of an execution plan that derives the facts of the head atoms
+/// of a rule with existential variables from the frontier matches that receive new nulls.
+#[derive(Debug)]
+pub(super) struct ExistentialHeadGenerator {
+    predicate_to_instructions: HashMap<Identifier, Vec<HeadInstruction>>,
+    predicate_to_full_existential: HashMap<Identifier, bool>,
+}
+
+impl ExistentialHeadGenerator {
+    /// Create a new [`ExistentialHeadGenerator`] for the head of the given rule.
+    pub fn initialize(rule: &ChaseRule, analysis: &RuleAnalysis) -> Self {
+        let mut predicate_to_instructions = HashMap::<Identifier, Vec<HeadInstruction>>::new();
+        let mut predicate_to_full_existential = HashMap::<Identifier, bool>::new();
+
+        for head_atom in rule.head() {
+            let is_existential = head_atom
+                .terms()
+                .iter()
+                .any(|t| matches!(t, Term::Variable(Variable::Existential(_))));
+
+            let instructions = predicate_to_instructions
+                .entry(head_atom.predicate())
+                .or_default();
+            instructions.push(head_instruction_from_atom(head_atom, analysis));
+
+            let is_full_existential = predicate_to_full_existential
+                .entry(head_atom.predicate())
+                .or_insert(true);
+            *is_full_existential &= is_existential;
+        }
+
+        Self {
+            predicate_to_instructions,
+            predicate_to_full_existential,
+        }
+    }
+
+    /// Add the plan for deriving the head atoms to `current_plan`.
+    ///
+    /// The node `node_matches_nulls` contains the frontier matches in the given order,
+    /// followed by a fresh null for each existential variable.
+    pub fn add_plan_head(
+        &self,
+        table_manager: &TableManager,
+        current_plan: &mut SubtableExecutionPlan,
+        node_matches_nulls: ExecutionNodeRef,
+        frontier_order: VariableOrder,
+        analysis: &RuleAnalysis,
+        step: usize,
+    ) {
+        let variables_matches_nulls =
+            append_existential_at_the_end(frontier_order, &analysis.head_variables);
+
+        // For each head atom project from the matches with nulls
+        for (predicate, head_instructions) in self.predicate_to_instructions.iter() {
+            let mut final_head_nodes =
+                Vec::<ExecutionNodeRef>::with_capacity(head_instructions.len());
+
+            for head_instruction in head_instructions {
+                let head_binding =
+                    atom_binding(&head_instruction.reduced_atom, &variables_matches_nulls);
+                let head_reordering = ProjectReordering::from_vector(
+                    head_binding.clone(),
+                    variables_matches_nulls.len(),
+                );
+
+                let project_node = current_plan
+                    .plan_mut()
+                    .project(node_matches_nulls.clone(), head_reordering);
+                let append_node = current_plan
+                    .plan_mut()
+                    .append_columns(project_node, head_instruction.append_instructions.clone());
+
+                final_head_nodes.push(append_node);
+            }
+
+            let new_tables_union = current_plan.plan_mut().union(final_head_nodes);
+
+            // We just pick the default order
+            // TODO: Is there a better pick?
+            let result_order = ColumnOrder::default();
+            let result_table_name =
+                table_manager.generate_table_name(predicate.clone(), &result_order, step);
+            let result_subtable_id = SubtableIdentifier::new(predicate.clone(), step);
+
+            if *self.predicate_to_full_existential.get(predicate).unwrap()
+                || !current_plan.eliminates_duplicates()
+            {
+                // Since every new entry will contain a fresh null no duplcate elimination is needed.
+                // Otherwise it may also be delayed to a later application of the rule.
+                current_plan.add_permanent_table(
+                    new_tables_union,
+                    "Head (Existential): Result Project",
+                    &result_table_name,
+                    result_subtable_id,
+                );
+            } else {
+                // Duplicate elimination for atoms thats do not contain existential variables
+                // Same as in plan_head_datalog
+                if current_plan.counts_derived_facts() {
+                    current_plan.add_derived_facts_table(
+                        new_tables_union.clone(),
+                        "Head (Existential): Derived Facts",
+                        predicate.clone(),
+                    );
+                }
+
+                let old_tables: Vec<TableId> =
+                    table_manager.tables_in_range(predicate.clone(), &(0..step));
+                let old_table_nodes: Vec<ExecutionNodeRef> = old_tables
+                    .into_iter()
+                    .map(|id| current_plan.plan_mut().fetch_existing(id))
+                    .collect();
+                let old_table_union = current_plan.plan_mut().union(old_table_nodes);
+
+                let remove_duplicate_node = current_plan
+                    .plan_mut()
+                    .minus(new_tables_union, old_table_union);
+
+                current_plan.add_permanent_table(
+                    remove_duplicate_node,
+                    "Head (Existential): Result Project",
+                    &result_table_name,
+                    result_subtable_id,
+                );
+            }
+        }
+    }
+}
+
+fn append_existential_at_the_end(
+    mut order: VariableOrder,
+    variables: &HashSet<Variable>,
+) -> VariableOrder {
+    for variable in variables {
+        if matches!(variable, Variable::Existential(_)) {
+            order.push(variable.clone());
+        }
+    }
+
+    order
+}
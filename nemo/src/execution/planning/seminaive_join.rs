@@ -1,6 +1,6 @@
 //! Defines the function that performs a seminaive join over a list of atoms.
 
-use std::collections::HashMap;
+use std::{cmp::Ordering, collections::HashMap, ops::Range};
 
 use nemo_physical::{
     management::execution_plan::{ExecutionNodeRef, ExecutionPlan},
@@ -8,12 +8,15 @@ use nemo_physical::{
 };
 
 use crate::{
-    model::{chase_model::ChaseAtom, Filter, PrimitiveType, Variable},
+    error::Error,
+    model::{chase_model::ChaseAtom, Filter, PrimitiveType, Term, Variable},
     program_analysis::variable_order::VariableOrder,
     table_manager::TableManager,
 };
 
-use super::plan_util::{atom_binding, compute_filters, subplan_union};
+use super::plan_util::{
+    atom_binding, atom_equal_classes, compute_filters, has_unknown_constant, subplan_union,
+};
 
 /// Generator for creating excution plans for seminaive joins of a fixed set of [`ChaseAtom`]s and [`Filter`]s.
 #[derive(Debug)]
@@ -27,94 +30,258 @@ pub struct SeminaiveJoinGenerator {
 }
 
 impl SeminaiveJoinGenerator {
-    /// Compute the appropriate execution tree to perform the join with the seminaive evaluation strategy.
-    /// Note: The [`VariableOrder`] must only contain variables that occur in the `atoms` parameter.
-    pub(crate) fn seminaive_join(
+    /// Divide the atoms into those which did not receive new elements since the last rule application
+    /// and those which did, returned in this order.
+    /// Returns `None` if the join is empty, since an atom has no tables or none received new elements.
+    fn divide_atoms(
         &self,
-        plan: &mut ExecutionPlan,
         table_manager: &TableManager,
         step_last_applied: usize,
-        current_step_number: usize,
-        variable_order: &VariableOrder,
-    ) -> ExecutionNodeRef {
-        // We divide the atoms of the body into two parts:
-        //    * Main: Those atoms who received new elements since the last rule application
-        //    * Side: Those atoms which did not receive new elements since the last rule application
+    ) -> Option<(Vec<&ChaseAtom>, Vec<&ChaseAtom>)> {
         let mut side_atoms = Vec::new();
         let mut main_atoms = Vec::new();
 
-        let mut side_binding = Vec::new();
-        let mut main_binding = Vec::new();
-
         for atom in &self.atoms {
-            let last_step = if let Some(step) = table_manager.last_step(atom.predicate()) {
-                step
+            if table_manager.last_step(atom.predicate())? < step_last_applied {
+                side_atoms.push(atom);
             } else {
-                return plan.union_empty();
-            };
+                main_atoms.push(atom);
+            }
+        }
 
-            let binding = atom_binding(atom, variable_order);
+        if main_atoms.is_empty() {
+            return None;
+        }
 
-            if last_step < step_last_applied {
-                side_binding.push(binding);
-                side_atoms.push(atom.predicate());
-            } else {
-                main_binding.push(binding);
-                main_atoms.push(atom.predicate());
+        Some((side_atoms, main_atoms))
+    }
+
+    /// Return the ranges of steps whose tables are joined for each atom
+    /// in the part of the seminaive join in which the main atom with the given index
+    /// is restricted to the tables that are new since the last rule application.
+    /// The ranges of the side atoms come first.
+    fn subtable_ranges(
+        num_side_atoms: usize,
+        num_main_atoms: usize,
+        atom_index: usize,
+        step_last_applied: usize,
+        current_step_number: usize,
+    ) -> Vec<Range<usize>> {
+        // For every atom that did not receive any update since the last rule application take all available elements
+        let side_ranges = (0..num_side_atoms).map(|_| 0..step_last_applied);
+
+        let main_ranges = (0..num_main_atoms).map(|index| match index.cmp(&atom_index) {
+            // For every atom before the mid point we take all the tables until the current `rule_step`
+            Ordering::Less => 0..current_step_number,
+            // For the middle atom we only take the new tables
+            Ordering::Equal => step_last_applied..current_step_number,
+            // For every atom past the mid point we take only the old tables
+            Ordering::Greater => 0..step_last_applied,
+        });
+
+        side_ranges.chain(main_ranges).collect()
+    }
+
+    /// Return whether the filters require a variable to be equal to a constant
+    /// which does not occur in any table of the atoms, so that the join has no results.
+    ///
+    /// This is only known if all tables of the atoms are available in memory,
+    /// since tables are loaded from their sources only when they are needed.
+    fn has_unknown_constant(&self, table_manager: &TableManager) -> bool {
+        self.atoms
+            .iter()
+            .all(|atom| table_manager.is_in_memory(&atom.predicate()))
+            && has_unknown_constant(
+                &self.filters,
+                &self.variable_types,
+                &table_manager.get_dict(),
+            )
+    }
+
+    /// Return whether the seminaive join might have any results,
+    /// according to the constants in its filters and the key filters of the tables of atoms
+    /// which have the same variable in their first position.
+    ///
+    /// Key filters are not taken into account if they are disabled, see [`TableManager::key_filter`].
+    pub(crate) fn may_have_matches(
+        &self,
+        table_manager: &mut TableManager,
+        step_last_applied: usize,
+        current_step_number: usize,
+    ) -> bool {
+        if self.has_unknown_constant(table_manager) {
+            return false;
+        }
+
+        if !table_manager.uses_key_filters() {
+            return true;
+        }
+
+        let Some((side_atoms, main_atoms)) = self.divide_atoms(table_manager, step_last_applied)
+        else {
+            return false;
+        };
+        let atoms = side_atoms.into_iter().chain(main_atoms.iter().copied());
+
+        // Indices of the atoms which share their first variable with a previous atom,
+        // together with the index of the first such atom
+        let mut first_atom = HashMap::<&Variable, usize>::new();
+        let mut shared_keys = Vec::new();
+        for (index, atom) in atoms.clone().enumerate() {
+            if let Some(Term::Variable(variable)) = atom.terms().first() {
+                let first = *first_atom.entry(variable).or_insert(index);
+                if first != index {
+                    shared_keys.push((first, index));
+                }
             }
         }
 
-        if main_atoms.is_empty() {
-            return plan.union_empty();
+        if shared_keys.is_empty() {
+            return true;
+        }
+
+        let atoms = atoms.collect::<Vec<_>>();
+        (0..main_atoms.len()).any(|atom_index| {
+            let ranges = Self::subtable_ranges(
+                atoms.len() - main_atoms.len(),
+                main_atoms.len(),
+                atom_index,
+                step_last_applied,
+                current_step_number,
+            );
+
+            shared_keys.iter().all(|&(first, other)| {
+                let first_filter =
+                    table_manager.key_filter(&atoms[first].predicate(), &ranges[first]);
+                let other_filter =
+                    table_manager.key_filter(&atoms[other].predicate(), &ranges[other]);
+
+                match (first_filter, other_filter) {
+                    (Some(first_filter), Some(other_filter)) => {
+                        !first_filter.is_disjoint(&other_filter)
+                    }
+                    _ => true,
+                }
+            })
+        })
+    }
+
+    /// Return the estimated number of results of the seminaive join,
+    /// based on the [`TableStatistics`](crate::table_manager::TableStatistics) of the tables of the atoms.
+    ///
+    /// For each variable, the values in the atom with the fewest distinct values are assumed
+    /// to occur in all other atoms containing the variable,
+    /// and the values of different variables are assumed to be independent.
+    /// Filters are not taken into account, unless they can not be satisfied.
+    pub(crate) fn estimate_matches(
+        &self,
+        table_manager: &mut TableManager,
+        step_last_applied: usize,
+        current_step_number: usize,
+    ) -> Result<f64, Error> {
+        if self.has_unknown_constant(table_manager) {
+            return Ok(0.0);
+        }
+
+        let Some((side_atoms, main_atoms)) = self.divide_atoms(table_manager, step_last_applied)
+        else {
+            return Ok(0.0);
+        };
+        let atoms = side_atoms
+            .iter()
+            .chain(main_atoms.iter())
+            .collect::<Vec<_>>();
+
+        let mut result = 0.0;
+        'variants: for atom_index in 0..main_atoms.len() {
+            let ranges = Self::subtable_ranges(
+                side_atoms.len(),
+                main_atoms.len(),
+                atom_index,
+                step_last_applied,
+                current_step_number,
+            );
+
+            let mut estimate = 1.0;
+            let mut distinct_values = HashMap::<&Variable, Vec<f64>>::new();
+            for (atom, range) in atoms.iter().zip(&ranges) {
+                let Some(statistics) = table_manager.statistics(&atom.predicate(), range)? else {
+                    continue 'variants;
+                };
+
+                estimate *= statistics.rows as f64;
+                for (column, term) in atom.terms().iter().enumerate() {
+                    if let Term::Variable(variable) = term {
+                        distinct_values
+                            .entry(variable)
+                            .or_default()
+                            .push(statistics.distinct_values(column));
+                    }
+                }
+            }
+
+            for mut counts in distinct_values.into_values() {
+                counts.sort_by(f64::total_cmp);
+                estimate /= counts.iter().skip(1).product::<f64>();
+            }
+
+            result += estimate;
         }
 
+        Ok(result)
+    }
+
+    /// Compute the appropriate execution tree to perform the join with the seminaive evaluation strategy.
+    /// Note: The [`VariableOrder`] must only contain variables that occur in the `atoms` parameter.
+    pub(crate) fn seminaive_join(
+        &self,
+        plan: &mut ExecutionPlan,
+        table_manager: &TableManager,
+        step_last_applied: usize,
+        current_step_number: usize,
+        variable_order: &VariableOrder,
+    ) -> ExecutionNodeRef {
+        // We divide the atoms of the body into two parts:
+        //    * Main: Those atoms who received new elements since the last rule application
+        //    * Side: Those atoms which did not receive new elements since the last rule application
+        let Some((side_atoms, main_atoms)) = self.divide_atoms(table_manager, step_last_applied)
+        else {
+            return plan.union_empty();
+        };
+
         // We then combine the bindings into one
-        let join_binding: JoinBindings = side_binding.into_iter().chain(main_binding).collect();
+        let atoms = side_atoms
+            .iter()
+            .chain(main_atoms.iter())
+            .collect::<Vec<_>>();
+        let join_binding: JoinBindings = atoms
+            .iter()
+            .map(|atom| atom_binding(atom, variable_order))
+            .collect();
+        // Repeated variables within one atom are already enforced on the table of the atom
+        let equal_classes = atoms
+            .iter()
+            .map(|atom| atom_equal_classes(atom, &self.filters))
+            .collect::<Vec<_>>();
 
         // Now we can finally calculate the execution tree
         let mut seminaive_union = plan.union_empty();
         for atom_index in 0..main_atoms.len() {
             let mut seminaive_node = plan.join_empty(join_binding.clone());
 
-            // For every atom that did not receive any update since the last rule application take all available elements
-            for predicate in side_atoms.iter() {
-                let subnode = subplan_union(
-                    plan,
-                    table_manager,
-                    predicate.clone(),
-                    &(0..step_last_applied),
-                );
-                seminaive_node.add_subnode(subnode);
-            }
-
-            // For every atom before the mid point we take all the tables until the current `rule_step`
-            for predicate in main_atoms.iter().take(atom_index) {
-                let subnode = subplan_union(
-                    plan,
-                    table_manager,
-                    predicate.clone(),
-                    &(0..current_step_number),
-                );
-                seminaive_node.add_subnode(subnode);
-            }
-
-            // For the middle atom we only take the new tables
-            let midnode = subplan_union(
-                plan,
-                table_manager,
-                main_atoms[atom_index].clone(),
-                &(step_last_applied..current_step_number),
+            let ranges = Self::subtable_ranges(
+                side_atoms.len(),
+                main_atoms.len(),
+                atom_index,
+                step_last_applied,
+                current_step_number,
             );
-            seminaive_node.add_subnode(midnode);
 
-            // For every atom past the mid point we take only the old tables
-            for predicate in main_atoms.iter().skip(atom_index + 1) {
-                let subnode = subplan_union(
-                    plan,
-                    table_manager,
-                    predicate.clone(),
-                    &(0..step_last_applied),
-                );
+            for ((atom, range), classes) in atoms.iter().zip(ranges).zip(&equal_classes) {
+                let mut subnode = subplan_union(plan, table_manager, atom.predicate(), &range);
+                if !classes.is_empty() {
+                    subnode = plan.select_equal(subnode, classes.clone());
+                }
                 seminaive_node.add_subnode(subnode);
             }
 
@@ -122,8 +289,12 @@ impl SeminaiveJoinGenerator {
         }
 
         // Apply filters
-        let (filter_classes, filter_assignments) =
-            compute_filters(variable_order, &self.filters, &self.variable_types);
+        let (filter_classes, filter_assignments) = compute_filters(
+            variable_order,
+            &self.filters,
+            &self.variable_types,
+            &table_manager.get_dict(),
+        );
 
         let node_select_value = plan.select_value(seminaive_union, filter_assignments);
 
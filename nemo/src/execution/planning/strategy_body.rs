@@ -4,6 +4,7 @@
 use nemo_physical::management::execution_plan::ExecutionNodeRef;
 
 use crate::{
+    error::Error,
     execution::execution_engine::RuleInfo,
     program_analysis::variable_order::VariableOrder,
     table_manager::{SubtableExecutionPlan, TableManager},
@@ -23,4 +24,21 @@ pub trait BodyStrategy: Debug {
         variable_order: &mut VariableOrder,
         step_number: usize,
     ) -> ExecutionNodeRef;
+
+    /// Return whether the body might have any matches.
+    /// If this returns `false`, the rule application can be skipped.
+    fn may_have_matches(
+        &self,
+        table_manager: &mut TableManager,
+        rule_info: &RuleInfo,
+        step_number: usize,
+    ) -> bool;
+
+    /// Return the estimated number of matches of the body.
+    fn estimate_matches(
+        &self,
+        table_manager: &mut TableManager,
+        rule_info: &RuleInfo,
+        step_number: usize,
+    ) -> Result<f64, Error>;
 }
@@ -0,0 +1,583 @@
+//! Answering queries over the tables of an [`ExecutionEngine`][super::ExecutionEngine].
+//!
+//! A query is given as an atom, whose variables may be bound by the facts of the queried predicate.
+//! Since applications often ask the same queries repeatedly (e.g. during interactive exploration),
+//! the plans of recent queries and the results of recent small queries are cached.
+
+use std::collections::{BTreeMap, HashMap};
+
+use nemo_physical::tabular::operations::{triescan_select::SelectEqualClasses, ValueAssignment};
+use thiserror::Error;
+
+use crate::model::{
+    types::primitive_logical_value::PrimitiveLogicalValueT, Atom, Identifier, PrimitiveType, Term,
+    TermOperation, Variable,
+};
+
+/// Queries with at most this many results are cached.
+const QUERY_CACHE_MAX_ROWS: usize = 1024;
+/// Maximal number of cached query plans
+const QUERY_CACHE_MAX_PLANS: usize = 1024;
+/// Maximal number of cached query results
+const QUERY_CACHE_MAX_RESULTS: usize = 256;
+
+/// Errors that can occur while answering a query.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum QueryError {
+    /// The queried predicate does not occur in the program.
+    #[error("The queried predicate \"{0}\" does not occur in the program.")]
+    UnknownPredicate(Identifier),
+    /// The arity of the query does not match the arity of the predicate.
+    #[error(
+        "The query uses predicate \"{predicate}\" with arity {used}, but its arity is {arity}."
+    )]
+    ArityMismatch {
+        /// The queried predicate
+        predicate: Identifier,
+        /// The arity of the predicate
+        arity: usize,
+        /// The arity used in the query
+        used: usize,
+    },
+    /// The query contains a term that is neither a constant nor a universal variable.
+    #[error("Queries may only contain constants and universal variables, found \"{0}\".")]
+    UnsupportedTerm(String),
+    /// A parameter of a prepared query does not occur in the query.
+    #[error("The parameter \"{0}\" does not occur in the query.")]
+    UnknownParameter(Variable),
+    /// A prepared query was executed with the wrong number of values.
+    #[error("The prepared query expects {expected} values, but {given} were given.")]
+    ParameterMismatch {
+        /// The number of parameters of the query
+        expected: usize,
+        /// The number of given values
+        given: usize,
+    },
+}
+
+/// Term of a [`QueryKey`].
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+enum QueryTerm {
+    /// Variable, identified by the position of its first occurrence
+    Variable(usize),
+    /// Constant
+    Constant(Term),
+}
+
+/// Normalized form of a query atom,
+/// such that queries that only differ in the names of their variables are treated the same.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) struct QueryKey {
+    predicate: Identifier,
+    terms: Vec<QueryTerm>,
+}
+
+impl QueryKey {
+    /// Create a [`QueryKey`] from the given query atom.
+    pub(crate) fn from_atom(atom: &Atom) -> Result<Self, QueryError> {
+        let mut variables = HashMap::<&Variable, usize>::new();
+        let mut terms = Vec::new();
+
+        for (position, term_tree) in atom.term_trees().iter().enumerate() {
+            let term = match term_tree.operation() {
+                TermOperation::Term(term) => term,
+                _ => return Err(QueryError::UnsupportedTerm(format!("{term_tree:?}"))),
+            };
+
+            let query_term = match term {
+                Term::Variable(variable @ Variable::Universal(_)) => {
+                    QueryTerm::Variable(*variables.entry(variable).or_insert(position))
+                }
+                Term::Variable(Variable::Existential(_)) | Term::Aggregate(_) => {
+                    return Err(QueryError::UnsupportedTerm(term.to_string()))
+                }
+                _ => QueryTerm::Constant(term.clone()),
+            };
+
+            terms.push(query_term);
+        }
+
+        Ok(Self {
+            predicate: atom.predicate(),
+            terms,
+        })
+    }
+
+    /// Return the queried predicate.
+    pub(crate) fn predicate(&self) -> &Identifier {
+        &self.predicate
+    }
+
+    /// Return the arity of the query.
+    pub(crate) fn arity(&self) -> usize {
+        self.terms.len()
+    }
+}
+
+/// Restrictions that need to be applied to the table of the queried predicate
+/// in order to obtain the answers to a query.
+#[derive(Debug, Clone)]
+pub(crate) struct QueryPlan {
+    /// Classes of columns that must contain the same value
+    pub(crate) equal_classes: SelectEqualClasses,
+    /// Restriction of columns to constant values
+    pub(crate) assignments: HashMap<usize, ValueAssignment>,
+    /// Whether the query has no answers regardless of the facts in the table,
+    /// e.g. because a constant cannot occur in a column of the given type
+    pub(crate) is_empty: bool,
+}
+
+impl QueryPlan {
+    /// Compute the [`QueryPlan`] for the given query over a predicate with the given types.
+    fn new(key: &QueryKey, types: &[PrimitiveType]) -> Self {
+        let mut equal_classes = BTreeMap::<usize, Vec<usize>>::new();
+        let mut assignments = HashMap::<usize, ValueAssignment>::new();
+        let mut is_empty = false;
+
+        for (position, (term, logical_type)) in key.terms.iter().zip(types).enumerate() {
+            match term {
+                QueryTerm::Variable(first) => {
+                    equal_classes.entry(*first).or_default().push(position)
+                }
+                QueryTerm::Constant(constant) => {
+                    match logical_type.ground_term_to_data_value_t(constant.clone()) {
+                        Ok(value) => {
+                            assignments.insert(position, ValueAssignment::equal(value));
+                        }
+                        Err(_) => is_empty = true,
+                    }
+                }
+            }
+        }
+
+        Self {
+            equal_classes: equal_classes
+                .into_values()
+                .filter(|class| class.len() > 1)
+                .collect(),
+            assignments,
+            is_empty,
+        }
+    }
+
+    /// Return `true` if the query asks for all facts of the predicate.
+    pub(crate) fn is_trivial(&self) -> bool {
+        !self.is_empty && self.equal_classes.is_empty() && self.assignments.is_empty()
+    }
+}
+
+/// A query whose parameters can be bound to different constants every time it is executed,
+/// see [`ExecutionEngine::prepare`][super::ExecutionEngine::prepare].
+///
+/// The plan of the query is only computed once,
+/// which makes this suitable for issuing a large number of similar queries, e.g. point lookups.
+#[derive(Debug, Clone)]
+pub struct PreparedQuery {
+    /// The queried predicate
+    predicate: Identifier,
+    /// Logical types of the columns of the queried predicate
+    types: Vec<PrimitiveType>,
+    /// For each parameter, the positions in the query at which it occurs
+    parameter_positions: Vec<Vec<usize>>,
+    /// Restrictions that are independent of the values of the parameters
+    plan: QueryPlan,
+}
+
+impl PreparedQuery {
+    /// Prepare the given query, where the given parameters are variables of the query.
+    pub(crate) fn new(
+        atom: &Atom,
+        parameters: &[Variable],
+        types: Vec<PrimitiveType>,
+    ) -> Result<Self, QueryError> {
+        let key = QueryKey::from_atom(atom)?;
+
+        let parameter_positions = parameters
+            .iter()
+            .map(|parameter| {
+                let positions: Vec<usize> = atom
+                    .term_trees()
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, term_tree)| {
+                        matches!(term_tree.operation(), TermOperation::Term(Term::Variable(variable)) if variable == parameter)
+                    })
+                    .map(|(position, _)| position)
+                    .collect();
+
+                if positions.is_empty() {
+                    Err(QueryError::UnknownParameter(parameter.clone()))
+                } else {
+                    Ok(positions)
+                }
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // Columns of parameters are restricted to the bound value,
+        // so they do not need to be compared to each other
+        let mut plan = QueryPlan::new(&key, &types);
+        plan.equal_classes.retain(|class| {
+            !parameter_positions
+                .iter()
+                .any(|positions| positions.contains(&class[0]))
+        });
+
+        Ok(Self {
+            predicate: key.predicate,
+            types,
+            parameter_positions,
+            plan,
+        })
+    }
+
+    /// Return the queried predicate.
+    pub fn predicate(&self) -> &Identifier {
+        &self.predicate
+    }
+
+    /// Return the number of parameters of this query.
+    pub fn num_parameters(&self) -> usize {
+        self.parameter_positions.len()
+    }
+
+    /// Return the logical types of the columns of the queried predicate.
+    pub(crate) fn types(&self) -> &[PrimitiveType] {
+        &self.types
+    }
+
+    /// Compute the [`QueryPlan`] for the given values of the parameters.
+    pub(crate) fn bind(&self, values: &[Term]) -> Result<QueryPlan, QueryError> {
+        if values.len() != self.num_parameters() {
+            return Err(QueryError::ParameterMismatch {
+                expected: self.num_parameters(),
+                given: values.len(),
+            });
+        }
+
+        let mut plan = self.plan.clone();
+
+        for (positions, value) in self.parameter_positions.iter().zip(values) {
+            for &position in positions {
+                match self.types[position].ground_term_to_data_value_t(value.clone()) {
+                    Ok(value) => {
+                        plan.assignments
+                            .insert(position, ValueAssignment::equal(value));
+                    }
+                    Err(_) => plan.is_empty = true,
+                }
+            }
+        }
+
+        Ok(plan)
+    }
+}
+
+/// Map with a limited number of entries,
+/// which removes the least recently used entry when a new entry is added to a full map.
+#[derive(Debug, Clone)]
+struct LruMap<K, V> {
+    /// Entries together with the time of their last use
+    entries: BTreeMap<K, (V, u64)>,
+    /// Maximal number of entries
+    capacity: usize,
+    /// Number of uses of the map so far
+    clock: u64,
+}
+
+impl<K: Ord + Clone, V> LruMap<K, V> {
+    /// Create a new empty [`LruMap`] holding at most `capacity` entries.
+    fn new(capacity: usize) -> Self {
+        Self {
+            entries: BTreeMap::new(),
+            capacity,
+            clock: 0,
+        }
+    }
+
+    /// Return the value of the given key, marking it as used.
+    fn get(&mut self, key: &K) -> Option<&V> {
+        self.clock += 1;
+
+        let (value, last_use) = self.entries.get_mut(key)?;
+        *last_use = self.clock;
+
+        Some(value)
+    }
+
+    /// Return the value of the given key, marking it as used,
+    /// or insert the value computed by the given function if there is none.
+    fn get_or_insert_with<F: FnOnce() -> V>(&mut self, key: &K, function: F) -> &V {
+        if !self.entries.contains_key(key) {
+            self.insert(key.clone(), function());
+        }
+
+        self.get(key)
+            .expect("The entry has been inserted if it did not exist.")
+    }
+
+    /// Insert a value for the given key, removing the least recently used entry if the map is full.
+    fn insert(&mut self, key: K, value: V) {
+        self.clock += 1;
+
+        if self.entries.len() >= self.capacity && !self.entries.contains_key(&key) {
+            let least_recent = self
+                .entries
+                .iter()
+                .min_by_key(|(_, (_, last_use))| *last_use)
+                .map(|(key, _)| key.clone());
+
+            if let Some(least_recent) = least_recent {
+                self.entries.remove(&least_recent);
+            }
+        }
+
+        self.entries.insert(key, (value, self.clock));
+    }
+
+    /// Remove all entries.
+    fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    /// Return the number of entries.
+    #[cfg(test)]
+    fn len(&self) -> usize {
+        self.entries.len()
+    }
+}
+
+/// Cache for the plans and results of queries.
+///
+/// Only the most recently used plans and results are kept,
+/// so that the cache does not grow without bounds if many different queries are asked.
+#[derive(Debug, Clone)]
+pub(crate) struct QueryCache {
+    /// Plans of recently answered queries
+    plans: LruMap<QueryKey, QueryPlan>,
+    /// Results of recently answered queries with few answers
+    results: LruMap<QueryKey, Vec<Vec<PrimitiveLogicalValueT>>>,
+    /// Step of the engine for which the results are valid
+    step: usize,
+}
+
+impl Default for QueryCache {
+    fn default() -> Self {
+        Self {
+            plans: LruMap::new(QUERY_CACHE_MAX_PLANS),
+            results: LruMap::new(QUERY_CACHE_MAX_RESULTS),
+            step: 0,
+        }
+    }
+}
+
+impl QueryCache {
+    /// Return the plan for the given query, computing it if it is not already cached.
+    pub(crate) fn plan(&mut self, key: &QueryKey, types: &[PrimitiveType]) -> &QueryPlan {
+        self.plans
+            .get_or_insert_with(key, || QueryPlan::new(key, types))
+    }
+
+    /// Return the cached results of the given query,
+    /// if they were computed in the given step of the engine.
+    pub(crate) fn result(
+        &mut self,
+        key: &QueryKey,
+        step: usize,
+    ) -> Option<&Vec<Vec<PrimitiveLogicalValueT>>> {
+        if self.step != step {
+            self.invalidate_results();
+            self.step = step;
+        }
+
+        self.results.get(key)
+    }
+
+    /// Cache the results of the given query if it has few answers.
+    pub(crate) fn store_result(
+        &mut self,
+        key: QueryKey,
+        step: usize,
+        rows: &[Vec<PrimitiveLogicalValueT>],
+    ) {
+        if self.step == step && rows.len() <= QUERY_CACHE_MAX_ROWS {
+            self.results.insert(key, rows.to_vec());
+        }
+    }
+
+    /// Remove all cached results, e.g. because the tables of the engine have been updated.
+    /// The cached plans remain valid.
+    pub(crate) fn invalidate_results(&mut self) {
+        self.results.clear();
+    }
+
+    /// Return the number of cached results.
+    #[cfg(test)]
+    pub(crate) fn num_results(&self) -> usize {
+        self.results.len()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        execution::{
+            test_util::{initialized_engine, materialized_engine, sorted_strings},
+            DefaultExecutionEngine,
+        },
+        io::parser::parse_atom,
+        model::{Identifier, Term, Variable},
+    };
+
+    use super::{LruMap, PreparedQuery, QueryError};
+
+    fn query(engine: &mut DefaultExecutionEngine, query: &str) -> Vec<Vec<String>> {
+        sorted_strings(engine.query(&parse_atom(query).unwrap()).unwrap())
+    }
+
+    fn run(
+        engine: &mut DefaultExecutionEngine,
+        query: &PreparedQuery,
+        values: &[Term],
+    ) -> Vec<Vec<String>> {
+        sorted_strings(engine.execute_prepared(query, values).unwrap())
+    }
+
+    #[test]
+    fn answers_queries() {
+        let mut engine = materialized_engine(
+            "edge(a, b) . edge(b, c) . edge(c, c) . edge(c, d) .\n\
+             path(?x, ?y) :- edge(?x, ?y) .\n\
+             path(?x, ?z) :- path(?x, ?y), edge(?y, ?z) .\n",
+        );
+
+        assert_eq!(query(&mut engine, "edge(?x, ?y)").len(), 4);
+        assert_eq!(
+            query(&mut engine, "path(b, ?y)"),
+            vec![vec!["b", "c"], vec!["b", "d"]]
+        );
+        assert_eq!(query(&mut engine, "edge(?x, ?x)"), vec![vec!["c", "c"]]);
+        assert_eq!(query(&mut engine, "path(d, ?y)"), Vec::<Vec<String>>::new());
+        assert_eq!(query(&mut engine, "path(a, d)"), vec![vec!["a", "d"]]);
+        assert_eq!(
+            query(&mut engine, "path(42, ?y)"),
+            Vec::<Vec<String>>::new()
+        );
+
+        assert!(matches!(
+            engine.query(&parse_atom("unknown(?x)").unwrap()),
+            Err(crate::error::Error::QueryError(
+                QueryError::UnknownPredicate(_)
+            ))
+        ));
+        assert!(matches!(
+            engine.query(&parse_atom("edge(?x)").unwrap()),
+            Err(crate::error::Error::QueryError(
+                QueryError::ArityMismatch { .. }
+            ))
+        ));
+    }
+
+    #[test]
+    fn caches_results() {
+        let mut engine = materialized_engine("edge(a, b) . edge(b, c) .\n");
+
+        assert_eq!(query(&mut engine, "edge(a, ?y)"), vec![vec!["a", "b"]]);
+        assert_eq!(engine.query_cache.num_results(), 1);
+
+        // Queries that only differ in the names of their variables share the cache entry
+        assert_eq!(query(&mut engine, "edge(a, ?z)"), vec![vec!["a", "b"]]);
+        assert_eq!(engine.query_cache.num_results(), 1);
+
+        assert_eq!(query(&mut engine, "edge(?x, c)"), vec![vec!["b", "c"]]);
+        assert_eq!(engine.query_cache.num_results(), 2);
+    }
+
+    #[test]
+    fn evicts_least_recently_used_entries() {
+        let mut map = LruMap::new(2);
+        map.insert(1, "a");
+        map.insert(2, "b");
+        assert_eq!(map.get(&1), Some(&"a"));
+
+        map.insert(3, "c");
+        assert_eq!(map.len(), 2);
+        assert_eq!(map.get(&2), None);
+        assert_eq!(map.get(&1), Some(&"a"));
+
+        assert_eq!(map.get_or_insert_with(&4, || "d"), &"d");
+        assert_eq!(map.get_or_insert_with(&1, || "e"), &"a");
+        assert_eq!(map.get(&3), None);
+    }
+
+    #[test]
+    fn invalidates_results_after_reasoning() {
+        let mut engine = initialized_engine("edge(a, b) .\nnode(?x) :- edge(?x, ?y) .\n");
+
+        assert_eq!(query(&mut engine, "node(a)"), Vec::<Vec<String>>::new());
+
+        engine.execute().unwrap();
+        assert_eq!(query(&mut engine, "node(a)"), vec![vec!["a"]]);
+    }
+
+    #[test]
+    fn prepared_queries() {
+        let mut engine = materialized_engine(
+            "edge(a, b) . edge(b, c) . edge(c, c) . edge(c, d) .\n\
+             path(?x, ?y) :- edge(?x, ?y) .\n\
+             path(?x, ?z) :- path(?x, ?y), edge(?y, ?z) .\n",
+        );
+
+        let x = Variable::Universal(Identifier("x".to_string()));
+        let y = Variable::Universal(Identifier("y".to_string()));
+        let constant = |name: &str| Term::Constant(Identifier(name.to_string()));
+
+        let successors = engine
+            .prepare(
+                &parse_atom("path(?x, ?y)").unwrap(),
+                std::slice::from_ref(&x),
+            )
+            .unwrap();
+        assert_eq!(successors.num_parameters(), 1);
+
+        assert_eq!(run(&mut engine, &successors, &[constant("b")]).len(), 2);
+        assert_eq!(run(&mut engine, &successors, &[constant("a")]).len(), 3);
+        assert_eq!(run(&mut engine, &successors, &[constant("d")]).len(), 0);
+
+        let lookup = engine
+            .prepare(&parse_atom("edge(?x, ?y)").unwrap(), &[x.clone(), y])
+            .unwrap();
+        assert_eq!(
+            run(&mut engine, &lookup, &[constant("c"), constant("d")]),
+            vec![vec!["c", "d"]]
+        );
+        assert_eq!(
+            run(&mut engine, &lookup, &[constant("d"), constant("c")]).len(),
+            0
+        );
+
+        let self_loop = engine
+            .prepare(
+                &parse_atom("edge(?x, ?x)").unwrap(),
+                std::slice::from_ref(&x),
+            )
+            .unwrap();
+        assert_eq!(
+            run(&mut engine, &self_loop, &[constant("c")]),
+            vec![vec!["c", "c"]]
+        );
+        assert_eq!(run(&mut engine, &self_loop, &[constant("a")]).len(), 0);
+
+        assert!(matches!(
+            engine.execute_prepared(&self_loop, &[]),
+            Err(crate::error::Error::QueryError(
+                QueryError::ParameterMismatch { .. }
+            ))
+        ));
+        assert!(matches!(
+            engine.prepare(&parse_atom("edge(a, ?y)").unwrap(), &[x]),
+            Err(crate::error::Error::QueryError(
+                QueryError::UnknownParameter(_)
+            ))
+        ));
+    }
+}
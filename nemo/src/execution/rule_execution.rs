@@ -4,17 +4,30 @@ use crate::{
     error::Error,
     model::{chase_model::ChaseRule, Identifier},
     program_analysis::{analysis::RuleAnalysis, variable_order::VariableOrder},
-    table_manager::{SubtableExecutionPlan, TableManager},
+    table_manager::{PlanResults, SubtableExecutionPlan, TableManager},
 };
 
 use super::{
     execution_engine::RuleInfo,
+    function_registry::FunctionRegistry,
     planning::{
         plan_body_seminaive::SeminaiveStrategy, plan_head_datalog::DatalogStrategy,
-        plan_head_restricted::RestrictedChaseStrategy, BodyStrategy, HeadStrategy,
+        plan_head_restricted::RestrictedChaseStrategy, plan_head_skolem::SkolemChaseStrategy,
+        BodyStrategy, HeadStrategy,
     },
 };
 
+/// Variant of the chase used for applying rules with existential variables in the head.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ChaseVariant {
+    /// Rules are only applied to matches whose head is not already satisfied.
+    #[default]
+    Restricted,
+    /// Rules are applied once for every binding of their frontier variables,
+    /// as if the existential variables were replaced by Skolem terms, see [`SkolemChaseStrategy`].
+    Skolem,
+}
+
 /// Object responsible for executing a "normal" rule.
 #[derive(Debug)]
 pub struct RuleExecution {
@@ -26,12 +39,21 @@ pub struct RuleExecution {
 
 impl RuleExecution {
     /// Create new [`RuleExecution`].
-    pub fn initialize(rule: &ChaseRule, analysis: &RuleAnalysis) -> Self {
-        let body_strategy = Box::new(SeminaiveStrategy::initialize(rule, analysis));
-        let head_strategy: Box<dyn HeadStrategy> = if analysis.is_existential {
-            Box::new(RestrictedChaseStrategy::initialize(rule, analysis))
-        } else {
-            Box::new(DatalogStrategy::initialize(rule, analysis))
+    pub fn initialize(
+        rule: &ChaseRule,
+        analysis: &RuleAnalysis,
+        functions: &FunctionRegistry,
+        chase_variant: ChaseVariant,
+    ) -> Self {
+        let body_strategy = Box::new(SeminaiveStrategy::initialize(rule, analysis, functions));
+        let head_strategy: Box<dyn HeadStrategy> = match (analysis.is_existential, chase_variant) {
+            (true, ChaseVariant::Restricted) => {
+                Box::new(RestrictedChaseStrategy::initialize(rule, analysis))
+            }
+            (true, ChaseVariant::Skolem) => {
+                Box::new(SkolemChaseStrategy::initialize(rule, analysis))
+            }
+            (false, _) => Box::new(DatalogStrategy::initialize(rule, analysis)),
         };
         let promising_variable_orders = analysis.promising_variable_orders.clone();
         Self {
@@ -49,7 +71,85 @@ impl RuleExecution {
         rule_info: &RuleInfo,
         step_number: usize,
     ) -> Result<Vec<Identifier>, Error> {
-        log::info!(
+        if !self.may_have_matches(table_manager, rule_info, step_number) {
+            return Ok(Vec::new());
+        }
+
+        let subtable_execution_plan = self.plan(
+            SubtableExecutionPlan::default(),
+            table_manager,
+            rule_info,
+            step_number,
+        );
+
+        let _span = tracing::info_span!("execute_plan").entered();
+        table_manager.execute_plan(subtable_execution_plan)
+    }
+
+    /// Execute the current rule, keeping the matches of its body
+    /// or counting the derived facts as requested by the given (empty) plan.
+    /// Returns the predicates which received new elements
+    /// together with the recorded results, see [`PlanResults`].
+    pub fn execute_recording(
+        &self,
+        subtable_execution_plan: SubtableExecutionPlan,
+        table_manager: &mut TableManager,
+        rule_info: &RuleInfo,
+        step_number: usize,
+    ) -> Result<PlanResults, Error> {
+        if !self.may_have_matches(table_manager, rule_info, step_number) {
+            return Ok(PlanResults::default());
+        }
+
+        let subtable_execution_plan = self.plan(
+            subtable_execution_plan,
+            table_manager,
+            rule_info,
+            step_number,
+        );
+
+        let _span = tracing::info_span!("execute_plan").entered();
+        table_manager.execute_plan_with_results(subtable_execution_plan)
+    }
+
+    /// Return whether the body of the current rule might have any matches,
+    /// logging if the rule application is skipped.
+    fn may_have_matches(
+        &self,
+        table_manager: &mut TableManager,
+        rule_info: &RuleInfo,
+        step_number: usize,
+    ) -> bool {
+        let result = self
+            .body_strategy
+            .may_have_matches(table_manager, rule_info, step_number);
+        if !result {
+            tracing::info!("Skipping rule application, since its body has no matches");
+        }
+
+        result
+    }
+
+    /// Return the estimated number of matches of the body of the current rule.
+    pub fn estimate_matches(
+        &self,
+        table_manager: &mut TableManager,
+        rule_info: &RuleInfo,
+        step_number: usize,
+    ) -> Result<f64, Error> {
+        self.body_strategy
+            .estimate_matches(table_manager, rule_info, step_number)
+    }
+
+    /// Add the body and head of the current rule to the given plan.
+    fn plan(
+        &self,
+        mut subtable_execution_plan: SubtableExecutionPlan,
+        table_manager: &TableManager,
+        rule_info: &RuleInfo,
+        step_number: usize,
+    ) -> SubtableExecutionPlan {
+        tracing::info!(
             "Available orders: {}",
             self.promising_variable_orders.iter().enumerate().fold(
                 "".to_string(),
@@ -61,7 +161,6 @@ impl RuleExecution {
         // TODO: Just because its the first doesn't mean its the best
         let best_variable_order = &mut self.promising_variable_orders[0].clone();
 
-        let mut subtable_execution_plan = SubtableExecutionPlan::default();
         let body_tree = self.body_strategy.add_plan_body(
             table_manager,
             &mut subtable_execution_plan,
@@ -79,6 +178,6 @@ impl RuleExecution {
             step_number,
         );
 
-        table_manager.execute_plan(subtable_execution_plan)
+        subtable_execution_plan
     }
 }
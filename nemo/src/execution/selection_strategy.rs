@@ -2,7 +2,9 @@
 
 pub mod strategy;
 
+pub mod strategy_datalog_first;
 pub mod strategy_graph;
+pub mod strategy_priority;
 pub mod strategy_random;
 pub mod strategy_round_robin;
 pub mod strategy_stratified_negation;
@@ -1,15 +1,76 @@
 //! Contains the trait that defines what constitutes a rule execution strategy.
 
+use std::fmt::Display;
+
 use thiserror::Error;
 
-use crate::{model::chase_model::ChaseRule, program_analysis::analysis::RuleAnalysis};
+use crate::{
+    model::{chase_model::ChaseRule, Identifier},
+    program_analysis::analysis::RuleAnalysis,
+};
+
+/// Rule of a [`NegationCycle`] together with the predicate
+/// by which the next rule of the cycle depends on it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NegationCycleStep {
+    /// Index of the rule in the program
+    pub rule_index: usize,
+    /// Predicates of the rule, given in the form `head :- body`
+    pub rule_predicates: String,
+    /// Predicate derived by the rule and used in the body of the next rule
+    pub predicate: Identifier,
+    /// Whether the predicate is negated in the next rule
+    pub negated: bool,
+}
+
+/// Cycle of rules that depend on each other,
+/// at least one time through a negated predicate.
+///
+/// The last step depends on the first one, which closes the cycle.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NegationCycle {
+    /// The rules of the cycle, where the first one derives a predicate that is negated in the second one
+    pub steps: Vec<NegationCycleStep>,
+}
+
+impl Display for NegationCycle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "The rules of the program are not stratified, since they contain a cycle through negation:"
+        )?;
+
+        for step in &self.steps {
+            let usage = if step.negated { "negated" } else { "used" };
+            writeln!(
+                f,
+                "  rule {} ({}) derives {}, which is {usage} in",
+                step.rule_index, step.rule_predicates, step.predicate
+            )?;
+        }
+
+        let first = &self.steps[0];
+        writeln!(
+            f,
+            "  rule {} ({}).",
+            first.rule_index, first.rule_predicates
+        )?;
+
+        let second = &self.steps[1 % self.steps.len()];
+        write!(
+            f,
+            "Consider splitting {} into a separate predicate for the facts that do not depend on {}, and negating that predicate in rule {} instead.",
+            first.predicate, second.predicate, second.rule_index
+        )
+    }
+}
 
 /// Errors that can occur while creating a strategy.
-#[derive(Error, Debug, Copy, Clone)]
+#[derive(Error, Debug, Clone)]
 pub enum SelectionStrategyError {
     /// Non-Stratifyable
-    #[error("The rules of the program is not stratified.")]
-    NonStratifiedProgram,
+    #[error("{0}")]
+    NonStratifiedProgram(NegationCycle),
 }
 
 /// Trait that defines a strategy for rule execution,
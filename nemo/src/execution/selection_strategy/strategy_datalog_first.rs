@@ -0,0 +1,141 @@
+//! Defines a rule execution strategy which saturates guarded Datalog rules before applying existential rules.
+
+use crate::{
+    model::chase_model::ChaseRule,
+    program_analysis::{
+        analysis::RuleAnalysis,
+        fragment::{is_existential, is_guarded},
+    },
+};
+
+use super::strategy::{RuleSelectionStrategy, SelectionStrategyError};
+
+/// Kind of the rule that was selected last by a [`StrategyDatalogFirst`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Selected {
+    Datalog,
+    Existential,
+}
+
+/// Rules that are applied one after another, until a round is completed without new derivations
+#[derive(Debug, Clone, Default)]
+struct RuleRound {
+    rules: Vec<usize>,
+    position: usize,
+    without_derivation: usize,
+}
+
+impl RuleRound {
+    /// Return whether every rule has been applied without deriving new facts.
+    fn is_saturated(&self) -> bool {
+        self.without_derivation >= self.rules.len()
+    }
+
+    /// Return the next rule of the round.
+    fn next(&mut self) -> usize {
+        let rule = self.rules[self.position];
+        self.position = (self.position + 1) % self.rules.len();
+
+        rule
+    }
+}
+
+/// Rules of a [`StrategyDatalogFirst`] together with the state of their evaluation
+#[derive(Debug, Clone)]
+enum Evaluation<SubStrategy> {
+    /// Rules that are applied Datalog-first
+    DatalogFirst {
+        /// Rules without existential variables
+        datalog: RuleRound,
+        /// Rules with existential variables
+        existential: RuleRound,
+        /// Kind of the rule that was selected last
+        selected: Option<Selected>,
+    },
+    /// Rules that are applied by the substrategy
+    SubStrategy(SubStrategy),
+}
+
+/// Defines a strategy for rules in the guarded (or linear) fragment of existential rules,
+/// whereby the Datalog rules, i.e. rules without existential variables, are applied exhaustively
+/// before each application of a rule with existential variables.
+///
+/// The Datalog rules of a guarded program only derive facts over the terms of single facts.
+/// Saturating them first completes the facts about these terms before new nulls are introduced,
+/// so that the restricted chase finds more of the facts that already satisfy the heads of existential rules.
+/// This reduces the number of nulls and makes the chase terminate in more cases.
+///
+/// Rules that contain unguarded rules, or only contain rules of one kind,
+/// are applied by the substrategy instead.
+#[derive(Debug, Clone)]
+pub struct StrategyDatalogFirst<SubStrategy: RuleSelectionStrategy> {
+    evaluation: Evaluation<SubStrategy>,
+}
+
+impl<SubStrategy: RuleSelectionStrategy> RuleSelectionStrategy
+    for StrategyDatalogFirst<SubStrategy>
+{
+    /// Create new [`StrategyDatalogFirst`].
+    fn new(
+        rules: Vec<&ChaseRule>,
+        rule_analyses: Vec<&RuleAnalysis>,
+    ) -> Result<Self, SelectionStrategyError> {
+        let (existential, datalog): (Vec<usize>, Vec<usize>) =
+            (0..rules.len()).partition(|&index| is_existential(rules[index]));
+
+        let evaluation = if datalog.is_empty()
+            || existential.is_empty()
+            || !rules.iter().all(|rule| is_guarded(rule))
+        {
+            Evaluation::SubStrategy(SubStrategy::new(rules, rule_analyses)?)
+        } else {
+            Evaluation::DatalogFirst {
+                datalog: RuleRound {
+                    rules: datalog,
+                    ..Default::default()
+                },
+                existential: RuleRound {
+                    rules: existential,
+                    ..Default::default()
+                },
+                selected: None,
+            }
+        };
+
+        Ok(Self { evaluation })
+    }
+
+    fn next_rule(&mut self, new_derivations: Option<bool>) -> Option<usize> {
+        let (datalog, existential, selected) = match &mut self.evaluation {
+            Evaluation::DatalogFirst {
+                datalog,
+                existential,
+                selected,
+            } => (datalog, existential, selected),
+            Evaluation::SubStrategy(substrategy) => return substrategy.next_rule(new_derivations),
+        };
+
+        match (*selected, new_derivations) {
+            (_, Some(true)) => {
+                // New facts may be used by every rule
+                datalog.without_derivation = 0;
+                existential.without_derivation = 0;
+            }
+            (Some(Selected::Datalog), Some(false)) => datalog.without_derivation += 1,
+            (Some(Selected::Existential), Some(false)) => existential.without_derivation += 1,
+            _ => {}
+        }
+
+        if !datalog.is_saturated() {
+            *selected = Some(Selected::Datalog);
+            return Some(datalog.next());
+        }
+
+        if !existential.is_saturated() {
+            *selected = Some(Selected::Existential);
+            return Some(existential.next());
+        }
+
+        None
+    }
+}
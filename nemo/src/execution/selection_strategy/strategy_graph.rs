@@ -10,7 +10,7 @@ use super::{
 };
 
 /// Defines a rule execution strategy which respects certain dependencies between rules
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct StrategyDependencyGraph<
     GraphConstructor: DependencyGraphConstructor,
     SubStrategy: RuleSelectionStrategy,
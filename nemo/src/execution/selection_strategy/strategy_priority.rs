@@ -0,0 +1,114 @@
+//! Defines a rule execution strategy which saturates rules of higher priority first.
+
+use std::cmp::Reverse;
+
+use crate::{model::chase_model::ChaseRule, program_analysis::analysis::RuleAnalysis};
+
+use super::strategy::{RuleSelectionStrategy, SelectionStrategyError};
+
+/// Defines a strategy where rules are grouped by their priority,
+/// and each group is applied exhaustively by a substrategy
+/// before continuing with the group of the next lower priority.
+/// If the rules of a group derive new facts,
+/// the execution starts over with the group of the highest priority,
+/// such that rules of higher priority are saturated before rules of lower priority are applied.
+#[derive(Debug, Clone)]
+pub struct StrategyPriority<SubStrategy: RuleSelectionStrategy> {
+    rules: Vec<ChaseRule>,
+    rule_analyses: Vec<RuleAnalysis>,
+
+    ordered_groups: Vec<Vec<usize>>,
+    substrategies: Vec<SubStrategy>,
+
+    current_group: usize,
+    current_group_derived: bool,
+}
+
+impl<SubStrategy: RuleSelectionStrategy> StrategyPriority<SubStrategy> {
+    fn create_substrategies(
+        ordered_groups: &[Vec<usize>],
+        rules: &[&ChaseRule],
+        rule_analyses: &[&RuleAnalysis],
+    ) -> Result<Vec<SubStrategy>, SelectionStrategyError> {
+        ordered_groups
+            .iter()
+            .map(|group| {
+                SubStrategy::new(
+                    group.iter().map(|&i| rules[i]).collect(),
+                    group.iter().map(|&i| rule_analyses[i]).collect(),
+                )
+            })
+            .collect()
+    }
+
+    /// Start over with the group of the highest priority.
+    fn restart(&mut self) {
+        let rules = self.rules.iter().collect::<Vec<_>>();
+        let rule_analyses = self.rule_analyses.iter().collect::<Vec<_>>();
+
+        self.substrategies =
+            Self::create_substrategies(&self.ordered_groups, &rules, &rule_analyses)
+                .expect("substrategies have already been created for the same rules");
+        self.current_group = 0;
+    }
+}
+
+impl<SubStrategy: RuleSelectionStrategy> RuleSelectionStrategy for StrategyPriority<SubStrategy> {
+    /// Create new [`StrategyPriority`].
+    fn new(
+        rules: Vec<&ChaseRule>,
+        rule_analyses: Vec<&RuleAnalysis>,
+    ) -> Result<Self, SelectionStrategyError> {
+        let mut priorities = rules.iter().map(|rule| rule.priority()).collect::<Vec<_>>();
+        priorities.sort_by_key(|&priority| Reverse(priority));
+        priorities.dedup();
+
+        let ordered_groups = priorities
+            .iter()
+            .map(|&priority| {
+                (0..rules.len())
+                    .filter(|&i| rules[i].priority() == priority)
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+
+        if ordered_groups.len() > 1 {
+            tracing::info!("Rules grouped by priority: {ordered_groups:?}")
+        }
+
+        let substrategies = Self::create_substrategies(&ordered_groups, &rules, &rule_analyses)?;
+
+        Ok(Self {
+            rules: rules.into_iter().cloned().collect(),
+            rule_analyses: rule_analyses.into_iter().cloned().collect(),
+            ordered_groups,
+            substrategies,
+            current_group: 0,
+            current_group_derived: false,
+        })
+    }
+
+    fn next_rule(&mut self, mut new_derivations: Option<bool>) -> Option<usize> {
+        while self.current_group < self.ordered_groups.len() {
+            self.current_group_derived |= new_derivations == Some(true);
+
+            if let Some(substrategy_next_rule) =
+                self.substrategies[self.current_group].next_rule(new_derivations)
+            {
+                return Some(self.ordered_groups[self.current_group][substrategy_next_rule]);
+            }
+
+            // Groups of higher priority might be applicable to the new facts
+            if self.current_group > 0 && self.current_group_derived {
+                self.restart();
+            } else {
+                self.current_group += 1;
+            }
+
+            self.current_group_derived = false;
+            new_derivations = None;
+        }
+
+        None
+    }
+}
@@ -9,7 +9,7 @@ use crate::{model::chase_model::ChaseRule, program_analysis::analysis::RuleAnaly
 use super::strategy::{RuleSelectionStrategy, SelectionStrategyError};
 
 /// Defines a strategy that selects rules randomly.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct StrategyRandom {
     rule_count: usize,
     no_derivations: HashSet<usize>,
@@ -1,8 +1,8 @@
 //! Defines the execution strategy by which each rule is applied in the order it appears.
 
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 
-use petgraph::Directed;
+use petgraph::{graph::NodeIndex, visit::EdgeRef, Directed};
 
 use crate::{
     model::{chase_model::ChaseRule, Identifier},
@@ -10,7 +10,9 @@ use crate::{
     util::labeled_graph::LabeledGraph,
 };
 
-use super::strategy::{RuleSelectionStrategy, SelectionStrategyError};
+use super::strategy::{
+    NegationCycle, NegationCycleStep, RuleSelectionStrategy, SelectionStrategyError,
+};
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
 enum EdgeLabel {
@@ -24,7 +26,7 @@ type NegationGraph = LabeledGraph<usize, EdgeLabel, Directed>;
 /// which are executed in succession.
 /// Entering a new statum implies that the table for every negated atom
 /// will not get any new elements.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct StrategyStratifiedNegation<SubStrategy: RuleSelectionStrategy> {
     ordered_strata: Vec<Vec<usize>>,
     substrategies: Vec<SubStrategy>,
@@ -91,6 +93,104 @@ impl<SubStrategy: RuleSelectionStrategy> StrategyStratifiedNegation<SubStrategy>
 
         graph
     }
+
+    /// Return the smallest predicate derived by rule `from` that occurs in the body of rule `to`,
+    /// either negated or not.
+    fn connecting_predicate(
+        rule_analyses: &[&RuleAnalysis],
+        from: usize,
+        to: usize,
+        negated: bool,
+    ) -> Identifier {
+        let body_predicates = if negated {
+            &rule_analyses[to].negative_body_predicates
+        } else {
+            &rule_analyses[to].positive_body_predicates
+        };
+
+        rule_analyses[from]
+            .head_predicates
+            .intersection(body_predicates)
+            .min()
+            .cloned()
+            .expect("edges of the graph are only added for shared predicates")
+    }
+
+    /// Find a cycle in the graph that contains a negative edge.
+    /// Returns `None` if there is no such cycle, i.e. if the program is stratified.
+    fn find_negation_cycle(
+        graph: &NegationGraph,
+        rules: &[&ChaseRule],
+        rule_analyses: &[&RuleAnalysis],
+    ) -> Option<NegationCycle> {
+        let graph = graph.graph();
+
+        let mut node_to_component = HashMap::<NodeIndex, usize>::new();
+        for (component_index, component) in
+            petgraph::algo::tarjan_scc(graph).into_iter().enumerate()
+        {
+            for node in component {
+                node_to_component.insert(node, component_index);
+            }
+        }
+
+        let negative_edge = graph.edge_references().find(|edge| {
+            *edge.weight() == EdgeLabel::Negative
+                && node_to_component[&edge.source()] == node_to_component[&edge.target()]
+        })?;
+
+        // Search for the shortest path leading from the negating rule back to the deriving rule
+        let mut predecessors = HashMap::<NodeIndex, (NodeIndex, EdgeLabel)>::new();
+        let mut queue = VecDeque::from([negative_edge.target()]);
+        while let Some(node) = queue.pop_front() {
+            if node == negative_edge.source() {
+                break;
+            }
+
+            for edge in graph.edges(node) {
+                if edge.target() != negative_edge.target()
+                    && !predecessors.contains_key(&edge.target())
+                {
+                    predecessors.insert(edge.target(), (node, *edge.weight()));
+                    queue.push_back(edge.target());
+                }
+            }
+        }
+
+        let mut path = vec![(negative_edge.source(), EdgeLabel::Negative)];
+        let mut current = negative_edge.source();
+        while current != negative_edge.target() {
+            let (predecessor, label) = predecessors[&current];
+            path.push((predecessor, label));
+            current = predecessor;
+        }
+        path.reverse();
+        path.rotate_right(1);
+
+        let steps = (0..path.len())
+            .map(|step_index| {
+                let (node, label) = path[step_index];
+                let next_node = path[(step_index + 1) % path.len()].0;
+
+                let rule_index = graph[node];
+                let negated = label == EdgeLabel::Negative;
+
+                NegationCycleStep {
+                    rule_index,
+                    rule_predicates: rules[rule_index].predicate_outline(),
+                    predicate: Self::connecting_predicate(
+                        rule_analyses,
+                        rule_index,
+                        graph[next_node],
+                        negated,
+                    ),
+                    negated,
+                }
+            })
+            .collect();
+
+        Some(NegationCycle { steps })
+    }
 }
 
 impl<SubStrategy: RuleSelectionStrategy> RuleSelectionStrategy
@@ -121,7 +221,7 @@ impl<SubStrategy: RuleSelectionStrategy> RuleSelectionStrategy
             }
 
             if strata.len() > 1 {
-                log::info!("Stratified program: {strata:?}")
+                tracing::info!("Stratified program: {strata:?}")
             }
 
             Ok(Self {
@@ -130,7 +230,10 @@ impl<SubStrategy: RuleSelectionStrategy> RuleSelectionStrategy
                 current_stratum: 0,
             })
         } else {
-            Err(SelectionStrategyError::NonStratifiedProgram)
+            let cycle = Self::find_negation_cycle(&graph, &rules, &rule_analyses)
+                .expect("a graph that cannot be stratified contains a cycle through negation");
+
+            Err(SelectionStrategyError::NonStratifiedProgram(cycle))
         }
     }
 
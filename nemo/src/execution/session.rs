@@ -0,0 +1,249 @@
+//! Sessions allow reasoning with several independent programs over the same input data.
+
+use std::{cell::RefCell, collections::HashMap, rc::Rc, sync::Arc};
+
+use nemo_physical::{management::database::Dict, tabular::table_types::trie::Trie};
+
+use crate::{
+    error::Error,
+    io::resource_providers::ResourceProviders,
+    model::{chase_model::ChaseProgram, Identifier, PrimitiveType, Program},
+    table_manager::TableManager,
+};
+
+use super::{
+    selection_strategy::strategy::RuleSelectionStrategy, DefaultExecutionEngine, ExecutionEngine,
+};
+
+/// Table of a [`Session`] together with the logical types of its columns.
+#[derive(Debug)]
+struct SharedTable {
+    types: Vec<PrimitiveType>,
+    trie: Arc<Trie>,
+}
+
+/// Data that is loaded once and shared by several [`ExecutionEngine`]s.
+///
+/// All engines created from a session start from the dictionary of the session
+/// and can access its tables without copying them.
+/// Tables derived by an engine and constants added to its dictionary are only visible to that engine.
+///
+/// A session can be shared between threads,
+/// such that each thread creates its own engines over the same data.
+#[derive(Debug)]
+pub struct Session {
+    /// Frozen dictionary, whose entries are shared by the dictionaries of all engines
+    dictionary: Dict,
+    tables: HashMap<Identifier, SharedTable>,
+    next_null: u64,
+}
+
+impl Session {
+    /// Create a new [`Session`] by materializing the given program.
+    ///
+    /// Every predicate of the program (whether loaded from a source or derived by a rule)
+    /// is made available to the programs executed in this session.
+    pub fn new(program: Program, resource_providers: ResourceProviders) -> Result<Self, Error> {
+        let mut engine: DefaultExecutionEngine =
+            ExecutionEngine::initialize(program, resource_providers)?;
+        engine.execute()?;
+
+        Self::from_engine(engine)
+    }
+
+    /// Create a new [`Session`] from the current tables of an [`ExecutionEngine`].
+    pub fn from_engine<Strategy: RuleSelectionStrategy>(
+        mut engine: ExecutionEngine<Strategy>,
+    ) -> Result<Self, Error> {
+        let predicate_types = engine.predicate_types().clone();
+        let table_manager = engine.table_manager_mut();
+
+        let mut tables = HashMap::new();
+        for (predicate, types) in predicate_types {
+            if let Some(trie) = table_manager.shared_predicate_trie(predicate.clone())? {
+                tables.insert(predicate, SharedTable { types, trie });
+            }
+        }
+
+        Ok(Self {
+            dictionary: table_manager.get_dict().frozen(),
+            tables,
+            next_null: table_manager.next_null(),
+        })
+    }
+
+    /// Return an iterator over all predicates provided by this session.
+    pub fn predicates(&self) -> impl Iterator<Item = &Identifier> {
+        self.tables.keys()
+    }
+
+    /// Initialize an [`ExecutionEngine`] for the given program within this session.
+    ///
+    /// See [`ExecutionEngine::initialize_in_session`].
+    pub fn engine<Strategy: RuleSelectionStrategy>(
+        &self,
+        program: Program,
+        resource_providers: ResourceProviders,
+    ) -> Result<ExecutionEngine<Strategy>, Error> {
+        ExecutionEngine::initialize_in_session(program, resource_providers, self)
+    }
+
+    /// Declare the types of all predicates of the session that occur in the given program,
+    /// so that they agree with the types of the shared tables.
+    pub(crate) fn declare_predicates(&self, program: &mut ChaseProgram) {
+        let program_predicates = program
+            .predicates()
+            .into_iter()
+            .chain(program.sources().map(|source| source.predicate.clone()))
+            .collect::<Vec<_>>();
+
+        for predicate in program_predicates {
+            if let Some(table) = self.tables.get(&predicate) {
+                program.add_predicate_declaration(predicate, table.types.clone());
+            }
+        }
+    }
+
+    /// Create a new [`TableManager`] whose dictionary extends the dictionary of this session.
+    pub(crate) fn table_manager(&self) -> TableManager {
+        let mut table_manager =
+            TableManager::with_dictionary(Rc::new(RefCell::new(self.dictionary.clone())));
+        table_manager.reserve_nulls(self.next_null);
+
+        table_manager
+    }
+
+    /// Return a handle to the table of the given predicate.
+    /// Returns `None` if the session does not contain such a predicate.
+    pub(crate) fn shared_trie(&self, predicate: &Identifier) -> Option<Arc<Trie>> {
+        self.tables
+            .get(predicate)
+            .map(|table| Arc::clone(&table.trie))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        execution::DefaultExecutionEngine,
+        io::{parser::parse_program, resource_providers::ResourceProviders},
+        model::Identifier,
+    };
+
+    use super::Session;
+
+    fn results(engine: &mut DefaultExecutionEngine, predicate: &str) -> Vec<Vec<String>> {
+        let mut rows: Vec<Vec<String>> = engine
+            .output_serialization(Identifier(predicate.to_string()))
+            .unwrap()
+            .map(|iter| iter.collect())
+            .unwrap_or_default();
+        rows.sort();
+        rows
+    }
+
+    #[test]
+    fn programs_share_input() {
+        let base = parse_program(
+            "edge(a, b) . edge(b, c) . edge(c, d) .\n\
+             node(?x) :- edge(?x, ?y) .\n",
+        )
+        .unwrap();
+        let session = Session::new(base, ResourceProviders::empty()).unwrap();
+
+        let mut predicates = session.predicates().cloned().collect::<Vec<_>>();
+        predicates.sort_by_key(|p| p.name());
+        assert_eq!(
+            predicates,
+            vec![
+                Identifier("edge".to_string()),
+                Identifier("node".to_string())
+            ]
+        );
+
+        let reachable = parse_program(
+            "reach(?x, ?y) :- edge(?x, ?y) .\n\
+             reach(?x, ?z) :- reach(?x, ?y), edge(?y, ?z) .\n",
+        )
+        .unwrap();
+        let mut engine: DefaultExecutionEngine = session
+            .engine(reachable, ResourceProviders::empty())
+            .unwrap();
+        engine.execute().unwrap();
+        assert_eq!(results(&mut engine, "reach").len(), 6);
+
+        let parameterized = parse_program(
+            "start(b) .\n\
+             result(?y) :- start(?x), edge(?x, ?y) .\n\
+             result(?y) :- node(?y), start(?y) .\n",
+        )
+        .unwrap();
+        let mut engine: DefaultExecutionEngine = session
+            .engine(parameterized, ResourceProviders::empty())
+            .unwrap();
+        engine.execute().unwrap();
+        assert_eq!(
+            results(&mut engine, "result"),
+            vec![vec!["b".to_string()], vec!["c".to_string()]]
+        );
+
+        // Derived tables of other engines are not visible
+        assert!(session
+            .predicates()
+            .all(|predicate| predicate.name() != "reach"));
+    }
+
+    #[test]
+    fn share_between_threads() {
+        fn assert_send_sync<T: Send + Sync>(_: &T) {}
+
+        let session = Session::new(
+            parse_program("edge(a, b) . edge(b, c) . edge(c, d) .").unwrap(),
+            ResourceProviders::empty(),
+        )
+        .unwrap();
+        assert_send_sync(&session);
+
+        let programs = [
+            (
+                "reach",
+                "reach(?x, ?y) :- edge(?x, ?y) . reach(?x, ?z) :- reach(?x, ?y), edge(?y, ?z) .",
+                6,
+            ),
+            (
+                "inverse",
+                "inverse(?y, ?x) :- edge(?x, ?y) . inverse(e, d) .",
+                4,
+            ),
+        ];
+
+        std::thread::scope(|scope| {
+            for (predicate, program, count) in programs {
+                let session = &session;
+                let handle = scope.spawn(move || {
+                    let mut engine: DefaultExecutionEngine = session
+                        .engine(parse_program(program).unwrap(), ResourceProviders::empty())
+                        .unwrap();
+                    engine.execute().unwrap();
+                    results(&mut engine, predicate).len()
+                });
+
+                assert_eq!(handle.join().unwrap(), count);
+            }
+        });
+    }
+
+    #[test]
+    fn arity_mismatch_is_rejected() {
+        let session = Session::new(
+            parse_program("edge(a, b) .").unwrap(),
+            ResourceProviders::empty(),
+        )
+        .unwrap();
+
+        let program = parse_program("loop(?x) :- edge(?x) .").unwrap();
+        let result: Result<DefaultExecutionEngine, _> =
+            session.engine(program, ResourceProviders::empty());
+        assert!(result.is_err());
+    }
+}
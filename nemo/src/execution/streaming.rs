@@ -0,0 +1,467 @@
+//! Continuous reasoning over external sources whose facts arrive over time.
+
+use std::{
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+    ops::Range,
+    rc::Rc,
+};
+
+use nemo_physical::error::ReadingError;
+
+use crate::{
+    error::Error,
+    io::resource_providers::{external::ExternalSourceProvider, ResourceProviders},
+    model::{
+        chase_model::ChaseProgram, ExternalSource, Identifier, NativeDataSource, Program, Term,
+    },
+};
+
+use super::DefaultExecutionEngine;
+
+/// Facts of a streamed external source that have been received so far.
+#[derive(Debug, Default, Clone)]
+struct StreamBuffer(Rc<RefCell<Vec<Vec<Term>>>>);
+
+impl StreamBuffer {
+    /// Return the number of facts received so far.
+    fn len(&self) -> usize {
+        self.0.borrow().len()
+    }
+
+    /// Return a provider for the facts received so far, starting with the fact at the given index.
+    fn facts_from(&self, start: usize) -> StreamSlice {
+        StreamSlice {
+            buffer: self.clone(),
+            range: start..self.len(),
+        }
+    }
+}
+
+/// Provides the facts of a [`StreamBuffer`] within a fixed range of indices.
+///
+/// Since sources are loaded lazily, this ensures that an engine does not see facts
+/// that have been received after it was given the source.
+#[derive(Debug)]
+struct StreamSlice {
+    buffer: StreamBuffer,
+    range: Range<usize>,
+}
+
+impl StreamSlice {
+    /// Return whether the slice contains no facts.
+    fn is_empty(&self) -> bool {
+        self.range.is_empty()
+    }
+}
+
+impl ExternalSourceProvider for StreamSlice {
+    fn facts(&self) -> Result<Box<dyn Iterator<Item = Vec<Term>> + '_>, ReadingError> {
+        Ok(Box::new(
+            self.range
+                .clone()
+                .map(move |index| self.buffer.0.borrow()[index].clone()),
+        ))
+    }
+}
+
+/// Return the predicates and declarations of the sources of the program
+/// that read from the external source with the given name.
+fn external_sources<'a>(
+    program: &'a ChaseProgram,
+    name: &'a str,
+) -> impl Iterator<Item = (Identifier, &'a ExternalSource, bool)> + 'a {
+    program
+        .sources()
+        .filter_map(move |declaration| match &declaration.source {
+            NativeDataSource::External(external_source) if external_source.name == name => Some((
+                declaration.predicate.clone(),
+                external_source,
+                declaration.is_bag(),
+            )),
+            _ => None,
+        })
+}
+
+/// Changes of the facts of an output predicate caused by an update of a [`StreamingReasoner`]
+/// or by hypothetical changes of the input facts (see [`ExecutionEngine::hypothetical`][super::ExecutionEngine::hypothetical]).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Delta {
+    /// The output predicate
+    pub predicate: Identifier,
+    /// Facts that have been derived by the update
+    pub added: Vec<Vec<String>>,
+    /// Facts that no longer follow after the update,
+    /// which is only possible for programs with negation or if input facts have been removed
+    pub removed: Vec<Vec<String>>,
+}
+
+impl Delta {
+    /// Compute the changes from the previous to the current facts of the given predicate.
+    /// Returns `None` if the facts have not changed.
+    pub(super) fn between(
+        predicate: Identifier,
+        previous: &HashSet<Vec<String>>,
+        current: &HashSet<Vec<String>>,
+    ) -> Option<Self> {
+        let mut added = current.difference(previous).cloned().collect::<Vec<_>>();
+        let mut removed = previous.difference(current).cloned().collect::<Vec<_>>();
+
+        if added.is_empty() && removed.is_empty() {
+            return None;
+        }
+
+        added.sort();
+        removed.sort();
+
+        Some(Self {
+            predicate,
+            added,
+            removed,
+        })
+    }
+}
+
+/// Maintains the materialization of a program,
+/// whose external sources are fed with new facts over time.
+///
+/// Facts are added to the sources declared as `@source p[2]: external("name") .`
+/// using [`StreamingReasoner::add_facts`].
+/// Every call to [`StreamingReasoner::update`] brings the materialization up to date
+/// and reports how the facts of the output predicates have changed.
+///
+/// The materialization is maintained incrementally: the new facts are added to the engine
+/// of the previous update, which applies the rules semi-naively to the new facts and their consequences.
+/// This is only possible if the new facts cannot invalidate facts derived before,
+/// i.e. if they do not reach a negated atom or an aggregate and are not read by a source that counts duplicates.
+/// Otherwise, the update reasons over all facts received so far from scratch.
+#[derive(Debug)]
+pub struct StreamingReasoner {
+    program: Program,
+    resource_providers: ResourceProviders,
+    streams: HashMap<String, StreamBuffer>,
+    /// Engine holding the materialization of the previous update
+    engine: Option<DefaultExecutionEngine>,
+    /// Number of facts of each stream that are contained in the materialization
+    received: HashMap<String, usize>,
+    results: HashMap<Identifier, HashSet<Vec<String>>>,
+}
+
+impl StreamingReasoner {
+    /// Create a new [`StreamingReasoner`] for the given program.
+    ///
+    /// Resources and external sources that are not streamed are resolved using the given [`ResourceProviders`].
+    pub fn new(program: Program, resource_providers: ResourceProviders) -> Self {
+        Self {
+            program,
+            resource_providers,
+            streams: HashMap::new(),
+            engine: None,
+            received: HashMap::new(),
+            results: HashMap::new(),
+        }
+    }
+
+    /// Add facts to the external source with the given name.
+    ///
+    /// The facts are taken into account by the next call to [`StreamingReasoner::update`].
+    pub fn add_facts<Facts>(&mut self, source: &str, facts: Facts)
+    where
+        Facts: IntoIterator<Item = Vec<Term>>,
+    {
+        self.streams
+            .entry(source.to_string())
+            .or_default()
+            .0
+            .borrow_mut()
+            .extend(facts);
+    }
+
+    /// Return the number of facts received so far for the external source with the given name.
+    pub fn count_facts(&self, source: &str) -> usize {
+        self.streams.get(source).map_or(0, StreamBuffer::len)
+    }
+
+    /// Bring the materialization up to date with the facts received so far.
+    ///
+    /// Returns the changes of every output predicate whose facts have changed since the last update.
+    pub fn update(&mut self) -> Result<Vec<Delta>, Error> {
+        let batches = self
+            .streams
+            .iter()
+            .map(|(name, buffer)| {
+                let received = self.received.get(name).copied().unwrap_or_default();
+                (name.clone(), buffer.facts_from(received))
+            })
+            .filter(|(_, batch)| !batch.is_empty())
+            .collect::<Vec<_>>();
+
+        match self.engine.take() {
+            Some(engine) if Self::is_insertion(&engine, &batches) => {
+                self.update_incrementally(engine, batches)
+            }
+            _ => self.update_from_scratch(),
+        }
+    }
+
+    /// Return whether adding the given batches of facts to the engine
+    /// can only add facts to its materialization.
+    fn is_insertion(engine: &DefaultExecutionEngine, batches: &[(String, StreamSlice)]) -> bool {
+        let mut predicates = HashSet::new();
+
+        for (name, _) in batches {
+            for (predicate, _, is_bag) in external_sources(engine.program(), name) {
+                if is_bag {
+                    return false;
+                }
+
+                predicates.insert(predicate);
+            }
+        }
+
+        engine.is_monotone_in(&predicates)
+    }
+
+    /// Add the given batches of facts to the materialization of the previous update
+    /// and continue reasoning from them.
+    fn update_incrementally(
+        &mut self,
+        mut engine: DefaultExecutionEngine,
+        batches: Vec<(String, StreamSlice)>,
+    ) -> Result<Vec<Delta>, Error> {
+        let first_step = engine.current_step();
+
+        for (name, batch) in batches {
+            let received = batch.range.end;
+            let batch: Rc<dyn ExternalSourceProvider> = Rc::new(batch);
+
+            let sources = external_sources(engine.program(), &name)
+                .map(|(predicate, external_source, _)| (predicate, external_source.clone()))
+                .collect::<Vec<_>>();
+            for (predicate, external_source) in sources {
+                engine.add_source_facts(predicate, &external_source, batch.clone())?;
+            }
+
+            self.received.insert(name, received);
+        }
+
+        engine.execute()?;
+
+        let mut deltas = Vec::new();
+        for predicate in engine.program().output_predicates().collect::<Vec<_>>() {
+            let added = engine
+                .facts_since_serialization(predicate.clone(), first_step)?
+                .map(|records| records.collect::<HashSet<_>>())
+                .unwrap_or_default();
+            let facts = self.results.entry(predicate.clone()).or_default();
+
+            deltas.extend(Delta::between(predicate, &HashSet::new(), &added));
+
+            facts.extend(added);
+        }
+
+        self.engine = Some(engine);
+
+        Ok(deltas)
+    }
+
+    /// Reason over all facts received so far, starting from scratch.
+    fn update_from_scratch(&mut self) -> Result<Vec<Delta>, Error> {
+        self.received.clear();
+
+        let mut resource_providers = self.resource_providers.clone();
+        for (name, buffer) in &self.streams {
+            resource_providers =
+                resource_providers.with_external_source(name, buffer.facts_from(0));
+            self.received.insert(name.clone(), buffer.len());
+        }
+
+        let mut engine =
+            DefaultExecutionEngine::initialize(self.program.clone(), resource_providers)?;
+        engine.execute()?;
+
+        let mut deltas = Vec::new();
+        for predicate in engine.program().output_predicates().collect::<Vec<_>>() {
+            let facts = engine
+                .output_serialization(predicate.clone())?
+                .map(|records| records.collect::<HashSet<_>>())
+                .unwrap_or_default();
+            let previous = self.results.remove(&predicate).unwrap_or_default();
+
+            deltas.extend(Delta::between(predicate.clone(), &previous, &facts));
+
+            self.results.insert(predicate, facts);
+        }
+
+        self.engine = Some(engine);
+
+        Ok(deltas)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        io::{parser::parse_program, resource_providers::ResourceProviders},
+        model::{Identifier, Term},
+    };
+
+    use super::{Delta, StreamingReasoner};
+
+    fn edge(from: &str, to: &str) -> Vec<Term> {
+        vec![
+            Term::Constant(Identifier(from.to_string())),
+            Term::Constant(Identifier(to.to_string())),
+        ]
+    }
+
+    fn fact(terms: &[&str]) -> Vec<String> {
+        terms.iter().map(|term| term.to_string()).collect()
+    }
+
+    #[test]
+    fn streaming_updates() {
+        let program = parse_program(
+            "@source edge[any, any]: external(\"edges\") .\n\
+             @source blocked[any]: external(\"blocked\") .\n\
+             node(?X) :- edge(?X, ?Y) .\n\
+             node(?Y) :- edge(?X, ?Y) .\n\
+             reachable(?X, ?Y) :- edge(?X, ?Y) .\n\
+             reachable(?X, ?Z) :- reachable(?X, ?Y), edge(?Y, ?Z) .\n\
+             open(?X) :- node(?X), ~blocked(?X) .\n\
+             @output reachable .\n\
+             @output open .\n",
+        )
+        .unwrap();
+
+        let mut reasoner = StreamingReasoner::new(program, ResourceProviders::empty());
+        reasoner.add_facts("blocked", vec![]);
+        reasoner.add_facts("edges", vec![edge("a", "b")]);
+
+        let mut deltas = reasoner.update().unwrap();
+        deltas.sort_by(|left, right| left.predicate.cmp(&right.predicate));
+        assert_eq!(
+            deltas,
+            vec![
+                Delta {
+                    predicate: Identifier("open".to_string()),
+                    added: vec![fact(&["a"]), fact(&["b"])],
+                    removed: vec![],
+                },
+                Delta {
+                    predicate: Identifier("reachable".to_string()),
+                    added: vec![fact(&["a", "b"])],
+                    removed: vec![],
+                },
+            ]
+        );
+
+        assert_eq!(reasoner.update().unwrap(), vec![]);
+
+        reasoner.add_facts("edges", vec![edge("b", "c")]);
+        reasoner.add_facts(
+            "blocked",
+            vec![vec![Term::Constant(Identifier("a".to_string()))]],
+        );
+
+        let mut deltas = reasoner.update().unwrap();
+        deltas.sort_by(|left, right| left.predicate.cmp(&right.predicate));
+        assert_eq!(
+            deltas,
+            vec![
+                Delta {
+                    predicate: Identifier("open".to_string()),
+                    added: vec![fact(&["c"])],
+                    removed: vec![fact(&["a"])],
+                },
+                Delta {
+                    predicate: Identifier("reachable".to_string()),
+                    added: vec![fact(&["a", "c"]), fact(&["b", "c"])],
+                    removed: vec![],
+                },
+            ]
+        );
+        assert_eq!(reasoner.count_facts("edges"), 2);
+    }
+
+    #[test]
+    fn incremental_updates() {
+        let program = parse_program(
+            "@source edge[any, any]: external(\"edges\") .\n\
+             @source blocked[any]: external(\"blocked\") .\n\
+             reachable(?X, ?Y) :- edge(?X, ?Y) .\n\
+             reachable(?X, ?Z) :- reachable(?X, ?Y), edge(?Y, ?Z) .\n\
+             open(?X) :- reachable(?X, ?Y), ~blocked(?X) .\n\
+             @output reachable .\n",
+        )
+        .unwrap();
+        let edge_predicate = Identifier("edge".to_string());
+
+        let mut reasoner = StreamingReasoner::new(program, ResourceProviders::empty());
+        reasoner.add_facts("blocked", vec![]);
+        reasoner.add_facts("edges", vec![edge("a", "b"), edge("c", "d")]);
+        reasoner.update().unwrap();
+
+        let engine = reasoner.engine.as_ref().unwrap();
+        let steps = engine.steps();
+        assert_eq!(
+            engine
+                .predicate_state(&edge_predicate)
+                .unwrap()
+                .subtables
+                .len(),
+            1
+        );
+
+        // New edges only add facts, so reasoning continues from the previous materialization
+        reasoner.add_facts("edges", vec![edge("b", "c"), edge("a", "b")]);
+        assert_eq!(
+            reasoner.update().unwrap(),
+            vec![Delta {
+                predicate: Identifier("reachable".to_string()),
+                added: vec![
+                    fact(&["a", "c"]),
+                    fact(&["a", "d"]),
+                    fact(&["b", "c"]),
+                    fact(&["b", "d"]),
+                ],
+                removed: vec![],
+            }]
+        );
+
+        let engine = reasoner.engine.as_ref().unwrap();
+        assert!(engine.steps() > steps);
+        assert_eq!(
+            engine
+                .predicate_state(&edge_predicate)
+                .unwrap()
+                .subtables
+                .len(),
+            2
+        );
+        assert_eq!(engine.count_facts_of_predicate(&edge_predicate), Some(3));
+
+        assert_eq!(reasoner.update().unwrap(), vec![]);
+
+        // Blocked nodes may invalidate derived facts, so the materialization is computed again
+        reasoner.add_facts(
+            "blocked",
+            vec![vec![Term::Constant(Identifier("a".to_string()))]],
+        );
+        assert_eq!(reasoner.update().unwrap(), vec![]);
+
+        let engine = reasoner.engine.as_ref().unwrap();
+        assert_eq!(
+            engine
+                .predicate_state(&edge_predicate)
+                .unwrap()
+                .subtables
+                .len(),
+            1
+        );
+        assert_eq!(
+            reasoner.results[&Identifier("reachable".to_string())].len(),
+            6
+        );
+    }
+}
@@ -0,0 +1,31 @@
+//! Fixtures shared by the tests of the execution modules.
+
+use crate::{
+    execution::DefaultExecutionEngine,
+    io::{parser::parse_program, resource_providers::ResourceProviders},
+    model::types::primitive_logical_value::PrimitiveLogicalValueT,
+};
+
+/// Initialize an engine for the given program, without reasoning.
+pub(crate) fn initialized_engine(program: &str) -> DefaultExecutionEngine {
+    DefaultExecutionEngine::initialize(parse_program(program).unwrap(), ResourceProviders::empty())
+        .unwrap()
+}
+
+/// Initialize an engine for the given program and compute its materialization.
+pub(crate) fn materialized_engine(program: &str) -> DefaultExecutionEngine {
+    let mut engine = initialized_engine(program);
+    engine.execute().unwrap();
+    engine
+}
+
+/// Render the given rows as strings and sort them, so that they can be compared
+/// independently of the order in which they were computed.
+pub(crate) fn sorted_strings(rows: Vec<Vec<PrimitiveLogicalValueT>>) -> Vec<Vec<String>> {
+    let mut rows: Vec<Vec<String>> = rows
+        .into_iter()
+        .map(|row| row.iter().map(PrimitiveLogicalValueT::to_string).collect())
+        .collect();
+    rows.sort();
+    rows
+}
@@ -0,0 +1,795 @@
+//! Goal-directed evaluation of queries, which only considers the rules and facts
+//! that are relevant for answering a given query atom.
+//!
+//! Queries are answered by a tabled top-down proof search in the spirit of SLG resolution.
+//! A subgoal, i.e. a predicate whose terms are either bound to constants or free,
+//! is associated with a table of its answers.
+//! Subgoals are resolved with the rules deriving their predicate,
+//! where the constants bound in the head are propagated into the body.
+//! Since subgoals may depend on themselves, their rules are applied repeatedly
+//! until no new answers are found, after which the subgoals are marked as complete.
+//! Negated subgoals are always completed before they are used,
+//! which requires the program to be stratified.
+
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
+    rc::Rc,
+};
+
+use nemo_physical::{
+    datatypes::DataValueT,
+    dictionary::{edit_distance_at_most, TextQuery},
+};
+use thiserror::Error;
+
+use crate::{
+    error::Error,
+    model::{
+        chase_model::{ChaseAtom, ChaseRule},
+        types::{error::TypeError, primitive_logical_value::PrimitiveLogicalValueT},
+        Atom, FilterOperation, Identifier, PrimitiveType, RdfLiteral, Term, TermOperation,
+        TermTree, Variable,
+    },
+    program_analysis::analysis::RuleAnalysis,
+};
+
+use super::{
+    query::QueryError, selection_strategy::strategy::RuleSelectionStrategy, ExecutionEngine,
+};
+
+/// Errors that can occur during top-down evaluation.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum TopDownError {
+    /// A predicate relevant for the query is derived by a rule that cannot be evaluated top-down.
+    #[error("The predicate \"{0}\" is derived by a rule with existential variables, aggregates or functions, which cannot be evaluated top-down.")]
+    UnsupportedRule(Identifier),
+    /// A fact cannot be traced, since its predicate is derived by a rule whose applications cannot be reconstructed.
+    #[error("Facts of the predicate \"{0}\" cannot be traced, since it is derived by a rule with aggregates or functions.")]
+    UntraceableRule(Identifier),
+}
+
+/// Values of the terms of a fact
+pub(super) type Row = Vec<Term>;
+
+/// Assignment of variables to constants
+pub(super) type Binding = BTreeMap<Variable, Term>;
+
+/// Return the term the given term is bound to,
+/// or the term itself if it is a constant or an unbound variable.
+pub(super) fn substitute(term: &Term, binding: &Binding) -> Term {
+    match term {
+        Term::Variable(variable) => binding.get(variable).unwrap_or(term).clone(),
+        _ => term.clone(),
+    }
+}
+
+/// Extend the given binding such that the given terms match the given row.
+/// Returns `None` if this is not possible.
+pub(super) fn extend(terms: &[Term], row: &[Term], binding: &Binding) -> Option<Binding> {
+    let mut result = binding.clone();
+
+    for (term, value) in terms.iter().zip(row) {
+        match term {
+            Term::Variable(variable) => match result.get(variable) {
+                Some(bound) if bound != value => return None,
+                Some(_) => {}
+                None => {
+                    result.insert(variable.clone(), value.clone());
+                }
+            },
+            constant if constant != value => return None,
+            _ => {}
+        }
+    }
+
+    Some(result)
+}
+
+/// Convert a constant into the term it is read back as
+/// after storing it in a column of the given type.
+/// Variables are left unchanged.
+///
+/// Returns `None` if the constant cannot be stored in such a column.
+pub(super) fn canonical_term(term: Term, logical_type: PrimitiveType) -> Option<Term> {
+    match term {
+        Term::Variable(_) => Some(term),
+        _ => logical_type
+            .ground_term_to_logical_value(term)
+            .ok()
+            .map(Term::from),
+    }
+}
+
+/// Return the text of a constant that is searched by [`FilterOperation::MatchesText`],
+/// or `None` if the constant is not searched.
+fn searched_text(term: &Term) -> Option<&str> {
+    match term {
+        Term::Constant(Identifier(name)) => Some(name),
+        Term::StringLiteral(value) => Some(value),
+        Term::RdfLiteral(RdfLiteral::LanguageString { value, .. }) => Some(value),
+        _ => None,
+    }
+}
+
+/// Subgoal of the proof search
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub(super) struct Subgoal {
+    predicate: Identifier,
+    /// For each position, the constant it is bound to or `None` if it is free
+    pattern: Vec<Option<Term>>,
+}
+
+impl Subgoal {
+    /// Create the [`Subgoal`] for an atom with the given predicate and terms under the given binding.
+    pub(super) fn new(predicate: Identifier, terms: &[Term], binding: &Binding) -> Self {
+        let pattern = terms
+            .iter()
+            .map(|term| match substitute(term, binding) {
+                Term::Variable(_) => None,
+                constant => Some(constant),
+            })
+            .collect();
+
+        Self { predicate, pattern }
+    }
+
+    /// Return an atom that asks for all facts matching this subgoal.
+    pub(super) fn atom(&self) -> Atom {
+        let term_trees = self
+            .pattern
+            .iter()
+            .enumerate()
+            .map(|(position, constant)| {
+                TermTree::leaf(constant.clone().unwrap_or_else(|| {
+                    Term::Variable(Variable::Universal(Identifier(format!("x{position}"))))
+                }))
+            })
+            .collect();
+
+        Atom::new(self.predicate.clone(), term_trees)
+    }
+}
+
+/// Answers found so far for a [`Subgoal`]
+#[derive(Debug, Default)]
+struct SubgoalTable {
+    answers: BTreeSet<Row>,
+    /// Whether all answers have been found
+    complete: bool,
+}
+
+/// Subgoals that are evaluated together until none of them has new answers
+#[derive(Debug, Default)]
+struct Scope {
+    subgoals: Vec<Subgoal>,
+    members: BTreeSet<Subgoal>,
+}
+
+impl Scope {
+    fn insert(&mut self, subgoal: &Subgoal) {
+        if self.members.insert(subgoal.clone()) {
+            self.subgoals.push(subgoal.clone());
+        }
+    }
+}
+
+/// Comparison in the body of a [`TopDownRule`]
+#[derive(Debug, Clone)]
+pub(super) struct TopDownFilter {
+    operation: FilterOperation,
+    left: Term,
+    right: Term,
+    /// Type of the compared values
+    logical_type: PrimitiveType,
+}
+
+impl TopDownFilter {
+    /// Return whether the filter is satisfied under the given binding,
+    /// which has to bind all of its variables.
+    pub(super) fn holds(&self, binding: &Binding) -> bool {
+        let left = substitute(&self.left, binding);
+        let right = substitute(&self.right, binding);
+
+        if self.operation == FilterOperation::Unequals {
+            return left != right;
+        }
+
+        if self.operation == FilterOperation::MatchesText {
+            return match (searched_text(&left), right) {
+                (Some(text), Term::StringLiteral(query)) => TextQuery::new(&query).matches(text),
+                _ => false,
+            };
+        }
+
+        if let FilterOperation::EditDistanceAtMost(max_distance) = self.operation {
+            return match (searched_text(&left), searched_text(&right)) {
+                (Some(left), Some(right)) => edit_distance_at_most(left, right, max_distance),
+                _ => false,
+            };
+        }
+
+        let (Ok(left), Ok(right)) = (
+            self.logical_type.ground_term_to_data_value_t(left),
+            self.logical_type.ground_term_to_data_value_t(right),
+        ) else {
+            return false;
+        };
+        let ordering = match (left, right) {
+            (DataValueT::I64(left), DataValueT::I64(right)) => left.cmp(&right),
+            (DataValueT::Double(left), DataValueT::Double(right)) => left.cmp(&right),
+            _ => return false,
+        };
+
+        match self.operation {
+            FilterOperation::Equals => ordering.is_eq(),
+            FilterOperation::Unequals => ordering.is_ne(),
+            FilterOperation::LessThan => ordering.is_lt(),
+            FilterOperation::GreaterThan => ordering.is_gt(),
+            FilterOperation::LessThanEq => ordering.is_le(),
+            FilterOperation::GreaterThanEq => ordering.is_ge(),
+            FilterOperation::MatchesText | FilterOperation::EditDistanceAtMost(_) => {
+                unreachable!("text searches and edit distances are handled above")
+            }
+        }
+    }
+}
+
+/// Rule of the program, prepared for top-down evaluation
+///
+/// All equalities of the rule are resolved,
+/// such that its atoms directly contain the constants and variables they refer to.
+/// Constants are given as they are stored in the tables of their predicates.
+#[derive(Debug, Clone)]
+pub(super) struct TopDownRule {
+    pub(super) head: Vec<ChaseAtom>,
+    pub(super) positive_body: Vec<ChaseAtom>,
+    pub(super) negative_body: Vec<ChaseAtom>,
+    pub(super) filters: Vec<TopDownFilter>,
+}
+
+impl TopDownRule {
+    /// Return whether the given rule can be evaluated top-down.
+    fn is_supported(rule: &ChaseRule, analysis: &RuleAnalysis) -> bool {
+        !analysis.is_existential && Self::can_prepare(rule)
+    }
+
+    /// Return whether the given rule can be prepared by [`TopDownRule::new`],
+    /// which keeps existential variables of its head as variables.
+    pub(super) fn can_prepare(rule: &ChaseRule) -> bool {
+        rule.constructors().is_empty()
+            && rule.aggregate().is_none()
+            && rule
+                .negative_filters()
+                .iter()
+                .all(|filter| filter.operation == FilterOperation::Equals)
+    }
+
+    /// Prepare the given rule for top-down evaluation.
+    ///
+    /// Returns `None` if the body of the rule can never be satisfied,
+    /// e.g. because it requires a variable to be equal to two different constants.
+    pub(super) fn new(
+        rule: &ChaseRule,
+        analysis: &RuleAnalysis,
+        predicate_types: &HashMap<Identifier, Vec<PrimitiveType>>,
+    ) -> Option<Self> {
+        let variable_type = |variable: &Variable| {
+            analysis
+                .variable_types
+                .get(variable)
+                .copied()
+                .unwrap_or(PrimitiveType::Any)
+        };
+
+        let mut equalities = Binding::new();
+        for filter in rule
+            .positive_filters()
+            .iter()
+            .chain(rule.negative_filters())
+            .filter(|filter| filter.operation == FilterOperation::Equals)
+        {
+            let right = canonical_term(filter.rhs.clone(), variable_type(&filter.lhs))?;
+            if !unify(&mut equalities, Term::Variable(filter.lhs.clone()), right) {
+                return None;
+            }
+        }
+
+        let prepare_atom = |atom: &ChaseAtom| {
+            let types = predicate_types.get(&atom.predicate());
+            let terms = atom
+                .terms()
+                .iter()
+                .enumerate()
+                .map(|(position, term)| {
+                    let logical_type = types
+                        .and_then(|types| types.get(position))
+                        .copied()
+                        .unwrap_or(PrimitiveType::Any);
+                    canonical_term(resolve(&equalities, term.clone()), logical_type)
+                })
+                .collect::<Option<Vec<_>>>()?;
+
+            Some(ChaseAtom::new(atom.predicate(), terms))
+        };
+
+        let head = rule
+            .head()
+            .iter()
+            .map(prepare_atom)
+            .collect::<Option<Vec<_>>>()?;
+        let positive_body = rule
+            .positive_body()
+            .iter()
+            .map(prepare_atom)
+            .collect::<Option<Vec<_>>>()?;
+        // Negated atoms containing constants that cannot occur in their tables are always satisfied
+        let negative_body = rule
+            .negative_body()
+            .iter()
+            .filter_map(prepare_atom)
+            .collect();
+
+        let mut filters = Vec::new();
+        for filter in rule
+            .positive_filters()
+            .iter()
+            .filter(|filter| filter.operation != FilterOperation::Equals)
+        {
+            let logical_type = variable_type(&filter.lhs);
+            let left = resolve(&equalities, Term::Variable(filter.lhs.clone()));
+            let right = resolve(&equalities, filter.rhs.clone());
+
+            match (
+                canonical_term(left, logical_type),
+                canonical_term(right, logical_type),
+            ) {
+                (Some(left), Some(right)) => filters.push(TopDownFilter {
+                    operation: filter.operation,
+                    left,
+                    right,
+                    logical_type,
+                }),
+                _ if filter.operation == FilterOperation::Unequals => {}
+                _ => return None,
+            }
+        }
+
+        Some(Self {
+            head,
+            positive_body,
+            negative_body,
+            filters,
+        })
+    }
+}
+
+/// Follow the given equalities starting from the given term.
+fn resolve(equalities: &Binding, mut term: Term) -> Term {
+    while let Term::Variable(variable) = &term {
+        match equalities.get(variable) {
+            Some(next) => term = next.clone(),
+            None => break,
+        }
+    }
+
+    term
+}
+
+/// Add an equality between two terms.
+/// Returns `false` if this makes two different constants equal.
+fn unify(equalities: &mut Binding, left: Term, right: Term) -> bool {
+    let left = resolve(equalities, left);
+    let right = resolve(equalities, right);
+
+    match (left, right) {
+        (left, right) if left == right => true,
+        (Term::Variable(variable), term) | (term, Term::Variable(variable)) => {
+            equalities.insert(variable, term);
+            true
+        }
+        _ => false,
+    }
+}
+
+/// Evaluates queries top-down, see the module documentation.
+#[derive(Debug)]
+pub(super) struct TopDownEvaluator {
+    rules: Vec<Rc<TopDownRule>>,
+    /// For each predicate, the indices of the rules that have it in their head
+    predicate_rules: HashMap<Identifier, Vec<usize>>,
+    /// Predicates that are derived by rules which cannot be evaluated top-down
+    unsupported: HashSet<Identifier>,
+    tables: BTreeMap<Subgoal, SubgoalTable>,
+    /// Number of answers and subgoals found so far
+    updates: usize,
+}
+
+impl TopDownEvaluator {
+    /// Create a new [`TopDownEvaluator`] for the given rules.
+    pub(super) fn new(
+        rules: &[ChaseRule],
+        rule_analysis: &[RuleAnalysis],
+        predicate_types: &HashMap<Identifier, Vec<PrimitiveType>>,
+    ) -> Self {
+        let mut result = Self {
+            rules: Vec::new(),
+            predicate_rules: HashMap::new(),
+            unsupported: HashSet::new(),
+            tables: BTreeMap::new(),
+            updates: 0,
+        };
+
+        for (rule, analysis) in rules.iter().zip(rule_analysis) {
+            if !TopDownRule::is_supported(rule, analysis) {
+                result
+                    .unsupported
+                    .extend(analysis.head_predicates.iter().cloned());
+                continue;
+            }
+
+            if let Some(prepared) = TopDownRule::new(rule, analysis, predicate_types) {
+                for predicate in &analysis.head_predicates {
+                    result
+                        .predicate_rules
+                        .entry(predicate.clone())
+                        .or_default()
+                        .push(result.rules.len());
+                }
+                result.rules.push(Rc::new(prepared));
+            }
+        }
+
+        result
+    }
+
+    /// Return all facts that match the given query atom, whose predicate has the given types.
+    pub(super) fn answer<Strategy: RuleSelectionStrategy>(
+        &mut self,
+        engine: &mut ExecutionEngine<Strategy>,
+        atom: &Atom,
+        types: &[PrimitiveType],
+    ) -> Result<Vec<Vec<PrimitiveLogicalValueT>>, Error> {
+        let mut terms = Vec::new();
+        for (term_tree, logical_type) in atom.term_trees().iter().zip(types) {
+            let TermOperation::Term(term) = term_tree.operation() else {
+                return Err(QueryError::UnsupportedTerm(format!("{term_tree:?}")).into());
+            };
+
+            match canonical_term(term.clone(), *logical_type) {
+                Some(term) => terms.push(term),
+                None => return Ok(Vec::new()),
+            }
+        }
+
+        let goal = Subgoal::new(atom.predicate(), &terms, &Binding::new());
+        self.complete(engine, &goal)?;
+
+        self.tables[&goal]
+            .answers
+            .iter()
+            .filter(|row| extend(&terms, row, &Binding::new()).is_some())
+            .map(|row| {
+                row.iter()
+                    .zip(types)
+                    .map(|(term, logical_type)| {
+                        logical_type
+                            .ground_term_to_logical_value(term.clone())
+                            .map_err(|error| TypeError::from(error).into())
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Compute all answers of the given subgoal.
+    fn complete<Strategy: RuleSelectionStrategy>(
+        &mut self,
+        engine: &mut ExecutionEngine<Strategy>,
+        goal: &Subgoal,
+    ) -> Result<(), Error> {
+        let mut scope = Scope::default();
+        self.register(engine, goal, &mut scope)?;
+
+        loop {
+            let updates = self.updates;
+
+            let mut index = 0;
+            while index < scope.subgoals.len() {
+                let subgoal = scope.subgoals[index].clone();
+                index += 1;
+
+                self.evaluate(engine, &subgoal, &mut scope)?;
+            }
+
+            if self.updates == updates {
+                break;
+            }
+        }
+
+        for subgoal in scope.subgoals {
+            if let Some(table) = self.tables.get_mut(&subgoal) {
+                table.complete = true;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Make sure that there is a table for the given subgoal, which initially contains its input facts.
+    /// If the subgoal is not complete, it is added to the given scope.
+    fn register<Strategy: RuleSelectionStrategy>(
+        &mut self,
+        engine: &mut ExecutionEngine<Strategy>,
+        subgoal: &Subgoal,
+        scope: &mut Scope,
+    ) -> Result<(), Error> {
+        match self.tables.get(subgoal) {
+            Some(table) if table.complete => return Ok(()),
+            Some(_) => {}
+            None => {
+                let answers = engine
+                    .query_inputs(&subgoal.atom())?
+                    .into_iter()
+                    .map(|row| row.into_iter().map(Term::from).collect())
+                    .collect();
+
+                self.tables.insert(
+                    subgoal.clone(),
+                    SubgoalTable {
+                        answers,
+                        complete: false,
+                    },
+                );
+                self.updates += 1;
+            }
+        }
+
+        scope.insert(subgoal);
+
+        Ok(())
+    }
+
+    /// Return the answers found so far for the given subgoal.
+    fn answers<Strategy: RuleSelectionStrategy>(
+        &mut self,
+        engine: &mut ExecutionEngine<Strategy>,
+        subgoal: &Subgoal,
+        scope: &mut Scope,
+    ) -> Result<Vec<Row>, Error> {
+        self.register(engine, subgoal, scope)?;
+
+        Ok(self.tables[subgoal].answers.iter().cloned().collect())
+    }
+
+    /// Apply all rules deriving the predicate of the given subgoal once.
+    fn evaluate<Strategy: RuleSelectionStrategy>(
+        &mut self,
+        engine: &mut ExecutionEngine<Strategy>,
+        subgoal: &Subgoal,
+        scope: &mut Scope,
+    ) -> Result<(), Error> {
+        if self.unsupported.contains(&subgoal.predicate) {
+            return Err(TopDownError::UnsupportedRule(subgoal.predicate.clone()).into());
+        }
+
+        let rule_indices = self
+            .predicate_rules
+            .get(&subgoal.predicate)
+            .cloned()
+            .unwrap_or_default();
+
+        for rule_index in rule_indices {
+            let rule = self.rules[rule_index].clone();
+
+            for head in rule
+                .head
+                .iter()
+                .filter(|atom| atom.predicate() == subgoal.predicate)
+            {
+                let Some(binding) = head.terms().iter().zip(&subgoal.pattern).try_fold(
+                    Binding::new(),
+                    |binding, (term, constant)| match constant {
+                        Some(constant) => extend(
+                            std::slice::from_ref(term),
+                            std::slice::from_ref(constant),
+                            &binding,
+                        ),
+                        None => Some(binding),
+                    },
+                ) else {
+                    continue;
+                };
+
+                for binding in self.resolve_body(engine, &rule, binding, scope)? {
+                    let row = head
+                        .terms()
+                        .iter()
+                        .map(|term| substitute(term, &binding))
+                        .collect();
+
+                    if let Some(table) = self.tables.get_mut(subgoal) {
+                        if table.answers.insert(row) {
+                            self.updates += 1;
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Return all extensions of the given binding that satisfy the body of the given rule,
+    /// based on the answers found so far.
+    fn resolve_body<Strategy: RuleSelectionStrategy>(
+        &mut self,
+        engine: &mut ExecutionEngine<Strategy>,
+        rule: &TopDownRule,
+        binding: Binding,
+        scope: &mut Scope,
+    ) -> Result<Vec<Binding>, Error> {
+        let mut bindings = vec![binding];
+        let mut remaining = rule.positive_body.iter().collect::<Vec<_>>();
+
+        while !remaining.is_empty() && !bindings.is_empty() {
+            // Continue with the atom that has the most bound terms,
+            // which is the same for all bindings, as they bind the same variables
+            let num_bound = |atom: &ChaseAtom| {
+                atom.terms()
+                    .iter()
+                    .filter(|term| !matches!(substitute(term, &bindings[0]), Term::Variable(_)))
+                    .count()
+            };
+            let mut next_index = 0;
+            for (index, atom) in remaining.iter().enumerate() {
+                if num_bound(atom) > num_bound(remaining[next_index]) {
+                    next_index = index;
+                }
+            }
+            let atom = remaining.remove(next_index);
+
+            let mut extended_bindings = Vec::new();
+            for binding in &bindings {
+                let subgoal = Subgoal::new(atom.predicate(), atom.terms(), binding);
+
+                for row in self.answers(engine, &subgoal, scope)? {
+                    extended_bindings.extend(extend(atom.terms(), &row, binding));
+                }
+            }
+
+            bindings = extended_bindings;
+        }
+
+        bindings.retain(|binding| rule.filters.iter().all(|filter| filter.holds(binding)));
+
+        let mut result = Vec::new();
+        'bindings: for binding in bindings {
+            for atom in &rule.negative_body {
+                let subgoal = Subgoal::new(atom.predicate(), atom.terms(), &binding);
+                self.complete(engine, &subgoal)?;
+
+                if self.tables[&subgoal]
+                    .answers
+                    .iter()
+                    .any(|row| extend(atom.terms(), row, &binding).is_some())
+                {
+                    continue 'bindings;
+                }
+            }
+
+            result.push(binding);
+        }
+
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        error::Error,
+        execution::test_util::{initialized_engine, sorted_strings},
+        io::parser::parse_atom,
+    };
+
+    use super::TopDownError;
+
+    /// Answer the given queries top-down, before and after materializing the program,
+    /// and compare the answers to the ones computed bottom-up.
+    fn compare_with_materialization(program: &str, queries: &[&str]) {
+        let mut engine = initialized_engine(program);
+
+        let top_down = queries
+            .iter()
+            .map(|query| {
+                sorted_strings(engine.query_top_down(&parse_atom(query).unwrap()).unwrap())
+            })
+            .collect::<Vec<_>>();
+
+        engine.execute().unwrap();
+
+        for (query, top_down) in queries.iter().zip(top_down) {
+            let atom = parse_atom(query).unwrap();
+            let bottom_up = sorted_strings(engine.query(&atom).unwrap());
+
+            assert_eq!(top_down, bottom_up, "{query}");
+            assert_eq!(
+                sorted_strings(engine.query_top_down(&atom).unwrap()),
+                bottom_up,
+                "{query}"
+            );
+        }
+    }
+
+    #[test]
+    fn recursive_queries() {
+        compare_with_materialization(
+            "edge(a, b) . edge(b, c) . edge(c, a) . edge(c, d) . edge(e, f) .\n\
+             reach(?x, ?y) :- edge(?x, ?y) .\n\
+             reach(?x, ?z) :- reach(?x, ?y), edge(?y, ?z) .\n\
+             loop(?x) :- reach(?x, ?x) .\n",
+            &[
+                "reach(a, ?x)",
+                "reach(?x, d)",
+                "reach(e, ?x)",
+                "reach(?x, ?y)",
+                "reach(d, a)",
+                "loop(?x)",
+                "reach(?x, ?x)",
+            ],
+        );
+    }
+
+    #[test]
+    fn constants_and_filters() {
+        compare_with_materialization(
+            "@declare age(any, integer) .\n\
+             age(alice, 31) . age(bob, 17) . age(carol, 65) . works(alice, acme) . works(carol, acme) .\n\
+             adult(?x) :- age(?x, ?y), ?y >= 18 .\n\
+             colleague(?x, ?y) :- works(?x, ?z), works(?y, ?z), ?x != ?y .\n\
+             acmeAdult(?x) :- adult(?x), works(?x, acme) .\n\
+             senior(?x, 65) :- age(?x, 65) .\n",
+            &[
+                "adult(?x)",
+                "adult(bob)",
+                "colleague(alice, ?x)",
+                "acmeAdult(?x)",
+                "senior(?x, ?y)",
+                "senior(?x, 64)",
+            ],
+        );
+    }
+
+    #[test]
+    fn negation() {
+        compare_with_materialization(
+            "edge(a, b) . edge(b, c) . edge(d, d) . node(a) . node(b) . node(c) . node(d) .\n\
+             reach(?x, ?y) :- edge(?x, ?y) .\n\
+             reach(?x, ?z) :- reach(?x, ?y), edge(?y, ?z) .\n\
+             unreachable(?x, ?y) :- node(?x), node(?y), ~reach(?x, ?y) .\n\
+             sink(?x) :- node(?x), ~edge(?x, ?y) .\n",
+            &["unreachable(a, ?x)", "unreachable(?x, ?y)", "sink(?x)"],
+        );
+    }
+
+    #[test]
+    fn existential_rules_are_unsupported() {
+        let mut engine = initialized_engine(
+            "person(alice) . friend(alice, bob) .\n\
+             parent(?x, !y) :- person(?x) .\n\
+             known(?x, ?y) :- friend(?x, ?y) .\n",
+        );
+
+        assert!(matches!(
+            engine.query_top_down(&parse_atom("parent(alice, ?x)").unwrap()),
+            Err(Error::TopDownError(TopDownError::UnsupportedRule(_)))
+        ));
+        assert_eq!(
+            engine
+                .query_top_down(&parse_atom("known(alice, ?x)").unwrap())
+                .unwrap()
+                .len(),
+            1
+        );
+    }
+}
@@ -4,14 +4,40 @@
 
 use std::io::Write;
 
+pub mod compiled_program;
+pub mod consequence_cache;
+pub mod encoding;
+pub mod environment;
 pub mod formats;
+pub mod input_cache;
 pub mod input_manager;
+pub mod input_statistics;
+#[cfg(feature = "kafka")]
+pub mod kafka;
+pub mod n3;
 pub mod output_file_manager;
 pub mod parser;
+pub mod r2rml;
 pub mod resource_providers;
+pub mod swrl;
+pub mod tptp;
+pub mod warm_start;
 
+pub use compiled_program::{CompiledProgram, CompiledProgramError, RuleVariableOrders};
+pub use consequence_cache::ConsequenceCache;
+pub use encoding::Encoding;
+pub use environment::{Environment, InterpolationError};
+pub use input_cache::InputCache;
 pub use input_manager::InputManager;
-pub use output_file_manager::OutputFileManager;
+pub use input_statistics::{InputStatistics, ResourceStatistics};
+pub use n3::N3Rules;
+pub use output_file_manager::{
+    FileSplitting, ManifestEntry, OutputFileManager, OutputFileWriter, OutputPolicy,
+};
+pub use r2rml::R2rmlMapping;
+pub use swrl::SwrlRules;
+pub use tptp::TptpProblem;
+pub use warm_start::WarmStart;
 
 use nemo_physical::dictionary::value_serializer::TrieSerializer;
 
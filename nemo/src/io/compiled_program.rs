@@ -0,0 +1,308 @@
+//! Compiled programs, which store a program together with the results of the expensive parts of its analysis.
+//!
+//! Computing the variable orders of the rules dominates the time needed to prepare large programs for reasoning.
+//! A [`CompiledProgram`] keeps the variable orders of a program that has been analyzed once,
+//! so that later runs can reuse them, see [`ExecutionEngine::initialize_with_variable_orders`][crate::execution::ExecutionEngine::initialize_with_variable_orders].
+//!
+//! Compiled programs are stored in a simple binary format, which is only read by the version of nemo that wrote it.
+
+use thiserror::Error;
+
+use crate::{
+    error::Error,
+    io::parser::parse_program,
+    model::{chase_model::ChaseProgram, Identifier, Variable},
+    program_analysis::variable_order::VariableOrder,
+};
+
+pub use crate::program_analysis::analysis::RuleVariableOrders;
+
+/// Bytes at the beginning of every compiled program.
+const MAGIC: &[u8] = b"NMOC";
+
+/// Version of nemo writing compiled programs,
+/// since variable orders are only valid for the normalization of the same version.
+const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Errors that can occur while reading a compiled program.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum CompiledProgramError {
+    /// The data does not start like a compiled program.
+    #[error("The file is not a compiled program.")]
+    NotCompiled,
+    /// The program was compiled by a different version.
+    #[error("The program was compiled by version {0} of nemo, but this is version {VERSION}; please compile it again.")]
+    Version(String),
+    /// The data ends unexpectedly or contains invalid values.
+    #[error("The compiled program is corrupted.")]
+    Corrupted,
+}
+
+/// A program together with the variable orders of its rules.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompiledProgram {
+    program: String,
+    variable_orders: Vec<RuleVariableOrders>,
+}
+
+impl CompiledProgram {
+    /// Create a new [`CompiledProgram`] from the text of a program and the variable orders of its rules.
+    pub fn new(program: String, variable_orders: Vec<RuleVariableOrders>) -> Self {
+        Self {
+            program,
+            variable_orders,
+        }
+    }
+
+    /// Validate and analyze the given program text and return it together with the variable orders of its rules.
+    ///
+    /// In contrast to initializing an [`ExecutionEngine`][crate::execution::ExecutionEngine],
+    /// this does not load the data sources of the program.
+    pub fn compile(program: String) -> Result<Self, Error> {
+        let parsed = parse_program(&program)?;
+        parsed.check_arities()?;
+
+        let mut chase_program: ChaseProgram = parsed.try_into()?;
+        chase_program.check_for_unsupported_features()?;
+        chase_program.normalize();
+
+        let variable_orders = chase_program.analyze()?.variable_orders();
+
+        Ok(Self::new(program, variable_orders))
+    }
+
+    /// Return the text of the program.
+    pub fn program(&self) -> &str {
+        &self.program
+    }
+
+    /// Return the variable orders of the rules, in the order of the normalized program.
+    pub fn variable_orders(&self) -> &[RuleVariableOrders] {
+        &self.variable_orders
+    }
+
+    /// Return the text of the program and the variable orders of its rules.
+    pub fn into_parts(self) -> (String, Vec<RuleVariableOrders>) {
+        (self.program, self.variable_orders)
+    }
+
+    /// Return whether the given data starts like a compiled program.
+    pub fn is_compiled_program(bytes: &[u8]) -> bool {
+        bytes.starts_with(MAGIC)
+    }
+
+    /// Encode the compiled program in its binary format.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut encoder = Encoder(MAGIC.to_vec());
+
+        encoder.string(VERSION);
+        encoder.string(&self.program);
+        encoder.number(self.variable_orders.len());
+        for orders in &self.variable_orders {
+            encoder.number(orders.promising.len());
+            for order in &orders.promising {
+                encoder.variable_order(order);
+            }
+            encoder.variable_order(&orders.existential_aux);
+        }
+
+        encoder.0
+    }
+
+    /// Decode a compiled program from its binary format.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, CompiledProgramError> {
+        let mut decoder = Decoder(
+            bytes
+                .strip_prefix(MAGIC)
+                .ok_or(CompiledProgramError::NotCompiled)?,
+        );
+
+        let version = decoder.string()?;
+        if version != VERSION {
+            return Err(CompiledProgramError::Version(version));
+        }
+
+        let program = decoder.string()?;
+        let variable_orders = (0..decoder.number()?)
+            .map(|_| {
+                let promising = (0..decoder.number()?)
+                    .map(|_| decoder.variable_order())
+                    .collect::<Result<_, _>>()?;
+                let existential_aux = decoder.variable_order()?;
+
+                Ok(RuleVariableOrders {
+                    promising,
+                    existential_aux,
+                })
+            })
+            .collect::<Result<_, _>>()?;
+
+        if !decoder.0.is_empty() {
+            return Err(CompiledProgramError::Corrupted);
+        }
+
+        Ok(Self {
+            program,
+            variable_orders,
+        })
+    }
+}
+
+/// Writes the values of a compiled program.
+struct Encoder(Vec<u8>);
+
+impl Encoder {
+    fn number(&mut self, number: usize) {
+        self.0.extend((number as u64).to_le_bytes());
+    }
+
+    fn string(&mut self, string: &str) {
+        self.number(string.len());
+        self.0.extend(string.as_bytes());
+    }
+
+    fn variable_order(&mut self, order: &VariableOrder) {
+        self.number(order.len());
+        for variable in order.iter() {
+            let (kind, Identifier(name)) = match variable {
+                Variable::Universal(name) => (b'?', name),
+                Variable::Existential(name) => (b'!', name),
+            };
+
+            self.0.push(kind);
+            self.string(name);
+        }
+    }
+}
+
+/// Reads the values of a compiled program.
+struct Decoder<'a>(&'a [u8]);
+
+impl<'a> Decoder<'a> {
+    fn take(&mut self, length: usize) -> Result<&'a [u8], CompiledProgramError> {
+        if self.0.len() < length {
+            return Err(CompiledProgramError::Corrupted);
+        }
+
+        let (result, rest) = self.0.split_at(length);
+        self.0 = rest;
+
+        Ok(result)
+    }
+
+    fn number(&mut self) -> Result<usize, CompiledProgramError> {
+        let bytes = self
+            .take(8)?
+            .try_into()
+            .expect("Exactly 8 bytes are taken.");
+
+        usize::try_from(u64::from_le_bytes(bytes)).map_err(|_| CompiledProgramError::Corrupted)
+    }
+
+    fn string(&mut self) -> Result<String, CompiledProgramError> {
+        let length = self.number()?;
+
+        String::from_utf8(self.take(length)?.to_vec()).map_err(|_| CompiledProgramError::Corrupted)
+    }
+
+    fn variable_order(&mut self) -> Result<VariableOrder, CompiledProgramError> {
+        let mut order = VariableOrder::new();
+        for _ in 0..self.number()? {
+            let kind = self.take(1)?[0];
+            let name = Identifier(self.string()?);
+
+            order.push(match kind {
+                b'?' => Variable::Universal(name),
+                b'!' => Variable::Existential(name),
+                _ => return Err(CompiledProgramError::Corrupted),
+            });
+        }
+
+        Ok(order)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        execution::{DefaultExecutionEngine, ExecutionEngine},
+        io::{parser::parse_program, resource_providers::ResourceProviders},
+    };
+
+    use super::{CompiledProgram, CompiledProgramError, MAGIC};
+
+    const PROGRAM: &str = "edge(1, 2) . edge(2, 3) .\n\
+                           path(?x, ?y) :- edge(?x, ?y) .\n\
+                           path(?x, ?z) :- path(?x, ?y), edge(?y, ?z) .\n\
+                           start(?x, !y) :- edge(?x, ?z), ~path(?z, ?x) .\n";
+
+    fn compile() -> CompiledProgram {
+        CompiledProgram::compile(PROGRAM.to_string()).unwrap()
+    }
+
+    #[test]
+    fn compiled_program_roundtrip() {
+        let compiled = compile();
+        let engine: DefaultExecutionEngine = ExecutionEngine::initialize(
+            parse_program(PROGRAM).unwrap(),
+            ResourceProviders::empty(),
+        )
+        .unwrap();
+        assert_eq!(compiled.variable_orders(), engine.variable_orders());
+
+        let bytes = compiled.to_bytes();
+
+        assert!(CompiledProgram::is_compiled_program(&bytes));
+        assert_eq!(CompiledProgram::from_bytes(&bytes), Ok(compiled.clone()));
+
+        let (program, variable_orders) = compiled.into_parts();
+        let mut engine: DefaultExecutionEngine = ExecutionEngine::initialize_with_variable_orders(
+            parse_program(program).unwrap(),
+            ResourceProviders::empty(),
+            variable_orders,
+            None,
+        )
+        .unwrap();
+        engine.execute().unwrap();
+
+        assert_eq!(engine.count_facts_of_derived_predicates(), 5);
+    }
+
+    #[test]
+    fn invalid_compiled_programs() {
+        let bytes = compile().to_bytes();
+
+        assert_eq!(
+            CompiledProgram::from_bytes(PROGRAM.as_bytes()),
+            Err(CompiledProgramError::NotCompiled)
+        );
+        assert_eq!(
+            CompiledProgram::from_bytes(&bytes[..bytes.len() - 1]),
+            Err(CompiledProgramError::Corrupted)
+        );
+
+        let mut other_version = MAGIC.to_vec();
+        other_version.extend(3u64.to_le_bytes());
+        other_version.extend(b"0.0");
+        assert_eq!(
+            CompiledProgram::from_bytes(&other_version),
+            Err(CompiledProgramError::Version("0.0".to_string()))
+        );
+    }
+
+    #[test]
+    fn variable_orders_of_other_program() {
+        let (_, variable_orders) = compile().into_parts();
+        let other = parse_program("edge(1, 2) . path(?x, ?y) :- edge(?y, ?x) .").unwrap();
+
+        let engine: Result<DefaultExecutionEngine, _> =
+            ExecutionEngine::initialize_with_variable_orders(
+                other,
+                ResourceProviders::empty(),
+                variable_orders,
+                None,
+            );
+
+        assert!(engine.is_err());
+    }
+}
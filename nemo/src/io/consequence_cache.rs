@@ -0,0 +1,409 @@
+//! Reusing the consequences of a program across runs in which the program is edited.
+//!
+//! After reasoning, the facts of each derived predicate can be stored in a cache directory,
+//! identified by a hash of everything they depend on, i.e. the rules that derive the predicate
+//! (directly or via other predicates) and the input facts of all predicates used by these rules.
+//! When the program is run again, e.g. after changing a single rule during rule development,
+//! predicates whose hash did not change are loaded from the cache and their rules are not applied,
+//! such that only the predicates affected by the change are computed again.
+//!
+//! Predicates are never cached if they depend on rules with existential variables,
+//! since their nulls cannot be restored, on functions registered by the application,
+//! whose behavior is unknown, or on external sources and SPARQL queries,
+//! whose content may change without notice.
+
+use std::{
+    collections::{BTreeSet, HashMap, HashSet},
+    fs::{create_dir_all, read_dir, remove_file, rename, File},
+    hash::Hasher,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    error::Error,
+    model::{
+        chase_model::{ChaseProgram, ChaseRule},
+        BuiltinFunction, DataSource, DsvFile, Identifier, NativeDataSource, PrimitiveType,
+    },
+    program_analysis::analysis::{ProgramAnalysis, RuleAnalysis},
+    util::stable_hash::StableHasher,
+};
+
+use super::{input_manager::InputManager, output_file_manager::FileFormat};
+
+/// Cached facts are stored as CSV files.
+const CACHE_FILE_FORMAT: FileFormat = FileFormat::DSV(b',');
+
+/// Directory containing the cached facts of derived predicates.
+#[derive(Debug, Clone)]
+pub struct ConsequenceCache {
+    directory: PathBuf,
+}
+
+impl ConsequenceCache {
+    /// Create a new [`ConsequenceCache`] which stores the facts of derived predicates in the given directory.
+    pub fn new(directory: PathBuf) -> Self {
+        Self { directory }
+    }
+
+    /// Return the directory containing the cached facts.
+    pub fn directory(&self) -> &Path {
+        &self.directory
+    }
+
+    /// Return the name of the files containing the cached facts of the given predicate,
+    /// without the hash and the extension.
+    fn file_stem(&self, predicate: &Identifier) -> String {
+        predicate
+            .sanitised_file_name(PathBuf::new())
+            .to_string_lossy()
+            .into_owned()
+    }
+
+    /// Return the file containing the facts of the given predicate for the given hash.
+    fn file(&self, predicate: &Identifier, hash: u64) -> PathBuf {
+        CACHE_FILE_FORMAT.file_name(
+            self.directory
+                .join(format!("{}.{hash:016x}", self.file_stem(predicate))),
+        )
+    }
+
+    /// Return `true` if the facts of the given predicate are cached for the given hash.
+    pub fn contains(&self, predicate: &Identifier, hash: u64) -> bool {
+        self.file(predicate, hash).is_file()
+    }
+
+    /// Return a data source for the cached facts of the given predicate,
+    /// which are parsed according to the given types.
+    /// Its rows are marked as sorted, since the facts have been stored in the order of their trie.
+    ///
+    /// Returns `None` if there are no cached facts for the given hash.
+    pub fn data_source(
+        &self,
+        predicate: &Identifier,
+        hash: u64,
+        types: &[PrimitiveType],
+    ) -> Option<DsvFile> {
+        let path = self.file(predicate, hash);
+        if !path.is_file() {
+            return None;
+        }
+
+        // The path is made absolute, so that it is not resolved relative to the input directory.
+        let path = path.canonicalize().unwrap_or(path);
+
+        Some(
+            DsvFile::new(
+                &path.to_string_lossy(),
+                b',',
+                types.iter().copied().collect(),
+            )
+            .with_sorted_rows(),
+        )
+    }
+
+    /// Store the given facts of a predicate for the given hash,
+    /// replacing the facts stored for other hashes.
+    pub fn store(
+        &self,
+        predicate: &Identifier,
+        hash: u64,
+        records: impl Iterator<Item = Vec<String>>,
+    ) -> Result<(), Error> {
+        create_dir_all(&self.directory)?;
+
+        // The facts are written to a temporary file first,
+        // so that an interrupted run does not leave incomplete facts behind.
+        let path = self.file(predicate, hash);
+        let temporary_path = path.with_extension("tmp");
+        {
+            let mut writer = CACHE_FILE_FORMAT.create_writer(File::create(&temporary_path)?);
+            for record in records {
+                writer.write_record(record)?;
+            }
+        }
+        rename(&temporary_path, &path)?;
+
+        self.remove_stale_files(predicate, &path)
+    }
+
+    /// Remove all files containing facts of the given predicate, except for the given one.
+    fn remove_stale_files(&self, predicate: &Identifier, current: &Path) -> Result<(), Error> {
+        let stem = self.file_stem(predicate);
+
+        for entry in read_dir(&self.directory)? {
+            let path = entry?.path();
+            if path == current {
+                continue;
+            }
+
+            let is_stale = path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .and_then(|name| name.strip_prefix(&stem))
+                .and_then(|name| name.strip_prefix('.'))
+                .and_then(|name| name.strip_suffix(".csv"))
+                .is_some_and(|hash| {
+                    hash.len() == 16 && hash.chars().all(|digit| digit.is_ascii_hexdigit())
+                });
+            if is_stale {
+                remove_file(path)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Return a description of the given rule which determines its consequences,
+/// or `None` if its consequences cannot be cached.
+fn rule_fingerprint(rule: &ChaseRule, analysis: &RuleAnalysis) -> Option<String> {
+    if analysis.is_existential {
+        return None;
+    }
+
+    let mut constructors = rule.constructors().iter().collect::<Vec<_>>();
+    constructors.sort_by_key(|&(variable, _)| variable);
+
+    let uses_registered_functions = constructors.iter().any(|(_, tree)| {
+        tree.functions()
+            .into_iter()
+            .any(|(identifier, _)| BuiltinFunction::from_identifier(identifier).is_none())
+    });
+    if uses_registered_functions {
+        return None;
+    }
+
+    Some(format!(
+        "rule {:?} {constructors:?} {:?} {:?} {:?} {:?} {:?} {}",
+        rule.head(),
+        rule.positive_body(),
+        rule.positive_filters(),
+        rule.negative_body(),
+        rule.negative_filters(),
+        rule.aggregate(),
+        rule.priority()
+    ))
+}
+
+/// Return descriptions of the input facts of each predicate,
+/// or `None` for predicates whose input facts cannot be cached.
+fn input_fingerprints(
+    program: &ChaseProgram,
+    input_manager: &InputManager,
+) -> HashMap<Identifier, Option<Vec<String>>> {
+    let mut result = HashMap::<Identifier, Option<Vec<String>>>::new();
+
+    for declaration in program.sources() {
+        let fingerprint = match &declaration.source {
+            NativeDataSource::External(_) | NativeDataSource::SparqlQuery(_) => None,
+            source => source
+                .resources()
+                .iter()
+                .map(|resource| input_manager.resource_hash(resource).ok())
+                .collect::<Option<Vec<_>>>()
+                .map(|hashes| format!("source {declaration:?} {hashes:x?}")),
+        };
+
+        let entry = result
+            .entry(declaration.predicate.clone())
+            .or_insert_with(|| Some(Vec::new()));
+        match (entry.as_mut(), fingerprint) {
+            (Some(fingerprints), Some(fingerprint)) => fingerprints.push(fingerprint),
+            _ => *entry = None,
+        }
+    }
+
+    for fact in program.facts() {
+        if let Some(fingerprints) = result
+            .entry(fact.0.predicate())
+            .or_insert_with(|| Some(Vec::new()))
+        {
+            fingerprints.push(format!("fact {:?}", fact.0));
+        }
+    }
+
+    result
+}
+
+/// Compute the hash of everything the facts of each derived predicate depend on,
+/// see the module documentation.
+///
+/// Derived predicates whose facts cannot be cached are omitted.
+pub(crate) fn consequence_hashes(
+    program: &ChaseProgram,
+    analysis: &ProgramAnalysis,
+    input_manager: &InputManager,
+) -> HashMap<Identifier, u64> {
+    let rule_fingerprints = program
+        .rules()
+        .iter()
+        .zip(&analysis.rule_analysis)
+        .map(|(rule, analysis)| rule_fingerprint(rule, analysis))
+        .collect::<Vec<_>>();
+    let input_fingerprints = input_fingerprints(program, input_manager);
+
+    let mut predicate_rules = HashMap::<&Identifier, Vec<usize>>::new();
+    for (index, rule_analysis) in analysis.rule_analysis.iter().enumerate() {
+        for predicate in &rule_analysis.head_predicates {
+            predicate_rules.entry(predicate).or_default().push(index);
+        }
+    }
+
+    let mut result = HashMap::new();
+
+    'predicates: for predicate in &analysis.derived_predicates {
+        // Collect the predicates and rules the facts of the predicate depend on
+        let mut predicates = HashSet::from([predicate]);
+        let mut rules = BTreeSet::new();
+        let mut stack = vec![predicate];
+        while let Some(current) = stack.pop() {
+            for &index in predicate_rules.get(current).into_iter().flatten() {
+                if !rules.insert(index) {
+                    continue;
+                }
+
+                let rule_analysis = &analysis.rule_analysis[index];
+                for body_predicate in rule_analysis
+                    .positive_body_predicates
+                    .iter()
+                    .chain(&rule_analysis.negative_body_predicates)
+                {
+                    if predicates.insert(body_predicate) {
+                        stack.push(body_predicate);
+                    }
+                }
+            }
+        }
+
+        let mut fingerprints = Vec::new();
+        for index in rules {
+            let Some(fingerprint) = &rule_fingerprints[index] else {
+                continue 'predicates;
+            };
+            fingerprints.push(fingerprint.clone());
+        }
+        for current in predicates {
+            match input_fingerprints.get(current) {
+                Some(Some(inputs)) => fingerprints.extend(inputs.iter().cloned()),
+                Some(None) => continue 'predicates,
+                None => {}
+            }
+
+            fingerprints.push(format!(
+                "predicate {current} {:?} {:?} {} {}",
+                analysis.predicate_types.get(current),
+                program.predicate_limits().get(current),
+                program.annotated_predicates().contains(current),
+                program.temporal_predicates().contains(current),
+            ));
+        }
+        fingerprints.sort();
+
+        let mut hasher = StableHasher::default();
+        hasher.write(predicate.name().as_bytes());
+        for fingerprint in fingerprints {
+            hasher.write(b"\n");
+            hasher.write(fingerprint.as_bytes());
+        }
+
+        result.insert(predicate.clone(), hasher.finish());
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use assert_fs::TempDir;
+
+    use super::{consequence_hashes, ConsequenceCache};
+    use crate::{
+        io::{
+            input_manager::InputManager, parser::parse_program,
+            resource_providers::ResourceProviders,
+        },
+        model::{chase_model::ChaseProgram, Identifier},
+    };
+
+    fn hashes(program: &str) -> HashMap<String, u64> {
+        let mut program: ChaseProgram = parse_program(program).unwrap().try_into().unwrap();
+        program.normalize();
+        let analysis = program.analyze().unwrap();
+
+        consequence_hashes(
+            &program,
+            &analysis,
+            &InputManager::new(ResourceProviders::empty()),
+        )
+        .into_iter()
+        .map(|(predicate, hash)| (predicate.name(), hash))
+        .collect()
+    }
+
+    #[test]
+    fn hashes_depend_on_rules_and_inputs() {
+        let original = hashes(
+            "edge(a, b) . node(a) .\n\
+             reach(?x, ?y) :- edge(?x, ?y) .\n\
+             reach(?x, ?z) :- reach(?x, ?y), edge(?y, ?z) .\n\
+             isolated(?x) :- node(?x), ~reach(?x, ?y) .\n\
+             labeled(?x) :- node(?x) .\n",
+        );
+        let edited_rule = hashes(
+            "edge(a, b) . node(a) .\n\
+             reach(?x, ?y) :- edge(?x, ?y) .\n\
+             reach(?x, ?z) :- reach(?x, ?y), edge(?y, ?z) .\n\
+             isolated(?x) :- node(?x), ~reach(?x, ?y) .\n\
+             labeled(?y) :- node(?y), edge(?y, ?z) .\n",
+        );
+        let edited_input = hashes(
+            "edge(a, c) . node(a) .\n\
+             reach(?x, ?y) :- edge(?x, ?y) .\n\
+             reach(?x, ?z) :- reach(?x, ?y), edge(?y, ?z) .\n\
+             isolated(?x) :- node(?x), ~reach(?x, ?y) .\n\
+             labeled(?x) :- node(?x) .\n",
+        );
+
+        assert_eq!(original["reach"], edited_rule["reach"]);
+        assert_eq!(original["isolated"], edited_rule["isolated"]);
+        assert_ne!(original["labeled"], edited_rule["labeled"]);
+
+        assert_ne!(original["reach"], edited_input["reach"]);
+        assert_ne!(original["isolated"], edited_input["isolated"]);
+        assert_eq!(original["labeled"], edited_input["labeled"]);
+    }
+
+    #[test]
+    fn existential_rules_are_not_cached() {
+        let hashes = hashes(
+            "person(alice) .\n\
+             parent(?x, !y) :- person(?x) .\n\
+             ancestor(?x, ?y) :- parent(?x, ?y) .\n\
+             known(?x) :- person(?x) .\n",
+        );
+
+        assert!(!hashes.contains_key("parent"));
+        assert!(!hashes.contains_key("ancestor"));
+        assert!(hashes.contains_key("known"));
+    }
+
+    #[test]
+    fn stores_facts() {
+        let directory = TempDir::new().unwrap();
+        let cache = ConsequenceCache::new(directory.path().join("cache"));
+        let predicate = Identifier("reach".to_string());
+        let records = || vec![vec!["a".to_string(), "b".to_string()]].into_iter();
+
+        assert!(!cache.contains(&predicate, 1));
+
+        cache.store(&predicate, 1, records()).unwrap();
+        assert!(cache.contains(&predicate, 1));
+
+        cache.store(&predicate, 2, records()).unwrap();
+        assert!(cache.contains(&predicate, 2));
+        assert!(!cache.contains(&predicate, 1));
+    }
+}
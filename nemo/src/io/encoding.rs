@@ -0,0 +1,222 @@
+//! Decoding of input files that are not encoded in UTF-8
+
+use std::{io::Read, str::FromStr};
+
+use crate::io::parser::ParseError;
+
+/// Character encoding of an input file.
+///
+/// Files are decoded into UTF-8 while they are read, such that legacy exports can be loaded.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Encoding {
+    /// UTF-8, where lines with invalid byte sequences are ignored when reading DSV files
+    #[default]
+    Utf8,
+    /// UTF-8, where invalid byte sequences are replaced by `U+FFFD`
+    Utf8Lossy,
+    /// ISO 8859-1, which maps every byte to the Unicode code point with the same value
+    Latin1,
+    /// Windows code page 1252, which extends ISO 8859-1 by printable characters in the range `0x80`–`0x9F`
+    Windows1252,
+}
+
+impl Encoding {
+    /// Characters of Windows code page 1252 for the bytes `0x80`–`0x9F`,
+    /// where unassigned bytes are mapped to the control characters of ISO 8859-1
+    const WINDOWS_1252_HIGH: [char; 32] = [
+        '€', '\u{81}', '‚', 'ƒ', '„', '…', '†', '‡', 'ˆ', '‰', 'Š', '‹', 'Œ', '\u{8d}', 'Ž',
+        '\u{8f}', '\u{90}', '‘', '’', '“', '”', '•', '–', '—', '˜', '™', 'š', '›', 'œ', '\u{9d}',
+        'ž', 'Ÿ',
+    ];
+
+    /// Wrap a reader of bytes in this encoding into a reader of the UTF-8 encoded text.
+    pub fn decode<R: Read + 'static>(self, reader: R) -> Box<dyn Read> {
+        match self {
+            Self::Utf8 => Box::new(reader),
+            _ => Box::new(DecodingReader::new(reader, self)),
+        }
+    }
+
+    /// Decode the given bytes and return the number of bytes that were decoded,
+    /// which is smaller than the length of the input if it ends in an incomplete character.
+    fn decode_bytes(self, input: &[u8], output: &mut String) -> usize {
+        match self {
+            Self::Utf8 | Self::Utf8Lossy => {
+                let mut remaining = input;
+
+                loop {
+                    match std::str::from_utf8(remaining) {
+                        Ok(valid) => {
+                            output.push_str(valid);
+                            return input.len();
+                        }
+                        Err(error) => {
+                            let (valid, invalid) = remaining.split_at(error.valid_up_to());
+                            output.push_str(
+                                std::str::from_utf8(valid).expect("prefix has been validated"),
+                            );
+
+                            match error.error_len() {
+                                Some(length) => {
+                                    output.push(char::REPLACEMENT_CHARACTER);
+                                    remaining = &invalid[length..];
+                                }
+                                None => return input.len() - invalid.len(),
+                            }
+                        }
+                    }
+                }
+            }
+            Self::Latin1 => {
+                output.extend(input.iter().map(|&byte| char::from(byte)));
+                input.len()
+            }
+            Self::Windows1252 => {
+                output.extend(input.iter().map(|&byte| match byte {
+                    0x80..=0x9f => Self::WINDOWS_1252_HIGH[usize::from(byte - 0x80)],
+                    _ => char::from(byte),
+                }));
+                input.len()
+            }
+        }
+    }
+}
+
+impl FromStr for Encoding {
+    type Err = ParseError;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name.to_ascii_lowercase().as_str() {
+            "utf-8" | "utf8" => Ok(Self::Utf8),
+            "utf-8-lossy" | "utf8-lossy" => Ok(Self::Utf8Lossy),
+            "latin-1" | "latin1" | "iso-8859-1" => Ok(Self::Latin1),
+            "windows-1252" | "cp1252" => Ok(Self::Windows1252),
+            _ => Err(ParseError::UnknownEncoding(name.to_string())),
+        }
+    }
+}
+
+impl std::fmt::Display for Encoding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Utf8 => write!(f, "utf-8"),
+            Self::Utf8Lossy => write!(f, "utf-8-lossy"),
+            Self::Latin1 => write!(f, "latin-1"),
+            Self::Windows1252 => write!(f, "windows-1252"),
+        }
+    }
+}
+
+/// Reader that decodes the bytes of another reader into UTF-8.
+struct DecodingReader<R> {
+    reader: R,
+    encoding: Encoding,
+    /// Bytes of the input that have not been decoded yet
+    input: Vec<u8>,
+    /// Decoded bytes that have not been read yet
+    output: Vec<u8>,
+    /// Position of the first unread byte of the output
+    position: usize,
+}
+
+impl<R: Read> DecodingReader<R> {
+    /// Number of bytes read from the underlying reader at once
+    const CHUNK_SIZE: usize = 8192;
+
+    fn new(reader: R, encoding: Encoding) -> Self {
+        Self {
+            reader,
+            encoding,
+            input: Vec::new(),
+            output: Vec::new(),
+            position: 0,
+        }
+    }
+
+    /// Decode the next chunk of the underlying reader.
+    /// Returns `false` if the underlying reader is exhausted.
+    fn fill_output(&mut self) -> std::io::Result<bool> {
+        let start = self.input.len();
+        self.input.resize(start + Self::CHUNK_SIZE, 0);
+        let read = self.reader.read(&mut self.input[start..])?;
+        self.input.truncate(start + read);
+
+        let mut decoded = String::new();
+        let consumed = self.encoding.decode_bytes(&self.input, &mut decoded);
+        if read == 0 && consumed < self.input.len() {
+            // Incomplete characters at the end of the input are invalid
+            decoded.push(char::REPLACEMENT_CHARACTER);
+            self.input.clear();
+        } else {
+            self.input.drain(..consumed);
+        }
+
+        self.output = decoded.into_bytes();
+        self.position = 0;
+
+        Ok(read > 0 || !self.output.is_empty())
+    }
+}
+
+impl<R: Read> Read for DecodingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        while self.position == self.output.len() {
+            if !self.fill_output()? {
+                return Ok(0);
+            }
+        }
+
+        let length = buf.len().min(self.output.len() - self.position);
+        buf[..length].copy_from_slice(&self.output[self.position..self.position + length]);
+        self.position += length;
+
+        Ok(length)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::io::{Cursor, Read};
+
+    use super::Encoding;
+
+    fn decode(encoding: Encoding, input: &[u8]) -> String {
+        let mut result = String::new();
+        encoding
+            .decode(Cursor::new(input.to_vec()))
+            .read_to_string(&mut result)
+            .unwrap();
+        result
+    }
+
+    #[test]
+    fn decode_legacy_encodings() {
+        let input = b"caf\xe9,\x80 5,\x93quoted\x94\n";
+
+        assert_eq!(
+            decode(Encoding::Latin1, input),
+            "café,\u{80} 5,\u{93}quoted\u{94}\n"
+        );
+        assert_eq!(decode(Encoding::Windows1252, input), "café,€ 5,“quoted”\n");
+        assert_eq!(
+            decode(Encoding::Utf8Lossy, input),
+            "caf\u{fffd},\u{fffd} 5,\u{fffd}quoted\u{fffd}\n"
+        );
+        assert_eq!(decode(Encoding::Utf8Lossy, "café".as_bytes()), "café");
+        assert_eq!(decode(Encoding::Utf8Lossy, b"caf\xc3"), "caf\u{fffd}");
+    }
+
+    #[test]
+    fn decode_across_chunks() {
+        // The two bytes of `é` are read in different chunks
+        let text = format!("{}é", "a".repeat(8191));
+        assert_eq!(decode(Encoding::Utf8Lossy, text.as_bytes()), text);
+    }
+
+    #[test]
+    fn parse_encoding() {
+        assert_eq!("Latin-1".parse::<Encoding>().unwrap(), Encoding::Latin1);
+        assert_eq!("cp1252".parse::<Encoding>().unwrap(), Encoding::Windows1252);
+        assert!("ebcdic".parse::<Encoding>().is_err());
+    }
+}
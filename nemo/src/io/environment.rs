@@ -0,0 +1,160 @@
+//! Interpolation of variables in the resources of data sources.
+//!
+//! Resources of data sources may refer to variables as `${NAME}`,
+//! e.g. `@source edge[2]: load-csv("${DATA_DIR}/edges.csv") .`,
+//! so that the same program can be used in different environments.
+//! Variables are looked up in the environment of the process,
+//! and then in the variables read from a `.env` file (see [`Environment::with_dotenv_file`]).
+//! Using a variable that is not set is an error.
+//! A literal `${` can be written as `$${`.
+
+use std::{collections::HashMap, fs::read_to_string, path::Path};
+
+use thiserror::Error;
+
+use crate::error::{Error, ReadingError};
+
+/// Errors that can occur while interpolating variables
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum InterpolationError {
+    /// A variable is not set
+    #[error("The variable \"{0}\" is not set in the environment or in a .env file (write \"$${{\" for a literal \"${{\").")]
+    UnsetVariable(String),
+    /// A variable is not terminated by `}`
+    #[error("The variable starting with \"${{{0}\" is not terminated by \"}}\".")]
+    UnterminatedVariable(String),
+    /// A line of a `.env` file is not of the form `NAME=VALUE`
+    #[error("Line {0} of the .env file is not of the form NAME=VALUE.")]
+    InvalidDotenvLine(usize),
+}
+
+/// Variables that can be used in the resources of data sources, see the module documentation.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Environment {
+    /// Variables read from `.env` files
+    dotenv: HashMap<String, String>,
+}
+
+impl Environment {
+    /// Add the variables of the given `.env` file,
+    /// which contains lines of the form `NAME=VALUE`, optionally preceded by `export`.
+    /// Values may be enclosed in single or double quotes.
+    /// Empty lines and lines starting with `#` are ignored.
+    pub fn with_dotenv_file(self, path: &Path) -> Result<Self, Error> {
+        let content = read_to_string(path).map_err(|error| ReadingError::IOReading {
+            error,
+            filename: path.to_path_buf(),
+        })?;
+
+        Ok(self.with_dotenv(&content)?)
+    }
+
+    /// Add the variables of the given content of a `.env` file, see [`Environment::with_dotenv_file`].
+    pub fn with_dotenv(mut self, content: &str) -> Result<Self, InterpolationError> {
+        for (index, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let line = line.strip_prefix("export ").unwrap_or(line);
+            let Some((name, value)) = line.split_once('=') else {
+                return Err(InterpolationError::InvalidDotenvLine(index + 1));
+            };
+            let value = value.trim();
+            let value = [('"', '"'), ('\'', '\'')]
+                .iter()
+                .find_map(|(start, end)| value.strip_prefix(*start)?.strip_suffix(*end))
+                .unwrap_or(value);
+
+            self.dotenv
+                .insert(name.trim().to_string(), value.to_string());
+        }
+
+        Ok(self)
+    }
+
+    /// Return the value of the given variable,
+    /// where variables of the process take precedence over those of `.env` files.
+    fn value(&self, name: &str) -> Option<String> {
+        std::env::var(name)
+            .ok()
+            .or_else(|| self.dotenv.get(name).cloned())
+    }
+
+    /// Replace all variables in the given resource by their values.
+    pub fn interpolate(&self, resource: &str) -> Result<String, InterpolationError> {
+        let mut result = String::new();
+        let mut remainder = resource;
+
+        while let Some(start) = remainder.find('$') {
+            result.push_str(&remainder[..start]);
+            remainder = &remainder[start..];
+
+            if let Some(rest) = remainder.strip_prefix("$${") {
+                result.push_str("${");
+                remainder = rest;
+            } else if let Some(rest) = remainder.strip_prefix("${") {
+                let Some(end) = rest.find('}') else {
+                    return Err(InterpolationError::UnterminatedVariable(rest.to_string()));
+                };
+                let name = &rest[..end];
+
+                let value = self
+                    .value(name)
+                    .ok_or_else(|| InterpolationError::UnsetVariable(name.to_string()))?;
+                result.push_str(&value);
+                remainder = &rest[end + 1..];
+            } else {
+                result.push('$');
+                remainder = &remainder[1..];
+            }
+        }
+        result.push_str(remainder);
+
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Environment, InterpolationError};
+
+    #[test]
+    fn interpolation() {
+        let environment = Environment::default()
+            .with_dotenv(
+                "# data of the staging system\n\
+                 export NEMO_TEST_DATA_DIR=\"/data/staging\"\n\
+                 NEMO_TEST_SUFFIX = 2024\n",
+            )
+            .unwrap();
+
+        assert_eq!(
+            environment
+                .interpolate("${NEMO_TEST_DATA_DIR}/edges-${NEMO_TEST_SUFFIX}.csv")
+                .unwrap(),
+            "/data/staging/edges-2024.csv"
+        );
+        assert_eq!(
+            environment
+                .interpolate("price$.csv $${NEMO_TEST_DATA_DIR}")
+                .unwrap(),
+            "price$.csv ${NEMO_TEST_DATA_DIR}"
+        );
+        assert_eq!(
+            environment.interpolate("${NEMO_TEST_UNSET}/edges.csv"),
+            Err(InterpolationError::UnsetVariable(
+                "NEMO_TEST_UNSET".to_string()
+            ))
+        );
+        assert!(matches!(
+            environment.interpolate("${NEMO_TEST_DATA_DIR"),
+            Err(InterpolationError::UnterminatedVariable(_))
+        ));
+        assert_eq!(
+            Environment::default().with_dotenv("NEMO_TEST_SUFFIX"),
+            Err(InterpolationError::InvalidDotenvLine(1))
+        );
+    }
+}
@@ -1,9 +1,21 @@
 //! The input and output formats supported by Nemo.
 
+pub mod custom;
 pub mod dsv;
+pub mod external;
+pub mod hdt;
+pub mod rdf_format;
 pub mod rdf_triples;
+#[cfg(feature = "xlsx")]
+pub mod xlsx;
 
+pub use custom::ImportFormat;
 pub use dsv::DSVReader;
+pub use external::ExternalSourceReader;
+pub use hdt::HDTReader;
+pub use rdf_format::RdfFormat;
 pub use rdf_triples::RDFTriplesReader;
+#[cfg(feature = "xlsx")]
+pub use xlsx::XlsxReader;
 
 const PROGRESS_NOTIFY_INCREMENT: u64 = 1_000_000;
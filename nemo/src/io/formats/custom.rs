@@ -0,0 +1,195 @@
+//! Import formats that are provided by other crates.
+
+use std::fmt::Debug;
+
+use nemo_physical::{
+    error::ReadingError,
+    table_reader::{Resource, TableReader},
+};
+
+use crate::{io::resource_providers::ResourceProviders, model::PrimitiveType};
+
+/// A format of resources, whose facts are read by a [`TableReader`] implemented outside of nemo.
+///
+/// Programs use such a format via `@source p[2]: load("edges.json") .`,
+/// where the format is detected by the extension of the resource,
+/// or via `@source p[2]: load("edges", format="json") .`,
+/// where the format is registered under the given name
+/// with [`ResourceProviders::with_format`].
+pub trait ImportFormat: Debug {
+    /// Return the extensions of resources in this format, e.g. `["json", "jsonl"]`,
+    /// which are used to detect the format if a source does not name it.
+    ///
+    /// The extensions are matched after removing a trailing `.gz`.
+    fn extensions(&self) -> Vec<String>;
+
+    /// Return a [`TableReader`] for the facts of the given resource,
+    /// whose columns are read as the given types.
+    ///
+    /// The resource can be opened with [`ResourceProviders::open_resource`].
+    fn reader(
+        &self,
+        resource_providers: ResourceProviders,
+        resource: &Resource,
+        logical_types: Vec<PrimitiveType>,
+    ) -> Result<Box<dyn TableReader>, ReadingError>;
+
+    /// Return whether the given resource is detected as this format by its extension.
+    fn matches_extension(&self, resource: &Resource) -> bool {
+        let resource = resource.strip_suffix(".gz").unwrap_or(resource);
+
+        self.extensions().iter().any(|extension| {
+            resource
+                .strip_suffix(extension.as_str())
+                .is_some_and(|rest| rest.ends_with('.'))
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        io::{BufRead, BufReader, Cursor, Read},
+        rc::Rc,
+    };
+
+    use nemo_physical::{
+        error::ReadingError,
+        table_reader::{Resource, TableReader},
+    };
+
+    use crate::{
+        error::Error,
+        execution::DefaultExecutionEngine,
+        io::{
+            formats::ExternalSourceReader,
+            parser::parse_program,
+            resource_providers::{
+                external::CallbackSourceProvider, ResourceProvider, ResourceProviders,
+            },
+        },
+        model::{
+            CustomFormatFile, ExternalSource, Identifier, PrimitiveType, Term, TupleConstraint,
+        },
+    };
+
+    use super::ImportFormat;
+
+    /// Format with one fact per line, whose terms are separated by spaces.
+    #[derive(Debug)]
+    struct SpaceSeparated;
+
+    impl ImportFormat for SpaceSeparated {
+        fn extensions(&self) -> Vec<String> {
+            vec!["ssv".to_string()]
+        }
+
+        fn reader(
+            &self,
+            resource_providers: ResourceProviders,
+            resource: &Resource,
+            logical_types: Vec<PrimitiveType>,
+        ) -> Result<Box<dyn TableReader>, ReadingError> {
+            let reader = BufReader::new(resource_providers.open_resource(resource, true)?);
+            let facts = reader
+                .lines()
+                .map(|line| {
+                    Ok(line?
+                        .split_whitespace()
+                        .map(|name| Term::Constant(Identifier(name.to_string())))
+                        .collect())
+                })
+                .collect::<Result<Vec<Vec<Term>>, std::io::Error>>()?;
+
+            Ok(Box::new(ExternalSourceReader::new(
+                &ExternalSource::new(resource, TupleConstraint::from_arity(logical_types.len())),
+                Rc::new(CallbackSourceProvider::new(move || facts.clone())),
+                logical_types,
+            )))
+        }
+    }
+
+    /// Provides every resource with the same content.
+    #[derive(Debug)]
+    struct ConstantResourceProvider(&'static str);
+
+    impl ResourceProvider for ConstantResourceProvider {
+        fn open_resource(
+            &self,
+            _resource: &Resource,
+        ) -> Result<Option<Box<dyn Read>>, ReadingError> {
+            Ok(Some(Box::new(Cursor::new(self.0))))
+        }
+    }
+
+    fn reachable(program: &str, resource_providers: ResourceProviders) -> Result<usize, Error> {
+        let program = format!(
+            "{program}\n\
+             reachable(?X, ?Y) :- edge(?X, ?Y) .\n\
+             reachable(?X, ?Z) :- reachable(?X, ?Y), edge(?Y, ?Z) .\n"
+        );
+
+        let mut engine =
+            DefaultExecutionEngine::initialize(parse_program(program)?, resource_providers)?;
+        engine.execute()?;
+
+        Ok(engine
+            .count_facts_of_predicate(&Identifier("reachable".to_string()))
+            .unwrap_or(0))
+    }
+
+    fn resource_providers() -> ResourceProviders {
+        ResourceProviders::from(vec![Box::new(ConstantResourceProvider("a b\nb c\n"))])
+            .with_format("ssv", SpaceSeparated)
+    }
+
+    #[test]
+    fn detect_format_by_extension() {
+        assert_eq!(
+            reachable(
+                "@source edge[any, any]: load(\"edges.ssv\") .",
+                resource_providers()
+            )
+            .unwrap(),
+            3
+        );
+        assert!(SpaceSeparated.matches_extension(&"edges.ssv.gz".to_string()));
+        assert!(!SpaceSeparated.matches_extension(&"edgesssv".to_string()));
+    }
+
+    #[test]
+    fn explicit_format() {
+        assert_eq!(
+            reachable(
+                "@source edge[any, any]: load(\"edges.txt\", format=\"ssv\") .",
+                resource_providers()
+            )
+            .unwrap(),
+            3
+        );
+    }
+
+    #[test]
+    fn undetected_format() {
+        let source = CustomFormatFile::new("edges.txt", None, TupleConstraint::from_arity(2));
+
+        assert!(matches!(
+            resource_providers().import_format(&source),
+            Err(ReadingError::FormatNotDetected(_))
+        ));
+    }
+
+    #[test]
+    fn unregistered_format() {
+        let source = CustomFormatFile::new(
+            "edges.ssv",
+            Some("json".to_string()),
+            TupleConstraint::from_arity(2),
+        );
+
+        assert!(matches!(
+            resource_providers().import_format(&source),
+            Err(ReadingError::FormatNotRegistered(_))
+        ));
+    }
+}
@@ -68,7 +68,7 @@
 //! #     ],
 //! # );
 //! # let table_reader:Box<dyn TableReader> = Box::new(csv_reader);
-//! # let mut dict = RefCell::new(nemo_physical::dictionary::PrefixedStringDictionary::default());
+//! # let mut dict = RefCell::new(nemo_physical::management::database::Dict::default());
 //! let mut builder = vec![
 //!     PhysicalBuilderProxyEnum::String(PhysicalStringColumnBuilderProxy::new(&dict)),
 //!     PhysicalBuilderProxyEnum::I64(Default::default()),
@@ -86,12 +86,14 @@ use csv::{Reader, ReaderBuilder};
 use nemo_physical::builder_proxy::{ColumnBuilderProxy, PhysicalBuilderProxyEnum};
 use nemo_physical::table_reader::{Resource, TableReader};
 
-use crate::model::types::primitive_logical_value::{LogicalFloat64, LogicalInteger, LogicalString};
+use crate::model::types::primitive_logical_value::{
+    LogicalDateTime, LogicalFloat64, LogicalInteger, LogicalIpAddress, LogicalString, LogicalUuid,
+};
 use crate::model::{DataSource, DsvFile, TupleConstraint, TypeConstraint};
 use crate::{
     builder_proxy::LogicalColumnBuilderProxyT,
     error::{Error, ReadingError},
-    io::{formats::PROGRESS_NOTIFY_INCREMENT, resource_providers::ResourceProviders},
+    io::{formats::PROGRESS_NOTIFY_INCREMENT, resource_providers::ResourceProviders, Encoding},
     model::{PrimitiveType, Term},
 };
 
@@ -112,6 +114,8 @@ pub struct DSVReader {
     resource: Resource,
     delimiter: u8,
     escape: u8,
+    encoding: Encoding,
+    sorted: bool,
     logical_types: Vec<PrimitiveType>,
     input_type_constraint: TupleConstraint,
 }
@@ -128,6 +132,8 @@ impl DSVReader {
             resource: dsv_file.resource.clone(),
             delimiter: dsv_file.delimiter,
             escape: b'\\',
+            encoding: dsv_file.encoding,
+            sorted: dsv_file.sorted,
             logical_types,
             input_type_constraint: dsv_file.input_types(),
         }
@@ -159,13 +165,30 @@ impl DSVReader {
         R2: Read,
     {
         let mut lines = 0;
+        let mut invalid_lines = 0;
+        let mut accepted_lines = 0;
+        let mut rejected_lines = 0;
+
+        for row in dsv_reader.records() {
+            let row = match row {
+                Ok(row) => row,
+                Err(error) => {
+                    if matches!(error.kind(), csv::ErrorKind::Utf8 { .. }) {
+                        invalid_lines += 1;
+                    }
+                    rejected_lines += 1;
+                    tracing::info!("Ignoring line, reading failed with: {error}");
+                    continue;
+                }
+            };
 
-        for row in dsv_reader.records().flatten() {
             if let Err(Error::Rollback(rollback)) =
                 row.iter().enumerate().try_for_each(|(idx, item)| {
                     if idx < builder.len() {
                         if let Err(column_err) = builder[idx].add(item.to_string()) {
-                            log::info!("Ignoring line {row:?}, parsing failed with: {column_err}");
+                            tracing::info!(
+                                "Ignoring line {row:?}, parsing failed with: {column_err}"
+                            );
                             Err(Error::Rollback(idx))
                         } else {
                             Ok(())
@@ -181,14 +204,29 @@ impl DSVReader {
                         builder.forget();
                     }
                 });
+                rejected_lines += 1;
+            } else {
+                accepted_lines += 1;
             }
 
             lines += 1;
             if (lines % PROGRESS_NOTIFY_INCREMENT) == 0 {
-                log::info!("loading: processed {lines} lines");
+                tracing::info!("loading: processed {lines} lines");
             }
         }
-        log::info!("Finished loading: processed {lines} lines");
+        tracing::info!("Finished loading: processed {lines} lines");
+        self.resource_providers.input_statistics().record_rows(
+            &self.resource,
+            accepted_lines,
+            rejected_lines,
+        );
+
+        if invalid_lines > 0 {
+            tracing::warn!(
+                "Ignored {invalid_lines} lines of \"{}\" that are not valid UTF-8, consider specifying the encoding of the file, e.g. `encoding=\"latin-1\"`",
+                self.resource
+            );
+        }
 
         Ok(())
     }
@@ -205,6 +243,9 @@ impl DSVReader {
                     TypeConstraint::Exact(PrimitiveType::String) | TypeConstraint::AtLeast(PrimitiveType::String) => Box::new($lcbp.into_parser::<LogicalString>()),
                     TypeConstraint::Exact(PrimitiveType::Integer) | TypeConstraint::AtLeast(PrimitiveType::Integer) => Box::new($lcbp.into_parser::<LogicalInteger>()),
                     TypeConstraint::Exact(PrimitiveType::Float64) | TypeConstraint::AtLeast(PrimitiveType::Float64) => Box::new($lcbp.into_parser::<LogicalFloat64>()),
+                    TypeConstraint::Exact(PrimitiveType::DateTime) | TypeConstraint::AtLeast(PrimitiveType::DateTime) => Box::new($lcbp.into_parser::<LogicalDateTime>()),
+                    TypeConstraint::Exact(PrimitiveType::Uuid) | TypeConstraint::AtLeast(PrimitiveType::Uuid) => Box::new($lcbp.into_parser::<LogicalUuid>()),
+                    TypeConstraint::Exact(PrimitiveType::IpAddress) | TypeConstraint::AtLeast(PrimitiveType::IpAddress) => Box::new($lcbp.into_parser::<LogicalIpAddress>()),
                     TypeConstraint::None => unreachable!("Type constraints for input types are always initialized (with fallbacks)."),
                     TypeConstraint::Tuple(_) => todo!("We do not support tuples in CSV currently. Should we?"),
                 };
@@ -223,6 +264,9 @@ impl DSVReader {
                 LogicalColumnBuilderProxyT::String(lcbp) => into_parser!(it, lcbp),
                 LogicalColumnBuilderProxyT::Integer(lcbp) => into_parser!(it, lcbp),
                 LogicalColumnBuilderProxyT::Float64(lcbp) => into_parser!(it, lcbp),
+                LogicalColumnBuilderProxyT::DateTime(lcbp) => into_parser!(it, lcbp),
+                LogicalColumnBuilderProxyT::Uuid(lcbp) => into_parser!(it, lcbp),
+                LogicalColumnBuilderProxyT::IpAddress(lcbp) => into_parser!(it, lcbp),
             })
             .collect();
 
@@ -239,10 +283,15 @@ impl TableReader for DSVReader {
             .resource_providers
             .open_resource(&self.resource, true)?;
 
+        let reader = self.encoding.decode(reader);
         let mut dsv_reader = Self::dsv_reader(reader, self.delimiter, Some(self.escape));
 
         self.read_into_builder_proxies_with_reader(physical_builder_proxies, &mut dsv_reader)
     }
+
+    fn is_sorted(&self) -> bool {
+        self.sorted
+    }
 }
 
 #[cfg(test)]
@@ -258,7 +307,8 @@ mod test {
             data_value::{DataValueIteratorT, PhysicalString},
             storage_value::VecT,
         },
-        dictionary::{Dictionary, PrefixedStringDictionary},
+        dictionary::Dictionary,
+        management::database::Dict,
     };
 
     #[test]
@@ -272,7 +322,7 @@ Boston;United States;4628910
             .delimiter(b';')
             .from_reader(data.as_bytes());
 
-        let mut dict = std::cell::RefCell::new(PrefixedStringDictionary::default());
+        let mut dict = std::cell::RefCell::new(Dict::default());
         let csvreader = DSVReader::dsv(
             ResourceProviders::empty(),
             &DsvFile::csv_file(
@@ -296,6 +346,7 @@ Boston;United States;4628910
             .map(|bp| match bp {
                 PhysicalBuilderProxyEnum::String(bp) => bp.finalize(),
                 PhysicalBuilderProxyEnum::I64(bp) => bp.finalize(),
+                PhysicalBuilderProxyEnum::U128(bp) => bp.finalize(),
                 PhysicalBuilderProxyEnum::U64(bp) => bp.finalize(),
                 PhysicalBuilderProxyEnum::U32(bp) => bp.finalize(),
                 PhysicalBuilderProxyEnum::Float(bp) => bp.finalize(),
@@ -364,7 +415,7 @@ The next 2 columns are empty;;;789
             .delimiter(b';')
             .from_reader(data.as_bytes());
 
-        let mut dict = std::cell::RefCell::new(PrefixedStringDictionary::default());
+        let mut dict = std::cell::RefCell::new(Dict::default());
         let csvreader = DSVReader::dsv(
             ResourceProviders::empty(),
             &DsvFile::csv_file(
@@ -470,9 +521,10 @@ node03;123;123;13;55;123;invalid
             .has_headers(false)
             .from_reader(data.as_bytes());
 
-        let dict = std::cell::RefCell::new(PrefixedStringDictionary::default());
+        let dict = std::cell::RefCell::new(Dict::default());
+        let resource_providers = ResourceProviders::empty();
         let csvreader: DSVReader = DSVReader::dsv(
-            ResourceProviders::empty(),
+            resource_providers.clone(),
             &DsvFile::csv_file(
                 "test",
                 [
@@ -510,6 +562,7 @@ node03;123;123;13;55;123;invalid
             .map(|bp| match bp {
                 PhysicalBuilderProxyEnum::String(bp) => bp.finalize(),
                 PhysicalBuilderProxyEnum::I64(bp) => bp.finalize(),
+                PhysicalBuilderProxyEnum::U128(bp) => bp.finalize(),
                 PhysicalBuilderProxyEnum::U64(bp) => bp.finalize(),
                 PhysicalBuilderProxyEnum::U32(bp) => bp.finalize(),
                 PhysicalBuilderProxyEnum::Float(bp) => bp.finalize(),
@@ -522,6 +575,49 @@ node03;123;123;13;55;123;invalid
         assert!(result.is_ok());
         assert_eq!(imported.len(), 6);
         assert_eq!(imported[1].len(), 3);
+
+        let statistics = resource_providers.input_statistics().resources();
+        assert_eq!(statistics.len(), 1);
+        assert_eq!(statistics[0].accepted_rows, 3);
+        assert_eq!(statistics[0].rejected_rows, 2);
+    }
+
+    #[test]
+    fn csv_with_uuids() {
+        let data = "\
+a,123e4567-e89b-12d3-a456-426614174000
+b,urn:uuid:00000000000000000000000000000001
+c,not-a-uuid
+";
+        let mut rdr = ReaderBuilder::new()
+            .has_headers(false)
+            .from_reader(data.as_bytes());
+
+        let dict = std::cell::RefCell::new(Dict::default());
+        let csvreader = DSVReader::dsv(
+            ResourceProviders::empty(),
+            &DsvFile::csv_file(
+                "test",
+                [PrimitiveType::Any, PrimitiveType::Uuid]
+                    .into_iter()
+                    .collect(),
+            ),
+            vec![PrimitiveType::Any, PrimitiveType::Uuid],
+        );
+        let mut builder = vec![
+            PhysicalBuilderProxyEnum::String(PhysicalStringColumnBuilderProxy::new(&dict)),
+            PhysicalBuilderProxyEnum::U128(Default::default()),
+        ];
+        let result = csvreader.read_into_builder_proxies_with_reader(&mut builder, &mut rdr);
+
+        assert!(result.is_ok());
+        let PhysicalBuilderProxyEnum::U128(uuids) = builder.pop().unwrap() else {
+            unreachable!()
+        };
+        assert_eq!(
+            uuids.finalize(),
+            VecT::U128(vec![0x123e4567_e89b_12d3_a456_426614174000, 1])
+        );
     }
 
     #[quickcheck]
@@ -587,6 +683,7 @@ node03;123;123;13;55;123;invalid
             .map(|bp| match bp {
                 PhysicalBuilderProxyEnum::String(bp) => bp.finalize(),
                 PhysicalBuilderProxyEnum::I64(bp) => bp.finalize(),
+                PhysicalBuilderProxyEnum::U128(bp) => bp.finalize(),
                 PhysicalBuilderProxyEnum::U64(bp) => bp.finalize(),
                 PhysicalBuilderProxyEnum::U32(bp) => bp.finalize(),
                 PhysicalBuilderProxyEnum::Float(bp) => bp.finalize(),
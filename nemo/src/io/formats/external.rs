@@ -0,0 +1,175 @@
+//! Reading the facts of external sources, which are provided by the application.
+
+use std::rc::Rc;
+
+use nemo_physical::{
+    builder_proxy::{ColumnBuilderProxy, PhysicalBuilderProxyEnum},
+    error::ReadingError,
+    table_reader::TableReader,
+};
+
+use crate::{
+    builder_proxy::LogicalColumnBuilderProxyT,
+    io::resource_providers::external::ExternalSourceProvider,
+    model::{ExternalSource, PrimitiveType, Term},
+};
+
+use super::PROGRESS_NOTIFY_INCREMENT;
+
+/// A reader object for pulling the facts of an [`ExternalSource`] from its provider.
+#[derive(Debug)]
+pub struct ExternalSourceReader {
+    name: String,
+    provider: Rc<dyn ExternalSourceProvider>,
+    logical_types: Vec<PrimitiveType>,
+}
+
+impl ExternalSourceReader {
+    /// Create a new [`ExternalSourceReader`]
+    pub fn new(
+        external_source: &ExternalSource,
+        provider: Rc<dyn ExternalSourceProvider>,
+        logical_types: Vec<PrimitiveType>,
+    ) -> Self {
+        Self {
+            name: external_source.name.clone(),
+            provider,
+            logical_types,
+        }
+    }
+
+    /// Add the terms of the given fact to the builders.
+    ///
+    /// If a term cannot be converted into the type of its column,
+    /// the already added terms are forgotten.
+    fn add_fact(
+        builders: &mut [LogicalColumnBuilderProxyT],
+        fact: Vec<Term>,
+    ) -> Result<(), ReadingError> {
+        for (index, term) in fact.into_iter().enumerate() {
+            if let Err(error) = builders[index].add(term) {
+                for builder in &mut builders[..index] {
+                    <LogicalColumnBuilderProxyT as ColumnBuilderProxy<Term>>::forget(builder);
+                }
+
+                return Err(error);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl TableReader for ExternalSourceReader {
+    fn read_into_builder_proxies<'a: 'b, 'b>(
+        self: Box<Self>,
+        builder_proxies: &'b mut Vec<PhysicalBuilderProxyEnum<'a>>,
+    ) -> Result<(), ReadingError> {
+        let mut builders = builder_proxies
+            .iter_mut()
+            .zip(self.logical_types.iter())
+            .map(|(bp, lt)| lt.wrap_physical_column_builder(bp))
+            .collect::<Vec<_>>();
+
+        let mut facts = 0;
+        for fact in self.provider.facts()? {
+            if fact.len() != builders.len() {
+                tracing::info!(
+                    "Ignoring fact {fact:?} of external source {}, which should have {} terms",
+                    self.name,
+                    builders.len()
+                );
+                continue;
+            }
+
+            if let Err(error) = Self::add_fact(&mut builders, fact) {
+                tracing::info!(
+                    "Ignoring fact of external source {}, conversion failed with: {error}",
+                    self.name
+                );
+                continue;
+            }
+
+            facts += 1;
+            if facts % PROGRESS_NOTIFY_INCREMENT == 0 {
+                tracing::info!("Loading: processed {facts} facts");
+            }
+        }
+
+        tracing::info!("Finished loading: processed {facts} facts");
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use nemo_physical::error::{Error as PhysicalError, ReadingError};
+
+    use crate::{
+        error::Error,
+        execution::DefaultExecutionEngine,
+        io::{
+            parser::parse_program,
+            resource_providers::{external::CallbackSourceProvider, ResourceProviders},
+        },
+        model::{Identifier, Term},
+    };
+
+    const PROGRAM: &str = "@source edge[any, any]: external(\"graph\") .\n\
+                           reachable(?X, ?Y) :- edge(?X, ?Y) .\n\
+                           reachable(?X, ?Z) :- reachable(?X, ?Y), edge(?Y, ?Z) .\n";
+
+    fn constant(name: &str) -> Term {
+        Term::Constant(Identifier(name.to_string()))
+    }
+
+    #[test]
+    fn external_source() {
+        let provider = CallbackSourceProvider::new(|| {
+            vec![
+                vec![constant("a"), constant("b")],
+                vec![constant("b"), constant("c")],
+                vec![constant("c")],
+            ]
+        });
+        let resource_providers = ResourceProviders::empty().with_external_source("graph", provider);
+
+        let mut engine =
+            DefaultExecutionEngine::initialize(parse_program(PROGRAM).unwrap(), resource_providers)
+                .unwrap();
+        engine.execute().unwrap();
+
+        let mut reachable: Vec<Vec<String>> = engine
+            .output_serialization(Identifier("reachable".to_string()))
+            .unwrap()
+            .unwrap()
+            .collect();
+        reachable.sort();
+
+        assert_eq!(
+            reachable,
+            vec![
+                vec!["a".to_string(), "b".to_string()],
+                vec!["a".to_string(), "c".to_string()],
+                vec!["b".to_string(), "c".to_string()],
+            ]
+        );
+    }
+
+    #[test]
+    fn missing_external_source() {
+        let mut engine = DefaultExecutionEngine::initialize(
+            parse_program(PROGRAM).unwrap(),
+            ResourceProviders::empty(),
+        )
+        .unwrap();
+
+        assert!(matches!(
+            engine.execute(),
+            Err(Error::PhysicalError(PhysicalError::ReadingError(
+                ReadingError::ExternalSourceNotProvided(_)
+            )))
+        ));
+    }
+}
@@ -0,0 +1,653 @@
+//! Reading of HDT (Header, Dictionary, Triples) files, a compressed binary format for RDF
+//!
+//! HDT files consist of a header, a dictionary assigning integer ids to the RDF terms,
+//! and the triples, which are stored as compressed sequences of ids.
+//! This module supports files with a four section dictionary of plain front coded strings
+//! and bitmap triples, which is the layout written by the reference implementations.
+//! Checksums of the file are not verified.
+
+use std::{
+    collections::HashMap,
+    io::{BufReader, Read},
+};
+
+use nemo_physical::{
+    builder_proxy::{ColumnBuilderProxy, PhysicalBuilderProxyEnum},
+    error::{ExternalReadingError, ReadingError},
+    table_reader::{Resource, TableReader},
+};
+use thiserror::Error;
+
+use crate::{
+    builder_proxy::LogicalColumnBuilderProxyT,
+    io::{formats::PROGRESS_NOTIFY_INCREMENT, resource_providers::ResourceProviders},
+    model::{PrimitiveType, RdfFile, RdfLiteral, Term},
+};
+
+/// Format of the dictionary with separate sections for shared terms, subjects, predicates and objects
+const DICTIONARY_FOUR: &str = "<http://purl.org/HDT/hdt#dictionaryFour>";
+/// Format of triples stored as bitmaps and sequences of ids
+const TRIPLES_BITMAP: &str = "<http://purl.org/HDT/hdt#triplesBitmap>";
+
+/// Errors that can occur when reading an HDT file.
+#[derive(Error, Debug)]
+pub enum HdtReadingError {
+    /// The file does not contain the expected part.
+    #[error("The HDT file is malformed: expected the {0}.")]
+    Malformed(&'static str),
+    /// A part of the file has a format that is not supported.
+    #[error("The HDT {0} format {1} is not supported.")]
+    UnsupportedFormat(&'static str, String),
+    /// The triples refer to an id that is not contained in the dictionary.
+    #[error("The HDT file refers to the {0} id {1}, which is not contained in the dictionary.")]
+    InvalidId(&'static str, u64),
+}
+
+impl ExternalReadingError for HdtReadingError {}
+
+impl From<HdtReadingError> for ReadingError {
+    fn from(error: HdtReadingError) -> Self {
+        ReadingError::ExternalReadingError(Box::new(error))
+    }
+}
+
+/// Types of the parts of an HDT file, as given in their control information
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PartType {
+    Global = 1,
+    Header = 2,
+    Dictionary = 3,
+    Triples = 4,
+}
+
+/// Format and properties of a part of an HDT file
+#[derive(Debug)]
+struct ControlInformation {
+    format: String,
+    properties: HashMap<String, String>,
+}
+
+impl ControlInformation {
+    /// Return the value of a numeric property.
+    fn number(&self, key: &str) -> Option<u64> {
+        self.properties
+            .get(key)
+            .and_then(|value| value.parse().ok())
+    }
+}
+
+/// A sequence of integers, each of which is stored with the same number of bits
+#[derive(Debug)]
+struct LogSequence {
+    bits: usize,
+    entries: usize,
+    data: Vec<u8>,
+}
+
+impl LogSequence {
+    /// Return the entry at the given position.
+    fn get(&self, index: usize) -> u64 {
+        let position = index * self.bits;
+        let mut window = [0u8; 16];
+        let bytes = &self.data[position / 8..self.data.len().min(position / 8 + 16)];
+        window[..bytes.len()].copy_from_slice(bytes);
+
+        let value = u128::from_le_bytes(window) >> (position % 8);
+        (value & ((1u128 << self.bits) - 1)) as u64
+    }
+}
+
+/// A sequence of bits
+#[derive(Debug)]
+struct Bitmap {
+    bits: usize,
+    data: Vec<u8>,
+}
+
+impl Bitmap {
+    /// Return whether the bit at the given position is set.
+    fn get(&self, index: usize) -> bool {
+        self.data[index / 8] & (1 << (index % 8)) != 0
+    }
+}
+
+/// Reads the parts of an HDT file from the underlying reader.
+struct HdtInput<R> {
+    reader: R,
+}
+
+impl<R: Read> HdtInput<R> {
+    fn bytes(&mut self, length: usize, part: &'static str) -> Result<Vec<u8>, ReadingError> {
+        let mut bytes = Vec::new();
+        (&mut self.reader)
+            .take(length as u64)
+            .read_to_end(&mut bytes)?;
+
+        if bytes.len() == length {
+            Ok(bytes)
+        } else {
+            Err(HdtReadingError::Malformed(part).into())
+        }
+    }
+
+    fn byte(&mut self, part: &'static str) -> Result<u8, ReadingError> {
+        let mut byte = [0];
+
+        match self.reader.read_exact(&mut byte) {
+            Ok(()) => Ok(byte[0]),
+            Err(error) if error.kind() == std::io::ErrorKind::UnexpectedEof => {
+                Err(HdtReadingError::Malformed(part).into())
+            }
+            Err(error) => Err(error.into()),
+        }
+    }
+
+    /// Read a variable length integer, where the highest bit marks the last byte.
+    fn vbyte(&mut self, part: &'static str) -> Result<u64, ReadingError> {
+        let mut value = 0;
+
+        for shift in (0..64).step_by(7) {
+            let byte = self.byte(part)?;
+            value |= u64::from(byte & 0x7f) << shift;
+
+            if byte & 0x80 != 0 {
+                return Ok(value);
+            }
+        }
+
+        Err(HdtReadingError::Malformed(part).into())
+    }
+
+    fn null_terminated(&mut self, part: &'static str) -> Result<String, ReadingError> {
+        let mut bytes = Vec::new();
+
+        loop {
+            match self.byte(part)? {
+                0 => break,
+                byte => bytes.push(byte),
+            }
+        }
+
+        String::from_utf8(bytes).map_err(|_| HdtReadingError::Malformed(part).into())
+    }
+
+    fn control_information(
+        &mut self,
+        part_type: PartType,
+        part: &'static str,
+    ) -> Result<ControlInformation, ReadingError> {
+        if self.bytes(4, part)? != b"$HDT" || self.byte(part)? != part_type as u8 {
+            return Err(HdtReadingError::Malformed(part).into());
+        }
+
+        let format = self.null_terminated(part)?;
+        let properties = self
+            .null_terminated(part)?
+            .split(';')
+            .filter_map(|property| property.split_once('='))
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect();
+        // CRC16 of the control information
+        self.bytes(2, part)?;
+
+        Ok(ControlInformation { format, properties })
+    }
+
+    fn log_sequence(&mut self, part: &'static str) -> Result<LogSequence, ReadingError> {
+        const TYPE_LOG: u8 = 1;
+
+        if self.byte(part)? != TYPE_LOG {
+            return Err(HdtReadingError::Malformed(part).into());
+        }
+
+        let bits = usize::from(self.byte(part)?);
+        let entries = self.vbyte(part)? as usize;
+        if bits > 64 {
+            return Err(HdtReadingError::Malformed(part).into());
+        }
+        // CRC8 of the preamble
+        self.bytes(1, part)?;
+
+        let data = self.bytes((bits * entries).div_ceil(8), part)?;
+        // CRC32 of the data
+        self.bytes(4, part)?;
+
+        Ok(LogSequence {
+            bits,
+            entries,
+            data,
+        })
+    }
+
+    fn bitmap(&mut self, part: &'static str) -> Result<Bitmap, ReadingError> {
+        const TYPE_PLAIN: u8 = 1;
+
+        if self.byte(part)? != TYPE_PLAIN {
+            return Err(HdtReadingError::Malformed(part).into());
+        }
+
+        let bits = self.vbyte(part)? as usize;
+        // CRC8 of the preamble
+        self.bytes(1, part)?;
+
+        let data = self.bytes(bits.div_ceil(8), part)?;
+        // CRC32 of the data
+        self.bytes(4, part)?;
+
+        Ok(Bitmap { bits, data })
+    }
+
+    /// Read a dictionary section of plain front coded strings,
+    /// where each string that does not start a block is stored as the length of the prefix
+    /// it shares with the previous string, followed by the remaining characters.
+    ///
+    /// The strings are converted into terms, where `None` marks strings that are not valid terms.
+    fn dictionary_section(&mut self) -> Result<Vec<Option<Term>>, ReadingError> {
+        const PART: &str = "dictionary section";
+        const TYPE_PFC: u8 = 2;
+
+        if self.byte(PART)? != TYPE_PFC {
+            return Err(HdtReadingError::Malformed(PART).into());
+        }
+
+        let strings = self.vbyte(PART)? as usize;
+        let length = self.vbyte(PART)? as usize;
+        let block_size = self.vbyte(PART)? as usize;
+        // CRC8 of the preamble
+        self.bytes(1, PART)?;
+        // The start positions of the blocks are only needed for random access
+        self.log_sequence(PART)?;
+
+        let text = self.bytes(length, PART)?;
+        // CRC32 of the text
+        self.bytes(4, PART)?;
+
+        let mut text = HdtInput {
+            reader: text.as_slice(),
+        };
+        let mut terms = Vec::with_capacity(strings);
+        let mut previous = String::new();
+
+        for index in 0..strings {
+            let string = if block_size == 0 || index % block_size == 0 {
+                text.null_terminated(PART)?
+            } else {
+                let shared = text.vbyte(PART)? as usize;
+                let prefix = previous
+                    .get(..shared)
+                    .ok_or(HdtReadingError::Malformed(PART))?;
+
+                format!("{prefix}{}", text.null_terminated(PART)?)
+            };
+
+            let term = hdt_string_to_term(&string);
+            if let Err(error) = &term {
+                tracing::info!("Ignoring triples containing the term {string}: {error}");
+            }
+
+            terms.push(term.ok());
+            previous = string;
+        }
+
+        Ok(terms)
+    }
+}
+
+/// Convert a string of an HDT dictionary into a term.
+///
+/// IRIs are stored without angle brackets,
+/// and literals are stored like in N-Triples but without escaping their value.
+fn hdt_string_to_term(string: &str) -> Result<Term, ReadingError> {
+    let Some(literal) = string.strip_prefix('"') else {
+        return Ok(Term::Constant(string.to_string().into()));
+    };
+
+    let end = literal
+        .rfind('"')
+        .ok_or_else(|| ReadingError::InvalidRdfLiteral(string.to_string()))?;
+    let (value, suffix) = (literal[..end].to_string(), &literal[end + 1..]);
+
+    if suffix.is_empty() {
+        Ok(Term::StringLiteral(value))
+    } else if let Some(tag) = suffix.strip_prefix('@') {
+        Ok(Term::try_from(RdfLiteral::LanguageString {
+            value,
+            tag: tag.to_string(),
+        })?)
+    } else if let Some(datatype) = suffix
+        .strip_prefix("^^<")
+        .and_then(|datatype| datatype.strip_suffix('>'))
+    {
+        Ok(Term::try_from(RdfLiteral::DatatypeValue {
+            value,
+            datatype: datatype.to_string(),
+        })?)
+    } else {
+        Err(ReadingError::InvalidRdfLiteral(string.to_string()))
+    }
+}
+
+/// The dictionary of an HDT file, where subjects and objects share the ids of common terms
+#[derive(Debug)]
+struct HdtDictionary {
+    shared: Vec<Option<Term>>,
+    subjects: Vec<Option<Term>>,
+    predicates: Vec<Option<Term>>,
+    objects: Vec<Option<Term>>,
+}
+
+impl HdtDictionary {
+    fn lookup<'a>(
+        shared: &'a [Option<Term>],
+        section: &'a [Option<Term>],
+        id: u64,
+        role: &'static str,
+    ) -> Result<&'a Option<Term>, HdtReadingError> {
+        let index = (id as usize)
+            .checked_sub(1)
+            .ok_or(HdtReadingError::InvalidId(role, id))?;
+
+        shared
+            .get(index)
+            .or_else(|| section.get(index - shared.len()))
+            .ok_or(HdtReadingError::InvalidId(role, id))
+    }
+
+    fn subject(&self, id: u64) -> Result<&Option<Term>, HdtReadingError> {
+        Self::lookup(&self.shared, &self.subjects, id, "subject")
+    }
+
+    fn predicate(&self, id: u64) -> Result<&Option<Term>, HdtReadingError> {
+        Self::lookup(&[], &self.predicates, id, "predicate")
+    }
+
+    fn object(&self, id: u64) -> Result<&Option<Term>, HdtReadingError> {
+        Self::lookup(&self.shared, &self.objects, id, "object")
+    }
+}
+
+/// The triples of an HDT file, which are sorted by the components given by `order`.
+///
+/// For each value of the first component, `bitmap_y` marks the last of its values of the second component
+/// in `sequence_y`, and for each such pair, `bitmap_z` marks the last of its values of the third component
+/// in `sequence_z`.
+#[derive(Debug)]
+struct BitmapTriples {
+    order: [usize; 3],
+    bitmap_y: Bitmap,
+    bitmap_z: Bitmap,
+    sequence_y: LogSequence,
+    sequence_z: LogSequence,
+}
+
+impl BitmapTriples {
+    /// Return the positions of subject, predicate and object in the components of the given order.
+    fn component_order(order: u64) -> Option<[usize; 3]> {
+        match order {
+            1 => Some([0, 1, 2]), // SPO
+            2 => Some([0, 2, 1]), // SOP
+            3 => Some([1, 0, 2]), // PSO
+            4 => Some([2, 0, 1]), // POS
+            5 => Some([1, 2, 0]), // OSP
+            6 => Some([2, 1, 0]), // OPS
+            _ => None,
+        }
+    }
+
+    /// Call the given function with the ids of the subject, predicate and object of each triple.
+    fn for_each<F>(&self, mut function: F) -> Result<(), ReadingError>
+    where
+        F: FnMut([u64; 3]) -> Result<(), ReadingError>,
+    {
+        const PART: &str = "triples";
+
+        if self.bitmap_y.bits != self.sequence_y.entries
+            || self.bitmap_z.bits != self.sequence_z.entries
+        {
+            return Err(HdtReadingError::Malformed(PART).into());
+        }
+
+        let mut x = 1;
+        let mut z_index = 0;
+
+        for y_index in 0..self.sequence_y.entries {
+            let y = self.sequence_y.get(y_index);
+
+            loop {
+                if z_index >= self.sequence_z.entries {
+                    return Err(HdtReadingError::Malformed(PART).into());
+                }
+
+                let components = [x, y, self.sequence_z.get(z_index)];
+                function(self.order.map(|position| components[position]))?;
+
+                z_index += 1;
+                if self.bitmap_z.get(z_index - 1) {
+                    break;
+                }
+            }
+
+            if self.bitmap_y.get(y_index) {
+                x += 1;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Read the dictionary and the triples of an HDT file.
+fn read_hdt<R: Read>(reader: R) -> Result<(HdtDictionary, BitmapTriples), ReadingError> {
+    let mut input = HdtInput { reader };
+
+    input.control_information(PartType::Global, "global control information")?;
+
+    let header = input.control_information(PartType::Header, "header")?;
+    let header_length = header
+        .number("length")
+        .ok_or(HdtReadingError::Malformed("header"))?;
+    input.bytes(header_length as usize, "header")?;
+
+    let dictionary = input.control_information(PartType::Dictionary, "dictionary")?;
+    if dictionary.format != DICTIONARY_FOUR {
+        return Err(HdtReadingError::UnsupportedFormat("dictionary", dictionary.format).into());
+    }
+    let dictionary = HdtDictionary {
+        shared: input.dictionary_section()?,
+        subjects: input.dictionary_section()?,
+        predicates: input.dictionary_section()?,
+        objects: input.dictionary_section()?,
+    };
+
+    let triples = input.control_information(PartType::Triples, "triples")?;
+    if triples.format != TRIPLES_BITMAP {
+        return Err(HdtReadingError::UnsupportedFormat("triples", triples.format).into());
+    }
+    let order = triples
+        .number("order")
+        .and_then(BitmapTriples::component_order)
+        .ok_or_else(|| {
+            HdtReadingError::UnsupportedFormat(
+                "triples order",
+                triples.properties.get("order").cloned().unwrap_or_default(),
+            )
+        })?;
+    let triples = BitmapTriples {
+        order,
+        bitmap_y: input.bitmap("triples")?,
+        bitmap_z: input.bitmap("triples")?,
+        sequence_y: input.log_sequence("triples")?,
+        sequence_z: input.log_sequence("triples")?,
+    };
+
+    Ok((dictionary, triples))
+}
+
+/// A [`TableReader`] for RDF files in the HDT format.
+///
+/// The dictionary of the file is decoded once,
+/// such that each term is only converted a single time,
+/// regardless of the number of triples it occurs in.
+#[derive(Debug, Clone)]
+pub struct HDTReader {
+    resource_providers: ResourceProviders,
+    resource: Resource,
+    logical_types: Vec<PrimitiveType>,
+}
+
+impl HDTReader {
+    /// Create a new [`HDTReader`]
+    pub fn new(
+        resource_providers: ResourceProviders,
+        rdf_file: &RdfFile,
+        logical_types: Vec<PrimitiveType>,
+    ) -> Self {
+        Self {
+            resource_providers,
+            resource: rdf_file.resource.clone(),
+            logical_types,
+        }
+    }
+}
+
+impl HDTReader {
+    /// Read the triples of the HDT file from the given reader of its content.
+    pub(crate) fn read_from<'a: 'b, 'b, R: Read>(
+        &self,
+        reader: R,
+        builder_proxies: &'b mut [PhysicalBuilderProxyEnum<'a>],
+    ) -> Result<(), ReadingError> {
+        let (dictionary, triples) = read_hdt(reader)?;
+
+        let mut builders = builder_proxies
+            .iter_mut()
+            .zip(self.logical_types.clone())
+            .map(|(bp, lt)| lt.wrap_physical_column_builder(bp))
+            .collect::<Vec<_>>();
+
+        assert!(builders.len() == 3);
+
+        let mut count = 0;
+        triples.for_each(|[subject, predicate, object]| {
+            let terms = [
+                dictionary.subject(subject)?,
+                dictionary.predicate(predicate)?,
+                dictionary.object(object)?,
+            ];
+            let [Some(_), Some(_), Some(_)] = terms else {
+                return Ok(());
+            };
+
+            for (index, term) in terms.into_iter().flatten().enumerate() {
+                if let Err(error) = builders[index].add(term.clone()) {
+                    tracing::info!("Ignoring triple, conversion failed with: {error}");
+                    for builder in &mut builders[..index] {
+                        <LogicalColumnBuilderProxyT as ColumnBuilderProxy<Term>>::forget(builder);
+                    }
+
+                    return Ok(());
+                }
+            }
+
+            count += 1;
+            if count % PROGRESS_NOTIFY_INCREMENT == 0 {
+                tracing::info!("Loading: processed {count} triples");
+            }
+
+            Ok(())
+        })?;
+
+        tracing::info!("Finished loading: processed {count} triples");
+
+        Ok(())
+    }
+}
+
+impl TableReader for HDTReader {
+    fn read_into_builder_proxies<'a: 'b, 'b>(
+        self: Box<Self>,
+        builder_proxies: &'b mut Vec<PhysicalBuilderProxyEnum<'a>>,
+    ) -> Result<(), ReadingError> {
+        let reader = self
+            .resource_providers
+            .open_resource(&self.resource, true)?;
+
+        self.read_from(BufReader::new(reader), builder_proxies)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use nemo_physical::error::ReadingError;
+
+    use super::{hdt_string_to_term, read_hdt, HdtInput, LogSequence};
+    use crate::model::{NumericLiteral, RdfLiteral, Term};
+
+    #[test]
+    fn log_sequence() {
+        // The entries 5, 0, 7, 2, 6 with 3 bits each, packed starting at the least significant bit
+        let sequence = LogSequence {
+            bits: 3,
+            entries: 5,
+            data: vec![0b1100_0101, 0b0110_0101],
+        };
+
+        assert_eq!(
+            (0..sequence.entries)
+                .map(|index| sequence.get(index))
+                .collect::<Vec<_>>(),
+            vec![5, 0, 7, 2, 6]
+        );
+
+        let sequence = LogSequence {
+            bits: 64,
+            entries: 2,
+            data: [u64::MAX.to_le_bytes(), 42u64.to_le_bytes()].concat(),
+        };
+        assert_eq!(sequence.get(0), u64::MAX);
+        assert_eq!(sequence.get(1), 42);
+    }
+
+    #[test]
+    fn vbyte() {
+        let mut input = HdtInput {
+            reader: [0x85, 0x2c, 0x82, 0x7f].as_slice(),
+        };
+
+        assert_eq!(input.vbyte("test").unwrap(), 5);
+        assert_eq!(input.vbyte("test").unwrap(), 300);
+        assert!(input.vbyte("test").is_err());
+    }
+
+    #[test]
+    fn dictionary_strings() {
+        assert_eq!(
+            hdt_string_to_term("http://example.org/a").unwrap(),
+            Term::Constant("http://example.org/a".to_string().into())
+        );
+        assert_eq!(
+            hdt_string_to_term("\"say \"hi\"\nplease\"").unwrap(),
+            Term::StringLiteral("say \"hi\"\nplease".to_string())
+        );
+        assert_eq!(
+            hdt_string_to_term("\"hallo\"@de").unwrap(),
+            Term::RdfLiteral(RdfLiteral::LanguageString {
+                value: "hallo".to_string(),
+                tag: "de".to_string()
+            })
+        );
+        assert_eq!(
+            hdt_string_to_term("\"2\"^^<http://www.w3.org/2001/XMLSchema#integer>").unwrap(),
+            Term::NumericLiteral(NumericLiteral::Integer(2))
+        );
+        assert!(hdt_string_to_term("\"2\"^^http://www.w3.org/2001/XMLSchema#integer").is_err());
+    }
+
+    #[test]
+    fn malformed_file() {
+        assert!(matches!(
+            read_hdt(b"$HDT\x01<http://purl.org/HDT/hdt#HDTv1>\0\0".as_slice()),
+            Err(ReadingError::ExternalReadingError(_))
+        ));
+    }
+}
@@ -0,0 +1,140 @@
+//! Detection of the format of RDF files
+
+use std::str::FromStr;
+
+use crate::io::parser::ParseError;
+
+/// Format of an RDF file.
+///
+/// The format of a data source is given by its `format` parameter, e.g. `load-rdf("data", format="turtle")`,
+/// or otherwise detected from the beginning of its content, see [`RdfFormat::detect`].
+/// The name of the resource is not taken into account,
+/// such that resources without extensions or with misleading names can be loaded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RdfFormat {
+    /// N-Triples
+    NTriples,
+    /// Turtle
+    Turtle,
+    /// RDF/XML, e.g. for OWL ontologies
+    RdfXml,
+    /// Header, Dictionary, Triples (HDT)
+    Hdt,
+}
+
+impl RdfFormat {
+    /// Magic bytes at the start of an HDT file
+    const HDT_COOKIE: &'static [u8] = b"$HDT";
+
+    /// Detect the format from the beginning of the (decompressed) content of a file.
+    ///
+    /// HDT files are recognized by their magic bytes and RDF/XML files by their XML declaration or namespace declarations.
+    /// Otherwise, the content is read as N-Triples if all of its complete lines are comments or N-Triples statements,
+    /// and as Turtle, which is a superset of N-Triples, if not.
+    pub fn detect(content: &[u8]) -> Self {
+        if content.starts_with(Self::HDT_COOKIE) {
+            return Self::Hdt;
+        }
+
+        let text = String::from_utf8_lossy(content);
+        let text = text.trim_start_matches('\u{feff}').trim_start();
+
+        if text.starts_with("<?xml")
+            || text.starts_with("<!")
+            || (text.starts_with('<') && text.contains("xmlns"))
+        {
+            return Self::RdfXml;
+        }
+
+        // The last line may have been cut off, so only its beginning is checked
+        let (complete, last) = match text.rfind('\n') {
+            Some(end) => text.split_at(end),
+            None => ("", text),
+        };
+        let last = last.trim();
+        let is_ntriples = complete
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .all(|line| {
+                (line.starts_with('<') || line.starts_with("_:"))
+                    && (line.ends_with('.') || line.contains(" . #"))
+            })
+            && (last.is_empty() || last.starts_with(['<', '#']) || last.starts_with("_:"));
+
+        if is_ntriples {
+            Self::NTriples
+        } else {
+            Self::Turtle
+        }
+    }
+}
+
+impl FromStr for RdfFormat {
+    type Err = ParseError;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name.to_ascii_lowercase().as_str() {
+            "ntriples" | "n-triples" | "nt" => Ok(Self::NTriples),
+            "turtle" | "ttl" => Ok(Self::Turtle),
+            "rdfxml" | "rdf/xml" | "rdf" | "owl" => Ok(Self::RdfXml),
+            "hdt" => Ok(Self::Hdt),
+            _ => Err(ParseError::UnknownRdfFormat(name.to_string())),
+        }
+    }
+}
+
+impl std::fmt::Display for RdfFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NTriples => write!(f, "ntriples"),
+            Self::Turtle => write!(f, "turtle"),
+            Self::RdfXml => write!(f, "rdfxml"),
+            Self::Hdt => write!(f, "hdt"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::RdfFormat;
+
+    #[test]
+    fn detect() {
+        assert_eq!(
+            RdfFormat::detect(b"$HDT\x01<http://purl.org/HDT/hdt#HDTv1>"),
+            RdfFormat::Hdt
+        );
+        assert_eq!(
+            RdfFormat::detect(b"<?xml version=\"1.0\"?>\n<rdf:RDF>"),
+            RdfFormat::RdfXml
+        );
+        assert_eq!(
+            RdfFormat::detect(
+                b"<rdf:RDF xmlns:rdf=\"http://www.w3.org/1999/02/22-rdf-syntax-ns#\">"
+            ),
+            RdfFormat::RdfXml
+        );
+        assert_eq!(
+            RdfFormat::detect(
+                b"# triples\n<http://a.example/s> <http://a.example/p> \"o\" .\n_:b <http://a.example/p> <http://a.example/o> . # comment\n<http://a.exa"
+            ),
+            RdfFormat::NTriples
+        );
+        assert_eq!(
+            RdfFormat::detect(b"@prefix ex: <http://a.example/> .\nex:s ex:p ex:o .\n"),
+            RdfFormat::Turtle
+        );
+        assert_eq!(
+            RdfFormat::detect(b"@prefix ex: <http://a.example/> . ex:s ex:p ex:o ."),
+            RdfFormat::Turtle
+        );
+        assert_eq!(
+            RdfFormat::detect(b"<http://a.example/s> <http://a.example/p> <http://a.example/o> ;\n    <http://a.example/q> 1 .\n"),
+            RdfFormat::Turtle
+        );
+
+        assert_eq!("Turtle".parse::<RdfFormat>().unwrap(), RdfFormat::Turtle);
+        assert!("json".parse::<RdfFormat>().is_err());
+    }
+}
@@ -1,4 +1,4 @@
-//! Reading of RDF 1.1 triples files (N-Triples, Turtle, RDF/XML)
+//! Reading of RDF 1.1 triples files (N-Triples, Turtle, RDF/XML, including OWL ontologies in RDF/XML, and HDT)
 use std::io::{BufRead, BufReader};
 
 use nemo_physical::{
@@ -16,7 +16,10 @@ use rio_xml::RdfXmlParser;
 
 use crate::{
     builder_proxy::LogicalColumnBuilderProxyT,
-    io::{formats::PROGRESS_NOTIFY_INCREMENT, resource_providers::ResourceProviders},
+    io::{
+        formats::{HDTReader, RdfFormat, PROGRESS_NOTIFY_INCREMENT},
+        resource_providers::ResourceProviders,
+    },
     model::{types::primitive_types::PrimitiveType, InvalidRdfLiteral, RdfFile, RdfLiteral, Term},
 };
 
@@ -85,6 +88,7 @@ pub struct RDFTriplesReader {
     resource_providers: ResourceProviders,
     resource: Resource,
     base: Option<Iri<String>>,
+    format: Option<RdfFormat>,
     logical_types: Vec<PrimitiveType>,
 }
 
@@ -103,6 +107,7 @@ impl RDFTriplesReader {
                 .as_ref()
                 .cloned()
                 .map(|iri| Iri::parse(iri).expect("should be a valid IRI.")),
+            format: rdf_file.format,
             logical_types,
         }
     }
@@ -156,21 +161,28 @@ impl RDFTriplesReader {
 
             triples += 1;
             if triples % PROGRESS_NOTIFY_INCREMENT == 0 {
-                log::info!("Loading: processed {triples} triples")
+                tracing::info!("Loading: processed {triples} triples")
             }
 
             Ok::<_, ReadingError>(())
         };
 
         let mut parser = make_parser(reader);
+        let mut malformed_triples = 0;
 
         while !parser.is_end() {
             if let Err(e) = parser.parse_step(&mut on_triple) {
-                log::info!("Ignoring malformed triple: {e}");
+                tracing::info!("Ignoring malformed triple: {e}");
+                malformed_triples += 1;
             }
         }
 
-        log::info!("Finished loading: processed {triples} triples");
+        tracing::info!("Finished loading: processed {triples} triples");
+        self.resource_providers.input_statistics().record_rows(
+            &self.resource,
+            triples,
+            malformed_triples,
+        );
 
         Ok(())
     }
@@ -186,17 +198,31 @@ impl TableReader for RDFTriplesReader {
             .open_resource(&self.resource, true)?;
 
         let mut reader = BufReader::new(reader);
+        let format = match self.format {
+            Some(format) => format,
+            None => RdfFormat::detect(reader.fill_buf()?),
+        };
 
-        if self.resource.ends_with(".ttl.gz") || self.resource.ends_with(".ttl") {
-            self.read_with_buf_reader(builder_proxies, &mut reader, |reader| {
-                TurtleParser::new(reader, self.base.clone())
-            })
-        } else if self.resource.ends_with(".rdf.gz") || self.resource.ends_with(".rdf") {
-            self.read_with_buf_reader(builder_proxies, &mut reader, |reader| {
-                RdfXmlParser::new(reader, self.base.clone())
-            })
-        } else {
-            self.read_with_buf_reader(builder_proxies, &mut reader, NTriplesParser::new)
+        match format {
+            RdfFormat::NTriples => {
+                self.read_with_buf_reader(builder_proxies, &mut reader, NTriplesParser::new)
+            }
+            RdfFormat::Turtle => {
+                self.read_with_buf_reader(builder_proxies, &mut reader, |reader| {
+                    TurtleParser::new(reader, self.base.clone())
+                })
+            }
+            RdfFormat::RdfXml => {
+                self.read_with_buf_reader(builder_proxies, &mut reader, |reader| {
+                    RdfXmlParser::new(reader, self.base.clone())
+                })
+            }
+            RdfFormat::Hdt => HDTReader::new(
+                self.resource_providers.clone(),
+                &RdfFile::new(&self.resource, None),
+                self.logical_types.clone(),
+            )
+            .read_from(reader, builder_proxies),
         }
     }
 }
@@ -208,7 +234,8 @@ mod test {
     use nemo_physical::{
         builder_proxy::{PhysicalColumnBuilderProxy, PhysicalStringColumnBuilderProxy},
         datatypes::data_value::{DataValueIteratorT, PhysicalString},
-        dictionary::{Dictionary, PrefixedStringDictionary},
+        dictionary::Dictionary,
+        management::database::Dict,
     };
     use rio_turtle::TurtleParser;
     use test_log::test;
@@ -225,7 +252,7 @@ mod test {
                       _:subject2 <http://an.example/predicate2> "object2" .
                       "#.as_bytes();
 
-                let dict = RefCell::new(PrefixedStringDictionary::default());
+                let dict = RefCell::new(Dict::default());
                 let mut builders = vec![
                     PhysicalBuilderProxyEnum::String(PhysicalStringColumnBuilderProxy::new(&dict)),
                     PhysicalBuilderProxyEnum::String(PhysicalStringColumnBuilderProxy::new(&dict)),
@@ -244,7 +271,7 @@ mod test {
                     })
                     .collect::<Vec<_>>();
 
-                log::debug!("columns: {columns:?}");
+                tracing::debug!("columns: {columns:?}");
                 let triples = (0..=2)
                     .map(|idx| {
                         columns
@@ -254,14 +281,14 @@ mod test {
                                     .get(idx)
                                     .and_then(|value| value.try_into().ok())
                                     .and_then(|u64: u64| usize::try_from(u64).ok())
-                                    .and_then(|usize| dict.borrow_mut().entry(usize))
+                                    .and_then(|usize| dict.borrow().entry(usize))
                                     .unwrap()
                             })
                             .map(PhysicalString::from)
                             .collect::<Vec<_>>()
                     })
                     .collect::<Vec<_>>();
-                log::debug!("triple: {triples:?}");
+                tracing::debug!("triple: {triples:?}");
                 for (value, expected) in PrimitiveType::Any.serialize_output(DataValueIteratorT::String(Box::new(triples[0].iter().cloned()))).zip(vec!["http://one.example/subject1", "http://one.example/predicate1", "http://one.example/object1"]) {
                     assert_eq!(value, expected);
                 }
@@ -292,7 +319,7 @@ mod test {
                       "#
         .as_bytes();
 
-        let dict = RefCell::new(PrefixedStringDictionary::default());
+        let dict = RefCell::new(Dict::default());
         let mut builders = vec![
             PhysicalBuilderProxyEnum::String(PhysicalStringColumnBuilderProxy::new(&dict)),
             PhysicalBuilderProxyEnum::String(PhysicalStringColumnBuilderProxy::new(&dict)),
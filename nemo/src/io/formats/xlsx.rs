@@ -0,0 +1,424 @@
+//! Reading of sheets of Excel (XLSX) files
+
+use std::io::{Cursor, Read};
+
+use calamine::{Data, Range, Reader, Xlsx, XlsxError};
+use thiserror::Error;
+
+use nemo_physical::{
+    builder_proxy::{ColumnBuilderProxy, PhysicalBuilderProxyEnum},
+    datatypes::Double,
+    error::{ExternalReadingError, ReadingError},
+    table_reader::{Resource, TableReader},
+};
+
+use crate::{
+    builder_proxy::{parse_rdf_term_from_string, LogicalColumnBuilderProxyT},
+    io::resource_providers::ResourceProviders,
+    model::{
+        types::primitive_logical_value::{LogicalDateTime, LogicalIpAddress, LogicalUuid},
+        CellRange, NumericLiteral, PrimitiveType, RdfLiteral, Term, XlsxFile, XSD_BOOLEAN,
+    },
+};
+
+use super::PROGRESS_NOTIFY_INCREMENT;
+
+/// Errors that can occur when reading a sheet of an XLSX file.
+#[derive(Error, Debug)]
+pub enum XlsxReadingError {
+    /// The file is not a valid XLSX file.
+    #[error("Failed to read the XLSX file \"{resource}\": {error}")]
+    Xlsx {
+        /// The XLSX resource
+        resource: Resource,
+        /// The error of the XLSX parser
+        error: XlsxError,
+    },
+    /// The file does not contain the requested sheet.
+    #[error("The XLSX file \"{resource}\" has no sheet \"{sheet}\".")]
+    MissingSheet {
+        /// The XLSX resource
+        resource: Resource,
+        /// The requested sheet
+        sheet: String,
+    },
+    /// The file does not contain any sheet.
+    #[error("The XLSX file \"{0}\" has no sheets.")]
+    NoSheets(Resource),
+}
+
+impl ExternalReadingError for XlsxReadingError {}
+
+impl From<XlsxReadingError> for ReadingError {
+    fn from(error: XlsxReadingError) -> Self {
+        ReadingError::ExternalReadingError(Box::new(error))
+    }
+}
+
+/// A reader object for reading the facts of an [`XlsxFile`],
+/// where each row of the selected cell range contains one fact.
+///
+/// Cells are converted into the logical type of their column.
+/// Rows that contain empty cells, errors or values that cannot be converted are ignored.
+#[derive(Debug)]
+pub struct XlsxReader {
+    resource_providers: ResourceProviders,
+    resource: Resource,
+    sheet: Option<String>,
+    range: Option<CellRange>,
+    logical_types: Vec<PrimitiveType>,
+}
+
+impl XlsxReader {
+    /// Create a new [`XlsxReader`]
+    pub fn new(
+        resource_providers: ResourceProviders,
+        xlsx_file: &XlsxFile,
+        logical_types: Vec<PrimitiveType>,
+    ) -> Self {
+        Self {
+            resource_providers,
+            resource: xlsx_file.resource.clone(),
+            sheet: xlsx_file.sheet.clone(),
+            range: xlsx_file.range,
+            logical_types,
+        }
+    }
+
+    /// Read the cells of the selected sheet and range.
+    fn read_cells(&self) -> Result<Range<Data>, ReadingError> {
+        let mut bytes = Vec::new();
+        self.resource_providers
+            .open_resource(&self.resource, false)?
+            .read_to_end(&mut bytes)?;
+
+        let xlsx_error = |error| XlsxReadingError::Xlsx {
+            resource: self.resource.clone(),
+            error,
+        };
+        let mut workbook = Xlsx::new(Cursor::new(bytes)).map_err(xlsx_error)?;
+
+        let sheet_names = workbook.sheet_names();
+        let sheet = match &self.sheet {
+            Some(sheet) if sheet_names.contains(sheet) => sheet.clone(),
+            Some(sheet) => {
+                return Err(XlsxReadingError::MissingSheet {
+                    resource: self.resource.clone(),
+                    sheet: sheet.clone(),
+                }
+                .into())
+            }
+            None => sheet_names
+                .first()
+                .cloned()
+                .ok_or_else(|| XlsxReadingError::NoSheets(self.resource.clone()))?,
+        };
+
+        let cells = workbook.worksheet_range(&sheet).map_err(xlsx_error)?;
+
+        Ok(self.select_range(&cells))
+    }
+
+    /// Restrict the used cells of a sheet to the selected range.
+    ///
+    /// Unbounded ranges contain as many columns as there are logical types
+    /// and extend to the last used row of the sheet.
+    fn select_range(&self, cells: &Range<Data>) -> Range<Data> {
+        let (Some(used_start), Some(used_end)) = (cells.start(), cells.end()) else {
+            return Range::empty();
+        };
+
+        let columns = (self.logical_types.len() as u32).saturating_sub(1);
+        let (start, end) = match self.range {
+            Some(CellRange {
+                start,
+                end: Some(end),
+            }) => (start, end),
+            Some(CellRange { start, end: None }) => (start, (used_end.0, start.1 + columns)),
+            None => (used_start, (used_end.0, used_start.1 + columns)),
+        };
+
+        if end.0 < start.0 || end.1 < start.1 {
+            return Range::empty();
+        }
+
+        cells.range(start, end)
+    }
+
+    /// Add the terms of the given fact to the builders.
+    ///
+    /// If a term cannot be converted into the type of its column,
+    /// the already added terms are forgotten.
+    fn add_fact(
+        builders: &mut [LogicalColumnBuilderProxyT],
+        fact: Vec<Term>,
+    ) -> Result<(), ReadingError> {
+        for (index, term) in fact.into_iter().enumerate() {
+            if let Err(error) = builders[index].add(term) {
+                for builder in &mut builders[..index] {
+                    <LogicalColumnBuilderProxyT as ColumnBuilderProxy<Term>>::forget(builder);
+                }
+
+                return Err(error);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Convert a floating point number into an integer if it has no fractional part.
+fn float_to_integer(value: f64) -> Option<i64> {
+    const BOUND: f64 = 9_223_372_036_854_775_808.0; // 2^63
+
+    (value.fract() == 0.0 && (-BOUND..BOUND).contains(&value)).then_some(value as i64)
+}
+
+/// Convert a cell into a point in time,
+/// where dates of Excel are interpreted as UTC.
+fn cell_to_date_time(cell: &Data) -> Option<LogicalDateTime> {
+    match cell {
+        Data::DateTime(date_time) if !date_time.is_duration() => date_time
+            .as_datetime()
+            .map(|date_time| date_time.timestamp().into()),
+        Data::DateTimeIso(value) | Data::String(value) => value.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+/// Convert a cell into a term of the given logical type,
+/// or return `None` if the cell does not contain a value of this type.
+///
+/// Since Excel stores all numbers as floating point numbers,
+/// numbers without fractional part are converted into integers in columns of type `any`.
+fn cell_to_term(cell: &Data, logical_type: PrimitiveType) -> Option<Term> {
+    let integer = |value| Term::NumericLiteral(NumericLiteral::Integer(value));
+    let double = |value| {
+        Double::new(value)
+            .ok()
+            .map(|value| Term::NumericLiteral(NumericLiteral::Double(value)))
+    };
+
+    match (logical_type, cell) {
+        (_, Data::Empty | Data::Error(_)) => None,
+        (PrimitiveType::Any, Data::Int(value)) => Some(integer(*value)),
+        (PrimitiveType::Any, Data::Float(value)) => float_to_integer(*value)
+            .map(integer)
+            .or_else(|| double(*value)),
+        (PrimitiveType::Any, Data::String(value)) => {
+            Some(parse_rdf_term_from_string(value.clone()))
+        }
+        (PrimitiveType::Any, Data::Bool(value)) => {
+            Some(Term::RdfLiteral(RdfLiteral::DatatypeValue {
+                value: value.to_string(),
+                datatype: XSD_BOOLEAN.to_string(),
+            }))
+        }
+        (PrimitiveType::Any, Data::DurationIso(value)) => Some(Term::StringLiteral(value.clone())),
+        (PrimitiveType::Any | PrimitiveType::DateTime, _) => {
+            cell_to_date_time(cell).map(Term::from)
+        }
+        (PrimitiveType::String, Data::DateTime(_)) => {
+            cell_to_date_time(cell).map(|date_time| Term::StringLiteral(date_time.to_string()))
+        }
+        (PrimitiveType::String, _) => Some(Term::StringLiteral(cell.to_string())),
+        (PrimitiveType::Integer, Data::Int(value)) => Some(integer(*value)),
+        (PrimitiveType::Integer, Data::Float(value)) => float_to_integer(*value).map(integer),
+        (PrimitiveType::Integer, Data::String(value)) => value.trim().parse().ok().map(integer),
+        (PrimitiveType::Float64, Data::Int(value)) => double(*value as f64),
+        (PrimitiveType::Float64, Data::Float(value)) => double(*value),
+        (PrimitiveType::Float64, Data::String(value)) => value.trim().parse().ok().and_then(double),
+        (PrimitiveType::Uuid, Data::String(value)) => {
+            value.trim().parse::<LogicalUuid>().ok().map(Term::from)
+        }
+        (PrimitiveType::IpAddress, Data::String(value)) => {
+            value.trim().parse::<LogicalIpAddress>().ok().map(Term::from)
+        }
+        (
+            PrimitiveType::Integer
+            | PrimitiveType::Float64
+            | PrimitiveType::Uuid
+            | PrimitiveType::IpAddress,
+            _,
+        ) => None,
+    }
+}
+
+impl TableReader for XlsxReader {
+    fn read_into_builder_proxies<'a: 'b, 'b>(
+        self: Box<Self>,
+        builder_proxies: &'b mut Vec<PhysicalBuilderProxyEnum<'a>>,
+    ) -> Result<(), ReadingError> {
+        let cells = self.read_cells()?;
+
+        let mut builders = builder_proxies
+            .iter_mut()
+            .zip(self.logical_types.iter())
+            .map(|(bp, lt)| lt.wrap_physical_column_builder(bp))
+            .collect::<Vec<_>>();
+
+        let first_row = cells.start().map_or(0, |(row, _)| row);
+
+        let mut facts = 0;
+        let mut ignored_rows = 0;
+        for (index, row) in cells.rows().enumerate() {
+            if row.iter().all(|cell| *cell == Data::Empty) {
+                continue;
+            }
+
+            let fact = row
+                .iter()
+                .zip(self.logical_types.iter())
+                .map(|(cell, logical_type)| cell_to_term(cell, *logical_type))
+                .collect::<Option<Vec<_>>>();
+
+            let Some(fact) = fact.filter(|fact| fact.len() == builders.len()) else {
+                tracing::info!(
+                    "Ignoring row {} of \"{}\", whose cells {row:?} cannot be converted into the types {:?}",
+                    first_row as usize + index + 1,
+                    self.resource,
+                    self.logical_types
+                );
+                ignored_rows += 1;
+                continue;
+            };
+
+            if let Err(error) = Self::add_fact(&mut builders, fact) {
+                tracing::info!(
+                    "Ignoring row {} of \"{}\", conversion failed with: {error}",
+                    first_row as usize + index + 1,
+                    self.resource
+                );
+                ignored_rows += 1;
+                continue;
+            }
+
+            facts += 1;
+            if facts % PROGRESS_NOTIFY_INCREMENT == 0 {
+                tracing::info!("Loading: processed {facts} facts");
+            }
+        }
+
+        tracing::info!("Finished loading: processed {facts} facts");
+        self.resource_providers
+            .input_statistics()
+            .record_rows(&self.resource, facts, ignored_rows);
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use calamine::{CellErrorType, Data, ExcelDateTime, ExcelDateTimeType};
+    use nemo_physical::error::ReadingError;
+
+    use super::{cell_to_term, XlsxReader};
+    use crate::{
+        io::resource_providers::ResourceProviders,
+        model::{NumericLiteral, PrimitiveType, Term, TupleConstraint, XlsxFile},
+    };
+
+    const FILE: &str = "../resources/doc/examples/cities.xlsx";
+
+    fn reader(sheet: Option<&str>, range: Option<&str>, arity: usize) -> XlsxReader {
+        XlsxReader::new(
+            ResourceProviders::default(),
+            &XlsxFile::new(
+                FILE,
+                sheet.map(String::from),
+                range.map(|range| range.parse().unwrap()),
+                TupleConstraint::from_arity(arity),
+            ),
+            vec![PrimitiveType::Any; arity],
+        )
+    }
+
+    fn string(value: &str) -> Data {
+        Data::String(value.to_string())
+    }
+
+    #[test]
+    fn convert_cells() {
+        let date = Data::DateTime(ExcelDateTime::new(
+            44927.5,
+            ExcelDateTimeType::DateTime,
+            false,
+        ));
+
+        assert_eq!(
+            cell_to_term(&Data::Float(42.0), PrimitiveType::Any),
+            Some(Term::NumericLiteral(NumericLiteral::Integer(42)))
+        );
+        assert_eq!(
+            cell_to_term(&Data::Float(42.0), PrimitiveType::String),
+            Some(Term::StringLiteral("42".to_string()))
+        );
+        assert_eq!(
+            cell_to_term(&string(" 7 "), PrimitiveType::Integer),
+            Some(Term::NumericLiteral(NumericLiteral::Integer(7)))
+        );
+        assert_eq!(
+            cell_to_term(&Data::Float(4.2), PrimitiveType::Integer),
+            None
+        );
+        assert_eq!(
+            cell_to_term(&date, PrimitiveType::String),
+            Some(Term::StringLiteral("2023-01-01T12:00:00Z".to_string()))
+        );
+        assert_eq!(
+            cell_to_term(&date, PrimitiveType::DateTime),
+            cell_to_term(
+                &Data::DateTimeIso("2023-01-01T12:00:00".to_string()),
+                PrimitiveType::Any
+            )
+        );
+        assert_eq!(
+            cell_to_term(&Data::Bool(true), PrimitiveType::DateTime),
+            None
+        );
+        assert_eq!(
+            cell_to_term(&Data::Error(CellErrorType::NA), PrimitiveType::String),
+            None
+        );
+        assert_eq!(cell_to_term(&Data::Empty, PrimitiveType::Any), None);
+    }
+
+    #[test]
+    fn select_cells() {
+        // Unbounded ranges extend to the last row and have one column per logical type
+        let cells = reader(Some("Cities"), Some("A2"), 3).read_cells().unwrap();
+        assert_eq!(cells.start(), Some((1, 0)));
+        assert_eq!(cells.end(), Some((6, 2)));
+        assert_eq!(
+            cells.rows().nth(2).unwrap(),
+            [Data::Empty, Data::Empty, Data::Empty]
+        );
+        assert_eq!(
+            cells.rows().nth(3).unwrap()[1],
+            Data::Error(CellErrorType::NA)
+        );
+
+        // The first sheet is read by default
+        let cells = reader(None, Some("A1:B3"), 2).read_cells().unwrap();
+        assert_eq!(
+            cells.rows().map(<[Data]>::to_vec).collect::<Vec<_>>(),
+            vec![
+                vec![string("City"), string("Population")],
+                vec![string("Dresden"), Data::Float(554649.0)],
+                vec![string("Boston"), Data::Float(654776.0)],
+            ]
+        );
+
+        let cells = reader(Some("Notes"), None, 1).read_cells().unwrap();
+        assert_eq!(
+            cells.rows().map(<[Data]>::to_vec).collect::<Vec<_>>(),
+            vec![vec![string("Population of some cities")]]
+        );
+
+        assert!(matches!(
+            reader(Some("Towns"), None, 1).read_cells(),
+            Err(ReadingError::ExternalReadingError(_))
+        ));
+    }
+}
@@ -0,0 +1,160 @@
+//! Reusing the encoded tables of input files across runs.
+//!
+//! Parsing large input files and encoding their values in the dictionary often dominates the time of a run.
+//! An [`InputCache`] stores the sorted trie of each input source in a cache directory,
+//! identified by a hash of the content of its resources together with the declaration of the source and its types.
+//! When the same input is loaded again, its table is read from the cache instead,
+//! such that only the dictionary entries of the table need to be added to the dictionary,
+//! see [`table_cache`][nemo_physical::management::table_cache].
+//!
+//! Cache files are never removed, since the same inputs may be used by several programs;
+//! the cache directory can be deleted at any time.
+//! External sources and SPARQL queries are never cached, since their content may change without notice.
+
+use std::{
+    fs::create_dir_all,
+    hash::{Hash, Hasher},
+    path::{Path, PathBuf},
+};
+
+use nemo_physical::management::database::TableSource;
+
+use crate::{
+    error::Error,
+    model::{DataSource, DataSourceDeclaration, NativeDataSource, PrimitiveType},
+    util::stable_hash::StableHasher,
+};
+
+use super::input_manager::InputManager;
+
+/// Directory containing the encoded tables of input sources.
+#[derive(Debug, Clone)]
+pub struct InputCache {
+    directory: PathBuf,
+}
+
+impl InputCache {
+    /// Create a new [`InputCache`] storing tables in the given directory,
+    /// which is created when the first table is cached.
+    pub fn new(directory: PathBuf) -> Self {
+        Self { directory }
+    }
+
+    /// Return the directory containing the cached tables.
+    pub fn directory(&self) -> &Path {
+        &self.directory
+    }
+
+    /// Return the path of the cache file of the table with the given hash.
+    pub fn table_path(&self, hash: u64) -> PathBuf {
+        self.directory.join(format!("{hash:016x}.table"))
+    }
+
+    /// Compute the hash identifying the table of the given source declaration,
+    /// whose columns have the given types.
+    ///
+    /// Returns `None` if the source cannot be cached.
+    pub(crate) fn source_hash(
+        declaration: &DataSourceDeclaration,
+        logical_types: &[PrimitiveType],
+        input_manager: &InputManager,
+    ) -> Result<Option<u64>, Error> {
+        if matches!(
+            declaration.source,
+            NativeDataSource::External(_) | NativeDataSource::SparqlQuery(_)
+        ) {
+            return Ok(None);
+        }
+
+        let mut hasher = StableHasher::default();
+        env!("CARGO_PKG_VERSION").hash(&mut hasher);
+        format!("{:?}", declaration.source).hash(&mut hasher);
+        format!("{logical_types:?}").hash(&mut hasher);
+        declaration.is_bag().hash(&mut hasher);
+        for resource in declaration.source.resources() {
+            input_manager.resource_hash(&resource)?.hash(&mut hasher);
+        }
+
+        Ok(Some(hasher.finish()))
+    }
+
+    /// Wrap the [`TableSource`] loading the given source declaration,
+    /// such that its table is read from this cache if it has been stored before,
+    /// and stored otherwise.
+    pub(crate) fn table_source(
+        &self,
+        table_source: TableSource,
+        declaration: &DataSourceDeclaration,
+        logical_types: &[PrimitiveType],
+        input_manager: &InputManager,
+    ) -> Result<TableSource, Error> {
+        let Some(hash) = Self::source_hash(declaration, logical_types, input_manager)? else {
+            return Ok(table_source);
+        };
+
+        create_dir_all(&self.directory)?;
+
+        Ok(TableSource::Cached(
+            self.table_path(hash),
+            Box::new(table_source),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{fs::write, path::Path};
+
+    use assert_fs::TempDir;
+
+    use crate::{
+        execution::DefaultExecutionEngine,
+        io::{parser::parse_program, resource_providers::ResourceProviders},
+        model::Identifier,
+    };
+
+    use super::InputCache;
+
+    fn edges(directory: &Path, resource_providers: ResourceProviders) -> Vec<Vec<String>> {
+        let program = parse_program(format!(
+            "@source edge[any, integer]: load-csv(\"{}\") .\n",
+            directory.join("edge.csv").display()
+        ))
+        .unwrap();
+
+        let mut engine = DefaultExecutionEngine::initialize(program, resource_providers).unwrap();
+
+        let mut rows = engine
+            .output_serialization(Identifier("edge".to_string()))
+            .unwrap()
+            .unwrap()
+            .collect::<Vec<_>>();
+        rows.sort();
+        rows
+    }
+
+    #[test]
+    fn cached_inputs_are_reused() {
+        let directory = TempDir::new().unwrap();
+        let cache = InputCache::new(directory.path().join("cache"));
+        let cached = || ResourceProviders::default().with_input_cache(cache.clone());
+        let cache_files = || std::fs::read_dir(cache.directory()).unwrap().count();
+
+        write(directory.path().join("edge.csv"), "b,2\na,1\nb,2\n").unwrap();
+        let expected = edges(directory.path(), ResourceProviders::default());
+        assert_eq!(expected.len(), 2);
+
+        assert_eq!(edges(directory.path(), cached()), expected);
+        assert_eq!(cache_files(), 1);
+
+        // The table is read from the cache file
+        assert_eq!(edges(directory.path(), cached()), expected);
+        assert_eq!(cache_files(), 1);
+
+        // Changing the input changes its hash
+        write(directory.path().join("edge.csv"), "c,3\n").unwrap();
+        let expected = edges(directory.path(), ResourceProviders::default());
+        assert_eq!(edges(directory.path(), cached()), expected);
+        assert_eq!(cache_files(), 2);
+    }
+}
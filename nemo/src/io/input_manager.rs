@@ -1,21 +1,29 @@
 //! Management of resource providers, handling of decompression and resolution of resources to readers.
 
+use std::hash::Hasher;
+
 use nemo_physical::{
-    error::ReadingError, management::database::TableSource, table_reader::TableReader,
+    error::ReadingError,
+    management::database::TableSource,
+    table_reader::{Resource, TableReader},
 };
 
+#[cfg(feature = "xlsx")]
+use crate::io::formats::XlsxReader;
 use crate::{
     error::Error,
     io::{
-        formats::{DSVReader, RDFTriplesReader},
+        formats::{DSVReader, ExternalSourceReader, HDTReader, RDFTriplesReader, RdfFormat},
+        input_statistics::ResourceStatistics,
         resource_providers::ResourceProviders,
     },
-    model::{NativeDataSource, PrimitiveType},
+    model::{DataSourceDeclaration, NativeDataSource, PrimitiveType},
+    util::stable_hash::StableHasher,
 };
 
 /// Manages everything related to resolving the inputs of a Nemo program.
 /// Currently, this is only the resource providers.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct InputManager {
     resource_providers: ResourceProviders,
 }
@@ -41,6 +49,58 @@ impl InputManager {
 
         Ok(TableSource::FileReader(resolver))
     }
+
+    /// Constructs a [`TableSource`] for a [`NativeDataSource`] whose tuples are treated as a bag.
+    ///
+    /// The last of the given types belongs to the column containing the number of occurrences
+    /// of each tuple, which is computed while loading the table.
+    pub fn load_counted_native_table_source(
+        &self,
+        data_source: NativeDataSource,
+        mut logical_types: Vec<PrimitiveType>,
+    ) -> Result<TableSource, Error> {
+        logical_types.pop();
+
+        let resolver = Box::new(NativeDataSourceResolver {
+            resource_providers: self.resource_providers.clone(),
+            data_source,
+            logical_types,
+        });
+
+        Ok(TableSource::CountedFileReader(resolver))
+    }
+
+    /// Wrap the [`TableSource`] loading the given source declaration,
+    /// such that its table is read from the [`InputCache`][crate::io::InputCache]
+    /// of the resource providers, if there is one.
+    pub fn cached_table_source(
+        &self,
+        table_source: TableSource,
+        declaration: &DataSourceDeclaration,
+        logical_types: &[PrimitiveType],
+    ) -> Result<TableSource, Error> {
+        match self.resource_providers.input_cache() {
+            Some(input_cache) => {
+                input_cache.table_source(table_source, declaration, logical_types, self)
+            }
+            None => Ok(table_source),
+        }
+    }
+
+    /// Return the statistics of the resources that have been read so far.
+    pub fn input_statistics(&self) -> Vec<ResourceStatistics> {
+        self.resource_providers.input_statistics().resources()
+    }
+
+    /// Compute a hash of the content of the given resource,
+    /// which is the same in every run as long as the resource does not change.
+    pub fn resource_hash(&self, resource: &Resource) -> Result<u64, Error> {
+        let mut reader = self.resource_providers.open_resource(resource, false)?;
+        let mut hasher = StableHasher::default();
+        std::io::copy(&mut reader, &mut hasher)?;
+
+        Ok(hasher.finish())
+    }
 }
 
 /// Implements [TableReader] by resolving the [NativeDataSource] upon read request.
@@ -62,6 +122,28 @@ impl NativeDataSourceResolver {
                 );
                 Ok(Box::new(dsv_reader))
             }
+            #[cfg(feature = "xlsx")]
+            NativeDataSource::XlsxFile(xlsx_file) => {
+                let xlsx_reader = XlsxReader::new(
+                    self.resource_providers.clone(),
+                    xlsx_file,
+                    self.logical_types.clone(),
+                );
+                Ok(Box::new(xlsx_reader))
+            }
+            #[cfg(not(feature = "xlsx"))]
+            NativeDataSource::XlsxFile(_) => Err(ReadingError::FeatureNotEnabled {
+                format: "XLSX",
+                feature: "xlsx",
+            }),
+            NativeDataSource::RdfFile(rdf_file) if rdf_file.format == Some(RdfFormat::Hdt) => {
+                let hdt_reader = HDTReader::new(
+                    self.resource_providers.clone(),
+                    rdf_file,
+                    self.logical_types.clone(),
+                );
+                Ok(Box::new(hdt_reader))
+            }
             NativeDataSource::RdfFile(rdf_file) => {
                 let rdf_reader = RDFTriplesReader::new(
                     self.resource_providers.clone(),
@@ -70,6 +152,23 @@ impl NativeDataSourceResolver {
                 );
                 Ok(Box::new(rdf_reader))
             }
+            NativeDataSource::External(external_source) => {
+                let external_reader = ExternalSourceReader::new(
+                    external_source,
+                    self.resource_providers
+                        .external_source(&external_source.name)?,
+                    self.logical_types.clone(),
+                );
+                Ok(Box::new(external_reader))
+            }
+            NativeDataSource::CustomFormat(custom_format_file) => self
+                .resource_providers
+                .import_format(custom_format_file)?
+                .reader(
+                    self.resource_providers.clone(),
+                    &custom_format_file.resource,
+                    self.logical_types.clone(),
+                ),
             NativeDataSource::SparqlQuery(_) => {
                 todo!("SPARQL query data sources are not yet implemented")
             }
@@ -85,4 +184,61 @@ impl TableReader for NativeDataSourceResolver {
         let table_reader = self.resolve_data_source()?;
         table_reader.read_into_builder_proxies(builder_proxies)
     }
+
+    fn is_sorted(&self) -> bool {
+        matches!(&self.data_source, NativeDataSource::DsvFile(dsv_file) if dsv_file.sorted)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs::write;
+
+    use assert_fs::TempDir;
+    use nemo_physical::{
+        datatypes::DataTypeName,
+        management::database::{ColumnOrder, DatabaseInstance},
+        tabular::{table_types::trie::Trie, traits::table_schema::TableSchema},
+    };
+
+    use crate::{
+        io::resource_providers::ResourceProviders,
+        model::{DsvFile, NativeDataSource, PrimitiveType, TupleConstraint},
+    };
+
+    use super::InputManager;
+
+    fn load(dsv_file: DsvFile) -> Trie {
+        let types = vec![PrimitiveType::Integer, PrimitiveType::Any];
+        let schema = TableSchema::from_vec(types.iter().copied().map(DataTypeName::from).collect());
+        let source = InputManager::new(ResourceProviders::default())
+            .load_native_table_source(NativeDataSource::DsvFile(dsv_file), types)
+            .unwrap();
+
+        let mut instance = DatabaseInstance::new();
+        let id = instance.register_table("p", schema);
+        instance.add_sources(id, ColumnOrder::default(), vec![source]);
+        instance
+            .get_trie_or_load(id, &ColumnOrder::default())
+            .unwrap()
+            .clone()
+    }
+
+    #[test]
+    fn sorted_rows_are_loaded_correctly() {
+        let directory = TempDir::new().unwrap();
+
+        for (file, content) in [
+            ("sorted.csv", "1,a\n1,a\n1,b\n2,a\n"),
+            ("unsorted.csv", "2,a\n1,b\n1,a\n2,a\n"),
+        ] {
+            let path = directory.path().join(file);
+            write(&path, content).unwrap();
+
+            let types = TupleConstraint::from_arity(2);
+            let dsv_file = DsvFile::csv_file(&path.to_string_lossy(), types);
+
+            assert_eq!(load(dsv_file.clone().with_sorted_rows()), load(dsv_file));
+        }
+    }
 }
@@ -0,0 +1,179 @@
+//! Statistics about the resources read while loading the inputs of a program.
+//!
+//! For every resource that is read completely, the number of bytes and a checksum of its content are recorded,
+//! so that results can be traced back to the exact version of their inputs.
+//! Readers of the supported formats additionally record how many rows they accepted or rejected.
+//! The checksum is the SHA-256 hash of the raw content of the resource, i.e. of the content before decompression.
+
+use std::{
+    cell::RefCell,
+    io::{Read, Result as IoResult},
+    rc::Rc,
+};
+
+use nemo_physical::table_reader::Resource;
+use sha2::{Digest, Sha256};
+
+/// Statistics about reading a single resource
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResourceStatistics {
+    /// The resource
+    pub resource: Resource,
+    /// Size of the (raw) content of the resource in bytes,
+    /// or `None` if the resource has not been read completely
+    pub bytes: Option<u64>,
+    /// SHA-256 hash of the (raw) content of the resource,
+    /// or `None` if the resource has not been read completely
+    pub checksum: Option<[u8; 32]>,
+    /// Number of rows that have been loaded
+    pub accepted_rows: u64,
+    /// Number of rows that have been ignored, since they could not be parsed
+    pub rejected_rows: u64,
+}
+
+impl ResourceStatistics {
+    fn new(resource: Resource) -> Self {
+        Self {
+            resource,
+            bytes: None,
+            checksum: None,
+            accepted_rows: 0,
+            rejected_rows: 0,
+        }
+    }
+
+    /// Return the checksum of the content of the resource as hexadecimal number,
+    /// or `None` if the resource has not been read completely.
+    pub fn checksum_hex(&self) -> Option<String> {
+        self.checksum
+            .map(|checksum| checksum.iter().map(|byte| format!("{byte:02x}")).collect())
+    }
+}
+
+/// Collects [`ResourceStatistics`] of all resources read through some
+/// [`ResourceProviders`][super::resource_providers::ResourceProviders],
+/// which is shared between all of their clones.
+///
+/// If a resource is read several times, the statistics of the last read are kept.
+#[derive(Debug, Clone, Default)]
+pub struct InputStatistics {
+    resources: Rc<RefCell<Vec<ResourceStatistics>>>,
+}
+
+impl InputStatistics {
+    /// Apply the given function to the statistics of the given resource.
+    fn update(&self, resource: &Resource, update: impl FnOnce(&mut ResourceStatistics)) {
+        let mut resources = self.resources.borrow_mut();
+        let index = match resources
+            .iter()
+            .position(|entry| &entry.resource == resource)
+        {
+            Some(index) => index,
+            None => {
+                resources.push(ResourceStatistics::new(resource.clone()));
+                resources.len() - 1
+            }
+        };
+
+        update(&mut resources[index]);
+    }
+
+    /// Record the number of rows of the given resource that have been accepted or rejected.
+    pub(crate) fn record_rows(&self, resource: &Resource, accepted: u64, rejected: u64) {
+        self.update(resource, |statistics| {
+            statistics.accepted_rows = accepted;
+            statistics.rejected_rows = rejected;
+        });
+    }
+
+    /// Record the size and the checksum of the content of the given resource.
+    fn record_content(&self, resource: &Resource, bytes: u64, checksum: [u8; 32]) {
+        self.update(resource, |statistics| {
+            statistics.bytes = Some(bytes);
+            statistics.checksum = Some(checksum);
+        });
+    }
+
+    /// Wrap the given reader of the content of a resource,
+    /// such that the size and the checksum of the content are recorded once it has been read completely.
+    pub(crate) fn reader(&self, resource: &Resource, reader: Box<dyn Read>) -> Box<dyn Read> {
+        Box::new(StatisticsReader {
+            reader,
+            resource: resource.clone(),
+            bytes: 0,
+            hasher: Sha256::new(),
+            statistics: self.clone(),
+            recorded: false,
+        })
+    }
+
+    /// Return the statistics of all resources, sorted by resource.
+    pub fn resources(&self) -> Vec<ResourceStatistics> {
+        let mut resources = self.resources.borrow().clone();
+        resources.sort_by(|first, second| first.resource.cmp(&second.resource));
+        resources
+    }
+}
+
+/// Reader computing the size and the checksum of the content read through it,
+/// see [`InputStatistics::reader`]
+struct StatisticsReader {
+    reader: Box<dyn Read>,
+    resource: Resource,
+    bytes: u64,
+    hasher: Sha256,
+    statistics: InputStatistics,
+    recorded: bool,
+}
+
+impl Read for StatisticsReader {
+    fn read(&mut self, buf: &mut [u8]) -> IoResult<usize> {
+        let read = self.reader.read(buf)?;
+
+        if read > 0 {
+            self.bytes += read as u64;
+            self.hasher.update(&buf[..read]);
+        } else if !buf.is_empty() && !self.recorded {
+            self.statistics.record_content(
+                &self.resource,
+                self.bytes,
+                self.hasher.finalize_reset().into(),
+            );
+            self.recorded = true;
+        }
+
+        Ok(read)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::io::Read;
+
+    use super::InputStatistics;
+
+    #[test]
+    fn records_content_when_read_completely() {
+        let statistics = InputStatistics::default();
+        let resource = "input.csv".to_string();
+
+        let mut partial = statistics.reader(&resource, Box::new(&b"a,1\nb,2\n"[..]));
+        partial.read_exact(&mut [0; 2]).unwrap();
+        statistics.record_rows(&resource, 2, 1);
+        assert_eq!(statistics.resources()[0].bytes, None);
+
+        let mut complete = statistics.reader(&resource, Box::new(&b"a,1\nb,2\n"[..]));
+        complete.read_to_end(&mut Vec::new()).unwrap();
+
+        let resources = statistics.resources();
+        assert_eq!(resources.len(), 1);
+        assert_eq!(resources[0].bytes, Some(8));
+        // SHA-256 hash of the content, as computed by `sha256sum`
+        assert_eq!(
+            resources[0].checksum_hex().unwrap(),
+            "e5fd38088cdea99f970183c3cfb6b06c5c20b22b52eac097729a490f5b7b5e04"
+        );
+        assert_eq!(resources[0].accepted_rows, 2);
+        assert_eq!(resources[0].rejected_rows, 1);
+    }
+}
@@ -0,0 +1,321 @@
+//! Continuous reasoning over facts that are consumed from Kafka topics.
+//!
+//! Each input topic feeds an external source of the program, e.g. `@source edge[2]: external("edges") .`,
+//! with the facts contained in its messages.
+//! After each batch of messages, the materialization is brought up to date
+//! and the changes of the output predicates are published to the output topic.
+
+use std::{collections::HashMap, time::Duration};
+
+use rdkafka::{
+    consumer::{BaseConsumer, Consumer},
+    producer::{BaseProducer, BaseRecord, Producer},
+    ClientConfig, Message,
+};
+use serde_json::{json, Value};
+use thiserror::Error;
+
+use crate::{
+    builder_proxy::parse_rdf_term_from_string,
+    error::Error,
+    execution::{Delta, StreamingReasoner},
+    model::{Identifier, NumericLiteral, Term},
+};
+
+/// Maximal number of messages that are consumed before the materialization is updated
+const BATCH_SIZE: usize = 10_000;
+
+/// Errors that can occur when parsing the payload of a message.
+#[derive(Error, Debug)]
+pub enum PayloadError {
+    /// The payload is not valid JSON.
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+    /// The payload is not valid CSV.
+    #[error(transparent)]
+    Csv(#[from] csv::Error),
+    /// The payload contains a JSON value that does not describe facts.
+    #[error("Expected a term or an array of terms, found {0}.")]
+    InvalidValue(Value),
+}
+
+/// Format of the payload of messages that contain facts.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum PayloadFormat {
+    /// A JSON array of terms, e.g. `["a", 42]`, or an array of such arrays for several facts.
+    /// Numbers are read as numeric literals, strings are parsed like the fields of CSV files.
+    #[default]
+    Json,
+    /// Comma-separated values without header, with one fact per line.
+    Csv,
+}
+
+impl PayloadFormat {
+    /// Parse the facts contained in the given payload.
+    pub fn parse(&self, payload: &[u8]) -> Result<Vec<Vec<Term>>, PayloadError> {
+        match self {
+            PayloadFormat::Json => match serde_json::from_slice(payload)? {
+                Value::Array(values) if values.iter().all(Value::is_array) => values
+                    .into_iter()
+                    .map(Self::json_fact)
+                    .collect::<Result<_, _>>(),
+                value => Ok(vec![Self::json_fact(value)?]),
+            },
+            PayloadFormat::Csv => csv::ReaderBuilder::new()
+                .has_headers(false)
+                .flexible(true)
+                .from_reader(payload)
+                .records()
+                .map(|record| {
+                    Ok(record?
+                        .iter()
+                        .map(|field| parse_rdf_term_from_string(field.to_string()))
+                        .collect())
+                })
+                .collect(),
+        }
+    }
+
+    fn json_fact(value: Value) -> Result<Vec<Term>, PayloadError> {
+        match value {
+            Value::Array(values) => values.into_iter().map(Self::json_term).collect(),
+            value => Err(PayloadError::InvalidValue(value)),
+        }
+    }
+
+    fn json_term(value: Value) -> Result<Term, PayloadError> {
+        match value {
+            Value::String(string) => Ok(parse_rdf_term_from_string(string)),
+            Value::Number(number) => match (number.as_i64(), number.as_f64()) {
+                (Some(integer), _) => Ok(Term::NumericLiteral(NumericLiteral::Integer(integer))),
+                (None, Some(double)) => Ok(Term::NumericLiteral(NumericLiteral::Double(
+                    double
+                        .try_into()
+                        .map_err(|_| PayloadError::InvalidValue(Value::Number(number)))?,
+                ))),
+                _ => Err(PayloadError::InvalidValue(Value::Number(number))),
+            },
+            value => Err(PayloadError::InvalidValue(value)),
+        }
+    }
+}
+
+/// Serialize the given [`Delta`] as the JSON payload of a message.
+fn delta_payload(delta: &Delta) -> String {
+    json!({
+        "predicate": delta.predicate.name(),
+        "added": delta.added,
+        "removed": delta.removed,
+    })
+    .to_string()
+}
+
+/// Configuration of a Kafka stream feeding a [`StreamingReasoner`].
+#[derive(Debug, Clone)]
+pub struct KafkaStream {
+    brokers: String,
+    group_id: String,
+    inputs: HashMap<String, String>,
+    output_topic: Option<String>,
+    format: PayloadFormat,
+    batch_timeout: Duration,
+}
+
+impl KafkaStream {
+    /// Create a new [`KafkaStream`] that connects to the given brokers (e.g. `localhost:9092`)
+    /// as a member of the given consumer group.
+    pub fn new(brokers: &str, group_id: &str) -> Self {
+        Self {
+            brokers: brokers.to_string(),
+            group_id: group_id.to_string(),
+            inputs: HashMap::new(),
+            output_topic: None,
+            format: PayloadFormat::default(),
+            batch_timeout: Duration::from_millis(500),
+        }
+    }
+
+    /// Feed the external source with the given name with the facts from the given topic.
+    pub fn with_input(mut self, topic: &str, source: &str) -> Self {
+        self.inputs.insert(topic.to_string(), source.to_string());
+        self
+    }
+
+    /// Publish the changes of the output predicates to the given topic.
+    ///
+    /// Each message contains the changes of one predicate,
+    /// e.g. `{"predicate":"p","added":[["a","b"]],"removed":[]}`, and is keyed by the predicate.
+    /// Without an output topic, the changes are only logged.
+    pub fn with_output(mut self, topic: &str) -> Self {
+        self.output_topic = Some(topic.to_string());
+        self
+    }
+
+    /// Set the format of the payload of the consumed messages.
+    pub fn with_format(mut self, format: PayloadFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Set how long to wait for further messages before the materialization is updated.
+    pub fn with_batch_timeout(mut self, batch_timeout: Duration) -> Self {
+        self.batch_timeout = batch_timeout;
+        self
+    }
+
+    /// Consume the input topics and keep the materialization of the given reasoner up to date.
+    ///
+    /// Since the received facts are only kept in memory,
+    /// the input topics are consumed from the beginning whenever the stream is started.
+    /// Messages whose payload cannot be parsed are skipped.
+    /// This only returns if an error occurs.
+    pub fn run(&self, reasoner: &mut StreamingReasoner) -> Result<(), Error> {
+        let consumer: BaseConsumer = ClientConfig::new()
+            .set("bootstrap.servers", &self.brokers)
+            .set("group.id", &self.group_id)
+            .set("auto.offset.reset", "earliest")
+            .set("enable.auto.commit", "false")
+            .create()?;
+        let topics = self.inputs.keys().map(String::as_str).collect::<Vec<_>>();
+        consumer.subscribe(&topics)?;
+
+        for source in self.inputs.values() {
+            reasoner.add_facts(source, []);
+        }
+
+        let producer: Option<BaseProducer> = match &self.output_topic {
+            Some(_) => Some(
+                ClientConfig::new()
+                    .set("bootstrap.servers", &self.brokers)
+                    .create()?,
+            ),
+            None => None,
+        };
+
+        self.publish(producer.as_ref(), reasoner.update()?)?;
+
+        loop {
+            let mut messages = 0;
+            while messages < BATCH_SIZE {
+                let Some(message) = consumer.poll(self.batch_timeout) else {
+                    break;
+                };
+                let message = message?;
+
+                let Some(source) = self.inputs.get(message.topic()) else {
+                    continue;
+                };
+
+                match self.format.parse(message.payload().unwrap_or_default()) {
+                    Ok(facts) => reasoner.add_facts(source, facts),
+                    Err(error) => {
+                        tracing::warn!(
+                            "Ignoring message at offset {} of topic {}: {error}",
+                            message.offset(),
+                            message.topic()
+                        );
+                        continue;
+                    }
+                }
+
+                messages += 1;
+            }
+
+            if messages > 0 {
+                tracing::info!("Updating materialization after {messages} messages");
+                self.publish(producer.as_ref(), reasoner.update()?)?;
+            }
+        }
+    }
+
+    /// Publish the given changes to the output topic, if there is one.
+    fn publish(&self, producer: Option<&BaseProducer>, deltas: Vec<Delta>) -> Result<(), Error> {
+        for delta in deltas {
+            let Identifier(predicate) = &delta.predicate;
+            tracing::info!(
+                "Predicate {predicate}: {} facts added, {} facts removed",
+                delta.added.len(),
+                delta.removed.len()
+            );
+
+            if let (Some(producer), Some(topic)) = (producer, &self.output_topic) {
+                let payload = delta_payload(&delta);
+                producer
+                    .send(BaseRecord::to(topic).key(predicate).payload(&payload))
+                    .map_err(|(error, _)| error)?;
+            }
+        }
+
+        if let Some(producer) = producer {
+            producer.flush(Duration::from_secs(10))?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        execution::Delta,
+        model::{Identifier, NumericLiteral, Term},
+    };
+
+    use super::{delta_payload, PayloadError, PayloadFormat};
+
+    fn constant(name: &str) -> Term {
+        Term::Constant(Identifier(name.to_string()))
+    }
+
+    #[test]
+    fn json_payload() {
+        let integer = Term::NumericLiteral(NumericLiteral::Integer(42));
+
+        assert_eq!(
+            PayloadFormat::Json.parse(br#"["a", 42]"#).unwrap(),
+            vec![vec![constant("a"), integer.clone()]]
+        );
+        assert_eq!(
+            PayloadFormat::Json
+                .parse(br#"[["a", 42], ["b", "\"some text\""]]"#)
+                .unwrap(),
+            vec![
+                vec![constant("a"), integer],
+                vec![constant("b"), Term::StringLiteral("some text".to_string())]
+            ]
+        );
+        assert!(matches!(
+            PayloadFormat::Json.parse(br#"{"a": 42}"#),
+            Err(PayloadError::InvalidValue(_))
+        ));
+        assert!(matches!(
+            PayloadFormat::Json.parse(b"[a"),
+            Err(PayloadError::Json(_))
+        ));
+    }
+
+    #[test]
+    fn csv_payload() {
+        assert_eq!(
+            PayloadFormat::Csv.parse(b"a,b\nc,d\n").unwrap(),
+            vec![
+                vec![constant("a"), constant("b")],
+                vec![constant("c"), constant("d")]
+            ]
+        );
+    }
+
+    #[test]
+    fn delta_serialization() {
+        let delta = Delta {
+            predicate: Identifier("p".to_string()),
+            added: vec![vec!["a".to_string(), "b".to_string()]],
+            removed: vec![],
+        };
+
+        assert_eq!(
+            delta_payload(&delta),
+            r#"{"added":[["a","b"]],"predicate":"p","removed":[]}"#
+        );
+    }
+}
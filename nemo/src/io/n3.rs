@@ -0,0 +1,947 @@
+//! Import of rules in [Notation3](https://w3c.github.io/N3/spec/) (N3),
+//! which extends Turtle by formulas (quoted graph patterns) and implications between them.
+//!
+//! Only the monotonic subset of N3 rules is supported:
+//! every statement at the top level is either a triple, which becomes a fact,
+//! or an implication `{ ... } => { ... }` (or `{ ... } <= { ... }`) between formulas of triples, which becomes a rule.
+//! Each triple is translated into an atom of the predicate [`TRIPLE_PREDICATE`],
+//! such that the rules can be combined with RDF data that is loaded into this predicate.
+//!
+//! Universal variables are written as `?x`, and every universal variable of the conclusion of a rule
+//! has to occur in its premise.
+//! Blank nodes in the premise of a rule match arbitrary terms,
+//! and blank nodes in the conclusion are translated into existential variables.
+//!
+//! Built-in predicates (such as `math:sum` or `log:notIncludes`), nested formulas, lists, paths,
+//! equality (`=`) and explicit quantification (`@forAll` and `@forSome`) are not supported.
+
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Write,
+};
+
+use nom::{
+    branch::alt,
+    bytes::complete::{tag, tag_no_case, take_while, take_while1},
+    character::complete::{alpha1, alphanumeric1, char, multispace1, not_line_ending, one_of},
+    combinator::{not, opt, peek, recognize, value},
+    multi::many0,
+    sequence::{delimited, pair, preceded, terminated, tuple},
+    IResult,
+};
+use oxiri::Iri;
+use thiserror::Error;
+
+use crate::{
+    io::{parser::turtle, r2rml::TRIPLE_PREDICATE},
+    model::{XSD_BOOLEAN, XSD_DECIMAL, XSD_DOUBLE, XSD_INTEGER},
+};
+
+/// Namespace of the built-in predicates of N3
+const SWAP: &str = "http://www.w3.org/2000/10/swap/";
+/// IRI of `log:implies`, which is abbreviated by `=>`
+const LOG_IMPLIES: &str = "http://www.w3.org/2000/10/swap/log#implies";
+/// IRI of `rdf:type`, which is abbreviated by `a`
+const RDF_TYPE: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#type";
+
+/// Errors that can occur when importing N3 rules.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum N3Error {
+    /// The rules are not syntactically valid (or use unsupported syntax).
+    #[error("Syntax error in N3 rules on line {0}.")]
+    Syntax(usize),
+    /// A prefixed name uses a prefix that has not been declared.
+    #[error("The prefix \"{0}\" is not declared.")]
+    UndeclaredPrefix(String),
+    /// An IRI cannot be resolved against the base IRI.
+    #[error("The IRI \"{0}\" is not valid.")]
+    InvalidIri(String),
+    /// A statement uses a feature that is not supported.
+    #[error("The statement on line {0} uses {1}, which is not supported.")]
+    Unsupported(usize, &'static str),
+    /// A variable of the conclusion of a rule (or of a fact) does not occur in its premise.
+    #[error("The variable ?{1} of the statement on line {0} does not occur in its premise.")]
+    UnsafeVariable(usize, String),
+}
+
+/// A term of a triple in N3 rules.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum N3Term {
+    Iri(String),
+    /// A literal, given in Nemo syntax
+    Literal(String),
+    Variable(String),
+    Blank(String),
+    /// A blank node without label, given by `[ ... ]`
+    Anonymous(usize),
+}
+
+impl N3Term {
+    fn boolean(value: bool) -> Self {
+        N3Term::Literal(format!("\"{value}\"^^<{XSD_BOOLEAN}>"))
+    }
+}
+
+/// A node of a triple as it is parsed, which may be a formula.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Node {
+    Term(N3Term),
+    Formula(Vec<Triple<Node>>),
+    /// Node using a feature that is not supported
+    Unsupported(&'static str),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Triple<T> {
+    subject: T,
+    predicate: T,
+    object: T,
+}
+
+impl<T> Triple<T> {
+    fn nodes(&self) -> [&T; 3] {
+        [&self.subject, &self.predicate, &self.object]
+    }
+}
+
+impl Triple<N3Term> {
+    fn variables(&self) -> impl Iterator<Item = &String> {
+        self.nodes().into_iter().filter_map(|term| match term {
+            N3Term::Variable(name) => Some(name),
+            _ => None,
+        })
+    }
+
+    /// Return the triple as atom in Nemo syntax,
+    /// where variables and blank nodes are written by the given function.
+    fn to_nemo(&self, mut name: impl FnMut(&N3Term) -> String) -> String {
+        let terms = self
+            .nodes()
+            .into_iter()
+            .map(|term| match term {
+                N3Term::Iri(iri) => format!("<{iri}>"),
+                N3Term::Literal(literal) => literal.clone(),
+                term => name(term),
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+        format!("{TRIPLE_PREDICATE}({terms})")
+    }
+}
+
+/// A rule given by an implication between two formulas.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct N3Rule {
+    head: Vec<Triple<N3Term>>,
+    body: Vec<Triple<N3Term>>,
+}
+
+type ParseResult<'a, T> = IResult<&'a str, T>;
+/// Triples of the statements of a document, together with the line on which each statement starts
+type Statements = Vec<(usize, Vec<Triple<Node>>)>;
+
+fn failure(input: &str) -> nom::Err<nom::error::Error<&str>> {
+    nom::Err::Failure(nom::error::Error::new(input, nom::error::ErrorKind::Verify))
+}
+
+/// Skip whitespace and comments.
+fn skip(input: &str) -> ParseResult<'_, ()> {
+    value(
+        (),
+        many0(alt((
+            value((), multispace1),
+            value((), pair(char('#'), not_line_ending)),
+        ))),
+    )(input)
+}
+
+/// Parse the given token, followed by whitespace or comments.
+fn token<'a>(token: &'static str) -> impl FnMut(&'a str) -> ParseResult<'a, &'a str> {
+    terminated(tag(token), skip)
+}
+
+/// Characters of prefixes and blank node labels
+fn name_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '-' || c == '.'
+}
+
+/// Characters of the local part of prefixed names
+fn local_char(c: char) -> bool {
+    name_char(c) || c == ':' || c == '%'
+}
+
+/// Characters of the names of variables
+fn variable_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// Parse a (possibly empty) name consisting of the given characters, which does not end with `.`.
+fn name(allowed: fn(char) -> bool) -> impl Fn(&str) -> ParseResult<'_, &str> {
+    move |input: &str| {
+        let length = input.find(|c| !allowed(c)).unwrap_or(input.len());
+        let length = input[..length].trim_end_matches('.').len();
+        Ok((&input[length..], &input[..length]))
+    }
+}
+
+/// Parse a keyword, which must not be the beginning of a prefixed name.
+fn keyword<'a>(keyword: &'static str) -> impl FnMut(&'a str) -> ParseResult<'a, &'a str> {
+    terminated(tag(keyword), pair(not(take_while1(local_char)), skip))
+}
+
+/// Parse a case-insensitive keyword of SPARQL, such as `PREFIX`.
+fn sparql_keyword<'a>(keyword: &'static str) -> impl FnMut(&'a str) -> ParseResult<'a, &'a str> {
+    terminated(
+        tag_no_case(keyword),
+        pair(not(take_while1(local_char)), skip),
+    )
+}
+
+/// Parse a string in one of the quoting styles of Turtle, and return its unescaped content.
+fn string(input: &str) -> ParseResult<'_, String> {
+    for delimiter in ["\"\"\"", "'''", "\"", "'"] {
+        let Some(rest) = input.strip_prefix(delimiter) else {
+            continue;
+        };
+
+        let mut escaped = false;
+        for (index, c) in rest.char_indices() {
+            if escaped {
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if delimiter.len() == 1 && c == '\n' {
+                break;
+            } else if rest[index..].starts_with(delimiter) {
+                // Long strings may end with up to two quotes before the delimiter
+                let mut end = index;
+                while delimiter.len() == 3
+                    && end < index + 2
+                    && rest[end + 1..].starts_with(delimiter)
+                {
+                    end += 1;
+                }
+
+                let content = &rest[..end];
+                let (rest, _) = skip(&rest[end + delimiter.len()..])?;
+                return Ok((rest, turtle::unescape(content)));
+            }
+        }
+
+        return Err(failure(rest));
+    }
+
+    Err(nom::Err::Error(nom::error::Error::new(
+        input,
+        nom::error::ErrorKind::Char,
+    )))
+}
+
+/// Parse an integer, decimal or double, and return it as literal in Nemo syntax.
+fn number(input: &str) -> ParseResult<'_, N3Term> {
+    let digits = || take_while1(|c: char| c.is_ascii_digit());
+    let exponent = || tuple((one_of("eE"), opt(one_of("+-")), digits()));
+
+    let (rest, number) = terminated(
+        recognize(pair(
+            opt(one_of("+-")),
+            alt((
+                recognize(tuple((
+                    take_while(|c: char| c.is_ascii_digit()),
+                    char('.'),
+                    digits(),
+                    opt(exponent()),
+                ))),
+                recognize(pair(digits(), opt(exponent()))),
+            )),
+        )),
+        skip,
+    )(input)?;
+
+    let datatype = if number.contains(['e', 'E']) {
+        XSD_DOUBLE
+    } else if number.contains('.') {
+        XSD_DECIMAL
+    } else {
+        XSD_INTEGER
+    };
+
+    Ok((rest, N3Term::Literal(format!("\"{number}\"^^<{datatype}>"))))
+}
+
+/// Return the given string as string literal in Nemo syntax.
+fn quote(string: &str) -> String {
+    format!(
+        "\"{}\"",
+        string
+            .replace('\\', "\\\\")
+            .replace('"', "\\\"")
+            .replace('\n', "\\n")
+            .replace('\r', "\\r")
+    )
+}
+
+/// Parser for N3 rules, which keeps track of prefixes, the base IRI and blank nodes.
+struct N3Parser<'a> {
+    input: &'a str,
+    base: Option<Iri<String>>,
+    prefixes: HashMap<String, String>,
+    /// Number of blank nodes without label created so far
+    anonymous: usize,
+    /// Error that caused parsing to fail, other than a syntax error
+    error: Option<N3Error>,
+}
+
+impl<'a> N3Parser<'a> {
+    /// Return the line of the input where the given remainder starts.
+    fn line(&self, rest: &str) -> usize {
+        self.input[..self.input.len() - rest.len()]
+            .matches('\n')
+            .count()
+            + 1
+    }
+
+    /// Stop parsing because of the given error.
+    fn fail(&mut self, input: &'a str, error: N3Error) -> nom::Err<nom::error::Error<&'a str>> {
+        self.error = Some(error);
+        failure(input)
+    }
+
+    fn anonymous(&mut self) -> N3Term {
+        self.anonymous += 1;
+        N3Term::Anonymous(self.anonymous)
+    }
+
+    /// Resolve an IRI against the base IRI.
+    fn resolve(&self, iri: &str) -> Result<String, N3Error> {
+        match &self.base {
+            Some(base) => base
+                .resolve(iri)
+                .map(Iri::into_inner)
+                .map_err(|_| N3Error::InvalidIri(iri.to_string())),
+            None => Ok(iri.to_string()),
+        }
+    }
+
+    /// Parse an IRI, which is either written in angle brackets or as a prefixed name.
+    fn iri(&mut self, input: &'a str) -> ParseResult<'a, String> {
+        if let Ok((rest, iri)) = delimited(
+            char::<_, nom::error::Error<_>>('<'),
+            take_while(|c: char| c != '>' && !c.is_whitespace()),
+            char('>'),
+        )(input)
+        {
+            let (rest, _) = skip(rest)?;
+            return match self.resolve(iri) {
+                Ok(iri) => Ok((rest, iri)),
+                Err(error) => Err(self.fail(input, error)),
+            };
+        }
+
+        let (rest, (prefix, local)) = terminated(
+            pair(terminated(name(name_char), char(':')), name(local_char)),
+            skip,
+        )(input)?;
+        match self.prefixes.get(prefix) {
+            Some(namespace) => Ok((rest, format!("{namespace}{local}"))),
+            None => Err(self.fail(input, N3Error::UndeclaredPrefix(prefix.to_string()))),
+        }
+    }
+
+    /// Parse a string literal with an optional language tag or datatype.
+    fn literal(&mut self, input: &'a str) -> ParseResult<'a, N3Term> {
+        let (rest, string) = string(input)?;
+
+        if let Ok((rest, language)) = terminated(
+            preceded(
+                char::<_, nom::error::Error<_>>('@'),
+                recognize(pair(alpha1, many0(pair(char('-'), alphanumeric1)))),
+            ),
+            skip,
+        )(rest)
+        {
+            return Ok((
+                rest,
+                N3Term::Literal(format!("{}@{language}", quote(&string))),
+            ));
+        }
+        if let Ok((rest, _)) = token("^^")(rest) {
+            let (rest, datatype) = self.iri(rest)?;
+            return Ok((
+                rest,
+                N3Term::Literal(format!("{}^^<{datatype}>", quote(&string))),
+            ));
+        }
+
+        Ok((rest, N3Term::Literal(quote(&string))))
+    }
+
+    /// Parse a node, which may be followed by a path.
+    fn node(&mut self, input: &'a str, triples: &mut Vec<Triple<Node>>) -> ParseResult<'a, Node> {
+        let (rest, node) = self.item(input, triples)?;
+
+        match alt((token("!"), token("^")))(rest) {
+            Ok((rest, _)) => {
+                let (rest, _) = self.node(rest, triples)?;
+                Ok((rest, Node::Unsupported("paths")))
+            }
+            Err(_) => Ok((rest, node)),
+        }
+    }
+
+    fn item(&mut self, input: &'a str, triples: &mut Vec<Triple<Node>>) -> ParseResult<'a, Node> {
+        if let Ok((rest, _)) = token("[")(input) {
+            let node = Node::Term(self.anonymous());
+            let rest = match token("]")(rest) {
+                Ok((rest, _)) => rest,
+                Err(_) => {
+                    let (rest, _) = self.predicate_object_list(rest, &node, triples)?;
+                    token("]")(rest)?.0
+                }
+            };
+            return Ok((rest, node));
+        }
+        if let Ok((rest, _)) = token("{")(input) {
+            let (rest, formula) = self.formula(rest)?;
+            return Ok((rest, Node::Formula(formula)));
+        }
+        if let Ok((mut rest, _)) = token("(")(input) {
+            while let Err(nom::Err::Error(_)) = token(")")(rest) {
+                rest = self.node(rest, triples)?.0;
+            }
+            return Ok((token(")")(rest)?.0, Node::Unsupported("lists")));
+        }
+        if input.starts_with(['"', '\'']) {
+            let (rest, literal) = self.literal(input)?;
+            return Ok((rest, Node::Term(literal)));
+        }
+        if let Ok((rest, number)) = number(input) {
+            return Ok((rest, Node::Term(number)));
+        }
+        if let Some(rest) = input.strip_prefix("_:") {
+            let (rest, label) = name(name_char)(rest)?;
+            if label.is_empty() {
+                return Err(failure(rest));
+            }
+            let (rest, _) = skip(rest)?;
+            return Ok((rest, Node::Term(N3Term::Blank(label.to_string()))));
+        }
+        if let Ok((rest, variable)) =
+            terminated(preceded(char('?'), take_while1(variable_char)), skip)(input)
+        {
+            return Ok((rest, Node::Term(N3Term::Variable(variable.to_string()))));
+        }
+        if let Ok((rest, boolean)) =
+            alt((value(true, keyword("true")), value(false, keyword("false"))))(input)
+        {
+            return Ok((rest, Node::Term(N3Term::boolean(boolean))));
+        }
+
+        let (rest, iri) = self.iri(input)?;
+        Ok((rest, Node::Term(N3Term::Iri(iri))))
+    }
+
+    /// Parse the content of a formula after the opening brace, including the closing brace.
+    fn formula(&mut self, mut input: &'a str) -> ParseResult<'a, Vec<Triple<Node>>> {
+        let mut triples = Vec::new();
+
+        loop {
+            if let Ok((rest, _)) = token("}")(input) {
+                return Ok((rest, triples));
+            }
+
+            let (rest, _) = self.statement(input, &mut triples)?;
+            input = match token(".")(rest) {
+                Ok((rest, _)) => rest,
+                Err(_) => {
+                    let (rest, _) = token("}")(rest)?;
+                    return Ok((rest, triples));
+                }
+            };
+        }
+    }
+
+    /// Parse a verb, and return it together with whether subject and object are swapped.
+    fn verb(
+        &mut self,
+        input: &'a str,
+        triples: &mut Vec<Triple<Node>>,
+    ) -> ParseResult<'a, (Node, bool)> {
+        let implies = || Node::Term(N3Term::Iri(LOG_IMPLIES.to_string()));
+
+        if let Ok((rest, _)) = token("=>")(input) {
+            return Ok((rest, (implies(), false)));
+        }
+        if let Ok((rest, _)) = token("<=")(input) {
+            return Ok((rest, (implies(), true)));
+        }
+        if let Ok((rest, _)) = token("=")(input) {
+            return Ok((rest, (Node::Unsupported("equality"), false)));
+        }
+        if let Ok((rest, _)) = keyword("a")(input) {
+            return Ok((rest, (Node::Term(N3Term::Iri(RDF_TYPE.to_string())), false)));
+        }
+        if let Ok((rest, _)) = keyword("has")(input) {
+            let (rest, predicate) = self.node(rest, triples)?;
+            return Ok((rest, (predicate, false)));
+        }
+        if let Ok((rest, _)) = keyword("is")(input) {
+            let (rest, predicate) = self.node(rest, triples)?;
+            let (rest, _) = keyword("of")(rest)?;
+            return Ok((rest, (predicate, true)));
+        }
+
+        let (rest, predicate) = self.node(input, triples)?;
+        Ok((rest, (predicate, false)))
+    }
+
+    /// Parse predicates and objects of the given subject, adding the resulting triples.
+    fn predicate_object_list(
+        &mut self,
+        mut input: &'a str,
+        subject: &Node,
+        triples: &mut Vec<Triple<Node>>,
+    ) -> ParseResult<'a, ()> {
+        loop {
+            let (rest, (predicate, inverse)) = self.verb(input, triples)?;
+            input = rest;
+
+            loop {
+                let (rest, object) = self.node(input, triples)?;
+                let (subject, object) = if inverse {
+                    (object, subject.clone())
+                } else {
+                    (subject.clone(), object)
+                };
+                triples.push(Triple {
+                    subject,
+                    predicate: predicate.clone(),
+                    object,
+                });
+
+                match token(",")(rest) {
+                    Ok((rest, _)) => input = rest,
+                    Err(_) => {
+                        input = rest;
+                        break;
+                    }
+                }
+            }
+
+            let (rest, separators) = many0(token(";"))(input)?;
+            input = rest;
+            if separators.is_empty()
+                || input.is_empty()
+                || peek(one_of::<_, _, ()>(".]}"))(input).is_ok()
+            {
+                return Ok((input, ()));
+            }
+        }
+    }
+
+    /// Parse a statement, i.e. a subject followed by its predicates and objects.
+    fn statement(
+        &mut self,
+        input: &'a str,
+        triples: &mut Vec<Triple<Node>>,
+    ) -> ParseResult<'a, ()> {
+        let (rest, subject) = self.node(input, triples)?;
+
+        if input.starts_with('[') && peek(one_of::<_, _, ()>(".}"))(rest).is_ok() {
+            return Ok((rest, ()));
+        }
+
+        self.predicate_object_list(rest, &subject, triples)
+    }
+
+    /// Parse a `@prefix` or `@base` directive (or their SPARQL variants),
+    /// and return the remaining input, or `None` if the input does not start with a directive.
+    fn directive(
+        &mut self,
+        input: &'a str,
+    ) -> Result<Option<&'a str>, nom::Err<nom::error::Error<&'a str>>> {
+        let line = self.line(input);
+
+        if let Ok((rest, directive)) = alt((token("@prefix"), sparql_keyword("prefix")))(input) {
+            let (rest, prefix) = terminated(terminated(name(name_char), char(':')), skip)(rest)?;
+            let (rest, namespace) = self.iri(rest)?;
+            let (rest, _) = if directive.starts_with('@') {
+                token(".")(rest)?
+            } else {
+                (rest, "")
+            };
+
+            self.prefixes.insert(prefix.to_string(), namespace);
+            return Ok(Some(rest));
+        }
+        if let Ok((rest, directive)) = alt((token("@base"), sparql_keyword("base")))(input) {
+            let (rest, base) = self.iri(rest)?;
+            let (rest, _) = if directive.starts_with('@') {
+                token(".")(rest)?
+            } else {
+                (rest, "")
+            };
+
+            match Iri::parse(base.clone()) {
+                Ok(base) => self.base = Some(base),
+                Err(_) => return Err(self.fail(input, N3Error::InvalidIri(base))),
+            }
+            return Ok(Some(rest));
+        }
+        if input.starts_with("@forAll") || input.starts_with("@forSome") {
+            return Err(self.fail(input, N3Error::Unsupported(line, "explicit quantification")));
+        }
+        if input.starts_with("@keywords") {
+            return Err(self.fail(input, N3Error::Unsupported(line, "@keywords")));
+        }
+
+        Ok(None)
+    }
+
+    /// Parse the statements of the document.
+    fn document(&mut self) -> Result<Statements, nom::Err<nom::error::Error<&'a str>>> {
+        let (mut input, _) = skip(self.input)?;
+        let mut statements = Vec::new();
+
+        while !input.is_empty() {
+            if let Some(rest) = self.directive(input)? {
+                input = rest;
+                continue;
+            }
+
+            let line = self.line(input);
+            let mut triples = Vec::new();
+            let (rest, _) = self.statement(input, &mut triples)?;
+            let (rest, _) = token(".")(rest)?;
+
+            statements.push((line, triples));
+            input = rest;
+        }
+
+        Ok(statements)
+    }
+}
+
+/// Return a name of the form `b1` that does not occur in `used`, and add it to `used`.
+fn fresh_name(used: &mut HashSet<String>) -> String {
+    let mut index = used.len();
+
+    loop {
+        index += 1;
+        let name = format!("b{index}");
+        if used.insert(name.clone()) {
+            return name;
+        }
+    }
+}
+
+/// A set of N3 rules, which can be translated into a Nemo program.
+///
+/// The program is obtained by [`N3Rules::program`] and derives the triples
+/// for the predicate [`TRIPLE_PREDICATE`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct N3Rules {
+    facts: Vec<Triple<N3Term>>,
+    rules: Vec<N3Rule>,
+}
+
+impl N3Rules {
+    /// Parse N3 rules, where relative IRIs are resolved against the given base IRI.
+    pub fn parse(input: &str, base: Option<&str>) -> Result<Self, N3Error> {
+        let base = base
+            .map(|base| {
+                Iri::parse(base.to_string()).map_err(|_| N3Error::InvalidIri(base.to_string()))
+            })
+            .transpose()?;
+        let mut parser = N3Parser {
+            input,
+            base,
+            prefixes: HashMap::new(),
+            anonymous: 0,
+            error: None,
+        };
+
+        let statements = match parser.document() {
+            Ok(statements) => statements,
+            Err(error) => {
+                if let Some(error) = parser.error.take() {
+                    return Err(error);
+                }
+
+                let rest = match error {
+                    nom::Err::Error(error) | nom::Err::Failure(error) => error.input,
+                    nom::Err::Incomplete(_) => "",
+                };
+                return Err(N3Error::Syntax(parser.line(rest)));
+            }
+        };
+
+        let mut facts = Vec::new();
+        let mut rules = Vec::new();
+
+        for (line, triples) in statements {
+            for triple in triples {
+                if triple.predicate != Node::Term(N3Term::Iri(LOG_IMPLIES.to_string())) {
+                    let fact = Self::triple(line, triple)?;
+                    if let Some(variable) = fact.variables().next() {
+                        return Err(N3Error::UnsafeVariable(line, variable.clone()));
+                    }
+
+                    facts.push(fact);
+                    continue;
+                }
+
+                let body = match triple.subject {
+                    Node::Formula(body) => body,
+                    Node::Term(term) if term == N3Term::boolean(true) => Vec::new(),
+                    _ => return Err(N3Error::Unsupported(line, "implications between terms")),
+                };
+                let head = match triple.object {
+                    Node::Formula(head) => head,
+                    Node::Term(term) if term == N3Term::boolean(false) => {
+                        return Err(N3Error::Unsupported(line, "inference fuses"))
+                    }
+                    _ => return Err(N3Error::Unsupported(line, "implications between terms")),
+                };
+
+                let body = body
+                    .into_iter()
+                    .map(|triple| Self::triple(line, triple))
+                    .collect::<Result<Vec<_>, _>>()?;
+                let mut head = head
+                    .into_iter()
+                    .map(|triple| Self::triple(line, triple))
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                let safe = body
+                    .iter()
+                    .flat_map(Triple::variables)
+                    .collect::<HashSet<_>>();
+                if let Some(variable) = head
+                    .iter()
+                    .flat_map(Triple::variables)
+                    .find(|variable| !safe.contains(variable))
+                {
+                    return Err(N3Error::UnsafeVariable(line, variable.clone()));
+                }
+
+                if !body.is_empty() {
+                    rules.push(N3Rule { head, body });
+                    continue;
+                }
+
+                // The conclusion of an implication from `true` consists of facts,
+                // whose blank nodes are distinct from those of other statements
+                let mut blank_nodes = HashMap::new();
+                for term in head.iter_mut().flat_map(|triple| {
+                    [
+                        &mut triple.subject,
+                        &mut triple.predicate,
+                        &mut triple.object,
+                    ]
+                }) {
+                    if let N3Term::Blank(label) = &*term {
+                        let anonymous = blank_nodes
+                            .entry(label.clone())
+                            .or_insert_with(|| parser.anonymous())
+                            .clone();
+                        *term = anonymous;
+                    }
+                }
+                facts.extend(head);
+            }
+        }
+
+        Ok(Self { facts, rules })
+    }
+
+    /// Convert a triple of a statement that does not contain formulas.
+    fn triple(line: usize, triple: Triple<Node>) -> Result<Triple<N3Term>, N3Error> {
+        let term = |node| match node {
+            Node::Term(term) => Ok(term),
+            Node::Formula(_) => Err(N3Error::Unsupported(
+                line,
+                "formulas outside of implications",
+            )),
+            Node::Unsupported(feature) => Err(N3Error::Unsupported(line, feature)),
+        };
+
+        let triple = Triple {
+            subject: term(triple.subject)?,
+            predicate: term(triple.predicate)?,
+            object: term(triple.object)?,
+        };
+
+        match &triple.predicate {
+            N3Term::Iri(iri) if iri.starts_with(SWAP) => {
+                Err(N3Error::Unsupported(line, "built-in predicates"))
+            }
+            _ => Ok(triple),
+        }
+    }
+
+    /// Return the Nemo program consisting of the facts and rules given by the N3 rules.
+    ///
+    /// Blank nodes of facts are kept as blank nodes,
+    /// whereas blank nodes of rules are translated into (existential) variables.
+    pub fn program(&self) -> String {
+        let mut program = String::new();
+
+        let mut used = self
+            .facts
+            .iter()
+            .flat_map(Triple::nodes)
+            .filter_map(|term| match term {
+                N3Term::Blank(label) => Some(label.clone()),
+                _ => None,
+            })
+            .collect::<HashSet<_>>();
+        let mut names = HashMap::new();
+        for fact in &self.facts {
+            let atom = fact.to_nemo(|term| match term {
+                N3Term::Blank(label) => format!("_:{label}"),
+                term => {
+                    let name = names
+                        .entry(term.clone())
+                        .or_insert_with(|| fresh_name(&mut used));
+                    format!("_:{name}")
+                }
+            });
+            let _ = writeln!(program, "{atom} .");
+        }
+
+        for rule in &self.rules {
+            let mut used = rule
+                .body
+                .iter()
+                .chain(&rule.head)
+                .flat_map(Triple::variables)
+                .cloned()
+                .collect::<HashSet<_>>();
+            let mut names = HashMap::new();
+            let mut atoms = |triples: &[Triple<N3Term>], existential: bool| {
+                triples
+                    .iter()
+                    .map(|triple| {
+                        triple.to_nemo(|term| match term {
+                            N3Term::Variable(name) => format!("?{name}"),
+                            term => {
+                                let name = names
+                                    .entry((existential, term.clone()))
+                                    .or_insert_with(|| fresh_name(&mut used));
+                                if existential {
+                                    format!("!{name}")
+                                } else {
+                                    format!("?{name}")
+                                }
+                            }
+                        })
+                    })
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            };
+
+            let head = atoms(&rule.head, true);
+            let body = atoms(&rule.body, false);
+            let _ = writeln!(program, "{head} :- {body} .");
+        }
+
+        program
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::io::parser::parse_program;
+
+    use super::{N3Error, N3Rules};
+
+    #[test]
+    fn rules_program() {
+        let rules = N3Rules::parse(
+            "@prefix : <http://example.org/> .\n\
+             PREFIX xsd: <http://www.w3.org/2001/XMLSchema#>\n\
+             # Facts\n\
+             :alice :knows :bob , :carol ; a :Person .\n\
+             :bob :age 42 ; :name \"Bob\"@en ; :height 1.8 ; :born \"1990\"^^xsd:gYear .\n\
+             [ :name 'anonymous' ] .\n\
+             { ?x :knows ?y } => { ?y :knows ?x } .\n\
+             { ?x a :Person } => { ?x :parent [ a :Person ] } .\n\
+             { ?x :friend ?y } <= { ?x :knows ?y . ?y :knows ?x } .\n\
+             { ?x :knows _:b1 } => { ?x :acquainted true } .\n\
+             true => { _:b1 :name \"\"\"some \"one\"\"\"\" } .\n",
+            None,
+        )
+        .unwrap();
+
+        assert_eq!(
+            rules.program(),
+            "triple(<http://example.org/alice>, <http://example.org/knows>, <http://example.org/bob>) .\n\
+             triple(<http://example.org/alice>, <http://example.org/knows>, <http://example.org/carol>) .\n\
+             triple(<http://example.org/alice>, <http://www.w3.org/1999/02/22-rdf-syntax-ns#type>, <http://example.org/Person>) .\n\
+             triple(<http://example.org/bob>, <http://example.org/age>, \"42\"^^<http://www.w3.org/2001/XMLSchema#integer>) .\n\
+             triple(<http://example.org/bob>, <http://example.org/name>, \"Bob\"@en) .\n\
+             triple(<http://example.org/bob>, <http://example.org/height>, \"1.8\"^^<http://www.w3.org/2001/XMLSchema#decimal>) .\n\
+             triple(<http://example.org/bob>, <http://example.org/born>, \"1990\"^^<http://www.w3.org/2001/XMLSchema#gYear>) .\n\
+             triple(_:b1, <http://example.org/name>, \"anonymous\") .\n\
+             triple(_:b2, <http://example.org/name>, \"some \\\"one\\\"\") .\n\
+             triple(?y, <http://example.org/knows>, ?x) :- triple(?x, <http://example.org/knows>, ?y) .\n\
+             triple(!b2, <http://www.w3.org/1999/02/22-rdf-syntax-ns#type>, <http://example.org/Person>), triple(?x, <http://example.org/parent>, !b2) :- triple(?x, <http://www.w3.org/1999/02/22-rdf-syntax-ns#type>, <http://example.org/Person>) .\n\
+             triple(?x, <http://example.org/friend>, ?y) :- triple(?x, <http://example.org/knows>, ?y), triple(?y, <http://example.org/knows>, ?x) .\n\
+             triple(?x, <http://example.org/acquainted>, \"true\"^^<http://www.w3.org/2001/XMLSchema#boolean>) :- triple(?x, <http://example.org/knows>, ?b2) .\n"
+        );
+        assert!(parse_program(rules.program()).is_ok());
+    }
+
+    #[test]
+    fn relative_iris() {
+        let rules = N3Rules::parse(
+            "<#a> <p> <../b> .\n@base <dir/> .\n<c> <p> <d> .",
+            Some("http://example.org/rules/main.n3"),
+        )
+        .unwrap();
+
+        assert_eq!(
+            rules.program(),
+            "triple(<http://example.org/rules/main.n3#a>, <http://example.org/rules/p>, <http://example.org/b>) .\n\
+             triple(<http://example.org/rules/dir/c>, <http://example.org/rules/dir/p>, <http://example.org/rules/dir/d>) .\n"
+        );
+    }
+
+    #[test]
+    fn unsupported_rules() {
+        let prefixes = "@prefix : <http://example.org/> .\n\
+                        @prefix math: <http://www.w3.org/2000/10/swap/math#> .\n";
+        let parse = |rules: &str| N3Rules::parse(&format!("{prefixes}{rules}"), None);
+
+        assert_eq!(
+            parse("{ (?x 1) math:sum ?y } => { ?x :next ?y } ."),
+            Err(N3Error::Unsupported(3, "lists"))
+        );
+        assert_eq!(
+            parse("{ ?x math:greaterThan 1 } => { ?x :large true } ."),
+            Err(N3Error::Unsupported(3, "built-in predicates"))
+        );
+        assert_eq!(
+            parse(":a :b :c .\n{ ?x :p ?y } => { { ?x :q ?y } => { ?y :q ?x } } ."),
+            Err(N3Error::Unsupported(4, "formulas outside of implications"))
+        );
+        assert_eq!(
+            parse("{ ?x :p ?y } => { ?x = ?y } ."),
+            Err(N3Error::Unsupported(3, "equality"))
+        );
+        assert_eq!(
+            parse("@forAll :x .\n"),
+            Err(N3Error::Unsupported(3, "explicit quantification"))
+        );
+        assert_eq!(
+            parse("{ ?x :p :a } => { ?x :q ?y } ."),
+            Err(N3Error::UnsafeVariable(3, "y".to_string()))
+        );
+        assert_eq!(
+            parse(":a ex:b :c ."),
+            Err(N3Error::UndeclaredPrefix("ex".to_string()))
+        );
+        assert_eq!(parse(":a :b :c .\n:d :e ."), Err(N3Error::Syntax(4)));
+    }
+}
@@ -1,16 +1,25 @@
 //! This module contains the OutputFileManager, which generates [`RecordWriter`] objects
 
 use std::{
-    fs::{create_dir_all, OpenOptions},
-    io::{ErrorKind, Write},
+    collections::HashMap,
+    fs::{create_dir_all, remove_dir_all, remove_file, rename, File, OpenOptions},
+    hash::Hasher,
+    io::{copy, ErrorKind, Write},
     path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    time::{SystemTime, UNIX_EPOCH},
 };
 
 use flate2::{write::GzEncoder, Compression};
+use path_slash::{PathBufExt, PathExt};
 
-use crate::{error::Error, model::Identifier};
+use crate::{
+    error::Error,
+    model::{types::primitive_logical_value::LogicalDateTime, Identifier},
+    util::stable_hash::StableHasher,
+};
 
-use super::RecordWriter;
+use super::{resource_providers::file::path_from_file_uri, RecordWriter};
 
 /// Compression level for gzip output, cf. gzip(1):
 ///
@@ -29,7 +38,8 @@ pub enum FileFormat {
 }
 
 impl FileFormat {
-    fn file_name(&self, file: PathBuf) -> PathBuf {
+    /// returns the file_name with the extension of this format
+    pub fn file_name(&self, file: PathBuf) -> PathBuf {
         let ext = match self {
             FileFormat::DSV(delimiter) => match delimiter {
                 b',' => "csv",
@@ -41,7 +51,7 @@ impl FileFormat {
     }
 
     /// Create [`RecordWriter`] for this file format writing to given [`Write`]
-    pub fn create_writer<W: Write>(&self, writer: W) -> impl RecordWriter {
+    pub fn create_writer<W: Write>(&self, writer: W) -> csv::Writer<W> {
         match self {
             FileFormat::DSV(delimiter) => csv::WriterBuilder::new()
                 .delimiter(*delimiter)
@@ -96,24 +106,115 @@ fn append_extension(file: &Path, ext: &str) -> String {
     }
 }
 
+/// Describes how the results of a single predicate are distributed over several files
+///
+/// If the results are split, they are written into a directory named after the predicate.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum FileSplitting {
+    /// Write all results into a single file
+    #[default]
+    None,
+    /// Start a new file `part-00000`, `part-00001`, ... after the given number of rows
+    MaxRows(usize),
+    /// Start a new file `part-00000`, `part-00001`, ... before the given number of
+    /// (uncompressed) bytes would be exceeded
+    MaxBytes(u64),
+    /// Write one file for each value of the column with the given index (starting at 0),
+    /// named after that value
+    ByColumn(usize),
+}
+
+/// Describes what happens with results of a predicate that have already been written
+/// to the output directory, e.g. by a previous run
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum OutputPolicy {
+    /// Fail if the output files already exist
+    #[default]
+    FailIfExists,
+    /// Replace existing output files
+    Overwrite,
+    /// Append the results to existing output files
+    Append,
+    /// Write the results into a new subdirectory of the output directory,
+    /// named after the current time (UTC)
+    Versioned,
+}
+
+/// Name of the manifest listing all files written to the output directory
+pub const MANIFEST_FILE_NAME: &str = "_manifest.csv";
+
+/// Extension of the files that are written before they are moved to their final location
+const TEMPORARY_EXTENSION: &str = "tmp";
+
+/// Extension of the output directories that are moved aside before they are replaced
+const REPLACED_EXTENSION: &str = "old";
+
+/// Entry of the manifest for a single output file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ManifestEntry {
+    /// Path of the file
+    pub file: PathBuf,
+    /// Number of rows written to the file
+    pub rows: usize,
+    /// FNV-1a checksum of the contents of the file (after compression)
+    pub checksum: u64,
+}
+
+/// Results of a predicate that have been written completely,
+/// but have not yet been moved to their final location
+#[derive(Debug)]
+struct PendingOutput {
+    /// Temporary file or directory the results have been written to
+    temporary: PathBuf,
+    /// Final file or directory of the results
+    path: PathBuf,
+    /// Final paths of all written files with their number of rows
+    files: Vec<(PathBuf, usize)>,
+}
+
+/// Outputs that are shared between an [`OutputFileManager`] and its writers
+#[derive(Debug, Default)]
+struct OutputState {
+    /// Outputs that have been finished but not yet moved to their final location
+    pending: Vec<PendingOutput>,
+    /// Files that have been moved to their final location
+    manifest: Vec<ManifestEntry>,
+    /// Whether the manifest of a previous run has been read
+    previous_manifest_loaded: bool,
+}
+
 /// Contains all the needed information, to create output file writers
+///
+/// Results are first written to temporary files,
+/// which are only moved to their final location by [`OutputFileManager::finalize`].
+/// Hence, results of a failed run cannot be mistaken for complete results.
 #[derive(Debug)]
 pub struct OutputFileManager {
     /// The path to where the results shall be written to.
     path: PathBuf,
-    /// Overwrite files, note that the target folder will be emptied if `overwrite` is set to [true].
-    overwrite: bool,
+    /// Handling of existing output files
+    policy: OutputPolicy,
     /// Compression and file format.
     pub compression_format: FileCompression,
     /// Data format used (csv, dsv, ...)
     pub data_format: FileFormat,
+    /// Distribution of the results of a predicate over several files
+    splitting: FileSplitting,
+    /// Finished and finalized outputs
+    state: Arc<Mutex<OutputState>>,
 }
 
 impl OutputFileManager {
     /// Instantiate an [`OutputFileManager`].
     ///
     /// Instantiates a new [`OutputFileManager`] if the given `path` is writable. Otherwise an [`Error`] is thrown.
+    /// Existing files are replaced if `overwrite` is set, see [`OutputFileManager::with_policy`].
+    /// The `path` may also be given as a `file:` URI.
     pub fn try_new(path: PathBuf, overwrite: bool, gzip: bool) -> Result<Self, Error> {
+        let path = match path.to_str() {
+            Some(uri) if uri.starts_with("file:") => path_from_file_uri(uri)?,
+            _ => path,
+        };
         create_dir_all(&path)?;
         let data_format = FileFormat::DSV(b',');
         let compression_format = if gzip {
@@ -121,13 +222,63 @@ impl OutputFileManager {
         } else {
             FileCompression::None
         };
+        let policy = if overwrite {
+            OutputPolicy::Overwrite
+        } else {
+            OutputPolicy::FailIfExists
+        };
         Ok(OutputFileManager {
             path,
-            overwrite,
+            policy,
             compression_format,
             data_format,
+            splitting: FileSplitting::None,
+            state: Default::default(),
         })
     }
+
+    /// Split the results of each predicate into several files.
+    pub fn with_splitting(mut self, splitting: FileSplitting) -> Self {
+        self.splitting = splitting;
+        self
+    }
+
+    /// Set how existing output files are handled.
+    ///
+    /// For [`OutputPolicy::Versioned`], the output directory is changed to a new subdirectory,
+    /// see [`OutputFileManager::output_directory`].
+    pub fn with_policy(mut self, policy: OutputPolicy) -> Self {
+        if policy == OutputPolicy::Versioned && self.policy != OutputPolicy::Versioned {
+            self.path = version_directory(&self.path);
+        }
+
+        self.policy = policy;
+        self
+    }
+
+    /// Return the directory the results are written to.
+    pub fn output_directory(&self) -> &Path {
+        &self.path
+    }
+}
+
+/// Return a subdirectory of the given directory that is named after the current time
+/// and does not exist yet.
+fn version_directory(directory: &Path) -> PathBuf {
+    let seconds = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs() as i64);
+    // colons are not allowed in file names on every platform
+    let version = LogicalDateTime::from(seconds).to_string().replace(':', "");
+
+    let mut path = directory.join(&version);
+    let mut suffix = 1;
+    while path.symlink_metadata().is_ok() {
+        path = directory.join(format!("{version}-{suffix}"));
+        suffix += 1;
+    }
+
+    path
 }
 
 impl OutputFileManager {
@@ -139,40 +290,219 @@ impl OutputFileManager {
         pred_path
     }
 
+    /// Get the path the results of the given predicate are written to,
+    /// which is a directory if the results are split into several files
+    fn get_output_path(&self, pred: &Identifier) -> PathBuf {
+        match self.splitting {
+            FileSplitting::None => self.get_output_file_name(pred),
+            _ => pred.sanitised_file_name(self.path.to_path_buf()),
+        }
+    }
+
     /// Creates a file for predicate.
     ///
     /// The created file uses the set file format and compression method.
-    /// Returns a [`RecordWriter`] for a file named appropriately for the given [`predicate`][Identifier]
-    pub fn create_file_writer(&self, predicate: &Identifier) -> Result<impl RecordWriter, Error> {
-        let mut options = OpenOptions::new();
-        options.write(true);
-        if self.overwrite {
-            options.create(true).truncate(true);
-        } else {
-            options.create_new(true);
+    /// Returns an [`OutputFileWriter`] for a file named appropriately for the given [`predicate`][Identifier]
+    ///
+    /// If the results are split, the files are created in a directory named after the predicate
+    /// once they are needed, see [`FileSplitting`].
+    ///
+    /// The results are written to a temporary file,
+    /// see [`OutputFileWriter::finish`] and [`OutputFileManager::finalize`].
+    pub fn create_file_writer(&self, predicate: &Identifier) -> Result<OutputFileWriter, Error> {
+        create_dir_all(&self.path)?;
+        let path = self.get_output_path(predicate);
+        let mut record_writer = OutputFileWriter {
+            predicate: predicate.clone(),
+            temporary: temporary_path(&path),
+            path,
+            compression_format: self.compression_format,
+            data_format: self.data_format,
+            splitting: self.splitting,
+            current: None,
+            bytes: 0,
+            keyed: HashMap::new(),
+            files: Vec::new(),
+            state: Arc::clone(&self.state),
         };
 
-        let pred_path = self.get_output_file_name(predicate);
-        log::info!("Creating {} as {pred_path:?}", predicate.name());
-
-        let file_writer = self.compression_format.create_writer(pred_path, options)?;
-        let record_writer = self.data_format.create_writer(file_writer);
+        if self.splitting == FileSplitting::None {
+            tracing::info!("Creating {} as {:?}", predicate.name(), record_writer.path);
+            record_writer.current = Some(record_writer.open(None)?);
+        } else {
+            tracing::info!(
+                "Creating {} as directory {:?}",
+                predicate.name(),
+                record_writer.path
+            );
+            if record_writer.temporary.is_dir() {
+                remove_dir_all(&record_writer.temporary)?;
+            }
+            create_dir_all(&record_writer.temporary)?;
+        }
 
         Ok(record_writer)
     }
 
+    /// Move all finished outputs to their final location
+    /// and write the manifest [`MANIFEST_FILE_NAME`] to the output directory,
+    /// which lists every output file with its number of rows and its checksum.
+    ///
+    /// Writers that have not been [finished][OutputFileWriter::finish] are ignored.
+    ///
+    /// If results are [appended][OutputPolicy::Append],
+    /// the manifest also lists the files of previous runs.
+    pub fn finalize(&self) -> Result<(), Error> {
+        let mut state = self
+            .state
+            .lock()
+            .expect("output state should not be poisoned");
+        let manifest_path = self.path.join(MANIFEST_FILE_NAME);
+
+        if self.policy == OutputPolicy::Append && !state.previous_manifest_loaded {
+            if manifest_path.exists() {
+                state.manifest = self.read_manifest(&manifest_path)?;
+            }
+            state.previous_manifest_loaded = true;
+        }
+
+        for pending in std::mem::take(&mut state.pending) {
+            let exists = pending.path.symlink_metadata().is_ok();
+            let mut previous_rows = HashMap::new();
+
+            match self.policy {
+                _ if !exists => rename(&pending.temporary, &pending.path)?,
+                OutputPolicy::FailIfExists | OutputPolicy::Versioned => {
+                    return Err(Error::IOExists {
+                        error: ErrorKind::AlreadyExists.into(),
+                        filename: pending.path,
+                    });
+                }
+                OutputPolicy::Overwrite => {
+                    if pending.path.is_dir() || pending.temporary.is_dir() {
+                        // Directories cannot be replaced by renaming,
+                        // so the previous results are only removed once the new ones are in place
+                        let replaced = extended_path(&pending.path, REPLACED_EXTENSION);
+                        rename(&pending.path, &replaced)?;
+                        rename(&pending.temporary, &pending.path)?;
+
+                        if replaced.is_dir() {
+                            remove_dir_all(&replaced)?;
+                        } else {
+                            remove_file(&replaced)?;
+                        }
+                    } else {
+                        rename(&pending.temporary, &pending.path)?;
+                    }
+                }
+                OutputPolicy::Append => {
+                    for (file, _) in &pending.files {
+                        let relative = file.strip_prefix(&pending.path).unwrap_or(Path::new(""));
+                        let temporary = if relative.as_os_str().is_empty() {
+                            pending.temporary.clone()
+                        } else {
+                            pending.temporary.join(relative)
+                        };
+
+                        if file.exists() {
+                            let mut target = OpenOptions::new().append(true).open(file)?;
+                            copy(&mut File::open(&temporary)?, &mut target)?;
+                            remove_file(&temporary)?;
+
+                            let rows = state
+                                .manifest
+                                .iter()
+                                .find(|entry| &entry.file == file)
+                                .map_or(0, |entry| entry.rows);
+                            previous_rows.insert(file.clone(), rows);
+                        } else {
+                            rename(&temporary, file)?;
+                        }
+                    }
+
+                    if pending.temporary.is_dir() {
+                        remove_dir_all(&pending.temporary)?;
+                    }
+                }
+            }
+
+            for (file, rows) in pending.files {
+                let mut hasher = StableHasher::default();
+                copy(&mut File::open(&file)?, &mut hasher)?;
+
+                state.manifest.retain(|entry| entry.file != file);
+                state.manifest.push(ManifestEntry {
+                    rows: rows + previous_rows.get(&file).copied().unwrap_or_default(),
+                    file,
+                    checksum: hasher.finish(),
+                });
+            }
+        }
+
+        let manifest_temporary = temporary_path(&manifest_path);
+        let mut writer = csv::Writer::from_path(&manifest_temporary)?;
+        writer.write_record(["file", "rows", "checksum"])?;
+        for entry in &state.manifest {
+            let file = entry.file.strip_prefix(&self.path).unwrap_or(&entry.file);
+            writer.write_record([
+                file.to_slash_lossy().into_owned(),
+                entry.rows.to_string(),
+                format!("{:016x}", entry.checksum),
+            ])?;
+        }
+        writer.flush()?;
+        drop(writer);
+        rename(manifest_temporary, manifest_path)?;
+
+        Ok(())
+    }
+
+    /// Read the manifest written by a previous run.
+    fn read_manifest(&self, manifest_path: &Path) -> Result<Vec<ManifestEntry>, Error> {
+        let mut entries = Vec::new();
+
+        for record in csv::Reader::from_path(manifest_path)?.records() {
+            let record = record?;
+            let (Some(file), Some(rows), Some(checksum)) =
+                (record.get(0), record.get(1), record.get(2))
+            else {
+                continue;
+            };
+            let (Ok(rows), Ok(checksum)) = (rows.parse(), u64::from_str_radix(checksum, 16)) else {
+                continue;
+            };
+
+            entries.push(ManifestEntry {
+                file: self.path.join(PathBuf::from_slash(file)),
+                rows,
+                checksum,
+            });
+        }
+
+        Ok(entries)
+    }
+
+    /// Return the entries of the manifest for all finalized outputs.
+    pub fn manifest(&self) -> Vec<ManifestEntry> {
+        self.state
+            .lock()
+            .expect("output state should not be poisoned")
+            .manifest
+            .clone()
+    }
+
     /// Checks if results shall be saved without allowing to overwrite
     /// Returns an Error if files are existing without being allowed to overwrite them
     pub fn prevent_accidental_overwrite(
         &self,
         output_predicates: impl Iterator<Item = Identifier>,
     ) -> Result<(), Error> {
-        if self.overwrite {
+        if self.policy != OutputPolicy::FailIfExists {
             return Ok(());
         }
 
         for pred in output_predicates {
-            let file = self.get_output_file_name(&pred);
+            let file = self.get_output_path(&pred);
             let meta_info = file.metadata();
             if let Err(err) = meta_info {
                 if err.kind() != ErrorKind::NotFound {
@@ -189,3 +519,388 @@ impl OutputFileManager {
         Ok(())
     }
 }
+
+/// Returns the path of the temporary file for the given path
+fn temporary_path(path: &Path) -> PathBuf {
+    extended_path(path, TEMPORARY_EXTENSION)
+}
+
+/// Returns the given path with the given extension appended to its file name
+fn extended_path(path: &Path, extension: &str) -> PathBuf {
+    let mut file_name = path.file_name().unwrap_or_default().to_os_string();
+    file_name.push(".");
+    file_name.push(extension);
+    path.with_file_name(file_name)
+}
+
+/// [`RecordWriter`] distributing the results of a predicate over one or more temporary files,
+/// see [`FileSplitting`]
+///
+/// The results only become visible once the writer is [finished][OutputFileWriter::finish]
+/// and the [`OutputFileManager`] that created it is [finalized][OutputFileManager::finalize].
+pub struct OutputFileWriter {
+    /// The predicate whose results are written
+    predicate: Identifier,
+    /// The final output file or, if the results are split, the final output directory
+    path: PathBuf,
+    /// The temporary file or directory the results are written to
+    temporary: PathBuf,
+    /// Compression of the output files
+    compression_format: FileCompression,
+    /// Data format of the output files
+    data_format: FileFormat,
+    /// Distribution of the results over several files
+    splitting: FileSplitting,
+    /// Writer for the current file, if not split by column
+    current: Option<csv::Writer<Box<dyn Write>>>,
+    /// Number of bytes (approximately) written to the current file
+    bytes: u64,
+    /// Writers for each file with their index in `files`, if split by column
+    keyed: HashMap<PathBuf, (csv::Writer<Box<dyn Write>>, usize)>,
+    /// Final paths of all opened files with the number of rows written to them
+    files: Vec<(PathBuf, usize)>,
+    /// State of the [`OutputFileManager`] that created this writer
+    state: Arc<Mutex<OutputState>>,
+}
+
+impl std::fmt::Debug for OutputFileWriter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("OutputFileWriter")
+            .field("predicate", &self.predicate)
+            .field("path", &self.path)
+            .field("temporary", &self.temporary)
+            .field("files", &self.files)
+            .finish_non_exhaustive()
+    }
+}
+
+impl OutputFileWriter {
+    /// Open a new temporary file with the given name inside the output directory,
+    /// or the output file itself if no name is given.
+    fn open(&mut self, name: Option<&Path>) -> Result<csv::Writer<Box<dyn Write>>, Error> {
+        let (temporary, path) = match name {
+            Some(name) => (self.temporary.join(name), self.path.join(name)),
+            None => (self.temporary.clone(), self.path.clone()),
+        };
+
+        let mut options = OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+        let file_writer = self.compression_format.create_writer(temporary, options)?;
+        self.files.push((path, 0));
+
+        Ok(self.data_format.create_writer(file_writer))
+    }
+
+    /// Return the name of a file in the output directory, including all extensions.
+    fn file_name(&self, name: PathBuf) -> PathBuf {
+        self.compression_format
+            .file_name(self.data_format.file_name(name))
+    }
+
+    /// Close the current file and continue with the next numbered part.
+    fn next_part(&mut self) -> Result<(), Error> {
+        if let Some(writer) = self.current.take() {
+            close(writer)?;
+        }
+
+        let name = self.file_name(PathBuf::from(format!("part-{:05}", self.files.len())));
+        self.current = Some(self.open(Some(&name))?);
+        self.bytes = 0;
+
+        Ok(())
+    }
+
+    /// Close all files and mark the results as complete,
+    /// such that they are moved to their final location by [`OutputFileManager::finalize`].
+    pub fn finish(mut self) -> Result<(), Error> {
+        if let Some(writer) = self.current.take() {
+            close(writer)?;
+        }
+        for (_, (writer, _)) in self.keyed.drain() {
+            close(writer)?;
+        }
+
+        self.state
+            .lock()
+            .expect("output state should not be poisoned")
+            .pending
+            .push(PendingOutput {
+                temporary: self.temporary,
+                path: self.path,
+                files: self.files,
+            });
+
+        Ok(())
+    }
+}
+
+/// Flush all contents of the given writer and close the underlying file.
+fn close(writer: csv::Writer<Box<dyn Write>>) -> Result<(), Error> {
+    let mut file_writer = writer.into_inner().map_err(|error| error.into_error())?;
+    file_writer.flush()?;
+    Ok(())
+}
+
+impl RecordWriter for OutputFileWriter {
+    fn write_record<I, T>(&mut self, record: I) -> Result<(), Error>
+    where
+        I: IntoIterator<Item = T>,
+        T: AsRef<[u8]>,
+    {
+        let record = record.into_iter().collect::<Vec<_>>();
+
+        match self.splitting {
+            FileSplitting::None => {}
+            FileSplitting::MaxRows(max_rows) => {
+                let rows = self.files.last().map_or(0, |(_, rows)| *rows);
+                if self.current.is_none() || rows >= max_rows {
+                    self.next_part()?;
+                }
+            }
+            FileSplitting::MaxBytes(max_bytes) => {
+                // every field is followed by a delimiter or a line break
+                let size = record
+                    .iter()
+                    .map(|field| field.as_ref().len() as u64 + 1)
+                    .sum::<u64>();
+                if self.current.is_none() || (self.bytes > 0 && self.bytes + size > max_bytes) {
+                    self.next_part()?;
+                }
+                self.bytes += size;
+            }
+            FileSplitting::ByColumn(column) => {
+                let key = record.get(column).ok_or_else(|| Error::OutputSplitColumn {
+                    predicate: self.predicate.name(),
+                    column,
+                    arity: record.len(),
+                })?;
+                let mut key = String::from_utf8_lossy(key.as_ref()).into_owned();
+                if key.is_empty() {
+                    key.push('_');
+                }
+                let name = self.file_name(Identifier(key).sanitised_file_name(PathBuf::new()));
+
+                if !self.keyed.contains_key(&name) {
+                    let writer = self.open(Some(&name))?;
+                    self.keyed
+                        .insert(name.clone(), (writer, self.files.len() - 1));
+                }
+
+                let (writer, index) = self
+                    .keyed
+                    .get_mut(&name)
+                    .expect("writer has been created above");
+                self.files[*index].1 += 1;
+                return RecordWriter::write_record(writer, record);
+            }
+        }
+
+        if let Some((_, rows)) = self.files.last_mut() {
+            *rows += 1;
+        }
+        let writer = self
+            .current
+            .as_mut()
+            .expect("current file is opened before writing");
+        RecordWriter::write_record(writer, record)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{fs::read_to_string, hash::Hasher};
+
+    use assert_fs::TempDir;
+
+    use super::{
+        FileSplitting, ManifestEntry, OutputFileManager, OutputPolicy, MANIFEST_FILE_NAME,
+    };
+    use crate::{
+        error::Error, io::RecordWriter, model::Identifier, util::stable_hash::StableHasher,
+    };
+
+    fn write_records(manager: &OutputFileManager, records: &[[&str; 2]]) -> Result<(), Error> {
+        let mut writer = manager.create_file_writer(&Identifier("p".to_string()))?;
+        for record in records {
+            writer.write_record(record)?;
+        }
+        writer.finish()?;
+        manager.finalize()
+    }
+
+    #[test]
+    fn split_by_rows() {
+        let directory = TempDir::new().unwrap();
+        let manager = OutputFileManager::try_new(directory.to_path_buf(), false, false)
+            .unwrap()
+            .with_splitting(FileSplitting::MaxRows(2));
+
+        write_records(&manager, &[["a", "1"], ["b", "2"], ["c", "3"]]).unwrap();
+
+        let path = directory.join("p");
+        assert_eq!(
+            read_to_string(path.join("part-00000.csv")).unwrap(),
+            "a,1\nb,2\n"
+        );
+        assert_eq!(
+            read_to_string(path.join("part-00001.csv")).unwrap(),
+            "c,3\n"
+        );
+        assert!(!path.join("part-00002.csv").exists());
+
+        assert!(manager
+            .prevent_accidental_overwrite([Identifier("p".to_string())].into_iter())
+            .is_err());
+    }
+
+    #[test]
+    fn split_by_size() {
+        let directory = TempDir::new().unwrap();
+        let manager = OutputFileManager::try_new(directory.to_path_buf(), false, false)
+            .unwrap()
+            .with_splitting(FileSplitting::MaxBytes(10));
+
+        write_records(&manager, &[["a", "1"], ["b", "2"], ["long", "value"]]).unwrap();
+
+        let path = directory.join("p");
+        assert_eq!(
+            read_to_string(path.join("part-00000.csv")).unwrap(),
+            "a,1\nb,2\n"
+        );
+        assert_eq!(
+            read_to_string(path.join("part-00001.csv")).unwrap(),
+            "long,value\n"
+        );
+    }
+
+    #[test]
+    fn split_by_column() {
+        let directory = TempDir::new().unwrap();
+        let manager = OutputFileManager::try_new(directory.to_path_buf(), true, false)
+            .unwrap()
+            .with_splitting(FileSplitting::ByColumn(1));
+
+        write_records(&manager, &[["a", "x"], ["b", "y/z"], ["c", "x"]]).unwrap();
+
+        let path = directory.join("p");
+        assert_eq!(read_to_string(path.join("x.csv")).unwrap(), "a,x\nc,x\n");
+        assert_eq!(read_to_string(path.join("y_z.csv")).unwrap(), "b,y/z\n");
+
+        let manager = manager.with_splitting(FileSplitting::ByColumn(2));
+        assert!(matches!(
+            write_records(&manager, &[["a", "x"]]),
+            Err(Error::OutputSplitColumn { column: 2, .. })
+        ));
+    }
+
+    #[test]
+    fn atomic_output_with_manifest() {
+        let directory = TempDir::new().unwrap();
+        let manager = OutputFileManager::try_new(directory.to_path_buf(), false, false).unwrap();
+
+        let mut writer = manager
+            .create_file_writer(&Identifier("p".to_string()))
+            .unwrap();
+        writer.write_record(["a", "1"]).unwrap();
+        assert!(!directory.join("p.csv").exists());
+        assert!(directory.join("p.csv.tmp").exists());
+
+        // results of unfinished writers are not moved
+        let mut unfinished = manager
+            .create_file_writer(&Identifier("q".to_string()))
+            .unwrap();
+        unfinished.write_record(["b", "2"]).unwrap();
+
+        writer.finish().unwrap();
+        manager.finalize().unwrap();
+
+        assert_eq!(read_to_string(directory.join("p.csv")).unwrap(), "a,1\n");
+        assert!(!directory.join("p.csv.tmp").exists());
+        assert!(!directory.join("q.csv").exists());
+
+        let mut hasher = StableHasher::default();
+        hasher.write(b"a,1\n");
+        let checksum = hasher.finish();
+        assert_eq!(
+            manager.manifest(),
+            vec![ManifestEntry {
+                file: directory.join("p.csv"),
+                rows: 1,
+                checksum,
+            }]
+        );
+        assert_eq!(
+            read_to_string(directory.join(MANIFEST_FILE_NAME)).unwrap(),
+            format!("file,rows,checksum\np.csv,1,{checksum:016x}\n")
+        );
+    }
+
+    #[test]
+    fn append_policy() {
+        let directory = TempDir::new().unwrap();
+
+        for record in [["a", "1"], ["b", "2"]] {
+            let manager = OutputFileManager::try_new(directory.to_path_buf(), false, false)
+                .unwrap()
+                .with_policy(OutputPolicy::Append);
+            write_records(&manager, &[record]).unwrap();
+        }
+
+        assert_eq!(
+            read_to_string(directory.join("p.csv")).unwrap(),
+            "a,1\nb,2\n"
+        );
+
+        let manager = OutputFileManager::try_new(directory.to_path_buf(), false, false)
+            .unwrap()
+            .with_policy(OutputPolicy::Append);
+        write_records(&manager, &[]).unwrap();
+        assert_eq!(manager.manifest()[0].rows, 2);
+    }
+
+    #[test]
+    fn overwrite_policy() {
+        let directory = TempDir::new().unwrap();
+        let manager = || OutputFileManager::try_new(directory.to_path_buf(), true, false).unwrap();
+
+        write_records(&manager(), &[["a", "1"]]).unwrap();
+        write_records(&manager(), &[["b", "2"]]).unwrap();
+        assert_eq!(read_to_string(directory.join("p.csv")).unwrap(), "b,2\n");
+
+        for records in [[["c", "3"]], [["d", "4"]]] {
+            let splitting = manager().with_splitting(FileSplitting::MaxRows(1));
+            write_records(&splitting, &records).unwrap();
+        }
+        assert_eq!(
+            read_to_string(directory.join("p").join("part-00000.csv")).unwrap(),
+            "d,4\n"
+        );
+        assert!(!directory.join("p.old").exists());
+    }
+
+    #[test]
+    fn versioned_policy() {
+        let directory = TempDir::new().unwrap();
+
+        let first = OutputFileManager::try_new(directory.to_path_buf(), false, false)
+            .unwrap()
+            .with_policy(OutputPolicy::Versioned);
+        write_records(&first, &[["a", "1"]]).unwrap();
+
+        let second = OutputFileManager::try_new(directory.to_path_buf(), false, false)
+            .unwrap()
+            .with_policy(OutputPolicy::Versioned);
+        write_records(&second, &[["b", "2"]]).unwrap();
+
+        assert_ne!(first.output_directory(), second.output_directory());
+        assert_eq!(first.output_directory().parent(), Some(directory.path()));
+        assert_eq!(
+            read_to_string(first.output_directory().join("p.csv")).unwrap(),
+            "a,1\n"
+        );
+        assert_eq!(
+            read_to_string(second.output_directory().join("p.csv")).unwrap(),
+            "b,2\n"
+        );
+    }
+}
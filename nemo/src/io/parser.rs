@@ -1,20 +1,32 @@
 //! A parser for rulewerk-style rules.
 
-use std::{cell::RefCell, collections::HashMap, fmt::Debug};
+use std::{
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+    fmt::Debug,
+};
 
-use crate::{error::Error, model::*};
+use crate::{
+    error::Error,
+    io::{Encoding, Environment},
+    model::{
+        types::primitive_logical_value::{LogicalDateTime, LogicalIpAddress},
+        *,
+    },
+};
 use nemo_physical::error::ReadingError;
 use nom::{
     branch::alt,
     bytes::complete::{is_not, tag},
-    character::complete::{alpha1, digit1, multispace1, none_of, satisfy},
-    combinator::{all_consuming, cut, map, map_res, opt, recognize, value},
+    character::complete::{alpha1, alphanumeric1, digit1, multispace1, none_of},
+    combinator::{all_consuming, cond, cut, map, map_res, not, opt, recognize, value},
     multi::{many0, many1, separated_list0, separated_list1},
-    sequence::{delimited, pair, preceded, terminated, tuple},
+    sequence::{delimited, pair, preceded, separated_pair, terminated, tuple},
     Err,
 };
 
 use macros::traced;
+use nom_locate::position;
 
 mod types;
 use types::{IntermediateResult, Span};
@@ -31,10 +43,49 @@ pub use types::{span_from_str, LocatedParseError, ParseError, ParseResult};
 /// # Error
 /// Returns an appropriate [`Error`] variant on parsing and feature check issues.
 pub fn parse_program(input: impl AsRef<str>) -> Result<Program, Error> {
-    let program = all_input_consumed(RuleParser::new().parse_program())(input.as_ref())?;
+    parse_program_with_disjunctive_heads(input, DisjunctiveHeads::default())
+}
+
+/// Parse a program in the given `input`-String and return a [`Program`],
+/// treating rules with disjunctive heads as specified by the given [`DisjunctiveHeads`].
+///
+/// # Error
+/// Returns an appropriate [`Error`] variant on parsing and feature check issues.
+pub fn parse_program_with_disjunctive_heads(
+    input: impl AsRef<str>,
+    disjunctive_heads: DisjunctiveHeads,
+) -> Result<Program, Error> {
+    parse_program_with_environment(input, disjunctive_heads, Environment::default())
+}
+
+/// Parse a program in the given `input`-String and return a [`Program`],
+/// treating rules with disjunctive heads as specified by the given [`DisjunctiveHeads`]
+/// and replacing variables in the resources of data sources by their values in the given [`Environment`].
+///
+/// # Error
+/// Returns an appropriate [`Error`] variant on parsing and feature check issues.
+pub fn parse_program_with_environment(
+    input: impl AsRef<str>,
+    disjunctive_heads: DisjunctiveHeads,
+    environment: Environment,
+) -> Result<Program, Error> {
+    let parser =
+        RuleParser::with_disjunctive_heads(disjunctive_heads).with_environment(environment);
+    let program = all_input_consumed(parser.parse_program())(input.as_ref())?;
     Ok(program)
 }
 
+/// Parse a single atom in the given `input`-String and return an [`Atom`].
+///
+/// This is used for queries, whose variables are bound by the facts of the queried predicate.
+///
+/// # Error
+/// Returns an appropriate [`Error`] variant on parsing issues.
+pub fn parse_atom(input: impl AsRef<str>) -> Result<Atom, Error> {
+    let atom = all_input_consumed(RuleParser::new().parse_atom())(input.as_ref().trim())?;
+    Ok(atom)
+}
+
 /// A combinator to add tracing to the parser.
 /// [fun] is an identifier for the parser and [parser] is the actual parser.
 #[inline(always)]
@@ -47,9 +98,9 @@ where
     P: FnMut(Span<'a>) -> IntermediateResult<'a, T>,
 {
     move |input| {
-        log::trace!(target: "parser", "{fun}({input:?})");
+        tracing::trace!(target: "parser", "{fun}({input:?})");
         let result = parser(input);
-        log::trace!(target: "parser", "{fun}({input:?}) -> {result:?}");
+        tracing::trace!(target: "parser", "{fun}({input:?}) -> {result:?}");
         result
     }
 }
@@ -83,6 +134,29 @@ pub fn comment(input: Span) -> IntermediateResult<()> {
     ))(input)
 }
 
+/// Extract the doc comment at the end of the given text, which precedes a statement.
+///
+/// A doc comment consists of consecutive lines starting with `%%`
+/// that are directly followed by the line of the statement.
+/// Like any other comment, doc comments are skipped by the grammar.
+fn doc_comment(preceding: &str) -> Option<String> {
+    let preceding = preceding.trim_end_matches([' ', '\t']);
+    if !(preceding.is_empty() || preceding.ends_with('\n')) {
+        return None;
+    }
+
+    let mut lines = preceding
+        .lines()
+        .rev()
+        .map(str::trim)
+        .map_while(|line| line.strip_prefix("%%"))
+        .map(|line| line.strip_prefix(' ').unwrap_or(line))
+        .collect::<Vec<_>>();
+    lines.reverse();
+
+    (!lines.is_empty()).then(|| lines.join("\n"))
+}
+
 /// A combinator that recognises an arbitrary amount of whitespace and
 /// comments.
 pub fn multispace_or_comment0(input: Span) -> IntermediateResult<()> {
@@ -166,11 +240,11 @@ fn resolve_prefixed_rdf_literal(
 ) -> RdfLiteral {
     match literal {
         turtle::RdfLiteral::LanguageString { value, tag } => RdfLiteral::LanguageString {
-            value: value.to_string(),
+            value: turtle::unescape(value),
             tag: tag.to_string(),
         },
         turtle::RdfLiteral::DatatypeValue { value, datatype } => RdfLiteral::DatatypeValue {
-            value: value.to_string(),
+            value: turtle::unescape(value),
             datatype: resolve_prefixed_name(prefixes, datatype)
                 .expect("prefix should have been registered during parsing"),
         },
@@ -181,38 +255,75 @@ fn resolve_prefixed_rdf_literal(
 pub(crate) fn parse_bare_name(input: Span<'_>) -> IntermediateResult<Span<'_>> {
     map_error(
         recognize(pair(
-            alpha1,
+            sparql::pn_chars_base,
             opt(preceded(
                 many0(tag(" ")),
-                separated_list1(
-                    many1(tag(" ")),
-                    many1(satisfy(|c| {
-                        ['0'..='9', 'a'..='z', 'A'..='Z', '-'..='-', '_'..='_']
-                            .iter()
-                            .any(|range| range.contains(&c))
-                    })),
-                ),
+                separated_list1(many1(tag(" ")), many1(sparql::pn_chars)),
             )),
         )),
         || ParseError::ExpectedBareName,
     )(input)
 }
 
+/// Parse a name in a Soufflé directive, such as the name of an attribute or the value of a parameter.
+#[traced("parser")]
+fn parse_souffle_name(input: Span<'_>) -> IntermediateResult<Span<'_>> {
+    map_error(
+        recognize(pair(
+            alt((alpha1, tag("_"))),
+            many0(alt((alphanumeric1, tag("_")))),
+        )),
+        || ParseError::ExpectedBareName,
+    )(input)
+}
+
+/// Parse a date like `2020-01-01` or a date with time like `2020-01-01T12:30:00`,
+/// optionally followed by a timezone like `Z` or `+02:00`.
+#[traced("parser")]
+fn parse_datetime(input: Span<'_>) -> IntermediateResult<LogicalDateTime> {
+    map_res(
+        recognize(tuple((
+            digit1,
+            tag("-"),
+            digit1,
+            tag("-"),
+            digit1,
+            opt(tuple((
+                tag("T"),
+                digit1,
+                tag(":"),
+                digit1,
+                tag(":"),
+                digit1,
+            ))),
+            opt(alt((
+                tag("Z"),
+                recognize(tuple((turtle::sign, digit1, tag(":"), digit1))),
+            ))),
+        ))),
+        |value: Span<'_>| value.parse::<LogicalDateTime>(),
+    )(input)
+}
+
+/// Return the logical type that corresponds to a type of a Soufflé attribute.
+///
+/// User-defined types are treated as [`PrimitiveType::Any`].
+fn souffle_type(type_name: &str) -> PrimitiveType {
+    match type_name {
+        "number" | "unsigned" => PrimitiveType::Integer,
+        "float" => PrimitiveType::Float64,
+        _ => PrimitiveType::Any,
+    }
+}
+
 #[traced("parser")]
 fn parse_simple_name(input: Span<'_>) -> IntermediateResult<Span<'_>> {
     map_error(
         recognize(pair(
-            alpha1,
+            sparql::pn_chars_base,
             opt(preceded(
                 many0(tag(" ")),
-                separated_list1(
-                    many1(tag(" ")),
-                    many1(satisfy(|c| {
-                        ['0'..='9', 'a'..='z', 'A'..='Z', '_'..='_']
-                            .iter()
-                            .any(|range| range.contains(&c))
-                    })),
-                ),
+                separated_list1(many1(tag(" ")), many1(sparql::varname_char)),
             )),
         )),
         || ParseError::ExpectedBareName,
@@ -225,18 +336,22 @@ fn parse_iri_constant<'a>(
 ) -> impl FnMut(Span<'a>) -> IntermediateResult<'a, Identifier> {
     map_error(
         move |input| {
-            let (remainder, name) = traced(
+            let (remainder, resolved) = traced(
                 "parse_iri_constant",
                 alt((
-                    map(sparql::iriref, |name| sparql::Name::IriReference(&name)),
-                    sparql::prefixed_name,
-                    sparql::blank_node_label,
-                    map(parse_bare_name, |name| sparql::Name::IriReference(&name)),
+                    map(sparql::iriref_escaped, Ok),
+                    map(
+                        alt((
+                            sparql::prefixed_name,
+                            sparql::blank_node_label,
+                            map(parse_bare_name, |name| sparql::Name::IriReference(&name)),
+                        )),
+                        |name| resolve_prefixed_name(&prefixes.borrow(), name),
+                    ),
                 )),
             )(input)?;
 
-            let resolved = resolve_prefixed_name(&prefixes.borrow(), name)
-                .map_err(|e| Err::Failure(e.at(input)))?;
+            let resolved = resolved.map_err(|e| Err::Failure(e.at(input)))?;
 
             Ok((remainder, Identifier(resolved)))
         },
@@ -259,7 +374,7 @@ pub fn parse_ground_term<'a>(
                         .map_err(ReadingError::from)
                 }),
                 map(turtle::string, move |literal| {
-                    Term::StringLiteral(literal.to_string())
+                    Term::StringLiteral(turtle::unescape(&literal))
                 }),
             )),
             || ParseError::ExpectedGroundTerm,
@@ -267,6 +382,29 @@ pub fn parse_ground_term<'a>(
     )
 }
 
+/// Treatment of rules with disjunctive heads, such as `p(?X) | q(?X) :- r(?X) .`
+///
+/// Nemo does not support reasoning with disjunctive rules,
+/// but such rules can be replaced by deterministic approximations.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum DisjunctiveHeads {
+    /// Reject rules with disjunctive heads with an error.
+    #[default]
+    Reject,
+    /// Only derive the first disjunct of the head.
+    ///
+    /// Since the result satisfies each disjunctive rule,
+    /// it is one possible model of a program without negation.
+    /// In particular, every fact that holds in all models is derived,
+    /// but some derived facts might not hold in other models.
+    ChooseFirst,
+    /// Derive all disjuncts of the head, as if they formed a conjunction.
+    ///
+    /// This is an over-approximation of all models of a program without negation,
+    /// i.e. every fact that holds in some minimal model is derived.
+    Split,
+}
+
 /// The main parser. Holds a hash map for
 /// prefixes, as well as the base IRI.
 #[derive(Debug, Default)]
@@ -279,6 +417,14 @@ pub struct RuleParser<'a> {
     sources: RefCell<Vec<DataSourceDeclaration>>,
     /// Declarations of predicates with their types.
     predicate_declarations: RefCell<HashMap<Identifier, Vec<PrimitiveType>>>,
+    /// Treatment of rules with disjunctive heads.
+    disjunctive_heads: DisjunctiveHeads,
+    /// Number of anonymous variables created so far.
+    anonymous_variables: RefCell<usize>,
+    /// The semiring of the annotations, if declared.
+    semiring: RefCell<Option<Semiring>>,
+    /// Variables that can be used in the resources of data sources.
+    environment: Environment,
 }
 
 /// Body may contain literals or filter expressions
@@ -288,6 +434,12 @@ pub enum BodyExpression {
     Literal(Literal),
     /// Filter
     Filter(Filter),
+    /// Negated conjunction of atoms
+    NegatedConjunction(Vec<Atom>),
+    /// Filter comparing the value of a function term with a term
+    FunctionFilter(TermTree, FilterOperation, Term),
+    /// Filters restricting a variable to the addresses of a network
+    CidrFilter(Vec<Filter>),
 }
 
 impl<'a> RuleParser<'a> {
@@ -296,6 +448,26 @@ impl<'a> RuleParser<'a> {
         Default::default()
     }
 
+    /// Construct a new [`RuleParser`] that treats rules with disjunctive heads
+    /// as specified by the given [`DisjunctiveHeads`].
+    pub fn with_disjunctive_heads(disjunctive_heads: DisjunctiveHeads) -> Self {
+        Self {
+            disjunctive_heads,
+            ..Default::default()
+        }
+    }
+
+    /// Use the given [`Environment`] for replacing variables in the resources of data sources.
+    pub fn with_environment(mut self, environment: Environment) -> Self {
+        self.environment = environment;
+        self
+    }
+
+    /// Replace the variables in the given resource of a data source.
+    fn resource(&self, resource: &str) -> Result<String, ParseError> {
+        Ok(self.environment.interpolate(resource)?)
+    }
+
     /// Parse the dot that ends declarations, optionally surrounded by spaces.
     fn parse_dot(&'a self) -> impl FnMut(Span<'a>) -> IntermediateResult<Span<'a>> {
         traced("parse_dot", space_delimited_token("."))
@@ -306,6 +478,16 @@ impl<'a> RuleParser<'a> {
         traced("parse_comma", space_delimited_token(","))
     }
 
+    /// Parse a vertical bar (`|`), optionally surrounded by spaces.
+    fn parse_bar(&'a self) -> impl FnMut(Span<'a>) -> IntermediateResult<'a, Span<'a>> {
+        traced("parse_bar", space_delimited_token("|"))
+    }
+
+    /// Parse a semicolon, optionally surrounded by spaces.
+    fn parse_semicolon(&'a self) -> impl FnMut(Span<'a>) -> IntermediateResult<'a, Span<'a>> {
+        traced("parse_semicolon", space_delimited_token(";"))
+    }
+
     /// Parse a negation sign (`~`), optionally surrounded by spaces.
     fn parse_not(&'a self) -> impl FnMut(Span<'a>) -> IntermediateResult<Span<'a>> {
         traced("parse_not", space_delimited_token("~"))
@@ -362,7 +544,7 @@ impl<'a> RuleParser<'a> {
                         cut(self.parse_dot()),
                     )(input)?;
 
-                    log::debug!(target: "parser", r#"parse_base: set new base: "{base}""#);
+                    tracing::debug!(target: "parser", r#"parse_base: set new base: "{base}""#);
                     *self.base.borrow_mut() = Some(&base);
 
                     Ok((remainder, Identifier(base.to_string())))
@@ -386,7 +568,7 @@ impl<'a> RuleParser<'a> {
                         cut(self.parse_dot()),
                     )(input)?;
 
-                    log::debug!(target: "parser", r#"parse_prefix: got prefix "{prefix}" for iri "{iri}""#);
+                    tracing::debug!(target: "parser", r#"parse_prefix: got prefix "{prefix}" for iri "{iri}""#);
                     if self.prefixes.borrow_mut().insert(&prefix, &iri).is_some() {
                         Err(Err::Failure(
                             ParseError::RedeclaredPrefix(prefix.to_string()).at(input),
@@ -461,48 +643,97 @@ impl<'a> RuleParser<'a> {
                         cut(self.parse_qualified_predicate_name()),
                     )(input)?;
 
-                    let (remainder, datasource): (_, Result<_, ParseError>) = cut(delimited(
+                    let (remainder, bag) = cut(preceded(
                         delimited(multispace_or_comment0, token(":"), multispace_or_comment1),
+                        opt(pair(token("bag"), self.parse_open_parenthesis())),
+                    ))(remainder)?;
+
+                    // The last column of a bag contains the counts,
+                    // which are not provided by the source itself
+                    let tuple_constraint = match bag {
+                        Some(_) if tuple_constraint.arity() < 2 => {
+                            return Err(Err::Failure(
+                                ParseError::BagSourceInvalidArity(predicate.name()).at(input),
+                            ));
+                        }
+                        Some(_) => tuple_constraint[..tuple_constraint.arity() - 1]
+                            .iter()
+                            .cloned()
+                            .collect(),
+                        None => tuple_constraint,
+                    };
+
+                    let (remainder, datasource): (_, Result<_, ParseError>) = cut(terminated(
                         alt((
                             map(
                                 delimited(
                                     preceded(token("load-csv"), cut(self.parse_open_parenthesis())),
-                                    turtle::string,
+                                    pair(turtle::string, self.parse_source_encoding()),
                                     self.parse_close_parenthesis(),
                                 ),
-                                |filename| {
-                                    Ok(NativeDataSource::DsvFile(DsvFile::csv_file(
-                                        &filename,
-                                        tuple_constraint.clone(),
-                                    )))
+                                |(filename, encoding)| {
+                                    Ok(NativeDataSource::DsvFile(
+                                        DsvFile::csv_file(
+                                            &self.resource(&filename)?,
+                                            tuple_constraint.clone(),
+                                        )
+                                        .with_encoding(encoding?),
+                                    ))
                                 },
                             ),
                             map(
                                 delimited(
                                     preceded(token("load-tsv"), cut(self.parse_open_parenthesis())),
-                                    turtle::string,
+                                    pair(turtle::string, self.parse_source_encoding()),
+                                    self.parse_close_parenthesis(),
+                                ),
+                                |(filename, encoding)| {
+                                    Ok(NativeDataSource::DsvFile(
+                                        DsvFile::tsv_file(
+                                            &self.resource(&filename)?,
+                                            tuple_constraint.clone(),
+                                        )
+                                        .with_encoding(encoding?),
+                                    ))
+                                },
+                            ),
+                            map(
+                                delimited(
+                                    preceded(
+                                        token("load-xlsx"),
+                                        cut(self.parse_open_parenthesis()),
+                                    ),
+                                    pair(turtle::string, self.parse_xlsx_parameters()),
                                     self.parse_close_parenthesis(),
                                 ),
-                                |filename| {
-                                    Ok(NativeDataSource::DsvFile(DsvFile::tsv_file(
-                                        &filename,
+                                |(filename, (sheet, range))| {
+                                    Ok(NativeDataSource::XlsxFile(XlsxFile::new_validated(
+                                        &self.resource(&filename)?,
+                                        sheet.map(|sheet| sheet.to_string()),
+                                        range.map(|range| range.parse()).transpose()?,
+                                        &predicate,
                                         tuple_constraint.clone(),
-                                    )))
+                                    )?))
                                 },
                             ),
                             map(
                                 delimited(
                                     preceded(token("load-rdf"), cut(self.parse_open_parenthesis())),
-                                    turtle::string,
+                                    pair(turtle::string, self.parse_source_format()),
                                     self.parse_close_parenthesis(),
                                 ),
-                                |filename| {
-                                    Ok(NativeDataSource::RdfFile(RdfFile::new_validated(
-                                        &filename,
+                                |(filename, format)| {
+                                    let rdf_file = RdfFile::new_validated(
+                                        &self.resource(&filename)?,
                                         self.base().map(String::from),
                                         &predicate,
                                         tuple_constraint.clone(),
-                                    )?))
+                                    )?;
+
+                                    Ok(NativeDataSource::RdfFile(match format {
+                                        Some(format) => rdf_file.with_format(format.parse()?),
+                                        None => rdf_file,
+                                    }))
                                 },
                             ),
                             map(
@@ -529,18 +760,50 @@ impl<'a> RuleParser<'a> {
                                     )?))
                                 },
                             ),
+                            map(
+                                delimited(
+                                    preceded(token("external"), cut(self.parse_open_parenthesis())),
+                                    turtle::string,
+                                    self.parse_close_parenthesis(),
+                                ),
+                                |name| {
+                                    Ok(NativeDataSource::External(ExternalSource::new(
+                                        &name,
+                                        tuple_constraint.clone(),
+                                    )))
+                                },
+                            ),
+                            map(
+                                delimited(
+                                    preceded(token("load"), cut(self.parse_open_parenthesis())),
+                                    pair(turtle::string, self.parse_source_format()),
+                                    self.parse_close_parenthesis(),
+                                ),
+                                |(filename, format)| {
+                                    Ok(NativeDataSource::CustomFormat(CustomFormatFile::new(
+                                        &self.resource(&filename)?,
+                                        format.map(|format| format.to_string()),
+                                        tuple_constraint.clone(),
+                                    )))
+                                },
+                            ),
                         )),
-                        cut(self.parse_dot()),
+                        pair(
+                            cond(bag.is_some(), self.parse_close_parenthesis()),
+                            cut(self.parse_dot()),
+                        ),
                     ))(
                         remainder
                     )?;
 
-                    let source = DataSourceDeclaration::new(
-                        predicate,
-                        datasource.map_err(|e| Err::Failure(e.at(input)))?,
-                    );
+                    let datasource = datasource.map_err(|e| Err::Failure(e.at(input)))?;
+                    let source = if bag.is_some() {
+                        DataSourceDeclaration::new_bag(predicate, datasource)
+                    } else {
+                        DataSourceDeclaration::new(predicate, datasource)
+                    };
 
-                    log::trace!("Found external data source {source:?}");
+                    tracing::trace!("Found external data source {source:?}");
                     self.sources.borrow_mut().push(source.clone());
 
                     Ok((remainder, source))
@@ -581,1404 +844,3097 @@ impl<'a> RuleParser<'a> {
         )
     }
 
-    /// Parses a statement.
-    pub fn parse_statement(&'a self) -> impl FnMut(Span<'a>) -> IntermediateResult<Statement> {
+    /// Parses the optional encoding of a data source, e.g. `, encoding="latin-1"`.
+    fn parse_source_encoding(
+        &'a self,
+    ) -> impl FnMut(Span<'a>) -> IntermediateResult<Result<Encoding, ParseError>> {
         traced(
-            "parse_statement",
-            map_error(
-                alt((
-                    map(self.parse_fact(), Statement::Fact),
-                    map(self.parse_rule(), Statement::Rule),
+            "parse_source_encoding",
+            map(
+                opt(preceded(
+                    tuple((
+                        self.parse_comma(),
+                        token("encoding"),
+                        space_delimited_token("="),
+                    )),
+                    turtle::string,
                 )),
-                || ParseError::ExpectedStatement,
-            ),
-        )
-    }
-
-    /// Parse a fact.
-    pub fn parse_fact(&'a self) -> impl FnMut(Span<'a>) -> IntermediateResult<Fact> {
-        traced(
-            "parse_fact",
-            map_error(
-                move |input| {
-                    let (remainder, (predicate, terms)) = terminated(
-                        pair(
-                            self.parse_iri_like_identifier(),
-                            self.parenthesised(separated_list1(
-                                self.parse_comma(),
-                                parse_ground_term(&self.prefixes),
-                            )),
-                        ),
-                        self.parse_dot(),
-                    )(input)?;
-
-                    let predicate_name = predicate.name();
-                    log::trace!(target: "parser", "found fact {predicate_name}({terms:?})");
-
-                    // We do not allow complex term trees in facts for now
-                    let terms = terms.into_iter().map(TermTree::leaf).collect();
-
-                    Ok((remainder, Fact(Atom::new(predicate, terms))))
-                },
-                || ParseError::ExpectedFact,
+                |encoding| encoding.map_or(Ok(Encoding::default()), |name| name.parse()),
             ),
         )
     }
 
-    /// Parse an IRI identifier, e.g. for predicate names.
-    pub fn parse_iri_identifier(
+    /// Parses the optional name of the format of a data source, e.g. `, format="json"`.
+    fn parse_source_format(
         &'a self,
-    ) -> impl FnMut(Span<'a>) -> IntermediateResult<Identifier> {
-        map_error(
-            move |input| {
-                let (remainder, name) = traced(
-                    "parse_iri_identifier",
-                    alt((
-                        map(sparql::iriref, |name| sparql::Name::IriReference(&name)),
-                        sparql::prefixed_name,
-                        sparql::blank_node_label,
-                    )),
-                )(input)?;
-
-                Ok((
-                    remainder,
-                    Identifier(
-                        resolve_prefixed_name(&self.prefixes.borrow(), name)
-                            .map_err(|e| Err::Failure(e.at(input)))?,
-                    ),
-                ))
-            },
-            || ParseError::ExpectedIriIdentifier,
+    ) -> impl FnMut(Span<'a>) -> IntermediateResult<Option<Span<'a>>> {
+        traced(
+            "parse_source_format",
+            opt(preceded(
+                tuple((
+                    self.parse_comma(),
+                    token("format"),
+                    space_delimited_token("="),
+                )),
+                turtle::string,
+            )),
         )
     }
 
-    /// Parse an IRI-like identifier.
-    ///
-    /// This is being used for:
-    /// * predicate names
-    /// * built-in functions in term trees
-    pub fn parse_iri_like_identifier(
+    /// Parses the optional sheet and cell range of an XLSX data source,
+    /// e.g. `, sheet="Sheet1", range="A2:C10"`.
+    fn parse_xlsx_parameters(
         &'a self,
-    ) -> impl FnMut(Span<'a>) -> IntermediateResult<Identifier> {
+    ) -> impl FnMut(Span<'a>) -> IntermediateResult<(Option<Span<'a>>, Option<Span<'a>>)> {
         traced(
-            "parse_iri_like_identifier",
-            map_error(
-                alt((
-                    self.parse_iri_identifier(),
-                    self.parse_bare_iri_like_identifier(),
+            "parse_xlsx_parameters",
+            pair(
+                opt(preceded(
+                    tuple((
+                        self.parse_comma(),
+                        token("sheet"),
+                        space_delimited_token("="),
+                    )),
+                    turtle::string,
+                )),
+                opt(preceded(
+                    tuple((
+                        self.parse_comma(),
+                        token("range"),
+                        space_delimited_token("="),
+                    )),
+                    turtle::string,
                 )),
-                || ParseError::ExpectedIriLikeIdentifier,
             ),
         )
     }
 
-    /// Parse a qualified predicate name – currently, this is a
-    /// predicate name together with its arity.
-    fn parse_qualified_predicate_name(
+    /// Parses the parameters of a Soufflé `.input` or `.output` directive,
+    /// e.g. `(IO=file, filename="edges.csv", delimiter=",")`.
+    fn parse_souffle_parameters(
         &'a self,
-    ) -> impl FnMut(Span<'a>) -> IntermediateResult<(Identifier, TupleConstraint)> {
+    ) -> impl FnMut(Span<'a>) -> IntermediateResult<HashMap<String, String>> {
         traced(
-            "parse_qualified_predicate_name",
-            pair(
-                self.parse_iri_like_identifier(),
-                preceded(
-                    multispace_or_comment0,
-                    delimited(
-                        token("["),
-                        cut(alt((
-                            map_res(digit1, |number: Span<'a>| {
-                                number.parse::<usize>().map(TupleConstraint::from_arity)
-                            }),
-                            map(
-                                separated_list1(self.parse_comma(), self.parse_type_name()),
-                                |type_names| type_names.into_iter().collect(),
-                            ),
-                        ))),
-                        cut(token("]")),
+            "parse_souffle_parameters",
+            map(
+                opt(self.parenthesised(separated_list0(
+                    self.parse_comma(),
+                    pair(
+                        terminated(parse_souffle_name, space_delimited_token("=")),
+                        alt((turtle::string, parse_souffle_name)),
                     ),
-                ),
+                ))),
+                |parameters| {
+                    parameters
+                        .unwrap_or_default()
+                        .into_iter()
+                        .map(|(key, value)| (key.to_string(), value.to_string()))
+                        .collect()
+                },
             ),
         )
     }
 
-    /// Parse an IRI-like identifier (e.g. a predicate name) that is not an IRI.
-    pub fn parse_bare_iri_like_identifier(
+    /// Parses a Soufflé `.decl` directive, such as `.decl edge(x: symbol, y: number)`,
+    /// which declares the types of a predicate.
+    fn parse_souffle_declaration(
         &'a self,
-    ) -> impl FnMut(Span<'a>) -> IntermediateResult<Identifier> {
-        traced("parse_bare_iri_like_identifier", move |input| {
-            let (remainder, name) = parse_bare_name(input)?;
-
-            Ok((remainder, Identifier(name.to_string())))
-        })
-    }
-
-    /// Parse a rule.
-    pub fn parse_rule(&'a self) -> impl FnMut(Span<'a>) -> IntermediateResult<Rule> {
+    ) -> impl FnMut(Span<'a>) -> IntermediateResult<(Identifier, Vec<PrimitiveType>)> {
         traced(
-            "parse_rule",
+            "parse_souffle_declaration",
             map_error(
                 move |input| {
-                    let (remainder, (head, body)) = pair(
-                        terminated(
-                            separated_list1(self.parse_comma(), self.parse_atom()),
-                            self.parse_arrow(),
-                        ),
-                        cut(terminated(
-                            separated_list1(self.parse_comma(), self.parse_body_expression()),
-                            self.parse_dot(),
+                    let (remainder, (predicate, types)) = delimited(
+                        terminated(token(".decl"), cut(multispace_or_comment1)),
+                        cut(pair(
+                            self.parse_iri_like_identifier(),
+                            self.parenthesised(separated_list1(
+                                self.parse_comma(),
+                                preceded(
+                                    terminated(parse_souffle_name, space_delimited_token(":")),
+                                    map(parse_souffle_name, |type_name| souffle_type(&type_name)),
+                                ),
+                            )),
                         )),
+                        multispace_or_comment0,
                     )(input)?;
 
-                    log::trace!(target: "parser", r#"found rule "{head:?}" :- "{body:?}""#);
-
-                    let literals = body
-                        .iter()
-                        .filter_map(|expr| match expr {
-                            BodyExpression::Literal(l) => Some(l.clone()),
-                            _ => None,
-                        })
-                        .collect();
-                    let filters = body
-                        .into_iter()
-                        .filter_map(|expr| match expr {
-                            BodyExpression::Filter(f) => Some(f),
-                            _ => None,
-                        })
-                        .collect();
-                    Ok((
-                        remainder,
-                        Rule::new_validated(head, literals, filters)
-                            .map_err(|e| Err::Failure(e.at(input)))?,
-                    ))
+                    self.predicate_declarations
+                        .borrow_mut()
+                        .entry(predicate.clone())
+                        .or_insert(types.clone());
+                    Ok((remainder, (predicate, types)))
                 },
-                || ParseError::ExpectedRule,
+                || ParseError::ExpectedSouffleDirective(".decl"),
             ),
         )
     }
 
-    /// Parse an atom.
-    pub fn parse_atom(&'a self) -> impl FnMut(Span<'a>) -> IntermediateResult<Atom> {
+    /// Parses a Soufflé `.input` directive, such as `.input edge` or `.input edge(filename="edges.csv")`,
+    /// which loads the facts of a declared predicate from a DSV file.
+    ///
+    /// As in Soufflé, the facts are read from the tab-separated file `<predicate>.facts` by default.
+    fn parse_souffle_input(
+        &'a self,
+    ) -> impl FnMut(Span<'a>) -> IntermediateResult<DataSourceDeclaration> {
         traced(
-            "parse_atom",
+            "parse_souffle_input",
             map_error(
                 move |input| {
-                    let (remainder, predicate) = self.parse_iri_like_identifier()(input)?;
-                    let (remainder, terms) = delimited(
-                        self.parse_open_parenthesis(),
-                        cut(separated_list1(self.parse_comma(), self.parse_term_tree())),
-                        cut(self.parse_close_parenthesis()),
-                    )(remainder)?;
-
-                    let predicate_name = predicate.name();
-                    log::trace!(target: "parser", "found atom {predicate_name}({terms:?})");
-
-                    Ok((remainder, Atom::new(predicate, terms)))
+                    let (remainder, (predicate, parameters)) = delimited(
+                        terminated(token(".input"), cut(multispace_or_comment1)),
+                        cut(pair(
+                            self.parse_iri_like_identifier(),
+                            self.parse_souffle_parameters(),
+                        )),
+                        multispace_or_comment0,
+                    )(input)?;
+
+                    let failure = |error: ParseError| Err::Failure(error.at(input));
+
+                    if let Some(io) = parameters.get("IO").filter(|io| *io != "file") {
+                        return Err(failure(ParseError::UnsupportedSouffleIo(io.clone())));
+                    }
+
+                    let Some(types) = self
+                        .predicate_declarations
+                        .borrow()
+                        .get(&predicate)
+                        .cloned()
+                    else {
+                        return Err(failure(ParseError::UndeclaredSoufflePredicate(
+                            predicate.name(),
+                        )));
+                    };
+
+                    let filename = match parameters.get("filename") {
+                        Some(filename) => self.resource(filename).map_err(failure)?,
+                        None => format!("{}.facts", predicate.name()),
+                    };
+                    let delimiter = match parameters.get("delimiter").map(String::as_str) {
+                        None | Some("\\t") | Some("\t") => b'\t',
+                        Some(delimiter) if delimiter.len() == 1 => delimiter.as_bytes()[0],
+                        Some(delimiter) => {
+                            return Err(failure(ParseError::InvalidSouffleDelimiter(
+                                delimiter.to_string(),
+                            )))
+                        }
+                    };
+
+                    let source = DataSourceDeclaration::new(
+                        predicate,
+                        NativeDataSource::DsvFile(DsvFile::new(
+                            &filename,
+                            delimiter,
+                            types.into_iter().collect(),
+                        )),
+                    );
+
+                    tracing::trace!("Found Soufflé input {source:?}");
+                    self.sources.borrow_mut().push(source.clone());
+
+                    Ok((remainder, source))
                 },
-                || ParseError::ExpectedAtom,
+                || ParseError::ExpectedSouffleDirective(".input"),
             ),
         )
     }
 
-    /// Parse a term.
-    pub fn parse_term(&'a self) -> impl FnMut(Span<'a>) -> IntermediateResult<Term> {
+    /// Parses a Soufflé `.output` directive, such as `.output path`,
+    /// which is treated like an `@output` directive.
+    ///
+    /// Parameters of the directive are ignored,
+    /// since the output files are determined by the options of the reasoner.
+    fn parse_souffle_output(
+        &'a self,
+    ) -> impl FnMut(Span<'a>) -> IntermediateResult<QualifiedPredicateName> {
         traced(
-            "parse_term",
+            "parse_souffle_output",
             map_error(
-                alt((
-                    parse_ground_term(&self.prefixes),
-                    self.parse_variable(),
-                    self.parse_aggregate(),
-                )),
-                || ParseError::ExpectedTerm,
+                map(
+                    delimited(
+                        terminated(token(".output"), cut(multispace_or_comment1)),
+                        cut(terminated(
+                            self.parse_iri_like_identifier(),
+                            self.parse_souffle_parameters(),
+                        )),
+                        multispace_or_comment0,
+                    ),
+                    QualifiedPredicateName::from,
+                ),
+                || ParseError::ExpectedSouffleDirective(".output"),
             ),
         )
     }
 
-    /// Parse an aggregate term.
-    pub fn parse_aggregate(&'a self) -> impl FnMut(Span<'a>) -> IntermediateResult<Term> {
+    /// Parse the declaration of the semiring that combines the annotations of facts,
+    /// e.g., `@semiring min-plus .`.
+    fn parse_semiring(&'a self) -> impl FnMut(Span<'a>) -> IntermediateResult<'a, Semiring> {
         traced(
-            "parse_aggregate",
+            "parse_semiring",
             map_error(
-                move |input| {
-                    let (remainder, _) = nom::character::complete::char('#')(input)?;
-                    let (remainder, aggregate_identifier) =
-                        self.parse_bare_iri_like_identifier()(remainder)?;
-                    let (remainder, variable_identifiers) = self.parenthesised(separated_list1(
-                        self.parse_comma(),
-                        map(self.parse_universal_variable(), |variable| match variable {
-                            Variable::Universal(identifier) => identifier,
-                            Variable::Existential(_) => panic!(),
-                        }),
-                    ))(remainder)?;
-
-                    let aggregate = Aggregate {
-                        aggregate_identifier,
-                        variable_identifiers,
-                    };
-
-                    Ok((remainder, Term::Aggregate(aggregate)))
-                },
-                || ParseError::ExpectedAggregate,
+                map(
+                    delimited(
+                        terminated(token("@semiring"), cut(multispace_or_comment1)),
+                        cut(map_res(
+                            recognize(pair(alpha1, many0(alt((alphanumeric1, tag("-")))))),
+                            |name: Span<'a>| name.parse::<Semiring>(),
+                        )),
+                        cut(self.parse_dot()),
+                    ),
+                    |semiring| {
+                        *self.semiring.borrow_mut() = Some(semiring);
+                        semiring
+                    },
+                ),
+                || ParseError::ExpectedSemiringDeclaration,
             ),
         )
     }
 
-    /// Parse a variable.
-    pub fn parse_variable(&'a self) -> impl FnMut(Span<'a>) -> IntermediateResult<Term> {
+    /// Parse the annotation preceding a fact or rule, e.g., `0.5 ::`,
+    /// which is either a number or a universally quantified variable.
+    fn parse_annotation(&'a self) -> impl FnMut(Span<'a>) -> IntermediateResult<'a, Term> {
         traced(
-            "parse_variable",
+            "parse_annotation",
             map_error(
-                map(
+                terminated(
                     alt((
-                        self.parse_universal_variable(),
-                        self.parse_existential_variable(),
+                        map(turtle::numeric_literal, Term::NumericLiteral),
+                        map(self.parse_universal_variable(), Term::Variable),
                     )),
-                    Term::Variable,
+                    space_delimited_token("::"),
                 ),
-                || ParseError::ExpectedVariable,
+                || ParseError::ExpectedAnnotation,
             ),
         )
     }
 
-    /// Parse a universally quantified variable.
-    pub fn parse_universal_variable(
-        &'a self,
-    ) -> impl FnMut(Span<'a>) -> IntermediateResult<Variable> {
+    /// Parses a statement.
+    pub fn parse_statement(&'a self) -> impl FnMut(Span<'a>) -> IntermediateResult<Statement> {
         traced(
-            "parse_universal_variable",
+            "parse_statement",
             map_error(
-                map(
-                    preceded(token("?"), cut(self.parse_variable_name())),
-                    Variable::Universal,
-                ),
-                || ParseError::ExpectedUniversalVariable,
+                alt((
+                    map(self.parse_fact(), Statement::Fact),
+                    map(self.parse_rule(), Statement::Rule),
+                )),
+                || ParseError::ExpectedStatement,
             ),
         )
     }
 
-    /// Parse an existentially quantified variable.
-    pub fn parse_existential_variable(
+    /// Parse a rule preceded by a priority annotation, e.g., `@priority 2`.
+    pub fn parse_prioritised_rule(
         &'a self,
-    ) -> impl FnMut(Span<'a>) -> IntermediateResult<Variable> {
+    ) -> impl FnMut(Span<'a>) -> IntermediateResult<'a, Rule> {
         traced(
-            "parse_existential_variable",
+            "parse_prioritised_rule",
             map_error(
                 map(
-                    preceded(token("!"), cut(self.parse_variable_name())),
-                    Variable::Existential,
+                    pair(
+                        delimited(
+                            terminated(token("@priority"), cut(multispace_or_comment1)),
+                            cut(map_res(
+                                recognize(pair(opt(turtle::sign), digit1)),
+                                |value: Span<'a>| value.parse::<i64>(),
+                            )),
+                            cut(multispace_or_comment1),
+                        ),
+                        cut(self.parse_rule()),
+                    ),
+                    |(priority, rule)| rule.with_priority(priority),
                 ),
-                || ParseError::ExpectedExistentialVariable,
+                || ParseError::ExpectedPriorityAnnotation,
             ),
         )
     }
 
-    /// Parse a variable name.
-    pub fn parse_variable_name(&'a self) -> impl FnMut(Span<'a>) -> IntermediateResult<Identifier> {
+    /// Parse a fact.
+    pub fn parse_fact(&'a self) -> impl FnMut(Span<'a>) -> IntermediateResult<Fact> {
         traced(
-            "parse_variable",
+            "parse_fact",
             map_error(
-                move |input| {
-                    let (remainder, name) = parse_simple_name(input)?;
-
-                    Ok((remainder, Identifier(name.to_string())))
-                },
-                || ParseError::ExpectedVariableName,
+                map(terminated(self.parse_ground_atom(), self.parse_dot()), Fact),
+                || ParseError::ExpectedFact,
             ),
         )
     }
 
-    /// Parse a literal (i.e., a possibly negated atom).
-    pub fn parse_literal(&'a self) -> impl FnMut(Span<'a>) -> IntermediateResult<Literal> {
+    /// Parse a fact that is valid in an interval of time, e.g., `p(a)@[2020-01-01, 2021-01-01) .`
+    pub fn parse_temporal_fact(
+        &'a self,
+    ) -> impl FnMut(Span<'a>) -> IntermediateResult<(Fact, ValidityInterval)> {
         traced(
-            "parse_literal",
+            "parse_temporal_fact",
             map_error(
-                alt((self.parse_negative_literal(), self.parse_positive_literal())),
-                || ParseError::ExpectedLiteral,
+                terminated(
+                    pair(
+                        map(self.parse_ground_atom(), Fact),
+                        self.parse_validity_interval(),
+                    ),
+                    self.parse_dot(),
+                ),
+                || ParseError::ExpectedFact,
             ),
         )
     }
 
-    /// Parse a non-negated literal.
-    pub fn parse_positive_literal(&'a self) -> impl FnMut(Span<'a>) -> IntermediateResult<Literal> {
-        traced(
-            "parse_positive_literal",
-            map_error(map(self.parse_atom(), Literal::Positive), || {
-                ParseError::ExpectedPositiveLiteral
-            }),
-        )
+    /// Parse an atom whose terms are ground terms.
+    fn parse_ground_atom(&'a self) -> impl FnMut(Span<'a>) -> IntermediateResult<Atom> {
+        traced("parse_ground_atom", move |input| {
+            let (remainder, (predicate, terms)) = pair(
+                self.parse_iri_like_identifier(),
+                self.parenthesised(separated_list1(
+                    self.parse_comma(),
+                    parse_ground_term(&self.prefixes),
+                )),
+            )(input)?;
+
+            let predicate_name = predicate.name();
+            tracing::trace!(target: "parser", "found fact {predicate_name}({terms:?})");
+
+            // We do not allow complex term trees in facts for now
+            let terms = terms.into_iter().map(TermTree::leaf).collect();
+
+            Ok((remainder, Atom::new(predicate, terms)))
+        })
     }
 
-    /// Parse a negated literal.
-    pub fn parse_negative_literal(&'a self) -> impl FnMut(Span<'a>) -> IntermediateResult<Literal> {
+    /// Parse the validity interval following a fact, e.g., `@[2020-01-01, 2021-01-01)`,
+    /// which contains its start but not its end.
+    fn parse_validity_interval(
+        &'a self,
+    ) -> impl FnMut(Span<'a>) -> IntermediateResult<'a, ValidityInterval> {
         traced(
-            "parse_negative_literal",
+            "parse_validity_interval",
             map_error(
-                map(
-                    preceded(self.parse_not(), cut(self.parse_atom())),
-                    Literal::Negative,
+                preceded(
+                    space_delimited_token("@["),
+                    cut(map_res(
+                        terminated(
+                            separated_pair(parse_datetime, self.parse_comma(), parse_datetime),
+                            space_delimited_token(")"),
+                        ),
+                        |(start, end)| ValidityInterval::new(start, end),
+                    )),
                 ),
-                || ParseError::ExpectedNegativeLiteral,
+                || ParseError::ExpectedValidityInterval,
             ),
         )
     }
 
-    /// Parse operation that is filters a variable
-    pub fn parse_filter_operator(
+    /// Parse the declaration of a temporal predicate, e.g., `@temporal employment .`,
+    /// which is needed for temporal predicates that only occur in data sources and rule bodies.
+    fn parse_temporal_declaration(
         &'a self,
-    ) -> impl FnMut(Span<'a>) -> IntermediateResult<FilterOperation> {
+    ) -> impl FnMut(Span<'a>) -> IntermediateResult<'a, Identifier> {
         traced(
-            "parse_filter_operator",
+            "parse_temporal_declaration",
             map_error(
                 delimited(
-                    multispace_or_comment0,
-                    alt((
-                        value(FilterOperation::LessThanEq, token("<=")),
-                        value(FilterOperation::LessThan, token("<")),
-                        value(FilterOperation::Equals, token("=")),
-                        value(FilterOperation::Unequals, token("!=")),
-                        value(FilterOperation::GreaterThanEq, token(">=")),
-                        value(FilterOperation::GreaterThan, token(">")),
-                    )),
-                    multispace_or_comment0,
+                    terminated(token("@temporal"), cut(multispace_or_comment1)),
+                    cut(self.parse_iri_like_identifier()),
+                    cut(self.parse_dot()),
                 ),
-                || ParseError::ExpectedFilterOperator,
+                || ParseError::ExpectedTemporalDeclaration,
             ),
         )
     }
 
-    /// Parse a term tree.
+    /// Parse a limit on the number of facts of a predicate, e.g., `@limit path 10_000_000 .`,
+    /// where underscores may separate groups of digits.
     ///
-    /// This may consist of:
-    /// * A function term
-    /// * An arithmetic expression, which handles e.g. precedence of addition over multiplication
-    pub fn parse_term_tree(&'a self) -> impl FnMut(Span<'a>) -> IntermediateResult<TermTree> {
+    /// Since the limit follows the predicate, bare predicate names must not contain spaces here.
+    fn parse_limit(&'a self) -> impl FnMut(Span<'a>) -> IntermediateResult<'a, (Identifier, usize)> {
         traced(
-            "parse_term_tree",
+            "parse_limit",
             map_error(
-                move |input| {
-                    delimited(
-                        multispace_or_comment0,
+                delimited(
+                    terminated(token("@limit"), cut(multispace_or_comment1)),
+                    cut(separated_pair(
                         alt((
-                            self.parse_function_term(),
-                            self.parse_arithmetic_expression(),
-                            self.parse_parenthesised_term_tree(),
+                            self.parse_iri_identifier(),
+                            map(
+                                recognize(pair(sparql::pn_chars_base, many0(sparql::pn_chars))),
+                                |name: Span<'a>| Identifier(name.to_string()),
+                            ),
                         )),
-                        multispace_or_comment0,
-                    )(input)
-                },
-                || ParseError::ExpectedTermTree,
+                        multispace_or_comment1,
+                        map_res(
+                            recognize(pair(digit1, many0(alt((digit1, tag("_")))))),
+                            |value: Span<'a>| value.replace('_', "").parse::<usize>(),
+                        ),
+                    )),
+                    cut(self.parse_dot()),
+                ),
+                || ParseError::ExpectedLimitDeclaration,
             ),
         )
     }
 
-    /// Parse a parenthesised term tree.
-    pub fn parse_parenthesised_term_tree(
+    /// Parse an IRI identifier, e.g. for predicate names.
+    pub fn parse_iri_identifier(
         &'a self,
-    ) -> impl FnMut(Span<'a>) -> IntermediateResult<TermTree> {
-        traced(
-            "parse_parenthesised_term_tree",
-            map_error(self.parenthesised(self.parse_term_tree()), || {
-                ParseError::ExpectedParenthesisedTermTree
-            }),
+    ) -> impl FnMut(Span<'a>) -> IntermediateResult<Identifier> {
+        map_error(
+            move |input| {
+                let (remainder, resolved) = traced(
+                    "parse_iri_identifier",
+                    alt((
+                        map(sparql::iriref_escaped, Ok),
+                        map(
+                            alt((sparql::prefixed_name, sparql::blank_node_label)),
+                            |name| resolve_prefixed_name(&self.prefixes.borrow(), name),
+                        ),
+                    )),
+                )(input)?;
+
+                Ok((
+                    remainder,
+                    Identifier(resolved.map_err(|e| Err::Failure(e.at(input)))?),
+                ))
+            },
+            || ParseError::ExpectedIriIdentifier,
         )
     }
 
-    /// Parse a function term, possibly with nested term trees.
-    pub fn parse_function_term(&'a self) -> impl FnMut(Span<'a>) -> IntermediateResult<TermTree> {
+    /// Parse an IRI-like identifier.
+    ///
+    /// This is being used for:
+    /// * predicate names
+    /// * built-in functions in term trees
+    pub fn parse_iri_like_identifier(
+        &'a self,
+    ) -> impl FnMut(Span<'a>) -> IntermediateResult<Identifier> {
         traced(
-            "parse_function_term",
+            "parse_iri_like_identifier",
             map_error(
-                move |input| {
-                    let (remainder, identifier) = self.parse_iri_like_identifier()(input)?;
-
-                    let (remainder, subtrees) = (self.parenthesised(separated_list0(
-                        self.parse_comma(),
-                        self.parse_term_tree(),
-                    )))(remainder)?;
-
-                    Ok((
-                        remainder,
-                        TermTree::tree(TermOperation::Function(identifier), subtrees),
-                    ))
-                },
-                || ParseError::ExpectedFunctionTerm,
+                alt((
+                    self.parse_iri_identifier(),
+                    self.parse_bare_iri_like_identifier(),
+                )),
+                || ParseError::ExpectedIriLikeIdentifier,
             ),
         )
     }
 
-    /// Parse an arithmetic expression
-    pub fn parse_arithmetic_expression(
-        &'a self,
-    ) -> impl FnMut(Span<'a>) -> IntermediateResult<TermTree> {
+    /// Parse a qualified predicate name – currently, this is a
+    /// predicate name together with its arity.
+    fn parse_qualified_predicate_name(
+        &'a self,
+    ) -> impl FnMut(Span<'a>) -> IntermediateResult<(Identifier, TupleConstraint)> {
         traced(
-            "parse_arithmetic_expression",
+            "parse_qualified_predicate_name",
+            pair(
+                self.parse_iri_like_identifier(),
+                preceded(
+                    multispace_or_comment0,
+                    delimited(
+                        token("["),
+                        cut(alt((
+                            map_res(digit1, |number: Span<'a>| {
+                                number.parse::<usize>().map(TupleConstraint::from_arity)
+                            }),
+                            map(
+                                separated_list1(self.parse_comma(), self.parse_type_name()),
+                                |type_names| type_names.into_iter().collect(),
+                            ),
+                        ))),
+                        cut(token("]")),
+                    ),
+                ),
+            ),
+        )
+    }
+
+    /// Parse an IRI-like identifier (e.g. a predicate name) that is not an IRI.
+    pub fn parse_bare_iri_like_identifier(
+        &'a self,
+    ) -> impl FnMut(Span<'a>) -> IntermediateResult<Identifier> {
+        traced("parse_bare_iri_like_identifier", move |input| {
+            let (remainder, name) = parse_bare_name(input)?;
+
+            Ok((remainder, Identifier(name.to_string())))
+        })
+    }
+
+    /// Parse a rule.
+    pub fn parse_rule(&'a self) -> impl FnMut(Span<'a>) -> IntermediateResult<Rule> {
+        traced(
+            "parse_rule",
             map_error(
                 move |input| {
-                    let (remainder, first) = self.parse_arithmetic_product()(input)?;
-                    let (remainder, expressions) = many0(alt((
-                        preceded(
-                            delimited(multispace_or_comment0, token("+"), multispace_or_comment0),
-                            map(self.parse_arithmetic_product(), |term| {
-                                (TermOperation::Addition, term)
-                            }),
-                        ),
-                        preceded(
-                            delimited(multispace_or_comment0, token("-"), multispace_or_comment0),
-                            map(self.parse_arithmetic_product(), |term| {
-                                (TermOperation::Subtraction, term)
-                            }),
+                    let (remainder, ((head, head_disjuncts), disjuncts)) = pair(
+                        terminated(
+                            pair(
+                                separated_list1(self.parse_comma(), self.parse_atom()),
+                                many0(pair(
+                                    self.parse_bar(),
+                                    separated_list1(self.parse_comma(), self.parse_atom()),
+                                )),
+                            ),
+                            self.parse_arrow(),
                         ),
-                    )))(remainder)?;
+                        cut(terminated(
+                            separated_list1(
+                                self.parse_semicolon(),
+                                separated_list1(self.parse_comma(), self.parse_body_expression()),
+                            ),
+                            self.parse_dot(),
+                        )),
+                    )(input)?;
 
-                    Ok((
-                        remainder,
-                        Self::fold_arithmetic_expressions(first, expressions),
-                    ))
+                    tracing::trace!(target: "parser", r#"found rule "{head:?}" :- "{disjuncts:?}""#);
+
+                    // All disjuncts of the head are validated,
+                    // even if only the first one is derived.
+                    let derived_head = match self.disjunctive_heads {
+                        DisjunctiveHeads::Reject => {
+                            if let Some((bar, _)) = head_disjuncts.first() {
+                                return Err(Err::Failure(ParseError::DisjunctiveHead.at(*bar)));
+                            }
+
+                            head.clone()
+                        }
+                        DisjunctiveHeads::ChooseFirst => head.clone(),
+                        DisjunctiveHeads::Split => head
+                            .iter()
+                            .chain(head_disjuncts.iter().flat_map(|(_, atoms)| atoms))
+                            .cloned()
+                            .collect(),
+                    };
+                    let full_head = head
+                        .into_iter()
+                        .chain(head_disjuncts.into_iter().flat_map(|(_, atoms)| atoms))
+                        .collect::<Vec<_>>();
+
+                    // Each disjunct of the body is validated as a rule of its own,
+                    // but errors are reported for the whole rule.
+                    let mut rules = Vec::new();
+                    for body in disjuncts {
+                        let mut rule = Self::body_to_rule(full_head.clone(), body)
+                            .map_err(|e| Err::Failure(e.at(input)))?;
+                        *rule.head_mut() = derived_head.clone();
+
+                        rules.push(rule);
+                    }
+                    let rule = rules.remove(0).with_alternatives(rules);
+
+                    Ok((remainder, rule))
                 },
-                || ParseError::ExpectedArithmeticExpression,
+                || ParseError::ExpectedRule,
             ),
         )
     }
 
-    /// Parse an arithmetic product, i.e., an expression involving
-    /// only `*` and `/` over subexpressions.
-    pub fn parse_arithmetic_product(
-        &'a self,
-    ) -> impl FnMut(Span<'a>) -> IntermediateResult<TermTree> {
+    /// Construct a validated rule from its head and the expressions in its body.
+    fn body_to_rule(head: Vec<Atom>, body: Vec<BodyExpression>) -> Result<Rule, ParseError> {
+        let literals = body
+            .iter()
+            .filter_map(|expr| match expr {
+                BodyExpression::Literal(l) => Some(l.clone()),
+                _ => None,
+            })
+            .collect();
+        let mut filters: Vec<Filter> = body
+            .iter()
+            .filter_map(|expr| match expr {
+                BodyExpression::Filter(f) => Some(vec![f.clone()]),
+                BodyExpression::CidrFilter(filters) => Some(filters.clone()),
+                _ => None,
+            })
+            .flatten()
+            .collect();
+        let negated_conjunctions = body
+            .iter()
+            .filter_map(|expr| match expr {
+                BodyExpression::NegatedConjunction(c) => Some(c.clone()),
+                _ => None,
+            })
+            .collect();
+
+        // The value of each function term in a filter is bound to a fresh variable.
+        let mut computed_variables = Vec::new();
+        for expr in body {
+            if let BodyExpression::FunctionFilter(function, operation, term) = expr {
+                let variable = Variable::Universal(Identifier(format!(
+                    "FILTER_FUNCTION_{}",
+                    computed_variables.len()
+                )));
+
+                filters.push(Filter::new(operation, variable.clone(), term));
+                computed_variables.push((variable, function));
+            }
+        }
+
+        Rule::new_validated(
+            head,
+            literals,
+            filters,
+            negated_conjunctions,
+            computed_variables,
+        )
+    }
+
+    /// Parse an atom.
+    pub fn parse_atom(&'a self) -> impl FnMut(Span<'a>) -> IntermediateResult<Atom> {
         traced(
-            "parse_arithmetic_product",
+            "parse_atom",
             map_error(
                 move |input| {
-                    let (remainder, first) = self.parse_arithmetic_factor()(input)?;
-                    let (remainder, factors) = many0(alt((
-                        preceded(
-                            delimited(multispace_or_comment0, token("*"), multispace_or_comment0),
-                            map(self.parse_arithmetic_factor(), |term| {
-                                (TermOperation::Multiplication, term)
-                            }),
-                        ),
-                        preceded(
-                            delimited(multispace_or_comment0, token("/"), multispace_or_comment0),
-                            map(self.parse_arithmetic_factor(), |term| {
-                                (TermOperation::Division, term)
-                            }),
-                        ),
-                    )))(remainder)?;
+                    let (remainder, predicate) = self.parse_iri_like_identifier()(input)?;
+                    let (remainder, terms) = delimited(
+                        self.parse_open_parenthesis(),
+                        cut(separated_list1(self.parse_comma(), self.parse_term_tree())),
+                        cut(self.parse_close_parenthesis()),
+                    )(remainder)?;
 
-                    Ok((remainder, Self::fold_arithmetic_expressions(first, factors)))
+                    let predicate_name = predicate.name();
+                    tracing::trace!(target: "parser", "found atom {predicate_name}({terms:?})");
+
+                    Ok((remainder, Atom::new(predicate, terms)))
                 },
-                || ParseError::ExpectedArithmeticProduct,
+                || ParseError::ExpectedAtom,
             ),
         )
     }
 
-    /// Parse an arithmetic factor.
-    pub fn parse_arithmetic_factor(
-        &'a self,
-    ) -> impl FnMut(Span<'a>) -> IntermediateResult<TermTree> {
+    /// Parse a term.
+    pub fn parse_term(&'a self) -> impl FnMut(Span<'a>) -> IntermediateResult<Term> {
         traced(
-            "parse_arithmetic_factor",
+            "parse_term",
             map_error(
                 alt((
-                    map(self.parse_term(), TermTree::leaf),
-                    self.parse_parenthesised_term_tree(),
+                    parse_ground_term(&self.prefixes),
+                    self.parse_variable(),
+                    self.parse_aggregate(),
                 )),
-                || ParseError::ExpectedArithmeticFactor,
+                || ParseError::ExpectedTerm,
             ),
         )
     }
 
-    /// Fold a sequence of [Term trees][TermTree] interleaved with
-    /// [Term operations][TermOperation] into a single [`TermTree`].
-    fn fold_arithmetic_expressions(
-        initial: TermTree,
-        sequence: Vec<(TermOperation, TermTree)>,
-    ) -> TermTree {
-        sequence.into_iter().fold(initial, |acc, pair| {
-            let (operation, expression) = pair;
-            let subtrees = vec![acc, expression];
+    /// Parse an aggregate term.
+    pub fn parse_aggregate(&'a self) -> impl FnMut(Span<'a>) -> IntermediateResult<Term> {
+        traced(
+            "parse_aggregate",
+            map_error(
+                move |input| {
+                    let (remainder, _) = nom::character::complete::char('#')(input)?;
+                    let (remainder, aggregate_identifier) =
+                        self.parse_bare_iri_like_identifier()(remainder)?;
+                    let (remainder, variable_identifiers) = self.parenthesised(separated_list1(
+                        self.parse_comma(),
+                        map(self.parse_universal_variable(), |variable| match variable {
+                            Variable::Universal(identifier) => identifier,
+                            Variable::Existential(_) => panic!(),
+                        }),
+                    ))(remainder)?;
 
-            use TermOperation::*;
+                    let aggregate = Aggregate {
+                        aggregate_identifier,
+                        variable_identifiers,
+                    };
 
-            match operation {
-                Addition => TermTree::tree(Addition, subtrees),
-                Subtraction => TermTree::tree(Subtraction, subtrees),
-                Multiplication => TermTree::tree(Multiplication, subtrees),
-                Division => TermTree::tree(Division, subtrees),
-                Term(term) => TermTree::leaf(term),
-                Function(_) => panic!("expressions folding is not implemented for functions"),
-            }
-        })
+                    Ok((remainder, Term::Aggregate(aggregate)))
+                },
+                || ParseError::ExpectedAggregate,
+            ),
+        )
     }
 
-    /// Parse expression of the form `<variable> <operation> <term>`
-    /// or `<term> <operation> <variable>`.
-    pub fn parse_filter_expression(&'a self) -> impl FnMut(Span<'a>) -> IntermediateResult<Filter> {
+    /// Parse a variable.
+    pub fn parse_variable(&'a self) -> impl FnMut(Span<'a>) -> IntermediateResult<Term> {
         traced(
-            "parse_filter_expression",
+            "parse_variable",
             map_error(
-                alt((
-                    map(
-                        tuple((
-                            self.parse_universal_variable(),
-                            self.parse_filter_operator(),
-                            cut(self.parse_term()),
-                        )),
-                        |(lhs, operation, rhs)| Filter::new(operation, lhs, rhs),
-                    ),
-                    map(
-                        tuple((
-                            self.parse_term(),
-                            self.parse_filter_operator(),
-                            cut(self.parse_universal_variable()),
-                        )),
-                        |(lhs, operation, rhs)| Filter::flipped(operation, lhs, rhs),
-                    ),
-                )),
-                || ParseError::ExpectedFilterExpression,
+                map(
+                    alt((
+                        self.parse_universal_variable(),
+                        self.parse_existential_variable(),
+                        self.parse_anonymous_variable(),
+                    )),
+                    Term::Variable,
+                ),
+                || ParseError::ExpectedVariable,
             ),
         )
     }
 
-    /// Parse body expression
-    pub fn parse_body_expression(
+    /// Parse a universally quantified variable.
+    pub fn parse_universal_variable(
         &'a self,
-    ) -> impl FnMut(Span<'a>) -> IntermediateResult<BodyExpression> {
+    ) -> impl FnMut(Span<'a>) -> IntermediateResult<Variable> {
         traced(
-            "parse_body_expression",
+            "parse_universal_variable",
             map_error(
-                alt((
-                    map(self.parse_filter_expression(), BodyExpression::Filter),
-                    map(self.parse_literal(), BodyExpression::Literal),
-                )),
-                || ParseError::ExpectedBodyExpression,
+                map(
+                    preceded(token("?"), cut(self.parse_variable_name())),
+                    Variable::Universal,
+                ),
+                || ParseError::ExpectedUniversalVariable,
             ),
         )
     }
 
-    /// Parses a program in the rules language.
-    pub fn parse_program(&'a self) -> impl FnMut(Span<'a>) -> IntermediateResult<Program> {
-        fn check_for_invalid_statement<'a, F>(
-            parser: &mut F,
-            input: Span<'a>,
-        ) -> IntermediateResult<'a, ()>
-        where
-            F: FnMut(Span<'a>) -> IntermediateResult<ParseError>,
-        {
-            if let Ok((_, e)) = parser(input) {
-                return Err(Err::Failure(e.at(input)));
-            }
-
-            Ok((input, ()))
-        }
-
-        traced("parse_program", move |input| {
-            let (remainder, _) = multispace_or_comment0(input)?;
-            let (remainder, _) = opt(self.parse_base())(remainder)?;
-
-            check_for_invalid_statement(
-                &mut map(self.parse_base(), |_| ParseError::LateBaseDeclaration),
-                remainder,
-            )?;
+    /// Parse an existentially quantified variable.
+    pub fn parse_existential_variable(
+        &'a self,
+    ) -> impl FnMut(Span<'a>) -> IntermediateResult<Variable> {
+        traced(
+            "parse_existential_variable",
+            map_error(
+                map(
+                    preceded(token("!"), cut(self.parse_variable_name())),
+                    Variable::Existential,
+                ),
+                || ParseError::ExpectedExistentialVariable,
+            ),
+        )
+    }
 
-            let (remainder, _) = many0(self.parse_prefix())(remainder)?;
+    /// Parse an anonymous variable `_`, which is replaced by a fresh variable.
+    pub fn parse_anonymous_variable(
+        &'a self,
+    ) -> impl FnMut(Span<'a>) -> IntermediateResult<'a, Variable> {
+        traced(
+            "parse_anonymous_variable",
+            map_error(
+                map(
+                    terminated(tag("_"), not(alt((sparql::pn_chars, tag(":"))))),
+                    |_| {
+                        let mut count = self.anonymous_variables.borrow_mut();
+                        *count += 1;
+                        Variable::anonymous(*count - 1)
+                    },
+                ),
+                || ParseError::ExpectedAnonymousVariable,
+            ),
+        )
+    }
 
-            check_for_invalid_statement(
-                &mut map(self.parse_base(), |_| ParseError::LateBaseDeclaration),
-                remainder,
-            )?;
-            check_for_invalid_statement(
-                &mut map(self.parse_prefix(), |_| ParseError::LatePrefixDeclaration),
-                remainder,
-            )?;
+    /// Parse a variable name.
+    pub fn parse_variable_name(&'a self) -> impl FnMut(Span<'a>) -> IntermediateResult<Identifier> {
+        traced(
+            "parse_variable",
+            map_error(
+                move |input| {
+                    let (remainder, name) = parse_simple_name(input)?;
 
-            let mut statements = Vec::new();
-            let mut output_predicates = Vec::new();
+                    Ok((remainder, Identifier(name.to_string())))
+                },
+                || ParseError::ExpectedVariableName,
+            ),
+        )
+    }
 
-            let (remainder, _) = many0(alt((
-                map(self.parse_predicate_declaration(), |_| ()),
-                map(self.parse_source(), |_| ()),
-                map(self.parse_statement(), |statement| {
-                    statements.push(statement)
-                }),
-                map(self.parse_output(), |output_predicate| {
-                    output_predicates.push(output_predicate)
-                }),
-            )))(remainder)?;
+    /// Parse a literal (i.e., a possibly negated atom).
+    pub fn parse_literal(&'a self) -> impl FnMut(Span<'a>) -> IntermediateResult<Literal> {
+        traced(
+            "parse_literal",
+            map_error(
+                alt((self.parse_negative_literal(), self.parse_positive_literal())),
+                || ParseError::ExpectedLiteral,
+            ),
+        )
+    }
 
-            check_for_invalid_statement(
-                &mut map(self.parse_base(), |_| ParseError::LateBaseDeclaration),
-                remainder,
-            )?;
-            check_for_invalid_statement(
-                &mut map(self.parse_prefix(), |_| ParseError::LatePrefixDeclaration),
-                remainder,
-            )?;
+    /// Parse a non-negated literal.
+    pub fn parse_positive_literal(&'a self) -> impl FnMut(Span<'a>) -> IntermediateResult<Literal> {
+        traced(
+            "parse_positive_literal",
+            map_error(map(self.parse_atom(), Literal::Positive), || {
+                ParseError::ExpectedPositiveLiteral
+            }),
+        )
+    }
 
-            let base = self.base().map(String::from);
-            let prefixes = self
-                .prefixes
-                .borrow()
-                .iter()
-                .map(|(&prefix, &iri)| (prefix.to_string(), iri.to_string()))
-                .collect();
-            let mut rules = Vec::new();
-            let mut facts = Vec::new();
+    /// Parse a negated literal.
+    pub fn parse_negative_literal(&'a self) -> impl FnMut(Span<'a>) -> IntermediateResult<Literal> {
+        traced(
+            "parse_negative_literal",
+            map_error(
+                map(
+                    preceded(self.parse_not(), cut(self.parse_atom())),
+                    Literal::Negative,
+                ),
+                || ParseError::ExpectedNegativeLiteral,
+            ),
+        )
+    }
 
-            statements.iter().for_each(|statement| match statement {
-                Statement::Fact(value) => facts.push(value.clone()),
-                Statement::Rule(value) => rules.push(value.clone()),
-            });
+    /// Parse a negated conjunction of atoms, e.g. `~(q(?X, ?Y), r(?Y))`.
+    pub fn parse_negated_conjunction(
+        &'a self,
+    ) -> impl FnMut(Span<'a>) -> IntermediateResult<'a, Vec<Atom>> {
+        traced(
+            "parse_negated_conjunction",
+            map_error(
+                preceded(
+                    self.parse_not(),
+                    delimited(
+                        self.parse_open_parenthesis(),
+                        cut(separated_list1(self.parse_comma(), self.parse_atom())),
+                        cut(self.parse_close_parenthesis()),
+                    ),
+                ),
+                || ParseError::ExpectedNegatedConjunction,
+            ),
+        )
+    }
 
-            Ok((
-                remainder,
-                Program::new(
-                    base,
-                    prefixes,
-                    self.sources.borrow().clone(),
-                    rules,
-                    facts,
-                    self.predicate_declarations.borrow().clone(),
-                    output_predicates.into(),
+    /// Parse operation that is filters a variable
+    pub fn parse_filter_operator(
+        &'a self,
+    ) -> impl FnMut(Span<'a>) -> IntermediateResult<FilterOperation> {
+        traced(
+            "parse_filter_operator",
+            map_error(
+                delimited(
+                    multispace_or_comment0,
+                    alt((
+                        value(FilterOperation::LessThanEq, token("<=")),
+                        value(FilterOperation::LessThan, token("<")),
+                        value(FilterOperation::Equals, token("=")),
+                        value(FilterOperation::Unequals, token("!=")),
+                        value(FilterOperation::GreaterThanEq, token(">=")),
+                        value(FilterOperation::GreaterThan, token(">")),
+                    )),
+                    multispace_or_comment0,
                 ),
-            ))
-        })
+                || ParseError::ExpectedFilterOperator,
+            ),
+        )
     }
 
-    /// Return the declared base, if set, or None.
-    #[must_use]
-    pub fn base(&self) -> Option<&'a str> {
-        *self.base.borrow()
+    /// Parse a term tree.
+    ///
+    /// This may consist of:
+    /// * A function term
+    /// * An arithmetic expression, which handles e.g. precedence of addition over multiplication
+    pub fn parse_term_tree(&'a self) -> impl FnMut(Span<'a>) -> IntermediateResult<TermTree> {
+        traced(
+            "parse_term_tree",
+            map_error(
+                move |input| {
+                    delimited(
+                        multispace_or_comment0,
+                        alt((
+                            self.parse_arithmetic_expression(),
+                            self.parse_parenthesised_term_tree(),
+                        )),
+                        multispace_or_comment0,
+                    )(input)
+                },
+                || ParseError::ExpectedTermTree,
+            ),
+        )
+    }
+
+    /// Parse a parenthesised term tree.
+    pub fn parse_parenthesised_term_tree(
+        &'a self,
+    ) -> impl FnMut(Span<'a>) -> IntermediateResult<TermTree> {
+        traced(
+            "parse_parenthesised_term_tree",
+            map_error(self.parenthesised(self.parse_term_tree()), || {
+                ParseError::ExpectedParenthesisedTermTree
+            }),
+        )
+    }
+
+    /// Parse a function term, possibly with nested term trees.
+    pub fn parse_function_term(&'a self) -> impl FnMut(Span<'a>) -> IntermediateResult<TermTree> {
+        traced(
+            "parse_function_term",
+            map_error(
+                move |input| {
+                    let (remainder, identifier) = self.parse_iri_like_identifier()(input)?;
+
+                    let (remainder, subtrees) = (self.parenthesised(separated_list0(
+                        self.parse_comma(),
+                        self.parse_term_tree(),
+                    )))(remainder)?;
+
+                    Ok((
+                        remainder,
+                        TermTree::tree(TermOperation::Function(identifier), subtrees),
+                    ))
+                },
+                || ParseError::ExpectedFunctionTerm,
+            ),
+        )
+    }
+
+    /// Parse an arithmetic expression
+    pub fn parse_arithmetic_expression(
+        &'a self,
+    ) -> impl FnMut(Span<'a>) -> IntermediateResult<TermTree> {
+        traced(
+            "parse_arithmetic_expression",
+            map_error(
+                move |input| {
+                    let (remainder, first) = self.parse_arithmetic_product()(input)?;
+                    let (remainder, expressions) = many0(alt((
+                        preceded(
+                            delimited(multispace_or_comment0, token("+"), multispace_or_comment0),
+                            map(self.parse_arithmetic_product(), |term| {
+                                (TermOperation::Addition, term)
+                            }),
+                        ),
+                        preceded(
+                            delimited(multispace_or_comment0, token("-"), multispace_or_comment0),
+                            map(self.parse_arithmetic_product(), |term| {
+                                (TermOperation::Subtraction, term)
+                            }),
+                        ),
+                    )))(remainder)?;
+
+                    Ok((
+                        remainder,
+                        Self::fold_arithmetic_expressions(first, expressions),
+                    ))
+                },
+                || ParseError::ExpectedArithmeticExpression,
+            ),
+        )
     }
 
-    /// Try to expand an IRI into an absolute IRI.
-    #[must_use]
-    pub fn absolutize_iri(&self, iri: Span) -> String {
-        if iri::is_absolute(iri) {
-            iri.to_string()
-        } else {
-            format!("{}{iri}", self.base().unwrap_or_default())
-        }
+    /// Parse an arithmetic product, i.e., an expression involving
+    /// only `*` and `/` over subexpressions.
+    pub fn parse_arithmetic_product(
+        &'a self,
+    ) -> impl FnMut(Span<'a>) -> IntermediateResult<TermTree> {
+        traced(
+            "parse_arithmetic_product",
+            map_error(
+                move |input| {
+                    let (remainder, first) = self.parse_arithmetic_factor()(input)?;
+                    let (remainder, factors) = many0(alt((
+                        preceded(
+                            delimited(multispace_or_comment0, token("*"), multispace_or_comment0),
+                            map(self.parse_arithmetic_factor(), |term| {
+                                (TermOperation::Multiplication, term)
+                            }),
+                        ),
+                        preceded(
+                            delimited(multispace_or_comment0, token("/"), multispace_or_comment0),
+                            map(self.parse_arithmetic_factor(), |term| {
+                                (TermOperation::Division, term)
+                            }),
+                        ),
+                    )))(remainder)?;
+
+                    Ok((remainder, Self::fold_arithmetic_expressions(first, factors)))
+                },
+                || ParseError::ExpectedArithmeticProduct,
+            ),
+        )
+    }
+
+    /// Parse an arithmetic factor.
+    pub fn parse_arithmetic_factor(
+        &'a self,
+    ) -> impl FnMut(Span<'a>) -> IntermediateResult<TermTree> {
+        traced(
+            "parse_arithmetic_factor",
+            map_error(
+                alt((
+                    self.parse_function_term(),
+                    map(self.parse_term(), TermTree::leaf),
+                    self.parse_negated_arithmetic_factor(),
+                    self.parse_parenthesised_term_tree(),
+                )),
+                || ParseError::ExpectedArithmeticFactor,
+            ),
+        )
+    }
+
+    /// Parse a term, function term, or parenthesised term tree preceded by a unary minus,
+    /// which is represented as a subtraction from zero.
+    ///
+    /// Negative numeric literals are already covered by [`RuleParser::parse_term`].
+    pub fn parse_negated_arithmetic_factor(
+        &'a self,
+    ) -> impl FnMut(Span<'a>) -> IntermediateResult<'a, TermTree> {
+        traced(
+            "parse_negated_arithmetic_factor",
+            map(
+                preceded(
+                    token("-"),
+                    alt((
+                        self.parse_function_term(),
+                        map(self.parse_term(), TermTree::leaf),
+                        self.parse_parenthesised_term_tree(),
+                    )),
+                ),
+                |factor| {
+                    TermTree::tree(
+                        TermOperation::Subtraction,
+                        vec![
+                            TermTree::leaf(Term::NumericLiteral(NumericLiteral::Integer(0))),
+                            factor,
+                        ],
+                    )
+                },
+            ),
+        )
+    }
+
+    /// Fold a sequence of [Term trees][TermTree] interleaved with
+    /// [Term operations][TermOperation] into a single [`TermTree`].
+    fn fold_arithmetic_expressions(
+        initial: TermTree,
+        sequence: Vec<(TermOperation, TermTree)>,
+    ) -> TermTree {
+        sequence.into_iter().fold(initial, |acc, pair| {
+            let (operation, expression) = pair;
+            let subtrees = vec![acc, expression];
+
+            use TermOperation::*;
+
+            match operation {
+                Addition => TermTree::tree(Addition, subtrees),
+                Subtraction => TermTree::tree(Subtraction, subtrees),
+                Multiplication => TermTree::tree(Multiplication, subtrees),
+                Division => TermTree::tree(Division, subtrees),
+                Term(term) => TermTree::leaf(term),
+                Function(_) => panic!("expressions folding is not implemented for functions"),
+            }
+        })
+    }
+
+    /// Parse expression of the form `<variable> <operation> <term>`
+    /// or `<term> <operation> <variable>`.
+    pub fn parse_filter_expression(&'a self) -> impl FnMut(Span<'a>) -> IntermediateResult<Filter> {
+        traced(
+            "parse_filter_expression",
+            map_error(
+                alt((
+                    map(
+                        tuple((
+                            self.parse_universal_variable(),
+                            self.parse_filter_operator(),
+                            cut(self.parse_term()),
+                        )),
+                        |(lhs, operation, rhs)| Filter::new(operation, lhs, rhs),
+                    ),
+                    map(
+                        tuple((
+                            self.parse_term(),
+                            self.parse_filter_operator(),
+                            cut(self.parse_universal_variable()),
+                        )),
+                        |(lhs, operation, rhs)| Filter::flipped(operation, lhs, rhs),
+                    ),
+                )),
+                || ParseError::ExpectedFilterExpression,
+            ),
+        )
+    }
+
+    /// Parse expression of the form `<function term> <operation> <term>`
+    /// or `<term> <operation> <function term>`.
+    ///
+    /// Returns the function term, the operation and the term,
+    /// where the operation is flipped in the second case.
+    pub fn parse_function_filter_expression(
+        &'a self,
+    ) -> impl FnMut(Span<'a>) -> IntermediateResult<'a, (TermTree, FilterOperation, Term)> {
+        traced(
+            "parse_function_filter_expression",
+            map_error(
+                alt((
+                    tuple((
+                        self.parse_function_term(),
+                        self.parse_filter_operator(),
+                        cut(self.parse_term()),
+                    )),
+                    map(
+                        tuple((
+                            self.parse_term(),
+                            self.parse_filter_operator(),
+                            self.parse_function_term(),
+                        )),
+                        |(term, operation, function)| (function, operation.flip(), term),
+                    ),
+                )),
+                || ParseError::ExpectedFilterExpression,
+            ),
+        )
+    }
+
+    /// Parse a check whether a variable is an IP address of a network in CIDR notation,
+    /// e.g. `IN_CIDR(?X, "10.0.0.0/8")`.
+    ///
+    /// The check is translated into filters comparing the variable
+    /// with the first and the last address of the network.
+    pub fn parse_cidr_filter(
+        &'a self,
+    ) -> impl FnMut(Span<'a>) -> IntermediateResult<'a, Vec<Filter>> {
+        traced(
+            "parse_cidr_filter",
+            map_error(
+                map(
+                preceded(
+                    token("IN_CIDR"),
+                    delimited(
+                        self.parse_open_parenthesis(),
+                        cut(separated_pair(
+                            self.parse_universal_variable(),
+                            self.parse_comma(),
+                            map_res(turtle::string, |network| {
+                                LogicalIpAddress::cidr_range(&turtle::unescape(&network))
+                                    .map_err(|_err| ParseError::InvalidCidrNetwork(network.to_string()))
+                            }),
+                        )),
+                        cut(self.parse_close_parenthesis()),
+                    ),
+                ),
+                |(variable, (first, last))| {
+                    vec![
+                        Filter::new(FilterOperation::GreaterThanEq, variable.clone(), first.into()),
+                        Filter::new(FilterOperation::LessThanEq, variable, last.into()),
+                    ]
+                },
+                ),
+                || ParseError::ExpectedCidrFilter,
+            ),
+        )
+    }
+
+    /// Parse a full-text search of the words of a query in the string a variable is bound to,
+    /// e.g. `MATCHES_TEXT(?X, "quantum comput*")`.
+    pub fn parse_text_filter(&'a self) -> impl FnMut(Span<'a>) -> IntermediateResult<'a, Filter> {
+        traced(
+            "parse_text_filter",
+            map_error(
+                map(
+                    preceded(
+                        token("MATCHES_TEXT"),
+                        delimited(
+                            self.parse_open_parenthesis(),
+                            cut(separated_pair(
+                                self.parse_universal_variable(),
+                                self.parse_comma(),
+                                turtle::string,
+                            )),
+                            cut(self.parse_close_parenthesis()),
+                        ),
+                    ),
+                    |(variable, query)| {
+                        Filter::new(
+                            FilterOperation::MatchesText,
+                            variable,
+                            Term::StringLiteral(turtle::unescape(&query)),
+                        )
+                    },
+                ),
+                || ParseError::ExpectedTextFilter,
+            ),
+        )
+    }
+
+    /// Parse a bound on the edit distance between the string a variable is bound to
+    /// and the string given by a term, e.g. `EDIT_DISTANCE(?X, ?Y) <= 2`.
+    pub fn parse_edit_distance_filter(
+        &'a self,
+    ) -> impl FnMut(Span<'a>) -> IntermediateResult<'a, Filter> {
+        traced(
+            "parse_edit_distance_filter",
+            map_error(
+                map(
+                    preceded(
+                        token("EDIT_DISTANCE"),
+                        cut(pair(
+                            delimited(
+                                self.parse_open_parenthesis(),
+                                separated_pair(
+                                    self.parse_universal_variable(),
+                                    self.parse_comma(),
+                                    self.parse_term(),
+                                ),
+                                self.parse_close_parenthesis(),
+                            ),
+                            preceded(
+                                delimited(
+                                    multispace_or_comment0,
+                                    token("<="),
+                                    multispace_or_comment0,
+                                ),
+                                map_res(digit1, |value: Span<'a>| value.parse::<usize>()),
+                            ),
+                        )),
+                    ),
+                    |((variable, term), max_distance)| {
+                        Filter::new(
+                            FilterOperation::EditDistanceAtMost(max_distance),
+                            variable,
+                            term,
+                        )
+                    },
+                ),
+                || ParseError::ExpectedEditDistanceFilter,
+            ),
+        )
+    }
+
+    /// Parse body expression
+    pub fn parse_body_expression(
+        &'a self,
+    ) -> impl FnMut(Span<'a>) -> IntermediateResult<BodyExpression> {
+        traced(
+            "parse_body_expression",
+            map_error(
+                alt((
+                    map(self.parse_edit_distance_filter(), BodyExpression::Filter),
+                    map(
+                        self.parse_function_filter_expression(),
+                        |(function, operation, term)| {
+                            BodyExpression::FunctionFilter(function, operation, term)
+                        },
+                    ),
+                    map(self.parse_filter_expression(), BodyExpression::Filter),
+                    map(self.parse_cidr_filter(), BodyExpression::CidrFilter),
+                    map(self.parse_text_filter(), BodyExpression::Filter),
+                    map(
+                        self.parse_negated_conjunction(),
+                        BodyExpression::NegatedConjunction,
+                    ),
+                    map(self.parse_literal(), BodyExpression::Literal),
+                )),
+                || ParseError::ExpectedBodyExpression,
+            ),
+        )
+    }
+
+    /// Parses a program in the rules language.
+    pub fn parse_program(&'a self) -> impl FnMut(Span<'a>) -> IntermediateResult<Program> {
+        fn check_for_invalid_statement<'a, F>(
+            parser: &mut F,
+            input: Span<'a>,
+        ) -> IntermediateResult<'a, ()>
+        where
+            F: FnMut(Span<'a>) -> IntermediateResult<ParseError>,
+        {
+            if let Ok((_, e)) = parser(input) {
+                return Err(Err::Failure(e.at(input)));
+            }
+
+            Ok((input, ()))
+        }
+
+        traced("parse_program", move |input| {
+            let (remainder, _) = multispace_or_comment0(input)?;
+            let (remainder, _) = opt(self.parse_base())(remainder)?;
+
+            check_for_invalid_statement(
+                &mut map(self.parse_base(), |_| ParseError::LateBaseDeclaration),
+                remainder,
+            )?;
+
+            let (remainder, _) = many0(self.parse_prefix())(remainder)?;
+
+            check_for_invalid_statement(
+                &mut map(self.parse_base(), |_| ParseError::LateBaseDeclaration),
+                remainder,
+            )?;
+            check_for_invalid_statement(
+                &mut map(self.parse_prefix(), |_| ParseError::LatePrefixDeclaration),
+                remainder,
+            )?;
+
+            let (remainder, _) = opt(self.parse_semiring())(remainder)?;
+
+            check_for_invalid_statement(
+                &mut map(self.parse_prefix(), |_| ParseError::LatePrefixDeclaration),
+                remainder,
+            )?;
+
+            let mut statements = Vec::new();
+            let mut output_predicates = Vec::new();
+            let mut declared_temporal_predicates = HashSet::new();
+            let mut predicate_limits = HashMap::new();
+
+            let mut predicate_docs: HashMap<Identifier, String> = HashMap::new();
+            let doc_before = |item: Span<'a>| {
+                doc_comment(&input[..item.location_offset() - input.location_offset()])
+            };
+
+            let (remainder, _) = many0(alt((
+                map(
+                    pair(
+                        position,
+                        alt((
+                            map(self.parse_predicate_declaration(), |(predicate, _)| {
+                                predicate
+                            }),
+                            map(self.parse_source(), |source| source.predicate),
+                        )),
+                    ),
+                    |(start, predicate)| {
+                        if let Some(doc) = doc_before(start) {
+                            predicate_docs
+                                .entry(predicate)
+                                .and_modify(|existing| *existing = format!("{existing}\n{doc}"))
+                                .or_insert(doc);
+                        }
+                    },
+                ),
+                map(
+                    tuple((
+                        position,
+                        opt(self.parse_annotation()),
+                        alt((
+                            map(self.parse_temporal_fact(), |(fact, validity)| {
+                                (Statement::Fact(fact), Some(validity))
+                            }),
+                            map(self.parse_prioritised_rule(), |rule| {
+                                (Statement::Rule(rule), None)
+                            }),
+                            map(self.parse_statement(), |statement| (statement, None)),
+                        )),
+                    )),
+                    |(start, annotation, (statement, validity))| {
+                        let statement = match statement {
+                            Statement::Rule(rule) => {
+                                Statement::Rule(rule.with_doc(doc_before(start)))
+                            }
+                            fact => fact,
+                        };
+                        statements.push((start, annotation, statement, validity))
+                    },
+                ),
+                map(self.parse_temporal_declaration(), |predicate| {
+                    declared_temporal_predicates.insert(predicate);
+                }),
+                map(self.parse_limit(), |(predicate, limit)| {
+                    predicate_limits.insert(predicate, limit);
+                }),
+                map(
+                    alt((self.parse_output(), self.parse_souffle_output())),
+                    |output_predicate| output_predicates.push(output_predicate),
+                ),
+                map(self.parse_souffle_declaration(), |_| ()),
+                map(self.parse_souffle_input(), |_| ()),
+            )))(remainder)?;
+
+            check_for_invalid_statement(
+                &mut map(self.parse_base(), |_| ParseError::LateBaseDeclaration),
+                remainder,
+            )?;
+            check_for_invalid_statement(
+                &mut map(self.parse_prefix(), |_| ParseError::LatePrefixDeclaration),
+                remainder,
+            )?;
+            check_for_invalid_statement(
+                &mut map(self.parse_semiring(), |_| {
+                    ParseError::LateSemiringDeclaration
+                }),
+                remainder,
+            )?;
+
+            let base = self.base().map(String::from);
+            let prefixes = self
+                .prefixes
+                .borrow()
+                .iter()
+                .map(|(&prefix, &iri)| (prefix.to_string(), iri.to_string()))
+                .collect();
+            let semiring = *self.semiring.borrow();
+            let mut rules = Vec::new();
+            let mut facts = Vec::new();
+
+            declared_temporal_predicates.extend(statements.iter().filter_map(
+                |(_, _, statement, validity)| match (statement, validity) {
+                    (Statement::Fact(Fact(atom)), Some(_)) => Some(atom.predicate()),
+                    _ => None,
+                },
+            ));
+            let temporal_predicates = ValidityInterval::temporal_predicates(
+                declared_temporal_predicates,
+                &statements
+                    .iter()
+                    .filter_map(|(_, _, statement, _)| match statement {
+                        Statement::Rule(rule) => Some(rule.clone()),
+                        Statement::Fact(_) => None,
+                    })
+                    .collect::<Vec<_>>(),
+            );
+
+            for (start, annotation, statement, validity) in statements {
+                let statement = match (statement, validity) {
+                    _ if semiring.is_some() && !temporal_predicates.is_empty() => {
+                        Err(ParseError::TemporalPredicatesWithSemiring)
+                    }
+                    (Statement::Fact(fact), Some(validity)) => {
+                        Ok(Statement::Fact(validity.annotate_fact(fact)))
+                    }
+                    (Statement::Fact(Fact(atom)), None)
+                        if temporal_predicates.contains(&atom.predicate()) =>
+                    {
+                        Err(ParseError::MissingValidityInterval(atom.predicate().name()))
+                    }
+                    (Statement::Rule(rule), _) if !temporal_predicates.is_empty() => {
+                        ValidityInterval::annotate_rule(&rule, &temporal_predicates)
+                            .map(Statement::Rule)
+                    }
+                    (statement, _) => Ok(statement),
+                }
+                .map_err(|e| Err::Failure(e.at(start)))?;
+
+                let statement = match (semiring, statement) {
+                    (None, _) if annotation.is_some() => Err(ParseError::AnnotationWithoutSemiring),
+                    (None, statement) => Ok(statement),
+                    (Some(semiring), Statement::Fact(fact)) => semiring
+                        .annotate_fact(fact, annotation)
+                        .map(Statement::Fact),
+                    (Some(semiring), Statement::Rule(rule)) => semiring
+                        .annotate_rule(&rule, annotation)
+                        .map(Statement::Rule),
+                }
+                .map_err(|e| Err::Failure(e.at(start)))?;
+
+                match statement {
+                    Statement::Fact(value) => facts.push(value),
+                    Statement::Rule(value) => rules.push(value),
+                }
+            }
+
+            let mut sources = self.sources.borrow().clone();
+            let mut predicate_declarations = self.predicate_declarations.borrow().clone();
+
+            if let Some(semiring) = semiring {
+                // Data sources provide unannotated tuples,
+                // which are annotated by an additional rule
+                let mut annotated_sources = HashSet::new();
+                for source in &mut sources {
+                    if annotated_sources.insert(source.predicate.clone()) {
+                        rules.push(
+                            semiring.source_rule(&source.predicate, source.input_types().arity()),
+                        );
+                    }
+                    source.predicate = Semiring::source_predicate(&source.predicate);
+                }
+
+                for types in predicate_declarations.values_mut() {
+                    types.push(PrimitiveType::Float64);
+                }
+            }
+
+            Ok((
+                remainder,
+                Program::new(
+                    base,
+                    prefixes,
+                    sources,
+                    rules,
+                    facts,
+                    predicate_declarations,
+                    output_predicates.into(),
+                )
+                .with_predicate_docs(predicate_docs)
+                .with_semiring(semiring)
+                .with_temporal_predicates(temporal_predicates)
+                .with_predicate_limits(predicate_limits),
+            ))
+        })
+    }
+
+    /// Return the declared base, if set, or None.
+    #[must_use]
+    pub fn base(&self) -> Option<&'a str> {
+        *self.base.borrow()
+    }
+
+    /// Try to expand an IRI into an absolute IRI.
+    #[must_use]
+    pub fn absolutize_iri(&self, iri: Span) -> String {
+        if iri::is_absolute(iri) {
+            iri.to_string()
+        } else {
+            format!("{}{iri}", self.base().unwrap_or_default())
+        }
+    }
+
+    /// Try to abbreviate an IRI given declared prefixes and base.
+    #[must_use]
+    pub fn unresolve_absolute_iri(iri: Span) -> String {
+        if iri::is_relative(iri) {
+            iri.to_string()
+        } else {
+            todo!()
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::assert_matches;
+
+    use test_log::test;
+
+    use nemo_physical::datatypes::Double;
+
+    use super::*;
+    use crate::io::{formats::RdfFormat, InterpolationError};
+
+    macro_rules! assert_parse {
+        ($parser:expr, $left:expr, $right:expr $(,) ?) => {
+            assert_eq!(
+                all_input_consumed($parser)($left).expect(
+                    format!("failed to parse `{:?}`\nexpected `{:?}`", $left, $right).as_str()
+                ),
+                $right
+            );
+        };
+    }
+
+    macro_rules! assert_fails {
+        ($parser:expr, $left:expr, $right:pat $(,) ?) => {
+            assert_matches!(all_input_consumed($parser)($left), Err($right));
+        };
+    }
+
+    macro_rules! assert_parse_error {
+        ($parser:expr, $left:expr, $right:pat $(,) ?) => {
+            assert_fails!($parser, $left, LocatedParseError { source: $right, .. });
+        };
+    }
+
+    macro_rules! assert_expected_token {
+        ($parser:expr, $left:expr, $right:expr $(,) ?) => {
+            let _token = String::from($right);
+            assert_parse_error!($parser, $left, ParseError::ExpectedToken(_token),);
+        };
+    }
+
+    #[test]
+    fn base_directive() {
+        let base = "http://example.org/foo";
+        let input = format!("@base <{base}> .");
+        let parser = RuleParser::new();
+        let b = Identifier(base.to_string());
+        assert!(parser.base().is_none());
+        assert_parse!(parser.parse_base(), input.as_str(), b);
+        assert_eq!(parser.base(), Some(base));
+    }
+
+    #[test]
+    fn prefix_directive() {
+        let prefix = unsafe { Span::new_from_raw_offset(8, 1, "foo", ()) };
+        let iri = "http://example.org/foo";
+        let input = format!("@prefix {prefix}: <{iri}> .");
+        let parser = RuleParser::new();
+        assert!(resolve_prefix(&parser.prefixes.borrow(), &prefix).is_err());
+        assert_parse!(parser.parse_prefix(), input.as_str(), prefix);
+        assert_eq!(
+            resolve_prefix(&parser.prefixes.borrow(), &prefix).map_err(|_| ()),
+            Ok(iri)
+        );
+    }
+
+    #[test]
+    fn source() {
+        let parser = RuleParser::new();
+        let file = "drinks.csv";
+        let predicate_name = "drink";
+        let predicate = Identifier(predicate_name.to_string());
+        let default_source = DataSourceDeclaration::new(
+            predicate.clone(),
+            NativeDataSource::DsvFile(DsvFile::csv_file(file, TupleConstraint::from_arity(1))),
+        );
+        let any_and_int_source = DataSourceDeclaration::new(
+            predicate.clone(),
+            NativeDataSource::DsvFile(DsvFile::csv_file(
+                file,
+                [PrimitiveType::Any, PrimitiveType::Integer]
+                    .into_iter()
+                    .collect(),
+            )),
+        );
+
+        let single_string_source = DataSourceDeclaration::new(
+            predicate,
+            NativeDataSource::DsvFile(DsvFile::csv_file(
+                file,
+                [PrimitiveType::String].into_iter().collect(),
+            )),
+        );
+
+        // rulewerk accepts all of these variants
+        let input = format!(r#"@source {predicate_name}[1]: load-csv("{file}") ."#);
+        assert_parse!(parser.parse_source(), &input, default_source);
+        let input = format!(r#"@source {predicate_name}[1] : load-csv("{file}") ."#);
+        assert_parse!(parser.parse_source(), &input, default_source);
+        let input = format!(r#"@source {predicate_name}[1] : load-csv ( "{file}" ) ."#);
+        assert_parse!(parser.parse_source(), &input, default_source);
+        let input = format!(r#"@source {predicate_name} [1] : load-csv ( "{file}" ) ."#);
+        assert_parse!(parser.parse_source(), &input, default_source);
+        let input = format!(r#"@source {predicate_name}[string]: load-csv ( "{file}" ) ."#);
+        assert_parse!(parser.parse_source(), &input, single_string_source);
+        let input = format!(r#"@source {predicate_name} [string] : load-csv ( "{file}" ) ."#);
+        assert_parse!(parser.parse_source(), &input, single_string_source);
+        let input = format!(r#"@source {predicate_name}[any, integer]: load-csv ( "{file}" ) ."#);
+        assert_parse!(parser.parse_source(), &input, any_and_int_source);
+        let input =
+            format!(r#"@source {predicate_name} [any  ,  integer] : load-csv ( "{file}" ) ."#);
+        assert_parse!(parser.parse_source(), &input, any_and_int_source);
+
+        let external_source = DataSourceDeclaration::new(
+            Identifier(predicate_name.to_string()),
+            NativeDataSource::External(ExternalSource::new(
+                "graph",
+                [PrimitiveType::Any, PrimitiveType::Integer]
+                    .into_iter()
+                    .collect(),
+            )),
+        );
+        let input = format!(r#"@source {predicate_name}[any, integer]: external("graph") ."#);
+        assert_parse!(parser.parse_source(), &input, external_source);
+
+        let custom_format_source = |format: Option<&str>| {
+            DataSourceDeclaration::new(
+                Identifier(predicate_name.to_string()),
+                NativeDataSource::CustomFormat(CustomFormatFile::new(
+                    "edges.json",
+                    format.map(String::from),
+                    TupleConstraint::from_arity(2),
+                )),
+            )
+        };
+        let input = format!(r#"@source {predicate_name}[2]: load("edges.json") ."#);
+        assert_parse!(parser.parse_source(), &input, custom_format_source(None));
+        let input =
+            format!(r#"@source {predicate_name}[2]: load("edges.json", format = "jsonl") ."#);
+        assert_parse!(
+            parser.parse_source(),
+            &input,
+            custom_format_source(Some("jsonl"))
+        );
+
+        let bag_source = DataSourceDeclaration::new_bag(
+            Identifier(predicate_name.to_string()),
+            NativeDataSource::DsvFile(DsvFile::csv_file(
+                file,
+                [PrimitiveType::Any].into_iter().collect(),
+            )),
+        );
+        let input = format!(r#"@source {predicate_name}[any, integer]: bag(load-csv("{file}")) ."#);
+        assert_parse!(parser.parse_source(), &input, bag_source);
+        assert_eq!(bag_source.input_types().arity(), 2);
+        let input = format!(r#"@source {predicate_name}[1]: bag(load-csv("{file}")) ."#);
+        let error = all_input_consumed(parser.parse_source())(&input).unwrap_err();
+        assert_matches!(
+            error.context[0].source,
+            ParseError::BagSourceInvalidArity(_)
+        );
+
+        let encoded_source = DataSourceDeclaration::new(
+            Identifier(predicate_name.to_string()),
+            NativeDataSource::DsvFile(
+                DsvFile::tsv_file(file, TupleConstraint::from_arity(1))
+                    .with_encoding(Encoding::Windows1252),
+            ),
+        );
+        let input =
+            format!(r#"@source {predicate_name}[1]: load-tsv("{file}", encoding = "cp1252") ."#);
+        assert_parse!(parser.parse_source(), &input, encoded_source);
+        let input =
+            format!(r#"@source {predicate_name}[1]: load-csv("{file}", encoding="ebcdic") ."#);
+        let error = all_input_consumed(parser.parse_source())(&input).unwrap_err();
+        assert_matches!(error.context[0].source, ParseError::UnknownEncoding(_));
+
+        let xlsx_source = DataSourceDeclaration::new(
+            Identifier(predicate_name.to_string()),
+            NativeDataSource::XlsxFile(XlsxFile::new(
+                "cities.xlsx",
+                Some("Cities".to_string()),
+                Some("B2:D10".parse().unwrap()),
+                TupleConstraint::from_arity(2),
+            )),
+        );
+        let input = format!(
+            r#"@source {predicate_name}[2]: load-xlsx("cities.xlsx", sheet="Cities", range="B2:D10") ."#
+        );
+        assert_parse!(parser.parse_source(), &input, xlsx_source);
+        let input = format!(r#"@source {predicate_name}[2]: load-xlsx("cities.xlsx") ."#);
+        assert_parse!(
+            parser.parse_source(),
+            &input,
+            DataSourceDeclaration::new(
+                Identifier(predicate_name.to_string()),
+                NativeDataSource::XlsxFile(XlsxFile::new(
+                    "cities.xlsx",
+                    None,
+                    None,
+                    TupleConstraint::from_arity(2),
+                )),
+            )
+        );
+        let input =
+            format!(r#"@source {predicate_name}[2]: load-xlsx("cities.xlsx", range="2:B") ."#);
+        let error = all_input_consumed(parser.parse_source())(&input).unwrap_err();
+        assert_matches!(error.context[0].source, ParseError::InvalidCellRange(_));
+        let input =
+            format!(r#"@source {predicate_name}[4]: load-xlsx("cities.xlsx", range="B2:D10") ."#);
+        let error = all_input_consumed(parser.parse_source())(&input).unwrap_err();
+        assert_matches!(
+            error.context[0].source,
+            ParseError::XlsxSourceInvalidArity(_, 4, 3)
+        );
+
+        let rdf_source = DataSourceDeclaration::new(
+            Identifier(predicate_name.to_string()),
+            NativeDataSource::RdfFile(
+                RdfFile::new("https://example.org/data", None).with_format(RdfFormat::Turtle),
+            ),
+        );
+        let input = format!(
+            r#"@source {predicate_name}[3]: load-rdf("https://example.org/data", format="turtle") ."#
+        );
+        assert_parse!(parser.parse_source(), &input, rdf_source);
+        let input =
+            format!(r#"@source {predicate_name}[3]: load-rdf("data.ttl", format="json") ."#);
+        let error = all_input_consumed(parser.parse_source())(&input).unwrap_err();
+        assert_matches!(error.context[0].source, ParseError::UnknownRdfFormat(_));
+    }
+
+    #[test]
+    fn fact() {
+        let parser = RuleParser::new();
+        let predicate = "p";
+        let value = "foo";
+        let datatype = "bar";
+        let p = Identifier(predicate.to_string());
+        let v = value.to_string();
+        let t = datatype.to_string();
+        let fact = format!(r#"{predicate}("{value}"^^<{datatype}>) ."#);
+
+        let expected_fact = Fact(Atom::new(
+            p,
+            vec![TermTree::leaf(Term::RdfLiteral(
+                RdfLiteral::DatatypeValue {
+                    value: v,
+                    datatype: t,
+                },
+            ))],
+        ));
+
+        assert_parse!(parser.parse_fact(), &fact, expected_fact,);
+    }
+
+    #[test]
+    fn fact_namespaced() {
+        let parser = RuleParser::new();
+        let predicate = "p";
+        let name = "foo";
+        let prefix = unsafe { Span::new_from_raw_offset(8, 1, "eg", ()) };
+        let iri = "http://example.org/foo";
+        let prefix_declaration = format!("@prefix {prefix}: <{iri}> .");
+        let p = Identifier(predicate.to_string());
+        let pn = format!("{prefix}:{name}");
+        let v = Identifier(format!("{iri}{name}"));
+        let fact = format!(r#"{predicate}({pn}) ."#);
+
+        assert_parse!(parser.parse_prefix(), &prefix_declaration, prefix);
+
+        let expected_fact = Fact(Atom::new(p, vec![TermTree::leaf(Term::Constant(v))]));
+
+        assert_parse!(parser.parse_fact(), &fact, expected_fact,);
+    }
+
+    #[test]
+    fn fact_bnode() {
+        let parser = RuleParser::new();
+        let predicate = "p";
+        let name = "foo";
+        let p = Identifier(predicate.to_string());
+        let pn = format!("_:{name}");
+        let fact = format!(r#"{predicate}({pn}) ."#);
+        let v = Identifier(pn);
+
+        let expected_fact = Fact(Atom::new(p, vec![TermTree::leaf(Term::Constant(v))]));
+
+        assert_parse!(parser.parse_fact(), &fact, expected_fact,);
+    }
+
+    #[test]
+    fn fact_numbers() {
+        let parser = RuleParser::new();
+        let predicate = "p";
+        let p = Identifier(predicate.to_string());
+        let int = 23_i64;
+        let dbl = Double::new(42.0).expect("is not NaN");
+        let dec = 13.37;
+        let fact = format!(r#"{predicate}({int}, {dbl:.1}E0, {dec:.2}) ."#);
+
+        let expected_fact = Fact(Atom::new(
+            p,
+            vec![
+                TermTree::leaf(Term::NumericLiteral(NumericLiteral::Integer(int))),
+                TermTree::leaf(Term::NumericLiteral(NumericLiteral::Double(dbl))),
+                TermTree::leaf(Term::NumericLiteral(NumericLiteral::Decimal(13, 37))),
+            ],
+        ));
+
+        assert_parse!(parser.parse_fact(), &fact, expected_fact,);
+    }
+
+    #[test]
+    fn fact_rdf_literal_xsd_string() {
+        let parser = RuleParser::new();
+
+        let prefix = unsafe { Span::new_from_raw_offset(8, 1, "xsd", ()) };
+        let iri = "http://www.w3.org/2001/XMLSchema#";
+        let prefix_declaration = format!("@prefix {prefix}: <{iri}> .");
+
+        assert_parse!(parser.parse_prefix(), &prefix_declaration, prefix);
+
+        let predicate = "p";
+        let value = "my nice string";
+        let datatype = "xsd:string";
+
+        let p = Identifier(predicate.to_string());
+        let v = value.to_string();
+        let fact = format!(r#"{predicate}("{value}"^^{datatype}) ."#);
+
+        let expected_fact = Fact(Atom::new(p, vec![TermTree::leaf(Term::StringLiteral(v))]));
+
+        assert_parse!(parser.parse_fact(), &fact, expected_fact,);
+    }
+
+    #[test]
+    fn fact_string_literal() {
+        let parser = RuleParser::new();
+        let predicate = "p";
+        let value = "my nice string";
+        let p = Identifier(predicate.to_string());
+        let v = value.to_string();
+        let fact = format!(r#"{predicate}("{value}") ."#);
+
+        let expected_fact = Fact(Atom::new(p, vec![TermTree::leaf(Term::StringLiteral(v))]));
+
+        assert_parse!(parser.parse_fact(), &fact, expected_fact,);
+    }
+
+    #[test]
+    fn fact_language_string() {
+        let parser = RuleParser::new();
+        let predicate = "p";
+        let v = "Qapla";
+        let langtag = "tlh";
+        let p = Identifier(predicate.to_string());
+        let value = v.to_string();
+        let fact = format!(r#"{predicate}("{v}"@{langtag}) ."#);
+        let tag = langtag.to_string();
+
+        let expected_fact = Fact(Atom::new(
+            p,
+            vec![TermTree::leaf(Term::RdfLiteral(
+                RdfLiteral::LanguageString { value, tag },
+            ))],
+        ));
+
+        assert_parse!(parser.parse_fact(), &fact, expected_fact);
+    }
+
+    #[test]
+    fn fact_abstract() {
+        let parser = RuleParser::new();
+        let predicate = "p";
+        let name = "a";
+        let p = Identifier(predicate.to_string());
+        let a = Identifier(name.to_string());
+        let fact = format!(r#"{predicate}({name}) ."#);
+
+        let expected_fact = Fact(Atom::new(p, vec![TermTree::leaf(Term::Constant(a))]));
+
+        assert_parse!(parser.parse_fact(), &fact, expected_fact,);
+    }
+
+    #[test]
+    fn fact_comment() {
+        let parser = RuleParser::new();
+        let predicate = "p";
+        let value = "foo";
+        let datatype = "bar";
+        let p = Identifier(predicate.to_string());
+        let v = value.to_string();
+        let t = datatype.to_string();
+        let fact = format!(
+            r#"{predicate}(% comment 1
+                 "{value}"^^<{datatype}> % comment 2
+                 ) % comment 3
+               . % comment 4
+               %"#
+        );
+
+        let expected_fact = Fact(Atom::new(
+            p,
+            vec![TermTree::leaf(Term::RdfLiteral(
+                RdfLiteral::DatatypeValue {
+                    value: v,
+                    datatype: t,
+                },
+            ))],
+        ));
+
+        assert_parse!(parser.parse_fact(), &fact, expected_fact,);
+    }
+
+    #[test]
+    fn filter() {
+        let parser = RuleParser::new();
+        let aa = "A";
+        let a = Identifier(aa.to_string());
+        let bb = "B";
+        let b = Identifier(bb.to_string());
+        let pp = "P";
+        let p = Identifier(pp.to_string());
+        let xx = "X";
+        let x = Identifier(xx.to_string());
+        let yy = "Y";
+        let y = Identifier(yy.to_string());
+        let zz = "Z";
+        let z = Identifier(zz.to_string());
+
+        let rule = format!(
+            "{pp}(?{xx}) :- {aa}(?{xx}, ?{yy}), ?{yy} > ?{xx}, {bb}(?{zz}), ?{xx} = 3, ?{zz} < 7, ?{xx} <= ?{zz}, ?{zz} >= ?{yy} ."
+        );
+
+        let expected_rule = Rule::new(
+            vec![Atom::new(
+                p,
+                vec![TermTree::leaf(Term::Variable(Variable::Universal(
+                    x.clone(),
+                )))],
+            )],
+            vec![
+                Literal::Positive(Atom::new(
+                    a,
+                    vec![
+                        TermTree::leaf(Term::Variable(Variable::Universal(x.clone()))),
+                        TermTree::leaf(Term::Variable(Variable::Universal(y.clone()))),
+                    ],
+                )),
+                Literal::Positive(Atom::new(
+                    b,
+                    vec![TermTree::leaf(Term::Variable(Variable::Universal(
+                        z.clone(),
+                    )))],
+                )),
+            ],
+            vec![
+                Filter::new(
+                    FilterOperation::GreaterThan,
+                    Variable::Universal(y.clone()),
+                    Term::Variable(Variable::Universal(x.clone())),
+                ),
+                Filter::new(
+                    FilterOperation::Equals,
+                    Variable::Universal(x.clone()),
+                    Term::NumericLiteral(NumericLiteral::Integer(3)),
+                ),
+                Filter::new(
+                    FilterOperation::LessThan,
+                    Variable::Universal(z.clone()),
+                    Term::NumericLiteral(NumericLiteral::Integer(7)),
+                ),
+                Filter::new(
+                    FilterOperation::LessThanEq,
+                    Variable::Universal(x),
+                    Term::Variable(Variable::Universal(z.clone())),
+                ),
+                Filter::new(
+                    FilterOperation::GreaterThanEq,
+                    Variable::Universal(z),
+                    Term::Variable(Variable::Universal(y)),
+                ),
+            ],
+        );
+
+        assert_parse!(parser.parse_rule(), &rule, expected_rule,);
     }
 
-    /// Try to abbreviate an IRI given declared prefixes and base.
-    #[must_use]
-    pub fn unresolve_absolute_iri(iri: Span) -> String {
-        if iri::is_relative(iri) {
-            iri.to_string()
-        } else {
-            todo!()
-        }
-    }
-}
+    #[test]
+    fn source_with_variables() {
+        let environment = Environment::default()
+            .with_dotenv("NEMO_PARSER_TEST_DIR=/data\n")
+            .unwrap();
+        let parser = RuleParser::new().with_environment(environment);
+
+        let source = DataSourceDeclaration::new(
+            Identifier("drink".to_string()),
+            NativeDataSource::DsvFile(DsvFile::csv_file(
+                "/data/drinks.csv",
+                TupleConstraint::from_arity(1),
+            )),
+        );
+        assert_parse!(
+            parser.parse_source(),
+            r#"@source drink[1]: load-csv("${NEMO_PARSER_TEST_DIR}/drinks.csv") ."#,
+            source
+        );
 
-#[cfg(test)]
-mod test {
-    use std::assert_matches::assert_matches;
+        let error = all_input_consumed(parser.parse_source())(
+            r#"@source drink[1]: load-csv("${NEMO_PARSER_TEST_UNSET}/drinks.csv") ."#,
+        )
+        .unwrap_err();
+        assert_matches!(
+            error.context[0].source,
+            ParseError::InterpolationError(InterpolationError::UnsetVariable(_))
+        );
+    }
 
-    use test_log::test;
+    #[test]
+    fn souffle_directives() {
+        let program = parse_program(
+            ".decl edge(x: symbol, y: number)\n\
+             .input edge(IO=file, delimiter=\",\")\n\
+             .output path\n\
+             path(?X, ?Y) :- edge(?X, ?Y) .",
+        )
+        .unwrap();
 
-    use nemo_physical::datatypes::Double;
+        let edge = Identifier("edge".to_string());
+        let types = vec![PrimitiveType::Any, PrimitiveType::Integer];
+        assert_eq!(
+            program.parsed_predicate_declarations().get(&edge),
+            Some(&types)
+        );
+        assert_eq!(
+            program.sources().cloned().collect::<Vec<_>>(),
+            vec![DataSourceDeclaration::new(
+                edge,
+                NativeDataSource::DsvFile(DsvFile::csv_file(
+                    "edge.facts",
+                    types.into_iter().collect()
+                ))
+            )]
+        );
+        assert_eq!(
+            program.output_predicates().collect::<Vec<_>>(),
+            vec![Identifier("path".to_string())]
+        );
 
-    use super::*;
+        let parser = RuleParser::new();
+        let error = all_input_consumed(parser.parse_program())(".input edge").unwrap_err();
+        assert_matches!(
+            error.context[0].source,
+            ParseError::UndeclaredSoufflePredicate(_)
+        );
 
-    macro_rules! assert_parse {
-        ($parser:expr, $left:expr, $right:expr $(,) ?) => {
-            assert_eq!(
-                all_input_consumed($parser)($left).expect(
-                    format!("failed to parse `{:?}`\nexpected `{:?}`", $left, $right).as_str()
-                ),
-                $right
-            );
-        };
+        let parser = RuleParser::new();
+        let error = all_input_consumed(parser.parse_program())(
+            ".decl edge(x: symbol)\n.input edge(IO=sqlite)",
+        )
+        .unwrap_err();
+        assert_matches!(error.context[0].source, ParseError::UnsupportedSouffleIo(_));
     }
 
-    macro_rules! assert_fails {
-        ($parser:expr, $left:expr, $right:pat $(,) ?) => {
-            assert_matches!(all_input_consumed($parser)($left), Err($right));
-        };
-    }
+    #[test]
+    #[allow(clippy::redundant_clone)]
+    fn parse_output() {
+        let parser = RuleParser::new();
 
-    macro_rules! assert_parse_error {
-        ($parser:expr, $left:expr, $right:pat $(,) ?) => {
-            assert_fails!($parser, $left, LocatedParseError { source: $right, .. });
-        };
-    }
+        let j2 = Identifier("J2".to_string());
 
-    macro_rules! assert_expected_token {
-        ($parser:expr, $left:expr, $right:expr $(,) ?) => {
-            let _token = String::from($right);
-            assert_parse_error!($parser, $left, ParseError::ExpectedToken(_token),);
-        };
+        assert_parse!(
+            parser.parse_output(),
+            "@output J2 .",
+            QualifiedPredicateName::new(j2.clone())
+        );
+        assert_parse!(
+            parser.parse_output(),
+            "@output J2[3] .",
+            QualifiedPredicateName::with_constraint(
+                j2.clone(),
+                TypeConstraint::Tuple(TupleConstraint::from_arity(3))
+            )
+        );
     }
 
     #[test]
-    fn base_directive() {
-        let base = "http://example.org/foo";
-        let input = format!("@base <{base}> .");
+    fn parse_errors() {
         let parser = RuleParser::new();
-        let b = Identifier(base.to_string());
-        assert!(parser.base().is_none());
-        assert_parse!(parser.parse_base(), input.as_str(), b);
-        assert_eq!(parser.base(), Some(base));
-    }
 
-    #[test]
-    fn prefix_directive() {
-        let prefix = unsafe { Span::new_from_raw_offset(8, 1, "foo", ()) };
-        let iri = "http://example.org/foo";
-        let input = format!("@prefix {prefix}: <{iri}> .");
-        let parser = RuleParser::new();
-        assert!(resolve_prefix(&parser.prefixes.borrow(), &prefix).is_err());
-        assert_parse!(parser.parse_prefix(), input.as_str(), prefix);
-        assert_eq!(
-            resolve_prefix(&parser.prefixes.borrow(), &prefix).map_err(|_| ()),
-            Ok(iri)
+        assert_expected_token!(parser.parse_dot(), "", ".");
+        assert_expected_token!(parser.parse_dot(), ":-", ".");
+        assert_expected_token!(parser.parse_comma(), "", ",");
+        assert_expected_token!(parser.parse_comma(), ":-", ",");
+        assert_expected_token!(parser.parse_not(), "", "~");
+        assert_expected_token!(parser.parse_not(), ":-", "~");
+        assert_expected_token!(parser.parse_arrow(), "", ":-");
+        assert_expected_token!(parser.parse_arrow(), "-:", ":-");
+        assert_expected_token!(parser.parse_open_parenthesis(), "", "(");
+        assert_expected_token!(parser.parse_open_parenthesis(), "-:", "(");
+        assert_expected_token!(parser.parse_close_parenthesis(), "", ")");
+        assert_expected_token!(parser.parse_close_parenthesis(), "-:", ")");
+
+        assert_parse_error!(
+            parser.parse_base(),
+            "@base <example.org .",
+            ParseError::ExpectedBaseDeclaration
+        );
+
+        assert_parse_error!(
+            parser.parse_type_name(),
+            "https://example.org/non-existant-type-name",
+            ParseError::ExpectedLogicalTypeName
+        );
+
+        assert_parse_error!(parser.parse_variable(), "!23", ParseError::ExpectedVariable);
+        assert_parse_error!(parser.parse_variable(), "?23", ParseError::ExpectedVariable);
+        assert_parse_error!(
+            parser.parse_predicate_declaration(),
+            "@declare p(InvalidTypeName) .",
+            ParseError::ExpectedPredicateDeclaration
+        );
+        assert_parse_error!(
+            parser.parse_program(),
+            "@declare p(InvalidTypeName) .",
+            ParseError::ExpectedPredicateDeclaration
+        );
+
+        assert_parse_error!(
+            parser.parse_rule(),
+            r"a(?X, !V) :- b23__#?\(?X, ?Y) .",
+            ParseError::ExpectedRule,
         );
     }
 
     #[test]
-    fn source() {
+    fn parse_arithmetic_expressions() {
         let parser = RuleParser::new();
-        let file = "drinks.csv";
-        let predicate_name = "drink";
-        let predicate = Identifier(predicate_name.to_string());
-        let default_source = DataSourceDeclaration::new(
-            predicate.clone(),
-            NativeDataSource::DsvFile(DsvFile::csv_file(file, TupleConstraint::from_arity(1))),
-        );
-        let any_and_int_source = DataSourceDeclaration::new(
-            predicate.clone(),
-            NativeDataSource::DsvFile(DsvFile::csv_file(
-                file,
-                [PrimitiveType::Any, PrimitiveType::Integer]
-                    .into_iter()
-                    .collect(),
-            )),
+
+        let twenty_three = TermTree::leaf(Term::NumericLiteral(NumericLiteral::Integer(23)));
+        let fourty_two = TermTree::leaf(Term::NumericLiteral(NumericLiteral::Integer(42)));
+        let twenty_three_times_fourty_two = TermTree::tree(
+            TermOperation::Multiplication,
+            vec![twenty_three.clone(), fourty_two],
         );
 
-        let single_string_source = DataSourceDeclaration::new(
-            predicate,
-            NativeDataSource::DsvFile(DsvFile::csv_file(
-                file,
-                [PrimitiveType::String].into_iter().collect(),
-            )),
+        assert_parse_error!(
+            parser.parse_arithmetic_factor(),
+            "",
+            ParseError::ExpectedArithmeticFactor
+        );
+        assert_parse_error!(
+            parser.parse_parenthesised_term_tree(),
+            "",
+            ParseError::ExpectedParenthesisedTermTree
+        );
+        assert_parse_error!(
+            parser.parse_arithmetic_product(),
+            "",
+            ParseError::ExpectedArithmeticProduct
+        );
+        assert_parse_error!(
+            parser.parse_arithmetic_expression(),
+            "",
+            ParseError::ExpectedArithmeticExpression
+        );
+        assert_parse!(parser.parse_arithmetic_factor(), "23", twenty_three);
+        assert_parse!(parser.parse_arithmetic_expression(), "23", twenty_three);
+        assert_parse!(
+            parser.parse_arithmetic_product(),
+            "23 * 42",
+            twenty_three_times_fourty_two
+        );
+        assert_parse!(
+            parser.parse_arithmetic_expression(),
+            "23 * 42",
+            twenty_three_times_fourty_two
+        );
+        assert_parse!(
+            parser.parse_arithmetic_expression(),
+            "23 + 23 * 42 + 42 - (23 * 42)",
+            TermTree::tree(
+                TermOperation::Subtraction,
+                vec![
+                    TermTree::tree(
+                        TermOperation::Addition,
+                        vec![
+                            TermTree::tree(
+                                TermOperation::Addition,
+                                vec![
+                                    TermTree::leaf(Term::NumericLiteral(NumericLiteral::Integer(
+                                        23
+                                    ))),
+                                    TermTree::tree(
+                                        TermOperation::Multiplication,
+                                        vec![
+                                            TermTree::leaf(Term::NumericLiteral(
+                                                NumericLiteral::Integer(23)
+                                            )),
+                                            TermTree::leaf(Term::NumericLiteral(
+                                                NumericLiteral::Integer(42)
+                                            ))
+                                        ],
+                                    )
+                                ]
+                            ),
+                            TermTree::leaf(Term::NumericLiteral(NumericLiteral::Integer(42)))
+                        ]
+                    ),
+                    TermTree::tree(
+                        TermOperation::Multiplication,
+                        vec![
+                            TermTree::leaf(Term::NumericLiteral(NumericLiteral::Integer(23))),
+                            TermTree::leaf(Term::NumericLiteral(NumericLiteral::Integer(42)))
+                        ],
+                    )
+                ]
+            )
         );
-
-        // rulewerk accepts all of these variants
-        let input = format!(r#"@source {predicate_name}[1]: load-csv("{file}") ."#);
-        assert_parse!(parser.parse_source(), &input, default_source);
-        let input = format!(r#"@source {predicate_name}[1] : load-csv("{file}") ."#);
-        assert_parse!(parser.parse_source(), &input, default_source);
-        let input = format!(r#"@source {predicate_name}[1] : load-csv ( "{file}" ) ."#);
-        assert_parse!(parser.parse_source(), &input, default_source);
-        let input = format!(r#"@source {predicate_name} [1] : load-csv ( "{file}" ) ."#);
-        assert_parse!(parser.parse_source(), &input, default_source);
-        let input = format!(r#"@source {predicate_name}[string]: load-csv ( "{file}" ) ."#);
-        assert_parse!(parser.parse_source(), &input, single_string_source);
-        let input = format!(r#"@source {predicate_name} [string] : load-csv ( "{file}" ) ."#);
-        assert_parse!(parser.parse_source(), &input, single_string_source);
-        let input = format!(r#"@source {predicate_name}[any, integer]: load-csv ( "{file}" ) ."#);
-        assert_parse!(parser.parse_source(), &input, any_and_int_source);
-        let input =
-            format!(r#"@source {predicate_name} [any  ,  integer] : load-csv ( "{file}" ) ."#);
-        assert_parse!(parser.parse_source(), &input, any_and_int_source);
     }
 
     #[test]
-    fn fact() {
+    fn program_statement_order() {
+        assert_matches!(
+            parse_program(
+                r#"@output s .
+                   s(?s, ?p, ?o) :- t(?s, ?p, ?o) .
+                   @source t[3]: load-rdf("triples.nt") .
+                  "#
+            ),
+            Ok(_)
+        );
+
         let parser = RuleParser::new();
-        let predicate = "p";
-        let value = "foo";
-        let datatype = "bar";
-        let p = Identifier(predicate.to_string());
-        let v = value.to_string();
-        let t = datatype.to_string();
-        let fact = format!(r#"{predicate}("{value}"^^<{datatype}>) ."#);
+        assert_parse_error!(
+            parser.parse_program(),
+            "@base <foo> . @base <bar> .",
+            ParseError::LateBaseDeclaration
+        );
 
-        let expected_fact = Fact(Atom::new(
-            p,
-            vec![TermTree::leaf(Term::RdfLiteral(
-                RdfLiteral::DatatypeValue {
-                    value: v,
-                    datatype: t,
-                },
-            ))],
-        ));
+        assert_parse_error!(
+            parser.parse_program(),
+            "@prefix f: <foo> . @base <bar> .",
+            ParseError::LateBaseDeclaration
+        );
 
-        assert_parse!(parser.parse_fact(), &fact, expected_fact,);
+        assert_parse_error!(
+            parser.parse_program(),
+            "@output p . @base <bar> .",
+            ParseError::LateBaseDeclaration
+        );
+
+        assert_parse_error!(
+            parser.parse_program(),
+            "@output p . @prefix g: <foo> .",
+            ParseError::LatePrefixDeclaration
+        );
     }
 
     #[test]
-    fn fact_namespaced() {
+    fn parse_function_terms() {
         let parser = RuleParser::new();
-        let predicate = "p";
-        let name = "foo";
-        let prefix = unsafe { Span::new_from_raw_offset(8, 1, "eg", ()) };
-        let iri = "http://example.org/foo";
-        let prefix_declaration = format!("@prefix {prefix}: <{iri}> .");
-        let p = Identifier(predicate.to_string());
-        let pn = format!("{prefix}:{name}");
-        let v = Identifier(format!("{iri}{name}"));
-        let fact = format!(r#"{predicate}({pn}) ."#);
 
-        assert_parse!(parser.parse_prefix(), &prefix_declaration, prefix);
+        let twenty_three = TermTree::leaf(Term::NumericLiteral(NumericLiteral::Integer(23)));
+        let fourty_two = TermTree::leaf(Term::NumericLiteral(NumericLiteral::Integer(42)));
+        let twenty_three_times_fourty_two = TermTree::tree(
+            TermOperation::Multiplication,
+            vec![twenty_three.clone(), fourty_two.clone()],
+        );
 
-        let expected_fact = Fact(Atom::new(p, vec![TermTree::leaf(Term::Constant(v))]));
+        assert_parse_error!(
+            parser.parse_function_term(),
+            "",
+            ParseError::ExpectedFunctionTerm
+        );
 
-        assert_parse!(parser.parse_fact(), &fact, expected_fact,);
-    }
+        let nullary_function = TermTree::tree(
+            TermOperation::Function(Identifier(String::from("nullary_function"))),
+            vec![],
+        );
+        assert_parse!(
+            parser.parse_function_term(),
+            "nullary_function()",
+            nullary_function
+        );
+        assert_parse!(
+            parser.parse_function_term(),
+            "nullary_function(   )",
+            nullary_function
+        );
+        assert_parse_error!(
+            parser.parse_function_term(),
+            "nullary_function(  () )",
+            ParseError::ExpectedFunctionTerm
+        );
 
-    #[test]
-    fn fact_bnode() {
-        let parser = RuleParser::new();
-        let predicate = "p";
-        let name = "foo";
-        let p = Identifier(predicate.to_string());
-        let pn = format!("_:{name}");
-        let fact = format!(r#"{predicate}({pn}) ."#);
-        let v = Identifier(pn);
+        let unary_function = TermTree::tree(
+            TermOperation::Function(Identifier(String::from("unary_function"))),
+            vec![fourty_two.clone()],
+        );
+        assert_parse!(
+            parser.parse_function_term(),
+            "unary_function(42)",
+            unary_function
+        );
+        assert_parse!(
+            parser.parse_function_term(),
+            "unary_function((42))",
+            unary_function
+        );
+        assert_parse!(
+            parser.parse_function_term(),
+            "unary_function(( (42 )))",
+            unary_function
+        );
 
-        let expected_fact = Fact(Atom::new(p, vec![TermTree::leaf(Term::Constant(v))]));
+        let binary_function = TermTree::tree(
+            TermOperation::Function(Identifier(String::from("binary_function"))),
+            vec![fourty_two.clone(), twenty_three.clone()],
+        );
+        assert_parse!(
+            parser.parse_function_term(),
+            "binary_function(42, 23)",
+            binary_function
+        );
 
-        assert_parse!(parser.parse_fact(), &fact, expected_fact,);
+        let function_with_nested_algebraic_expression = TermTree::tree(
+            TermOperation::Function(Identifier(String::from("function"))),
+            vec![twenty_three_times_fourty_two],
+        );
+        assert_parse!(
+            parser.parse_function_term(),
+            "function( 23 *42)",
+            function_with_nested_algebraic_expression
+        );
+
+        let nested_function = TermTree::tree(
+            TermOperation::Function(Identifier(String::from("nested_function"))),
+            vec![nullary_function.clone()],
+        );
+        assert_parse!(
+            parser.parse_function_term(),
+            "nested_function(nullary_function())",
+            nested_function
+        );
+
+        let triple_nested_function = TermTree::tree(
+            TermOperation::Function(Identifier(String::from("nested_function"))),
+            vec![TermTree::tree(
+                TermOperation::Function(Identifier(String::from("nested_function"))),
+                vec![TermTree::tree(
+                    TermOperation::Function(Identifier(String::from("nested_function"))),
+                    vec![nullary_function.clone()],
+                )],
+            )],
+        );
+        assert_parse!(
+            parser.parse_function_term(),
+            "nested_function(  nested_function(  (nested_function(nullary_function()) )  ))",
+            triple_nested_function
+        );
     }
 
     #[test]
-    fn fact_numbers() {
+    fn parse_term_trees() {
         let parser = RuleParser::new();
-        let predicate = "p";
-        let p = Identifier(predicate.to_string());
-        let int = 23_i64;
-        let dbl = Double::new(42.0).expect("is not NaN");
-        let dec = 13.37;
-        let fact = format!(r#"{predicate}({int}, {dbl:.1}E0, {dec:.2}) ."#);
 
-        let expected_fact = Fact(Atom::new(
-            p,
-            vec![
-                TermTree::leaf(Term::NumericLiteral(NumericLiteral::Integer(int))),
-                TermTree::leaf(Term::NumericLiteral(NumericLiteral::Double(dbl))),
-                TermTree::leaf(Term::NumericLiteral(NumericLiteral::Decimal(13, 37))),
-            ],
-        ));
+        assert_parse_error!(parser.parse_term_tree(), "", ParseError::ExpectedTermTree);
 
-        assert_parse!(parser.parse_fact(), &fact, expected_fact,);
+        assert_parse!(
+            parser.parse_term_tree(),
+            "constant",
+            TermTree::leaf(Term::Constant(Identifier(String::from("constant"))))
+        );
     }
 
     #[test]
-    fn fact_rdf_literal_xsd_string() {
+    fn parse_aggregates() {
         let parser = RuleParser::new();
 
-        let prefix = unsafe { Span::new_from_raw_offset(8, 1, "xsd", ()) };
-        let iri = "http://www.w3.org/2001/XMLSchema#";
-        let prefix_declaration = format!("@prefix {prefix}: <{iri}> .");
-
-        assert_parse!(parser.parse_prefix(), &prefix_declaration, prefix);
-
-        let predicate = "p";
-        let value = "my nice string";
-        let datatype = "xsd:string";
-
-        let p = Identifier(predicate.to_string());
-        let v = value.to_string();
-        let fact = format!(r#"{predicate}("{value}"^^{datatype}) ."#);
+        assert_parse_error!(parser.parse_aggregate(), "", ParseError::ExpectedAggregate);
 
-        let expected_fact = Fact(Atom::new(p, vec![TermTree::leaf(Term::StringLiteral(v))]));
+        assert_parse!(
+            parser.parse_aggregate(),
+            "#min(?VARIABLE)",
+            Term::Aggregate(Aggregate {
+                aggregate_identifier: Identifier(String::from("min")),
+                variable_identifiers: vec![Identifier(String::from("VARIABLE"))]
+            })
+        );
 
-        assert_parse!(parser.parse_fact(), &fact, expected_fact,);
+        assert_parse!(
+            parser.parse_aggregate(),
+            "#test(?VAR1, ?VAR2)",
+            Term::Aggregate(Aggregate {
+                aggregate_identifier: Identifier(String::from("test")),
+                variable_identifiers: vec![
+                    Identifier(String::from("VAR1")),
+                    Identifier(String::from("VAR2"))
+                ]
+            })
+        );
     }
 
     #[test]
-    fn fact_string_literal() {
+    fn parse_negated_conjunctions() {
         let parser = RuleParser::new();
-        let predicate = "p";
-        let value = "my nice string";
-        let p = Identifier(predicate.to_string());
-        let v = value.to_string();
-        let fact = format!(r#"{predicate}("{value}") ."#);
 
-        let expected_fact = Fact(Atom::new(p, vec![TermTree::leaf(Term::StringLiteral(v))]));
+        let rule = all_input_consumed(parser.parse_rule())("p(?X) :- q(?X), ~(r(?X, ?Y), s(?Y)) .")
+            .unwrap();
+        assert_eq!(rule.negated_conjunctions().len(), 1);
+        assert_eq!(rule.negated_conjunctions()[0].len(), 2);
 
-        assert_parse!(parser.parse_fact(), &fact, expected_fact,);
+        let error = all_input_consumed(parser.parse_rule())("p(?X) :- q(?X), ~(r(?Y), s(?Y)) .")
+            .unwrap_err();
+        assert_matches!(
+            error.context.as_slice(),
+            [LocatedParseError {
+                source: ParseError::UnconnectedNegatedConjunction,
+                ..
+            }]
+        );
     }
 
     #[test]
-    fn fact_language_string() {
+    fn parse_disjunctive_rules() {
         let parser = RuleParser::new();
-        let predicate = "p";
-        let v = "Qapla";
-        let langtag = "tlh";
-        let p = Identifier(predicate.to_string());
-        let value = v.to_string();
-        let fact = format!(r#"{predicate}("{v}"@{langtag}) ."#);
-        let tag = langtag.to_string();
-
-        let expected_fact = Fact(Atom::new(
-            p,
-            vec![TermTree::leaf(Term::RdfLiteral(
-                RdfLiteral::LanguageString { value, tag },
-            ))],
-        ));
 
-        assert_parse!(parser.parse_fact(), &fact, expected_fact);
+        let rule = all_input_consumed(parser.parse_rule())("p(?X) :- q(?X) ; r(?X, ?Y), ?Y > 3 .")
+            .unwrap();
+        assert_eq!(rule.alternatives().len(), 1);
+
+        let disjuncts = rule.disjuncts();
+        assert_eq!(disjuncts.len(), 2);
+        assert!(disjuncts.iter().all(|rule| rule.alternatives().is_empty()));
+        assert_eq!(disjuncts[0].body().len(), 1);
+        assert!(disjuncts[0].filters().is_empty());
+        assert_eq!(disjuncts[1].body().len(), 1);
+        assert_eq!(disjuncts[1].filters().len(), 1);
+
+        // Each disjunct needs to bind the variables of the head
+        let error =
+            all_input_consumed(parser.parse_rule())("p(?X) :- q(?X) ; r(?Y) .").unwrap_err();
+        assert_matches!(
+            error.context.as_slice(),
+            [LocatedParseError {
+                source: ParseError::UnsafeHeadVariable { .. },
+                column: 1,
+                ..
+            }]
+        );
     }
 
     #[test]
-    fn fact_abstract() {
+    fn parse_unsafe_head_variables() {
         let parser = RuleParser::new();
-        let predicate = "p";
-        let name = "a";
-        let p = Identifier(predicate.to_string());
-        let a = Identifier(name.to_string());
-        let fact = format!(r#"{predicate}({name}) ."#);
-
-        let expected_fact = Fact(Atom::new(p, vec![TermTree::leaf(Term::Constant(a))]));
 
-        assert_parse!(parser.parse_fact(), &fact, expected_fact,);
+        let error = all_input_consumed(parser.parse_rule())("p(?X, ?Y, ?Z, ?Y) :- q(?X), ~r(?Z) .")
+            .unwrap_err();
+        let [LocatedParseError { source, .. }] = error.context.as_slice() else {
+            panic!("expected a single error as context");
+        };
+        assert_matches!(
+            source,
+            ParseError::UnsafeHeadVariable { variables, suggestion }
+                if variables == "?Y, ?Z" && suggestion == "domain(?Y), domain(?Z)"
+        );
     }
 
     #[test]
-    fn fact_comment() {
+    fn parse_builtin_functions() {
         let parser = RuleParser::new();
-        let predicate = "p";
-        let value = "foo";
-        let datatype = "bar";
-        let p = Identifier(predicate.to_string());
-        let v = value.to_string();
-        let t = datatype.to_string();
-        let fact = format!(
-            r#"{predicate}(% comment 1
-                 "{value}"^^<{datatype}> % comment 2
-                 ) % comment 3
-               . % comment 4
-               %"#
+
+        let rule = all_input_consumed(parser.parse_rule())(
+            "p(?X, HASH(?X) * 2) :- q(?X), HASH(?X) < 10, 0.5 > RAND() .",
+        )
+        .unwrap();
+        assert_eq!(rule.computed_variables().len(), 2);
+        assert_eq!(rule.filters().len(), 2);
+        assert_eq!(
+            rule.computed_variables()[1].1.fixed_type(),
+            Some(PrimitiveType::Float64)
         );
+        assert_eq!(rule.filters()[1].operation, FilterOperation::LessThan);
 
-        let expected_fact = Fact(Atom::new(
-            p,
-            vec![TermTree::leaf(Term::RdfLiteral(
-                RdfLiteral::DatatypeValue {
-                    value: v,
-                    datatype: t,
-                },
-            ))],
-        ));
+        // Other functions may be defined by users of the library
+        let rule = all_input_consumed(parser.parse_rule())("p(?X, HASHES(?X)) :- q(?X) .").unwrap();
+        assert_eq!(
+            rule.head()[0].term_trees()[1].functions()[0].0.name(),
+            "HASHES"
+        );
 
-        assert_parse!(parser.parse_fact(), &fact, expected_fact,);
+        let error = all_input_consumed(parser.parse_rule())("p(?X) :- q(?X), RAND(?X) < 0.5 .")
+            .unwrap_err();
+        assert_matches!(
+            error.context.as_slice(),
+            [LocatedParseError {
+                source: ParseError::InvalidFunctionArity(_, 0, 1),
+                ..
+            }]
+        );
+
+        let error =
+            all_input_consumed(parser.parse_rule())("p(INT(?X + 1)) :- q(?X) .").unwrap_err();
+        assert_matches!(
+            error.context.as_slice(),
+            [LocatedParseError {
+                source: ParseError::InvalidConversionArgument(_),
+                ..
+            }]
+        );
+
+        let error =
+            all_input_consumed(parser.parse_rule())("p(?X) :- q(?X), HASH(?Y) < 5 .").unwrap_err();
+        assert_matches!(
+            error.context.as_slice(),
+            [LocatedParseError {
+                source: ParseError::UnsafeFilterVariable(_),
+                ..
+            }]
+        );
     }
 
     #[test]
-    fn filter() {
+    fn parse_unicode_names() {
         let parser = RuleParser::new();
-        let aa = "A";
-        let a = Identifier(aa.to_string());
-        let bb = "B";
-        let b = Identifier(bb.to_string());
-        let pp = "P";
-        let p = Identifier(pp.to_string());
-        let xx = "X";
-        let x = Identifier(xx.to_string());
-        let yy = "Y";
-        let y = Identifier(yy.to_string());
-        let zz = "Z";
-        let z = Identifier(zz.to_string());
 
-        let rule = format!(
-            "{pp}(?{xx}) :- {aa}(?{xx}, ?{yy}), ?{yy} > ?{xx}, {bb}(?{zz}), ?{xx} = 3, ?{zz} < 7, ?{xx} <= ?{zz}, ?{zz} >= ?{yy} ."
+        let rule = all_input_consumed(parser.parse_rule())(
+            "café(?naïve, ?東京) :- données(?naïve, ?東京) .",
+        )
+        .unwrap();
+        assert_eq!(rule.head()[0].predicate(), Identifier("café".to_string()));
+        assert_eq!(
+            rule.head()[0].terms().cloned().collect::<Vec<_>>(),
+            vec![
+                Term::Variable(Variable::Universal(Identifier("naïve".to_string()))),
+                Term::Variable(Variable::Universal(Identifier("東京".to_string()))),
+            ]
+        );
+        assert_eq!(
+            rule.body()[0].predicate(),
+            Identifier("données".to_string())
         );
 
-        let expected_rule = Rule::new(
-            vec![Atom::new(
-                p,
-                vec![TermTree::leaf(Term::Variable(Variable::Universal(
-                    x.clone(),
-                )))],
-            )],
-            vec![
-                Literal::Positive(Atom::new(
-                    a,
-                    vec![
-                        TermTree::leaf(Term::Variable(Variable::Universal(x.clone()))),
-                        TermTree::leaf(Term::Variable(Variable::Universal(y.clone()))),
-                    ],
-                )),
-                Literal::Positive(Atom::new(
-                    b,
-                    vec![TermTree::leaf(Term::Variable(Variable::Universal(
-                        z.clone(),
-                    )))],
-                )),
-            ],
+        let fact = all_input_consumed(parser.parse_fact())(
+            r"<http://example.org/café>(東京, <http://example.org/\U0001F600>) .",
+        )
+        .unwrap();
+        assert_eq!(
+            fact.0.predicate(),
+            Identifier("http://example.org/café".to_string())
+        );
+        assert_eq!(
+            fact.0.terms().cloned().collect::<Vec<_>>(),
             vec![
-                Filter::new(
-                    FilterOperation::GreaterThan,
-                    Variable::Universal(y.clone()),
-                    Term::Variable(Variable::Universal(x.clone())),
-                ),
-                Filter::new(
-                    FilterOperation::Equals,
-                    Variable::Universal(x.clone()),
-                    Term::NumericLiteral(NumericLiteral::Integer(3)),
-                ),
-                Filter::new(
-                    FilterOperation::LessThan,
-                    Variable::Universal(z.clone()),
-                    Term::NumericLiteral(NumericLiteral::Integer(7)),
-                ),
-                Filter::new(
-                    FilterOperation::LessThanEq,
-                    Variable::Universal(x),
-                    Term::Variable(Variable::Universal(z.clone())),
-                ),
-                Filter::new(
-                    FilterOperation::GreaterThanEq,
-                    Variable::Universal(z),
-                    Term::Variable(Variable::Universal(y)),
-                ),
-            ],
+                Term::Constant(Identifier("東京".to_string())),
+                Term::Constant(Identifier("http://example.org/😀".to_string())),
+            ]
         );
 
-        assert_parse!(parser.parse_rule(), &rule, expected_rule,);
+        // Escape sequences are decoded before the IRI is validated
+        assert!(
+            all_input_consumed(parser.parse_fact())(r"p(<http://example.org/a\u0020b>) .").is_err()
+        );
     }
 
     #[test]
-    #[allow(clippy::redundant_clone)]
-    fn parse_output() {
-        let parser = RuleParser::new();
-
-        let j2 = Identifier("J2".to_string());
+    fn parse_doc_comments() {
+        let program = parse_program(
+            "%% Edges of the graph,\n\
+             %%   given as pairs of nodes.\n\
+             @source edge[2]: load-csv(\"edges.csv\") .\n\
+             %% Colors of the nodes\n\
+             @declare color(any, string) .\n\
+             %% Paths in the graph\n\
+             path(?X, ?Y) :- edge(?X, ?Y) . % plain comment\n\
+             path(?X, ?Z) :- path(?X, ?Y), edge(?Y, ?Z) .\n\
+             %% Separated by a blank line\n\
+             \n\
+             loop(?X) :- path(?X, ?X) . %% Not on a line of its own\n\
+             \x20 %% Indented\n\
+             \x20 colored(?X) :- color(?X, ?C) .\n",
+        )
+        .unwrap();
 
-        assert_parse!(
-            parser.parse_output(),
-            "@output J2 .",
-            QualifiedPredicateName::new(j2.clone())
+        assert_eq!(
+            program.predicate_doc(&Identifier("edge".to_string())),
+            Some("Edges of the graph,\n  given as pairs of nodes.")
         );
-        assert_parse!(
-            parser.parse_output(),
-            "@output J2[3] .",
-            QualifiedPredicateName::with_constraint(
-                j2.clone(),
-                TypeConstraint::Tuple(TupleConstraint::from_arity(3))
-            )
+        assert_eq!(
+            program.predicate_doc(&Identifier("color".to_string())),
+            Some("Colors of the nodes")
+        );
+        assert_eq!(program.predicate_doc(&Identifier("path".to_string())), None);
+        assert_eq!(
+            program
+                .rules()
+                .iter()
+                .map(|rule| rule.doc())
+                .collect::<Vec<_>>(),
+            vec![Some("Paths in the graph"), None, None, Some("Indented")]
         );
     }
 
     #[test]
-    fn parse_errors() {
+    fn parse_string_and_numeric_literals() {
         let parser = RuleParser::new();
+        let string = |value: &str| TermTree::leaf(Term::StringLiteral(value.to_string()));
+        let double = |value: f64| {
+            TermTree::leaf(Term::NumericLiteral(NumericLiteral::Double(
+                Double::new(value).unwrap(),
+            )))
+        };
 
-        assert_expected_token!(parser.parse_dot(), "", ".");
-        assert_expected_token!(parser.parse_dot(), ":-", ".");
-        assert_expected_token!(parser.parse_comma(), "", ",");
-        assert_expected_token!(parser.parse_comma(), ":-", ",");
-        assert_expected_token!(parser.parse_not(), "", "~");
-        assert_expected_token!(parser.parse_not(), ":-", "~");
-        assert_expected_token!(parser.parse_arrow(), "", ":-");
-        assert_expected_token!(parser.parse_arrow(), "-:", ":-");
-        assert_expected_token!(parser.parse_open_parenthesis(), "", "(");
-        assert_expected_token!(parser.parse_open_parenthesis(), "-:", "(");
-        assert_expected_token!(parser.parse_close_parenthesis(), "", ")");
-        assert_expected_token!(parser.parse_close_parenthesis(), "-:", ")");
-
-        assert_parse_error!(
-            parser.parse_base(),
-            "@base <example.org .",
-            ParseError::ExpectedBaseDeclaration
+        let fact = all_input_consumed(parser.parse_fact())(
+            r#"p("""first line
+"quoted" and ""twice"" """, '''it''s''', "  %\t\"é\\") ."#,
+        )
+        .unwrap();
+        assert_eq!(
+            fact.0.term_trees(),
+            &[
+                string("first line\n\"quoted\" and \"\"twice\"\" "),
+                string("it''s"),
+                string("  %\t\"é\\"),
+            ]
         );
 
-        assert_parse_error!(
-            parser.parse_type_name(),
-            "https://example.org/non-existant-type-name",
-            ParseError::ExpectedLogicalTypeName
+        let fact = all_input_consumed(parser.parse_fact())("p(1.5e3, -2E-2, .5e1) .").unwrap();
+        assert_eq!(
+            fact.0.term_trees(),
+            &[double(1500.0), double(-0.02), double(5.0)]
         );
 
-        assert_parse_error!(parser.parse_variable(), "!23", ParseError::ExpectedVariable);
-        assert_parse_error!(parser.parse_variable(), "?23", ParseError::ExpectedVariable);
-        assert_parse_error!(
-            parser.parse_predicate_declaration(),
-            "@declare p(InvalidTypeName) .",
-            ParseError::ExpectedPredicateDeclaration
+        let rule = all_input_consumed(parser.parse_rule())(
+            "q(?X, ?Y) :- p(?X, ?Y), ?X >= 1e-3, ?Y = '''a\nb''' .",
+        )
+        .unwrap();
+        assert_eq!(
+            rule.filters()[0].rhs,
+            Term::NumericLiteral(NumericLiteral::Double(Double::new(0.001).unwrap()))
         );
-        assert_parse_error!(
-            parser.parse_program(),
-            "@declare p(InvalidTypeName) .",
-            ParseError::ExpectedPredicateDeclaration
+        assert_eq!(
+            rule.filters()[1].rhs,
+            Term::StringLiteral("a\nb".to_string())
         );
+    }
 
-        assert_parse_error!(
-            parser.parse_rule(),
-            r"a(?X, !V) :- b23__#?\(?X, ?Y) .",
-            ParseError::ExpectedRule,
+    #[test]
+    fn parse_anonymous_variables() {
+        let parser = RuleParser::new();
+
+        let rule =
+            all_input_consumed(parser.parse_rule())("p(?X) :- q(?X, _, _), r(_:b, _) .").unwrap();
+        let anonymous = rule
+            .body()
+            .iter()
+            .flat_map(|literal| literal.variables())
+            .filter(|variable| variable.is_anonymous())
+            .collect::<Vec<_>>();
+        assert_eq!(anonymous.len(), 3);
+        assert_ne!(anonymous[0], anonymous[1]);
+        assert_ne!(anonymous[1], anonymous[2]);
+        assert_eq!(
+            rule.body()[1].terms()[0],
+            TermTree::leaf(Term::Constant(Identifier("_:b".to_string())))
         );
+
+        for input in ["p(_) :- q(_) .", "p(?X) :- q(?X), ?X > _ ."] {
+            let error = all_input_consumed(parser.parse_rule())(input).unwrap_err();
+            assert_matches!(
+                error.context.as_slice(),
+                [LocatedParseError {
+                    source: ParseError::MisplacedAnonymousVariable,
+                    ..
+                }]
+            );
+        }
     }
 
     #[test]
-    fn parse_arithmetic_expressions() {
+    fn parse_negative_numbers() {
         let parser = RuleParser::new();
+        let number = |value: NumericLiteral| TermTree::leaf(Term::NumericLiteral(value));
+        let double = |value: f64| number(NumericLiteral::Double(Double::new(value).unwrap()));
+        let variable = TermTree::leaf(Term::Variable(Variable::Universal(Identifier(
+            "X".to_string(),
+        ))));
+        let negate = |tree: TermTree| {
+            TermTree::tree(
+                TermOperation::Subtraction,
+                vec![number(NumericLiteral::Integer(0)), tree],
+            )
+        };
 
-        let twenty_three = TermTree::leaf(Term::NumericLiteral(NumericLiteral::Integer(23)));
-        let fourty_two = TermTree::leaf(Term::NumericLiteral(NumericLiteral::Integer(42)));
-        let twenty_three_times_fourty_two = TermTree::tree(
-            TermOperation::Multiplication,
-            vec![twenty_three.clone(), fourty_two],
+        let fact = all_input_consumed(parser.parse_fact())(
+            "p(-5, +3, -0.5, -.5, +.25, 1.05, -2.0, -2.5e3) .",
+        )
+        .unwrap();
+        assert_eq!(
+            fact.0.term_trees(),
+            &[
+                number(NumericLiteral::Integer(-5)),
+                number(NumericLiteral::Integer(3)),
+                double(-0.5),
+                double(-0.5),
+                number(NumericLiteral::Decimal(0, 25)),
+                double(1.05),
+                number(NumericLiteral::Decimal(-2, 0)),
+                double(-2500.0),
+            ]
         );
 
-        assert_parse_error!(
-            parser.parse_arithmetic_factor(),
-            "",
-            ParseError::ExpectedArithmeticFactor
-        );
-        assert_parse_error!(
-            parser.parse_parenthesised_term_tree(),
-            "",
-            ParseError::ExpectedParenthesisedTermTree
-        );
-        assert_parse_error!(
-            parser.parse_arithmetic_product(),
-            "",
-            ParseError::ExpectedArithmeticProduct
-        );
-        assert_parse_error!(
-            parser.parse_arithmetic_expression(),
-            "",
-            ParseError::ExpectedArithmeticExpression
-        );
-        assert_parse!(parser.parse_arithmetic_factor(), "23", twenty_three);
-        assert_parse!(parser.parse_arithmetic_expression(), "23", twenty_three);
-        assert_parse!(
-            parser.parse_arithmetic_product(),
-            "23 * 42",
-            twenty_three_times_fourty_two
-        );
         assert_parse!(
             parser.parse_arithmetic_expression(),
-            "23 * 42",
-            twenty_three_times_fourty_two
+            "-?X",
+            negate(variable.clone())
         );
         assert_parse!(
             parser.parse_arithmetic_expression(),
-            "23 + 23 * 42 + 42 - (23 * 42)",
+            "?X - -5",
             TermTree::tree(
                 TermOperation::Subtraction,
+                vec![variable.clone(), number(NumericLiteral::Integer(-5))],
+            )
+        );
+        assert_parse!(
+            parser.parse_arithmetic_expression(),
+            "2 * -(?X + 1)",
+            TermTree::tree(
+                TermOperation::Multiplication,
                 vec![
-                    TermTree::tree(
+                    number(NumericLiteral::Integer(2)),
+                    negate(TermTree::tree(
                         TermOperation::Addition,
-                        vec![
-                            TermTree::tree(
-                                TermOperation::Addition,
-                                vec![
-                                    TermTree::leaf(Term::NumericLiteral(NumericLiteral::Integer(
-                                        23
-                                    ))),
-                                    TermTree::tree(
-                                        TermOperation::Multiplication,
-                                        vec![
-                                            TermTree::leaf(Term::NumericLiteral(
-                                                NumericLiteral::Integer(23)
-                                            )),
-                                            TermTree::leaf(Term::NumericLiteral(
-                                                NumericLiteral::Integer(42)
-                                            ))
-                                        ],
-                                    )
-                                ]
-                            ),
-                            TermTree::leaf(Term::NumericLiteral(NumericLiteral::Integer(42)))
-                        ]
-                    ),
-                    TermTree::tree(
-                        TermOperation::Multiplication,
-                        vec![
-                            TermTree::leaf(Term::NumericLiteral(NumericLiteral::Integer(23))),
-                            TermTree::leaf(Term::NumericLiteral(NumericLiteral::Integer(42)))
-                        ],
-                    )
-                ]
+                        vec![variable.clone(), number(NumericLiteral::Integer(1))],
+                    )),
+                ],
             )
         );
+
+        let rule = all_input_consumed(parser.parse_rule())("q(?X) :- p(?X), ?X > -5 .").unwrap();
+        assert_eq!(
+            rule.filters()[0].rhs,
+            Term::NumericLiteral(NumericLiteral::Integer(-5))
+        );
     }
 
     #[test]
-    fn program_statement_order() {
-        assert_matches!(
-            parse_program(
-                r#"@output s .
-                   s(?s, ?p, ?o) :- t(?s, ?p, ?o) .
-                   @source t[3]: load-rdf("triples.nt") .
-                  "#
-            ),
-            Ok(_)
+    fn parse_rule_priorities() {
+        let program = parse_program(
+            "q(?X) :- p(?X) .\n\
+             %% Overrides the default\n\
+             @priority 2 r(?X) :- q(?X) .\n\
+             @priority -1\n\
+             s(?X) :- p(?X), ~r(?X) .\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            program
+                .rules()
+                .iter()
+                .map(|rule| (rule.priority(), rule.doc()))
+                .collect::<Vec<_>>(),
+            vec![(0, None), (2, Some("Overrides the default")), (-1, None)]
         );
 
         let parser = RuleParser::new();
         assert_parse_error!(
-            parser.parse_program(),
-            "@base <foo> . @base <bar> .",
-            ParseError::LateBaseDeclaration
+            parser.parse_prioritised_rule(),
+            "@priority high r(?X) :- q(?X) .",
+            ParseError::ExpectedPriorityAnnotation
         );
-
         assert_parse_error!(
-            parser.parse_program(),
-            "@prefix f: <foo> . @base <bar> .",
-            ParseError::LateBaseDeclaration
+            parser.parse_prioritised_rule(),
+            "@priority 2 r(a) .",
+            ParseError::ExpectedPriorityAnnotation
         );
+    }
 
-        assert_parse_error!(
-            parser.parse_program(),
-            "@output p . @base <bar> .",
-            ParseError::LateBaseDeclaration
+    #[test]
+    fn parse_semiring_annotations() {
+        let program = parse_program(
+            "@semiring min-plus .\n\
+             @declare edge(any, any) .\n\
+             @source road[2]: load-csv(\"roads.csv\") .\n\
+             3 :: edge(a, b) .\n\
+             edge(b, c) .\n\
+             path(?X, ?Y) :- edge(?X, ?Y) .\n\
+             ?W :: path(?X, ?Z) :- path(?X, ?Y), weighted(?Y, ?Z, ?W) .\n",
+        )
+        .unwrap();
+
+        assert_eq!(program.semiring(), Some(Semiring::MinPlus));
+        assert_eq!(
+            program
+                .facts()
+                .iter()
+                .map(|Fact(atom)| atom.terms().last().cloned())
+                .collect::<Vec<_>>(),
+            vec![
+                Some(Term::NumericLiteral(NumericLiteral::Integer(3))),
+                Some(Term::NumericLiteral(NumericLiteral::Integer(0))),
+            ]
+        );
+        assert_eq!(
+            program.parsed_predicate_declarations()[&Identifier("edge".to_string())],
+            vec![
+                PrimitiveType::Any,
+                PrimitiveType::Any,
+                PrimitiveType::Float64
+            ]
+        );
+        assert_eq!(
+            program.sources().next().unwrap().predicate,
+            Identifier("ANNOTATION_SOURCE_FOR_PREDICATE_road".to_string())
         );
 
-        assert_parse_error!(
-            parser.parse_program(),
-            "@output p . @prefix g: <foo> .",
-            ParseError::LatePrefixDeclaration
+        // Each body atom binds its annotation, and the head combines them
+        let rule = &program.rules()[1];
+        assert_eq!(rule.head()[0].term_trees().len(), 3);
+        assert_eq!(
+            rule.body()
+                .iter()
+                .map(|literal| literal.terms().len())
+                .collect::<Vec<_>>(),
+            vec![3, 4]
         );
-    }
+        assert_eq!(
+            rule.head()[0].term_trees()[2].operation(),
+            &TermOperation::Addition
+        );
+        assert_eq!(program.rules().len(), 3);
+
+        let program = parse_program(
+            "@semiring max-times .\n\
+             0.5 :: link(a, b) .\n\
+             reach(?X, ?Y) :- link(?X, ?Y) .\n",
+        )
+        .unwrap();
+        assert_eq!(program.semiring(), Some(Semiring::MaxTimes));
+        assert_eq!(program.rules()[0].filters().len(), 1);
 
-    #[test]
-    fn parse_function_terms() {
         let parser = RuleParser::new();
+        let error = all_input_consumed(parser.parse_program())("1 :: p(a) .").unwrap_err();
+        assert_matches!(error.source, ParseError::AnnotationWithoutSemiring);
 
-        let twenty_three = TermTree::leaf(Term::NumericLiteral(NumericLiteral::Integer(23)));
-        let fourty_two = TermTree::leaf(Term::NumericLiteral(NumericLiteral::Integer(42)));
-        let twenty_three_times_fourty_two = TermTree::tree(
-            TermOperation::Multiplication,
-            vec![twenty_three.clone(), fourty_two.clone()],
-        );
+        let parser = RuleParser::new();
+        let error =
+            all_input_consumed(parser.parse_program())("@semiring min-plus .\n?X :: p(a) .")
+                .unwrap_err();
+        assert_matches!(error.source, ParseError::InvalidFactAnnotation);
 
-        assert_parse_error!(
-            parser.parse_function_term(),
-            "",
-            ParseError::ExpectedFunctionTerm
-        );
+        let parser = RuleParser::new();
+        let error = all_input_consumed(parser.parse_program())(
+            "@semiring min-plus .\nq(?X) :- p(?X), ~r(?X) .",
+        )
+        .unwrap_err();
+        assert_matches!(error.source, ParseError::AnnotatedNegation);
 
-        let nullary_function = TermTree::tree(
-            TermOperation::Function(Identifier(String::from("nullary_function"))),
-            vec![],
+        let parser = RuleParser::new();
+        let error =
+            all_input_consumed(parser.parse_program())("p(a) .\n@semiring min-plus .").unwrap_err();
+        assert_matches!(error.source, ParseError::LateSemiringDeclaration);
+
+        let parser = RuleParser::new();
+        let error =
+            all_input_consumed(parser.parse_semiring())("@semiring sum-product .").unwrap_err();
+        assert_matches!(
+            error.context[0].context[0].source,
+            ParseError::UnknownSemiring(_)
         );
-        assert_parse!(
-            parser.parse_function_term(),
-            "nullary_function()",
-            nullary_function
+    }
+
+    #[test]
+    fn parse_temporal_facts() {
+        let program = parse_program(
+            "employed(alice, acme) @[2019-01-01, 2021-07-01T12:00:00Z) .\n\
+             office(acme, dresden) @[2020-01-01, 2023-01-01) .\n\
+             company(acme) .\n\
+             worked_in(?P, ?C) :- employed(?P, ?O), office(?O, ?C), company(?O) .\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            program.temporal_predicates(),
+            &HashSet::from([
+                Identifier("employed".to_string()),
+                Identifier("office".to_string()),
+                Identifier("worked_in".to_string()),
+            ])
         );
-        assert_parse!(
-            parser.parse_function_term(),
-            "nullary_function(   )",
-            nullary_function
+        assert_eq!(
+            program
+                .facts()
+                .iter()
+                .map(|Fact(atom)| atom.terms().count())
+                .collect::<Vec<_>>(),
+            vec![4, 4, 1]
         );
-        assert_parse_error!(
-            parser.parse_function_term(),
-            "nullary_function(  () )",
-            ParseError::ExpectedFunctionTerm
+        assert_eq!(
+            program.facts()[0].0.terms().nth(3),
+            Some(&Term::from(LogicalDateTime::from(1_625_140_800)))
         );
 
-        let unary_function = TermTree::tree(
-            TermOperation::Function(Identifier(String::from("unary_function"))),
-            vec![fourty_two.clone()],
-        );
-        assert_parse!(
-            parser.parse_function_term(),
-            "unary_function(42)",
-            unary_function
+        // Only temporal body atoms bind an interval, which are intersected for the head
+        let rule = &program.rules()[0];
+        assert_eq!(
+            rule.body()
+                .iter()
+                .map(|literal| literal.terms().len())
+                .collect::<Vec<_>>(),
+            vec![4, 4, 1]
         );
-        assert_parse!(
-            parser.parse_function_term(),
-            "unary_function((42))",
-            unary_function
+        assert_eq!(rule.filters().len(), 2);
+        assert_eq!(rule.head()[0].term_trees().len(), 4);
+        assert_eq!(
+            rule.head()[0].term_trees()[2].operation(),
+            &TermOperation::Function(Identifier("MAX".to_string()))
         );
-        assert_parse!(
-            parser.parse_function_term(),
-            "unary_function(( (42 )))",
-            unary_function
+        assert_eq!(
+            rule.head()[0].term_trees()[3].operation(),
+            &TermOperation::Function(Identifier("MIN".to_string()))
         );
 
-        let binary_function = TermTree::tree(
-            TermOperation::Function(Identifier(String::from("binary_function"))),
-            vec![fourty_two.clone(), twenty_three.clone()],
-        );
-        assert_parse!(
-            parser.parse_function_term(),
-            "binary_function(42, 23)",
-            binary_function
-        );
+        let program = parse_program("@temporal p .\nq(?X) :- p(?X) .\n").unwrap();
+        assert_eq!(program.rules()[0].head()[0].term_trees().len(), 3);
 
-        let function_with_nested_algebraic_expression = TermTree::tree(
-            TermOperation::Function(Identifier(String::from("function"))),
-            vec![twenty_three_times_fourty_two],
-        );
-        assert_parse!(
-            parser.parse_function_term(),
-            "function( 23 *42)",
-            function_with_nested_algebraic_expression
+        let parser = RuleParser::new();
+        let error =
+            all_input_consumed(parser.parse_validity_interval())("@[2020-01-01, 2020-01-01)")
+                .unwrap_err();
+        assert_matches!(
+            error.context[0].context[0].source,
+            ParseError::EmptyValidityInterval(_, _)
         );
 
-        let nested_function = TermTree::tree(
-            TermOperation::Function(Identifier(String::from("nested_function"))),
-            vec![nullary_function.clone()],
-        );
-        assert_parse!(
-            parser.parse_function_term(),
-            "nested_function(nullary_function())",
-            nested_function
-        );
+        let parser = RuleParser::new();
+        let error =
+            all_input_consumed(parser.parse_program())("p(a) @[2020-01-01, 2021-01-01) .\np(b) .")
+                .unwrap_err();
+        assert_matches!(error.source, ParseError::MissingValidityInterval(_));
 
-        let triple_nested_function = TermTree::tree(
-            TermOperation::Function(Identifier(String::from("nested_function"))),
-            vec![TermTree::tree(
-                TermOperation::Function(Identifier(String::from("nested_function"))),
-                vec![TermTree::tree(
-                    TermOperation::Function(Identifier(String::from("nested_function"))),
-                    vec![nullary_function.clone()],
-                )],
-            )],
-        );
-        assert_parse!(
-            parser.parse_function_term(),
-            "nested_function(  nested_function(  (nested_function(nullary_function()) )  ))",
-            triple_nested_function
-        );
+        let parser = RuleParser::new();
+        let error = all_input_consumed(parser.parse_program())("@temporal p .\np(?X) :- q(?X) .")
+            .unwrap_err();
+        assert_matches!(error.source, ParseError::UntimedTemporalDerivation(_));
+
+        let parser = RuleParser::new();
+        let error =
+            all_input_consumed(parser.parse_program())("@temporal p .\nr(?X) :- q(?X), ~p(?X) .")
+                .unwrap_err();
+        assert_matches!(error.source, ParseError::TemporalNegation(_));
     }
 
     #[test]
-    fn parse_term_trees() {
+    fn parse_limits() {
+        let program = parse_program(
+            "@prefix ex: <http://example.org/> .\n\
+             @limit path 10_000_000 .\n\
+             @limit ex:edge 5 .\n\
+             path(?X, ?Y) :- ex:edge(?X, ?Y) .\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            program.predicate_limits(),
+            &HashMap::from([
+                (Identifier("path".to_string()), 10_000_000),
+                (Identifier("http://example.org/edge".to_string()), 5),
+            ])
+        );
+
         let parser = RuleParser::new();
+        let error = all_input_consumed(parser.parse_program())("@limit path many .").unwrap_err();
+        assert_matches!(error.source, ParseError::ExpectedLimitDeclaration);
+    }
 
-        assert_parse_error!(parser.parse_term_tree(), "", ParseError::ExpectedTermTree);
+    #[test]
+    fn parse_cidr_filters() {
+        let program =
+            parse_program("internal(?X) :- flow(?X), IN_CIDR(?X, \"10.0.0.0/8\") .\n").unwrap();
+        let rule = &program.rules()[0];
+        let variable = Variable::Universal(Identifier("X".to_string()));
 
-        assert_parse!(
-            parser.parse_term_tree(),
-            "constant",
-            TermTree::leaf(Term::Constant(Identifier(String::from("constant"))))
+        assert_eq!(rule.body().len(), 1);
+        assert_eq!(
+            rule.filters(),
+            &vec![
+                Filter::new(
+                    FilterOperation::GreaterThanEq,
+                    variable.clone(),
+                    Term::StringLiteral("10.0.0.0".to_string())
+                ),
+                Filter::new(
+                    FilterOperation::LessThanEq,
+                    variable,
+                    Term::StringLiteral("10.255.255.255".to_string())
+                ),
+            ]
+        );
+
+        let parser = RuleParser::new();
+        let error =
+            all_input_consumed(parser.parse_cidr_filter())("IN_CIDR(?X, \"10.0.0.0/40\")")
+                .unwrap_err();
+        assert_matches!(
+            error.context[0].context[0].source,
+            ParseError::InvalidCidrNetwork(_)
         );
     }
 
     #[test]
-    fn parse_aggregates() {
+    fn parse_text_filters() {
+        let program = parse_program(
+            "quantum(?P) :- label(?P, ?L), MATCHES_TEXT(?L, \"Quantum comput*\") .\n",
+        )
+        .unwrap();
+        let rule = &program.rules()[0];
+
+        assert_eq!(rule.body().len(), 1);
+        assert_eq!(
+            rule.filters(),
+            &vec![Filter::new(
+                FilterOperation::MatchesText,
+                Variable::Universal(Identifier("L".to_string())),
+                Term::StringLiteral("Quantum comput*".to_string())
+            )]
+        );
+
         let parser = RuleParser::new();
+        let error =
+            all_input_consumed(parser.parse_text_filter())("MATCHES_TEXT(?L, query)").unwrap_err();
+        assert_matches!(error.source, ParseError::ExpectedTextFilter);
+    }
 
-        assert_parse_error!(parser.parse_aggregate(), "", ParseError::ExpectedAggregate);
+    #[test]
+    fn parse_edit_distance_filters() {
+        let program = parse_program(
+            "same(?X, ?Y) :- name(?X, ?A), name(?Y, ?B), EDIT_DISTANCE(?A, ?B) <= 2 .\n",
+        )
+        .unwrap();
+        let rule = &program.rules()[0];
 
-        assert_parse!(
-            parser.parse_aggregate(),
-            "#min(?VARIABLE)",
-            Term::Aggregate(Aggregate {
-                aggregate_identifier: Identifier(String::from("min")),
-                variable_identifiers: vec![Identifier(String::from("VARIABLE"))]
-            })
+        assert_eq!(rule.body().len(), 2);
+        assert_eq!(
+            rule.filters(),
+            &vec![Filter::new(
+                FilterOperation::EditDistanceAtMost(2),
+                Variable::Universal(Identifier("A".to_string())),
+                Term::Variable(Variable::Universal(Identifier("B".to_string())))
+            )]
         );
 
-        assert_parse!(
-            parser.parse_aggregate(),
-            "#test(?VAR1, ?VAR2)",
-            Term::Aggregate(Aggregate {
-                aggregate_identifier: Identifier(String::from("test")),
-                variable_identifiers: vec![
-                    Identifier(String::from("VAR1")),
-                    Identifier(String::from("VAR2"))
-                ]
-            })
-        );
+        let parser = RuleParser::new();
+        let filter = all_input_consumed(parser.parse_edit_distance_filter())(
+            "EDIT_DISTANCE(?A, \"Smith\") <= 1",
+        )
+        .unwrap();
+        assert_eq!(filter.operation, FilterOperation::EditDistanceAtMost(1));
+
+        let error =
+            all_input_consumed(parser.parse_edit_distance_filter())("EDIT_DISTANCE(?A, ?B) < 2")
+                .unwrap_err();
+        assert_matches!(error.source, ParseError::ExpectedEditDistanceFilter);
     }
 }
@@ -4,7 +4,7 @@ use std::fmt::Display;
 use nom::{
     branch::alt,
     character::complete::{one_of, satisfy},
-    combinator::{map, opt, recognize},
+    combinator::{all_consuming, map, map_opt, map_res, opt, recognize},
     multi::{many0, many1, separated_list0},
     sequence::{delimited, pair, preceded, terminated, tuple},
 };
@@ -13,7 +13,7 @@ use super::{
     iri, map_error,
     rfc5234::digit,
     token,
-    turtle::hex,
+    turtle::{hex, uchar},
     types::{IntermediateResult, Span},
     ParseError,
 };
@@ -52,6 +52,39 @@ pub fn iriref(input: Span) -> IntermediateResult<Span> {
     )(input)
 }
 
+/// Parse an IRI reference that may contain `\uXXXX` and `\UXXXXXXXX` escape sequences,
+/// as permitted by the [IRIREF](https://www.w3.org/TR/sparql11-query/#rIRIREF) production,
+/// and return the IRI with all escape sequences decoded.
+/// As for [`iriref`], the decoded IRI is verified against the
+/// [RFC 3987](https://www.ietf.org/rfc/rfc3987.txt) grammar.
+#[traced("parser::sparql")]
+pub fn iriref_escaped(input: Span) -> IntermediateResult<String> {
+    map_error(
+        map_res(
+            delimited(
+                token("<"),
+                many0(alt((
+                    map_opt(uchar, |escape| {
+                        u32::from_str_radix(&escape[2..], 16)
+                            .ok()
+                            .and_then(char::from_u32)
+                    }),
+                    satisfy(|c| c > ' ' && !"<>\"{}|^`\\".contains(c)),
+                ))),
+                token(">"),
+            ),
+            |characters| {
+                let iri = characters.into_iter().collect::<String>();
+                all_consuming(iri::iri_reference)(Span::new(&iri))
+                    .map_err(|_| ParseError::ExpectedIriref)?;
+
+                Ok::<_, ParseError>(iri)
+            },
+        ),
+        || ParseError::ExpectedIriref,
+    )(input)
+}
+
 #[traced("parser::sparql")]
 pub fn iri(input: Span) -> IntermediateResult<Name> {
     alt((map(iriref, |name| Name::IriReference(&name)), prefixed_name))(input)
@@ -110,6 +143,22 @@ pub fn pn_chars(input: Span) -> IntermediateResult<Span> {
     ))(input)
 }
 
+/// Parse a character that may occur in a variable name, cf. the
+/// [VARNAME](https://www.w3.org/TR/sparql11-query/#rVARNAME) production.
+#[traced("parser::sparql")]
+pub fn varname_char(input: Span) -> IntermediateResult<Span> {
+    alt((
+        pn_chars_u,
+        digit,
+        token("\u{00B7}"),
+        recognize(satisfy(|c| {
+            [0x0300u32..=0x036F, 0x203F..=0x2040]
+                .iter()
+                .any(|range| range.contains(&c.into()))
+        })),
+    ))(input)
+}
+
 #[traced("parser::sparql")]
 pub fn pn_prefix(input: Span) -> IntermediateResult<Span> {
     recognize(tuple((
@@ -1,8 +1,7 @@
 //! Parsers for productions from the RDF 1.1 Turtle grammar.
-use std::num::ParseIntError;
-
 use nom::{
     branch::alt,
+    bytes::complete::tag,
     character::complete::{alpha1, alphanumeric1, digit0, digit1, none_of, one_of},
     combinator::{cut, map, map_res, opt, recognize},
     multi::{count, many0},
@@ -11,7 +10,7 @@ use nom::{
 
 use macros::traced;
 
-use crate::model::NumericLiteral;
+use crate::{error::ReadingError, model::NumericLiteral};
 use nemo_physical::datatypes::Double;
 
 use super::{
@@ -41,10 +40,13 @@ pub fn string(input: Span) -> IntermediateResult<Span> {
     )(input)
 }
 
+// NOTE: the opening quotes are matched with `tag` instead of `token`,
+// since whitespace following them belongs to the string.
+
 #[traced("parser::turtle")]
 pub fn string_literal_quote(input: Span) -> IntermediateResult<Span> {
     delimited(
-        token(r#"""#),
+        tag(r#"""#),
         cut(recognize(many0(alt((
             recognize(none_of(REQUIRES_ESCAPE)),
             echar,
@@ -57,7 +59,7 @@ pub fn string_literal_quote(input: Span) -> IntermediateResult<Span> {
 #[traced("parser::turtle")]
 pub fn string_literal_single_quote(input: Span) -> IntermediateResult<Span> {
     delimited(
-        token("'"),
+        tag("'"),
         cut(recognize(many0(alt((
             recognize(none_of(REQUIRES_ESCAPE)),
             echar,
@@ -70,12 +72,11 @@ pub fn string_literal_single_quote(input: Span) -> IntermediateResult<Span> {
 #[traced("parser::turtle")]
 pub fn string_literal_long_single_quote(input: Span) -> IntermediateResult<Span> {
     delimited(
-        token("'''"),
-        cut(recognize(many0(alt((
-            recognize(none_of(r"'\")),
-            echar,
-            uchar,
-        ))))),
+        tag("'''"),
+        cut(recognize(many0(pair(
+            opt(alt((tag("''"), tag("'")))),
+            alt((recognize(none_of(r"'\")), echar, uchar)),
+        )))),
         token("'''"),
     )(input)
 }
@@ -83,16 +84,55 @@ pub fn string_literal_long_single_quote(input: Span) -> IntermediateResult<Span>
 #[traced("parser::turtle")]
 pub fn string_literal_long_quote(input: Span) -> IntermediateResult<Span> {
     delimited(
-        token(r#"""""#),
-        cut(recognize(many0(alt((
-            recognize(none_of(r#""\"#)),
-            echar,
-            uchar,
-        ))))),
+        tag(r#"""""#),
+        cut(recognize(many0(pair(
+            opt(alt((tag(r#""""#), tag(r#"""#)))),
+            alt((recognize(none_of(r#""\"#)), echar, uchar)),
+        )))),
         token(r#"""""#),
     )(input)
 }
 
+/// Replace the escape sequences in the content of a string literal
+/// by the characters they represent.
+///
+/// The content is expected to be recognized by one of the string literal parsers,
+/// such that every backslash starts an [`echar`] or a [`uchar`].
+/// Escaped code points that are not valid characters are replaced by U+FFFD.
+pub fn unescape(content: &str) -> String {
+    let mut result = String::with_capacity(content.len());
+    let mut characters = content.chars();
+
+    while let Some(character) = characters.next() {
+        if character != '\\' {
+            result.push(character);
+            continue;
+        }
+
+        match characters.next() {
+            Some('t') => result.push('\t'),
+            Some('b') => result.push('\u{08}'),
+            Some('n') => result.push('\n'),
+            Some('r') => result.push('\r'),
+            Some('f') => result.push('\u{0C}'),
+            Some(escape @ ('u' | 'U')) => {
+                let length = if escape == 'u' { 4 } else { 8 };
+                let code = characters.by_ref().take(length).collect::<String>();
+                result.push(
+                    u32::from_str_radix(&code, 16)
+                        .ok()
+                        .and_then(char::from_u32)
+                        .unwrap_or(char::REPLACEMENT_CHARACTER),
+                );
+            }
+            Some(escaped) => result.push(escaped),
+            None => result.push('\\'),
+        }
+    }
+
+    result
+}
+
 #[traced("parser::turtle")]
 pub fn hex(input: Span) -> IntermediateResult<Span> {
     recognize(one_of(HEXDIGIT))(input)
@@ -101,14 +141,14 @@ pub fn hex(input: Span) -> IntermediateResult<Span> {
 #[traced("parser::turtle")]
 pub fn uchar(input: Span) -> IntermediateResult<Span> {
     recognize(alt((
-        preceded(token(r"\u"), count(hex, 4)),
-        preceded(token(r"\U"), count(hex, 8)),
+        preceded(tag(r"\u"), count(hex, 4)),
+        preceded(tag(r"\U"), count(hex, 8)),
     )))(input)
 }
 
 #[traced("parser::turtle")]
 pub fn echar(input: Span) -> IntermediateResult<Span> {
-    recognize(preceded(token(r"\"), one_of(r#"tbnrf"'\"#)))(input)
+    recognize(preceded(tag(r"\"), one_of(r#"tbnrf"'\"#)))(input)
 }
 
 #[traced("parser::turtle")]
@@ -131,7 +171,22 @@ pub fn decimal(input: Span) -> IntermediateResult<NumericLiteral> {
             preceded(token("."), digit1),
         ),
         |(whole, fraction)| {
-            Ok::<_, ParseIntError>(NumericLiteral::Decimal(whole.parse()?, fraction.parse()?))
+            let digits = whole.trim_start_matches(['+', '-']);
+            let fraction_is_zero = fraction.trim_start_matches('0').is_empty();
+
+            // A decimal is represented by its whole and fractional part,
+            // which cannot capture the sign of numbers between -1 and 0
+            // or leading zeros of the fraction, so those are parsed as doubles
+            if !fraction_is_zero
+                && (fraction.starts_with('0')
+                    || (whole.starts_with('-') && digits.trim_start_matches('0').is_empty()))
+            {
+                let value = format!("{whole}.{fraction}").parse::<f64>()?;
+                return Ok::<_, ReadingError>(NumericLiteral::Double(Double::new(value)?));
+            }
+
+            let whole = if digits.is_empty() { 0 } else { whole.parse()? };
+            Ok(NumericLiteral::Decimal(whole, fraction.parse()?))
         },
     )(input)
 }
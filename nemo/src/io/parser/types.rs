@@ -7,7 +7,7 @@ use nom::{
 use nom_locate::LocatedSpan;
 use thiserror::Error;
 
-use crate::model::PrimitiveType;
+use crate::{io::environment::InterpolationError, model::PrimitiveType};
 
 /// A [`LocatedSpan`] over the input.
 pub(super) type Span<'a> = LocatedSpan<&'a str>;
@@ -65,6 +65,9 @@ pub enum ParseError {
     /// An external error during parsing.
     #[error(transparent)]
     ExternalError(#[from] Box<crate::error::Error>),
+    /// A variable in a resource cannot be interpolated.
+    #[error(transparent)]
+    InterpolationError(#[from] InterpolationError),
     /// A syntax error. Note that we cannot take [&'a str] here, as
     /// bounds on [std::error::Error] require ['static] lifetime.
     #[error("Syntax error: {0}")]
@@ -81,17 +84,43 @@ pub enum ParseError {
     /// An existentially quantified variable occurs in the body of a rule.
     #[error(r#"Variable "{0}" occurs existentially quantified in the rule body."#)]
     BodyExistential(String),
-    /// The universal variable does not occur in a positive body literal.
-    #[error(r#"The universal variable "{0}" does not occur in a positive body literal."#)]
-    UnsafeHeadVariable(String),
+    /// Universal variables of the rule head do not occur in a positive body literal.
+    #[error(
+        r#"Unsafe rule: no positive body literal binds the head variables {variables}. Bind them in the body, e.g. with a predicate listing their possible values: "{suggestion}"."#
+    )]
+    UnsafeHeadVariable {
+        /// The unbound variables
+        variables: String,
+        /// Body literals binding the variables, which are suggested to the user
+        suggestion: String,
+    },
     /// The unsafe variable appears in multiple negative body literals.
     #[error(r#"The unsafe variable "{0}" appears in multuple negative body literals."#)]
     UnsafeVariableInMulltipleNegativeLiterals(String),
+    /// A negated conjunction does not share any variable with the positive body literals.
+    #[error("A negated conjunction must share a variable with the positive body literals.")]
+    UnconnectedNegatedConjunction,
+    /// A rule has a disjunctive head, but no approximation for disjunctive heads has been selected.
+    #[error(
+        "Disjunctive rule heads are not supported unless an approximation for them is selected."
+    )]
+    DisjunctiveHead,
+    /// An anonymous variable is used outside of a body literal.
+    #[error(
+        r#"The anonymous variable "_" can only be used in body literals, since it is never bound to a value."#
+    )]
+    MisplacedAnonymousVariable,
     /// A variable used in a comparison does not occur in a positive body literal.
     #[error(
         r#"The variable "{0}" used in a comparison does not occur in a positive body literal."#
     )]
     UnsafeFilterVariable(String),
+    /// A function is applied to a wrong number of arguments.
+    #[error(r#"The function "{0}" expects {1} arguments, but is applied to {2}."#)]
+    InvalidFunctionArity(String, usize, usize),
+    /// A conversion function is applied to something else than a variable.
+    #[error(r#"The argument of the conversion function "{0}" must be a variable."#)]
+    InvalidConversionArgument(String),
     /// A variable is both existentially and universally quantified
     #[error(r#"Variable "{0}" occurs with existential and universal quantification"#)]
     BothQuantifiers(String),
@@ -105,6 +134,79 @@ pub enum ParseError {
         r#"SPARQL data source for predicate "{0}" has arity {1}, but {2} variables are given"#
     )]
     SparqlSourceInvalidArity(String, usize, usize),
+    /// A data source that is treated as a bag has no column besides the counts.
+    #[error(
+        r#"Bag data source for predicate "{0}" needs an arity of at least 2, since its last column contains the counts"#
+    )]
+    BagSourceInvalidArity(String),
+    /// A cell range of an XLSX data source is not given in A1 notation.
+    #[error(
+        r#"Invalid cell range "{0}", expected a range like "A2:C10" or a single cell like "A2"."#
+    )]
+    InvalidCellRange(String),
+    /// The cell range of an XLSX data source has fewer columns than the predicate.
+    #[error(
+        r#"XLSX data source for predicate "{0}" has arity {1}, but its cell range only has {2} columns"#
+    )]
+    XlsxSourceInvalidArity(String, usize, usize),
+    /// An unknown semiring is declared.
+    #[error(r#"Unknown semiring "{0}", supported are "min-plus" and "max-times"."#)]
+    UnknownSemiring(String),
+    /// A fact or rule is annotated, but the program does not declare a semiring.
+    #[error(r#"Annotations of facts and rules can only be used after a "@semiring" declaration."#)]
+    AnnotationWithoutSemiring,
+    /// A fact is annotated with something else than a number.
+    #[error("The annotation of a fact must be a number.")]
+    InvalidFactAnnotation,
+    /// The variable annotating a rule does not occur in its body.
+    #[error(r#"The variable "{0}" annotating a rule does not occur in the rule body."#)]
+    UnsafeAnnotationVariable(String),
+    /// A rule with negation is used in a program with a semiring.
+    #[error("Negation is not supported in programs with a semiring.")]
+    AnnotatedNegation,
+    /// A rule with an aggregate is used in a program with a semiring.
+    #[error("Aggregates are not supported in programs with a semiring.")]
+    AnnotatedAggregate,
+    /// A validity interval does not contain any point in time.
+    #[error("The validity interval from {0} to {1} is empty.")]
+    EmptyValidityInterval(String, String),
+    /// A fact of a temporal predicate has no validity interval.
+    #[error(r#"The fact needs a validity interval, since "{0}" is a temporal predicate."#)]
+    MissingValidityInterval(String),
+    /// A temporal predicate is derived by a rule without temporal predicates in its body.
+    #[error(r#"The temporal predicate "{0}" is derived by a rule without temporal predicates in its body."#)]
+    UntimedTemporalDerivation(String),
+    /// A temporal predicate is negated.
+    #[error(r#"The temporal predicate "{0}" cannot be negated."#)]
+    TemporalNegation(String),
+    /// A rule with an aggregate derives temporal predicates.
+    #[error("Aggregates are not supported in rules with temporal predicates.")]
+    TemporalAggregate,
+    /// Validity intervals are used in a program with a semiring.
+    #[error("Temporal predicates are not supported in programs with a semiring.")]
+    TemporalPredicatesWithSemiring,
+    /// A Soufflé `.input` directive refers to a predicate without `.decl` directive.
+    #[error(r#"The predicate "{0}" must be declared with ".decl" before it is used in ".input"."#)]
+    UndeclaredSoufflePredicate(String),
+    /// A Soufflé `.input` directive uses an unsupported kind of IO.
+    #[error(r#"Unsupported IO "{0}" in ".input" directive, only "file" is supported."#)]
+    UnsupportedSouffleIo(String),
+    /// The network of an `IN_CIDR` check is not in CIDR notation.
+    #[error(
+        r#"Invalid network "{0}", expected CIDR notation like "10.0.0.0/8" or "2001:db8::/32"."#
+    )]
+    InvalidCidrNetwork(String),
+    /// An unknown encoding is given for a data source.
+    #[error(r#"Unknown encoding "{0}", supported are "utf-8", "utf-8-lossy", "latin-1", and "windows-1252"."#)]
+    UnknownEncoding(String),
+    /// An unknown format is given for an RDF data source.
+    #[error(
+        r#"Unknown RDF format "{0}", supported are "ntriples", "turtle", "rdfxml", and "hdt"."#
+    )]
+    UnknownRdfFormat(String),
+    /// A Soufflé `.input` directive uses a delimiter that is not a single character.
+    #[error(r#"Invalid delimiter "{0}" in ".input" directive, expected a single character."#)]
+    InvalidSouffleDelimiter(String),
     /// Unknown logical type name in program.
     #[error(
         "A predicate declaration used an unknown type ({0}). The known types are: {}",
@@ -138,6 +240,27 @@ pub enum ParseError {
     /// Expected an output declaration.
     #[error(r#"Expected an "@output" declaration"#)]
     ExpectedOutputDeclaration,
+    /// Expected a priority annotation.
+    #[error(r#"Expected a "@priority" annotation followed by a rule"#)]
+    ExpectedPriorityAnnotation,
+    /// Expected a semiring declaration.
+    #[error(r#"Expected a "@semiring" declaration"#)]
+    ExpectedSemiringDeclaration,
+    /// Expected an annotation of a fact or rule.
+    #[error(r#"Expected a number or variable followed by "::""#)]
+    ExpectedAnnotation,
+    /// Expected a validity interval.
+    #[error(r#"Expected a validity interval, e.g., "@[2020-01-01, 2021-01-01)""#)]
+    ExpectedValidityInterval,
+    /// Expected a temporal predicate declaration.
+    #[error(r#"Expected a "@temporal" declaration"#)]
+    ExpectedTemporalDeclaration,
+    /// Expected a limit on the number of facts of a predicate.
+    #[error(r#"Expected a "@limit" declaration, e.g., "@limit path 1000 .""#)]
+    ExpectedLimitDeclaration,
+    /// Expected a Soufflé directive.
+    #[error(r#"Expected a Soufflé "{0}" directive"#)]
+    ExpectedSouffleDirective(&'static str),
     /// Expected a string literal.
     #[error("Expected a string literal")]
     ExpectedStringLiteral,
@@ -191,6 +314,9 @@ pub enum ParseError {
     /// Expected a variable name.
     #[error("Expected a variable name")]
     ExpectedVariableName,
+    /// Expected an anonymous variable.
+    #[error("Expected an anonymous variable")]
+    ExpectedAnonymousVariable,
     /// Expected a literal.
     #[error("Expected a literal")]
     ExpectedLiteral,
@@ -206,9 +332,21 @@ pub enum ParseError {
     /// Expected a filter expression.
     #[error("Expected a filter expression")]
     ExpectedFilterExpression,
+    /// Expected a check whether an IP address belongs to a network.
+    #[error(r#"Expected a network check like "IN_CIDR(?X, \"10.0.0.0/8\")""#)]
+    ExpectedCidrFilter,
+    /// Expected a full-text search.
+    #[error(r#"Expected a full-text search like "MATCHES_TEXT(?X, \"quantum comput*\")""#)]
+    ExpectedTextFilter,
+    /// Expected a bound on an edit distance.
+    #[error(r#"Expected a bound on an edit distance like "EDIT_DISTANCE(?X, ?Y) <= 2""#)]
+    ExpectedEditDistanceFilter,
     /// Expected a body expression.
     #[error("Expected a literal or a filter expression")]
     ExpectedBodyExpression,
+    /// Expected a negated conjunction.
+    #[error("Expected a negated conjunction")]
+    ExpectedNegatedConjunction,
     /// Expected an arithmetic expression.
     #[error("Expected an arithmetic expression")]
     ExpectedArithmeticExpression,
@@ -224,6 +362,9 @@ pub enum ParseError {
     /// Encountered a prefix declaration after any non-base non-prefix directive.
     #[error("A @prefix declaration must occur before any non-@base non-@prefix declarations.")]
     LatePrefixDeclaration,
+    /// Encountered a semiring declaration after any non-base non-prefix directive.
+    #[error("A @semiring declaration must occur before any non-@base non-@prefix declarations.")]
+    LateSemiringDeclaration,
     /// Expected a function term
     #[error("Expected a function term")]
     ExpectedFunctionTerm,
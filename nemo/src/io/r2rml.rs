@@ -0,0 +1,913 @@
+//! Import of [R2RML](https://www.w3.org/TR/r2rml/) mappings,
+//! which describe how the rows of tables are mapped to RDF triples.
+//!
+//! A mapping is compiled into a Nemo program that derives the mapped triples for the predicate `triple`.
+//! For every triples map, the program declares external sources, whose facts are computed from the rows of the table,
+//! and rules that combine them with the constant parts of the mapping (classes and predicates).
+//!
+//! Only a practical subset of R2RML is supported:
+//! - logical tables are given by `rr:tableName` and refer to CSV files with a header row,
+//!   where the table `people` is read from `people.csv` (and names ending in `.tsv` are read as TSV files),
+//! - term maps are given by `rr:constant`, `rr:column` or `rr:template`,
+//!   optionally together with `rr:termType`, `rr:language` and `rr:datatype`,
+//! - predicate maps have to be constant.
+//!
+//! Empty fields are treated as `NULL` values, i.e. no triple is produced for them.
+//! Referencing object maps (`rr:parentTriplesMap`) and SQL queries are not supported,
+//! and graph maps are ignored.
+
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Write,
+};
+
+use csv::{ReaderBuilder, StringRecord};
+use nemo_physical::{
+    error::{ExternalReadingError, ReadingError},
+    table_reader::Resource,
+};
+use oxiri::Iri;
+use rio_api::{
+    model::{Literal, Subject, Term as RioTerm, Triple},
+    parser::TriplesParser,
+};
+use rio_turtle::{TurtleError, TurtleParser};
+use thiserror::Error;
+
+use crate::{
+    io::resource_providers::{external::ExternalSourceProvider, ResourceProviders},
+    model::{RdfLiteral, Term},
+};
+
+/// Namespace of the R2RML vocabulary
+const RR: &str = "http://www.w3.org/ns/r2rml#";
+/// IRI of `rdf:type`
+const RDF_TYPE: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#type";
+
+/// Name of the predicate that contains the triples produced by a mapping
+pub const TRIPLE_PREDICATE: &str = "triple";
+
+/// Errors that can occur when importing an R2RML mapping or reading the mapped tables.
+#[derive(Error, Debug)]
+pub enum R2rmlError {
+    /// The mapping is not valid Turtle.
+    #[error(transparent)]
+    Turtle(#[from] TurtleError),
+    /// The given base IRI is not valid.
+    #[error("The base IRI \"{0}\" is not valid.")]
+    InvalidBase(String),
+    /// A triples map or term map lacks a required property.
+    #[error("The triples map {0} has no {1}.")]
+    MissingProperty(String, &'static str),
+    /// A triples map uses a feature of R2RML that is not supported.
+    #[error("The triples map {0} uses {1}, which is not supported.")]
+    UnsupportedFeature(String, &'static str),
+    /// A property of a triples map has a value of the wrong kind.
+    #[error("The value of {1} in the triples map {0} is not valid.")]
+    InvalidValue(String, &'static str),
+    /// A template contains unbalanced braces.
+    #[error("The template \"{0}\" is not well-formed.")]
+    InvalidTemplate(String),
+    /// A term map refers to a column that does not exist.
+    #[error("The table \"{table}\" has no column \"{column}\".")]
+    MissingColumn {
+        /// The table
+        table: String,
+        /// The column
+        column: String,
+    },
+    /// A table could not be read.
+    #[error(transparent)]
+    Csv(#[from] csv::Error),
+}
+
+impl ExternalReadingError for R2rmlError {}
+
+impl From<R2rmlError> for ReadingError {
+    fn from(error: R2rmlError) -> Self {
+        ReadingError::ExternalReadingError(Box::new(error))
+    }
+}
+
+/// An RDF term of the mapping.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum Node {
+    Iri(String),
+    Blank(String),
+    Literal {
+        value: String,
+        language: Option<String>,
+        datatype: Option<String>,
+    },
+}
+
+impl Node {
+    fn from_subject(subject: Subject) -> Option<Self> {
+        match subject {
+            Subject::NamedNode(node) => Some(Node::Iri(node.iri.to_string())),
+            Subject::BlankNode(node) => Some(Node::Blank(node.id.to_string())),
+            Subject::Triple(_) => None,
+        }
+    }
+
+    fn from_term(term: RioTerm) -> Option<Self> {
+        match term {
+            RioTerm::NamedNode(node) => Some(Node::Iri(node.iri.to_string())),
+            RioTerm::BlankNode(node) => Some(Node::Blank(node.id.to_string())),
+            RioTerm::Literal(Literal::Simple { value }) => Some(Node::Literal {
+                value: value.to_string(),
+                language: None,
+                datatype: None,
+            }),
+            RioTerm::Literal(Literal::LanguageTaggedString { value, language }) => {
+                Some(Node::Literal {
+                    value: value.to_string(),
+                    language: Some(language.to_string()),
+                    datatype: None,
+                })
+            }
+            RioTerm::Literal(Literal::Typed { value, datatype }) => Some(Node::Literal {
+                value: value.to_string(),
+                language: None,
+                datatype: Some(datatype.iri.to_string()),
+            }),
+            RioTerm::Triple(_) => None,
+        }
+    }
+
+    /// Return the lexical form of a literal.
+    fn literal(&self) -> Option<&str> {
+        match self {
+            Node::Literal { value, .. } => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Return the IRI of a named node.
+    fn iri(&self) -> Option<&str> {
+        match self {
+            Node::Iri(iri) => Some(iri),
+            _ => None,
+        }
+    }
+}
+
+/// The triples of a mapping, indexed by their subject.
+#[derive(Debug, Default)]
+struct Graph(HashMap<Node, Vec<(String, Node)>>);
+
+impl Graph {
+    fn parse(mapping: &str, base: Option<Iri<String>>) -> Result<Self, R2rmlError> {
+        let mut graph = Self::default();
+
+        TurtleParser::new(mapping.as_bytes(), base).parse_all(&mut |triple: Triple| {
+            if let (Some(subject), Some(object)) = (
+                Node::from_subject(triple.subject),
+                Node::from_term(triple.object),
+            ) {
+                graph
+                    .0
+                    .entry(subject)
+                    .or_default()
+                    .push((triple.predicate.iri.to_string(), object));
+            }
+
+            Ok::<_, TurtleError>(())
+        })?;
+
+        Ok(graph)
+    }
+
+    /// Return all objects of the given subject and R2RML property.
+    fn objects<'a>(&'a self, subject: &Node, property: &str) -> Vec<&'a Node> {
+        self.0
+            .get(subject)
+            .into_iter()
+            .flatten()
+            .filter(|(predicate, _)| {
+                predicate
+                    .strip_prefix(RR)
+                    .is_some_and(|name| name == property)
+            })
+            .map(|(_, object)| object)
+            .collect()
+    }
+
+    /// Return the object of the given subject and R2RML property, if there is one.
+    fn object<'a>(&'a self, subject: &Node, property: &str) -> Option<&'a Node> {
+        self.objects(subject, property).into_iter().next()
+    }
+
+    /// Return the subjects of all triples maps, in a deterministic order.
+    fn triples_maps(&self) -> Vec<&Node> {
+        let mut maps = self
+            .0
+            .keys()
+            .filter(|node| self.object(node, "logicalTable").is_some())
+            .collect::<Vec<_>>();
+        maps.sort_by_key(|node| format!("{node:?}"));
+        maps
+    }
+}
+
+/// The kind of terms generated by a term map.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TermType {
+    Iri,
+    BlankNode,
+    Literal,
+}
+
+/// A part of a template, i.e. either text or a reference to a column.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum TemplatePart {
+    Text(String),
+    Column(String),
+}
+
+/// Parse a template like `http://example.org/person/{id}`.
+fn parse_template(template: &str) -> Result<Vec<TemplatePart>, R2rmlError> {
+    let invalid = || R2rmlError::InvalidTemplate(template.to_string());
+
+    let mut parts = Vec::new();
+    let mut current = String::new();
+    let mut in_column = false;
+    let mut characters = template.chars();
+
+    while let Some(character) = characters.next() {
+        match character {
+            '\\' => current.push(characters.next().ok_or_else(invalid)?),
+            '{' if !in_column => {
+                if !current.is_empty() {
+                    parts.push(TemplatePart::Text(std::mem::take(&mut current)));
+                }
+                in_column = true;
+            }
+            '}' if in_column => {
+                parts.push(TemplatePart::Column(std::mem::take(&mut current)));
+                in_column = false;
+            }
+            '{' | '}' => return Err(invalid()),
+            _ => current.push(character),
+        }
+    }
+
+    if in_column {
+        return Err(invalid());
+    }
+    if !current.is_empty() {
+        parts.push(TemplatePart::Text(current));
+    }
+
+    Ok(parts)
+}
+
+/// Percent-encode all characters of the given value that are not unreserved in IRIs,
+/// as required for values that are inserted into IRI templates.
+fn iri_safe(value: &str) -> String {
+    let mut result = String::new();
+    for byte in value.bytes() {
+        if byte.is_ascii_alphanumeric() || b"-._~".contains(&byte) {
+            result.push(byte as char);
+        } else {
+            let _ = write!(result, "%{byte:02X}");
+        }
+    }
+    result
+}
+
+/// How the values of a term map are obtained.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum TermValue {
+    Constant(Term),
+    Column(String),
+    Template(Vec<TemplatePart>),
+}
+
+/// A term map, which generates a term for each row of a table.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct TermMap {
+    value: TermValue,
+    term_type: TermType,
+    language: Option<String>,
+    datatype: Option<String>,
+}
+
+impl TermMap {
+    /// Convert a constant node of the mapping into a [`Term`].
+    fn constant(node: &Node) -> Option<Term> {
+        match node {
+            Node::Iri(iri) => Some(Term::Constant(iri.clone().into())),
+            Node::Blank(id) => Some(Term::Constant(format!("_:{id}").into())),
+            Node::Literal {
+                value,
+                language: Some(tag),
+                ..
+            } => Term::try_from(RdfLiteral::LanguageString {
+                value: value.clone(),
+                tag: tag.clone(),
+            })
+            .ok(),
+            Node::Literal {
+                value,
+                datatype: Some(datatype),
+                ..
+            } => Term::try_from(RdfLiteral::DatatypeValue {
+                value: value.clone(),
+                datatype: datatype.clone(),
+            })
+            .ok(),
+            Node::Literal { value, .. } => Some(Term::StringLiteral(value.clone())),
+        }
+    }
+
+    /// Read the term map given by the node `map`,
+    /// where `shortcut` is the constant-valued shortcut property (e.g. `rr:subject` for `rr:subjectMap`).
+    fn parse(
+        graph: &Graph,
+        triples_map: &str,
+        subject: &Node,
+        property: &'static str,
+        shortcut: &'static str,
+        is_object: bool,
+    ) -> Result<Vec<TermMap>, R2rmlError> {
+        let invalid = || R2rmlError::InvalidValue(triples_map.to_string(), property);
+        let mut term_maps = Vec::new();
+
+        for constant in graph.objects(subject, shortcut) {
+            term_maps.push(TermMap {
+                value: TermValue::Constant(Self::constant(constant).ok_or_else(invalid)?),
+                term_type: TermType::Iri,
+                language: None,
+                datatype: None,
+            });
+        }
+
+        for map in graph.objects(subject, property) {
+            if graph.object(map, "parentTriplesMap").is_some() {
+                return Err(R2rmlError::UnsupportedFeature(
+                    triples_map.to_string(),
+                    "rr:parentTriplesMap",
+                ));
+            }
+
+            let language = graph
+                .object(map, "language")
+                .map(|node| node.literal().map(str::to_string).ok_or_else(invalid))
+                .transpose()?;
+            let datatype = graph
+                .object(map, "datatype")
+                .map(|node| node.iri().map(str::to_string).ok_or_else(invalid))
+                .transpose()?;
+
+            let (value, default_type) = if let Some(constant) = graph.object(map, "constant") {
+                (
+                    TermValue::Constant(Self::constant(constant).ok_or_else(invalid)?),
+                    TermType::Iri,
+                )
+            } else if let Some(column) = graph.object(map, "column") {
+                let column = column.literal().ok_or_else(invalid)?;
+                let default_type = if is_object {
+                    TermType::Literal
+                } else {
+                    TermType::Iri
+                };
+                (TermValue::Column(column.to_string()), default_type)
+            } else if let Some(template) = graph.object(map, "template") {
+                let template = template.literal().ok_or_else(invalid)?;
+                (
+                    TermValue::Template(parse_template(template)?),
+                    TermType::Iri,
+                )
+            } else {
+                return Err(R2rmlError::MissingProperty(
+                    triples_map.to_string(),
+                    "rr:constant, rr:column or rr:template",
+                ));
+            };
+
+            let term_type = match graph.object(map, "termType").map(Node::iri) {
+                Some(Some(iri)) if iri == format!("{RR}IRI") => TermType::Iri,
+                Some(Some(iri)) if iri == format!("{RR}BlankNode") => TermType::BlankNode,
+                Some(Some(iri)) if iri == format!("{RR}Literal") => TermType::Literal,
+                Some(_) => return Err(invalid()),
+                None if language.is_some() || datatype.is_some() => TermType::Literal,
+                None => default_type,
+            };
+
+            term_maps.push(TermMap {
+                value,
+                term_type,
+                language,
+                datatype,
+            });
+        }
+
+        Ok(term_maps)
+    }
+
+    /// Return the columns referenced by this term map.
+    fn columns(&self) -> Vec<&str> {
+        match &self.value {
+            TermValue::Constant(_) => Vec::new(),
+            TermValue::Column(column) => vec![column],
+            TermValue::Template(parts) => parts
+                .iter()
+                .filter_map(|part| match part {
+                    TemplatePart::Column(column) => Some(column.as_str()),
+                    TemplatePart::Text(_) => None,
+                })
+                .collect(),
+        }
+    }
+
+    /// Generate the term for the given row,
+    /// which does not exist if one of the referenced fields is empty.
+    fn evaluate(&self, row: &StringRecord, columns: &HashMap<String, usize>) -> Option<Term> {
+        let field = |column: &String| {
+            Some(row.get(columns[column])?)
+                .filter(|field| !field.is_empty())
+                .map(str::to_string)
+        };
+
+        let value = match &self.value {
+            TermValue::Constant(term) => return Some(term.clone()),
+            TermValue::Column(column) => field(column)?,
+            TermValue::Template(parts) => {
+                let mut value = String::new();
+                for part in parts {
+                    match part {
+                        TemplatePart::Text(text) => value.push_str(text),
+                        TemplatePart::Column(column) if self.term_type == TermType::Iri => {
+                            value.push_str(&iri_safe(&field(column)?))
+                        }
+                        TemplatePart::Column(column) => value.push_str(&field(column)?),
+                    }
+                }
+                value
+            }
+        };
+
+        let literal = match (self.term_type, &self.language, &self.datatype) {
+            (TermType::Iri, _, _) => return Some(Term::Constant(value.into())),
+            (TermType::BlankNode, _, _) => {
+                return Some(Term::Constant(format!("_:{value}").into()))
+            }
+            (TermType::Literal, None, None) => return Some(Term::StringLiteral(value)),
+            (TermType::Literal, Some(tag), _) => RdfLiteral::LanguageString {
+                value,
+                tag: tag.clone(),
+            },
+            (TermType::Literal, None, Some(datatype)) => RdfLiteral::DatatypeValue {
+                value,
+                datatype: datatype.clone(),
+            },
+        };
+
+        match Term::try_from(literal) {
+            Ok(term) => Some(term),
+            Err(error) => {
+                tracing::info!("Ignoring invalid literal: {error}");
+                None
+            }
+        }
+    }
+}
+
+/// A triples map, restricted to the supported features.
+#[derive(Debug, Clone)]
+struct TriplesMap {
+    /// Name used for the predicates and external sources of this map
+    name: String,
+    /// The table
+    table: String,
+    /// The subject map
+    subject: TermMap,
+    /// IRIs of the classes of the subjects
+    classes: Vec<String>,
+    /// Pairs of constant predicates and object maps
+    predicate_objects: Vec<(String, TermMap)>,
+}
+
+impl TriplesMap {
+    /// Return a name for the given triples map that can be used as predicate name.
+    fn local_name(node: &Node, index: usize) -> String {
+        let name = match node {
+            Node::Iri(iri) => iri.rsplit(['#', '/']).next().unwrap_or_default(),
+            _ => "",
+        };
+
+        if name.is_empty() {
+            format!("r2rml_map{index}")
+        } else {
+            let name = name
+                .chars()
+                .map(|c| {
+                    if c.is_ascii_alphanumeric() || c == '_' {
+                        c
+                    } else {
+                        '_'
+                    }
+                })
+                .collect::<String>();
+            format!("r2rml_{name}")
+        }
+    }
+
+    fn parse(graph: &Graph, node: &Node, name: String) -> Result<Self, R2rmlError> {
+        let label = match node {
+            Node::Iri(iri) => format!("<{iri}>"),
+            _ => name.clone(),
+        };
+
+        if graph.object(node, "graphMap").is_some() || graph.object(node, "graph").is_some() {
+            tracing::warn!("Ignoring graph maps of the triples map {label}");
+        }
+
+        let logical_table = graph
+            .object(node, "logicalTable")
+            .ok_or_else(|| R2rmlError::MissingProperty(label.clone(), "rr:logicalTable"))?;
+        if graph.object(logical_table, "sqlQuery").is_some() {
+            return Err(R2rmlError::UnsupportedFeature(label, "rr:sqlQuery"));
+        }
+        let table = graph
+            .object(logical_table, "tableName")
+            .ok_or_else(|| R2rmlError::MissingProperty(label.clone(), "rr:tableName"))?
+            .literal()
+            .ok_or_else(|| R2rmlError::InvalidValue(label.clone(), "rr:tableName"))?
+            .to_string();
+
+        let mut subjects =
+            TermMap::parse(graph, &label, node, "subjectMap", "subject", false)?.into_iter();
+        let subject = match (subjects.next(), subjects.next()) {
+            (Some(subject), None) => subject,
+            (None, _) => return Err(R2rmlError::MissingProperty(label, "rr:subjectMap")),
+            (Some(_), Some(_)) => return Err(R2rmlError::InvalidValue(label, "rr:subjectMap")),
+        };
+        if subject.term_type == TermType::Literal {
+            return Err(R2rmlError::InvalidValue(label, "rr:subjectMap"));
+        }
+
+        let mut classes = Vec::new();
+        for subject_map in graph.objects(node, "subjectMap") {
+            for class in graph.objects(subject_map, "class") {
+                classes.push(
+                    class
+                        .iri()
+                        .ok_or_else(|| R2rmlError::InvalidValue(label.clone(), "rr:class"))?
+                        .to_string(),
+                );
+            }
+        }
+
+        let mut predicate_objects = Vec::new();
+        for predicate_object_map in graph.objects(node, "predicateObjectMap") {
+            let predicates = TermMap::parse(
+                graph,
+                &label,
+                predicate_object_map,
+                "predicateMap",
+                "predicate",
+                false,
+            )?
+            .into_iter()
+            .map(|predicate| match predicate.value {
+                TermValue::Constant(Term::Constant(iri)) => Ok(iri.name()),
+                _ => Err(R2rmlError::UnsupportedFeature(
+                    label.clone(),
+                    "a predicate map that is not constant",
+                )),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+            let objects = TermMap::parse(
+                graph,
+                &label,
+                predicate_object_map,
+                "objectMap",
+                "object",
+                true,
+            )?;
+
+            for predicate in &predicates {
+                for object in &objects {
+                    predicate_objects.push((predicate.clone(), object.clone()));
+                }
+            }
+        }
+
+        Ok(Self {
+            name,
+            table,
+            subject,
+            classes,
+            predicate_objects,
+        })
+    }
+
+    /// Return the resource and the delimiter of the table.
+    fn table_resource(&self) -> (Resource, u8) {
+        let resource = if self.table.contains('.') {
+            self.table.clone()
+        } else {
+            format!("{}.csv", self.table)
+        };
+        let delimiter = if resource.ends_with(".tsv") || resource.ends_with(".tsv.gz") {
+            b'\t'
+        } else {
+            b','
+        };
+
+        (resource, delimiter)
+    }
+}
+
+/// [`ExternalSourceProvider`] that generates the terms of some term maps for each row of a table.
+#[derive(Debug)]
+struct TableSourceProvider {
+    resource_providers: ResourceProviders,
+    resource: Resource,
+    delimiter: u8,
+    term_maps: Vec<TermMap>,
+}
+
+impl ExternalSourceProvider for TableSourceProvider {
+    fn facts(&self) -> Result<Box<dyn Iterator<Item = Vec<Term>> + '_>, ReadingError> {
+        let reader = self
+            .resource_providers
+            .open_resource(&self.resource, true)?;
+        let mut reader = ReaderBuilder::new()
+            .delimiter(self.delimiter)
+            .flexible(true)
+            .from_reader(reader);
+
+        let columns = reader
+            .headers()
+            .map_err(R2rmlError::from)?
+            .iter()
+            .enumerate()
+            .map(|(index, column)| (column.to_string(), index))
+            .collect::<HashMap<_, _>>();
+
+        for column in self.term_maps.iter().flat_map(TermMap::columns) {
+            if !columns.contains_key(column) {
+                return Err(R2rmlError::MissingColumn {
+                    table: self.resource.clone(),
+                    column: column.to_string(),
+                }
+                .into());
+            }
+        }
+
+        let mut facts = Vec::new();
+        for row in reader.records() {
+            let row = row.map_err(R2rmlError::from)?;
+
+            if let Some(fact) = self
+                .term_maps
+                .iter()
+                .map(|term_map| term_map.evaluate(&row, &columns))
+                .collect::<Option<Vec<_>>>()
+            {
+                facts.push(fact);
+            }
+        }
+
+        Ok(Box::new(facts.into_iter()))
+    }
+}
+
+/// An R2RML mapping, which can be compiled into a Nemo program.
+///
+/// The program is obtained by [`R2rmlMapping::program`] and derives the mapped triples
+/// for the predicate [`TRIPLE_PREDICATE`].
+/// The external sources used by the program have to be registered with [`R2rmlMapping::register_sources`].
+#[derive(Debug, Clone)]
+pub struct R2rmlMapping {
+    triples_maps: Vec<TriplesMap>,
+}
+
+impl R2rmlMapping {
+    /// Parse an R2RML mapping given in Turtle,
+    /// where relative IRIs are resolved against the given base IRI.
+    pub fn parse(mapping: &str, base: Option<&str>) -> Result<Self, R2rmlError> {
+        let base = base
+            .map(|base| {
+                Iri::parse(base.to_string()).map_err(|_| R2rmlError::InvalidBase(base.to_string()))
+            })
+            .transpose()?;
+        let graph = Graph::parse(mapping, base)?;
+
+        let mut names = HashSet::new();
+        let mut triples_maps = Vec::new();
+        for (index, node) in graph.triples_maps().into_iter().enumerate() {
+            let mut name = TriplesMap::local_name(node, index);
+            if !names.insert(name.clone()) {
+                name = format!("{name}_{index}");
+                names.insert(name.clone());
+            }
+
+            triples_maps.push(TriplesMap::parse(&graph, node, name)?);
+        }
+
+        Ok(Self { triples_maps })
+    }
+
+    /// Return the external sources of the program together with the term maps that provide their facts.
+    fn sources(&self) -> Vec<(String, &TriplesMap, Vec<TermMap>)> {
+        let mut sources = Vec::new();
+
+        for triples_map in &self.triples_maps {
+            if !triples_map.classes.is_empty() {
+                sources.push((
+                    triples_map.name.clone(),
+                    triples_map,
+                    vec![triples_map.subject.clone()],
+                ));
+            }
+
+            for (index, (_, object)) in triples_map.predicate_objects.iter().enumerate() {
+                sources.push((
+                    format!("{}_{}", triples_map.name, index + 1),
+                    triples_map,
+                    vec![triples_map.subject.clone(), object.clone()],
+                ));
+            }
+        }
+
+        sources
+    }
+
+    /// Return the Nemo program that derives the triples described by the mapping.
+    ///
+    /// For every triples map, the program contains a unary external source for the subjects of the classes,
+    /// and a binary external source for the subjects and objects of each predicate,
+    /// e.g. `@source r2rml_PersonMap_1[any, any]: external("r2rml_PersonMap_1") .`.
+    pub fn program(&self) -> String {
+        let mut program = String::new();
+
+        for (name, _, term_maps) in self.sources() {
+            let types = vec!["any"; term_maps.len()].join(", ");
+            let _ = writeln!(program, "@source {name}[{types}]: external(\"{name}\") .");
+        }
+
+        for triples_map in &self.triples_maps {
+            let name = &triples_map.name;
+
+            for class in &triples_map.classes {
+                let _ = writeln!(
+                    program,
+                    "{TRIPLE_PREDICATE}(?S, <{RDF_TYPE}>, <{class}>) :- {name}(?S) ."
+                );
+            }
+
+            for (index, (predicate, _)) in triples_map.predicate_objects.iter().enumerate() {
+                let _ = writeln!(
+                    program,
+                    "{TRIPLE_PREDICATE}(?S, <{predicate}>, ?O) :- {name}_{}(?S, ?O) .",
+                    index + 1
+                );
+            }
+        }
+
+        program
+    }
+
+    /// Register the external sources used by the program of this mapping,
+    /// whose tables are resolved using the given [`ResourceProviders`].
+    pub fn register_sources(&self, resource_providers: ResourceProviders) -> ResourceProviders {
+        self.sources().into_iter().fold(
+            resource_providers.clone(),
+            |providers, (name, triples_map, term_maps)| {
+                let (resource, delimiter) = triples_map.table_resource();
+
+                providers.with_external_source(
+                    &name,
+                    TableSourceProvider {
+                        resource_providers: resource_providers.clone(),
+                        resource,
+                        delimiter,
+                        term_maps,
+                    },
+                )
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::model::{Identifier, RdfLiteral, Term};
+
+    use super::{parse_template, R2rmlError, R2rmlMapping, TemplatePart};
+
+    const MAPPING: &str = r#"
+        @prefix rr: <http://www.w3.org/ns/r2rml#> .
+        @prefix foaf: <http://xmlns.com/foaf/0.1/> .
+        @prefix ex: <http://example.org/> .
+
+        <#PersonMap>
+            rr:logicalTable [ rr:tableName "people" ] ;
+            rr:subjectMap [
+                rr:template "http://example.org/person/{id}" ;
+                rr:class foaf:Person
+            ] ;
+            rr:predicateObjectMap [
+                rr:predicate foaf:name ;
+                rr:objectMap [ rr:column "name" ; rr:language "en" ]
+            ] ;
+            rr:predicateObjectMap [
+                rr:predicateMap [ rr:constant ex:city ] ;
+                rr:objectMap [ rr:template "http://example.org/city/{city}" ]
+            ] .
+    "#;
+
+    #[test]
+    fn templates() {
+        assert_eq!(
+            parse_template("http://example.org/{id}/\\{x\\}").unwrap(),
+            vec![
+                TemplatePart::Text("http://example.org/".to_string()),
+                TemplatePart::Column("id".to_string()),
+                TemplatePart::Text("/{x}".to_string()),
+            ]
+        );
+        assert!(matches!(
+            parse_template("http://example.org/{id"),
+            Err(R2rmlError::InvalidTemplate(_))
+        ));
+        assert_eq!(super::iri_safe("New York"), "New%20York");
+    }
+
+    #[test]
+    fn compile_mapping() {
+        let mapping = R2rmlMapping::parse(MAPPING, Some("http://example.org/mapping")).unwrap();
+
+        assert_eq!(
+            mapping.program(),
+            "@source r2rml_PersonMap[any]: external(\"r2rml_PersonMap\") .\n\
+             @source r2rml_PersonMap_1[any, any]: external(\"r2rml_PersonMap_1\") .\n\
+             @source r2rml_PersonMap_2[any, any]: external(\"r2rml_PersonMap_2\") .\n\
+             triple(?S, <http://www.w3.org/1999/02/22-rdf-syntax-ns#type>, <http://xmlns.com/foaf/0.1/Person>) :- r2rml_PersonMap(?S) .\n\
+             triple(?S, <http://xmlns.com/foaf/0.1/name>, ?O) :- r2rml_PersonMap_1(?S, ?O) .\n\
+             triple(?S, <http://example.org/city>, ?O) :- r2rml_PersonMap_2(?S, ?O) .\n"
+        );
+        assert!(crate::io::parser::parse_program(mapping.program()).is_ok());
+
+        let triples_map = &mapping.triples_maps[0];
+        let row = csv::StringRecord::from(vec!["1", "Alice", "New York"]);
+        let columns = [("id", 0), ("name", 1), ("city", 2)]
+            .into_iter()
+            .map(|(column, index)| (column.to_string(), index))
+            .collect();
+
+        assert_eq!(
+            triples_map.subject.evaluate(&row, &columns),
+            Some(Term::Constant(Identifier(
+                "http://example.org/person/1".to_string()
+            )))
+        );
+        assert_eq!(
+            triples_map.predicate_objects[0].1.evaluate(&row, &columns),
+            Some(Term::RdfLiteral(RdfLiteral::LanguageString {
+                value: "Alice".to_string(),
+                tag: "en".to_string()
+            }))
+        );
+        assert_eq!(
+            triples_map.predicate_objects[1].1.evaluate(&row, &columns),
+            Some(Term::Constant(Identifier(
+                "http://example.org/city/New%20York".to_string()
+            )))
+        );
+
+        let row = csv::StringRecord::from(vec!["2", "Bob", ""]);
+        assert_eq!(
+            triples_map.predicate_objects[1].1.evaluate(&row, &columns),
+            None
+        );
+    }
+
+    #[test]
+    fn unsupported_features() {
+        let mapping = r#"
+            @prefix rr: <http://www.w3.org/ns/r2rml#> .
+            <http://example.org/QueryMap>
+                rr:logicalTable [ rr:sqlQuery "SELECT * FROM people" ] ;
+                rr:subjectMap [ rr:column "id" ] .
+        "#;
+        assert!(matches!(
+            R2rmlMapping::parse(mapping, None),
+            Err(R2rmlError::UnsupportedFeature(_, "rr:sqlQuery"))
+        ));
+
+        let mapping = r#"
+            @prefix rr: <http://www.w3.org/ns/r2rml#> .
+            <http://example.org/PersonMap>
+                rr:logicalTable [ rr:tableName "people" ] .
+        "#;
+        assert!(matches!(
+            R2rmlMapping::parse(mapping, None),
+            Err(R2rmlError::MissingProperty(_, "rr:subjectMap"))
+        ));
+    }
+}
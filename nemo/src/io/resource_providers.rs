@@ -1,19 +1,47 @@
 //! Resource providers for external resources that can be used in reasoning.
 
-use std::{io::Read, path::PathBuf, rc::Rc};
+use std::{
+    collections::HashMap,
+    io::{BufRead, BufReader, Read},
+    path::PathBuf,
+    rc::Rc,
+};
 
-use flate2::read::MultiGzDecoder;
+use flate2::{bufread, read::MultiGzDecoder};
 
-use crate::io::parser::{all_input_consumed, iri::iri};
+use crate::{
+    io::{
+        formats::ImportFormat,
+        input_cache::InputCache,
+        input_statistics::InputStatistics,
+        parser::{all_input_consumed, iri::iri},
+    },
+    model::CustomFormatFile,
+};
 use nemo_physical::{error::ReadingError, table_reader::Resource};
 
+/// Resources inside of zip and tar archives.
+pub mod archive;
+/// Providers for facts that are computed by the application.
+pub mod external;
 /// A resource provider for files.
 pub mod file;
+/// Providers for facts that are streamed from gRPC services.
+#[cfg(feature = "grpc")]
+pub mod grpc;
 /// A resource provider for HTTP(s) requests.
 pub mod http;
+/// Resources that are read in chunks, e.g. while they are downloaded.
+pub mod stream;
+
+use external::ExternalSourceProvider;
+use stream::{ResourceFuture, ResourceStream, StreamReader};
+
+/// Magic bytes at the start of gzip compressed content
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
 
 fn is_iri(resource: &Resource) -> bool {
-    all_input_consumed(iri)(resource).is_ok()
+    !file::is_windows_path(resource) && all_input_consumed(iri)(resource).is_ok()
 }
 
 /// Allows resolving resources to readers.
@@ -28,44 +56,214 @@ pub trait ResourceProvider: std::fmt::Debug {
     fn open_resource(&self, resource: &Resource) -> Result<Option<Box<dyn Read>>, ReadingError>;
 }
 
-/// A list of [`ResourceProvider`] sorted by decreasing priority.
+/// Allows resolving resources to streams, whose content is read in chunks.
+///
+/// In contrast to a [`ResourceProvider`], opening the resource and reading its chunks is asynchronous,
+/// so that e.g. remote resources are read while they are downloaded instead of being buffered first.
+/// An [`AsyncResourceProvider`] can be used as a [`ResourceProvider`] via [`BlockingResourceProvider`].
+pub trait AsyncResourceProvider: std::fmt::Debug {
+    /// Return whether this provider is responsible for the given resource.
+    ///
+    /// This is checked before any asynchronous work is started,
+    /// so it should not require accessing the resource.
+    fn handles(&self, resource: &Resource) -> bool;
+
+    /// Resolve and open a resource, see [`ResourceProvider::open_resource`].
+    ///
+    /// This is only called for resources that are handled by this provider.
+    fn open_resource_stream<'a>(
+        &'a self,
+        resource: &'a Resource,
+    ) -> ResourceFuture<'a, Option<Box<dyn ResourceStream>>>;
+}
+
+/// [`ResourceProvider`] that opens resources using an [`AsyncResourceProvider`],
+/// waiting for each chunk only when the content read so far has been consumed.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BlockingResourceProvider<P> {
+    provider: P,
+}
+
+impl<P: AsyncResourceProvider> BlockingResourceProvider<P> {
+    /// Create a new [`BlockingResourceProvider`] for the given [`AsyncResourceProvider`].
+    pub fn new(provider: P) -> Self {
+        Self { provider }
+    }
+
+    /// Return the underlying [`AsyncResourceProvider`].
+    pub fn provider(&self) -> &P {
+        &self.provider
+    }
+}
+
+impl<P: AsyncResourceProvider> ResourceProvider for BlockingResourceProvider<P> {
+    fn open_resource(&self, resource: &Resource) -> Result<Option<Box<dyn Read>>, ReadingError> {
+        if !self.provider.handles(resource) {
+            return Ok(None);
+        }
+
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()?;
+
+        let Some(stream) = runtime.block_on(self.provider.open_resource_stream(resource))? else {
+            return Ok(None);
+        };
+
+        Ok(Some(Box::new(StreamReader::new(runtime, resource, stream))))
+    }
+}
+
+/// A list of [`ResourceProvider`] sorted by decreasing priority,
+/// together with the [`ExternalSourceProvider`]s and [`ImportFormat`]s registered by name.
 ///
 /// This allows resolving a given resource, which may occur in a Nemo program,
 /// to a reader (which return the actual by of e.g. a referenced file).
 ///
 /// The list of [`ResourceProviders`] can be customized by users of the Rust nemo crate.
 #[derive(Debug, Clone)]
-pub struct ResourceProviders(Rc<Vec<Box<dyn ResourceProvider>>>);
+pub struct ResourceProviders {
+    providers: Rc<Vec<Box<dyn ResourceProvider>>>,
+    external_sources: HashMap<String, Rc<dyn ExternalSourceProvider>>,
+    /// Formats in the order of their registration, which decides between formats with the same extension
+    formats: Vec<(String, Rc<dyn ImportFormat>)>,
+    input_cache: Option<InputCache>,
+    statistics: InputStatistics,
+}
 
 impl ResourceProviders {
     /// Construct using a list of [`ResourceProvider`]s
     pub fn from(r: Vec<Box<dyn ResourceProvider>>) -> Self {
-        Self(Rc::new(r))
+        Self {
+            providers: Rc::new(r),
+            external_sources: HashMap::new(),
+            formats: Vec::new(),
+            input_cache: None,
+            statistics: InputStatistics::default(),
+        }
     }
 
     /// Construct default with a base path for the `FileResourceProvider`
     pub fn with_base_path(base_path: Option<PathBuf>) -> Self {
-        Self(Rc::new(vec![
-            Box::<http::HTTPResourceProvider>::default(),
+        Self::from(vec![
+            Box::<BlockingResourceProvider<http::HTTPResourceProvider>>::default(),
             Box::new(file::FileResourceProvider::new(base_path)),
-        ]))
+        ])
     }
 
     /// Returns instance which is unable to resolve any resources.
     pub fn empty() -> Self {
-        Self(Rc::new(vec![]))
+        Self::from(vec![])
+    }
+
+    /// Register an [`ExternalSourceProvider`] under the given name,
+    /// which provides the facts of the external sources with this name.
+    pub fn with_external_source<P>(mut self, name: &str, provider: P) -> Self
+    where
+        P: ExternalSourceProvider + 'static,
+    {
+        self.external_sources
+            .insert(name.to_string(), Rc::new(provider));
+        self
+    }
+
+    /// Return the [`ExternalSourceProvider`] registered under the given name.
+    pub fn external_source(
+        &self,
+        name: &str,
+    ) -> Result<Rc<dyn ExternalSourceProvider>, ReadingError> {
+        self.external_sources
+            .get(name)
+            .cloned()
+            .ok_or_else(|| ReadingError::ExternalSourceNotProvided(name.to_string()))
+    }
+
+    /// Register an [`ImportFormat`] under the given name,
+    /// replacing a format registered earlier under the same name.
+    ///
+    /// If several formats have the extension of a resource, the one registered first is used.
+    pub fn with_format<F>(mut self, name: &str, format: F) -> Self
+    where
+        F: ImportFormat + 'static,
+    {
+        let format: Rc<dyn ImportFormat> = Rc::new(format);
+        match self.formats.iter_mut().find(|(other, _)| other == name) {
+            Some((_, registered)) => *registered = format,
+            None => self.formats.push((name.to_string(), format)),
+        }
+
+        self
+    }
+
+    /// Return the [`ImportFormat`] of the given source,
+    /// which is registered under the name given by the source or for the extension of its resource.
+    pub fn import_format(
+        &self,
+        source: &CustomFormatFile,
+    ) -> Result<Rc<dyn ImportFormat>, ReadingError> {
+        match &source.format {
+            Some(name) => self
+                .formats
+                .iter()
+                .find(|(other, _)| other == name)
+                .map(|(_, format)| format.clone())
+                .ok_or_else(|| ReadingError::FormatNotRegistered(name.clone())),
+            None => self
+                .formats
+                .iter()
+                .find(|(_, format)| format.matches_extension(&source.resource))
+                .map(|(_, format)| format.clone())
+                .ok_or_else(|| ReadingError::FormatNotDetected(source.resource.clone())),
+        }
+    }
+
+    /// Store the tables of the input sources in the given [`InputCache`],
+    /// and read them from there if the same inputs are loaded again.
+    pub fn with_input_cache(mut self, input_cache: InputCache) -> Self {
+        self.input_cache = Some(input_cache);
+        self
+    }
+
+    /// Return the [`InputCache`] storing the tables of the input sources, if any.
+    pub fn input_cache(&self) -> Option<&InputCache> {
+        self.input_cache.as_ref()
+    }
+
+    /// Return the statistics of the resources that have been opened via [`ResourceProviders::open_resource`].
+    pub fn input_statistics(&self) -> &InputStatistics {
+        &self.statistics
     }
 
     /// Resolves a resource.
     ///
     /// First checks if the resource can be opened as gzip, otherwise opens the file directly.
+    /// Members of archives are given as `archive.zip!data/facts.csv`, see [`archive`].
     pub fn open_resource(
         &self,
         resource: &Resource,
         try_gzip: bool,
     ) -> Result<Box<dyn Read>, ReadingError> {
-        for resource_provider in self.0.iter() {
+        if let Some((archive, member)) = archive::split_archive_resource(resource) {
+            let archive_reader = self.open_resource(&archive.to_string(), true)?;
+            let reader = self.statistics.reader(
+                resource,
+                archive::open_member(archive, archive_reader, member)?,
+            );
+            if !try_gzip {
+                return Ok(reader);
+            }
+
+            let mut reader = BufReader::new(reader);
+            return if reader.fill_buf()?.starts_with(&GZIP_MAGIC) {
+                Ok(Box::new(bufread::MultiGzDecoder::new(reader)))
+            } else {
+                Ok(Box::new(reader))
+            };
+        }
+
+        for resource_provider in self.providers.iter() {
             if let Some(reader) = resource_provider.open_resource(resource)? {
+                let reader = self.statistics.reader(resource, reader);
                 if !try_gzip {
                     return Ok(reader);
                 }
@@ -78,7 +276,7 @@ impl ResourceProviders {
                 } else {
                     // Try again without gzip, otherwise go to next provider
                     if let Some(reader) = resource_provider.open_resource(resource)? {
-                        return Ok(reader);
+                        return Ok(self.statistics.reader(resource, reader));
                     };
                 }
             }
@@ -0,0 +1,513 @@
+//! Resources inside of zip and tar archives, e.g. `archive.zip!data/facts.csv`.
+//!
+//! Archives are read as streams: their entries are skipped until the requested member is found,
+//! which is then extracted while it is read.
+//! Zip archives may contain stored or deflated members, and tar archives may be compressed with gzip.
+
+use std::io::{copy, sink, BufRead, BufReader, Read};
+
+use flate2::bufread::DeflateDecoder;
+use nemo_physical::error::{ExternalReadingError, ReadingError};
+use thiserror::Error;
+
+/// Extensions of archives whose members can be used as resources
+const ARCHIVE_EXTENSIONS: [&str; 4] = [".zip", ".tar", ".tar.gz", ".tgz"];
+
+/// Signature of the local header of a member of a zip archive
+const ZIP_LOCAL_HEADER: [u8; 4] = *b"PK\x03\x04";
+/// Signature of the optional data descriptor following a member of a zip archive
+const ZIP_DATA_DESCRIPTOR: [u8; 4] = *b"PK\x07\x08";
+/// Id of the extra field containing the 64 bit sizes of a member of a zip archive
+const ZIP64_EXTRA_FIELD: u16 = 0x0001;
+
+/// Size of the blocks of a tar archive
+const TAR_BLOCK_SIZE: u64 = 512;
+
+/// Errors that can occur when reading a member of an archive.
+#[derive(Error, Debug)]
+pub enum ArchiveError {
+    /// The archive does not contain the member.
+    #[error(r#"The archive "{archive}" does not contain "{member}"."#)]
+    MemberNotFound {
+        /// The resource of the archive
+        archive: String,
+        /// The path of the member
+        member: String,
+    },
+    /// The archive is neither a zip nor a tar archive.
+    #[error(r#"The archive "{0}" is neither a zip nor a tar archive, or it is malformed."#)]
+    Malformed(String),
+    /// The member of a zip archive is compressed with an unsupported method.
+    #[error(r#"The member "{member}" of the archive "{archive}" is compressed with the unsupported method {method}, only stored and deflated members are supported."#)]
+    UnsupportedCompression {
+        /// The resource of the archive
+        archive: String,
+        /// The path of the member
+        member: String,
+        /// The number of the compression method
+        method: u16,
+    },
+    /// The member of a zip archive is encrypted.
+    #[error(r#"The member "{member}" of the archive "{archive}" is encrypted."#)]
+    Encrypted {
+        /// The resource of the archive
+        archive: String,
+        /// The path of the member
+        member: String,
+    },
+}
+
+impl ExternalReadingError for ArchiveError {}
+
+impl From<ArchiveError> for ReadingError {
+    fn from(error: ArchiveError) -> Self {
+        ReadingError::ExternalReadingError(Box::new(error))
+    }
+}
+
+/// Split a resource referring to a member of an archive, like `archive.zip!data/facts.csv`,
+/// into the resource of the archive and the path of the member.
+///
+/// Returns `None` if the resource does not refer to a member of an archive,
+/// i.e., if no `!` follows one of the extensions `.zip`, `.tar`, `.tar.gz` or `.tgz`.
+pub(crate) fn split_archive_resource(resource: &str) -> Option<(&str, &str)> {
+    resource.match_indices('!').find_map(|(index, _)| {
+        let archive = &resource[..index];
+        let lowercase = archive.to_ascii_lowercase();
+
+        ARCHIVE_EXTENSIONS
+            .iter()
+            .any(|extension| lowercase.ends_with(extension))
+            .then(|| (archive, &resource[index + 1..]))
+    })
+}
+
+/// Normalize the path of a member of an archive, by removing leading `/` and `./`.
+fn normalize_member(path: &str) -> &str {
+    let mut path = path;
+    while let Some(rest) = path.strip_prefix("./").or_else(|| path.strip_prefix('/')) {
+        path = rest;
+    }
+    path
+}
+
+/// Open the member with the given path of the archive read from the given reader,
+/// whose type is detected from its content.
+pub(crate) fn open_member(
+    archive: &str,
+    reader: Box<dyn Read>,
+    member: &str,
+) -> Result<Box<dyn Read>, ReadingError> {
+    let mut archive = ArchiveReader {
+        archive,
+        member: normalize_member(member),
+        reader: BufReader::new(reader),
+    };
+
+    if archive.reader.fill_buf()?.starts_with(&ZIP_LOCAL_HEADER) {
+        archive.open_zip_member()
+    } else {
+        archive.open_tar_member()
+    }
+}
+
+/// Reader of the entries of an archive, looking for a member
+struct ArchiveReader<'a> {
+    archive: &'a str,
+    member: &'a str,
+    reader: BufReader<Box<dyn Read>>,
+}
+
+impl ArchiveReader<'_> {
+    fn not_found(&self) -> ReadingError {
+        ArchiveError::MemberNotFound {
+            archive: self.archive.to_string(),
+            member: self.member.to_string(),
+        }
+        .into()
+    }
+
+    fn malformed(&self) -> ReadingError {
+        ArchiveError::Malformed(self.archive.to_string()).into()
+    }
+
+    /// Fill the buffer with the next bytes of the archive,
+    /// returning `false` if the archive ends before.
+    fn read_bytes(&mut self, buffer: &mut [u8]) -> Result<bool, ReadingError> {
+        match self.reader.read_exact(buffer) {
+            Ok(()) => Ok(true),
+            Err(error) if error.kind() == std::io::ErrorKind::UnexpectedEof => Ok(false),
+            Err(error) => Err(error.into()),
+        }
+    }
+
+    /// Skip the given number of bytes of the archive.
+    fn skip(&mut self, length: u64) -> Result<(), ReadingError> {
+        if copy(&mut (&mut self.reader).take(length), &mut sink())? != length {
+            return Err(self.malformed());
+        }
+
+        Ok(())
+    }
+
+    /// Read the local headers of the members of a zip archive until the member is found.
+    ///
+    /// The central directory at the end of the archive is not used,
+    /// such that the archive does not need to be read completely.
+    fn open_zip_member(mut self) -> Result<Box<dyn Read>, ReadingError> {
+        let little_endian_16 = |bytes: &[u8]| u16::from_le_bytes([bytes[0], bytes[1]]);
+        let little_endian_32 =
+            |bytes: &[u8]| u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+
+        loop {
+            let mut signature = [0; 4];
+            if !self.read_bytes(&mut signature)? || signature != ZIP_LOCAL_HEADER {
+                // The local headers are followed by the central directory
+                return Err(self.not_found());
+            }
+
+            let mut header = [0; 26];
+            if !self.read_bytes(&mut header)? {
+                return Err(self.malformed());
+            }
+            let mut name = vec![0; usize::from(little_endian_16(&header[22..]))];
+            let mut extra = vec![0; usize::from(little_endian_16(&header[24..]))];
+            if !self.read_bytes(&mut name)? || !self.read_bytes(&mut extra)? {
+                return Err(self.malformed());
+            }
+
+            let flags = little_endian_16(&header[2..]);
+            let method = little_endian_16(&header[4..]);
+            let has_data_descriptor = flags & 0x08 != 0;
+
+            // Sizes not fitting into 32 bits are given in the zip64 extended information
+            let zip64 = zip64_field(&extra);
+            let compressed_size = match (little_endian_32(&header[14..]), zip64) {
+                (u32::MAX, Some(zip64)) => zip64_size(zip64, 8).ok_or_else(|| self.malformed())?,
+                (size, _) => u64::from(size),
+            };
+
+            let name = String::from_utf8_lossy(&name);
+            if normalize_member(&name) == self.member && !name.ends_with('/') {
+                if flags & 0x01 != 0 {
+                    return Err(ArchiveError::Encrypted {
+                        archive: self.archive.to_string(),
+                        member: self.member.to_string(),
+                    }
+                    .into());
+                }
+
+                return match method {
+                    0 if !has_data_descriptor => Ok(Box::new(self.reader.take(compressed_size))),
+                    8 => Ok(Box::new(DeflateDecoder::new(self.reader))),
+                    _ => Err(ArchiveError::UnsupportedCompression {
+                        archive: self.archive.to_string(),
+                        member: self.member.to_string(),
+                        method,
+                    }
+                    .into()),
+                };
+            }
+
+            if !has_data_descriptor {
+                self.skip(compressed_size)?;
+            } else if method == 8 {
+                // The size is only given after the member, so it has to be decompressed to find its end
+                copy(&mut DeflateDecoder::new(&mut self.reader), &mut sink())?;
+
+                if self.reader.fill_buf()?.starts_with(&ZIP_DATA_DESCRIPTOR) {
+                    self.skip(4)?;
+                }
+                // CRC-32 and the compressed and uncompressed sizes
+                self.skip(if zip64.is_some() { 4 + 16 } else { 4 + 8 })?;
+            } else {
+                return Err(self.malformed());
+            }
+        }
+    }
+
+    /// Read the headers of the entries of a tar archive until the member is found.
+    fn open_tar_member(mut self) -> Result<Box<dyn Read>, ReadingError> {
+        let mut long_name: Option<String> = None;
+        let mut is_first_entry = true;
+
+        loop {
+            let mut header = [0; TAR_BLOCK_SIZE as usize];
+            if !self.read_bytes(&mut header)? {
+                return Err(if is_first_entry {
+                    self.malformed()
+                } else {
+                    self.not_found()
+                });
+            }
+            if header.iter().all(|&byte| byte == 0) {
+                return Err(self.not_found());
+            }
+            if !tar_checksum_matches(&header) {
+                return Err(self.malformed());
+            }
+            is_first_entry = false;
+
+            let size = tar_number(&header[124..136]).ok_or_else(|| self.malformed())?;
+            let padding = size.next_multiple_of(TAR_BLOCK_SIZE) - size;
+
+            match header[156] {
+                // GNU long name and PAX extended header of the next entry
+                kind @ (b'L' | b'x') => {
+                    let mut data = vec![0; usize::try_from(size).map_err(|_| self.malformed())?];
+                    if !self.read_bytes(&mut data)? {
+                        return Err(self.malformed());
+                    }
+                    self.skip(padding)?;
+
+                    let data = String::from_utf8_lossy(&data);
+                    if kind == b'L' {
+                        long_name = Some(data.trim_end_matches('\0').to_string());
+                    } else if let Some(path) = pax_path(&data) {
+                        long_name = Some(path);
+                    }
+                }
+                kind => {
+                    let name = long_name.take().unwrap_or_else(|| tar_name(&header));
+                    let is_file = matches!(kind, 0 | b'0' | b'7');
+
+                    if is_file && normalize_member(&name) == self.member {
+                        return Ok(Box::new(self.reader.take(size)));
+                    }
+
+                    self.skip(size + padding)?;
+                }
+            }
+        }
+    }
+}
+
+/// Return the data of the zip64 extended information in the extra field of a zip archive member, if any.
+fn zip64_field(extra: &[u8]) -> Option<&[u8]> {
+    let mut fields = extra;
+
+    while fields.len() >= 4 {
+        let id = u16::from_le_bytes([fields[0], fields[1]]);
+        let length = usize::from(u16::from_le_bytes([fields[2], fields[3]]));
+        let data = fields.get(4..4 + length)?;
+
+        if id == ZIP64_EXTRA_FIELD {
+            return Some(data);
+        }
+
+        fields = &fields[4 + length..];
+    }
+
+    None
+}
+
+/// Return the size at the given offset of the zip64 extended information,
+/// which starts with the uncompressed size followed by the compressed size.
+fn zip64_size(zip64: &[u8], offset: usize) -> Option<u64> {
+    zip64
+        .get(offset..offset + 8)
+        .map(|bytes| u64::from_le_bytes(bytes.try_into().expect("slice has length 8")))
+}
+
+/// Check the checksum of the header of a tar entry,
+/// which is the sum of its bytes, where the checksum field itself counts as spaces.
+fn tar_checksum_matches(header: &[u8]) -> bool {
+    let sum: u64 = header
+        .iter()
+        .enumerate()
+        .map(|(index, &byte)| {
+            if (148..156).contains(&index) {
+                u64::from(b' ')
+            } else {
+                u64::from(byte)
+            }
+        })
+        .sum();
+
+    tar_number(&header[148..156]) == Some(sum)
+}
+
+/// Parse a number of a tar header, which is given in octal,
+/// or in base 256 if the first bit is set (for large sizes).
+fn tar_number(field: &[u8]) -> Option<u64> {
+    if field[0] & 0x80 != 0 {
+        return Some(
+            field[1..]
+                .iter()
+                .fold(u64::from(field[0] & 0x7f), |number, &byte| {
+                    (number << 8) | u64::from(byte)
+                }),
+        );
+    }
+
+    let digits = std::str::from_utf8(field).ok()?;
+    let digits = digits.trim_matches(|c: char| c == '\0' || c == ' ');
+    if digits.is_empty() {
+        return Some(0);
+    }
+
+    u64::from_str_radix(digits, 8).ok()
+}
+
+/// Return the path of a tar entry, which consists of the prefix (for ustar archives) and the name.
+fn tar_name(header: &[u8]) -> String {
+    let field = |bytes: &[u8]| {
+        let end = bytes
+            .iter()
+            .position(|&byte| byte == 0)
+            .unwrap_or(bytes.len());
+        String::from_utf8_lossy(&bytes[..end]).into_owned()
+    };
+    let name = field(&header[0..100]);
+
+    if &header[257..262] == b"ustar" {
+        let prefix = field(&header[345..500]);
+        if !prefix.is_empty() {
+            return format!("{prefix}/{name}");
+        }
+    }
+
+    name
+}
+
+/// Return the path given by the records `LENGTH path=PATH\n` of a PAX extended header.
+fn pax_path(data: &str) -> Option<String> {
+    data.lines().find_map(|record| {
+        let (_, key_value) = record.split_once(' ')?;
+        key_value.strip_prefix("path=").map(String::from)
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use std::io::{Cursor, Read, Write};
+
+    use assert_fs::TempDir;
+    use flate2::{
+        write::{DeflateEncoder, GzEncoder},
+        Compression,
+    };
+
+    use super::{open_member, split_archive_resource};
+    use crate::io::resource_providers::ResourceProviders;
+
+    fn read_member(archive: &[u8], member: &str) -> Result<String, String> {
+        let mut content = String::new();
+        open_member("archive", Box::new(Cursor::new(archive.to_vec())), member)
+            .map_err(|error| error.to_string())?
+            .read_to_string(&mut content)
+            .map_err(|error| error.to_string())?;
+        Ok(content)
+    }
+
+    /// Zip archive with a stored member and a deflated member followed by a data descriptor
+    fn zip_archive() -> Vec<u8> {
+        let mut archive = Vec::new();
+
+        let stored = b"a,b\n";
+        archive.extend(b"PK\x03\x04");
+        archive.extend([20, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+        archive.extend((stored.len() as u32).to_le_bytes());
+        archive.extend((stored.len() as u32).to_le_bytes());
+        archive.extend((9u16).to_le_bytes());
+        archive.extend((0u16).to_le_bytes());
+        archive.extend(b"edges.csv");
+        archive.extend(stored);
+
+        let deflated = b"b,c\nc,d\n";
+        let mut encoder = DeflateEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(deflated).unwrap();
+        let compressed = encoder.finish().unwrap();
+        archive.extend(b"PK\x03\x04");
+        archive.extend([20, 0, 0x08, 0, 8, 0, 0, 0, 0, 0]);
+        archive.extend([0; 12]);
+        archive.extend((14u16).to_le_bytes());
+        archive.extend((0u16).to_le_bytes());
+        archive.extend(b"data/edges.csv");
+        archive.extend(&compressed);
+        archive.extend(b"PK\x07\x08");
+        archive.extend([0; 4]);
+        archive.extend((compressed.len() as u32).to_le_bytes());
+        archive.extend((deflated.len() as u32).to_le_bytes());
+
+        // Start of the central directory
+        archive.extend(b"PK\x01\x02");
+        archive
+    }
+
+    /// Tar archive with a single member
+    fn tar_archive(name: &str, content: &[u8]) -> Vec<u8> {
+        let mut header = [0u8; 512];
+        header[..name.len()].copy_from_slice(name.as_bytes());
+        header[100..107].copy_from_slice(b"0000644");
+        header[124..135].copy_from_slice(format!("{:011o}", content.len()).as_bytes());
+        header[156] = b'0';
+        header[257..263].copy_from_slice(b"ustar\0");
+        header[148..156].copy_from_slice(b"        ");
+        let checksum: u32 = header.iter().map(|&byte| u32::from(byte)).sum();
+        header[148..155].copy_from_slice(format!("{checksum:06o}\0").as_bytes());
+
+        let mut archive = header.to_vec();
+        archive.extend(content);
+        archive.resize(archive.len().next_multiple_of(512) + 1024, 0);
+        archive
+    }
+
+    #[test]
+    fn split_resources() {
+        assert_eq!(
+            split_archive_resource("archive.zip!data/facts.csv"),
+            Some(("archive.zip", "data/facts.csv"))
+        );
+        assert_eq!(
+            split_archive_resource("https://example.org/dump!.TAR.GZ!facts.csv"),
+            Some(("https://example.org/dump!.TAR.GZ", "facts.csv"))
+        );
+        assert_eq!(split_archive_resource("facts!.csv"), None);
+    }
+
+    #[test]
+    fn zip_members() {
+        let archive = zip_archive();
+
+        assert_eq!(read_member(&archive, "edges.csv").unwrap(), "a,b\n");
+        assert_eq!(
+            read_member(&archive, "./data/edges.csv").unwrap(),
+            "b,c\nc,d\n"
+        );
+        assert!(read_member(&archive, "nodes.csv")
+            .unwrap_err()
+            .contains("does not contain \"nodes.csv\""));
+    }
+
+    #[test]
+    fn tar_members() {
+        let archive = tar_archive("./data/edges.csv", b"a,b\n");
+
+        assert_eq!(read_member(&archive, "data/edges.csv").unwrap(), "a,b\n");
+        assert!(read_member(&archive, "edges.csv").is_err());
+        assert!(read_member(b"not an archive", "edges.csv")
+            .unwrap_err()
+            .contains("neither a zip nor a tar archive"));
+    }
+
+    #[test]
+    fn compressed_archive_resource() {
+        let directory = TempDir::new().unwrap();
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder
+            .write_all(&tar_archive("data/edges.csv", b"a,b\n"))
+            .unwrap();
+        std::fs::write(directory.join("dump.tar.gz"), encoder.finish().unwrap()).unwrap();
+
+        let resource_providers = ResourceProviders::with_base_path(Some(directory.to_path_buf()));
+        let mut content = String::new();
+        resource_providers
+            .open_resource(&"dump.tar.gz!data/edges.csv".to_string(), true)
+            .unwrap()
+            .read_to_string(&mut content)
+            .unwrap();
+
+        assert_eq!(content, "a,b\n");
+    }
+}
@@ -0,0 +1,50 @@
+use std::fmt::Debug;
+
+use nemo_physical::error::ReadingError;
+
+use crate::model::Term;
+
+/// Provides the facts of an external source, which are computed by the application at runtime,
+/// e.g. from its state or from an in-memory data structure, instead of being read from a resource.
+///
+/// Programs use such a source via `@source p[2]: external("name") .`,
+/// where the provider is registered under the given name
+/// with [`ResourceProviders::with_external_source`][super::ResourceProviders::with_external_source].
+pub trait ExternalSourceProvider: Debug {
+    /// Return an iterator over the facts of the source, each of which is given by its terms.
+    ///
+    /// The facts are pulled from the iterator while the source is loaded,
+    /// which happens once for each engine that uses the source.
+    fn facts(&self) -> Result<Box<dyn Iterator<Item = Vec<Term>> + '_>, ReadingError>;
+}
+
+/// [`ExternalSourceProvider`] that obtains the facts from a callback,
+/// which is called whenever the source is loaded.
+pub struct CallbackSourceProvider<F> {
+    callback: F,
+}
+
+impl<F> CallbackSourceProvider<F> {
+    /// Create a new [`CallbackSourceProvider`].
+    pub fn new(callback: F) -> Self {
+        Self { callback }
+    }
+}
+
+impl<F> Debug for CallbackSourceProvider<F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CallbackSourceProvider")
+            .finish_non_exhaustive()
+    }
+}
+
+impl<F, I> ExternalSourceProvider for CallbackSourceProvider<F>
+where
+    F: Fn() -> I,
+    I: IntoIterator<Item = Vec<Term>>,
+    I::IntoIter: 'static,
+{
+    fn facts(&self) -> Result<Box<dyn Iterator<Item = Vec<Term>> + '_>, ReadingError> {
+        Ok(Box::new((self.callback)().into_iter()))
+    }
+}
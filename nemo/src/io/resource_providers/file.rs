@@ -1,13 +1,112 @@
-use std::{fs::File, io::Read, path::PathBuf};
+use std::{
+    fs::File,
+    io::Read,
+    path::{Path, PathBuf},
+};
 
 use nemo_physical::{error::ReadingError, table_reader::Resource};
-use path_slash::PathBufExt;
+use path_slash::{PathBufExt, PathExt};
+use percent_encoding::{percent_decode_str, utf8_percent_encode, AsciiSet, CONTROLS};
 
 use super::{is_iri, ResourceProvider};
 
+/// Characters that are percent-encoded in the path of a `file:` URI
+const PATH_ENCODE_SET: &AsciiSet = &CONTROLS
+    .add(b' ')
+    .add(b'"')
+    .add(b'#')
+    .add(b'%')
+    .add(b'<')
+    .add(b'>')
+    .add(b'?')
+    .add(b'`')
+    .add(b'{')
+    .add(b'}');
+
+/// Returns whether the resource is a Windows path starting with a drive letter,
+/// like `C:\data\edges.csv` or `C:/data/edges.csv`, or a UNC path, like `\\server\share\edges.csv`.
+///
+/// Such paths must not be mistaken for IRIs with the scheme `C`.
+pub(crate) fn is_windows_path(resource: &str) -> bool {
+    let bytes = resource.as_bytes();
+    let drive = bytes.len() >= 3
+        && bytes[0].is_ascii_alphabetic()
+        && bytes[1] == b':'
+        && matches!(bytes[2], b'/' | b'\\');
+
+    drive || resource.starts_with(r"\\")
+}
+
+/// Converts a `file:` URI into a path.
+///
+/// Only local files are supported, i.e., URIs whose host is empty or `localhost`,
+/// like `file:///C:/data/edges.csv` or `file:/data/edges.csv`,
+/// and UNC paths, like `file://server/share/edges.csv` or `file:////server/share/edges.csv`.
+/// Percent-encoded characters in the path are decoded.
+pub fn path_from_file_uri(uri: &str) -> Result<PathBuf, ReadingError> {
+    let invalid = || ReadingError::InvalidFileUri(uri.to_string());
+
+    let rest = uri.strip_prefix("file:").ok_or_else(invalid)?;
+    let path = match rest.strip_prefix("//") {
+        Some(authority_and_path) => {
+            let (host, path) = authority_and_path.split_at(
+                authority_and_path
+                    .find('/')
+                    .unwrap_or(authority_and_path.len()),
+            );
+            match host {
+                "" | "localhost" => path.to_string(),
+                host => format!("//{host}{path}"),
+            }
+        }
+        None => rest.to_string(),
+    };
+    if !path.starts_with('/') {
+        return Err(invalid());
+    }
+
+    let path = percent_decode_str(&path)
+        .decode_utf8()
+        .map_err(|_| invalid())?;
+    let path = match path.strip_prefix('/') {
+        Some(windows_path) if is_windows_path(windows_path) => windows_path,
+        _ => &path,
+    };
+
+    Ok(PathBuf::from_slash(path))
+}
+
+/// Converts an absolute path into a `file:` URI,
+/// e.g. `C:\data\edges.csv` into `file:///C:/data/edges.csv`
+/// and `\\server\share\edges.csv` into `file://server/share/edges.csv`.
+pub fn file_uri_from_path(path: &Path) -> String {
+    let path = path.to_slash_lossy();
+    // Canonical paths on Windows are verbatim paths like `\\?\C:\data` or `\\?\UNC\server\share`
+    let path = [r"\\?\UNC\", "//?/UNC/"]
+        .iter()
+        .find_map(|prefix| path.strip_prefix(prefix))
+        .map(|unc| format!("//{}", unc.replace('\\', "/")))
+        .or_else(|| {
+            [r"\\?\", "//?/"]
+                .iter()
+                .find_map(|prefix| path.strip_prefix(prefix))
+                .map(|verbatim| verbatim.replace('\\', "/"))
+        })
+        .unwrap_or_else(|| path.into_owned());
+
+    if let Some(unc) = path.strip_prefix("//") {
+        format!("file://{}", utf8_percent_encode(unc, PATH_ENCODE_SET))
+    } else if is_windows_path(&path) {
+        format!("file:///{}", utf8_percent_encode(&path, PATH_ENCODE_SET))
+    } else {
+        format!("file://{}", utf8_percent_encode(&path, PATH_ENCODE_SET))
+    }
+}
+
 /// Resolves resources from the OS-provided file system.
 ///
 /// Handles `file:` IRIs and non-IRI, (possibly relative) file paths.
+/// Backslashes in file paths are treated as path separators on all platforms.
 #[derive(Debug, Clone)]
 pub struct FileResourceProvider {
     base_path: Option<PathBuf>,
@@ -23,26 +122,20 @@ impl FileResourceProvider {
 impl FileResourceProvider {
     fn parse_resource(&self, resource: &Resource) -> Result<Option<PathBuf>, ReadingError> {
         if is_iri(resource) {
-            if resource.starts_with("file://") {
-                // File URI. We only support local files, i.e., URIs
-                // where the host part is either empty or `localhost`.
-
-                let path = resource
-                    .strip_prefix("file://localhost")
-                    .or_else(|| resource.strip_prefix("file://"))
-                    .ok_or_else(|| ReadingError::InvalidFileUri(resource.to_string()))?;
-                Ok(Some(PathBuf::from_slash(path)))
+            if resource.starts_with("file:") {
+                Ok(Some(path_from_file_uri(resource)?))
             } else {
                 // Non-file IRI, file resource provider is not responsible
                 Ok(None)
             }
         } else {
             // Not a valid URI, interpret as path directly
+            let path = PathBuf::from_backslash(resource);
             Ok(Some(
                 self.base_path
                     .as_ref()
-                    .map(|bp| bp.join(resource))
-                    .unwrap_or(resource.into()),
+                    .map(|bp| bp.join(&path))
+                    .unwrap_or(path),
             ))
         }
     }
@@ -59,3 +152,75 @@ impl ResourceProvider for FileResourceProvider {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use std::path::PathBuf;
+
+    use path_slash::PathBufExt;
+
+    use super::{file_uri_from_path, path_from_file_uri, FileResourceProvider};
+
+    #[test]
+    fn file_uris() {
+        let path = |uri: &str| path_from_file_uri(uri).unwrap();
+
+        assert_eq!(
+            path("file:///data/edges.csv"),
+            PathBuf::from("/data/edges.csv")
+        );
+        assert_eq!(
+            path("file://localhost/data/my%20edges.csv"),
+            PathBuf::from("/data/my edges.csv")
+        );
+        assert_eq!(
+            path("file:/data/edges.csv"),
+            PathBuf::from("/data/edges.csv")
+        );
+        assert_eq!(
+            path("file:///C:/data/edges.csv"),
+            PathBuf::from_slash("C:/data/edges.csv")
+        );
+        assert_eq!(
+            path("file://server/share/edges.csv"),
+            PathBuf::from_slash("//server/share/edges.csv")
+        );
+        assert_eq!(
+            path("file:////server/share/edges.csv"),
+            PathBuf::from_slash("//server/share/edges.csv")
+        );
+        assert!(path_from_file_uri("file:edges.csv").is_err());
+
+        for uri in [
+            "file:///data/my%20edges.csv",
+            "file:///C:/data/edges.csv",
+            "file://server/share/edges.csv",
+        ] {
+            assert_eq!(file_uri_from_path(&path(uri)), uri);
+        }
+    }
+
+    #[test]
+    fn windows_paths() {
+        let provider = FileResourceProvider::new(Some(PathBuf::from("base")));
+        let path = |resource: &str| {
+            provider
+                .parse_resource(&resource.to_string())
+                .unwrap()
+                .unwrap()
+        };
+
+        assert_eq!(
+            path(r"data\edges.csv"),
+            PathBuf::from_slash("base/data/edges.csv")
+        );
+        assert_eq!(
+            path("C:/data/edges.csv"),
+            PathBuf::from("base").join(PathBuf::from_slash("C:/data/edges.csv"))
+        );
+        assert_eq!(
+            path(r"\\server\share\edges.csv"),
+            PathBuf::from_slash("//server/share/edges.csv")
+        );
+    }
+}
@@ -0,0 +1,284 @@
+use nemo_physical::{
+    datatypes::Double,
+    error::{ExternalReadingError, ReadingError},
+};
+use thiserror::Error;
+use tonic::{
+    client::Grpc,
+    codec::ProstCodec,
+    codegen::http::uri::PathAndQuery,
+    transport::{Channel, Endpoint},
+    Request,
+};
+
+use crate::model::{Identifier, NumericLiteral, RdfLiteral, Term};
+
+use super::external::ExternalSourceProvider;
+
+/// Messages of the protocol in `nemo/proto/facts.proto`.
+pub mod proto {
+    /// Request for the facts of an external source.
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct FactsRequest {
+        /// Name of the external source
+        #[prost(string, tag = "1")]
+        pub source: ::prost::alloc::string::String,
+    }
+
+    /// A single fact, given by its terms.
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct Fact {
+        /// The terms of the fact
+        #[prost(message, repeated, tag = "1")]
+        pub terms: ::prost::alloc::vec::Vec<Value>,
+    }
+
+    /// A single term of a fact.
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct Value {
+        /// The term, which must be set
+        #[prost(oneof = "value::Value", tags = "1, 2, 3, 4, 5, 6")]
+        pub value: ::core::option::Option<value::Value>,
+    }
+
+    /// Nested types of [`Value`].
+    pub mod value {
+        /// The different kinds of terms.
+        #[derive(Clone, PartialEq, ::prost::Oneof)]
+        pub enum Value {
+            /// An (abstract) constant
+            #[prost(string, tag = "1")]
+            Constant(::prost::alloc::string::String),
+            /// A string literal
+            #[prost(string, tag = "2")]
+            String(::prost::alloc::string::String),
+            /// An integer literal
+            #[prost(int64, tag = "3")]
+            Integer(i64),
+            /// A double literal
+            #[prost(double, tag = "4")]
+            Double(f64),
+            /// A language string
+            #[prost(message, tag = "5")]
+            LanguageString(super::LanguageString),
+            /// A literal with a datatype
+            #[prost(message, tag = "6")]
+            DatatypeValue(super::DatatypeValue),
+        }
+    }
+
+    /// A language string.
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct LanguageString {
+        /// The literal value
+        #[prost(string, tag = "1")]
+        pub value: ::prost::alloc::string::String,
+        /// The language tag
+        #[prost(string, tag = "2")]
+        pub tag: ::prost::alloc::string::String,
+    }
+
+    /// A literal with a datatype.
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct DatatypeValue {
+        /// The literal value
+        #[prost(string, tag = "1")]
+        pub value: ::prost::alloc::string::String,
+        /// The datatype IRI
+        #[prost(string, tag = "2")]
+        pub datatype: ::prost::alloc::string::String,
+    }
+}
+
+/// Path of the method that streams the facts of an external source.
+const STREAM_FACTS: &str = "/nemo.facts.FactService/StreamFacts";
+
+/// Errors that can occur when obtaining facts from a gRPC service.
+#[derive(Error, Debug)]
+pub enum GrpcReadingError {
+    /// The service could not be reached.
+    #[error(transparent)]
+    Transport(#[from] tonic::transport::Error),
+    /// The service answered with an error.
+    #[error("The gRPC service failed with: {0}")]
+    Status(#[from] tonic::Status),
+    /// A fact sent by the service contains a term without value.
+    #[error("The gRPC service sent a term without value for the external source \"{0}\".")]
+    MissingValue(String),
+}
+
+impl ExternalReadingError for GrpcReadingError {}
+
+impl From<GrpcReadingError> for ReadingError {
+    fn from(error: GrpcReadingError) -> Self {
+        ReadingError::ExternalReadingError(Box::new(error))
+    }
+}
+
+/// [`ExternalSourceProvider`] that obtains the facts from a gRPC service,
+/// which implements the `FactService` of `nemo/proto/facts.proto`.
+///
+/// The service is asked for the facts of the source by its name
+/// and streams them while the source is loaded.
+#[derive(Debug, Clone)]
+pub struct GrpcSourceProvider {
+    endpoint: String,
+    source: String,
+}
+
+impl GrpcSourceProvider {
+    /// Create a new [`GrpcSourceProvider`], which requests the facts of the external source
+    /// with the given name from the service at the given endpoint (e.g. `http://localhost:50051`).
+    pub fn new(endpoint: &str, source: &str) -> Self {
+        Self {
+            endpoint: endpoint.to_string(),
+            source: source.to_string(),
+        }
+    }
+
+    async fn stream_facts(&self) -> Result<Vec<Vec<Term>>, ReadingError> {
+        let channel: Channel = Endpoint::from_shared(self.endpoint.clone())
+            .map_err(GrpcReadingError::from)?
+            .connect()
+            .await
+            .map_err(GrpcReadingError::from)?;
+
+        let mut client = Grpc::new(channel);
+        client.ready().await.map_err(GrpcReadingError::from)?;
+
+        let request = Request::new(proto::FactsRequest {
+            source: self.source.clone(),
+        });
+        let mut stream = client
+            .server_streaming(
+                request,
+                PathAndQuery::from_static(STREAM_FACTS),
+                ProstCodec::<proto::FactsRequest, proto::Fact>::default(),
+            )
+            .await
+            .map_err(GrpcReadingError::from)?
+            .into_inner();
+
+        let mut facts = Vec::new();
+        while let Some(fact) = stream.message().await.map_err(GrpcReadingError::from)? {
+            facts.push(self.fact_to_terms(fact)?);
+        }
+
+        Ok(facts)
+    }
+
+    fn fact_to_terms(&self, fact: proto::Fact) -> Result<Vec<Term>, ReadingError> {
+        fact.terms
+            .into_iter()
+            .map(|term| {
+                use proto::value::Value;
+
+                Ok(
+                    match term
+                        .value
+                        .ok_or_else(|| GrpcReadingError::MissingValue(self.source.clone()))?
+                    {
+                        Value::Constant(name) => Term::Constant(Identifier(name)),
+                        Value::String(string) => Term::StringLiteral(string),
+                        Value::Integer(integer) => {
+                            Term::NumericLiteral(NumericLiteral::Integer(integer))
+                        }
+                        Value::Double(double) => {
+                            Term::NumericLiteral(NumericLiteral::Double(Double::new(double)?))
+                        }
+                        Value::LanguageString(proto::LanguageString { value, tag }) => {
+                            Term::RdfLiteral(RdfLiteral::LanguageString { value, tag })
+                        }
+                        Value::DatatypeValue(proto::DatatypeValue { value, datatype }) => {
+                            Term::RdfLiteral(RdfLiteral::DatatypeValue { value, datatype })
+                        }
+                    },
+                )
+            })
+            .collect()
+    }
+}
+
+impl ExternalSourceProvider for GrpcSourceProvider {
+    fn facts(&self) -> Result<Box<dyn Iterator<Item = Vec<Term>> + '_>, ReadingError> {
+        let rt = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()?;
+        let facts = rt.block_on(self.stream_facts())?;
+
+        Ok(Box::new(facts.into_iter()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use nemo_physical::{datatypes::Double, error::ReadingError};
+
+    use crate::{
+        io::resource_providers::external::ExternalSourceProvider,
+        model::{Identifier, NumericLiteral, RdfLiteral, Term},
+    };
+
+    use super::{
+        proto::{value::Value, Fact, LanguageString},
+        GrpcSourceProvider,
+    };
+
+    fn fact(values: Vec<Option<Value>>) -> Fact {
+        Fact {
+            terms: values
+                .into_iter()
+                .map(|value| super::proto::Value { value })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn fact_to_terms() {
+        let provider = GrpcSourceProvider::new("http://localhost:50051", "graph");
+
+        let terms = provider
+            .fact_to_terms(fact(vec![
+                Some(Value::Constant("a".to_string())),
+                Some(Value::String("b".to_string())),
+                Some(Value::Integer(42)),
+                Some(Value::Double(0.5)),
+                Some(Value::LanguageString(LanguageString {
+                    value: "c".to_string(),
+                    tag: "en".to_string(),
+                })),
+            ]))
+            .unwrap();
+        assert_eq!(
+            terms,
+            vec![
+                Term::Constant(Identifier("a".to_string())),
+                Term::StringLiteral("b".to_string()),
+                Term::NumericLiteral(NumericLiteral::Integer(42)),
+                Term::NumericLiteral(NumericLiteral::Double(Double::new(0.5).unwrap())),
+                Term::RdfLiteral(RdfLiteral::LanguageString {
+                    value: "c".to_string(),
+                    tag: "en".to_string()
+                }),
+            ]
+        );
+
+        assert!(matches!(
+            provider.fact_to_terms(fact(vec![None])),
+            Err(ReadingError::ExternalReadingError(_))
+        ));
+        assert!(matches!(
+            provider.fact_to_terms(fact(vec![Some(Value::Double(f64::NAN))])),
+            Err(ReadingError::FloatIsNaN(_))
+        ));
+    }
+
+    #[test]
+    fn unreachable_service() {
+        let provider = GrpcSourceProvider::new("http://127.0.0.1:1", "graph");
+        assert!(matches!(
+            provider.facts(),
+            Err(ReadingError::ExternalReadingError(_))
+        ));
+    }
+}
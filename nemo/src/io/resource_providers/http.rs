@@ -1,72 +1,61 @@
-use std::io::Read;
+use nemo_physical::table_reader::Resource;
 
-use nemo_physical::{error::ReadingError, table_reader::Resource};
-
-use super::{is_iri, ResourceProvider};
+use super::{
+    is_iri,
+    stream::{ResourceFuture, ResourceStream},
+    AsyncResourceProvider,
+};
 
 /// Resolves resources using HTTP or HTTPS.
 ///
 /// Handles `http:` and `https:` IRIs.
+/// The content of a resource is read while it is downloaded.
 #[derive(Debug, Clone, Copy, Default)]
 pub struct HTTPResourceProvider {}
 
-/// The result of fetching a resource via HTTP.
-#[derive(Debug, Clone)]
+/// The content of a resource that is downloaded via HTTP.
+#[derive(Debug)]
 pub struct HTTPResource {
-    /// IRI that this resource was fetched from
+    /// IRI that this resource is downloaded from
     url: Resource,
-    /// Content of the resource
-    content: Vec<u8>,
+    /// Response whose body has not been read yet
+    response: reqwest::Response,
 }
 
 impl HTTPResource {
-    /// Return the IRI this resource was fetched from.
+    /// Return the IRI this resource is downloaded from.
     pub fn url(&self) -> &Resource {
         &self.url
     }
-
-    /// Return the content of this resource.
-    pub fn content(&self) -> String {
-        String::from_utf8(self.content.clone()).expect("is valid UTF-8")
-    }
 }
 
-impl Read for HTTPResource {
-    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
-        let amount = self.content.as_slice().read(buf)?;
-        self.content.drain(0..amount);
-
-        Ok(amount)
+impl ResourceStream for HTTPResource {
+    fn content_length(&self) -> Option<u64> {
+        self.response.content_length()
     }
-}
 
-impl HTTPResourceProvider {
-    async fn get(url: &Resource) -> Result<HTTPResource, ReadingError> {
-        let response = reqwest::get(url).await?;
-        let content = response.text().await?;
-
-        Ok(HTTPResource {
-            url: url.to_string(),
-            content: content.into(),
-        })
+    fn next_chunk(&mut self) -> ResourceFuture<'_, Option<Vec<u8>>> {
+        Box::pin(async move { Ok(self.response.chunk().await?.map(Vec::from)) })
     }
 }
 
-impl ResourceProvider for HTTPResourceProvider {
-    fn open_resource(&self, resource: &Resource) -> Result<Option<Box<dyn Read>>, ReadingError> {
-        if !is_iri(resource) {
-            return Ok(None);
-        }
-
-        if !(resource.starts_with("http:") || resource.starts_with("https:")) {
-            // Non-http IRI, resource provider is not responsible
-            return Ok(None);
-        }
+impl AsyncResourceProvider for HTTPResourceProvider {
+    fn handles(&self, resource: &Resource) -> bool {
+        // Non-http IRIs are not handled by this provider
+        is_iri(resource) && (resource.starts_with("http:") || resource.starts_with("https:"))
+    }
 
-        let rt = tokio::runtime::Builder::new_current_thread()
-            .enable_all()
-            .build()?;
-        let response = rt.block_on(Self::get(resource))?;
-        Ok(Some(Box::new(response)))
+    fn open_resource_stream<'a>(
+        &'a self,
+        resource: &'a Resource,
+    ) -> ResourceFuture<'a, Option<Box<dyn ResourceStream>>> {
+        Box::pin(async move {
+            let stream: Box<dyn ResourceStream> = Box::new(HTTPResource {
+                url: resource.to_string(),
+                response: reqwest::get(resource).await?,
+            });
+
+            Ok(Some(stream))
+        })
     }
 }
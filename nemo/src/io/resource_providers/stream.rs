@@ -0,0 +1,192 @@
+use std::{collections::VecDeque, future::Future, io::Read, pin::Pin};
+
+use bytesize::ByteSize;
+use nemo_physical::{error::ReadingError, table_reader::Resource};
+
+/// A future returned by the asynchronous parts of the resource provider interface.
+pub type ResourceFuture<'a, T> = Pin<Box<dyn Future<Output = Result<T, ReadingError>> + 'a>>;
+
+/// The content of a resource, which is read in chunks, e.g. while it is downloaded.
+pub trait ResourceStream {
+    /// Return the length of the content in bytes, if it is known before reading it.
+    ///
+    /// This is only a hint, e.g. for reporting progress,
+    /// and does not need to match the number of bytes that are actually returned.
+    fn content_length(&self) -> Option<u64> {
+        None
+    }
+
+    /// Return the next chunk of the content, or `None` if all of it has been returned.
+    fn next_chunk(&mut self) -> ResourceFuture<'_, Option<Vec<u8>>>;
+}
+
+/// [`ResourceStream`] whose chunks are given in advance.
+#[derive(Debug, Clone, Default)]
+pub struct ChunkedResource {
+    chunks: VecDeque<Vec<u8>>,
+}
+
+impl ChunkedResource {
+    /// Create a new [`ChunkedResource`] returning the given chunks in order.
+    pub fn new(chunks: impl IntoIterator<Item = Vec<u8>>) -> Self {
+        Self {
+            chunks: chunks.into_iter().collect(),
+        }
+    }
+}
+
+impl ResourceStream for ChunkedResource {
+    fn content_length(&self) -> Option<u64> {
+        Some(self.chunks.iter().map(|chunk| chunk.len() as u64).sum())
+    }
+
+    fn next_chunk(&mut self) -> ResourceFuture<'_, Option<Vec<u8>>> {
+        let chunk = self.chunks.pop_front();
+        Box::pin(async move { Ok(chunk) })
+    }
+}
+
+/// Implements [`Read`] for a [`ResourceStream`],
+/// waiting for each chunk only once all previous chunks have been read.
+pub struct StreamReader {
+    runtime: tokio::runtime::Runtime,
+    stream: Box<dyn ResourceStream>,
+    chunk: Vec<u8>,
+    position: usize,
+}
+
+impl std::fmt::Debug for StreamReader {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("StreamReader")
+            .field("position", &self.position)
+            .finish_non_exhaustive()
+    }
+}
+
+impl StreamReader {
+    /// Create a new [`StreamReader`], which waits for the chunks on the given runtime.
+    pub fn new(
+        runtime: tokio::runtime::Runtime,
+        resource: &Resource,
+        stream: Box<dyn ResourceStream>,
+    ) -> Self {
+        match stream.content_length() {
+            Some(length) => tracing::info!("Streaming {resource} ({})", ByteSize(length)),
+            None => tracing::info!("Streaming {resource}"),
+        }
+
+        Self {
+            runtime,
+            stream,
+            chunk: Vec::new(),
+            position: 0,
+        }
+    }
+}
+
+impl Read for StreamReader {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        while self.position == self.chunk.len() {
+            match self
+                .runtime
+                .block_on(self.stream.next_chunk())
+                .map_err(|error| std::io::Error::other(error.to_string()))?
+            {
+                Some(chunk) => {
+                    self.chunk = chunk;
+                    self.position = 0;
+                }
+                None => return Ok(0),
+            }
+        }
+
+        let amount = (&self.chunk[self.position..]).read(buf)?;
+        self.position += amount;
+
+        Ok(amount)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::io::Read;
+
+    use nemo_physical::table_reader::Resource;
+
+    use crate::io::resource_providers::{
+        AsyncResourceProvider, BlockingResourceProvider, ResourceProvider, ResourceProviders,
+    };
+
+    use super::{ChunkedResource, ResourceFuture, ResourceStream};
+
+    /// Provides resources starting with `memory:` by splitting their name into chunks,
+    /// where `;` separates lines.
+    #[derive(Debug)]
+    struct MemoryProvider;
+
+    impl AsyncResourceProvider for MemoryProvider {
+        fn handles(&self, resource: &Resource) -> bool {
+            resource.starts_with("memory:")
+        }
+
+        fn open_resource_stream<'a>(
+            &'a self,
+            resource: &'a Resource,
+        ) -> ResourceFuture<'a, Option<Box<dyn ResourceStream>>> {
+            Box::pin(async move {
+                let Some(content) = resource.strip_prefix("memory:") else {
+                    return Ok(None);
+                };
+                let chunks = content
+                    .replace(';', "\n")
+                    .as_bytes()
+                    .chunks(3)
+                    .map(|chunk| chunk.to_vec())
+                    .collect::<Vec<_>>();
+                let stream: Box<dyn ResourceStream> = Box::new(ChunkedResource::new(chunks));
+
+                Ok(Some(stream))
+            })
+        }
+    }
+
+    #[test]
+    fn read_chunks() {
+        let mut content = String::new();
+        BlockingResourceProvider::new(MemoryProvider)
+            .open_resource(&"memory:a,b;b,c".to_string())
+            .unwrap()
+            .unwrap()
+            .read_to_string(&mut content)
+            .unwrap();
+        assert_eq!(content, "a,b\nb,c");
+
+        assert!(BlockingResourceProvider::new(MemoryProvider)
+            .open_resource(&"edges.csv".to_string())
+            .unwrap()
+            .is_none());
+        assert_eq!(
+            ChunkedResource::new([vec![1, 2], vec![], vec![3]]).content_length(),
+            Some(3)
+        );
+    }
+
+    #[test]
+    fn async_resource_provider() {
+        let resource_providers = ResourceProviders::from(vec![Box::new(
+            BlockingResourceProvider::new(MemoryProvider),
+        )]);
+
+        let mut content = String::new();
+        resource_providers
+            .open_resource(&"memory:a,b;b,c;c,d".to_string(), true)
+            .unwrap()
+            .read_to_string(&mut content)
+            .unwrap();
+        assert_eq!(content, "a,b\nb,c\nc,d");
+
+        assert!(resource_providers
+            .open_resource(&"edges.csv".to_string(), true)
+            .is_err());
+    }
+}
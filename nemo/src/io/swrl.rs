@@ -0,0 +1,551 @@
+//! Import of [SWRL](https://www.w3.org/submissions/SWRL/) rules that are embedded in OWL ontologies,
+//! which are given in RDF/XML or Turtle.
+//!
+//! The rules (resources of type `swrl:Imp`) are translated into Nemo rules over the predicate [`TRIPLE_PREDICATE`],
+//! such that they apply to the triples of the ontology (and any other RDF data loaded into this predicate).
+//! Class atoms `C(?x)` become atoms `triple(?x, rdf:type, C)`,
+//! and individual or data valued property atoms `p(?x, ?y)` become atoms `triple(?x, p, ?y)`.
+//! Since the variables of the rules are only bound to terms that occur in the loaded triples,
+//! the translation follows the semantics of DL-safe SWRL rules.
+//!
+//! Rules that use built-ins, complex class expressions, data ranges, (in)equality atoms or anonymous individuals
+//! are not translated, but reported together with the atom that uses the unsupported construct.
+
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Write,
+};
+
+use oxiri::Iri;
+use rio_api::{
+    model::{Literal, Subject, Term as RioTerm, Triple},
+    parser::TriplesParser,
+};
+use rio_turtle::{TurtleError, TurtleParser};
+use rio_xml::{RdfXmlError, RdfXmlParser};
+use thiserror::Error;
+
+use crate::io::r2rml::TRIPLE_PREDICATE;
+
+/// Namespace of the SWRL vocabulary
+const SWRL: &str = "http://www.w3.org/2003/11/swrl#";
+/// IRI of `rdf:type`
+const RDF_TYPE: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#type";
+/// IRI of `rdf:first`
+const RDF_FIRST: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#first";
+/// IRI of `rdf:rest`
+const RDF_REST: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#rest";
+/// IRI of `rdf:nil`
+const RDF_NIL: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#nil";
+/// IRI of `rdfs:label`
+const RDFS_LABEL: &str = "http://www.w3.org/2000/01/rdf-schema#label";
+
+/// Errors that can occur when importing the SWRL rules of an ontology.
+#[derive(Error, Debug)]
+pub enum SwrlError {
+    /// The ontology is not valid Turtle.
+    #[error(transparent)]
+    Turtle(#[from] TurtleError),
+    /// The ontology is not valid RDF/XML.
+    #[error(transparent)]
+    RdfXml(#[from] RdfXmlError),
+    /// The given base IRI is not valid.
+    #[error("The base IRI \"{0}\" is not valid.")]
+    InvalidBase(String),
+    /// An atom of a rule uses a construct that is not supported.
+    #[error("The {0} uses {1}, which is not supported.")]
+    Unsupported(String, &'static str),
+    /// A rule or one of its atoms lacks a required property.
+    #[error("The {0} is not well-formed.")]
+    Malformed(String),
+    /// A variable of the head of a rule does not occur in its body.
+    #[error("The variable {1} of the head of the SWRL rule {0} does not occur in its body.")]
+    UnsafeVariable(String, String),
+}
+
+/// An RDF term of the ontology.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum Node {
+    Iri(String),
+    Blank(String),
+    /// A literal, given in Nemo syntax
+    Literal(String),
+}
+
+impl Node {
+    fn from_subject(subject: Subject) -> Option<Self> {
+        match subject {
+            Subject::NamedNode(node) => Some(Node::Iri(node.iri.to_string())),
+            Subject::BlankNode(node) => Some(Node::Blank(node.id.to_string())),
+            Subject::Triple(_) => None,
+        }
+    }
+
+    fn from_term(term: RioTerm) -> Option<Self> {
+        let quoted = |value: &str| {
+            format!(
+                "\"{}\"",
+                value
+                    .replace('\\', "\\\\")
+                    .replace('"', "\\\"")
+                    .replace('\n', "\\n")
+                    .replace('\r', "\\r")
+            )
+        };
+
+        match term {
+            RioTerm::NamedNode(node) => Some(Node::Iri(node.iri.to_string())),
+            RioTerm::BlankNode(node) => Some(Node::Blank(node.id.to_string())),
+            RioTerm::Literal(Literal::Simple { value }) => Some(Node::Literal(quoted(value))),
+            RioTerm::Literal(Literal::LanguageTaggedString { value, language }) => {
+                Some(Node::Literal(format!("{}@{language}", quoted(value))))
+            }
+            RioTerm::Literal(Literal::Typed { value, datatype }) => Some(Node::Literal(format!(
+                "{}^^<{}>",
+                quoted(value),
+                datatype.iri
+            ))),
+            RioTerm::Triple(_) => None,
+        }
+    }
+}
+
+/// The triples of an ontology, indexed by their subject.
+#[derive(Debug, Default)]
+struct Graph {
+    triples: HashMap<Node, Vec<(String, Node)>>,
+    /// Subjects of type `swrl:Imp`, in the order of their occurrence
+    rules: Vec<Node>,
+}
+
+impl Graph {
+    fn add(&mut self, triple: Triple) {
+        let (Some(subject), Some(object)) = (
+            Node::from_subject(triple.subject),
+            Node::from_term(triple.object),
+        ) else {
+            return;
+        };
+
+        if triple.predicate.iri == RDF_TYPE
+            && object == Node::Iri(format!("{SWRL}Imp"))
+            && !self.rules.contains(&subject)
+        {
+            self.rules.push(subject.clone());
+        }
+
+        self.triples
+            .entry(subject)
+            .or_default()
+            .push((triple.predicate.iri.to_string(), object));
+    }
+
+    /// Return the object of the given subject and property, if there is one.
+    fn object<'a>(&'a self, subject: &Node, property: &str) -> Option<&'a Node> {
+        self.triples
+            .get(subject)?
+            .iter()
+            .find(|(predicate, _)| predicate == property)
+            .map(|(_, object)| object)
+    }
+
+    /// Return whether the given node is an instance of the given SWRL class.
+    fn is_a(&self, node: &Node, class: &str) -> bool {
+        self.triples.get(node).is_some_and(|triples| {
+            triples.iter().any(|(predicate, object)| {
+                predicate == RDF_TYPE && *object == Node::Iri(format!("{SWRL}{class}"))
+            })
+        })
+    }
+
+    /// Return the elements of an RDF list, or `None` if the list is not well-formed.
+    fn list<'a>(&'a self, mut list: &'a Node) -> Option<Vec<&'a Node>> {
+        let mut elements = Vec::new();
+        let mut visited = HashSet::new();
+
+        while *list != Node::Iri(RDF_NIL.to_string()) {
+            if !visited.insert(list) {
+                return None;
+            }
+
+            elements.push(self.object(list, RDF_FIRST)?);
+            list = self.object(list, RDF_REST)?;
+        }
+
+        Some(elements)
+    }
+
+    /// Return a name of the given rule for reporting,
+    /// which is its IRI, its label, or its position among the rules of the ontology.
+    fn rule_name(&self, rule: &Node, index: usize) -> String {
+        match (rule, self.object(rule, RDFS_LABEL)) {
+            (Node::Iri(iri), _) => format!("<{iri}>"),
+            (_, Some(Node::Literal(label))) => label.clone(),
+            _ => format!("number {}", index + 1),
+        }
+    }
+}
+
+/// An argument of an atom of a SWRL rule.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum SwrlTerm {
+    /// A variable, given by its IRI
+    Variable(String),
+    /// An individual or a literal, given in Nemo syntax
+    Constant(String),
+}
+
+/// An atom of a SWRL rule, given as triple.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct SwrlAtom([SwrlTerm; 3]);
+
+impl SwrlAtom {
+    fn variables(&self) -> impl Iterator<Item = &String> {
+        self.0.iter().filter_map(|term| match term {
+            SwrlTerm::Variable(variable) => Some(variable),
+            SwrlTerm::Constant(_) => None,
+        })
+    }
+
+    fn to_nemo(&self, variables: &HashMap<&String, String>) -> String {
+        let terms = self
+            .0
+            .iter()
+            .map(|term| match term {
+                SwrlTerm::Variable(variable) => format!("?{}", variables[variable]),
+                SwrlTerm::Constant(constant) => constant.clone(),
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+        format!("{TRIPLE_PREDICATE}({terms})")
+    }
+}
+
+/// A SWRL rule, where facts are rules with an empty body.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct SwrlRule {
+    head: Vec<SwrlAtom>,
+    body: Vec<SwrlAtom>,
+}
+
+impl SwrlRule {
+    /// Read the rule given by the node `rule`.
+    fn parse(graph: &Graph, rule: &Node, name: &str) -> Result<Self, SwrlError> {
+        let atoms = |property: &str, part: &str| {
+            let list = match graph.object(rule, &format!("{SWRL}{property}")) {
+                Some(list) => graph.list(list).ok_or_else(|| {
+                    SwrlError::Malformed(format!("{part} of the SWRL rule {name}"))
+                })?,
+                None => Vec::new(),
+            };
+
+            list.into_iter()
+                .enumerate()
+                .map(|(index, atom)| {
+                    Self::atom(
+                        graph,
+                        atom,
+                        format!("{part} atom {} of the SWRL rule {name}", index + 1),
+                    )
+                })
+                .collect::<Result<Vec<_>, _>>()
+        };
+
+        let body = atoms("body", "body")?;
+        let head = atoms("head", "head")?;
+
+        let safe = body
+            .iter()
+            .flat_map(SwrlAtom::variables)
+            .collect::<HashSet<_>>();
+        if let Some(variable) = head
+            .iter()
+            .flat_map(SwrlAtom::variables)
+            .find(|variable| !safe.contains(variable))
+        {
+            return Err(SwrlError::UnsafeVariable(
+                name.to_string(),
+                variable_name(variable),
+            ));
+        }
+
+        Ok(Self { head, body })
+    }
+
+    /// Translate the atom given by the node `atom` into a triple.
+    fn atom(graph: &Graph, atom: &Node, location: String) -> Result<SwrlAtom, SwrlError> {
+        let argument = |property: &str| match graph.object(atom, &format!("{SWRL}{property}")) {
+            Some(node) if graph.is_a(node, "Variable") => match node {
+                Node::Iri(iri) | Node::Blank(iri) => Ok(SwrlTerm::Variable(iri.clone())),
+                Node::Literal(_) => Err(SwrlError::Malformed(location.clone())),
+            },
+            Some(Node::Iri(iri)) => Ok(SwrlTerm::Constant(format!("<{iri}>"))),
+            Some(Node::Literal(literal)) => Ok(SwrlTerm::Constant(literal.clone())),
+            Some(Node::Blank(_)) => Err(SwrlError::Unsupported(
+                location.clone(),
+                "anonymous individuals",
+            )),
+            None => Err(SwrlError::Malformed(location.clone())),
+        };
+
+        if graph.is_a(atom, "ClassAtom") {
+            let class = match graph.object(atom, &format!("{SWRL}classPredicate")) {
+                Some(Node::Iri(class)) => format!("<{class}>"),
+                Some(_) => {
+                    return Err(SwrlError::Unsupported(
+                        location,
+                        "complex class expressions",
+                    ))
+                }
+                None => return Err(SwrlError::Malformed(location)),
+            };
+
+            return Ok(SwrlAtom([
+                argument("argument1")?,
+                SwrlTerm::Constant(format!("<{RDF_TYPE}>")),
+                SwrlTerm::Constant(class),
+            ]));
+        }
+
+        if graph.is_a(atom, "IndividualPropertyAtom") || graph.is_a(atom, "DatavaluedPropertyAtom")
+        {
+            let property = match graph.object(atom, &format!("{SWRL}propertyPredicate")) {
+                Some(Node::Iri(property)) => format!("<{property}>"),
+                _ => return Err(SwrlError::Malformed(location)),
+            };
+
+            return Ok(SwrlAtom([
+                argument("argument1")?,
+                SwrlTerm::Constant(property),
+                argument("argument2")?,
+            ]));
+        }
+
+        let feature = [
+            ("SameIndividualAtom", "equality atoms"),
+            ("DifferentIndividualsAtom", "inequality atoms"),
+            ("BuiltinAtom", "built-ins"),
+            ("DataRangeAtom", "data ranges"),
+        ]
+        .into_iter()
+        .find(|(class, _)| graph.is_a(atom, class));
+
+        match feature {
+            Some((_, feature)) => Err(SwrlError::Unsupported(location, feature)),
+            None => Err(SwrlError::Malformed(location)),
+        }
+    }
+}
+
+/// Return the local name of the IRI of a variable.
+fn variable_name(iri: &str) -> String {
+    iri.rsplit(['#', '/', ':'])
+        .next()
+        .unwrap_or(iri)
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric() || *c == '_')
+        .collect()
+}
+
+/// The SWRL rules of an ontology, which can be translated into a Nemo program.
+///
+/// The program is obtained by [`SwrlRules::program`] and derives triples for the predicate [`TRIPLE_PREDICATE`].
+/// Rules that cannot be translated are available via [`SwrlRules::unsupported`].
+#[derive(Debug)]
+pub struct SwrlRules {
+    rules: Vec<SwrlRule>,
+    unsupported: Vec<SwrlError>,
+}
+
+impl SwrlRules {
+    /// Parse the SWRL rules of an ontology given in RDF/XML,
+    /// where relative IRIs are resolved against the given base IRI.
+    pub fn parse_rdf_xml(ontology: &str, base: Option<&str>) -> Result<Self, SwrlError> {
+        let mut graph = Graph::default();
+
+        RdfXmlParser::new(ontology.as_bytes(), Self::base(base)?).parse_all(
+            &mut |triple: Triple| {
+                graph.add(triple);
+                Ok::<_, RdfXmlError>(())
+            },
+        )?;
+
+        Ok(Self::from_graph(&graph))
+    }
+
+    /// Parse the SWRL rules of an ontology given in Turtle,
+    /// where relative IRIs are resolved against the given base IRI.
+    pub fn parse_turtle(ontology: &str, base: Option<&str>) -> Result<Self, SwrlError> {
+        let mut graph = Graph::default();
+
+        TurtleParser::new(ontology.as_bytes(), Self::base(base)?).parse_all(
+            &mut |triple: Triple| {
+                graph.add(triple);
+                Ok::<_, TurtleError>(())
+            },
+        )?;
+
+        Ok(Self::from_graph(&graph))
+    }
+
+    fn base(base: Option<&str>) -> Result<Option<Iri<String>>, SwrlError> {
+        base.map(|base| {
+            Iri::parse(base.to_string()).map_err(|_| SwrlError::InvalidBase(base.to_string()))
+        })
+        .transpose()
+    }
+
+    fn from_graph(graph: &Graph) -> Self {
+        let mut rules = Vec::new();
+        let mut unsupported = Vec::new();
+
+        for (index, rule) in graph.rules.iter().enumerate() {
+            match SwrlRule::parse(graph, rule, &graph.rule_name(rule, index)) {
+                Ok(rule) => rules.push(rule),
+                Err(error) => {
+                    tracing::warn!("Ignoring SWRL rule: {error}");
+                    unsupported.push(error);
+                }
+            }
+        }
+
+        Self { rules, unsupported }
+    }
+
+    /// Return the reasons why rules of the ontology could not be translated.
+    pub fn unsupported(&self) -> &[SwrlError] {
+        &self.unsupported
+    }
+
+    /// Return the Nemo program consisting of the translated rules.
+    pub fn program(&self) -> String {
+        let mut program = String::new();
+
+        for rule in &self.rules {
+            let mut used = HashSet::new();
+            let mut variables = HashMap::new();
+            for variable in rule.body.iter().flat_map(SwrlAtom::variables) {
+                if !variables.contains_key(variable) {
+                    let name = variable_name(variable);
+                    let mut unique = name.clone();
+                    let mut index = 1;
+                    while unique.is_empty() || !used.insert(unique.clone()) {
+                        index += 1;
+                        unique = format!("{name}_{index}");
+                    }
+                    variables.insert(variable, unique);
+                }
+            }
+
+            let atoms = |atoms: &[SwrlAtom]| {
+                atoms
+                    .iter()
+                    .map(|atom| atom.to_nemo(&variables))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            };
+
+            if rule.body.is_empty() {
+                for atom in &rule.head {
+                    let _ = writeln!(program, "{} .", atom.to_nemo(&variables));
+                }
+            } else if !rule.head.is_empty() {
+                let _ = writeln!(program, "{} :- {} .", atoms(&rule.head), atoms(&rule.body));
+            }
+        }
+
+        program
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::io::parser::parse_program;
+
+    use super::SwrlRules;
+
+    const ONTOLOGY: &str = r#"
+        @prefix : <http://example.org/family#> .
+        @prefix owl: <http://www.w3.org/2002/07/owl#> .
+        @prefix rdfs: <http://www.w3.org/2000/01/rdf-schema#> .
+        @prefix swrl: <http://www.w3.org/2003/11/swrl#> .
+        @prefix swrlb: <http://www.w3.org/2003/11/swrlb#> .
+        @prefix var: <urn:swrl:var#> .
+
+        :alice :hasParent :bob .
+        var:x a swrl:Variable . var:y a swrl:Variable . var:z a swrl:Variable .
+
+        [] a swrl:Imp ;
+            swrl:body (
+                [ a swrl:IndividualPropertyAtom ; swrl:propertyPredicate :hasParent ;
+                  swrl:argument1 var:x ; swrl:argument2 var:y ]
+                [ a swrl:IndividualPropertyAtom ; swrl:propertyPredicate :hasBrother ;
+                  swrl:argument1 var:y ; swrl:argument2 var:z ] ) ;
+            swrl:head (
+                [ a swrl:IndividualPropertyAtom ; swrl:propertyPredicate :hasUncle ;
+                  swrl:argument1 var:x ; swrl:argument2 var:z ] ) .
+
+        :adult a swrl:Imp ;
+            swrl:body (
+                [ a swrl:ClassAtom ; swrl:classPredicate :Person ; swrl:argument1 var:x ]
+                [ a swrl:DatavaluedPropertyAtom ; swrl:propertyPredicate :status ;
+                  swrl:argument1 var:x ; swrl:argument2 "adult" ] ) ;
+            swrl:head ( [ a swrl:ClassAtom ; swrl:classPredicate :Adult ; swrl:argument1 var:x ] ) .
+
+        [] a swrl:Imp ; rdfs:label "age" ;
+            swrl:body (
+                [ a swrl:DatavaluedPropertyAtom ; swrl:propertyPredicate :hasAge ;
+                  swrl:argument1 var:x ; swrl:argument2 var:y ]
+                [ a swrl:BuiltinAtom ; swrl:builtin swrlb:greaterThan ; swrl:arguments ( var:y 17 ) ] ) ;
+            swrl:head ( [ a swrl:ClassAtom ; swrl:classPredicate :Adult ; swrl:argument1 var:x ] ) .
+
+        [] a swrl:Imp ;
+            swrl:body ( [ a swrl:ClassAtom ; swrl:classPredicate :Parent ; swrl:argument1 var:x ] ) ;
+            swrl:head ( [ a swrl:ClassAtom ; swrl:argument1 var:x ;
+                          swrl:classPredicate [ a owl:Restriction ] ] ) .
+
+        [] a swrl:Imp ;
+            swrl:body ( [ a swrl:ClassAtom ; swrl:classPredicate :Person ; swrl:argument1 var:x ] ) ;
+            swrl:head (
+                [ a swrl:IndividualPropertyAtom ; swrl:propertyPredicate :hasParent ;
+                  swrl:argument1 var:x ; swrl:argument2 var:z ] ) .
+
+        [] a swrl:Imp ;
+            swrl:head ( [ a swrl:ClassAtom ; swrl:classPredicate :Person ; swrl:argument1 :alice ] ) .
+    "#;
+
+    #[test]
+    fn translate_rules() {
+        let rules = SwrlRules::parse_turtle(ONTOLOGY, None).unwrap();
+
+        assert_eq!(
+            rules.program(),
+            "triple(?x, <http://example.org/family#hasUncle>, ?z) :- \
+             triple(?x, <http://example.org/family#hasParent>, ?y), \
+             triple(?y, <http://example.org/family#hasBrother>, ?z) .\n\
+             triple(?x, <http://www.w3.org/1999/02/22-rdf-syntax-ns#type>, <http://example.org/family#Adult>) :- \
+             triple(?x, <http://www.w3.org/1999/02/22-rdf-syntax-ns#type>, <http://example.org/family#Person>), \
+             triple(?x, <http://example.org/family#status>, \"adult\") .\n\
+             triple(<http://example.org/family#alice>, <http://www.w3.org/1999/02/22-rdf-syntax-ns#type>, <http://example.org/family#Person>) .\n"
+        );
+        assert!(parse_program(rules.program()).is_ok());
+    }
+
+    #[test]
+    fn report_unsupported_rules() {
+        let rules = SwrlRules::parse_turtle(ONTOLOGY, None).unwrap();
+
+        assert_eq!(
+            rules
+                .unsupported()
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>(),
+            vec![
+                "The body atom 2 of the SWRL rule \"age\" uses built-ins, which is not supported.",
+                "The head atom 1 of the SWRL rule number 4 uses complex class expressions, which is not supported.",
+                "The variable z of the head of the SWRL rule number 5 does not occur in its body.",
+            ]
+        );
+    }
+}
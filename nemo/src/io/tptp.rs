@@ -0,0 +1,851 @@
+//! Import of problems in the [TPTP](https://tptp.org/) format,
+//! which is used by the datasets of first-order theorem provers.
+//!
+//! Only the Horn fragment of `cnf` and `fof` formulas is supported:
+//! every clause may contain at most one positive literal,
+//! and `fof` axioms have to be atoms or implications from conjunctions of atoms to conjunctions of atoms,
+//! where the variables of the conclusion may be existentially quantified.
+//! Every variable of a conclusion has to occur in the premise or be existentially quantified.
+//!
+//! The axioms are translated into facts and rules of a Nemo program,
+//! where propositional atoms `p` become unary atoms `p(true)`.
+//! Ground conjectures (or clauses with role `negated_conjecture` that consist of negative ground literals)
+//! are translated into a query, whose result is non-empty if and only if the conjecture follows from the axioms.
+//!
+//! Function symbols, equality, non-integer numbers and `include` directives are not supported.
+
+use std::{
+    collections::{BTreeSet, HashSet},
+    fmt::Write,
+};
+
+use nom::{
+    branch::alt,
+    bytes::complete::{tag, take_until, take_while, take_while1},
+    character::complete::{char, multispace1, not_line_ending, satisfy},
+    combinator::{all_consuming, cut, map, not, opt, recognize, value},
+    multi::{many0, separated_list1},
+    sequence::{delimited, pair, preceded, terminated, tuple},
+    IResult,
+};
+use thiserror::Error;
+
+use crate::model::{Atom, Identifier, NumericLiteral, Term, TermTree};
+
+/// Name of the predicate that is derived for ground conjectures consisting of several atoms
+pub const CONJECTURE_PREDICATE: &str = "tptp_conjecture";
+/// Constant used for the argument of propositional atoms
+const TRUE: &str = "true";
+
+/// Errors that can occur when importing a TPTP problem.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum TptpError {
+    /// The problem is not syntactically valid (or uses unsupported syntax).
+    #[error("Syntax error in TPTP problem on line {0}.")]
+    Syntax(usize),
+    /// A formula is not in the Horn fragment.
+    #[error("The formula {0} is not a Horn clause.")]
+    NotHorn(String),
+    /// A formula uses a feature that is not supported.
+    #[error("The formula {0} uses {1}, which is not supported.")]
+    Unsupported(String, &'static str),
+    /// A variable of the conclusion of a formula does not occur in its premise.
+    #[error("The variable {1} of the formula {0} does not occur in its premise.")]
+    UnsafeVariable(String, String),
+}
+
+/// A term of a TPTP formula.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+enum TptpTerm {
+    Variable(String),
+    Constant(String),
+    String(String),
+    Integer(i64),
+}
+
+impl TptpTerm {
+    /// Return the term in Nemo syntax,
+    /// where the variables in `existentials` are written as existential variables.
+    fn to_nemo(&self, existentials: &BTreeSet<String>) -> String {
+        match self {
+            TptpTerm::Variable(name) if existentials.contains(name) => format!("!{name}"),
+            TptpTerm::Variable(name) => format!("?{name}"),
+            TptpTerm::Constant(name) => name.clone(),
+            TptpTerm::String(string) => {
+                format!("\"{}\"", string.replace('\\', "\\\\").replace('"', "\\\""))
+            }
+            TptpTerm::Integer(integer) => integer.to_string(),
+        }
+    }
+
+    /// Convert a ground term into a [`Term`].
+    fn to_term(&self) -> Term {
+        match self {
+            TptpTerm::Variable(name) | TptpTerm::Constant(name) => {
+                Term::Constant(Identifier(name.clone()))
+            }
+            TptpTerm::String(string) => Term::StringLiteral(string.clone()),
+            TptpTerm::Integer(integer) => Term::NumericLiteral(NumericLiteral::Integer(*integer)),
+        }
+    }
+}
+
+/// An atom of a TPTP formula.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct TptpAtom {
+    predicate: String,
+    terms: Vec<TptpTerm>,
+}
+
+impl TptpAtom {
+    fn variables(&self) -> impl Iterator<Item = &String> {
+        self.terms.iter().filter_map(|term| match term {
+            TptpTerm::Variable(name) => Some(name),
+            _ => None,
+        })
+    }
+
+    fn to_nemo(&self, existentials: &BTreeSet<String>) -> String {
+        let terms = self
+            .terms
+            .iter()
+            .map(|term| term.to_nemo(existentials))
+            .collect::<Vec<_>>()
+            .join(", ");
+        format!("{}({terms})", self.predicate)
+    }
+
+    fn to_atom(&self) -> Atom {
+        Atom::new(
+            Identifier(self.predicate.clone()),
+            self.terms
+                .iter()
+                .map(|term| TermTree::leaf(term.to_term()))
+                .collect(),
+        )
+    }
+}
+
+/// A (Horn fragment of a) TPTP formula.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Formula {
+    Atom(TptpAtom),
+    Not(Box<Formula>),
+    And(Vec<Formula>),
+    Or(Vec<Formula>),
+    Implies(Box<Formula>, Box<Formula>),
+    Forall(Vec<String>, Box<Formula>),
+    Exists(Vec<String>, Box<Formula>),
+    /// Formula using a connective or equality, which is not supported
+    Unsupported(&'static str),
+}
+
+/// A Horn clause, i.e. a rule or a fact.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct HornClause {
+    head: Vec<TptpAtom>,
+    body: Vec<TptpAtom>,
+    existentials: BTreeSet<String>,
+}
+
+/// A single annotated formula of a problem.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct AnnotatedFormula {
+    language: &'static str,
+    name: String,
+    role: String,
+    formula: Formula,
+}
+
+type ParseResult<'a, T> = IResult<&'a str, T>;
+
+/// Skip whitespace and comments.
+fn skip(input: &str) -> ParseResult<'_, ()> {
+    value(
+        (),
+        many0(alt((
+            value((), multispace1),
+            value((), pair(char('%'), not_line_ending)),
+            value((), tuple((tag("/*"), take_until("*/"), tag("*/")))),
+        ))),
+    )(input)
+}
+
+/// Parse the given token, followed by whitespace or comments.
+fn token<'a>(token: &'static str) -> impl FnMut(&'a str) -> ParseResult<'a, &'a str> {
+    terminated(tag(token), skip)
+}
+
+fn alphanumeric(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_'
+}
+
+fn lower_word(input: &str) -> ParseResult<'_, &str> {
+    terminated(
+        recognize(pair(
+            satisfy(|c| c.is_ascii_lowercase()),
+            take_while(alphanumeric),
+        )),
+        skip,
+    )(input)
+}
+
+fn upper_word(input: &str) -> ParseResult<'_, String> {
+    terminated(
+        map(
+            recognize(pair(
+                satisfy(|c| c.is_ascii_uppercase()),
+                take_while(alphanumeric),
+            )),
+            str::to_string,
+        ),
+        skip,
+    )(input)
+}
+
+/// Parse a quoted string with the given delimiter, where `\\` and the escaped delimiter may occur.
+fn quoted<'a>(delimiter: char) -> impl FnMut(&'a str) -> ParseResult<'a, String> {
+    move |input: &'a str| {
+        let (mut rest, _) = char(delimiter)(input)?;
+        let mut result = String::new();
+
+        loop {
+            let mut characters = rest.chars();
+            match characters.next() {
+                Some('\\') => {
+                    let escaped = characters.next().ok_or_else(|| {
+                        nom::Err::Error(nom::error::Error::new(rest, nom::error::ErrorKind::Char))
+                    })?;
+                    result.push(escaped);
+                }
+                Some(c) if c == delimiter => {
+                    let (rest, _) = skip(characters.as_str())?;
+                    return Ok((rest, result));
+                }
+                Some(c) => result.push(c),
+                None => {
+                    return Err(nom::Err::Error(nom::error::Error::new(
+                        rest,
+                        nom::error::ErrorKind::Char,
+                    )))
+                }
+            }
+            rest = characters.as_str();
+        }
+    }
+}
+
+/// Return whether the given name can be used without quotes.
+fn is_lower_word(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_ascii_lowercase()) && name.chars().all(alphanumeric)
+}
+
+/// Parse the name of a predicate or constant.
+fn atomic_word(input: &str) -> ParseResult<'_, String> {
+    alt((map(lower_word, str::to_string), quoted('\'')))(input)
+}
+
+/// Parse a number, where non-integer numbers are returned as `None`.
+fn number(input: &str) -> ParseResult<'_, Option<i64>> {
+    terminated(
+        map(
+            recognize(tuple((
+                opt(alt((char('+'), char('-')))),
+                take_while1(|c: char| c.is_ascii_digit()),
+                opt(pair(
+                    alt((char('.'), char('/'), char('E'), char('e'))),
+                    take_while1(|c: char| c.is_ascii_digit() || "+-eE".contains(c)),
+                )),
+            ))),
+            |number: &str| number.parse().ok(),
+        ),
+        skip,
+    )(input)
+}
+
+/// Parse a term, where `None` represents an unsupported term.
+fn term(input: &str) -> ParseResult<'_, Option<TptpTerm>> {
+    alt((
+        map(upper_word, |name| Some(TptpTerm::Variable(name))),
+        map(quoted('"'), |string| Some(TptpTerm::String(string))),
+        map(number, |number| number.map(TptpTerm::Integer)),
+        map(
+            pair(atomic_word, opt(arguments)),
+            |(name, arguments)| match arguments {
+                Some(_) => None,
+                None if is_lower_word(&name) => Some(TptpTerm::Constant(name)),
+                None => Some(TptpTerm::String(name)),
+            },
+        ),
+    ))(input)
+}
+
+fn arguments(input: &str) -> ParseResult<'_, Vec<Option<TptpTerm>>> {
+    delimited(token("("), separated_list1(token(","), term), token(")"))(input)
+}
+
+/// Parse `=` or `!=`, but not the beginning of `=>`.
+fn equality_sign(input: &str) -> ParseResult<'_, &str> {
+    alt((
+        token("!="),
+        terminated(tag("="), pair(not(char('>')), skip)),
+    ))(input)
+}
+
+/// Parse an atom, an equation, or `$true`/`$false`.
+fn atomic_formula(input: &str) -> ParseResult<'_, Formula> {
+    alt((
+        value(
+            Formula::Unsupported("$true and $false"),
+            alt((token("$true"), token("$false"))),
+        ),
+        map(
+            tuple((atomic_word, opt(arguments), opt(equality_sign))),
+            |(predicate, arguments, equality)| {
+                if equality.is_some() {
+                    return Formula::Unsupported("equality");
+                }
+                if !is_lower_word(&predicate) {
+                    return Formula::Unsupported("quoted predicate names");
+                }
+
+                let terms = match arguments {
+                    Some(arguments) => match arguments.into_iter().collect::<Option<Vec<_>>>() {
+                        Some(terms) => terms,
+                        None => {
+                            return Formula::Unsupported("function symbols or non-integer numbers")
+                        }
+                    },
+                    None => vec![TptpTerm::Constant(TRUE.to_string())],
+                };
+
+                Formula::Atom(TptpAtom { predicate, terms })
+            },
+        ),
+        map(tuple((term, equality_sign, term)), |_| {
+            Formula::Unsupported("equality")
+        }),
+    ))(input)
+}
+
+fn unitary_formula(input: &str) -> ParseResult<'_, Formula> {
+    alt((
+        map(
+            tuple((
+                alt((token("!"), token("?"))),
+                delimited(
+                    token("["),
+                    separated_list1(token(","), upper_word),
+                    token("]"),
+                ),
+                preceded(token(":"), unitary_formula),
+            )),
+            |(quantifier, variables, formula)| match quantifier {
+                "!" => Formula::Forall(variables, Box::new(formula)),
+                _ => Formula::Exists(variables, Box::new(formula)),
+            },
+        ),
+        map(preceded(token("~"), unitary_formula), |formula| {
+            Formula::Not(Box::new(formula))
+        }),
+        delimited(token("("), formula, token(")")),
+        atomic_formula,
+    ))(input)
+}
+
+fn formula(input: &str) -> ParseResult<'_, Formula> {
+    let (input, first) = unitary_formula(input)?;
+
+    match pair(
+        alt((
+            token("=>"),
+            token("<=>"),
+            token("<="),
+            token("<~>"),
+            token("~|"),
+            token("~&"),
+        )),
+        cut(unitary_formula),
+    )(input)
+    {
+        Ok((input, (connective, second))) => {
+            let formula = match connective {
+                "=>" => Formula::Implies(Box::new(first), Box::new(second)),
+                "<=" => Formula::Implies(Box::new(second), Box::new(first)),
+                "<=>" => Formula::Unsupported("equivalences"),
+                _ => Formula::Unsupported("negated connectives"),
+            };
+            return Ok((input, formula));
+        }
+        Err(nom::Err::Error(_)) => {}
+        Err(error) => return Err(error),
+    }
+
+    for connective in ["&", "|"] {
+        let (input, rest) = many0(preceded(token(connective), cut(unitary_formula)))(input)?;
+        if !rest.is_empty() {
+            let operands = [vec![first], rest].concat();
+            let formula = match connective {
+                "&" => Formula::And(operands),
+                _ => Formula::Or(operands),
+            };
+            return Ok((input, formula));
+        }
+    }
+
+    Ok((input, first))
+}
+
+/// Skip a general term, as used for the annotations of formulas.
+fn general_term(input: &str) -> ParseResult<'_, ()> {
+    value(
+        (),
+        many0(alt((
+            value((), quoted('\'')),
+            value((), quoted('"')),
+            value(
+                (),
+                delimited(
+                    token("("),
+                    separated_list1(token(","), general_term),
+                    token(")"),
+                ),
+            ),
+            value(
+                (),
+                delimited(
+                    token("["),
+                    opt(separated_list1(token(","), general_term)),
+                    token("]"),
+                ),
+            ),
+            value(
+                (),
+                terminated(
+                    take_while1(|c: char| !"()[],'\"".contains(c) && !c.is_whitespace()),
+                    skip,
+                ),
+            ),
+        ))),
+    )(input)
+}
+
+fn annotated_formula(input: &str) -> ParseResult<'_, Option<AnnotatedFormula>> {
+    alt((
+        map(
+            tuple((
+                alt((value("cnf", token("cnf")), value("fof", token("fof")))),
+                cut(tuple((
+                    token("("),
+                    alt((
+                        atomic_word,
+                        map(number, |number| number.unwrap_or_default().to_string()),
+                    )),
+                    token(","),
+                    lower_word,
+                    token(","),
+                    formula,
+                    opt(preceded(token(","), general_term)),
+                    token(")"),
+                    token("."),
+                ))),
+            )),
+            |(language, (_, name, _, role, _, formula, _, _, _))| {
+                Some(AnnotatedFormula {
+                    language,
+                    name,
+                    role: role.to_string(),
+                    formula,
+                })
+            },
+        ),
+        map(
+            tuple((
+                alt((token("include"), token("tff"), token("thf"), token("tcf"))),
+                cut(tuple((token("("), general_term, token(")"), token(".")))),
+            )),
+            |_| None,
+        ),
+    ))(input)
+}
+
+/// Strip leading universal quantifiers.
+fn strip_forall(formula: Formula) -> Formula {
+    match formula {
+        Formula::Forall(_, formula) => strip_forall(*formula),
+        formula => formula,
+    }
+}
+
+/// Return the atoms of a conjunction of atoms, or `None` if the formula is not of this form.
+fn conjunction(formula: &Formula) -> Result<Option<Vec<TptpAtom>>, &'static str> {
+    match formula {
+        Formula::Atom(atom) => Ok(Some(vec![atom.clone()])),
+        Formula::And(conjuncts) => {
+            let mut atoms = Vec::new();
+            for conjunct in conjuncts {
+                match conjunction(conjunct)? {
+                    Some(conjunct_atoms) => atoms.extend(conjunct_atoms),
+                    None => return Ok(None),
+                }
+            }
+            Ok(Some(atoms))
+        }
+        Formula::Unsupported(feature) => Err(feature),
+        _ => Ok(None),
+    }
+}
+
+/// Positive and negative literals of a clause
+type Literals = (Vec<TptpAtom>, Vec<TptpAtom>);
+
+/// Return the positive and negative atoms of a clause, or `None` if the formula is not a clause.
+fn clause(formula: &Formula) -> Result<Option<Literals>, &'static str> {
+    let literals = match formula {
+        Formula::Or(literals) => literals.iter().collect(),
+        literal => vec![literal],
+    };
+
+    let mut positive = Vec::new();
+    let mut negative = Vec::new();
+    for literal in literals {
+        match literal {
+            Formula::Atom(atom) => positive.push(atom.clone()),
+            Formula::Not(negated) => match negated.as_ref() {
+                Formula::Atom(atom) => negative.push(atom.clone()),
+                Formula::Unsupported(feature) => return Err(feature),
+                _ => return Ok(None),
+            },
+            Formula::Or(_) => match clause(literal)? {
+                Some((more_positive, more_negative)) => {
+                    positive.extend(more_positive);
+                    negative.extend(more_negative);
+                }
+                None => return Ok(None),
+            },
+            Formula::Unsupported(feature) => return Err(feature),
+            _ => return Ok(None),
+        }
+    }
+
+    Ok(Some((positive, negative)))
+}
+
+/// A problem in the TPTP format, restricted to the Horn fragment.
+///
+/// The axioms are available as Nemo program via [`TptpProblem::program`],
+/// and a ground conjecture as query via [`TptpProblem::query`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TptpProblem {
+    clauses: Vec<HornClause>,
+    conjecture: Option<Vec<TptpAtom>>,
+}
+
+impl TptpProblem {
+    /// Parse a TPTP problem.
+    pub fn parse(input: &str) -> Result<Self, TptpError> {
+        let (_, formulas) = all_consuming(preceded(skip, many0(annotated_formula)))(input)
+            .map_err(|error| {
+                let rest = match error {
+                    nom::Err::Error(error) | nom::Err::Failure(error) => error.input,
+                    nom::Err::Incomplete(_) => "",
+                };
+                TptpError::Syntax(input[..input.len() - rest.len()].lines().count().max(1))
+            })?;
+
+        let mut clauses = Vec::new();
+        let mut conjectures = Vec::new();
+        let mut ground_conjecture = true;
+
+        for formula in formulas.into_iter().flatten() {
+            let name = formula.name.clone();
+            let unsupported = |feature| TptpError::Unsupported(name.clone(), feature);
+            let not_horn = || TptpError::NotHorn(name.clone());
+
+            match formula.role.as_str() {
+                "conjecture" => {
+                    let (ground, atoms) = match strip_forall(formula.formula) {
+                        Formula::Exists(_, formula) => (false, conjunction(&formula)),
+                        formula => (true, conjunction(&formula)),
+                    };
+                    let atoms = atoms.map_err(unsupported)?.ok_or_else(not_horn)?;
+
+                    ground_conjecture &=
+                        ground && atoms.iter().all(|atom| atom.variables().next().is_none());
+                    conjectures.extend(atoms);
+                }
+                "negated_conjecture" => {
+                    let (positive, negative) = clause(&strip_forall(formula.formula))
+                        .map_err(unsupported)?
+                        .ok_or_else(not_horn)?;
+                    if !positive.is_empty() {
+                        return Err(TptpError::Unsupported(
+                            name,
+                            "negated conjectures with positive literals",
+                        ));
+                    }
+
+                    ground_conjecture &= negative
+                        .iter()
+                        .all(|atom| atom.variables().next().is_none());
+                    conjectures.extend(negative);
+                }
+                _ if formula.language == "cnf" => {
+                    let (mut positive, negative) = clause(&formula.formula)
+                        .map_err(unsupported)?
+                        .ok_or_else(not_horn)?;
+                    if positive.len() != 1 {
+                        return Err(not_horn());
+                    }
+
+                    clauses.push(Self::horn_clause(
+                        &name,
+                        vec![positive.remove(0)],
+                        negative,
+                        BTreeSet::new(),
+                    )?);
+                }
+                _ => clauses.extend(Self::fof_clauses(&name, strip_forall(formula.formula))?),
+            }
+        }
+
+        let conjecture = if ground_conjecture && !conjectures.is_empty() {
+            Some(conjectures)
+        } else {
+            if !conjectures.is_empty() {
+                tracing::warn!("Ignoring conjecture with variables");
+            }
+            None
+        };
+
+        Ok(Self {
+            clauses,
+            conjecture,
+        })
+    }
+
+    /// Translate an axiom given in first-order form into Horn clauses.
+    fn fof_clauses(name: &str, formula: Formula) -> Result<Vec<HornClause>, TptpError> {
+        let unsupported = |feature| TptpError::Unsupported(name.to_string(), feature);
+        let not_horn = || TptpError::NotHorn(name.to_string());
+
+        match formula {
+            Formula::And(conjuncts) => Ok(conjuncts
+                .into_iter()
+                .map(|conjunct| Self::fof_clauses(name, strip_forall(conjunct)))
+                .collect::<Result<Vec<_>, _>>()?
+                .concat()),
+            Formula::Implies(premise, conclusion) => {
+                let body = conjunction(&premise)
+                    .map_err(unsupported)?
+                    .ok_or_else(not_horn)?;
+
+                let (existentials, conclusion) = match strip_forall(*conclusion) {
+                    Formula::Exists(variables, conclusion) => {
+                        (variables.into_iter().collect(), *conclusion)
+                    }
+                    conclusion => (BTreeSet::new(), conclusion),
+                };
+                let head = conjunction(&conclusion)
+                    .map_err(unsupported)?
+                    .ok_or_else(not_horn)?;
+
+                Ok(vec![Self::horn_clause(name, head, body, existentials)?])
+            }
+            Formula::Unsupported(feature) => Err(unsupported(feature)),
+            formula => match clause(&formula).map_err(unsupported)? {
+                Some((mut positive, negative)) if positive.len() == 1 => {
+                    Ok(vec![Self::horn_clause(
+                        name,
+                        vec![positive.remove(0)],
+                        negative,
+                        BTreeSet::new(),
+                    )?])
+                }
+                _ => Err(not_horn()),
+            },
+        }
+    }
+
+    /// Create a Horn clause, after checking that all variables of the head are safe.
+    fn horn_clause(
+        name: &str,
+        head: Vec<TptpAtom>,
+        body: Vec<TptpAtom>,
+        existentials: BTreeSet<String>,
+    ) -> Result<HornClause, TptpError> {
+        let safe = body
+            .iter()
+            .flat_map(TptpAtom::variables)
+            .collect::<HashSet<_>>();
+
+        if let Some(variable) = head
+            .iter()
+            .flat_map(TptpAtom::variables)
+            .find(|variable| !safe.contains(variable) && !existentials.contains(*variable))
+        {
+            return Err(TptpError::UnsafeVariable(
+                name.to_string(),
+                variable.to_string(),
+            ));
+        }
+
+        Ok(HornClause {
+            head,
+            body,
+            existentials,
+        })
+    }
+
+    /// Return the Nemo program consisting of the facts and rules given by the axioms of the problem.
+    ///
+    /// If the problem has a ground conjecture with several atoms,
+    /// the program also contains a rule deriving [`CONJECTURE_PREDICATE`]`(true)` from them.
+    pub fn program(&self) -> String {
+        let mut program = String::new();
+
+        for clause in &self.clauses {
+            let head = clause
+                .head
+                .iter()
+                .map(|atom| atom.to_nemo(&clause.existentials))
+                .collect::<Vec<_>>()
+                .join(", ");
+
+            if clause.body.is_empty() {
+                let _ = writeln!(program, "{head} .");
+            } else {
+                let body = clause
+                    .body
+                    .iter()
+                    .map(|atom| atom.to_nemo(&clause.existentials))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                let _ = writeln!(program, "{head} :- {body} .");
+            }
+        }
+
+        if let Some(conjecture) = self.conjecture.as_ref().filter(|atoms| atoms.len() > 1) {
+            let body = conjecture
+                .iter()
+                .map(|atom| atom.to_nemo(&BTreeSet::new()))
+                .collect::<Vec<_>>()
+                .join(", ");
+            let _ = writeln!(program, "{CONJECTURE_PREDICATE}({TRUE}) :- {body} .");
+        }
+
+        program
+    }
+
+    /// Return a query, which has an answer after reasoning over the program
+    /// if and only if the conjecture of the problem follows from its axioms.
+    ///
+    /// Returns `None` if the problem has no conjecture or if the conjecture is not ground.
+    pub fn query(&self) -> Option<Atom> {
+        match self.conjecture.as_deref()? {
+            [atom] => Some(atom.to_atom()),
+            _ => Some(Atom::new(
+                Identifier(CONJECTURE_PREDICATE.to_string()),
+                vec![TermTree::leaf(Term::Constant(Identifier(TRUE.to_string())))],
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        io::parser::parse_program,
+        model::{Atom, Identifier, Term, TermTree},
+    };
+
+    use super::{TptpError, TptpProblem};
+
+    #[test]
+    fn cnf_problem() {
+        let problem = TptpProblem::parse(
+            "% Transitive closure\n\
+             cnf(edge_ab, axiom, edge(a, b)).\n\
+             cnf(edge_bc, axiom, edge('b', c)).\n\
+             cnf(base, axiom, ~ edge(X, Y) | path(X, Y)).\n\
+             cnf(step, axiom, (path(X, Z) | ~path(X, Y) | ~edge(Y, Z))).\n\
+             cnf(goal, negated_conjecture, ~path(a, c)).\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            problem.program(),
+            "edge(a, b) .\n\
+             edge(b, c) .\n\
+             path(?X, ?Y) :- edge(?X, ?Y) .\n\
+             path(?X, ?Z) :- path(?X, ?Y), edge(?Y, ?Z) .\n"
+        );
+        assert_eq!(
+            problem.query(),
+            Some(Atom::new(
+                Identifier("path".to_string()),
+                vec![
+                    TermTree::leaf(Term::Constant(Identifier("a".to_string()))),
+                    TermTree::leaf(Term::Constant(Identifier("c".to_string()))),
+                ]
+            ))
+        );
+        assert!(parse_program(problem.program()).is_ok());
+    }
+
+    #[test]
+    fn fof_problem() {
+        let problem = TptpProblem::parse(
+            "/* Existential rules */\n\
+             fof(human, axiom, human(socrates) & human(plato)).\n\
+             fof(parent, axiom, ! [X] : (human(X) => ? [Y] : (parent(X, Y) & person(Y)))).\n\
+             fof(mortal, axiom, ! [X] : (mortal(X) <= human(X)), file('Socrates.p', [a, b])).\n\
+             fof(rain, axiom, rain).\n\
+             fof(wet, axiom, rain => wet).\n\
+             fof(goal, conjecture, mortal(socrates) & mortal(plato) & wet).\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            problem.program(),
+            "human(socrates) .\n\
+             human(plato) .\n\
+             parent(?X, !Y), person(!Y) :- human(?X) .\n\
+             mortal(?X) :- human(?X) .\n\
+             rain(true) .\n\
+             wet(true) :- rain(true) .\n\
+             tptp_conjecture(true) :- mortal(socrates), mortal(plato), wet(true) .\n"
+        );
+        assert!(parse_program(problem.program()).is_ok());
+
+        let problem = TptpProblem::parse("fof(goal, conjecture, ? [X] : mortal(X)).").unwrap();
+        assert_eq!(problem.query(), None);
+    }
+
+    #[test]
+    fn unsupported_problems() {
+        assert_eq!(
+            TptpProblem::parse("cnf(c, axiom, p(X) | q(X))."),
+            Err(TptpError::NotHorn("c".to_string()))
+        );
+        assert_eq!(
+            TptpProblem::parse("cnf(c, axiom, p(f(X)) | ~q(X))."),
+            Err(TptpError::Unsupported(
+                "c".to_string(),
+                "function symbols or non-integer numbers"
+            ))
+        );
+        assert_eq!(
+            TptpProblem::parse("fof(c, axiom, ! [X] : (p(X) => X = a))."),
+            Err(TptpError::Unsupported("c".to_string(), "equality"))
+        );
+        assert_eq!(
+            TptpProblem::parse("fof(c, axiom, ! [X, Y] : (p(X) => q(X, Y)))."),
+            Err(TptpError::UnsafeVariable("c".to_string(), "Y".to_string()))
+        );
+        assert_eq!(
+            TptpProblem::parse("cnf(a, axiom, p(a)).\ncnf(b, axiom, p(b)"),
+            Err(TptpError::Syntax(2))
+        );
+    }
+}
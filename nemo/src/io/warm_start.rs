@@ -0,0 +1,169 @@
+//! Support for warm-starting the chase from previously exported results.
+//!
+//! A directory containing the output files of an earlier run (as written by the [`OutputFileManager`][super::OutputFileManager])
+//! can be used to pre-populate derived predicates, such that the rules only need to compute facts
+//! that were not already derived in the earlier run.
+//!
+//! Note that this is only sound for monotone programs, i.e. programs without negation,
+//! for which the previous materialization is a subset of the new one.
+//! In particular, facts of the previous run are never retracted.
+
+use std::path::{Path, PathBuf};
+
+use crate::{
+    error::Error,
+    model::{DataSourceDeclaration, DsvFile, Identifier, NativeDataSource, PrimitiveType, Program},
+};
+
+use super::output_file_manager::{FileCompression, FileFormat};
+
+/// File formats (and compressions) that are tried, in this order, when looking for previous results.
+const WARM_START_FILE_FORMATS: [(FileFormat, FileCompression); 4] = [
+    (FileFormat::DSV(b','), FileCompression::None),
+    (FileFormat::DSV(b','), FileCompression::Gzip),
+    (FileFormat::DSV(b'\t'), FileCompression::None),
+    (FileFormat::DSV(b'\t'), FileCompression::Gzip),
+];
+
+/// Directory containing the previously exported materialization of derived predicates.
+#[derive(Debug, Clone)]
+pub struct WarmStart {
+    directory: PathBuf,
+}
+
+impl WarmStart {
+    /// Create a new [`WarmStart`] which looks for previous results in the given directory.
+    pub fn new(directory: PathBuf) -> Self {
+        Self { directory }
+    }
+
+    /// Return the directory containing the previous results.
+    pub fn directory(&self) -> &Path {
+        &self.directory
+    }
+
+    /// Find the file containing the previous results for the given predicate.
+    ///
+    /// Files are expected to be named like the output files produced by the [`OutputFileManager`][super::OutputFileManager].
+    /// Returns `None` if no such file exists.
+    pub fn file_for_predicate(&self, predicate: &Identifier) -> Option<(PathBuf, FileFormat)> {
+        let base = predicate.sanitised_file_name(self.directory.clone());
+
+        WARM_START_FILE_FORMATS
+            .iter()
+            .map(|(format, compression)| {
+                (
+                    compression.file_name(format.file_name(base.clone())),
+                    *format,
+                )
+            })
+            .find(|(path, _)| path.is_file())
+    }
+
+    /// Return a data source for the previous results of the given predicate,
+    /// which are parsed according to the given types.
+    /// Its rows are marked as sorted, since the results have been exported from a trie.
+    ///
+    /// Returns `None` if there are no previous results for this predicate.
+    pub fn data_source(&self, predicate: &Identifier, types: &[PrimitiveType]) -> Option<DsvFile> {
+        let (path, FileFormat::DSV(delimiter)) = self.file_for_predicate(predicate)?;
+
+        // The path is made absolute, so that it is not resolved relative to the input directory.
+        let path = path.canonicalize().unwrap_or(path);
+
+        Some(
+            DsvFile::new(
+                &path.to_string_lossy(),
+                delimiter,
+                types.iter().copied().collect(),
+            )
+            .with_sorted_rows(),
+        )
+    }
+
+    /// Return a program without rules, whose data sources load the previous results of the given predicates
+    /// with the given arities, e.g. to query them without reasoning again.
+    ///
+    /// Returns an error if there are no previous results for one of the predicates.
+    pub fn program(&self, predicates: &[(Identifier, usize)]) -> Result<Program, Error> {
+        let mut sources = Vec::new();
+
+        for (predicate, arity) in predicates {
+            let dsv_file = self
+                .data_source(predicate, &vec![PrimitiveType::Any; *arity])
+                .ok_or_else(|| Error::MissingSavedResults {
+                    predicate: predicate.to_string(),
+                    directory: self.directory.clone(),
+                })?;
+
+            sources.push(DataSourceDeclaration::new(
+                predicate.clone(),
+                NativeDataSource::DsvFile(dsv_file),
+            ));
+        }
+
+        Ok(Program::from((sources, Vec::new())))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs::{write, File};
+
+    use assert_fs::TempDir;
+
+    use super::WarmStart;
+    use crate::{
+        error::Error,
+        io::output_file_manager::FileFormat,
+        model::{DataSource, Identifier, NativeDataSource},
+    };
+
+    #[test]
+    fn finds_exported_files() {
+        let directory = TempDir::new().unwrap();
+        File::create(directory.path().join("a.csv")).unwrap();
+        File::create(directory.path().join("b.tsv.gz")).unwrap();
+
+        let warm_start = WarmStart::new(directory.path().to_path_buf());
+
+        let (path, format) = warm_start
+            .file_for_predicate(&Identifier("a".to_string()))
+            .unwrap();
+        assert_eq!(path, directory.path().join("a.csv"));
+        assert!(matches!(format, FileFormat::DSV(b',')));
+
+        let (path, format) = warm_start
+            .file_for_predicate(&Identifier("b".to_string()))
+            .unwrap();
+        assert_eq!(path, directory.path().join("b.tsv.gz"));
+        assert!(matches!(format, FileFormat::DSV(b'\t')));
+
+        assert!(warm_start
+            .file_for_predicate(&Identifier("c".to_string()))
+            .is_none());
+    }
+
+    #[test]
+    fn program_of_saved_results() {
+        let directory = TempDir::new().unwrap();
+        write(directory.path().join("edge.csv"), "a,b\na,c\nb,c\n").unwrap();
+
+        let warm_start = WarmStart::new(directory.path().to_path_buf());
+
+        let program = warm_start
+            .program(&[(Identifier("edge".to_string()), 2)])
+            .unwrap();
+        let sources = program.sources().collect::<Vec<_>>();
+        assert_eq!(sources.len(), 1);
+        assert_eq!(sources[0].predicate, Identifier("edge".to_string()));
+        assert!(matches!(&sources[0].source, NativeDataSource::DsvFile(_)));
+        assert_eq!(sources[0].source.input_types().arity(), 2);
+        assert_eq!(program.rules().len(), 0);
+
+        assert!(matches!(
+            warm_start.program(&[(Identifier("path".to_string()), 2)]),
+            Err(Error::MissingSavedResults { .. })
+        ));
+    }
+}
@@ -15,7 +15,6 @@
 )]
 #![feature(macro_metavar_expr)]
 #![feature(is_sorted)]
-#![feature(assert_matches)]
 #![feature(iter_intersperse)]
 
 /// The crate for underlying physical operations.
@@ -36,6 +35,7 @@ mod table_manager;
 mod util;
 
 pub use nemo_physical::meta;
+pub use program_analysis::fragment::Fragment;
 
 // TODO: this is a temporary reexport, as long as the datatype mapping is not fully implemented
 pub use nemo_physical::datatypes;
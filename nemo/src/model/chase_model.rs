@@ -8,3 +8,6 @@ pub use rule::*;
 
 mod atom;
 pub use atom::*;
+
+mod aggregate;
+pub use aggregate::*;
@@ -0,0 +1,261 @@
+//! Aggregates in the heads of rules of a [`super::ChaseProgram`].
+
+use std::{cmp::Ordering, fmt::Display};
+
+use nemo_physical::datatypes::Double;
+use thiserror::Error;
+
+use crate::model::{Identifier, NumericLiteral, Term};
+
+/// Errors that can occur when translating, analyzing or computing aggregates
+#[derive(Error, Debug, Clone)]
+pub enum AggregateError {
+    /// Unknown aggregate operation
+    #[error(
+        r#"Unknown aggregate operation "{0}", supported are "min", "max", "count" and "sum"."#
+    )]
+    UnknownOperation(String),
+    /// Aggregate operation applied to a wrong number of variables
+    #[error(r#"The aggregate operation "{0}" cannot be applied to this number of variables."#)]
+    InvalidVariableCount(AggregateOperation),
+    /// Rule with an aggregate that is not the only one in a single head atom
+    #[error(r#"Rules with aggregates must have a single head atom without existential variables, containing a single aggregate (predicate "{0}")."#)]
+    UnsupportedHead(Identifier),
+    /// Aggregated variable that is not bound by the body
+    #[error(r#"The aggregated variable "{0}" does not occur in a positive body literal."#)]
+    UnsafeVariable(String),
+    /// Aggregated variable that is also used for grouping
+    #[error(r#"The aggregated variable "{0}" must not occur in the head."#)]
+    GroupedVariable(String),
+    /// Predicate that is derived in different ways
+    #[error(r#"Predicate "{0}" is derived by rules with different aggregates or without an aggregate, or also occurs in facts or data sources."#)]
+    InconsistentDerivation(Identifier),
+    /// Aggregate in recursion that is not monotone
+    #[error(r#"The aggregate operation "{1}" of predicate "{0}" is not monotone and cannot be used in recursion."#)]
+    NonMonotoneOperation(Identifier, AggregateOperation),
+    /// Recursive aggregate that is used in a non-monotone way
+    #[error(r#"The recursive aggregate of predicate "{0}" is used in a non-monotone way: {1}"#)]
+    NonMonotoneUse(Identifier, String),
+    /// Value that cannot be summed up
+    #[error(r#"The value "{0}" cannot be summed up."#)]
+    NonNumericValue(Term),
+}
+
+/// Operation for computing an aggregate
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AggregateOperation {
+    /// Smallest value
+    Min,
+    /// Largest value
+    Max,
+    /// Number of distinct values (or tuples of values)
+    Count,
+    /// Sum of distinct values,
+    /// or of the first value of each distinct tuple of values
+    Sum,
+}
+
+impl AggregateOperation {
+    /// Return the [`AggregateOperation`] with the given name.
+    pub fn from_identifier(identifier: &Identifier) -> Result<Self, AggregateError> {
+        match identifier.name().as_str() {
+            "min" => Ok(Self::Min),
+            "max" => Ok(Self::Max),
+            "count" => Ok(Self::Count),
+            "sum" => Ok(Self::Sum),
+            name => Err(AggregateError::UnknownOperation(name.to_string())),
+        }
+    }
+
+    /// Return whether the operation accepts the given number of variables.
+    pub fn accepts_variables(&self, count: usize) -> bool {
+        match self {
+            Self::Count | Self::Sum => count > 0,
+            Self::Min | Self::Max => count == 1,
+        }
+    }
+
+    /// Return whether the aggregate value only changes monotonically
+    /// if more facts are added to the aggregated predicate,
+    /// i.e. whether the operation can be used in recursion.
+    ///
+    /// Minima only decrease, whereas maxima and counts only increase.
+    /// Sums might change in either direction in the presence of negative values.
+    pub fn is_monotone(&self) -> bool {
+        !matches!(self, Self::Sum)
+    }
+
+    /// Return whether the aggregate value decreases as more facts are added.
+    pub fn is_decreasing(&self) -> bool {
+        matches!(self, Self::Min)
+    }
+
+    /// Compute the aggregate over the given values,
+    /// which contains one list of values for each distinct tuple in the group.
+    ///
+    /// Returns `None` if there are no values.
+    pub fn aggregate(&self, values: &[Vec<Term>]) -> Result<Option<Term>, AggregateError> {
+        let result = match self {
+            Self::Min => values
+                .iter()
+                .map(|value| &value[0])
+                .min_by(|a, b| compare_values(a, b))
+                .cloned(),
+            Self::Max => values
+                .iter()
+                .map(|value| &value[0])
+                .max_by(|a, b| compare_values(a, b))
+                .cloned(),
+            Self::Count => Some(Term::NumericLiteral(NumericLiteral::Integer(
+                values.len() as i64
+            ))),
+            Self::Sum => Some(sum_values(values.iter().map(|value| &value[0]))?),
+        };
+
+        Ok(result)
+    }
+}
+
+impl Display for AggregateOperation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Min => write!(f, "min"),
+            Self::Max => write!(f, "max"),
+            Self::Count => write!(f, "count"),
+            Self::Sum => write!(f, "sum"),
+        }
+    }
+}
+
+/// Return the value of a numeric literal as a float.
+fn numeric_as_f64(literal: &NumericLiteral) -> f64 {
+    match literal {
+        NumericLiteral::Integer(value) => *value as f64,
+        NumericLiteral::Decimal(left, right) => format!("{left}.{right}").parse().unwrap_or(0.0),
+        NumericLiteral::Double(value) => f64::from(*value),
+    }
+}
+
+/// Compare two values, where numeric values are compared by their value
+/// and all other values by their syntactic representation.
+fn compare_values(left: &Term, right: &Term) -> Ordering {
+    match (left, right) {
+        (
+            Term::NumericLiteral(NumericLiteral::Integer(left)),
+            Term::NumericLiteral(NumericLiteral::Integer(right)),
+        ) => left.cmp(right),
+        (Term::NumericLiteral(left), Term::NumericLiteral(right)) => {
+            numeric_as_f64(left).total_cmp(&numeric_as_f64(right))
+        }
+        _ => left.cmp(right),
+    }
+}
+
+/// Compute the sum of the given values.
+///
+/// The result is an integer if all values are integers (and the sum does not overflow)
+/// and a double otherwise.
+fn sum_values<'a>(values: impl Iterator<Item = &'a Term>) -> Result<Term, AggregateError> {
+    let mut integer_sum = Some(0i64);
+    let mut double_sum = 0.0;
+
+    for value in values {
+        let Term::NumericLiteral(literal) = value else {
+            return Err(AggregateError::NonNumericValue(value.clone()));
+        };
+
+        integer_sum = match literal {
+            NumericLiteral::Integer(integer) => {
+                integer_sum.and_then(|sum| sum.checked_add(*integer))
+            }
+            _ => None,
+        };
+        double_sum += numeric_as_f64(literal);
+    }
+
+    match integer_sum {
+        Some(sum) => Ok(Term::NumericLiteral(NumericLiteral::Integer(sum))),
+        None => Double::new(double_sum)
+            .map(|sum| Term::NumericLiteral(NumericLiteral::Double(sum)))
+            .map_err(|_| {
+                AggregateError::NonNumericValue(Term::StringLiteral(double_sum.to_string()))
+            }),
+    }
+}
+
+/// Aggregate computed by a [`super::ChaseRule`].
+///
+/// The body of such a rule consists of a single atom,
+/// whose first terms are the values of the grouping positions of the head
+/// and whose remaining terms are the aggregated values.
+/// The head contains the result of the aggregate at the given position.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChaseAggregate {
+    /// Operation of the aggregate
+    operation: AggregateOperation,
+    /// Position of the aggregate in the head
+    position: usize,
+}
+
+impl ChaseAggregate {
+    /// Create a new [`ChaseAggregate`].
+    pub fn new(operation: AggregateOperation, position: usize) -> Self {
+        Self {
+            operation,
+            position,
+        }
+    }
+
+    /// Return the operation of the aggregate.
+    pub fn operation(&self) -> AggregateOperation {
+        self.operation
+    }
+
+    /// Return the position of the aggregate in the head.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::model::{NumericLiteral, Term};
+
+    use super::AggregateOperation;
+
+    fn integers(values: &[i64]) -> Vec<Vec<Term>> {
+        values
+            .iter()
+            .map(|value| vec![Term::NumericLiteral(NumericLiteral::Integer(*value))])
+            .collect()
+    }
+
+    #[test]
+    fn aggregate_values() {
+        let values = integers(&[3, -2, 10]);
+
+        let integer = |value| Some(Term::NumericLiteral(NumericLiteral::Integer(value)));
+
+        assert_eq!(
+            AggregateOperation::Min.aggregate(&values).unwrap(),
+            integer(-2)
+        );
+        assert_eq!(
+            AggregateOperation::Max.aggregate(&values).unwrap(),
+            integer(10)
+        );
+        assert_eq!(
+            AggregateOperation::Count.aggregate(&values).unwrap(),
+            integer(3)
+        );
+        assert_eq!(
+            AggregateOperation::Sum.aggregate(&values).unwrap(),
+            integer(11)
+        );
+
+        assert_eq!(AggregateOperation::Min.aggregate(&[]).unwrap(), None);
+        assert!(AggregateOperation::Sum
+            .aggregate(&[vec![Term::StringLiteral("a".to_string())]])
+            .is_err());
+    }
+}
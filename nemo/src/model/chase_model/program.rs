@@ -1,16 +1,17 @@
 //! Defines a variant of [`crate::model::Program`], suitable for computing the chase.
 
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 
 use crate::{
     error::Error,
     model::{
-        DataSourceDeclaration, Fact, Identifier, OutputPredicateSelection, PrimitiveType, Program,
-        QualifiedPredicateName,
+        Atom, DataSourceDeclaration, Fact, Identifier, Literal, OutputPredicateSelection,
+        PrimitiveType, Program, QualifiedPredicateName, Rule, Semiring, Term, TermOperation,
+        TermTree, Variable,
     },
 };
 
-use super::ChaseRule;
+use super::{AggregateError, AggregateOperation, ChaseAggregate, ChaseAtom, ChaseRule};
 
 #[allow(dead_code)]
 /// Representation of a datalog program that is used for generating execution plans for the physical layer.
@@ -23,6 +24,9 @@ pub struct ChaseProgram {
     facts: Vec<Fact>,
     parsed_predicate_declarations: HashMap<Identifier, Vec<PrimitiveType>>,
     output_predicates: OutputPredicateSelection,
+    annotated_predicates: HashSet<Identifier>,
+    temporal_predicates: HashSet<Identifier>,
+    predicate_limits: HashMap<Identifier, usize>,
 }
 
 impl From<Vec<ChaseRule>> for ChaseProgram {
@@ -64,9 +68,39 @@ impl ChaseProgram {
             facts,
             parsed_predicate_declarations,
             output_predicates,
+            annotated_predicates: HashSet::new(),
+            temporal_predicates: HashSet::new(),
+            predicate_limits: HashMap::new(),
         }
     }
 
+    /// Mark the given predicates as annotated, see [`ChaseProgram::annotated_predicates`].
+    pub(crate) fn with_annotated_predicates(
+        mut self,
+        annotated_predicates: HashSet<Identifier>,
+    ) -> Self {
+        self.annotated_predicates = annotated_predicates;
+        self
+    }
+
+    /// Mark the given predicates as temporal, see [`ChaseProgram::temporal_predicates`].
+    pub(crate) fn with_temporal_predicates(
+        mut self,
+        temporal_predicates: HashSet<Identifier>,
+    ) -> Self {
+        self.temporal_predicates = temporal_predicates;
+        self
+    }
+
+    /// Limit the number of facts of the given predicates, see [`ChaseProgram::predicate_limits`].
+    pub(crate) fn with_predicate_limits(
+        mut self,
+        predicate_limits: HashMap<Identifier, usize>,
+    ) -> Self {
+        self.predicate_limits = predicate_limits;
+        self
+    }
+
     /// Get the base IRI, if set.
     #[must_use]
     pub fn base(&self) -> Option<String> {
@@ -164,6 +198,36 @@ impl ChaseProgram {
         self.parsed_predicate_declarations.clone()
     }
 
+    /// Return the predicates whose last column contains the annotations of their facts,
+    /// see [`crate::model::Semiring`].
+    #[must_use]
+    pub fn annotated_predicates(&self) -> &HashSet<Identifier> {
+        &self.annotated_predicates
+    }
+
+    /// Return the predicates whose last two columns contain the validity intervals of their facts,
+    /// see [`crate::model::ValidityInterval`].
+    #[must_use]
+    pub fn temporal_predicates(&self) -> &HashSet<Identifier> {
+        &self.temporal_predicates
+    }
+
+    /// Return the maximal number of facts of each predicate with a `@limit` directive,
+    /// see [`crate::model::Program::predicate_limits`].
+    #[must_use]
+    pub fn predicate_limits(&self) -> &HashMap<Identifier, usize> {
+        &self.predicate_limits
+    }
+
+    /// Add a type declaration for a predicate, replacing any previous declaration.
+    pub(crate) fn add_predicate_declaration(
+        &mut self,
+        predicate: Identifier,
+        types: Vec<PrimitiveType>,
+    ) {
+        self.parsed_predicate_declarations.insert(predicate, types);
+    }
+
     /// Force the given selection of output predicates.
     pub fn force_output_predicate_selection(
         &mut self,
@@ -173,26 +237,444 @@ impl ChaseProgram {
     }
 }
 
+/// Return the name of the auxiliary predicate that binds
+/// the value of a function term in a filter of a rule.
+fn function_filter_predicate(rule_index: usize, function_index: usize) -> Identifier {
+    Identifier(format!(
+        "FUNCTION_FILTER_IDENTIFIER_FOR_RULE_{rule_index}_{function_index}"
+    ))
+}
+
+/// Bind each variable of the given rule that holds the value of a function term in a filter
+/// by an additional positive body atom.
+///
+/// The auxiliary predicate is derived by an additional rule,
+/// which has the positive body literals of the rule as its body
+/// and contains all of their variables as well as the function term in its head.
+/// Hence, each match of the body is assigned exactly one value,
+/// even if the function computes a random number.
+/// Returns the transformed rule followed by the additional rules.
+fn compile_function_filters(rule: &Rule, rule_index: usize) -> Vec<Rule> {
+    if rule.computed_variables().is_empty() {
+        return vec![rule.clone()];
+    }
+
+    let positive_literals = rule
+        .body()
+        .iter()
+        .filter(|literal| literal.is_positive())
+        .cloned()
+        .collect::<Vec<_>>();
+
+    let mut positive_variables = Vec::<Variable>::new();
+    for variable in positive_literals
+        .iter()
+        .flat_map(|literal| literal.variables())
+    {
+        if !positive_variables.contains(variable) {
+            positive_variables.push(variable.clone());
+        }
+    }
+
+    let mut result = vec![rule.clone()];
+    let computed_variables = std::mem::take(result[0].computed_variables_mut());
+
+    for (function_index, (variable, function)) in computed_variables.into_iter().enumerate() {
+        let predicate = function_filter_predicate(rule_index, function_index);
+        let variable_terms = positive_variables
+            .iter()
+            .map(|variable| TermTree::leaf(Term::Variable(variable.clone())));
+
+        result[0].body_mut().push(Literal::Positive(Atom::new(
+            predicate.clone(),
+            variable_terms
+                .clone()
+                .chain(std::iter::once(TermTree::leaf(Term::Variable(variable))))
+                .collect(),
+        )));
+        result.push(
+            Rule::new(
+                vec![Atom::new(
+                    predicate,
+                    variable_terms.chain(std::iter::once(function)).collect(),
+                )],
+                positive_literals.clone(),
+                Vec::new(),
+            )
+            .with_priority(rule.priority()),
+        );
+    }
+
+    result
+}
+
+/// Return the name of the auxiliary predicate that represents
+/// a negated conjunction of a rule.
+fn negated_conjunction_predicate(rule_index: usize, conjunction_index: usize) -> Identifier {
+    Identifier(format!(
+        "NEGATED_CONJUNCTION_IDENTIFIER_FOR_RULE_{rule_index}_{conjunction_index}"
+    ))
+}
+
+/// Replace each negated conjunction in the body of the given rule by a negated auxiliary atom.
+///
+/// The auxiliary predicate is derived by an additional rule, which has the conjunction as its body
+/// and contains the variables that the conjunction shares with the rest of the rule.
+/// Returns the transformed rule followed by the additional rules.
+fn compile_negated_conjunctions(rule: &Rule, rule_index: usize) -> Vec<Rule> {
+    if rule.negated_conjunctions().is_empty() {
+        return vec![rule.clone()];
+    }
+
+    let positive_variables = rule
+        .body()
+        .iter()
+        .filter(|literal| literal.is_positive())
+        .flat_map(|literal| literal.variables())
+        .collect::<HashSet<_>>();
+
+    let mut body = rule.body().clone();
+    let mut auxiliary_rules = Vec::new();
+
+    for (conjunction_index, conjunction) in rule.negated_conjunctions().iter().enumerate() {
+        let mut shared_variables = Vec::<Variable>::new();
+        for variable in conjunction.iter().flat_map(|atom| atom.variables()) {
+            if positive_variables.contains(variable) && !shared_variables.contains(variable) {
+                shared_variables.push(variable.clone());
+            }
+        }
+
+        let auxiliary_atom = Atom::new(
+            negated_conjunction_predicate(rule_index, conjunction_index),
+            shared_variables
+                .into_iter()
+                .map(|variable| TermTree::leaf(Term::Variable(variable)))
+                .collect(),
+        );
+
+        body.push(Literal::Negative(auxiliary_atom.clone()));
+        auxiliary_rules.push(
+            Rule::new(
+                vec![auxiliary_atom],
+                conjunction.iter().cloned().map(Literal::Positive).collect(),
+                Vec::new(),
+            )
+            .with_priority(rule.priority()),
+        );
+    }
+
+    let mut result =
+        vec![Rule::new(rule.head().clone(), body, rule.filters().clone())
+            .with_priority(rule.priority())];
+    result.extend(auxiliary_rules);
+
+    result
+}
+
+/// Return the name of the auxiliary predicate that collects the values
+/// from which the aggregate of the given predicate is computed.
+fn aggregate_input_predicate(predicate: &Identifier) -> Identifier {
+    Identifier(format!("AGGREGATE_INPUT_FOR_PREDICATE_{predicate}"))
+}
+
+/// Shape of the aggregate of a predicate,
+/// which has to be the same for all rules deriving this predicate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct AggregateShape {
+    aggregate: ChaseAggregate,
+    arity: usize,
+    variables: usize,
+}
+
+/// Replace a rule with an aggregate in its head by a rule
+/// that derives the auxiliary input predicate of the aggregate.
+///
+/// The input predicate contains the terms of the head atom except for the aggregate,
+/// followed by the aggregated variables.
+/// Returns `None` if the rule does not contain an aggregate.
+fn compile_aggregate(
+    rule: &Rule,
+) -> Result<Option<(Rule, Identifier, AggregateShape)>, AggregateError> {
+    let Some(atom) = rule
+        .head()
+        .iter()
+        .find(|atom| atom.aggregates().next().is_some())
+    else {
+        return Ok(None);
+    };
+    let predicate = atom.predicate();
+
+    let position = atom.term_trees().iter().position(|term_tree| {
+        matches!(
+            term_tree.operation(),
+            TermOperation::Term(Term::Aggregate(_))
+        )
+    });
+
+    let (Some(position), 1, 1, 0) = (
+        position,
+        rule.head().len(),
+        atom.aggregates().count(),
+        atom.existential_variables().count(),
+    ) else {
+        return Err(AggregateError::UnsupportedHead(predicate));
+    };
+
+    let Some(aggregate) = atom.aggregates().next() else {
+        unreachable!("the head atom contains an aggregate")
+    };
+
+    let operation = AggregateOperation::from_identifier(&aggregate.aggregate_identifier)?;
+    if !operation.accepts_variables(aggregate.variable_identifiers.len()) {
+        return Err(AggregateError::InvalidVariableCount(operation));
+    }
+
+    let positive_variables = rule
+        .body()
+        .iter()
+        .filter(|literal| literal.is_positive())
+        .flat_map(|literal| literal.variables())
+        .collect::<HashSet<_>>();
+
+    let mut aggregated_terms = Vec::new();
+    for identifier in &aggregate.variable_identifiers {
+        let variable = Variable::Universal(identifier.clone());
+
+        if !positive_variables.contains(&variable) {
+            return Err(AggregateError::UnsafeVariable(variable.name()));
+        }
+
+        if atom
+            .variables()
+            .any(|head_variable| *head_variable == variable)
+        {
+            return Err(AggregateError::GroupedVariable(variable.name()));
+        }
+
+        aggregated_terms.push(TermTree::leaf(Term::Variable(variable)));
+    }
+
+    let shape = AggregateShape {
+        aggregate: ChaseAggregate::new(operation, position),
+        arity: atom.term_trees().len(),
+        variables: aggregated_terms.len(),
+    };
+
+    let mut input_terms = atom.term_trees().clone();
+    input_terms.remove(position);
+    input_terms.extend(aggregated_terms);
+
+    let input_rule = Rule::new(
+        vec![Atom::new(
+            aggregate_input_predicate(&predicate),
+            input_terms,
+        )],
+        rule.body().clone(),
+        rule.filters().clone(),
+    )
+    .with_priority(rule.priority());
+
+    Ok(Some((input_rule, predicate, shape)))
+}
+
+/// Construct the rule that computes the aggregate of the given predicate from its input predicate.
+fn aggregate_rule(predicate: &Identifier, shape: AggregateShape) -> ChaseRule {
+    let variable = |name: String| Term::Variable(Variable::Universal(Identifier(name)));
+
+    let group_terms = (0..shape.arity - 1)
+        .map(|index| variable(format!("AGGREGATE_GROUP_{index}")))
+        .collect::<Vec<_>>();
+    let value_terms = (0..shape.variables)
+        .map(|index| variable(format!("AGGREGATE_VALUE_{index}")))
+        .collect::<Vec<_>>();
+
+    // Except for counting, the result of the aggregate is one of the aggregated values,
+    // so its type is the type of the aggregated variable
+    let result_term = match shape.aggregate.operation() {
+        AggregateOperation::Count => variable("AGGREGATE_RESULT".to_string()),
+        _ => value_terms[0].clone(),
+    };
+
+    let mut head_terms = group_terms.clone();
+    head_terms.insert(shape.aggregate.position(), result_term);
+
+    let mut body_terms = group_terms;
+    body_terms.extend(value_terms);
+
+    ChaseRule::new(
+        vec![ChaseAtom::new(predicate.clone(), head_terms)],
+        HashMap::new(),
+        vec![ChaseAtom::new(
+            aggregate_input_predicate(predicate),
+            body_terms,
+        )],
+        Vec::new(),
+        Vec::new(),
+        Vec::new(),
+    )
+    .with_aggregate(shape.aggregate)
+}
+
+/// Translate the rules of the given program into [`ChaseRule`]s.
+///
+/// Each rule with an aggregate in its head is replaced by a rule
+/// deriving the input of the aggregate,
+/// and for each aggregated predicate a single rule computing the aggregate is added.
+/// Auxiliary rules inherit the priority of the rule they originate from.
+fn compile_rules(program: &Program) -> Result<Vec<ChaseRule>, Error> {
+    let mut rules = Vec::<ChaseRule>::new();
+    let mut aggregates = BTreeMap::<Identifier, AggregateShape>::new();
+
+    let compiled_rules = program
+        .rules()
+        .iter()
+        .flat_map(|rule| rule.disjuncts())
+        .enumerate()
+        .flat_map(|(rule_index, rule)| {
+            compile_function_filters(&rule, rule_index)
+                .into_iter()
+                .flat_map(move |rule| compile_negated_conjunctions(&rule, rule_index))
+        });
+
+    for rule in compiled_rules {
+        let Some((input_rule, predicate, shape)) = compile_aggregate(&rule)? else {
+            rules.push(rule.try_into()?);
+            continue;
+        };
+
+        if *aggregates.entry(predicate.clone()).or_insert(shape) != shape {
+            return Err(AggregateError::InconsistentDerivation(predicate).into());
+        }
+
+        rules.push(input_rule.try_into()?);
+    }
+
+    for predicate in aggregates.keys() {
+        let derived_otherwise = rules
+            .iter()
+            .flat_map(|rule| rule.head())
+            .any(|atom| atom.predicate() == *predicate)
+            || program
+                .facts()
+                .iter()
+                .any(|fact| fact.0.predicate() == *predicate)
+            || program
+                .sources()
+                .any(|source| source.predicate == *predicate);
+
+        if derived_otherwise {
+            return Err(AggregateError::InconsistentDerivation(predicate.clone()).into());
+        }
+    }
+
+    rules.extend(
+        aggregates
+            .iter()
+            .map(|(predicate, shape)| aggregate_rule(predicate, *shape)),
+    );
+
+    Ok(rules)
+}
+
+/// Return the name of the auxiliary predicate that collects the annotations
+/// of all derivations of the facts of the given predicate.
+fn annotation_candidate_predicate(predicate: &Identifier) -> Identifier {
+    Identifier(format!("ANNOTATION_CANDIDATE_FOR_PREDICATE_{predicate}"))
+}
+
+/// Combine the annotations of different derivations of the same fact according to the given semiring.
+///
+/// The rules and facts of each annotated predicate derive an auxiliary candidate predicate instead,
+/// from which the annotated predicate is obtained by an additional rule
+/// that selects the best annotation of each tuple by an aggregate.
+/// Returns the resulting program together with the annotated predicates.
+fn compile_annotations(program: Program, semiring: Semiring) -> (Program, HashSet<Identifier>) {
+    let mut arities = BTreeMap::<Identifier, usize>::new();
+    let mut candidate_atom = |atom: &mut Atom| {
+        let predicate = atom.predicate();
+        atom.set_predicate(annotation_candidate_predicate(&predicate));
+        arities.insert(predicate, atom.term_trees().len());
+    };
+
+    let mut rules = program
+        .rules()
+        .iter()
+        .flat_map(|rule| rule.disjuncts())
+        .collect::<Vec<_>>();
+    for rule in &mut rules {
+        rule.head_mut().iter_mut().for_each(&mut candidate_atom);
+    }
+
+    let mut facts = program.facts().clone();
+    for Fact(atom) in &mut facts {
+        candidate_atom(atom);
+    }
+
+    rules.extend(arities.iter().map(|(predicate, arity)| {
+        semiring.selection_rule(
+            predicate,
+            &annotation_candidate_predicate(predicate),
+            *arity,
+        )
+    }));
+
+    let mut declarations = program.parsed_predicate_declarations();
+    for predicate in arities.keys() {
+        if let Some(types) = declarations.get(predicate).cloned() {
+            declarations.insert(annotation_candidate_predicate(predicate), types);
+        }
+    }
+
+    let annotated_predicates = arities
+        .into_keys()
+        .flat_map(|predicate| [annotation_candidate_predicate(&predicate), predicate])
+        .collect();
+
+    let annotated_program = Program::new(
+        program.base(),
+        program.prefixes().clone(),
+        program.sources().cloned().collect(),
+        rules,
+        facts,
+        declarations,
+        program.selected_output_predicates().into(),
+    )
+    .with_predicate_limits(program.predicate_limits().clone());
+
+    (annotated_program, annotated_predicates)
+}
+
+/// Translate a program without annotations into a [`ChaseProgram`].
+fn compile_program(program: Program) -> Result<ChaseProgram, Error> {
+    let temporal_predicates = program.temporal_predicates().clone();
+    let predicate_limits = program.predicate_limits().clone();
+
+    Ok(ChaseProgram::new(
+        program.base(),
+        program.prefixes().clone(),
+        program.sources().cloned().collect(),
+        compile_rules(&program)?,
+        program.facts().to_vec(),
+        program.parsed_predicate_declarations(),
+        program
+            .output_predicates()
+            .map(QualifiedPredicateName::new)
+            .collect::<Vec<_>>()
+            .into(),
+    )
+    .with_temporal_predicates(temporal_predicates)
+    .with_predicate_limits(predicate_limits))
+}
+
 impl TryFrom<Program> for ChaseProgram {
     type Error = Error;
 
     fn try_from(program: Program) -> Result<Self, Error> {
-        Ok(Self::new(
-            program.base(),
-            program.prefixes().clone(),
-            program.sources().cloned().collect(),
-            program
-                .rules()
-                .iter()
-                .map(|rule| rule.clone().try_into())
-                .collect::<Result<Vec<ChaseRule>, Error>>()?,
-            program.facts().to_vec(),
-            program.parsed_predicate_declarations(),
-            program
-                .output_predicates()
-                .map(QualifiedPredicateName::new)
-                .collect::<Vec<_>>()
-                .into(),
-        ))
+        let Some(semiring) = program.semiring() else {
+            return compile_program(program);
+        };
+
+        let (program, annotated_predicates) = compile_annotations(program, semiring);
+        Ok(compile_program(program)?.with_annotated_predicates(annotated_predicates))
     }
 }
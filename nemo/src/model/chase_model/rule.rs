@@ -7,7 +7,7 @@ use crate::{
     model::{Filter, Identifier, Literal, Rule, Term, TermOperation, TermTree, Variable},
 };
 
-use super::ChaseAtom;
+use super::{ChaseAggregate, ChaseAtom};
 
 /// Representation of a rule in a [`super::ChaseProgram`].
 #[allow(dead_code)]
@@ -25,6 +25,10 @@ pub struct ChaseRule {
     negative_body: Vec<ChaseAtom>,
     /// Filters applied to the body
     negative_filters: Vec<Filter>,
+    /// Aggregate computed by the rule
+    aggregate: Option<ChaseAggregate>,
+    /// Priority of the rule, see [`Rule::priority`]
+    priority: i64,
 }
 
 #[allow(dead_code)]
@@ -45,8 +49,33 @@ impl ChaseRule {
             positive_filters,
             negative_body,
             negative_filters,
+            aggregate: None,
+            priority: 0,
         }
     }
+
+    /// Turn this rule into a rule that computes the given aggregate.
+    pub fn with_aggregate(mut self, aggregate: ChaseAggregate) -> Self {
+        self.aggregate = Some(aggregate);
+        self
+    }
+
+    /// Return the aggregate computed by the rule, if any.
+    pub fn aggregate(&self) -> Option<&ChaseAggregate> {
+        self.aggregate.as_ref()
+    }
+
+    /// Assign the given priority to this rule.
+    pub fn with_priority(mut self, priority: i64) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    /// Return the priority of the rule.
+    pub fn priority(&self) -> i64 {
+        self.priority
+    }
+
     /// Return the head atoms of the rule - immutable.
     #[must_use]
     pub fn head(&self) -> &Vec<ChaseAtom> {
@@ -130,6 +159,28 @@ impl ChaseRule {
     pub fn negative_filters_mut(&mut self) -> &mut Vec<Filter> {
         &mut self.negative_filters
     }
+
+    /// Return the predicates of the rule in the form `head :- body`,
+    /// where negated body predicates are prefixed with `~`.
+    pub fn predicate_outline(&self) -> String {
+        let head = self
+            .head
+            .iter()
+            .map(|atom| atom.predicate().to_string())
+            .collect::<Vec<_>>();
+        let body = self
+            .positive_body
+            .iter()
+            .map(|atom| atom.predicate().to_string())
+            .chain(
+                self.negative_body
+                    .iter()
+                    .map(|atom| format!("~{}", atom.predicate())),
+            )
+            .collect::<Vec<_>>();
+
+        format!("{} :- {}", head.join(", "), body.join(", "))
+    }
 }
 
 impl TryFrom<Rule> for ChaseRule {
@@ -177,6 +228,8 @@ impl TryFrom<Rule> for ChaseRule {
             negative_body,
             positive_filters: rule.filters().clone(),
             negative_filters: Vec::new(),
+            aggregate: None,
+            priority: rule.priority(),
         })
     }
 }
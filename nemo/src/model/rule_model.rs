@@ -26,3 +26,18 @@ pub use term::*;
 
 mod term_operation;
 pub use term_operation::*;
+
+mod visitor;
+pub use visitor::*;
+
+mod program_builder;
+pub use program_builder::*;
+
+mod arity_check;
+pub use arity_check::*;
+
+mod semiring;
+pub use semiring::*;
+
+mod temporal;
+pub use temporal::*;
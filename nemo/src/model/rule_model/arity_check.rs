@@ -0,0 +1,238 @@
+//! Checking that every predicate of a [`Program`] is used with a single arity.
+
+use std::collections::BTreeMap;
+
+use thiserror::Error;
+
+use crate::model::{DataSource, TypeConstraint};
+
+use super::{
+    walk_fact, walk_rule, Atom, DataSourceDeclaration, Fact, Identifier, Program, ProgramVisitor,
+    Rule,
+};
+
+/// Place in a program where a predicate is used.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PredicateUsage {
+    /// A type declaration
+    Declaration,
+    /// A data source declaration
+    Source,
+    /// The fact with the given index
+    Fact(usize),
+    /// The rule with the given index
+    Rule(usize),
+    /// An output directive
+    Output,
+}
+
+impl std::fmt::Display for PredicateUsage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Declaration => write!(f, "type declaration"),
+            Self::Source => write!(f, "data source"),
+            Self::Fact(index) => write!(f, "fact {index}"),
+            Self::Rule(index) => write!(f, "rule {index}"),
+            Self::Output => write!(f, "output directive"),
+        }
+    }
+}
+
+/// A predicate that is used with different arities.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArityConflict {
+    /// The predicate
+    pub predicate: Identifier,
+    /// Every usage of the predicate together with its arity, in the order of the program
+    pub usages: Vec<(PredicateUsage, usize)>,
+}
+
+impl ArityConflict {
+    /// Return the different arities of the predicate in the order of their first usage.
+    pub fn arities(&self) -> Vec<usize> {
+        let mut arities = Vec::new();
+        for (_, arity) in &self.usages {
+            if !arities.contains(arity) {
+                arities.push(*arity);
+            }
+        }
+
+        arities
+    }
+}
+
+impl std::fmt::Display for ArityConflict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let arities = self
+            .arities()
+            .into_iter()
+            .map(|arity| {
+                let usages = self
+                    .usages
+                    .iter()
+                    .filter(|(_, usage_arity)| *usage_arity == arity)
+                    .map(|(usage, _)| usage.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+
+                format!("arity {arity} in {usages}")
+            })
+            .collect::<Vec<_>>()
+            .join("; ");
+
+        write!(f, "\"{}\" is used with {arities}", self.predicate)
+    }
+}
+
+/// Error reporting all predicates of a program that are used with different arities.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+#[error("Predicates are used with different arities:\n{}", format_conflicts(.0))]
+pub struct ArityError(pub Vec<ArityConflict>);
+
+fn format_conflicts(conflicts: &[ArityConflict]) -> String {
+    conflicts
+        .iter()
+        .map(|conflict| format!("  {conflict}"))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Collects the arities with which each predicate is used.
+#[derive(Debug, Default)]
+struct ArityCollector {
+    usages: BTreeMap<Identifier, Vec<(PredicateUsage, usize)>>,
+    current: Option<PredicateUsage>,
+    facts: usize,
+    rules: usize,
+}
+
+impl ArityCollector {
+    fn add(&mut self, predicate: Identifier, usage: PredicateUsage, arity: usize) {
+        self.usages
+            .entry(predicate)
+            .or_default()
+            .push((usage, arity));
+    }
+}
+
+impl ProgramVisitor for ArityCollector {
+    fn visit_source(&mut self, source: &DataSourceDeclaration) {
+        self.add(
+            source.predicate.clone(),
+            PredicateUsage::Source,
+            source.input_types().arity(),
+        );
+    }
+
+    fn visit_fact(&mut self, fact: &Fact) {
+        self.current = Some(PredicateUsage::Fact(self.facts));
+        self.facts += 1;
+        walk_fact(self, fact);
+    }
+
+    fn visit_rule(&mut self, rule: &Rule) {
+        self.current = Some(PredicateUsage::Rule(self.rules));
+        self.rules += 1;
+        walk_rule(self, rule);
+    }
+
+    fn visit_atom(&mut self, atom: &Atom) {
+        let usage = self.current.expect("atoms only occur in facts and rules");
+        self.add(atom.predicate(), usage, atom.term_trees().len());
+    }
+}
+
+impl Program {
+    /// Check that every predicate is used with the same arity
+    /// in all type declarations, data sources, facts, rules and output directives.
+    ///
+    /// All predicates violating this are reported at once, together with the places where they are used.
+    pub fn check_arities(&self) -> Result<(), ArityError> {
+        let mut collector = ArityCollector::default();
+
+        for (predicate, types) in self.parsed_predicate_declarations() {
+            collector.add(predicate, PredicateUsage::Declaration, types.len());
+        }
+
+        self.accept(&mut collector);
+
+        for output in self.selected_output_predicates() {
+            if let Some(TypeConstraint::Tuple(types)) = &output.associated_type {
+                collector.add(output.identifier, PredicateUsage::Output, types.arity());
+            }
+        }
+
+        let conflicts = collector
+            .usages
+            .into_iter()
+            .map(|(predicate, usages)| ArityConflict { predicate, usages })
+            .filter(|conflict| conflict.arities().len() > 1)
+            .collect::<Vec<_>>();
+
+        if conflicts.is_empty() {
+            Ok(())
+        } else {
+            Err(ArityError(conflicts))
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::io::parser::parse_program;
+
+    use super::{ArityConflict, ArityError, PredicateUsage};
+
+    #[test]
+    fn consistent_arities() {
+        let program = parse_program(
+            "@declare edge(integer, integer) .\n\
+             edge(1, 2) .\n\
+             path(?X, ?Y) :- edge(?X, ?Y) .\n\
+             @output path[2] .\n",
+        )
+        .unwrap();
+
+        assert_eq!(program.check_arities(), Ok(()));
+    }
+
+    #[test]
+    fn conflicting_arities() {
+        let program = parse_program(
+            "p(a) .\n\
+             p(a, b) .\n\
+             q(?X) :- p(?X), r(?X) .\n\
+             r(?X, ?Y) :- p(?X, ?Y) .\n\
+             @output q[2] .\n",
+        )
+        .unwrap();
+
+        let error = program.check_arities().unwrap_err();
+        assert_eq!(
+            error,
+            ArityError(vec![
+                ArityConflict {
+                    predicate: "p".into(),
+                    usages: vec![
+                        (PredicateUsage::Fact(0), 1),
+                        (PredicateUsage::Fact(1), 2),
+                        (PredicateUsage::Rule(0), 1),
+                        (PredicateUsage::Rule(1), 2),
+                    ],
+                },
+                ArityConflict {
+                    predicate: "q".into(),
+                    usages: vec![(PredicateUsage::Rule(0), 1), (PredicateUsage::Output, 2)],
+                },
+                ArityConflict {
+                    predicate: "r".into(),
+                    usages: vec![(PredicateUsage::Rule(0), 1), (PredicateUsage::Rule(1), 2)],
+                },
+            ])
+        );
+        assert_eq!(
+            error.0[0].to_string(),
+            "\"p\" is used with arity 1 in fact 0, rule 0; arity 2 in fact 1, rule 1"
+        );
+    }
+}
@@ -24,6 +24,11 @@ impl Atom {
         self.predicate.clone()
     }
 
+    /// Replace the predicate of the atom.
+    pub(crate) fn set_predicate(&mut self, predicate: Identifier) {
+        self.predicate = predicate;
+    }
+
     /// Return the terms trees in the atom - immutable.
     #[must_use]
     pub fn term_trees(&self) -> &Vec<TermTree> {
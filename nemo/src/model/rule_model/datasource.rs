@@ -1,9 +1,9 @@
-use std::fmt::Debug;
+use std::{fmt::Debug, str::FromStr};
 
 use nemo_physical::table_reader::Resource;
 
 use crate::{
-    io::parser::ParseError,
+    io::{formats::RdfFormat, parser::ParseError, Encoding},
     model::{PrimitiveType, TupleConstraint, TypeConstraint},
 };
 
@@ -25,6 +25,10 @@ pub struct DsvFile {
     pub resource: Resource,
     /// the delimiter separating the values
     pub delimiter: u8,
+    /// the character encoding of the resource
+    pub encoding: Encoding,
+    /// whether the rows of the resource are known to be sorted, e.g. since it has been exported by nemo
+    pub sorted: bool,
     /// Input Types
     input_types: TupleConstraint,
 }
@@ -37,6 +41,8 @@ impl DsvFile {
         Self {
             resource: path.to_string(),
             delimiter,
+            encoding: Encoding::default(),
+            sorted: false,
             input_types: input_types
                 .iter()
                 .map(|tc| match tc {
@@ -56,6 +62,19 @@ impl DsvFile {
     pub fn tsv_file(path: &str, input_types: TupleConstraint) -> Self {
         Self::new(path, b'\t', input_types)
     }
+
+    /// Set the character encoding of the file.
+    pub fn with_encoding(mut self, encoding: Encoding) -> Self {
+        self.encoding = encoding;
+        self
+    }
+
+    /// Mark the rows of the file as sorted,
+    /// such that its table can be built without sorting them.
+    pub fn with_sorted_rows(mut self) -> Self {
+        self.sorted = true;
+        self
+    }
 }
 
 impl DataSource for DsvFile {
@@ -68,6 +87,173 @@ impl DataSource for DsvFile {
     }
 }
 
+/// A rectangular range of cells of a spreadsheet, given in A1 notation, e.g. `A2:C10`.
+///
+/// Rows and columns are counted from zero.
+/// A range that consists of a single cell, e.g. `A2`, starts at this cell
+/// and extends to the last row of the sheet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CellRange {
+    /// Row and column of the top left cell
+    pub start: (u32, u32),
+    /// Row and column of the bottom right cell, if the range is bounded
+    pub end: Option<(u32, u32)>,
+}
+
+impl CellRange {
+    /// Number of columns of the range, or `None` if the range is unbounded.
+    pub fn width(&self) -> Option<usize> {
+        self.end
+            .map(|(_, end_column)| (end_column - self.start.1) as usize + 1)
+    }
+
+    /// Parse a single cell reference, e.g. `C10`, into its row and column.
+    fn parse_cell(cell: &str) -> Option<(u32, u32)> {
+        let digits = cell.find(|c: char| c.is_ascii_digit())?;
+        let (letters, number) = cell.split_at(digits);
+
+        if !(1..=3).contains(&letters.len()) || !letters.chars().all(|c| c.is_ascii_alphabetic()) {
+            return None;
+        }
+        if !number.chars().all(|c| c.is_ascii_digit()) {
+            return None;
+        }
+
+        let column = letters.chars().fold(0, |column, letter| {
+            column * 26 + u32::from(letter.to_ascii_uppercase() as u8 - b'A') + 1
+        });
+        let row = number.parse::<u32>().ok().filter(|&row| row > 0)?;
+
+        Some((row - 1, column - 1))
+    }
+
+    /// Format the given row and column as a cell reference.
+    fn format_cell(f: &mut std::fmt::Formatter<'_>, (row, column): (u32, u32)) -> std::fmt::Result {
+        let mut letters = Vec::new();
+        let mut remaining = column + 1;
+        while remaining > 0 {
+            letters.push(char::from(b'A' + ((remaining - 1) % 26) as u8));
+            remaining = (remaining - 1) / 26;
+        }
+
+        write!(f, "{}{}", letters.iter().rev().collect::<String>(), row + 1)
+    }
+}
+
+impl FromStr for CellRange {
+    type Err = ParseError;
+
+    fn from_str(range: &str) -> Result<Self, Self::Err> {
+        let invalid = || ParseError::InvalidCellRange(range.to_string());
+
+        match range.trim().split_once(':') {
+            Some((start, end)) => {
+                let start = Self::parse_cell(start).ok_or_else(invalid)?;
+                let end = Self::parse_cell(end).ok_or_else(invalid)?;
+
+                if end.0 < start.0 || end.1 < start.1 {
+                    return Err(invalid());
+                }
+
+                Ok(Self {
+                    start,
+                    end: Some(end),
+                })
+            }
+            None => Ok(Self {
+                start: Self::parse_cell(range.trim()).ok_or_else(invalid)?,
+                end: None,
+            }),
+        }
+    }
+}
+
+impl std::fmt::Display for CellRange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Self::format_cell(f, self.start)?;
+
+        if let Some(end) = self.end {
+            write!(f, ":")?;
+            Self::format_cell(f, end)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// A sheet of an Excel (XLSX) file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct XlsxFile {
+    /// the XLSX resource
+    pub resource: Resource,
+    /// the name of the sheet, or `None` for the first sheet of the file
+    pub sheet: Option<String>,
+    /// the range of cells containing the facts, or `None` for all used cells of the sheet
+    pub range: Option<CellRange>,
+    /// Input Types
+    input_types: TupleConstraint,
+}
+
+impl XlsxFile {
+    const DEFAULT_COLUMN_TYPE: PrimitiveType = PrimitiveType::Any;
+
+    /// Construct a new XLSX file data source from a given path.
+    pub fn new(
+        path: &str,
+        sheet: Option<String>,
+        range: Option<CellRange>,
+        input_types: TupleConstraint,
+    ) -> Self {
+        Self {
+            resource: path.to_string(),
+            sheet,
+            range,
+            input_types: input_types
+                .iter()
+                .map(|tc| match tc {
+                    TypeConstraint::None => TypeConstraint::AtLeast(Self::DEFAULT_COLUMN_TYPE),
+                    _ => tc.clone(),
+                })
+                .collect(),
+        }
+    }
+
+    /// Construct a new XLSX file data source,
+    /// checking that the cell range has enough columns for the predicate.
+    pub(crate) fn new_validated(
+        path: &str,
+        sheet: Option<String>,
+        range: Option<CellRange>,
+        predicate: &Identifier,
+        tuple_constraint: TupleConstraint,
+    ) -> Result<Self, ParseError> {
+        let arity = tuple_constraint.arity();
+
+        if let Some(width) = range
+            .and_then(|range| range.width())
+            .filter(|&width| width < arity)
+        {
+            return Err(ParseError::XlsxSourceInvalidArity(
+                predicate.name(),
+                arity,
+                width,
+            ));
+        }
+
+        Ok(Self::new(path, sheet, range, tuple_constraint))
+    }
+}
+
+impl DataSource for XlsxFile {
+    fn input_types(&self) -> TupleConstraint {
+        self.input_types.clone()
+    }
+
+    fn resources(&self) -> Vec<Resource> {
+        vec![self.resource.clone()]
+    }
+}
+
 /// An RDF file.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct RdfFile {
@@ -75,6 +261,8 @@ pub struct RdfFile {
     pub resource: Resource,
     /// the optional base IRI
     pub base: Option<String>,
+    /// the format of the resource, or `None` if it should be detected from its content
+    pub format: Option<RdfFormat>,
 }
 
 impl RdfFile {
@@ -85,9 +273,16 @@ impl RdfFile {
         Self {
             resource: path.to_string(),
             base,
+            format: None,
         }
     }
 
+    /// Set the format of the file, instead of detecting it from its content.
+    pub fn with_format(mut self, format: RdfFormat) -> Self {
+        self.format = Some(format);
+        self
+    }
+
     // TODO: it should not be possible to specify types or arities for Rdf sources;
     // this will change in the future
     pub(crate) fn new_validated(
@@ -213,31 +408,128 @@ impl DataSource for SparqlQuery {
     }
 }
 
+/// A source whose facts are provided by the application at runtime.
+///
+/// The facts are obtained from the
+/// [`ExternalSourceProvider`][crate::io::resource_providers::external::ExternalSourceProvider]
+/// that is registered under the name of the source.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExternalSource {
+    /// The name under which the provider of the facts is registered
+    pub name: String,
+    /// Input Types
+    input_types: TupleConstraint,
+}
+
+impl ExternalSource {
+    const DEFAULT_COLUMN_TYPE: PrimitiveType = PrimitiveType::Any;
+
+    /// Construct a new external source with the given name.
+    pub fn new(name: &str, input_types: TupleConstraint) -> Self {
+        Self {
+            name: name.to_string(),
+            input_types: input_types
+                .iter()
+                .map(|tc| match tc {
+                    TypeConstraint::None => TypeConstraint::AtLeast(Self::DEFAULT_COLUMN_TYPE),
+                    _ => tc.clone(),
+                })
+                .collect(),
+        }
+    }
+}
+
+impl DataSource for ExternalSource {
+    fn input_types(&self) -> TupleConstraint {
+        self.input_types.clone()
+    }
+
+    fn resources(&self) -> Vec<Resource> {
+        vec![]
+    }
+}
+
+/// A resource in a format that is registered by the application.
+///
+/// The facts are read by the
+/// [`ImportFormat`][crate::io::formats::ImportFormat]
+/// registered under the given name or, if no name is given, for the extension of the resource.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CustomFormatFile {
+    /// The resource containing the facts
+    pub resource: Resource,
+    /// The name of the format, if given explicitly
+    pub format: Option<String>,
+    /// Input Types
+    input_types: TupleConstraint,
+}
+
+impl CustomFormatFile {
+    const DEFAULT_COLUMN_TYPE: PrimitiveType = PrimitiveType::Any;
+
+    /// Construct a new data source for a resource in a registered format.
+    pub fn new(path: &str, format: Option<String>, input_types: TupleConstraint) -> Self {
+        Self {
+            resource: path.to_string(),
+            format,
+            input_types: input_types
+                .iter()
+                .map(|tc| match tc {
+                    TypeConstraint::None => TypeConstraint::AtLeast(Self::DEFAULT_COLUMN_TYPE),
+                    _ => tc.clone(),
+                })
+                .collect(),
+        }
+    }
+}
+
+impl DataSource for CustomFormatFile {
+    fn input_types(&self) -> TupleConstraint {
+        self.input_types.clone()
+    }
+
+    fn resources(&self) -> Vec<Resource> {
+        vec![self.resource.clone()]
+    }
+}
+
 /// An external data source.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum NativeDataSource {
     /// A DSV (delimiter-separated values) resource data source with the given path and delimiter.
     DsvFile(DsvFile),
+    /// A sheet of an XLSX file with an optional range of cells.
+    XlsxFile(XlsxFile),
     /// An RDF file data source with the given path and optional base IRI.
     RdfFile(RdfFile),
     /// A SPARQL query data source.
     SparqlQuery(SparqlQuery),
+    /// A data source whose facts are provided by the application.
+    External(ExternalSource),
+    /// A resource in a format that is registered by the application.
+    CustomFormat(CustomFormatFile),
 }
 
 impl DataSource for NativeDataSource {
     fn input_types(&self) -> TupleConstraint {
         match self {
             Self::DsvFile(d) => d.input_types(),
+            Self::XlsxFile(x) => x.input_types(),
             Self::RdfFile(r) => r.input_types(),
             Self::SparqlQuery(s) => s.input_types(),
+            Self::External(e) => e.input_types(),
+            Self::CustomFormat(c) => c.input_types(),
         }
     }
 
     fn resources(&self) -> Vec<Resource> {
         match self {
             Self::DsvFile(d) => d.resources(),
+            Self::XlsxFile(x) => x.resources(),
             Self::RdfFile(r) => r.resources(),
             Self::SparqlQuery(s) => s.resources(),
+            Self::External(e) => e.resources(),
+            Self::CustomFormat(c) => c.resources(),
         }
     }
 }
@@ -247,21 +539,81 @@ impl DataSource for NativeDataSource {
 pub struct DataSourceDeclaration {
     pub(crate) predicate: Identifier,
     pub(crate) source: NativeDataSource,
+    /// Whether duplicate tuples of the source are counted (see [`DataSourceDeclaration::is_bag`])
+    pub(crate) bag: bool,
 }
 
 impl DataSourceDeclaration {
     /// Construct a new data source declaration.
     pub(crate) fn new(predicate: Identifier, source: NativeDataSource) -> Self {
-        Self { predicate, source }
+        Self {
+            predicate,
+            source,
+            bag: false,
+        }
+    }
+
+    /// Construct a new data source declaration,
+    /// which treats the tuples of the source as a bag.
+    pub(crate) fn new_bag(predicate: Identifier, source: NativeDataSource) -> Self {
+        Self {
+            predicate,
+            source,
+            bag: true,
+        }
+    }
+
+    /// Return whether the tuples of the source are treated as a bag instead of a set.
+    ///
+    /// In this case, each distinct tuple is imported once,
+    /// followed by an additional integer column containing the number of its occurrences.
+    #[must_use]
+    pub fn is_bag(&self) -> bool {
+        self.bag
     }
 }
 
 impl DataSource for DataSourceDeclaration {
     fn input_types(&self) -> TupleConstraint {
-        self.source.input_types()
+        let input_types = self.source.input_types();
+
+        if self.bag {
+            input_types
+                .iter()
+                .cloned()
+                .chain(std::iter::once(TypeConstraint::Exact(
+                    PrimitiveType::Integer,
+                )))
+                .collect()
+        } else {
+            input_types
+        }
     }
 
     fn resources(&self) -> Vec<Resource> {
         self.source.resources()
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::CellRange;
+
+    #[test]
+    fn parse_cell_range() {
+        let range: CellRange = "b2:AA10".parse().unwrap();
+        assert_eq!(range.start, (1, 1));
+        assert_eq!(range.end, Some((9, 26)));
+        assert_eq!(range.width(), Some(26));
+        assert_eq!(range.to_string(), "B2:AA10");
+
+        let range: CellRange = "C3".parse().unwrap();
+        assert_eq!(range.start, (2, 2));
+        assert_eq!(range.end, None);
+        assert_eq!(range.to_string(), "C3");
+
+        for invalid in ["", "A0", "3B", "A1:", "C3:A1", "ABCD1", "A1:B2:C3"] {
+            assert!(invalid.parse::<CellRange>().is_err(), "{invalid}");
+        }
+    }
+}
@@ -15,6 +15,10 @@ pub enum FilterOperation {
     LessThanEq,
     /// Value of variable is gretaer than or equal to the value of the term
     GreaterThanEq,
+    /// Value of variable is a string containing the words of the text query given by the term
+    MatchesText,
+    /// Value of variable is a string within the given edit distance of the string given by the term
+    EditDistanceAtMost(usize),
 }
 
 impl FilterOperation {
@@ -28,6 +32,8 @@ impl FilterOperation {
             Self::GreaterThan => Self::LessThan,
             Self::LessThanEq => Self::GreaterThanEq,
             Self::GreaterThanEq => Self::LessThanEq,
+            Self::MatchesText => unreachable!("text queries are always compared to variables"),
+            Self::EditDistanceAtMost(max_distance) => Self::EditDistanceAtMost(*max_distance),
         }
     }
 }
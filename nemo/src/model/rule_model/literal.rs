@@ -29,6 +29,14 @@ impl Literal {
             Self::Negative(atom) => atom,
         }
     }
+
+    /// Returns the underlying atom - mutable.
+    pub(crate) fn atom_mut(&mut self) -> &mut Atom {
+        match self {
+            Self::Positive(atom) => atom,
+            Self::Negative(atom) => atom,
+        }
+    }
 }
 
 impl Neg for Literal {
@@ -3,9 +3,38 @@ use std::{
     path::Path,
 };
 
-use crate::model::PrimitiveType;
+use thiserror::Error;
 
-use super::{Atom, DataSourceDeclaration, Identifier, QualifiedPredicateName, Rule};
+use crate::{
+    io::parser::{iri::is_absolute, span_from_str},
+    model::PrimitiveType,
+};
+
+use super::{Atom, DataSourceDeclaration, Identifier, QualifiedPredicateName, Rule, Semiring};
+
+/// Errors that can occur when composing programs.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum CompositionError {
+    /// The programs declare different base IRIs.
+    #[error("The programs use the different base IRIs \"{0}\" and \"{1}\".")]
+    ConflictingBase(String, String),
+    /// The programs declare the same prefix for different IRIs.
+    #[error("The prefix \"{prefix}\" is declared both as \"{first}\" and as \"{second}\".")]
+    ConflictingPrefix {
+        /// The prefix
+        prefix: String,
+        /// The first IRI
+        first: String,
+        /// The second IRI
+        second: String,
+    },
+    /// A predicate is declared with different types.
+    #[error("The predicate \"{0}\" is declared with different types.")]
+    ConflictingDeclaration(Identifier),
+    /// The programs declare different semirings, or only one of them declares a semiring.
+    #[error("The programs use different semirings for their annotations.")]
+    ConflictingSemiring,
+}
 
 /// A (ground) fact.
 #[derive(Debug, Eq, PartialEq, Clone)]
@@ -40,6 +69,25 @@ impl From<Vec<QualifiedPredicateName>> for OutputPredicateSelection {
     }
 }
 
+/// Policy for relating predicate names that are given as bare names, IRIs and prefixed names,
+/// see [`Program::normalize_predicate_names`].
+///
+/// The parser resolves prefixed names like `ex:p` to absolute IRIs,
+/// but keeps bare names like `p` and relative IRIs like `<p>` as they are,
+/// so that the latter two denote the same predicate `p`, which differs from `ex:p`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum PredicateNames {
+    /// Keep predicate names as they are parsed.
+    #[default]
+    Keep,
+    /// Resolve bare names and relative IRIs against the base IRI of the program, if it declares one.
+    ///
+    /// Then `p`, `<p>` and `ex:p` denote the same predicate if the base is the IRI of the prefix `ex:`.
+    /// Since the predicates of other programs and of data files are only known by name,
+    /// such programs should use the same base.
+    Absolutize,
+}
+
 /// A directive that can occur in the program.
 #[derive(Debug)]
 pub enum Directive {
@@ -59,6 +107,10 @@ pub struct Program {
     facts: Vec<Fact>,
     parsed_predicate_declarations: HashMap<Identifier, Vec<PrimitiveType>>,
     output_predicates: OutputPredicateSelection,
+    predicate_docs: HashMap<Identifier, String>,
+    semiring: Option<Semiring>,
+    temporal_predicates: HashSet<Identifier>,
+    predicate_limits: HashMap<Identifier, usize>,
 }
 
 impl From<Vec<Rule>> for Program {
@@ -99,9 +151,62 @@ impl Program {
             facts,
             parsed_predicate_declarations,
             output_predicates,
+            predicate_docs: HashMap::new(),
+            semiring: None,
+            temporal_predicates: HashSet::new(),
+            predicate_limits: HashMap::new(),
         }
     }
 
+    /// Attach documentation to predicates.
+    pub(crate) fn with_predicate_docs(mut self, docs: HashMap<Identifier, String>) -> Self {
+        self.predicate_docs = docs;
+        self
+    }
+
+    /// Declare the semiring that combines the annotations of the facts of this program.
+    pub(crate) fn with_semiring(mut self, semiring: Option<Semiring>) -> Self {
+        self.semiring = semiring;
+        self
+    }
+
+    /// Return the semiring declared by the `@semiring` directive, if any.
+    ///
+    /// The facts and rules of such a program contain the annotations in the last column of each atom.
+    #[must_use]
+    pub fn semiring(&self) -> Option<Semiring> {
+        self.semiring
+    }
+
+    /// Mark the given predicates as temporal, see [`Program::temporal_predicates`].
+    pub(crate) fn with_temporal_predicates(mut self, predicates: HashSet<Identifier>) -> Self {
+        self.temporal_predicates = predicates;
+        self
+    }
+
+    /// Return the predicates whose facts are valid in intervals of time.
+    ///
+    /// The facts and rules of such a predicate contain the start and end of the validity interval
+    /// in the last two columns (see [`ValidityInterval`](super::ValidityInterval)).
+    #[must_use]
+    pub fn temporal_predicates(&self) -> &HashSet<Identifier> {
+        &self.temporal_predicates
+    }
+
+    /// Limit the number of facts of the given predicates, see [`Program::predicate_limits`].
+    pub(crate) fn with_predicate_limits(mut self, limits: HashMap<Identifier, usize>) -> Self {
+        self.predicate_limits = limits;
+        self
+    }
+
+    /// Return the maximal number of facts of each predicate with a `@limit` directive.
+    ///
+    /// Reasoning is aborted as soon as such a predicate has more facts.
+    #[must_use]
+    pub fn predicate_limits(&self) -> &HashMap<Identifier, usize> {
+        &self.predicate_limits
+    }
+
     /// Get the base IRI, if set.
     #[must_use]
     pub fn base(&self) -> Option<String> {
@@ -126,16 +231,30 @@ impl Program {
         &self.facts
     }
 
+    /// Return all facts in the program - mutable.
+    #[must_use]
+    pub fn facts_mut(&mut self) -> &mut Vec<Fact> {
+        &mut self.facts
+    }
+
     /// Return a HashSet of all predicates in the program (in rules and facts).
     #[must_use]
     pub fn predicates(&self) -> HashSet<Identifier> {
         self.rules()
             .iter()
+            .flat_map(|rule| rule.disjuncts())
             .flat_map(|rule| {
                 rule.head()
                     .iter()
                     .map(|atom| atom.predicate())
                     .chain(rule.body().iter().map(|literal| literal.predicate()))
+                    .chain(
+                        rule.negated_conjunctions()
+                            .iter()
+                            .flatten()
+                            .map(|atom| atom.predicate()),
+                    )
+                    .collect::<Vec<_>>()
             })
             .chain(self.facts().iter().map(|atom| atom.0.predicate()))
             .collect()
@@ -199,6 +318,13 @@ impl Program {
         self.parsed_predicate_declarations.clone()
     }
 
+    /// Return the documentation of the given predicate,
+    /// given by the `%%` comments preceding its type declaration or data source.
+    #[must_use]
+    pub fn predicate_doc(&self, predicate: &Identifier) -> Option<&str> {
+        self.predicate_docs.get(predicate).map(String::as_str)
+    }
+
     /// Force the given selection of output predicates.
     pub fn force_output_predicate_selection(
         &mut self,
@@ -206,4 +332,432 @@ impl Program {
     ) {
         self.output_predicates = output_predicates;
     }
+
+    /// Return the selected output predicates, where [`OutputPredicateSelection::AllIDBPredicates`] is made explicit.
+    pub(crate) fn selected_output_predicates(&self) -> Vec<QualifiedPredicateName> {
+        match &self.output_predicates {
+            OutputPredicateSelection::AllIDBPredicates => self
+                .output_predicates()
+                .map(QualifiedPredicateName::new)
+                .collect(),
+            OutputPredicateSelection::SelectedPredicates(predicates) => predicates.clone(),
+        }
+    }
+
+    /// Add the predicate declarations of the given program,
+    /// which must not declare other types for the same predicates.
+    fn add_predicate_declarations(
+        &mut self,
+        declarations: HashMap<Identifier, Vec<PrimitiveType>>,
+    ) -> Result<(), CompositionError> {
+        for (predicate, types) in declarations {
+            match self.parsed_predicate_declarations.get(&predicate) {
+                Some(existing) if *existing != types => {
+                    return Err(CompositionError::ConflictingDeclaration(predicate))
+                }
+                _ => {
+                    self.parsed_predicate_declarations.insert(predicate, types);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Combine this program with another one,
+    /// such that the result contains the rules, facts, sources and declarations of both programs.
+    ///
+    /// The prefixes of both programs are combined, and the output predicates of the result
+    /// are the output predicates of both programs.
+    /// Fails if the programs declare different base IRIs,
+    /// the same prefix for different IRIs, different types for the same predicate,
+    /// or different semirings.
+    pub fn merge(mut self, other: Program) -> Result<Program, CompositionError> {
+        if self.semiring != other.semiring {
+            return Err(CompositionError::ConflictingSemiring);
+        }
+
+        self.base = match (self.base.take(), other.base.clone()) {
+            (Some(first), Some(second)) if first != second => {
+                return Err(CompositionError::ConflictingBase(first, second))
+            }
+            (first, second) => first.or(second),
+        };
+
+        for (prefix, iri) in other.prefixes.clone() {
+            match self.prefixes.get(&prefix) {
+                Some(existing) if *existing != iri => {
+                    return Err(CompositionError::ConflictingPrefix {
+                        prefix,
+                        first: existing.clone(),
+                        second: iri,
+                    })
+                }
+                _ => {
+                    self.prefixes.insert(prefix, iri);
+                }
+            }
+        }
+
+        self.add_predicate_declarations(other.parsed_predicate_declarations.clone())?;
+
+        self.output_predicates = match (&self.output_predicates, &other.output_predicates) {
+            (
+                OutputPredicateSelection::AllIDBPredicates,
+                OutputPredicateSelection::AllIDBPredicates,
+            ) => OutputPredicateSelection::AllIDBPredicates,
+            _ => {
+                let mut selected = self.selected_output_predicates();
+                for predicate in other.selected_output_predicates() {
+                    if !selected.contains(&predicate) {
+                        selected.push(predicate);
+                    }
+                }
+                OutputPredicateSelection::SelectedPredicates(selected)
+            }
+        };
+
+        for source in other.sources {
+            if !self.sources.contains(&source) {
+                self.sources.push(source);
+            }
+        }
+        self.rules.extend(other.rules);
+        self.facts.extend(other.facts);
+        self.temporal_predicates.extend(other.temporal_predicates);
+        for (predicate, limit) in other.predicate_limits {
+            self.predicate_limits
+                .entry(predicate)
+                .and_modify(|existing| *existing = (*existing).min(limit))
+                .or_insert(limit);
+        }
+        for (predicate, doc) in other.predicate_docs {
+            self.predicate_docs.entry(predicate).or_insert(doc);
+        }
+
+        Ok(self)
+    }
+
+    /// Rename the predicates of the program according to the given map,
+    /// keeping all predicates that do not occur in it.
+    ///
+    /// Fails if predicates with different declared types are renamed to the same predicate.
+    pub fn rename_predicates(
+        mut self,
+        renaming: &HashMap<Identifier, Identifier>,
+    ) -> Result<Program, CompositionError> {
+        let rename = |predicate: &Identifier| {
+            renaming
+                .get(predicate)
+                .cloned()
+                .unwrap_or_else(|| predicate.clone())
+        };
+        let rename_atom = |atom: &mut Atom| atom.set_predicate(rename(&atom.predicate()));
+
+        for rule in &mut self.rules {
+            rule.atoms_mut().into_iter().for_each(rename_atom);
+        }
+        for Fact(atom) in &mut self.facts {
+            rename_atom(atom);
+        }
+        for source in &mut self.sources {
+            source.predicate = rename(&source.predicate);
+        }
+        if let OutputPredicateSelection::SelectedPredicates(predicates) =
+            &mut self.output_predicates
+        {
+            for predicate in predicates.iter_mut() {
+                predicate.identifier = rename(&predicate.identifier);
+            }
+        }
+
+        let declarations = std::mem::take(&mut self.parsed_predicate_declarations)
+            .into_iter()
+            .map(|(predicate, types)| (rename(&predicate), types))
+            .collect::<Vec<_>>();
+        for (predicate, types) in declarations {
+            self.add_predicate_declarations(HashMap::from([(predicate, types)]))?;
+        }
+
+        self.predicate_docs = std::mem::take(&mut self.predicate_docs)
+            .into_iter()
+            .map(|(predicate, doc)| (rename(&predicate), doc))
+            .collect();
+        self.temporal_predicates = self.temporal_predicates.iter().map(rename).collect();
+        self.predicate_limits = std::mem::take(&mut self.predicate_limits)
+            .into_iter()
+            .map(|(predicate, limit)| (rename(&predicate), limit))
+            .collect();
+
+        Ok(self)
+    }
+
+    /// Normalize the names of all predicates of the program according to the given [`PredicateNames`] policy.
+    ///
+    /// Constants are not affected, since they need to match the values of input files.
+    /// Fails if predicates with different declared types get the same name.
+    pub fn normalize_predicate_names(
+        self,
+        policy: PredicateNames,
+    ) -> Result<Program, CompositionError> {
+        let (PredicateNames::Absolutize, Some(base)) = (policy, self.base.clone()) else {
+            return Ok(self);
+        };
+
+        let mut predicates = self.predicates();
+        predicates.extend(self.sources.iter().map(|source| source.predicate.clone()));
+        predicates.extend(self.parsed_predicate_declarations.keys().cloned());
+        predicates.extend(self.temporal_predicates.iter().cloned());
+        predicates.extend(self.predicate_limits.keys().cloned());
+        predicates.extend(
+            self.selected_output_predicates()
+                .into_iter()
+                .map(|predicate| predicate.identifier),
+        );
+
+        let renaming = predicates
+            .into_iter()
+            .filter(|predicate| !is_absolute(span_from_str(predicate.name().as_str())))
+            .map(|predicate| {
+                let absolute = Identifier(format!("{base}{}", predicate.name()));
+                (predicate, absolute)
+            })
+            .collect();
+
+        self.rename_predicates(&renaming)
+    }
+
+    /// Restrict the program to the given output predicates,
+    /// keeping only the rules, facts, sources and declarations that are needed to derive them.
+    pub fn restrict_to(mut self, outputs: &[Identifier]) -> Program {
+        let mut relevant = outputs.iter().cloned().collect::<HashSet<_>>();
+        let mut kept = vec![false; self.rules.len()];
+
+        let mut changed = true;
+        while changed {
+            changed = false;
+
+            for (rule, kept) in self.rules.iter().zip(kept.iter_mut()) {
+                if *kept
+                    || !rule
+                        .head()
+                        .iter()
+                        .any(|atom| relevant.contains(&atom.predicate()))
+                {
+                    continue;
+                }
+
+                *kept = true;
+                changed = true;
+
+                for disjunct in rule.disjuncts() {
+                    relevant.extend(disjunct.body().iter().map(|literal| literal.predicate()));
+                    relevant.extend(
+                        disjunct
+                            .negated_conjunctions()
+                            .iter()
+                            .flatten()
+                            .map(|atom| atom.predicate()),
+                    );
+                }
+            }
+        }
+
+        let mut kept = kept.into_iter();
+        self.rules.retain(|_| kept.next().unwrap_or_default());
+        self.facts
+            .retain(|Fact(atom)| relevant.contains(&atom.predicate()));
+        self.sources
+            .retain(|source| relevant.contains(&source.predicate));
+        self.parsed_predicate_declarations
+            .retain(|predicate, _| relevant.contains(predicate));
+        self.predicate_limits
+            .retain(|predicate, _| relevant.contains(predicate));
+        self.output_predicates = OutputPredicateSelection::SelectedPredicates(
+            outputs
+                .iter()
+                .cloned()
+                .map(QualifiedPredicateName::new)
+                .collect(),
+        );
+
+        self
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use crate::{io::parser::parse_program, model::Identifier};
+
+    use super::{CompositionError, PredicateNames};
+
+    fn identifiers(names: &[&str]) -> Vec<Identifier> {
+        let mut identifiers = names
+            .iter()
+            .map(|name| Identifier(name.to_string()))
+            .collect::<Vec<_>>();
+        identifiers.sort();
+        identifiers
+    }
+
+    fn sorted(predicates: impl IntoIterator<Item = Identifier>) -> Vec<Identifier> {
+        let mut predicates = predicates.into_iter().collect::<Vec<_>>();
+        predicates.sort();
+        predicates
+    }
+
+    #[test]
+    fn merge_programs() {
+        let first = parse_program(
+            "@prefix ex: <http://example.org/> .\n\
+             edge(a, b) .\n\
+             path(?X, ?Y) :- edge(?X, ?Y) .\n\
+             @output path .",
+        )
+        .unwrap();
+        let second = parse_program(
+            "@prefix ex: <http://example.org/> .\n\
+             @prefix foaf: <http://xmlns.com/foaf/0.1/> .\n\
+             path(?X, ?Z) :- path(?X, ?Y), edge(?Y, ?Z) .\n\
+             loop(?X) :- path(?X, ?X) .\n\
+             @output loop .",
+        )
+        .unwrap();
+
+        let merged = first.clone().merge(second).unwrap();
+        assert_eq!(merged.rules().len(), 3);
+        assert_eq!(merged.facts().len(), 1);
+        assert_eq!(merged.prefixes().len(), 2);
+        assert_eq!(
+            sorted(merged.output_predicates()),
+            identifiers(&["loop", "path"])
+        );
+
+        let conflicting = parse_program("@prefix ex: <http://example.com/> .").unwrap();
+        assert!(matches!(
+            first.clone().merge(conflicting),
+            Err(CompositionError::ConflictingPrefix { .. })
+        ));
+
+        let conflicting = parse_program("@declare edge(integer, integer) .").unwrap();
+        let declared = parse_program("@declare edge(string, string) .").unwrap();
+        assert_eq!(
+            declared.merge(conflicting).unwrap_err(),
+            CompositionError::ConflictingDeclaration(Identifier("edge".to_string()))
+        );
+    }
+
+    #[test]
+    fn rename_predicates() {
+        let program = parse_program(
+            "edge(a, b) .\n\
+             path(?X, ?Y) :- edge(?X, ?Y), ~blocked(?X) .\n\
+             @output path .",
+        )
+        .unwrap();
+
+        let renamed = program
+            .rename_predicates(&HashMap::from([
+                (
+                    Identifier("edge".to_string()),
+                    Identifier("link".to_string()),
+                ),
+                (
+                    Identifier("path".to_string()),
+                    Identifier("reach".to_string()),
+                ),
+            ]))
+            .unwrap();
+
+        assert_eq!(
+            sorted(renamed.predicates()),
+            identifiers(&["blocked", "link", "reach"])
+        );
+        assert_eq!(sorted(renamed.output_predicates()), identifiers(&["reach"]));
+        assert_eq!(
+            renamed.facts()[0].0.predicate(),
+            Identifier("link".to_string())
+        );
+    }
+
+    #[test]
+    fn normalize_predicate_names() {
+        let program = parse_program(
+            "@base <http://example.org/> .\n\
+             @prefix ex: <http://example.org/> .\n\
+             edge(a, b) .\n\
+             <path>(?X, ?Y) :- ex:edge(?X, ?Y) .\n\
+             <http://example.com/loop>(?X) :- path(?X, ?X) .\n\
+             @output path .",
+        )
+        .unwrap();
+
+        let kept = program
+            .clone()
+            .normalize_predicate_names(PredicateNames::Keep)
+            .unwrap();
+        assert_eq!(
+            sorted(kept.predicates()),
+            identifiers(&[
+                "edge",
+                "http://example.com/loop",
+                "http://example.org/edge",
+                "path"
+            ])
+        );
+
+        let absolute = program
+            .normalize_predicate_names(PredicateNames::Absolutize)
+            .unwrap();
+        assert_eq!(
+            sorted(absolute.predicates()),
+            identifiers(&[
+                "http://example.com/loop",
+                "http://example.org/edge",
+                "http://example.org/path"
+            ])
+        );
+        assert_eq!(
+            sorted(absolute.output_predicates()),
+            identifiers(&["http://example.org/path"])
+        );
+
+        let without_base = parse_program("p(a) .").unwrap();
+        assert_eq!(
+            sorted(
+                without_base
+                    .normalize_predicate_names(PredicateNames::Absolutize)
+                    .unwrap()
+                    .predicates()
+            ),
+            identifiers(&["p"])
+        );
+    }
+
+    #[test]
+    fn restrict_to_outputs() {
+        let program = parse_program(
+            "edge(a, b) .\n\
+             color(a, red) .\n\
+             path(?X, ?Y) :- edge(?X, ?Y) .\n\
+             path(?X, ?Z) :- path(?X, ?Y), edge(?Y, ?Z) .\n\
+             red(?X) :- color(?X, red) .\n\
+             redPath(?X, ?Y) :- path(?X, ?Y), red(?X) .",
+        )
+        .unwrap();
+
+        let restricted = program.clone().restrict_to(&identifiers(&["path"]));
+        assert_eq!(restricted.rules().len(), 2);
+        assert_eq!(restricted.facts().len(), 1);
+        assert_eq!(
+            sorted(restricted.output_predicates()),
+            identifiers(&["path"])
+        );
+
+        let restricted = program.restrict_to(&identifiers(&["redPath"]));
+        assert_eq!(restricted.rules().len(), 4);
+        assert_eq!(restricted.facts().len(), 2);
+    }
 }
@@ -0,0 +1,374 @@
+//! A builder for constructing [`Program`]s directly in Rust.
+
+use std::collections::HashMap;
+
+use thiserror::Error;
+
+use crate::{io::parser::ParseError, model::PrimitiveType};
+
+use super::{
+    ArityError, Atom, DataSourceDeclaration, Fact, Filter, FilterOperation, Identifier, Literal,
+    NumericLiteral, OutputPredicateSelection, Program, QualifiedPredicateName, Rule, Term,
+    TermTree, Variable,
+};
+
+/// Errors that can occur when building a program with a [`ProgramBuilder`].
+#[derive(Error, Debug)]
+pub enum ProgramBuildError {
+    /// A fact contains a term that is not ground.
+    #[error("The fact for predicate \"{0}\" contains the non-ground term \"{1}\".")]
+    NonGroundFact(Identifier, Term),
+    /// A predicate is used with different arities.
+    #[error("The predicate \"{predicate}\" is used with arity {first} and with arity {second}.")]
+    ArityMismatch {
+        /// The predicate
+        predicate: Identifier,
+        /// The arity of the first occurrence
+        first: usize,
+        /// The arity of a later occurrence
+        second: usize,
+    },
+    /// A rule violates a constraint on the usage of variables.
+    #[error("Rule {0} is invalid: {1}")]
+    InvalidRule(usize, #[source] ParseError),
+}
+
+impl Variable {
+    /// Construct a universally quantified variable with the given name.
+    pub fn universal(name: impl Into<Identifier>) -> Self {
+        Self::Universal(name.into())
+    }
+
+    /// Construct an existentially quantified variable with the given name.
+    pub fn existential(name: impl Into<Identifier>) -> Self {
+        Self::Existential(name.into())
+    }
+}
+
+impl Term {
+    /// Construct an (abstract) constant with the given name.
+    pub fn constant(name: impl Into<Identifier>) -> Self {
+        Self::Constant(name.into())
+    }
+
+    /// Construct a string literal.
+    pub fn string(value: impl Into<String>) -> Self {
+        Self::StringLiteral(value.into())
+    }
+}
+
+/// String slices are interpreted as (abstract) constants.
+impl From<&str> for Term {
+    fn from(value: &str) -> Self {
+        Self::constant(value)
+    }
+}
+
+impl From<i64> for Term {
+    fn from(value: i64) -> Self {
+        Self::NumericLiteral(NumericLiteral::Integer(value))
+    }
+}
+
+impl From<Variable> for Term {
+    fn from(value: Variable) -> Self {
+        Self::Variable(value)
+    }
+}
+
+impl<T: Into<Term>> From<T> for TermTree {
+    fn from(value: T) -> Self {
+        TermTree::leaf(value.into())
+    }
+}
+
+fn atom<T: Into<TermTree>>(
+    predicate: impl Into<Identifier>,
+    terms: impl IntoIterator<Item = T>,
+) -> Atom {
+    Atom::new(
+        predicate.into(),
+        terms.into_iter().map(Into::into).collect(),
+    )
+}
+
+/// Builder for a [`Program`].
+///
+/// Facts are checked to be ground, predicates are checked to be used with a single arity,
+/// and rules are validated like rules that are parsed from a file
+/// when the program is built with [`ProgramBuilder::build`].
+#[derive(Debug, Default)]
+pub struct ProgramBuilder {
+    base: Option<String>,
+    prefixes: HashMap<String, String>,
+    sources: Vec<DataSourceDeclaration>,
+    rules: Vec<RuleParts>,
+    facts: Vec<Fact>,
+    predicate_declarations: HashMap<Identifier, Vec<PrimitiveType>>,
+    output_predicates: Vec<QualifiedPredicateName>,
+}
+
+/// The components of a rule that is validated once the program is built.
+#[derive(Debug, Default)]
+struct RuleParts {
+    head: Vec<Atom>,
+    body: Vec<Literal>,
+    filters: Vec<Filter>,
+}
+
+impl ProgramBuilder {
+    /// Construct a builder for an empty program.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the base IRI.
+    pub fn base(mut self, base: impl Into<String>) -> Self {
+        self.base = Some(base.into());
+        self
+    }
+
+    /// Declare a prefix.
+    pub fn prefix(mut self, prefix: impl Into<String>, iri: impl Into<String>) -> Self {
+        self.prefixes.insert(prefix.into(), iri.into());
+        self
+    }
+
+    /// Add a data source declaration.
+    pub fn source(mut self, source: DataSourceDeclaration) -> Self {
+        self.sources.push(source);
+        self
+    }
+
+    /// Declare the types of a predicate.
+    pub fn declare(mut self, predicate: impl Into<Identifier>, types: Vec<PrimitiveType>) -> Self {
+        self.predicate_declarations.insert(predicate.into(), types);
+        self
+    }
+
+    /// Add a fact.
+    pub fn fact<T: Into<TermTree>>(
+        mut self,
+        predicate: impl Into<Identifier>,
+        terms: impl IntoIterator<Item = T>,
+    ) -> Self {
+        self.facts.push(Fact(atom(predicate, terms)));
+        self
+    }
+
+    /// Start building a rule, which is added to the program by [`RuleBuilder::done`].
+    pub fn rule(self) -> RuleBuilder {
+        RuleBuilder {
+            program: self,
+            rule: RuleParts::default(),
+        }
+    }
+
+    /// Mark a predicate as output predicate.
+    ///
+    /// If no output predicates are given, all IDB predicates are output.
+    pub fn output(mut self, predicate: impl Into<Identifier>) -> Self {
+        self.output_predicates
+            .push(QualifiedPredicateName::new(predicate.into()));
+        self
+    }
+
+    /// Validate the components and construct the [`Program`].
+    pub fn build(self) -> Result<Program, ProgramBuildError> {
+        for Fact(atom) in &self.facts {
+            if let Some(term) = atom.terms().find(|term| !term.is_ground()) {
+                return Err(ProgramBuildError::NonGroundFact(
+                    atom.predicate(),
+                    term.clone(),
+                ));
+            }
+        }
+
+        let rules = self
+            .rules
+            .into_iter()
+            .enumerate()
+            .map(|(index, rule)| {
+                Rule::new_validated(rule.head, rule.body, rule.filters, Vec::new(), Vec::new())
+                    .map_err(|error| ProgramBuildError::InvalidRule(index, error))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let program = Program::new(
+            self.base,
+            self.prefixes,
+            self.sources,
+            rules,
+            self.facts,
+            self.predicate_declarations,
+            OutputPredicateSelection::from(self.output_predicates),
+        );
+
+        if let Err(ArityError(conflicts)) = program.check_arities() {
+            let conflict = &conflicts[0];
+            let arities = conflict.arities();
+
+            return Err(ProgramBuildError::ArityMismatch {
+                predicate: conflict.predicate.clone(),
+                first: arities[0],
+                second: arities[1],
+            });
+        }
+
+        Ok(program)
+    }
+}
+
+/// Builder for a rule of a [`ProgramBuilder`].
+#[derive(Debug)]
+pub struct RuleBuilder {
+    program: ProgramBuilder,
+    rule: RuleParts,
+}
+
+impl RuleBuilder {
+    /// Add an atom to the head of the rule.
+    pub fn head<T: Into<TermTree>>(
+        mut self,
+        predicate: impl Into<Identifier>,
+        terms: impl IntoIterator<Item = T>,
+    ) -> Self {
+        self.rule.head.push(atom(predicate, terms));
+        self
+    }
+
+    /// Add a positive literal to the body of the rule.
+    pub fn body<T: Into<TermTree>>(
+        mut self,
+        predicate: impl Into<Identifier>,
+        terms: impl IntoIterator<Item = T>,
+    ) -> Self {
+        self.rule
+            .body
+            .push(Literal::Positive(atom(predicate, terms)));
+        self
+    }
+
+    /// Add a negative literal to the body of the rule.
+    pub fn negated<T: Into<TermTree>>(
+        mut self,
+        predicate: impl Into<Identifier>,
+        terms: impl IntoIterator<Item = T>,
+    ) -> Self {
+        self.rule
+            .body
+            .push(Literal::Negative(atom(predicate, terms)));
+        self
+    }
+
+    /// Add a filter to the body of the rule.
+    pub fn filter(
+        mut self,
+        operation: FilterOperation,
+        lhs: Variable,
+        rhs: impl Into<Term>,
+    ) -> Self {
+        self.rule
+            .filters
+            .push(Filter::new(operation, lhs, rhs.into()));
+        self
+    }
+
+    /// Add the rule to the program.
+    pub fn done(mut self) -> ProgramBuilder {
+        self.program.rules.push(self.rule);
+        self.program
+    }
+}
+
+impl Program {
+    /// Return a [`ProgramBuilder`] for constructing a program.
+    pub fn builder() -> ProgramBuilder {
+        ProgramBuilder::new()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        io::parser::{parse_program, ParseError},
+        model::{FilterOperation, Term, Variable},
+    };
+
+    use super::{Program, ProgramBuildError};
+
+    #[test]
+    fn build_program() {
+        let x = || Variable::universal("X");
+        let y = || Variable::universal("Y");
+        let z = || Variable::universal("Z");
+
+        let program = Program::builder()
+            .fact("edge", ["a", "b"])
+            .fact("edge", [Term::from("b"), Term::from(3)])
+            .rule()
+            .head("path", [x(), y()])
+            .body("edge", [x(), y()])
+            .done()
+            .rule()
+            .head("path", [x(), z()])
+            .body("path", [x(), y()])
+            .body("edge", [y(), z()])
+            .filter(FilterOperation::Unequals, x(), z())
+            .done()
+            .output("path")
+            .build()
+            .unwrap();
+
+        let expected = parse_program(
+            "edge(a, b) .\n\
+             edge(b, 3) .\n\
+             path(?X, ?Y) :- edge(?X, ?Y) .\n\
+             path(?X, ?Z) :- path(?X, ?Y), edge(?Y, ?Z), ?X != ?Z .\n\
+             @output path .\n",
+        )
+        .unwrap();
+
+        assert_eq!(program.facts(), expected.facts());
+        assert_eq!(program.rules(), expected.rules());
+        assert_eq!(
+            program.output_predicates().collect::<Vec<_>>(),
+            expected.output_predicates().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn build_invalid_program() {
+        let error = Program::builder()
+            .fact("p", [Variable::universal("X")])
+            .build()
+            .unwrap_err();
+        assert!(matches!(error, ProgramBuildError::NonGroundFact(..)));
+
+        let error = Program::builder()
+            .fact("p", ["a"])
+            .fact("p", ["a", "b"])
+            .build()
+            .unwrap_err();
+        assert!(matches!(
+            error,
+            ProgramBuildError::ArityMismatch {
+                first: 1,
+                second: 2,
+                ..
+            }
+        ));
+
+        let error = Program::builder()
+            .rule()
+            .head("q", [Variable::universal("Y")])
+            .body("p", [Variable::universal("X")])
+            .done()
+            .build()
+            .unwrap_err();
+        assert!(matches!(
+            error,
+            ProgramBuildError::InvalidRule(0, ParseError::UnsafeHeadVariable { .. })
+        ));
+    }
+}
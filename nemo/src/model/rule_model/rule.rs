@@ -2,7 +2,7 @@ use std::collections::HashSet;
 
 use crate::io::parser::ParseError;
 
-use super::{Atom, Filter, Literal, Term, Variable};
+use super::{Atom, BuiltinFunction, Filter, Literal, Term, TermOperation, TermTree, Variable};
 
 /// A rule.
 #[derive(Debug, Eq, PartialEq, Clone)]
@@ -13,6 +13,16 @@ pub struct Rule {
     body: Vec<Literal>,
     /// Filters applied to the body
     filters: Vec<Filter>,
+    /// Negated conjunctions of atoms in the body
+    negated_conjunctions: Vec<Vec<Atom>>,
+    /// Variables used in filters that are bound to the value of a function term
+    computed_variables: Vec<(Variable, TermTree)>,
+    /// Further disjuncts of the body, each represented by a rule with the same head
+    alternatives: Vec<Rule>,
+    /// Documentation given by `%%` comments preceding the rule
+    doc: Option<String>,
+    /// Priority of the rule, where rules of higher priority are saturated first
+    priority: i64,
 }
 
 impl Rule {
@@ -22,6 +32,11 @@ impl Rule {
             head,
             body,
             filters,
+            negated_conjunctions: Vec::new(),
+            computed_variables: Vec::new(),
+            alternatives: Vec::new(),
+            doc: None,
+            priority: 0,
         }
     }
 
@@ -30,11 +45,19 @@ impl Rule {
         head: Vec<Atom>,
         body: Vec<Literal>,
         filters: Vec<Filter>,
+        negated_conjunctions: Vec<Vec<Atom>>,
+        computed_variables: Vec<(Variable, TermTree)>,
     ) -> Result<Self, ParseError> {
         // Check if existential variables occur in the body.
         let existential_variables = body
             .iter()
             .flat_map(|literal| literal.existential_variables())
+            .chain(
+                negated_conjunctions
+                    .iter()
+                    .flatten()
+                    .flat_map(|atom| atom.existential_variables()),
+            )
             .collect::<Vec<_>>();
 
         if !existential_variables.is_empty() {
@@ -77,6 +100,34 @@ impl Rule {
             unsafe_negative_variables.extend(current_unsafe)
         }
 
+        // Variables that only occur in a negated conjunction are local to it,
+        // so each negated conjunction needs to share a variable with the positive part of the body.
+        for conjunction in &negated_conjunctions {
+            let mut current_unsafe = HashSet::<Variable>::new();
+            let mut is_connected = false;
+
+            for variable in conjunction.iter().flat_map(|atom| atom.variables()) {
+                if positive_varibales.contains(variable) {
+                    is_connected = true;
+                    continue;
+                }
+
+                if unsafe_negative_variables.contains(variable) {
+                    return Err(ParseError::UnsafeVariableInMulltipleNegativeLiterals(
+                        variable.name(),
+                    ));
+                }
+
+                current_unsafe.insert(variable.clone());
+            }
+
+            if !is_connected {
+                return Err(ParseError::UnconnectedNegatedConjunction);
+            }
+
+            unsafe_negative_variables.extend(current_unsafe)
+        }
+
         // Check if a variable occurs with both existential and universal quantification.
         let universal_variables = body
             .iter()
@@ -99,15 +150,89 @@ impl Rule {
             ));
         }
 
+        // Check if anonymous variables are only used in body literals
+        let is_misplaced =
+            |term: &Term| matches!(term, Term::Variable(variable) if variable.is_anonymous());
+        if head.iter().flat_map(|atom| atom.terms()).any(is_misplaced)
+            || computed_variables
+                .iter()
+                .flat_map(|(_, term_tree)| term_tree.terms())
+                .any(is_misplaced)
+            || filters
+                .iter()
+                .any(|filter| filter.lhs.is_anonymous() || is_misplaced(&filter.rhs))
+        {
+            return Err(ParseError::MisplacedAnonymousVariable);
+        }
+
         // Check if there are universal variables in the head which do not occur in a positive body literal
-        let head_universal_variables = head
+        let mut unsafe_head_variables = Vec::<&Variable>::new();
+        for head_variable in head.iter().flat_map(|atom| atom.universal_variables()) {
+            if !positive_varibales.contains(head_variable)
+                && !unsafe_head_variables.contains(&head_variable)
+            {
+                unsafe_head_variables.push(head_variable);
+            }
+        }
+
+        if !unsafe_head_variables.is_empty() {
+            return Err(ParseError::UnsafeHeadVariable {
+                variables: unsafe_head_variables
+                    .iter()
+                    .map(|variable| format!("?{}", variable.name()))
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                suggestion: unsafe_head_variables
+                    .iter()
+                    .map(|variable| format!("domain(?{})", variable.name()))
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            });
+        }
+
+        // Check if built-in functions are used correctly
+        // (other functions are defined by users of the library and are checked before execution)
+        for term_tree in head
             .iter()
-            .flat_map(|atom| atom.universal_variables())
-            .collect::<HashSet<_>>();
+            .flat_map(|atom| atom.term_trees())
+            .chain(computed_variables.iter().map(|(_, term_tree)| term_tree))
+        {
+            for (identifier, arguments) in term_tree.functions() {
+                let Some(function) = BuiltinFunction::from_identifier(identifier) else {
+                    continue;
+                };
 
-        for head_variable in &head_universal_variables {
-            if !positive_varibales.contains(head_variable) {
-                return Err(ParseError::UnsafeHeadVariable(head_variable.name()));
+                if function.arity() != arguments.len() {
+                    return Err(ParseError::InvalidFunctionArity(
+                        identifier.name(),
+                        function.arity(),
+                        arguments.len(),
+                    ));
+                }
+
+                if function.is_conversion()
+                    && !matches!(arguments[0].tag, TermOperation::Term(Term::Variable(_)))
+                {
+                    return Err(ParseError::InvalidConversionArgument(identifier.name()));
+                }
+            }
+        }
+
+        // Check if function terms in filters only use variables from positive body literals
+        for term in computed_variables
+            .iter()
+            .flat_map(|(_, term_tree)| term_tree.terms())
+        {
+            match term {
+                Term::Variable(variable @ Variable::Universal(universal_variable)) => {
+                    if !positive_varibales.contains(variable) {
+                        return Err(ParseError::UnsafeFilterVariable(universal_variable.name()));
+                    }
+                }
+                Term::Variable(Variable::Existential(existential_variable)) => {
+                    return Err(ParseError::BodyExistential(existential_variable.name()))
+                }
+                _ => {}
             }
         }
 
@@ -122,7 +247,11 @@ impl Rule {
             for variable in filter_variables {
                 match variable {
                     Variable::Universal(universal_variable) => {
-                        if !positive_varibales.contains(variable) {
+                        let is_computed = computed_variables
+                            .iter()
+                            .any(|(computed_variable, _)| computed_variable == variable);
+
+                        if !positive_varibales.contains(variable) && !is_computed {
                             return Err(ParseError::UnsafeFilterVariable(
                                 universal_variable.name(),
                             ));
@@ -139,9 +268,51 @@ impl Rule {
             head,
             body,
             filters,
+            negated_conjunctions,
+            computed_variables,
+            alternatives: Vec::new(),
+            doc: None,
+            priority: 0,
         })
     }
 
+    /// Turn this rule into a rule with a disjunctive body,
+    /// where the given rules (with the same head) provide the further disjuncts.
+    pub(crate) fn with_alternatives(mut self, alternatives: Vec<Rule>) -> Self {
+        debug_assert!(alternatives
+            .iter()
+            .all(|alternative| alternative.head == self.head));
+
+        self.alternatives = alternatives;
+        self
+    }
+
+    /// Attach the given documentation to this rule.
+    pub(crate) fn with_doc(mut self, doc: Option<String>) -> Self {
+        self.doc = doc;
+        self
+    }
+
+    /// Return the documentation of the rule, given by the `%%` comments preceding it.
+    #[must_use]
+    pub fn doc(&self) -> Option<&str> {
+        self.doc.as_deref()
+    }
+
+    /// Assign the given priority to this rule.
+    pub(crate) fn with_priority(mut self, priority: i64) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    /// Return the priority of the rule, given by a preceding `@priority` annotation.
+    ///
+    /// Rules without such an annotation have priority `0`.
+    #[must_use]
+    pub fn priority(&self) -> i64 {
+        self.priority
+    }
+
     /// Return the head atoms of the rule - immutable.
     #[must_use]
     pub fn head(&self) -> &Vec<Atom> {
@@ -177,4 +348,53 @@ impl Rule {
     pub fn filters_mut(&mut self) -> &mut Vec<Filter> {
         &mut self.filters
     }
+
+    /// Return the negated conjunctions of the rule - immutable.
+    #[must_use]
+    pub fn negated_conjunctions(&self) -> &Vec<Vec<Atom>> {
+        &self.negated_conjunctions
+    }
+
+    /// Return the variables used in filters that are bound to the value of a function term - immutable.
+    #[must_use]
+    pub fn computed_variables(&self) -> &Vec<(Variable, TermTree)> {
+        &self.computed_variables
+    }
+
+    /// Return the variables used in filters that are bound to the value of a function term - mutable.
+    #[must_use]
+    pub fn computed_variables_mut(&mut self) -> &mut Vec<(Variable, TermTree)> {
+        &mut self.computed_variables
+    }
+
+    /// Return the further disjuncts of the body of the rule,
+    /// each represented by a rule with the same head.
+    #[must_use]
+    pub fn alternatives(&self) -> &Vec<Rule> {
+        &self.alternatives
+    }
+
+    /// Return the further disjuncts of the body of the rule - mutable.
+    pub(crate) fn alternatives_mut(&mut self) -> &mut Vec<Rule> {
+        &mut self.alternatives
+    }
+
+    /// Return all atoms of the rule (including those of its further disjuncts) - mutable.
+    pub(crate) fn atoms_mut(&mut self) -> Vec<&mut Atom> {
+        self.head
+            .iter_mut()
+            .chain(self.body.iter_mut().map(Literal::atom_mut))
+            .chain(self.negated_conjunctions.iter_mut().flatten())
+            .chain(self.alternatives.iter_mut().flat_map(Rule::atoms_mut))
+            .collect()
+    }
+
+    /// Split a rule with a disjunctive body into one rule for each disjunct.
+    #[must_use]
+    pub fn disjuncts(&self) -> Vec<Rule> {
+        let mut first = self.clone();
+        let alternatives = std::mem::take(&mut first.alternatives);
+
+        std::iter::once(first).chain(alternatives).collect()
+    }
 }
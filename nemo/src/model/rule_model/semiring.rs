@@ -0,0 +1,261 @@
+use std::str::FromStr;
+
+use crate::io::parser::ParseError;
+
+use super::{
+    Aggregate, Atom, Fact, Filter, FilterOperation, Identifier, Literal, NumericLiteral, Rule,
+    Term, TermOperation, TermTree, Variable,
+};
+
+/// Semiring according to which the annotations of facts are combined,
+/// as declared by a `@semiring` directive.
+///
+/// In a program with a semiring, every fact carries a numeric annotation in an additional last column.
+/// The annotation of a derived fact combines the annotations of the facts it is derived from,
+/// and if a fact has several derivations, the best annotation is kept.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Semiring {
+    /// Annotations are costs, which are added up along a derivation,
+    /// where the derivation of minimal cost is kept (`min-plus`).
+    MinPlus,
+    /// Annotations are probabilities or confidences, which are multiplied along a derivation,
+    /// where the derivation of maximal probability is kept (`max-times`).
+    ///
+    /// Negative annotations are ignored.
+    MaxTimes,
+}
+
+impl Semiring {
+    /// Return the annotation of facts and data sources for which no annotation is given,
+    /// which does not change the annotation it is combined with.
+    pub fn one(&self) -> NumericLiteral {
+        match self {
+            Self::MinPlus => NumericLiteral::Integer(0),
+            Self::MaxTimes => NumericLiteral::Integer(1),
+        }
+    }
+
+    /// Return the operation that combines the annotations along a derivation.
+    fn combination(&self) -> TermOperation {
+        match self {
+            Self::MinPlus => TermOperation::Addition,
+            Self::MaxTimes => TermOperation::Multiplication,
+        }
+    }
+
+    /// Return the name of the aggregate that selects the best annotation among several derivations.
+    pub(crate) fn aggregate(&self) -> Identifier {
+        match self {
+            Self::MinPlus => Identifier("min".to_string()),
+            Self::MaxTimes => Identifier("max".to_string()),
+        }
+    }
+
+    /// Return the variable that binds the annotation of the body literal with the given index.
+    fn annotation_variable(index: usize) -> Variable {
+        Variable::Universal(Identifier(format!("ANNOTATION_{index}")))
+    }
+
+    /// Add the given annotation to a fact, or the neutral annotation if none is given.
+    pub(crate) fn annotate_fact(
+        &self,
+        Fact(mut atom): Fact,
+        annotation: Option<Term>,
+    ) -> Result<Fact, ParseError> {
+        let annotation = match annotation {
+            Some(annotation @ Term::NumericLiteral(_)) => annotation,
+            Some(_) => return Err(ParseError::InvalidFactAnnotation),
+            None => Term::NumericLiteral(self.one()),
+        };
+
+        atom.terms_trees_mut().push(TermTree::leaf(annotation));
+        Ok(Fact(atom))
+    }
+
+    /// Add annotations to each disjunct of a rule.
+    ///
+    /// Each body atom binds its annotation by a fresh variable,
+    /// and each head atom is annotated with the combination of these annotations
+    /// and the given annotation of the rule.
+    /// For [`Semiring::MaxTimes`], the body annotations are required to be non-negative.
+    pub(crate) fn annotate_rule(
+        &self,
+        rule: &Rule,
+        annotation: Option<Term>,
+    ) -> Result<Rule, ParseError> {
+        let mut disjuncts = rule
+            .disjuncts()
+            .into_iter()
+            .map(|disjunct| self.annotate_disjunct(disjunct, annotation.clone()))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let first = disjuncts.remove(0);
+        Ok(first.with_alternatives(disjuncts))
+    }
+
+    fn annotate_disjunct(
+        &self,
+        mut rule: Rule,
+        annotation: Option<Term>,
+    ) -> Result<Rule, ParseError> {
+        if !rule.negated_conjunctions().is_empty()
+            || rule.body().iter().any(|literal| literal.is_negative())
+        {
+            return Err(ParseError::AnnotatedNegation);
+        }
+
+        if rule
+            .head()
+            .iter()
+            .any(|atom| atom.aggregates().next().is_some())
+        {
+            return Err(ParseError::AnnotatedAggregate);
+        }
+
+        let mut nonnegative_variables = Vec::new();
+        let mut factors = Vec::new();
+
+        // Variables are converted, since they might be bound to integers
+        match annotation {
+            Some(Term::Variable(variable))
+                if !rule.body().iter().any(|literal| {
+                    literal
+                        .variables()
+                        .any(|body_variable| *body_variable == variable)
+                }) =>
+            {
+                return Err(ParseError::UnsafeAnnotationVariable(variable.name()));
+            }
+            Some(Term::Variable(variable)) => {
+                nonnegative_variables.push(variable.clone());
+                factors.push(TermTree::tree(
+                    TermOperation::Function(Identifier("DOUBLE".to_string())),
+                    vec![TermTree::leaf(Term::Variable(variable))],
+                ));
+            }
+            Some(term) => factors.push(TermTree::leaf(term)),
+            None => {}
+        }
+
+        for (index, literal) in rule.body_mut().iter_mut().enumerate() {
+            let variable = Self::annotation_variable(index);
+
+            literal
+                .atom_mut()
+                .terms_trees_mut()
+                .push(TermTree::leaf(Term::Variable(variable.clone())));
+            nonnegative_variables.push(variable.clone());
+            factors.push(TermTree::leaf(Term::Variable(variable)));
+        }
+
+        if *self == Self::MaxTimes {
+            rule.filters_mut()
+                .extend(nonnegative_variables.into_iter().map(|variable| {
+                    Filter::new(
+                        FilterOperation::GreaterThanEq,
+                        variable,
+                        Term::NumericLiteral(NumericLiteral::Integer(0)),
+                    )
+                }));
+        }
+
+        let combined = factors
+            .into_iter()
+            .reduce(|left, right| TermTree::tree(self.combination(), vec![left, right]))
+            .unwrap_or_else(|| TermTree::leaf(Term::NumericLiteral(self.one())));
+
+        for atom in rule.head_mut() {
+            atom.terms_trees_mut().push(combined.clone());
+        }
+
+        Ok(rule)
+    }
+
+    /// Return the predicate that holds the unannotated tuples of a data source for the given predicate.
+    pub(crate) fn source_predicate(predicate: &Identifier) -> Identifier {
+        Identifier(format!("ANNOTATION_SOURCE_FOR_PREDICATE_{predicate}"))
+    }
+
+    /// Return the rule that derives the tuples of a data source for the given predicate
+    /// with the neutral annotation.
+    pub(crate) fn source_rule(&self, predicate: &Identifier, arity: usize) -> Rule {
+        let terms = (0..arity)
+            .map(|index| {
+                TermTree::leaf(Term::Variable(Variable::Universal(Identifier(format!(
+                    "SOURCE_{index}"
+                )))))
+            })
+            .collect::<Vec<_>>();
+
+        let mut head_terms = terms.clone();
+        head_terms.push(TermTree::leaf(Term::NumericLiteral(self.one())));
+
+        Rule::new(
+            vec![Atom::new(predicate.clone(), head_terms)],
+            vec![Literal::Positive(Atom::new(
+                Self::source_predicate(predicate),
+                terms,
+            ))],
+            Vec::new(),
+        )
+    }
+
+    /// Return the rule that selects the best annotation for each tuple of the given predicate
+    /// among the annotations of the candidate predicate.
+    pub(crate) fn selection_rule(
+        &self,
+        predicate: &Identifier,
+        candidate_predicate: &Identifier,
+        arity: usize,
+    ) -> Rule {
+        let annotation = Identifier("ANNOTATION".to_string());
+        let terms = (0..arity - 1)
+            .map(|index| {
+                TermTree::leaf(Term::Variable(Variable::Universal(Identifier(format!(
+                    "ANNOTATED_{index}"
+                )))))
+            })
+            .collect::<Vec<_>>();
+
+        let mut head_terms = terms.clone();
+        head_terms.push(TermTree::leaf(Term::Aggregate(Aggregate {
+            aggregate_identifier: self.aggregate(),
+            variable_identifiers: vec![annotation.clone()],
+        })));
+
+        let mut body_terms = terms;
+        body_terms.push(TermTree::leaf(Term::Variable(Variable::Universal(
+            annotation,
+        ))));
+
+        Rule::new(
+            vec![Atom::new(predicate.clone(), head_terms)],
+            vec![Literal::Positive(Atom::new(
+                candidate_predicate.clone(),
+                body_terms,
+            ))],
+            Vec::new(),
+        )
+    }
+}
+
+impl FromStr for Semiring {
+    type Err = ParseError;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name {
+            "min-plus" => Ok(Self::MinPlus),
+            "max-times" => Ok(Self::MaxTimes),
+            _ => Err(ParseError::UnknownSemiring(name.to_string())),
+        }
+    }
+}
+
+impl std::fmt::Display for Semiring {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MinPlus => write!(f, "min-plus"),
+            Self::MaxTimes => write!(f, "max-times"),
+        }
+    }
+}
@@ -0,0 +1,207 @@
+use std::collections::HashSet;
+
+use crate::{io::parser::ParseError, model::types::primitive_logical_value::LogicalDateTime};
+
+use super::{
+    Fact, Filter, FilterOperation, Identifier, Literal, Rule, Term, TermOperation, TermTree,
+    Variable,
+};
+
+/// Interval of time in which a fact is valid, written as `@[start, end)` after the fact.
+///
+/// The interval contains its start, but not its end.
+/// Facts of temporal predicates hold the start and end of their validity interval
+/// in two additional last columns of type `datetime`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ValidityInterval {
+    start: LogicalDateTime,
+    end: LogicalDateTime,
+}
+
+impl ValidityInterval {
+    /// Create a new [`ValidityInterval`].
+    ///
+    /// Fails if the interval does not contain any point in time.
+    pub fn new(start: LogicalDateTime, end: LogicalDateTime) -> Result<Self, ParseError> {
+        if start < end {
+            Ok(Self { start, end })
+        } else {
+            Err(ParseError::EmptyValidityInterval(
+                start.to_string(),
+                end.to_string(),
+            ))
+        }
+    }
+
+    /// Return the first point in time in which the fact is valid.
+    pub fn start(&self) -> LogicalDateTime {
+        self.start
+    }
+
+    /// Return the first point in time after the interval in which the fact is valid.
+    pub fn end(&self) -> LogicalDateTime {
+        self.end
+    }
+
+    /// Add this interval to the given fact.
+    pub(crate) fn annotate_fact(&self, Fact(mut atom): Fact) -> Fact {
+        atom.terms_trees_mut()
+            .extend([self.start, self.end].map(|bound| TermTree::leaf(bound.into())));
+        Fact(atom)
+    }
+
+    /// Return the temporal predicates of a program,
+    /// i.e., the given predicates and all predicates derived by rules with temporal predicates in their body.
+    pub(crate) fn temporal_predicates(
+        mut predicates: HashSet<Identifier>,
+        rules: &[Rule],
+    ) -> HashSet<Identifier> {
+        let disjuncts = rules.iter().flat_map(Rule::disjuncts).collect::<Vec<_>>();
+
+        loop {
+            let previous_count = predicates.len();
+
+            for rule in &disjuncts {
+                if rule
+                    .body()
+                    .iter()
+                    .any(|literal| predicates.contains(&literal.predicate()))
+                {
+                    predicates.extend(rule.head().iter().map(|atom| atom.predicate()));
+                }
+            }
+
+            if predicates.len() == previous_count {
+                return predicates;
+            }
+        }
+    }
+
+    /// Return the variables that bind the start and end of the validity interval
+    /// of the body literal with the given index.
+    fn interval_variables(index: usize) -> (Variable, Variable) {
+        (
+            Variable::Universal(Identifier(format!("VALID_FROM_{index}"))),
+            Variable::Universal(Identifier(format!("VALID_UNTIL_{index}"))),
+        )
+    }
+
+    /// Combine the given bounds of validity intervals by the given binary function.
+    fn combine_bounds(function: &str, bounds: Vec<Variable>) -> TermTree {
+        bounds
+            .into_iter()
+            .map(|bound| TermTree::leaf(Term::Variable(bound)))
+            .reduce(|left, right| {
+                TermTree::tree(
+                    TermOperation::Function(Identifier(function.to_string())),
+                    vec![left, right],
+                )
+            })
+            .expect("only called for rules with temporal body atoms")
+    }
+
+    /// Add validity intervals to each disjunct of a rule, given the temporal predicates of the program.
+    ///
+    /// The facts derived by a rule are valid in the intersection of the validity intervals
+    /// of the temporal atoms of its body, which therefore need to overlap.
+    pub(crate) fn annotate_rule(
+        rule: &Rule,
+        temporal_predicates: &HashSet<Identifier>,
+    ) -> Result<Rule, ParseError> {
+        let mut disjuncts = rule
+            .disjuncts()
+            .into_iter()
+            .map(|disjunct| Self::annotate_disjunct(disjunct, temporal_predicates))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let first = disjuncts.remove(0);
+        Ok(first.with_alternatives(disjuncts))
+    }
+
+    fn annotate_disjunct(
+        mut rule: Rule,
+        temporal_predicates: &HashSet<Identifier>,
+    ) -> Result<Rule, ParseError> {
+        let negated_predicate = rule
+            .body()
+            .iter()
+            .filter(|literal| literal.is_negative())
+            .map(Literal::predicate)
+            .chain(
+                rule.negated_conjunctions()
+                    .iter()
+                    .flatten()
+                    .map(|atom| atom.predicate()),
+            )
+            .find(|predicate| temporal_predicates.contains(predicate));
+        if let Some(predicate) = negated_predicate {
+            return Err(ParseError::TemporalNegation(predicate.name()));
+        }
+
+        let mut starts = Vec::new();
+        let mut ends = Vec::new();
+
+        for (index, literal) in rule.body_mut().iter_mut().enumerate() {
+            if !temporal_predicates.contains(&literal.predicate()) {
+                continue;
+            }
+
+            let (start, end) = Self::interval_variables(index);
+            literal.atom_mut().terms_trees_mut().extend([
+                TermTree::leaf(Term::Variable(start.clone())),
+                TermTree::leaf(Term::Variable(end.clone())),
+            ]);
+            starts.push(start);
+            ends.push(end);
+        }
+
+        if starts.is_empty() {
+            return match rule
+                .head()
+                .iter()
+                .find(|atom| temporal_predicates.contains(&atom.predicate()))
+            {
+                Some(atom) => Err(ParseError::UntimedTemporalDerivation(
+                    atom.predicate().name(),
+                )),
+                None => Ok(rule),
+            };
+        }
+
+        if rule
+            .head()
+            .iter()
+            .any(|atom| atom.aggregates().next().is_some())
+        {
+            return Err(ParseError::TemporalAggregate);
+        }
+
+        // The validity intervals overlap if each of them starts before all others end
+        for (start_index, start) in starts.iter().enumerate() {
+            for (end_index, end) in ends.iter().enumerate() {
+                if start_index != end_index {
+                    rule.filters_mut().push(Filter::new(
+                        FilterOperation::LessThan,
+                        start.clone(),
+                        Term::Variable(end.clone()),
+                    ));
+                }
+            }
+        }
+
+        let start = Self::combine_bounds("MAX", starts);
+        let end = Self::combine_bounds("MIN", ends);
+
+        for atom in rule.head_mut() {
+            atom.terms_trees_mut().extend([start.clone(), end.clone()]);
+        }
+
+        Ok(rule)
+    }
+}
+
+impl std::fmt::Display for ValidityInterval {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "@[{}, {})", self.start, self.end)
+    }
+}
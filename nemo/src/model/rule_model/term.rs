@@ -20,6 +20,12 @@ pub const XSD_DOUBLE: &str = "http://www.w3.org/2001/XMLSchema#double";
 pub const XSD_DECIMAL: &str = "http://www.w3.org/2001/XMLSchema#decimal";
 /// XSD type for integer
 pub const XSD_INTEGER: &str = "http://www.w3.org/2001/XMLSchema#integer";
+/// XSD type for date and time
+pub const XSD_DATE_TIME: &str = "http://www.w3.org/2001/XMLSchema#dateTime";
+/// XSD type for date
+pub const XSD_DATE: &str = "http://www.w3.org/2001/XMLSchema#date";
+/// XSD type for boolean
+pub const XSD_BOOLEAN: &str = "http://www.w3.org/2001/XMLSchema#boolean";
 
 /// An identifier for, e.g., a Term or a Predicate.
 #[derive(Debug, Eq, PartialEq, Hash, Clone, PartialOrd, Ord)]
@@ -31,6 +37,11 @@ impl Identifier {
         self.0.clone()
     }
 
+    /// Returns a reference to the associated name
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
     /// Returns a sanitised path with respect to the associated name
     pub fn sanitised_file_name(&self, mut path: PathBuf) -> PathBuf {
         let sanitise_options = Options::<Option<char>> {
@@ -55,6 +66,12 @@ impl From<String> for Identifier {
     }
 }
 
+impl From<&str> for Identifier {
+    fn from(value: &str) -> Self {
+        Identifier(value.to_string())
+    }
+}
+
 /// A qualified predicate name, i.e., a predicate name together with a type constraint.
 #[derive(Debug, Eq, PartialEq, Hash, Clone)]
 pub struct QualifiedPredicateName {
@@ -98,6 +115,9 @@ pub enum Variable {
     Existential(Identifier),
 }
 
+/// Prefix of the names of anonymous variables, which cannot occur in names given in a program.
+const ANONYMOUS_VARIABLE_PREFIX: &str = "_";
+
 impl Variable {
     /// Return the name of the variable.
     pub fn name(&self) -> String {
@@ -105,6 +125,19 @@ impl Variable {
             Self::Universal(identifier) | Self::Existential(identifier) => identifier.name(),
         }
     }
+
+    /// Construct the anonymous variable with the given index.
+    ///
+    /// Every occurrence of `_` in a rule is replaced by a different anonymous variable,
+    /// so that it matches anything and is never joined with other terms.
+    pub fn anonymous(index: usize) -> Self {
+        Self::Universal(Identifier(format!("{ANONYMOUS_VARIABLE_PREFIX}{index}")))
+    }
+
+    /// Return whether this is an anonymous variable, see [`Variable::anonymous`].
+    pub fn is_anonymous(&self) -> bool {
+        matches!(self, Self::Universal(identifier) if identifier.0.starts_with(ANONYMOUS_VARIABLE_PREFIX))
+    }
 }
 
 impl std::fmt::Display for Variable {
@@ -1,5 +1,7 @@
 use nemo_physical::util::TaggedTree;
 
+use crate::model::PrimitiveType;
+
 use super::{Identifier, Term};
 
 /// Supported operations between terms.
@@ -19,6 +21,92 @@ pub enum TermOperation {
     Function(Identifier),
 }
 
+/// Functions that are built into the language and can be used in function terms.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum BuiltinFunction {
+    /// Deterministic hash of a value (`HASH(?x)`)
+    Hash,
+    /// Random number, which differs for each computed value (`RAND()`)
+    Random,
+    /// Pseudo-random number, which is determined by the given seed (`RAND_SEEDED(?s)`)
+    SeededRandom,
+    /// Conversion of a number into an integer, rounding towards zero (`INT(?x)`)
+    Integer,
+    /// Conversion of a number into a floating point value (`DOUBLE(?x)`)
+    Double,
+    /// Rounding to the nearest integer, away from zero in halfway cases (`ROUND(?x)`)
+    Round,
+    /// Rounding up (`CEIL(?x)`)
+    Ceil,
+    /// Rounding down (`FLOOR(?x)`)
+    Floor,
+    /// Absolute value (`ABS(?x)`)
+    Absolute,
+    /// Non-negative remainder of a division (`MOD(?x, ?y)`)
+    Modulo,
+    /// Exponentiation (`POW(?x, ?y)`)
+    Power,
+    /// Natural logarithm (`LOG(?x)`)
+    Logarithm,
+    /// Square root (`SQRT(?x)`)
+    SquareRoot,
+    /// Larger of two values (`MAX(?x, ?y)`)
+    Maximum,
+    /// Smaller of two values (`MIN(?x, ?y)`)
+    Minimum,
+}
+
+impl BuiltinFunction {
+    /// Return the [`BuiltinFunction`] with the given name, if there is one.
+    pub fn from_identifier(identifier: &Identifier) -> Option<Self> {
+        match identifier.name().as_str() {
+            "HASH" => Some(Self::Hash),
+            "RAND" => Some(Self::Random),
+            "RAND_SEEDED" => Some(Self::SeededRandom),
+            "INT" => Some(Self::Integer),
+            "DOUBLE" => Some(Self::Double),
+            "ROUND" => Some(Self::Round),
+            "CEIL" => Some(Self::Ceil),
+            "FLOOR" => Some(Self::Floor),
+            "ABS" => Some(Self::Absolute),
+            "MOD" => Some(Self::Modulo),
+            "POW" => Some(Self::Power),
+            "LOG" => Some(Self::Logarithm),
+            "SQRT" => Some(Self::SquareRoot),
+            "MAX" => Some(Self::Maximum),
+            "MIN" => Some(Self::Minimum),
+            _ => None,
+        }
+    }
+
+    /// Return the number of arguments of the function.
+    pub fn arity(&self) -> usize {
+        match self {
+            Self::Random => 0,
+            Self::Modulo | Self::Power | Self::Maximum | Self::Minimum => 2,
+            _ => 1,
+        }
+    }
+
+    /// Return whether the function converts its argument into another type.
+    ///
+    /// The argument of such a function has to be a variable,
+    /// whose type is independent of the type of the result.
+    pub fn is_conversion(&self) -> bool {
+        matches!(self, Self::Integer | Self::Double)
+    }
+
+    /// Return the type of the result of the function,
+    /// if it does not depend on the types of its arguments.
+    pub fn result_type(&self) -> Option<PrimitiveType> {
+        match self {
+            Self::Random | Self::Double => Some(PrimitiveType::Float64),
+            Self::Integer => Some(PrimitiveType::Integer),
+            _ => None,
+        }
+    }
+}
+
 /// [`TaggedTree`] with [`TermOperation`] as tags.
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct TermTree(pub TaggedTree<TermOperation>);
@@ -53,13 +141,83 @@ impl TermTree {
         self.0
             .leaves()
             .into_iter()
-            .map(|l| {
-                if let TermOperation::Term(term) = l {
-                    term
-                } else {
-                    unreachable!("This is the only Leaf type");
-                }
+            .filter_map(|l| match l {
+                TermOperation::Term(term) => Some(term),
+                // Functions without arguments are the only other leaves
+                _ => None,
             })
             .collect()
     }
+
+    /// Return the name and the arguments of each function term in this tree.
+    pub fn functions(&self) -> Vec<(&Identifier, &Vec<TaggedTree<TermOperation>>)> {
+        fn functions_recursive<'a>(
+            tree: &'a TaggedTree<TermOperation>,
+            result: &mut Vec<(&'a Identifier, &'a Vec<TaggedTree<TermOperation>>)>,
+        ) {
+            if let TermOperation::Function(identifier) = &tree.tag {
+                result.push((identifier, &tree.subtrees));
+            }
+
+            for subtree in &tree.subtrees {
+                functions_recursive(subtree, result);
+            }
+        }
+
+        let mut result = Vec::new();
+        functions_recursive(&self.0, &mut result);
+        result
+    }
+
+    /// Return a list of all the [`Term`]s contained in this tree,
+    /// except for the arguments of conversion functions.
+    ///
+    /// The type of the result of the tree has to agree with the types of these terms.
+    pub fn terms_determining_type(&self) -> Vec<&Term> {
+        fn terms_recursive<'a>(tree: &'a TaggedTree<TermOperation>, result: &mut Vec<&'a Term>) {
+            match &tree.tag {
+                TermOperation::Term(term) => result.push(term),
+                TermOperation::Function(identifier)
+                    if BuiltinFunction::from_identifier(identifier)
+                        .is_some_and(|function| function.is_conversion()) => {}
+                _ => {
+                    for subtree in &tree.subtrees {
+                        terms_recursive(subtree, result);
+                    }
+                }
+            }
+        }
+
+        let mut result = Vec::new();
+        terms_recursive(&self.0, &mut result);
+        result
+    }
+
+    /// Return whether this tree contains operations that can only be applied to numbers,
+    /// i.e. arithmetic operations or built-in functions.
+    ///
+    /// Other functions are defined by users of the library and may be applied to values of any type.
+    pub fn requires_numeric_types(&self) -> bool {
+        fn requires_recursive(tree: &TaggedTree<TermOperation>) -> bool {
+            match &tree.tag {
+                TermOperation::Term(_) => false,
+                TermOperation::Function(identifier)
+                    if BuiltinFunction::from_identifier(identifier).is_none() =>
+                {
+                    tree.subtrees.iter().any(requires_recursive)
+                }
+                _ => true,
+            }
+        }
+
+        requires_recursive(&self.0)
+    }
+
+    /// Return the type of the result of this tree,
+    /// if it is determined by one of its functions (see [`BuiltinFunction::result_type`]).
+    pub fn fixed_type(&self) -> Option<PrimitiveType> {
+        self.functions()
+            .into_iter()
+            .find_map(|(identifier, _)| BuiltinFunction::from_identifier(identifier)?.result_type())
+    }
 }
@@ -0,0 +1,296 @@
+//! Traversal and rewriting of the components of a [`Program`].
+//!
+//! Passes over programs implement [`ProgramVisitor`] to inspect the parts they are interested in,
+//! or [`TermRewriter`] to replace terms wherever they occur,
+//! instead of iterating over the rules of a program by hand.
+
+use super::{
+    Atom, DataSourceDeclaration, Fact, Filter, Literal, Program, Rule, Term, TermOperation,
+    TermTree, Variable,
+};
+
+/// Visits the components of a [`Program`].
+///
+/// Every method is called for the corresponding component and visits its parts by default,
+/// using the respective `walk_*` function.
+/// Implementations override the methods for the components they are interested in
+/// and call the `walk_*` function if they want to continue the traversal below that component.
+pub trait ProgramVisitor {
+    /// Visit a program.
+    fn visit_program(&mut self, program: &Program) {
+        walk_program(self, program)
+    }
+
+    /// Visit a data source declaration.
+    fn visit_source(&mut self, _source: &DataSourceDeclaration) {}
+
+    /// Visit a fact.
+    fn visit_fact(&mut self, fact: &Fact) {
+        walk_fact(self, fact)
+    }
+
+    /// Visit a rule, including the further disjuncts of its body.
+    fn visit_rule(&mut self, rule: &Rule) {
+        walk_rule(self, rule)
+    }
+
+    /// Visit an atom, which may occur in a fact, in the head or in the body of a rule.
+    fn visit_atom(&mut self, atom: &Atom) {
+        walk_atom(self, atom)
+    }
+
+    /// Visit a literal of a rule body.
+    fn visit_literal(&mut self, literal: &Literal) {
+        walk_literal(self, literal)
+    }
+
+    /// Visit a filter of a rule body.
+    fn visit_filter(&mut self, filter: &Filter) {
+        walk_filter(self, filter)
+    }
+
+    /// Visit a term tree, e.g. an argument of an atom.
+    fn visit_term_tree(&mut self, term_tree: &TermTree) {
+        walk_term_tree(self, term_tree)
+    }
+
+    /// Visit a term.
+    fn visit_term(&mut self, _term: &Term) {}
+}
+
+/// Visit the sources, facts and rules of a program.
+pub fn walk_program<V: ProgramVisitor + ?Sized>(visitor: &mut V, program: &Program) {
+    for source in program.sources() {
+        visitor.visit_source(source);
+    }
+    for fact in program.facts() {
+        visitor.visit_fact(fact);
+    }
+    for rule in program.rules() {
+        visitor.visit_rule(rule);
+    }
+}
+
+/// Visit the atom of a fact.
+pub fn walk_fact<V: ProgramVisitor + ?Sized>(visitor: &mut V, fact: &Fact) {
+    visitor.visit_atom(&fact.0);
+}
+
+/// Visit the head atoms, body literals, negated conjunctions, filters and computed variables of a rule,
+/// followed by the further disjuncts of its body.
+pub fn walk_rule<V: ProgramVisitor + ?Sized>(visitor: &mut V, rule: &Rule) {
+    for atom in rule.head() {
+        visitor.visit_atom(atom);
+    }
+    for literal in rule.body() {
+        visitor.visit_literal(literal);
+    }
+    for atom in rule.negated_conjunctions().iter().flatten() {
+        visitor.visit_atom(atom);
+    }
+    for filter in rule.filters() {
+        visitor.visit_filter(filter);
+    }
+    for (variable, term_tree) in rule.computed_variables() {
+        visitor.visit_term(&Term::Variable(variable.clone()));
+        visitor.visit_term_tree(term_tree);
+    }
+    for alternative in rule.alternatives() {
+        walk_rule(visitor, alternative);
+    }
+}
+
+/// Visit the term trees of an atom.
+pub fn walk_atom<V: ProgramVisitor + ?Sized>(visitor: &mut V, atom: &Atom) {
+    for term_tree in atom.term_trees() {
+        visitor.visit_term_tree(term_tree);
+    }
+}
+
+/// Visit the atom of a literal.
+pub fn walk_literal<V: ProgramVisitor + ?Sized>(visitor: &mut V, literal: &Literal) {
+    visitor.visit_atom(literal.atom());
+}
+
+/// Visit both sides of a filter.
+pub fn walk_filter<V: ProgramVisitor + ?Sized>(visitor: &mut V, filter: &Filter) {
+    visitor.visit_term(&Term::Variable(filter.lhs.clone()));
+    visitor.visit_term(&filter.rhs);
+}
+
+/// Visit the terms at the leaves of a term tree.
+pub fn walk_term_tree<V: ProgramVisitor + ?Sized>(visitor: &mut V, term_tree: &TermTree) {
+    for term in term_tree.terms() {
+        visitor.visit_term(term);
+    }
+}
+
+/// Replaces terms wherever they occur in a program, rule or atom.
+///
+/// Terms are replaced in the arguments of atoms (including the leaves of function terms),
+/// on the right-hand side of filters, and in the definitions of computed variables.
+/// Since the left-hand sides of filters and computed variables have to be variables,
+/// they are only replaced if the replacement is a variable.
+pub trait TermRewriter {
+    /// Return the replacement for the given term, or `None` if it is kept.
+    fn rewrite_term(&mut self, term: &Term) -> Option<Term>;
+}
+
+impl<F> TermRewriter for F
+where
+    F: FnMut(&Term) -> Option<Term>,
+{
+    fn rewrite_term(&mut self, term: &Term) -> Option<Term> {
+        self(term)
+    }
+}
+
+impl TermTree {
+    /// Replace the terms at the leaves of this tree using the given [`TermRewriter`].
+    pub fn rewrite_terms<R: TermRewriter + ?Sized>(&mut self, rewriter: &mut R) {
+        fn rewrite<R: TermRewriter + ?Sized>(
+            tree: &mut nemo_physical::util::TaggedTree<TermOperation>,
+            rewriter: &mut R,
+        ) {
+            if let TermOperation::Term(term) = &mut tree.tag {
+                if let Some(replacement) = rewriter.rewrite_term(term) {
+                    *term = replacement;
+                }
+            }
+
+            for subtree in &mut tree.subtrees {
+                rewrite(subtree, rewriter);
+            }
+        }
+
+        rewrite(&mut self.0, rewriter)
+    }
+}
+
+impl Atom {
+    /// Replace the terms of this atom using the given [`TermRewriter`].
+    pub fn rewrite_terms<R: TermRewriter + ?Sized>(&mut self, rewriter: &mut R) {
+        for term_tree in self.terms_trees_mut() {
+            term_tree.rewrite_terms(rewriter);
+        }
+    }
+}
+
+/// Replace a variable that has to remain a variable.
+fn rewrite_variable<R: TermRewriter + ?Sized>(variable: &mut Variable, rewriter: &mut R) {
+    if let Some(Term::Variable(replacement)) =
+        rewriter.rewrite_term(&Term::Variable(variable.clone()))
+    {
+        *variable = replacement;
+    }
+}
+
+impl Rule {
+    /// Replace the terms of this rule (including the further disjuncts of its body)
+    /// using the given [`TermRewriter`].
+    pub fn rewrite_terms<R: TermRewriter + ?Sized>(&mut self, rewriter: &mut R) {
+        for atom in self.atoms_mut() {
+            atom.rewrite_terms(rewriter);
+        }
+
+        let mut rules = vec![self];
+        while let Some(rule) = rules.pop() {
+            for filter in rule.filters_mut() {
+                rewrite_variable(&mut filter.lhs, rewriter);
+                if let Some(replacement) = rewriter.rewrite_term(&filter.rhs) {
+                    filter.rhs = replacement;
+                }
+            }
+            for (variable, term_tree) in rule.computed_variables_mut() {
+                rewrite_variable(variable, rewriter);
+                term_tree.rewrite_terms(rewriter);
+            }
+
+            rules.extend(rule.alternatives_mut().iter_mut());
+        }
+    }
+}
+
+impl Program {
+    /// Visit this program with the given [`ProgramVisitor`].
+    pub fn accept<V: ProgramVisitor + ?Sized>(&self, visitor: &mut V) {
+        visitor.visit_program(self)
+    }
+
+    /// Replace the terms of all facts and rules of this program using the given [`TermRewriter`].
+    pub fn rewrite_terms<R: TermRewriter + ?Sized>(&mut self, rewriter: &mut R) {
+        for Fact(atom) in self.facts_mut() {
+            atom.rewrite_terms(rewriter);
+        }
+        for rule in self.rules_mut() {
+            rule.rewrite_terms(rewriter);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::BTreeSet;
+
+    use crate::{
+        io::parser::parse_program,
+        model::{Identifier, Term, Variable},
+    };
+
+    use super::ProgramVisitor;
+
+    #[derive(Default)]
+    struct Constants(BTreeSet<String>);
+
+    impl ProgramVisitor for Constants {
+        fn visit_term(&mut self, term: &Term) {
+            if let Term::Constant(Identifier(name)) = term {
+                self.0.insert(name.clone());
+            }
+        }
+    }
+
+    #[test]
+    fn visit_constants() {
+        let program = parse_program(
+            "edge(a, b) .\n\
+             path(?X, ?Y) :- edge(?X, ?Y), ~blocked(c) .\n\
+             next(?X, ?Y + 1) :- edge(?X, ?Y), ?X != d .\n",
+        )
+        .unwrap();
+
+        let mut constants = Constants::default();
+        program.accept(&mut constants);
+        assert_eq!(
+            constants.0.into_iter().collect::<Vec<_>>(),
+            vec!["a", "b", "c", "d"]
+        );
+    }
+
+    #[test]
+    fn rewrite_terms() {
+        let mut program = parse_program(
+            "edge(a, b) .\n\
+             path(?X, ?Y) :- edge(?X, ?Y), ?X != a .\n",
+        )
+        .unwrap();
+
+        program.rewrite_terms(&mut |term: &Term| match term {
+            Term::Constant(Identifier(name)) if name == "a" => {
+                Some(Term::Constant(Identifier("alpha".to_string())))
+            }
+            Term::Variable(Variable::Universal(Identifier(name))) if name == "X" => Some(
+                Term::Variable(Variable::Universal(Identifier("Source".to_string()))),
+            ),
+            _ => None,
+        });
+
+        let expected = parse_program(
+            "edge(alpha, b) .\n\
+             path(?Source, ?Y) :- edge(?Source, ?Y), ?Source != alpha .\n",
+        )
+        .unwrap();
+        assert_eq!(program.facts(), expected.facts());
+        assert_eq!(program.rules(), expected.rules());
+    }
+}
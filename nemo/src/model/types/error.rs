@@ -35,8 +35,14 @@ pub enum TypeError {
     #[error(transparent)]
     InvalidRuleTermConversion(#[from] InvalidRuleTermConversion),
     /// Comparison of a non-numeric type
-    #[error("Invalid type declarations. Comparison operator can only be used with numeric types.")]
+    #[error("Invalid type declarations. Comparison operator can only be used with numeric types and IP addresses.")]
     InvalidRuleNonNumericComparison,
+    /// Full-text search in values of a non-string type
+    #[error("Invalid type declarations. Full-text search can only be used with strings.")]
+    InvalidRuleNonStringTextSearch,
+    /// Edit distance of values of a non-string type
+    #[error("Invalid type declarations. Edit distances can only be computed for strings.")]
+    InvalidRuleNonStringEditDistance,
     /// Arithmetic operations with of a non-numeric type
     #[error(
         "Invalid type declarations. Arithmetic operations can only be used with numeric types."
@@ -1,4 +1,6 @@
+use std::net::{IpAddr, Ipv6Addr};
 use std::num::ParseIntError;
+use std::str::FromStr;
 
 use num::FromPrimitive;
 
@@ -6,21 +8,23 @@ use nemo_physical::datatypes::data_value::PhysicalString;
 use nemo_physical::datatypes::Double;
 use nemo_physical::error::ReadingError;
 use nemo_physical::{
-    datatypes::data_value::DataValueIteratorT, dictionary::value_serializer::NULL_PREFIX,
+    datatypes::data_value::DataValueIteratorT,
+    dictionary::{
+        domain_dictionary::{IRI_PREFIX as CONSTANT_PREFIX, LANGUAGE_STRING_PREFIX, STRING_PREFIX},
+        value_serializer::NULL_PREFIX,
+    },
 };
 
 use crate::model::{
-    Identifier, NumericLiteral, RdfLiteral, Term, XSD_DECIMAL, XSD_DOUBLE, XSD_INTEGER,
+    Identifier, NumericLiteral, RdfLiteral, Term, XSD_DATE, XSD_DATE_TIME, XSD_DECIMAL, XSD_DOUBLE,
+    XSD_INTEGER,
 };
 
 use super::{error::InvalidRuleTermConversion, primitive_types::PrimitiveType};
 
-const LANGUAGE_STRING_PREFIX: &str = "LANGUAGE_STRING:";
-const STRING_PREFIX: &str = "STRING:";
 const INTEGER_PREFIX: &str = "INTEGER:";
 const DECIMAL_PREFIX: &str = "DECIMAL:";
 const DOUBLE_PREFIX: &str = "DOUBLE:";
-const CONSTANT_PREFIX: &str = "CONSTANT:";
 const DATATYPE_VALUE_PREFIX: &str = "DATATYPE_VALUE:";
 
 /// The prefix used to indicate constants that are Nulls
@@ -101,6 +105,315 @@ impl std::fmt::Display for LogicalFloat64 {
     }
 }
 
+/// An Api wrapper fot the logical datetime type,
+/// which represents a point in time by the number of seconds since 1970-01-01T00:00:00Z
+#[repr(transparent)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct LogicalDateTime(i64);
+
+impl From<i64> for LogicalDateTime {
+    fn from(value: i64) -> Self {
+        LogicalDateTime(value)
+    }
+}
+
+impl From<LogicalDateTime> for i64 {
+    fn from(value: LogicalDateTime) -> Self {
+        value.0
+    }
+}
+
+const SECONDS_PER_DAY: i64 = 86400;
+
+/// Return the number of days between 1970-01-01 and the given date of the proleptic Gregorian calendar.
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year.rem_euclid(400);
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+
+    era * 146097 + day_of_era - 719468
+}
+
+/// Return the year, month and day of the date that is the given number of days after 1970-01-01.
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let days = days + 719468;
+    let era = days.div_euclid(146097);
+    let day_of_era = days.rem_euclid(146097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    };
+
+    (year_of_era + era * 400 + i64::from(month <= 2), month, day)
+}
+
+impl FromStr for LogicalDateTime {
+    type Err = ReadingError;
+
+    /// Parse a date (`2020-01-01`) or a date with time (`2020-01-01T12:30:00`),
+    /// optionally followed by a timezone (`Z` or an offset like `+02:00`),
+    /// where values without timezone are interpreted as UTC.
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let invalid = || {
+            ReadingError::TypeConversionError(
+                value.to_string(),
+                PrimitiveType::DateTime.to_string(),
+            )
+        };
+        let number = |digits: &str, min_length: usize, max_length: usize| {
+            if (min_length..=max_length).contains(&digits.len())
+                && digits.chars().all(|c| c.is_ascii_digit())
+            {
+                digits.parse::<i64>().ok()
+            } else {
+                None
+            }
+        };
+
+        if !value.is_ascii() {
+            return Err(invalid());
+        }
+
+        let bytes = value.as_bytes();
+        let (local, offset) = if let Some(local) = value.strip_suffix('Z') {
+            (local, 0)
+        } else if bytes.len() > 6
+            && matches!(bytes[bytes.len() - 6], b'+' | b'-')
+            && bytes[bytes.len() - 3] == b':'
+        {
+            let (local, offset) = value.split_at(value.len() - 6);
+            let hours = number(&offset[1..3], 2, 2).ok_or_else(invalid)?;
+            let minutes = number(&offset[4..], 2, 2).ok_or_else(invalid)?;
+            let seconds = (hours * 60 + minutes) * 60;
+
+            (
+                local,
+                if offset.starts_with('-') {
+                    -seconds
+                } else {
+                    seconds
+                },
+            )
+        } else {
+            (value, 0)
+        };
+
+        let (date, time) = local.split_once('T').unwrap_or((local, "00:00:00"));
+
+        let mut date_parts = date.split('-');
+        let (Some(year), Some(month), Some(day), None) = (
+            date_parts.next().and_then(|year| number(year, 4, 9)),
+            date_parts.next().and_then(|month| number(month, 2, 2)),
+            date_parts.next().and_then(|day| number(day, 2, 2)),
+            date_parts.next(),
+        ) else {
+            return Err(invalid());
+        };
+
+        let mut time_parts = time.split(':');
+        let (Some(hour), Some(minute), Some(second), None) = (
+            time_parts.next().and_then(|hour| number(hour, 2, 2)),
+            time_parts.next().and_then(|minute| number(minute, 2, 2)),
+            time_parts.next().and_then(|second| number(second, 2, 2)),
+            time_parts.next(),
+        ) else {
+            return Err(invalid());
+        };
+
+        let days = days_from_civil(year, month, day);
+        if !(1..=12).contains(&month)
+            || civil_from_days(days) != (year, month, day)
+            || hour >= 24
+            || minute >= 60
+            || second >= 60
+        {
+            return Err(invalid());
+        }
+
+        Ok(LogicalDateTime(
+            days * SECONDS_PER_DAY + hour * 3600 + minute * 60 + second - offset,
+        ))
+    }
+}
+
+impl std::fmt::Display for LogicalDateTime {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (year, month, day) = civil_from_days(self.0.div_euclid(SECONDS_PER_DAY));
+        let seconds = self.0.rem_euclid(SECONDS_PER_DAY);
+
+        write!(
+            f,
+            "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}Z",
+            seconds / 3600,
+            seconds / 60 % 60,
+            seconds % 60
+        )
+    }
+}
+
+/// Prefix of the IRIs by which UUIDs are represented as constants (RFC 4122)
+const UUID_URN_PREFIX: &str = "urn:uuid:";
+
+/// An Api wrapper fot the logical UUID type
+#[repr(transparent)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct LogicalUuid(u128);
+
+impl From<u128> for LogicalUuid {
+    fn from(value: u128) -> Self {
+        LogicalUuid(value)
+    }
+}
+
+impl From<LogicalUuid> for u128 {
+    fn from(value: LogicalUuid) -> Self {
+        value.0
+    }
+}
+
+impl FromStr for LogicalUuid {
+    type Err = ReadingError;
+
+    /// Parse a UUID in its hyphenated form (`123e4567-e89b-12d3-a456-426614174000`)
+    /// or as 32 hexadecimal digits without hyphens, optionally prefixed by `urn:uuid:`.
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let invalid = || {
+            ReadingError::TypeConversionError(value.to_string(), PrimitiveType::Uuid.to_string())
+        };
+
+        let uuid = value.strip_prefix(UUID_URN_PREFIX).unwrap_or(value);
+        let hyphenated = uuid.len() == 36
+            && uuid
+                .char_indices()
+                .filter(|&(_, c)| c == '-')
+                .map(|(position, _)| position)
+                .eq([8, 13, 18, 23]);
+        let digits = if hyphenated {
+            uuid.replace('-', "")
+        } else {
+            uuid.to_string()
+        };
+
+        if digits.len() != 32 || !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(invalid());
+        }
+
+        u128::from_str_radix(&digits, 16)
+            .map(LogicalUuid)
+            .map_err(|_err| invalid())
+    }
+}
+
+impl std::fmt::Display for LogicalUuid {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let digits = format!("{:032x}", self.0);
+
+        write!(
+            f,
+            "{}-{}-{}-{}-{}",
+            &digits[..8],
+            &digits[8..12],
+            &digits[12..16],
+            &digits[16..20],
+            &digits[20..]
+        )
+    }
+}
+
+/// An Api wrapper for the logical IP address type
+///
+/// IPv4 addresses are stored as IPv4-mapped IPv6 addresses (`::ffff:a.b.c.d`),
+/// so that addresses of both versions share one ordered value space.
+#[repr(transparent)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct LogicalIpAddress(u128);
+
+impl LogicalIpAddress {
+    /// Return the first and the last address of a network in CIDR notation,
+    /// e.g. `10.0.0.0/8` or `2001:db8::/32`.
+    pub fn cidr_range(cidr: &str) -> Result<(Self, Self), ReadingError> {
+        let invalid = || {
+            ReadingError::TypeConversionError(cidr.to_string(), "CIDR network".to_string())
+        };
+
+        let (address, prefix_length) = cidr.trim().split_once('/').ok_or_else(invalid)?;
+        let address = address.parse::<IpAddr>().map_err(|_err| invalid())?;
+        let prefix_length = prefix_length.parse::<u32>().map_err(|_err| invalid())?;
+
+        let prefix_length = match address {
+            IpAddr::V4(_) if prefix_length <= 32 => prefix_length + 96,
+            IpAddr::V6(_) if prefix_length <= 128 => prefix_length,
+            _ => return Err(invalid()),
+        };
+        let host_mask = u128::MAX.checked_shr(prefix_length).unwrap_or(0);
+
+        let first = Self::from(address).0 & !host_mask;
+        Ok((Self(first), Self(first | host_mask)))
+    }
+}
+
+impl From<u128> for LogicalIpAddress {
+    fn from(value: u128) -> Self {
+        LogicalIpAddress(value)
+    }
+}
+
+impl From<LogicalIpAddress> for u128 {
+    fn from(value: LogicalIpAddress) -> Self {
+        value.0
+    }
+}
+
+impl From<IpAddr> for LogicalIpAddress {
+    fn from(value: IpAddr) -> Self {
+        let address = match value {
+            IpAddr::V4(address) => address.to_ipv6_mapped(),
+            IpAddr::V6(address) => address,
+        };
+
+        LogicalIpAddress(address.into())
+    }
+}
+
+impl From<LogicalIpAddress> for IpAddr {
+    fn from(value: LogicalIpAddress) -> Self {
+        let address = Ipv6Addr::from(value.0);
+
+        match address.to_ipv4_mapped() {
+            Some(address) => IpAddr::V4(address),
+            None => IpAddr::V6(address),
+        }
+    }
+}
+
+impl FromStr for LogicalIpAddress {
+    type Err = ReadingError;
+
+    /// Parse an IPv4 address in dotted decimal notation or an IPv6 address.
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        value.parse::<IpAddr>().map(Self::from).map_err(|_err| {
+            ReadingError::TypeConversionError(
+                value.to_string(),
+                PrimitiveType::IpAddress.to_string(),
+            )
+        })
+    }
+}
+
+impl std::fmt::Display for LogicalIpAddress {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", IpAddr::from(*self))
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 struct LanguageString(String, String);
 
@@ -128,6 +441,27 @@ impl From<LogicalFloat64> for Term {
     }
 }
 
+impl From<LogicalDateTime> for Term {
+    fn from(value: LogicalDateTime) -> Self {
+        Self::RdfLiteral(RdfLiteral::DatatypeValue {
+            value: value.to_string(),
+            datatype: XSD_DATE_TIME.to_string(),
+        })
+    }
+}
+
+impl From<LogicalUuid> for Term {
+    fn from(value: LogicalUuid) -> Self {
+        Self::Constant(Identifier(format!("{UUID_URN_PREFIX}{value}")))
+    }
+}
+
+impl From<LogicalIpAddress> for Term {
+    fn from(value: LogicalIpAddress) -> Self {
+        Self::StringLiteral(value.to_string())
+    }
+}
+
 impl From<LanguageString> for PhysicalString {
     fn from(value: LanguageString) -> Self {
         format!("{LANGUAGE_STRING_PREFIX}{}@{}", value.0, value.1).into()
@@ -158,6 +492,24 @@ impl From<LogicalFloat64> for PhysicalString {
     }
 }
 
+impl From<LogicalDateTime> for PhysicalString {
+    fn from(value: LogicalDateTime) -> Self {
+        DatatypeValue(value.to_string(), XSD_DATE_TIME.to_string()).into()
+    }
+}
+
+impl From<LogicalUuid> for PhysicalString {
+    fn from(value: LogicalUuid) -> Self {
+        format!("{CONSTANT_PREFIX}{UUID_URN_PREFIX}{value}").into()
+    }
+}
+
+impl From<LogicalIpAddress> for PhysicalString {
+    fn from(value: LogicalIpAddress) -> Self {
+        format!("{STRING_PREFIX}{value}").into()
+    }
+}
+
 impl From<Identifier> for PhysicalString {
     fn from(value: Identifier) -> Self {
         format!("{CONSTANT_PREFIX}{value}").into()
@@ -182,6 +534,76 @@ impl From<LogicalFloat64> for LogicalString {
     }
 }
 
+impl From<LogicalDateTime> for LogicalString {
+    fn from(value: LogicalDateTime) -> Self {
+        value.to_string().into()
+    }
+}
+
+impl From<LogicalDateTime> for LogicalInteger {
+    fn from(value: LogicalDateTime) -> Self {
+        value.0.into()
+    }
+}
+
+impl From<LogicalInteger> for LogicalDateTime {
+    fn from(value: LogicalInteger) -> Self {
+        value.0.into()
+    }
+}
+
+impl TryFrom<LogicalString> for LogicalDateTime {
+    type Error = ReadingError;
+
+    fn try_from(value: LogicalString) -> Result<Self, Self::Error> {
+        value.0.parse()
+    }
+}
+
+impl TryFrom<LogicalFloat64> for LogicalDateTime {
+    type Error = ReadingError;
+
+    fn try_from(value: LogicalFloat64) -> Result<Self, Self::Error> {
+        LogicalInteger::try_from(value).map(|i| i.into())
+    }
+}
+
+impl TryFrom<LogicalDateTime> for LogicalFloat64 {
+    type Error = ReadingError;
+
+    fn try_from(value: LogicalDateTime) -> Result<Self, Self::Error> {
+        LogicalInteger::from(value).try_into()
+    }
+}
+
+impl From<LogicalUuid> for LogicalString {
+    fn from(value: LogicalUuid) -> Self {
+        value.to_string().into()
+    }
+}
+
+impl TryFrom<LogicalString> for LogicalUuid {
+    type Error = ReadingError;
+
+    fn try_from(value: LogicalString) -> Result<Self, Self::Error> {
+        value.0.parse()
+    }
+}
+
+impl From<LogicalIpAddress> for LogicalString {
+    fn from(value: LogicalIpAddress) -> Self {
+        value.to_string().into()
+    }
+}
+
+impl TryFrom<LogicalString> for LogicalIpAddress {
+    type Error = ReadingError;
+
+    fn try_from(value: LogicalString) -> Result<Self, Self::Error> {
+        value.0.parse()
+    }
+}
+
 impl TryFrom<LogicalString> for LogicalInteger {
     type Error = ParseIntError;
 
@@ -226,6 +648,125 @@ impl TryFrom<LogicalInteger> for LogicalFloat64 {
     }
 }
 
+impl TryFrom<LogicalUuid> for LogicalInteger {
+    type Error = ReadingError;
+
+    fn try_from(value: LogicalUuid) -> Result<Self, Self::Error> {
+        i64::try_from(value.0).map(|i| i.into()).map_err(|_err| {
+            ReadingError::TypeConversionError(value.to_string(), PrimitiveType::Integer.to_string())
+        })
+    }
+}
+
+impl TryFrom<LogicalInteger> for LogicalUuid {
+    type Error = ReadingError;
+
+    fn try_from(value: LogicalInteger) -> Result<Self, Self::Error> {
+        u128::try_from(value.0).map(|u| u.into()).map_err(|_err| {
+            ReadingError::TypeConversionError(value.to_string(), PrimitiveType::Uuid.to_string())
+        })
+    }
+}
+
+impl TryFrom<LogicalUuid> for LogicalFloat64 {
+    type Error = ReadingError;
+
+    fn try_from(value: LogicalUuid) -> Result<Self, Self::Error> {
+        LogicalInteger::try_from(value)?.try_into()
+    }
+}
+
+impl TryFrom<LogicalFloat64> for LogicalUuid {
+    type Error = ReadingError;
+
+    fn try_from(value: LogicalFloat64) -> Result<Self, Self::Error> {
+        LogicalInteger::try_from(value)?.try_into()
+    }
+}
+
+impl TryFrom<LogicalUuid> for LogicalDateTime {
+    type Error = ReadingError;
+
+    fn try_from(value: LogicalUuid) -> Result<Self, Self::Error> {
+        LogicalInteger::try_from(value).map(|i| i.into())
+    }
+}
+
+impl TryFrom<LogicalDateTime> for LogicalUuid {
+    type Error = ReadingError;
+
+    fn try_from(value: LogicalDateTime) -> Result<Self, Self::Error> {
+        LogicalInteger::from(value).try_into()
+    }
+}
+
+impl TryFrom<LogicalIpAddress> for LogicalInteger {
+    type Error = ReadingError;
+
+    fn try_from(value: LogicalIpAddress) -> Result<Self, Self::Error> {
+        i64::try_from(value.0).map(|i| i.into()).map_err(|_err| {
+            ReadingError::TypeConversionError(value.to_string(), PrimitiveType::Integer.to_string())
+        })
+    }
+}
+
+impl TryFrom<LogicalInteger> for LogicalIpAddress {
+    type Error = ReadingError;
+
+    fn try_from(value: LogicalInteger) -> Result<Self, Self::Error> {
+        u128::try_from(value.0).map(|u| u.into()).map_err(|_err| {
+            ReadingError::TypeConversionError(
+                value.to_string(),
+                PrimitiveType::IpAddress.to_string(),
+            )
+        })
+    }
+}
+
+impl TryFrom<LogicalIpAddress> for LogicalFloat64 {
+    type Error = ReadingError;
+
+    fn try_from(value: LogicalIpAddress) -> Result<Self, Self::Error> {
+        LogicalInteger::try_from(value)?.try_into()
+    }
+}
+
+impl TryFrom<LogicalFloat64> for LogicalIpAddress {
+    type Error = ReadingError;
+
+    fn try_from(value: LogicalFloat64) -> Result<Self, Self::Error> {
+        LogicalInteger::try_from(value)?.try_into()
+    }
+}
+
+impl TryFrom<LogicalIpAddress> for LogicalDateTime {
+    type Error = ReadingError;
+
+    fn try_from(value: LogicalIpAddress) -> Result<Self, Self::Error> {
+        LogicalInteger::try_from(value).map(|i| i.into())
+    }
+}
+
+impl TryFrom<LogicalDateTime> for LogicalIpAddress {
+    type Error = ReadingError;
+
+    fn try_from(value: LogicalDateTime) -> Result<Self, Self::Error> {
+        LogicalInteger::from(value).try_into()
+    }
+}
+
+impl From<LogicalIpAddress> for LogicalUuid {
+    fn from(value: LogicalIpAddress) -> Self {
+        value.0.into()
+    }
+}
+
+impl From<LogicalUuid> for LogicalIpAddress {
+    fn from(value: LogicalUuid) -> Self {
+        u128::from(value).into()
+    }
+}
+
 impl TryFrom<Term> for LogicalString {
     type Error = InvalidRuleTermConversion;
 
@@ -284,6 +825,56 @@ impl TryFrom<Term> for Double {
     }
 }
 
+impl TryFrom<Term> for LogicalDateTime {
+    type Error = InvalidRuleTermConversion;
+
+    fn try_from(term: Term) -> Result<Self, Self::Error> {
+        match &term {
+            Term::RdfLiteral(RdfLiteral::DatatypeValue { value, datatype })
+                if datatype == XSD_DATE_TIME || datatype == XSD_DATE =>
+            {
+                value
+                    .parse()
+                    .map_err(|_err| InvalidRuleTermConversion::new(term, PrimitiveType::DateTime))
+            }
+            _ => Err(InvalidRuleTermConversion::new(
+                term,
+                PrimitiveType::DateTime,
+            )),
+        }
+    }
+}
+
+impl TryFrom<Term> for LogicalUuid {
+    type Error = InvalidRuleTermConversion;
+
+    fn try_from(term: Term) -> Result<Self, Self::Error> {
+        let parsed = match &term {
+            Term::Constant(Identifier(iri)) if iri.starts_with(UUID_URN_PREFIX) => iri.parse(),
+            Term::StringLiteral(value) => value.parse(),
+            _ => return Err(InvalidRuleTermConversion::new(term, PrimitiveType::Uuid)),
+        };
+
+        parsed.map_err(|_err| InvalidRuleTermConversion::new(term, PrimitiveType::Uuid))
+    }
+}
+
+impl TryFrom<Term> for LogicalIpAddress {
+    type Error = InvalidRuleTermConversion;
+
+    fn try_from(term: Term) -> Result<Self, Self::Error> {
+        match &term {
+            Term::StringLiteral(value) => value
+                .parse()
+                .map_err(|_err| InvalidRuleTermConversion::new(term, PrimitiveType::IpAddress)),
+            _ => Err(InvalidRuleTermConversion::new(
+                term,
+                PrimitiveType::IpAddress,
+            )),
+        }
+    }
+}
+
 impl TryFrom<Term> for PhysicalString {
     type Error = InvalidRuleTermConversion;
 
@@ -307,7 +898,7 @@ impl TryFrom<Term> for PhysicalString {
 }
 
 /// Enum for values in the logical layer
-#[derive(Debug)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub enum PrimitiveLogicalValueT {
     /// Any variant
     Any(Term),
@@ -317,6 +908,26 @@ pub enum PrimitiveLogicalValueT {
     Integer(LogicalInteger),
     /// Float64 variant
     Float64(LogicalFloat64),
+    /// DateTime variant
+    DateTime(LogicalDateTime),
+    /// Uuid variant
+    Uuid(LogicalUuid),
+    /// IpAddress variant
+    IpAddress(LogicalIpAddress),
+}
+
+impl std::fmt::Display for PrimitiveLogicalValueT {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Any(term) => write!(f, "{term}"),
+            Self::String(value) => write!(f, "{value}"),
+            Self::Integer(value) => write!(f, "{value}"),
+            Self::Float64(value) => write!(f, "{value}"),
+            Self::DateTime(value) => write!(f, "{value}"),
+            Self::Uuid(value) => write!(f, "{value}"),
+            Self::IpAddress(value) => write!(f, "{value}"),
+        }
+    }
 }
 
 impl From<Term> for PrimitiveLogicalValueT {
@@ -343,10 +954,45 @@ impl From<LogicalFloat64> for PrimitiveLogicalValueT {
     }
 }
 
+impl From<LogicalDateTime> for PrimitiveLogicalValueT {
+    fn from(value: LogicalDateTime) -> Self {
+        Self::DateTime(value)
+    }
+}
+
+impl From<LogicalUuid> for PrimitiveLogicalValueT {
+    fn from(value: LogicalUuid) -> Self {
+        Self::Uuid(value)
+    }
+}
+
+impl From<LogicalIpAddress> for PrimitiveLogicalValueT {
+    fn from(value: LogicalIpAddress) -> Self {
+        Self::IpAddress(value)
+    }
+}
+
+impl From<PrimitiveLogicalValueT> for Term {
+    fn from(value: PrimitiveLogicalValueT) -> Self {
+        match value {
+            PrimitiveLogicalValueT::Any(term) => term,
+            PrimitiveLogicalValueT::String(value) => value.into(),
+            PrimitiveLogicalValueT::Integer(value) => value.into(),
+            PrimitiveLogicalValueT::Float64(value) => value.into(),
+            PrimitiveLogicalValueT::DateTime(value) => value.into(),
+            PrimitiveLogicalValueT::Uuid(value) => value.into(),
+            PrimitiveLogicalValueT::IpAddress(value) => value.into(),
+        }
+    }
+}
+
 pub(super) type DefaultAnyIterator<'a> = Box<dyn Iterator<Item = Term> + 'a>;
 pub(super) type DefaultStringIterator<'a> = Box<dyn Iterator<Item = LogicalString> + 'a>;
 pub(super) type DefaultIntegerIterator<'a> = Box<dyn Iterator<Item = LogicalInteger> + 'a>;
 pub(super) type DefaultFloat64Iterator<'a> = Box<dyn Iterator<Item = LogicalFloat64> + 'a>;
+pub(super) type DefaultDateTimeIterator<'a> = Box<dyn Iterator<Item = LogicalDateTime> + 'a>;
+pub(super) type DefaultUuidIterator<'a> = Box<dyn Iterator<Item = LogicalUuid> + 'a>;
+pub(super) type DefaultIpAddressIterator<'a> = Box<dyn Iterator<Item = LogicalIpAddress> + 'a>;
 pub(super) type DefaultSerializedIterator<'a> = Box<dyn Iterator<Item = String> + 'a>;
 
 /// Iterator over one kind of possible logical values
@@ -360,6 +1006,12 @@ pub enum PrimitiveLogicalValueIteratorT<'a> {
     Integer(DefaultIntegerIterator<'a>),
     /// Float64 variant
     Float64(DefaultFloat64Iterator<'a>),
+    /// DateTime variant
+    DateTime(DefaultDateTimeIterator<'a>),
+    /// Uuid variant
+    Uuid(DefaultUuidIterator<'a>),
+    /// IpAddress variant
+    IpAddress(DefaultIpAddressIterator<'a>),
 }
 
 impl<'a> Iterator for PrimitiveLogicalValueIteratorT<'a> {
@@ -371,6 +1023,9 @@ impl<'a> Iterator for PrimitiveLogicalValueIteratorT<'a> {
             Self::String(iter) => Some(PrimitiveLogicalValueT::String(iter.next()?)),
             Self::Integer(iter) => Some(PrimitiveLogicalValueT::Integer(iter.next()?)),
             Self::Float64(iter) => Some(PrimitiveLogicalValueT::Float64(iter.next()?)),
+            Self::DateTime(iter) => Some(PrimitiveLogicalValueT::DateTime(iter.next()?)),
+            Self::Uuid(iter) => Some(PrimitiveLogicalValueT::Uuid(iter.next()?)),
+            Self::IpAddress(iter) => Some(PrimitiveLogicalValueT::IpAddress(iter.next()?)),
         }
     }
 }
@@ -586,6 +1241,93 @@ impl<'a> From<Float64OutputMapper<'a>> for DefaultSerializedIterator<'a> {
     }
 }
 
+pub(super) struct DateTimeOutputMapper<'a> {
+    physical_iter: Box<dyn Iterator<Item = i64> + 'a>,
+}
+
+impl<'a> DateTimeOutputMapper<'a> {
+    pub(super) fn new(phy: DataValueIteratorT<'a>) -> Self {
+        match phy {
+            DataValueIteratorT::I64(physical_iter) => Self { physical_iter },
+            _ => unreachable!("If the database representation of the logical types is correct, we never reach this branch.")
+        }
+    }
+}
+
+impl<'a> From<DateTimeOutputMapper<'a>> for DefaultDateTimeIterator<'a> {
+    fn from(source: DateTimeOutputMapper<'a>) -> Self {
+        Box::new(source.physical_iter.map(|i| i.into()))
+    }
+}
+
+impl<'a> From<DateTimeOutputMapper<'a>> for DefaultSerializedIterator<'a> {
+    fn from(source: DateTimeOutputMapper<'a>) -> Self {
+        Box::new(
+            source
+                .physical_iter
+                .map(|i| LogicalDateTime::from(i).to_string()),
+        )
+    }
+}
+
+pub(super) struct UuidOutputMapper<'a> {
+    physical_iter: Box<dyn Iterator<Item = u128> + 'a>,
+}
+
+impl<'a> UuidOutputMapper<'a> {
+    pub(super) fn new(phy: DataValueIteratorT<'a>) -> Self {
+        match phy {
+            DataValueIteratorT::U128(physical_iter) => Self { physical_iter },
+            _ => unreachable!("If the database representation of the logical types is correct, we never reach this branch.")
+        }
+    }
+}
+
+impl<'a> From<UuidOutputMapper<'a>> for DefaultUuidIterator<'a> {
+    fn from(source: UuidOutputMapper<'a>) -> Self {
+        Box::new(source.physical_iter.map(|u| u.into()))
+    }
+}
+
+impl<'a> From<UuidOutputMapper<'a>> for DefaultSerializedIterator<'a> {
+    fn from(source: UuidOutputMapper<'a>) -> Self {
+        Box::new(
+            source
+                .physical_iter
+                .map(|u| LogicalUuid::from(u).to_string()),
+        )
+    }
+}
+
+pub(super) struct IpAddressOutputMapper<'a> {
+    physical_iter: Box<dyn Iterator<Item = u128> + 'a>,
+}
+
+impl<'a> IpAddressOutputMapper<'a> {
+    pub(super) fn new(phy: DataValueIteratorT<'a>) -> Self {
+        match phy {
+            DataValueIteratorT::U128(physical_iter) => Self { physical_iter },
+            _ => unreachable!("If the database representation of the logical types is correct, we never reach this branch.")
+        }
+    }
+}
+
+impl<'a> From<IpAddressOutputMapper<'a>> for DefaultIpAddressIterator<'a> {
+    fn from(source: IpAddressOutputMapper<'a>) -> Self {
+        Box::new(source.physical_iter.map(|a| a.into()))
+    }
+}
+
+impl<'a> From<IpAddressOutputMapper<'a>> for DefaultSerializedIterator<'a> {
+    fn from(source: IpAddressOutputMapper<'a>) -> Self {
+        Box::new(
+            source
+                .physical_iter
+                .map(|a| LogicalIpAddress::from(a).to_string()),
+        )
+    }
+}
+
 #[cfg(test)]
 mod test {
     use std::assert_eq;
@@ -1073,4 +1815,101 @@ mod test {
             .collect::<Vec<_>>(),
         );
     }
+
+    #[test]
+    fn datetime_parsing() {
+        let parse = |input: &str| input.parse::<LogicalDateTime>().map(i64::from).ok();
+
+        assert_eq!(parse("1970-01-01"), Some(0));
+        assert_eq!(parse("1969-12-31T23:59:59Z"), Some(-1));
+        assert_eq!(parse("2000-02-29T12:30:00+02:00"), Some(951_820_200));
+        assert_eq!(parse("2001-02-29"), None);
+        assert_eq!(parse("2020-01-01T24:00:00"), None);
+        assert_eq!(parse("2020-1-01"), None);
+
+        assert_eq!(
+            LogicalDateTime::from(951_820_200).to_string(),
+            "2000-02-29T10:30:00Z"
+        );
+        assert_eq!(
+            LogicalDateTime::try_from(Term::RdfLiteral(RdfLiteral::DatatypeValue {
+                value: "2020-05-17".to_string(),
+                datatype: XSD_DATE.to_string(),
+            }))
+            .map(i64::from)
+            .ok(),
+            Some(1_589_673_600)
+        );
+    }
+
+    #[test]
+    fn uuid_parsing() {
+        let parse = |input: &str| input.parse::<LogicalUuid>().map(u128::from).ok();
+
+        assert_eq!(
+            parse("123e4567-e89b-12d3-a456-426614174000"),
+            Some(0x123e4567_e89b_12d3_a456_426614174000)
+        );
+        assert_eq!(
+            parse("urn:uuid:123E4567E89B12D3A456426614174000"),
+            Some(0x123e4567_e89b_12d3_a456_426614174000)
+        );
+        assert_eq!(parse("123e4567-e89b-12d3-a456-42661417400"), None);
+        assert_eq!(parse("123e4567e-89b-12d3-a456-426614174000"), None);
+        assert_eq!(parse("+23e4567e89b12d3a456426614174000"), None);
+
+        let uuid = LogicalUuid::from(0x00000000_0000_0000_0000_00000000002a);
+        assert_eq!(uuid.to_string(), "00000000-0000-0000-0000-00000000002a");
+        assert_eq!(LogicalUuid::try_from(Term::from(uuid)).ok(), Some(uuid));
+        assert_eq!(
+            LogicalUuid::try_from(Term::StringLiteral(uuid.to_string())).ok(),
+            Some(uuid)
+        );
+    }
+
+    #[test]
+    fn ip_address_parsing() {
+        let parse = |input: &str| input.parse::<LogicalIpAddress>().ok();
+
+        let ipv4 = parse("192.168.1.20").unwrap();
+        assert_eq!(u128::from(ipv4), 0xffff_c0a8_0114);
+        assert_eq!(ipv4.to_string(), "192.168.1.20");
+        assert_eq!(parse("2001:DB8::1").unwrap().to_string(), "2001:db8::1");
+        assert_eq!(parse("192.168.1.256"), None);
+        assert_eq!(
+            LogicalIpAddress::try_from(Term::from(ipv4)).ok(),
+            Some(ipv4)
+        );
+
+        let range = |cidr: &str| {
+            LogicalIpAddress::cidr_range(cidr)
+                .map(|(first, last)| (first.to_string(), last.to_string()))
+                .ok()
+        };
+
+        assert_eq!(
+            range("10.1.2.3/8"),
+            Some(("10.0.0.0".to_string(), "10.255.255.255".to_string()))
+        );
+        assert_eq!(
+            range("0.0.0.0/0"),
+            Some(("0.0.0.0".to_string(), "255.255.255.255".to_string()))
+        );
+        assert_eq!(
+            range("2001:db8::/32"),
+            Some((
+                "2001:db8::".to_string(),
+                "2001:db8:ffff:ffff:ffff:ffff:ffff:ffff".to_string()
+            ))
+        );
+        assert_eq!(
+            range("::/0"),
+            Some((
+                "::".to_string(),
+                "ffff:ffff:ffff:ffff:ffff:ffff:ffff:ffff".to_string()
+            ))
+        );
+        assert_eq!(range("10.0.0.0/33"), None);
+        assert_eq!(range("10.0.0.0"), None);
+    }
 }
@@ -2,18 +2,22 @@ use std::fmt::Display;
 use std::str::FromStr;
 
 use crate::builder_proxy::{
-    LogicalAnyColumnBuilderProxy, LogicalColumnBuilderProxyT, LogicalFloat64ColumnBuilderProxy,
-    LogicalIntegerColumnBuilderProxy, LogicalStringColumnBuilderProxy,
+    LogicalAnyColumnBuilderProxy, LogicalColumnBuilderProxyT, LogicalDateTimeColumnBuilderProxy,
+    LogicalFloat64ColumnBuilderProxy, LogicalIntegerColumnBuilderProxy,
+    LogicalIpAddressColumnBuilderProxy, LogicalStringColumnBuilderProxy,
+    LogicalUuidColumnBuilderProxy,
 };
 use crate::io::parser::ParseError;
 use nemo_physical::builder_proxy::PhysicalBuilderProxyEnum;
 use nemo_physical::datatypes::data_value::DataValueIteratorT;
-use nemo_physical::datatypes::{DataTypeName, DataValueT};
+use nemo_physical::datatypes::{data_value::PhysicalString, DataTypeName, DataValueT};
 
 use super::error::InvalidRuleTermConversion;
 use super::primitive_logical_value::{
-    AnyOutputMapper, DefaultSerializedIterator, Float64OutputMapper, IntegerOutputMapper,
-    PrimitiveLogicalValueIteratorT, StringOutputMapper,
+    AnyOutputMapper, DateTimeOutputMapper, DefaultSerializedIterator, Float64OutputMapper,
+    IntegerOutputMapper, IpAddressOutputMapper, LogicalDateTime, LogicalFloat64, LogicalInteger,
+    LogicalIpAddress, LogicalString, LogicalUuid, PrimitiveLogicalValueIteratorT,
+    PrimitiveLogicalValueT, StringOutputMapper, UuidOutputMapper,
 };
 use crate::model::{NestedType, Term};
 
@@ -69,7 +73,10 @@ generate_logical_type_enum!(
     (Any, "any"),
     (String, "string"),
     (Integer, "integer"),
-    (Float64, "float64")
+    (Float64, "float64"),
+    (DateTime, "datetime"),
+    (Uuid, "uuid"),
+    (IpAddress, "ipaddr")
 );
 
 impl PartialOrd for PrimitiveType {
@@ -97,6 +104,21 @@ impl PartialOrd for PrimitiveType {
                 Self::Float64 => Some(std::cmp::Ordering::Equal),
                 _ => None,
             },
+            Self::DateTime => match other {
+                Self::Any => None, // TODO: should be the following once reasoning supports casting: Some(std::cmp::Ordering::Less),
+                Self::DateTime => Some(std::cmp::Ordering::Equal),
+                _ => None,
+            },
+            Self::Uuid => match other {
+                Self::Any => None, // TODO: should be the following once reasoning supports casting: Some(std::cmp::Ordering::Less),
+                Self::Uuid => Some(std::cmp::Ordering::Equal),
+                _ => None,
+            },
+            Self::IpAddress => match other {
+                Self::Any => None, // TODO: should be the following once reasoning supports casting: Some(std::cmp::Ordering::Less),
+                Self::IpAddress => Some(std::cmp::Ordering::Equal),
+                _ => None,
+            },
         }
     }
 }
@@ -114,6 +136,9 @@ impl From<PrimitiveType> for DataTypeName {
             PrimitiveType::String => Self::String,
             PrimitiveType::Integer => Self::I64,
             PrimitiveType::Float64 => Self::Double,
+            PrimitiveType::DateTime => Self::I64,
+            PrimitiveType::Uuid => Self::U128,
+            PrimitiveType::IpAddress => Self::U128,
         }
     }
 }
@@ -140,21 +165,56 @@ impl PrimitiveType {
             Self::String => DataValueT::String(gt.try_into()?),
             Self::Integer => DataValueT::I64(gt.try_into()?),
             Self::Float64 => DataValueT::Double(gt.try_into()?),
+            Self::DateTime => DataValueT::I64(LogicalDateTime::try_from(gt)?.into()),
+            Self::Uuid => DataValueT::U128(LogicalUuid::try_from(gt)?.into()),
+            Self::IpAddress => DataValueT::U128(LogicalIpAddress::try_from(gt)?.into()),
+        };
+
+        Ok(result)
+    }
+
+    /// Convert a given ground term to the logical value
+    /// it is read back as after storing it in a column of the current logical type
+    pub fn ground_term_to_logical_value(
+        &self,
+        gt: Term,
+    ) -> Result<PrimitiveLogicalValueT, InvalidRuleTermConversion> {
+        let result = match self {
+            Self::Any => PrimitiveLogicalValueT::Any(PhysicalString::try_from(gt)?.into()),
+            Self::String => LogicalString::try_from(gt)?.into(),
+            Self::Integer => LogicalInteger::try_from(gt)?.into(),
+            Self::Float64 => LogicalFloat64::try_from(gt)?.into(),
+            Self::DateTime => LogicalDateTime::try_from(gt)?.into(),
+            Self::Uuid => LogicalUuid::try_from(gt)?.into(),
+            Self::IpAddress => LogicalIpAddress::try_from(gt)?.into(),
         };
 
         Ok(result)
     }
 
     /// Whether this logical type can be used to perform numeric operations.
+    ///
+    /// Datetimes are compared and combined as numbers of seconds.
     pub fn allows_numeric_operations(&self) -> bool {
         match self {
             Self::Any => false,
             Self::String => false,
             Self::Integer => true,
             Self::Float64 => true,
+            Self::DateTime => true,
+            Self::Uuid => false,
+            Self::IpAddress => false,
         }
     }
 
+    /// Whether values of this logical type can be compared with `<`, `<=`, `>` and `>=`.
+    ///
+    /// IP addresses are ordered by their numeric value, which allows checking
+    /// whether they belong to a network.
+    pub fn allows_comparisons(&self) -> bool {
+        self.allows_numeric_operations() || matches!(self, Self::IpAddress)
+    }
+
     /// Wrap physical builder proxy into logical equivalent
     pub fn wrap_physical_column_builder<'a: 'b, 'b>(
         self,
@@ -173,6 +233,15 @@ impl PrimitiveType {
             Self::Float64 => {
                 LogicalColumnBuilderProxyT::Float64(LogicalFloat64ColumnBuilderProxy::new(physical))
             }
+            Self::DateTime => LogicalColumnBuilderProxyT::DateTime(
+                LogicalDateTimeColumnBuilderProxy::new(physical),
+            ),
+            Self::Uuid => {
+                LogicalColumnBuilderProxyT::Uuid(LogicalUuidColumnBuilderProxy::new(physical))
+            }
+            Self::IpAddress => LogicalColumnBuilderProxyT::IpAddress(
+                LogicalIpAddressColumnBuilderProxy::new(physical),
+            ),
         }
     }
 
@@ -194,6 +263,15 @@ impl PrimitiveType {
             Self::Float64 => PrimitiveLogicalValueIteratorT::Float64(
                 Float64OutputMapper::new(physical_iter).into(),
             ),
+            Self::DateTime => PrimitiveLogicalValueIteratorT::DateTime(
+                DateTimeOutputMapper::new(physical_iter).into(),
+            ),
+            Self::Uuid => {
+                PrimitiveLogicalValueIteratorT::Uuid(UuidOutputMapper::new(physical_iter).into())
+            }
+            Self::IpAddress => PrimitiveLogicalValueIteratorT::IpAddress(
+                IpAddressOutputMapper::new(physical_iter).into(),
+            ),
         }
     }
 
@@ -208,6 +286,9 @@ impl PrimitiveType {
             Self::String => StringOutputMapper::new(physical_iter).into(),
             Self::Integer => IntegerOutputMapper::new(physical_iter).into(),
             Self::Float64 => Float64OutputMapper::new(physical_iter).into(),
+            Self::DateTime => DateTimeOutputMapper::new(physical_iter).into(),
+            Self::Uuid => UuidOutputMapper::new(physical_iter).into(),
+            Self::IpAddress => IpAddressOutputMapper::new(physical_iter).into(),
         }
     }
 }
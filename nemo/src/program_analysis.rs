@@ -6,5 +6,11 @@ pub mod analysis;
 /// Transformation of a program into an normalized form
 pub mod normalization;
 
+/// Checks for the monotone use of aggregates in recursion
+pub mod monotonicity;
+
+/// Detection of the fragment of existential rules a program belongs to
+pub mod fragment;
+
 /// Functionality for computing promising variable orders from a program
 pub mod variable_order;
@@ -7,12 +7,13 @@ use crate::{
     model::chase_model::{ChaseProgram, ChaseRule},
     model::{
         chase_model::ChaseAtom, types::error::TypeError, DataSource, FilterOperation, Identifier,
-        PrimitiveType, Term, TermOperation, TypeConstraint, Variable,
+        PrimitiveType, Term, TermOperation, TermTree, TypeConstraint, Variable,
     },
     util::labeled_graph::LabeledGraph,
 };
 
 use super::{
+    fragment::Fragment,
     normalization::normalize_atom_vector,
     variable_order::{build_preferable_variable_orders, BuilderResultVariants, VariableOrder},
 };
@@ -67,6 +68,18 @@ pub struct RuleAnalysis {
     pub predicate_types: HashMap<Identifier, Vec<PrimitiveType>>,
 }
 
+/// Variable orders of a rule, whose computation is the most expensive part of the analysis of a program.
+///
+/// They can be computed once and reused for later analyses of the same program,
+/// see [`ChaseProgram::analyze_with_variable_orders`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RuleVariableOrders {
+    /// Variable orders that are worth considering for the body of the rule.
+    pub promising: Vec<VariableOrder>,
+    /// Variable order for the join of the head atoms, if the rule is existential.
+    pub existential_aux: VariableOrder,
+}
+
 /// Errors than can occur during rule analysis
 #[derive(Error, Debug, Copy, Clone)]
 #[allow(clippy::enum_variant_names)]
@@ -74,6 +87,9 @@ pub enum RuleAnalysisError {
     /// Unsupported feature: Overloading of predicate names by arity/type
     #[error("Overloading of predicate names by arity is currently not supported.")]
     UnsupportedFeaturePredicateOverloading,
+    /// Precomputed variable orders do not belong to the analyzed program
+    #[error("The precomputed variable orders do not match the rules of the program.")]
+    VariableOrderMismatch,
 }
 
 /// Return true if there is a predicate in the positive part of the rule that also appears in the head of the rule.
@@ -121,12 +137,7 @@ fn get_fresh_rule_predicate(rule_index: usize) -> Identifier {
     ))
 }
 
-fn construct_existential_aux_rule(
-    rule_index: usize,
-    head_atoms: &Vec<ChaseAtom>,
-    predicate_types: &HashMap<Identifier, Vec<PrimitiveType>>,
-    column_orders: &HashMap<Identifier, HashSet<ColumnOrder>>,
-) -> (ChaseRule, VariableOrder, HashMap<Variable, PrimitiveType>) {
+fn construct_existential_aux_rule(rule_index: usize, head_atoms: &Vec<ChaseAtom>) -> ChaseRule {
     let normalized_head = normalize_atom_vector(head_atoms, &[], &mut 0);
 
     let temp_head_identifier = get_fresh_rule_predicate(rule_index);
@@ -144,8 +155,24 @@ fn construct_existential_aux_rule(
         }
     }
 
+    let temp_head_atom = ChaseAtom::new(temp_head_identifier, term_vec);
+    ChaseRule::new(
+        vec![temp_head_atom],
+        HashMap::default(),
+        normalized_head.atoms,
+        normalized_head.filters,
+        vec![],
+        vec![],
+    )
+}
+
+/// Return the types of the variables of the auxillary rule of an existential rule.
+fn existential_aux_types(
+    aux_rule: &ChaseRule,
+    predicate_types: &HashMap<Identifier, Vec<PrimitiveType>>,
+) -> HashMap<Variable, PrimitiveType> {
     let mut variable_types = HashMap::<Variable, PrimitiveType>::new();
-    for atom in &normalized_head.atoms {
+    for atom in aux_rule.positive_body() {
         let types = predicate_types
             .get(&atom.predicate())
             .expect("Every predicate should have type information at this point");
@@ -157,31 +184,27 @@ fn construct_existential_aux_rule(
         }
     }
 
-    let temp_head_atom = ChaseAtom::new(temp_head_identifier, term_vec);
-    let temp_rule = ChaseRule::new(
-        vec![temp_head_atom],
-        HashMap::default(),
-        normalized_head.atoms,
-        normalized_head.filters,
-        vec![],
-        vec![],
-    );
-
-    let temp_program = vec![temp_rule.clone()].into();
-    let variable_order =
-        build_preferable_variable_orders(&temp_program, Some(column_orders.clone()))
-            .all_variable_orders
-            .pop()
-            .and_then(|mut v| v.pop())
-            .expect("This functions provides at least one variable order");
+    variable_types
+}
 
-    (temp_rule, variable_order, variable_types)
+/// Compute the variable order for the join of the head atoms of the existential rule with the given index.
+fn existential_aux_order(
+    rule_index: usize,
+    head_atoms: &Vec<ChaseAtom>,
+    column_orders: &HashMap<Identifier, HashSet<ColumnOrder>>,
+) -> VariableOrder {
+    let temp_program = vec![construct_existential_aux_rule(rule_index, head_atoms)].into();
+
+    build_preferable_variable_orders(&temp_program, Some(column_orders.clone()))
+        .all_variable_orders
+        .pop()
+        .and_then(|mut v| v.pop())
+        .expect("This functions provides at least one variable order")
 }
 
 fn analyze_rule(
     rule: &ChaseRule,
-    promising_variable_orders: Vec<VariableOrder>,
-    promising_column_orders: &[HashMap<Identifier, HashSet<ColumnOrder>>],
+    variable_orders: RuleVariableOrders,
     rule_index: usize,
     type_declarations: &HashMap<Identifier, Vec<PrimitiveType>>,
 ) -> RuleAnalysis {
@@ -207,18 +230,14 @@ fn analyze_rule(
         .map(|a| a.predicate())
         .collect();
 
-    let (existential_aux_rule, existential_aux_order, existential_aux_types) =
-        if num_existential > 0 {
-            // TODO: We only consider the first variable order
-            construct_existential_aux_rule(
-                rule_index,
-                rule.head(),
-                type_declarations,
-                &promising_column_orders[0],
-            )
-        } else {
-            (ChaseRule::default(), VariableOrder::new(), HashMap::new())
-        };
+    let (existential_aux_rule, existential_aux_types) = if num_existential > 0 {
+        let aux_rule = construct_existential_aux_rule(rule_index, rule.head());
+        let aux_types = existential_aux_types(&aux_rule, type_declarations);
+
+        (aux_rule, aux_types)
+    } else {
+        (ChaseRule::default(), HashMap::new())
+    };
 
     RuleAnalysis {
         is_existential: num_existential > 0,
@@ -233,9 +252,9 @@ fn analyze_rule(
         head_variables: get_variables(rule.head()),
         num_existential,
         existential_aux_rule,
-        existential_aux_order,
+        existential_aux_order: variable_orders.existential_aux,
         existential_aux_types,
-        promising_variable_orders,
+        promising_variable_orders: variable_orders.promising,
         variable_types,
         predicate_types: type_declarations
             .iter()
@@ -362,7 +381,7 @@ impl From<TypeRequirement> for Option<PrimitiveType> {
 }
 
 /// Contains useful information about the
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct ProgramAnalysis {
     /// Analysis result for each rule.
     pub rule_analysis: Vec<RuleAnalysis>,
@@ -374,6 +393,21 @@ pub struct ProgramAnalysis {
     pub predicate_types: HashMap<Identifier, Vec<PrimitiveType>>,
     /// Graph representing the information flow between predicates
     pub position_graph: PositionGraph,
+    /// Most restrictive fragment of existential rules that contains the program
+    pub fragment: Fragment,
+}
+
+impl ProgramAnalysis {
+    /// Return the variable orders of all rules, in the order of the program.
+    pub fn variable_orders(&self) -> Vec<RuleVariableOrders> {
+        self.rule_analysis
+            .iter()
+            .map(|analysis| RuleVariableOrders {
+                promising: analysis.promising_variable_orders.clone(),
+                existential_aux: analysis.existential_aux_order.clone(),
+            })
+            .collect()
+    }
 }
 
 impl ChaseProgram {
@@ -500,7 +534,7 @@ impl ChaseProgram {
                     .get(head_variable)
                     .expect("The loop at the top went through all head atoms")[0];
 
-                for term in tree.terms() {
+                for term in tree.terms_determining_type() {
                     if let Term::Variable(body_variable) = term {
                         let body_position = variables_to_last_node
                             .get(body_variable)
@@ -577,21 +611,27 @@ impl ChaseProgram {
                 })
                 .collect::<HashMap<_, _>>();
 
+            // Existential variables hold nulls,
+            // whereas the results of some functions (e.g. `RAND()` or `INT(?x)`) have a fixed type
             let existential_decls = self
                 .rules()
                 .iter()
-                .flat_map(|r| r.head())
-                .map(|a| {
+                .flat_map(|r| r.head().iter().map(move |a| (r, a)))
+                .map(|(r, a)| {
                     (
                         a.predicate(),
                         a.terms()
                             .iter()
-                            .map(|t| {
-                                if matches!(t, Term::Variable(Variable::Existential(_))) {
+                            .map(|t| match t {
+                                Term::Variable(Variable::Existential(_)) => {
                                     TypeRequirement::Hard(PrimitiveType::Any)
-                                } else {
-                                    TypeRequirement::None
                                 }
+                                Term::Variable(variable) => r
+                                    .constructors()
+                                    .get(variable)
+                                    .and_then(TermTree::fixed_type)
+                                    .map_or(TypeRequirement::None, TypeRequirement::Hard),
+                                _ => TypeRequirement::None,
                             })
                             .collect::<Vec<_>>(),
                     )
@@ -640,6 +680,50 @@ impl ChaseProgram {
                     .entry(predicate.clone())
                     .or_insert(vec![TypeRequirement::None; *arity]);
             }
+
+            // The last column of annotated predicates contains floating point annotations
+            let annotation_type = TypeRequirement::Hard(PrimitiveType::Float64);
+            for predicate in self.annotated_predicates() {
+                let Some(types) = predicate_types.get_mut(predicate) else {
+                    continue;
+                };
+                let (position, last) = (types.len(), types.last_mut());
+
+                if let Some(last) = last {
+                    *last = last.stricter_requirement(annotation_type).ok_or_else(|| {
+                        TypeError::InvalidRuleConflictingTypes(
+                            predicate.0.clone(),
+                            position,
+                            Option::<PrimitiveType>::from(*last)
+                                .expect("if the type requirement is none, there is a maximum"),
+                            PrimitiveType::Float64,
+                        )
+                    })?;
+                }
+            }
+
+            // The last two columns of temporal predicates contain the validity intervals
+            let interval_type = TypeRequirement::Hard(PrimitiveType::DateTime);
+            for predicate in self.temporal_predicates() {
+                let Some(types) = predicate_types.get_mut(predicate) else {
+                    continue;
+                };
+                let arity = types.len();
+
+                for (position, bound) in types.iter_mut().enumerate().skip(arity.saturating_sub(2))
+                {
+                    *bound = bound.stricter_requirement(interval_type).ok_or_else(|| {
+                        TypeError::InvalidRuleConflictingTypes(
+                            predicate.0.clone(),
+                            position + 1,
+                            Option::<PrimitiveType>::from(*bound)
+                                .expect("if the type requirement is none, there is a maximum"),
+                            PrimitiveType::DateTime,
+                        )
+                    })?;
+                }
+            }
+
             predicate_types
         };
 
@@ -741,6 +825,10 @@ impl ChaseProgram {
     pub fn check_for_unsupported_features(&self) -> Result<(), RuleAnalysisError> {
         let mut arities = HashMap::new();
 
+        for (predicate, types) in self.parsed_predicate_declarations() {
+            arities.insert(predicate, types.len());
+        }
+
         for source in self.sources() {
             arities.insert(source.predicate.clone(), source.input_types().arity());
         }
@@ -773,8 +861,9 @@ impl ChaseProgram {
                         .variable_types
                         .get(variable)
                         .expect("Previous analysis should have assigned a type to each variable.");
+                    let requires_numeric_types = term_tree.requires_numeric_types();
 
-                    if !variable_type.allows_numeric_operations() {
+                    if requires_numeric_types && !variable_type.allows_numeric_operations() {
                         return Err(TypeError::InvalidRuleNonNumericArithmetic);
                     }
 
@@ -784,7 +873,8 @@ impl ChaseProgram {
                                 "Previous analysis should have assigned a type to each variable.",
                             );
 
-                            if !variable_type.allows_numeric_operations() {
+                            if requires_numeric_types && !variable_type.allows_numeric_operations()
+                            {
                                 return Err(TypeError::InvalidRuleNonNumericArithmetic);
                             }
                         } else {
@@ -825,6 +915,33 @@ impl ChaseProgram {
         for (rule, analysis) in self.rules().iter().zip(analyses.iter()) {
             for filter in rule.all_filters() {
                 let left_variable = &filter.lhs;
+
+                if let FilterOperation::EditDistanceAtMost(_) = filter.operation {
+                    let is_string = |variable: &Variable| {
+                        matches!(
+                            analysis.variable_types.get(variable).expect(
+                                "Previous analysis should have assigned a type to each variable."
+                            ),
+                            PrimitiveType::String | PrimitiveType::Any
+                        )
+                    };
+
+                    let right_is_string = match &filter.rhs {
+                        Term::Variable(right_variable) => is_string(right_variable),
+                        right_term => {
+                            PrimitiveType::String
+                                .ground_term_to_data_value_t(right_term.clone())?;
+                            true
+                        }
+                    };
+
+                    if !is_string(left_variable) || !right_is_string {
+                        return Err(TypeError::InvalidRuleNonStringEditDistance);
+                    }
+
+                    continue;
+                }
+
                 let right_term = if let Term::Variable(_) = filter.rhs {
                     continue;
                 } else {
@@ -836,8 +953,15 @@ impl ChaseProgram {
                     .get(left_variable)
                     .expect("Previous analysis should have assigned a type to each variable.");
 
-                if filter.operation != FilterOperation::Equals
-                    && !variable_type.allows_numeric_operations()
+                if filter.operation == FilterOperation::MatchesText {
+                    if !matches!(variable_type, PrimitiveType::String | PrimitiveType::Any) {
+                        return Err(TypeError::InvalidRuleNonStringTextSearch);
+                    }
+
+                    continue;
+                }
+
+                if filter.operation != FilterOperation::Equals && !variable_type.allows_comparisons()
                 {
                     return Err(TypeError::InvalidRuleNonNumericComparison);
                 }
@@ -852,7 +976,11 @@ impl ChaseProgram {
 
                 for (term_index, term) in atom.terms().iter().enumerate() {
                     if let Term::Variable(head_variable) = term {
-                        if rule.constructors().contains_key(head_variable) {
+                        if rule
+                            .constructors()
+                            .get(head_variable)
+                            .is_some_and(|tree| tree.0.is_leaf() || tree.requires_numeric_types())
+                        {
                             let variable_type = analysis.variable_types.get(head_variable).expect(
                                 "Previous analysis should have assigned a type to each variable.",
                             );
@@ -873,13 +1001,90 @@ impl ChaseProgram {
         Ok(())
     }
 
-    /// Analyze itself and return a struct containing the results.
-    pub fn analyze(&self) -> Result<ProgramAnalysis, Error> {
+    /// Compute the variable orders of all rules, in the order of the program.
+    fn compute_variable_orders(&self) -> Vec<RuleVariableOrders> {
         let BuilderResultVariants {
             all_variable_orders,
             all_column_orders,
         } = build_preferable_variable_orders(self, None);
 
+        self.rules()
+            .iter()
+            .zip(all_variable_orders)
+            .enumerate()
+            .map(|(rule_index, (rule, promising))| {
+                let existential_aux = if count_distinct_existential_variables(rule) > 0 {
+                    // TODO: We only consider the first variable order
+                    existential_aux_order(rule_index, rule.head(), &all_column_orders[0])
+                } else {
+                    VariableOrder::new()
+                };
+
+                RuleVariableOrders {
+                    promising,
+                    existential_aux,
+                }
+            })
+            .collect()
+    }
+
+    /// Check that the given variable orders belong to the rules of this program,
+    /// i.e. that they order exactly the variables of the corresponding rules.
+    fn check_variable_orders(
+        &self,
+        variable_orders: &[RuleVariableOrders],
+    ) -> Result<(), RuleAnalysisError> {
+        if variable_orders.len() != self.rules().len() {
+            return Err(RuleAnalysisError::VariableOrderMismatch);
+        }
+
+        for (rule_index, (rule, orders)) in self.rules().iter().zip(variable_orders).enumerate() {
+            let body_variables = get_variables(rule.positive_body());
+            let aux_variables = if count_distinct_existential_variables(rule) > 0 {
+                get_variables(
+                    construct_existential_aux_rule(rule_index, rule.head()).positive_body(),
+                )
+            } else {
+                HashSet::new()
+            };
+
+            let orders_variables =
+                |order: &VariableOrder| order.iter().cloned().collect::<HashSet<_>>();
+            if orders.promising.is_empty()
+                || orders
+                    .promising
+                    .iter()
+                    .any(|order| orders_variables(order) != body_variables)
+                || orders_variables(&orders.existential_aux) != aux_variables
+            {
+                return Err(RuleAnalysisError::VariableOrderMismatch);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Analyze itself and return a struct containing the results.
+    pub fn analyze(&self) -> Result<ProgramAnalysis, Error> {
+        self.analyze_with_variable_orders(None)
+    }
+
+    /// Analyze itself and return a struct containing the results,
+    /// reusing the given variable orders (e.g. of an earlier analysis) instead of computing them.
+    ///
+    /// Returns an error if the variable orders do not belong to the rules of this program.
+    pub fn analyze_with_variable_orders(
+        &self,
+        variable_orders: Option<Vec<RuleVariableOrders>>,
+    ) -> Result<ProgramAnalysis, Error> {
+        let variable_orders = match variable_orders {
+            Some(variable_orders) => {
+                self.check_variable_orders(&variable_orders)?;
+                variable_orders
+            }
+            None => self.compute_variable_orders(),
+        };
+
         let all_predicates = self.get_all_predicates();
         let derived_predicates = self.get_head_predicates();
 
@@ -889,20 +1094,14 @@ impl ChaseProgram {
         let rule_analysis: Vec<RuleAnalysis> = self
             .rules()
             .iter()
+            .zip(variable_orders)
             .enumerate()
-            .map(|(i, r)| {
-                analyze_rule(
-                    r,
-                    all_variable_orders[i].clone(),
-                    &all_column_orders,
-                    i,
-                    &predicate_types,
-                )
-            })
+            .map(|(i, (r, orders))| analyze_rule(r, orders, i, &predicate_types))
             .collect();
 
         self.check_for_incompatible_constant_types(&rule_analysis, &predicate_types)?;
         self.check_for_nonnumeric_arithmetic(&rule_analysis)?;
+        self.check_for_nonmonotone_aggregates()?;
 
         Ok(ProgramAnalysis {
             rule_analysis,
@@ -910,6 +1109,7 @@ impl ChaseProgram {
             all_predicates,
             predicate_types,
             position_graph,
+            fragment: self.fragment(),
         })
     }
 }
@@ -0,0 +1,135 @@
+use std::{collections::HashSet, fmt::Display};
+
+use crate::model::{
+    chase_model::{ChaseAtom, ChaseProgram, ChaseRule},
+    Term, Variable,
+};
+
+/// Fragment of existential rules (Datalog±) that a program belongs to.
+///
+/// The fragments are ordered from the most to the least restrictive one,
+/// such that a program belongs to every fragment that is not smaller than its [`ChaseProgram::fragment`].
+/// The chase of programs in the linear and guarded fragments may not terminate,
+/// but their models have bounded treewidth, such that query answering is decidable.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Fragment {
+    /// No rule contains existential variables, so the chase always terminates.
+    Datalog,
+    /// Every rule has at most one positive body atom.
+    Linear,
+    /// Every rule has a positive body atom, its guard, that contains all variables of the positive body.
+    Guarded,
+    /// Some rule with existential variables is neither linear nor guarded.
+    Unrestricted,
+}
+
+impl Display for Fragment {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Datalog => write!(f, "Datalog"),
+            Self::Linear => write!(f, "linear existential rules"),
+            Self::Guarded => write!(f, "guarded existential rules"),
+            Self::Unrestricted => {
+                write!(f, "existential rules that are neither linear nor guarded")
+            }
+        }
+    }
+}
+
+/// Return the variables of the given atom.
+fn atom_variables(atom: &ChaseAtom) -> HashSet<&Variable> {
+    atom.terms()
+        .iter()
+        .filter_map(|term| match term {
+            Term::Variable(variable) => Some(variable),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Return whether the rule has an existential variable in its head.
+pub(crate) fn is_existential(rule: &ChaseRule) -> bool {
+    rule.head().iter().any(|atom| {
+        atom.terms()
+            .iter()
+            .any(|term| matches!(term, Term::Variable(Variable::Existential(_))))
+    })
+}
+
+/// Return whether the rule has a positive body atom that contains all variables of the positive body.
+pub(crate) fn is_guarded(rule: &ChaseRule) -> bool {
+    let body_variables = rule
+        .positive_body()
+        .iter()
+        .flat_map(atom_variables)
+        .collect::<HashSet<_>>();
+
+    rule.positive_body().is_empty()
+        || rule
+            .positive_body()
+            .iter()
+            .any(|atom| atom_variables(atom).is_superset(&body_variables))
+}
+
+impl ChaseProgram {
+    /// Return the most restrictive [`Fragment`] that contains this program.
+    pub fn fragment(&self) -> Fragment {
+        if !self.rules().iter().any(is_existential) {
+            Fragment::Datalog
+        } else if self
+            .rules()
+            .iter()
+            .all(|rule| rule.positive_body().len() <= 1)
+        {
+            Fragment::Linear
+        } else if self.rules().iter().all(is_guarded) {
+            Fragment::Guarded
+        } else {
+            Fragment::Unrestricted
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{io::parser::parse_program, model::chase_model::ChaseProgram};
+
+    use super::Fragment;
+
+    fn fragment(program: &str) -> Fragment {
+        let mut program: ChaseProgram = parse_program(program).unwrap().try_into().unwrap();
+        program.normalize();
+
+        program.fragment()
+    }
+
+    #[test]
+    fn fragments() {
+        assert_eq!(
+            fragment("path(?X, ?Z) :- path(?X, ?Y), edge(?Y, ?Z) ."),
+            Fragment::Datalog
+        );
+        assert_eq!(
+            fragment(
+                "parent(?X, !Y) :- person(?X) .\n\
+                 person(?Y) :- parent(?X, ?Y) .\n\
+                 loop(?X) :- parent(?X, ?X) ."
+            ),
+            Fragment::Linear
+        );
+        assert_eq!(
+            fragment(
+                "works(?X, !P) :- employee(?X) .\n\
+                 employee(?X) :- works(?X, ?P), project(?P), works(?X, ?P) ."
+            ),
+            Fragment::Guarded
+        );
+        assert_eq!(
+            fragment(
+                "works(?X, !P) :- employee(?X) .\n\
+                 colleague(?X, ?Y) :- works(?X, ?P), works(?Y, ?P) ."
+            ),
+            Fragment::Unrestricted
+        );
+    }
+}
@@ -0,0 +1,340 @@
+use std::collections::{HashMap, HashSet};
+
+use nemo_physical::util::TaggedTree;
+use petgraph::{algo::tarjan_scc, Directed, Graph};
+
+use crate::model::{
+    chase_model::{AggregateError, ChaseProgram, ChaseRule},
+    BuiltinFunction, FilterOperation, Identifier, NumericLiteral, Term, TermOperation, Variable,
+};
+
+/// Return whether the given term is the given variable.
+fn is_variable(term: &Term, variable: &Variable) -> bool {
+    matches!(term, Term::Variable(term_variable) if term_variable == variable)
+}
+
+/// Return whether the value of the given term cannot be negative,
+/// given the variables that are known to be non-negative.
+fn is_nonnegative_term(term: &Term, nonnegative: &HashSet<&Variable>) -> bool {
+    match term {
+        Term::Variable(variable) => nonnegative.contains(variable),
+        Term::NumericLiteral(NumericLiteral::Integer(value)) => *value >= 0,
+        Term::NumericLiteral(NumericLiteral::Decimal(whole, _)) => *whole >= 0,
+        Term::NumericLiteral(NumericLiteral::Double(value)) => f64::from(*value) >= 0.0,
+        _ => false,
+    }
+}
+
+/// Return whether the value of the given expression cannot be negative,
+/// given the variables that are known to be non-negative.
+fn is_nonnegative_expression(
+    tree: &TaggedTree<TermOperation>,
+    nonnegative: &HashSet<&Variable>,
+) -> bool {
+    match &tree.tag {
+        TermOperation::Term(term) => is_nonnegative_term(term, nonnegative),
+        TermOperation::Addition | TermOperation::Multiplication => tree
+            .subtrees
+            .iter()
+            .all(|subtree| is_nonnegative_expression(subtree, nonnegative)),
+        TermOperation::Function(identifier)
+            if BuiltinFunction::from_identifier(identifier)
+                .is_some_and(|function| function.is_conversion()) =>
+        {
+            tree.subtrees
+                .iter()
+                .all(|subtree| is_nonnegative_expression(subtree, nonnegative))
+        }
+        _ => false,
+    }
+}
+
+/// Return whether the value of the given expression only increases
+/// if the value of the given variable increases,
+/// given the variables that are known to be non-negative.
+fn is_monotone_expression(
+    tree: &TaggedTree<TermOperation>,
+    variable: &Variable,
+    nonnegative: &HashSet<&Variable>,
+) -> bool {
+    let contains_variable = |subtree: &TaggedTree<TermOperation>| {
+        subtree
+            .leaves()
+            .into_iter()
+            .any(|leaf| matches!(leaf, TermOperation::Term(term) if is_variable(term, variable)))
+    };
+
+    match &tree.tag {
+        TermOperation::Term(_) => true,
+        TermOperation::Addition => tree
+            .subtrees
+            .iter()
+            .all(|subtree| is_monotone_expression(subtree, variable, nonnegative)),
+        TermOperation::Subtraction => tree.subtrees.iter().enumerate().all(|(index, subtree)| {
+            if index == 0 {
+                is_monotone_expression(subtree, variable, nonnegative)
+            } else {
+                !contains_variable(subtree)
+            }
+        }),
+        // A product increases with a factor if all other factors are non-negative
+        TermOperation::Multiplication => {
+            tree.subtrees.iter().enumerate().all(|(index, subtree)| {
+                !contains_variable(subtree)
+                    || (is_monotone_expression(subtree, variable, nonnegative)
+                        && tree.subtrees.iter().enumerate().all(|(other, factor)| {
+                            other == index || is_nonnegative_expression(factor, nonnegative)
+                        }))
+            })
+        }
+        TermOperation::Function(identifier)
+            if matches!(
+                BuiltinFunction::from_identifier(identifier),
+                Some(BuiltinFunction::Maximum | BuiltinFunction::Minimum)
+            ) =>
+        {
+            tree.subtrees
+                .iter()
+                .all(|subtree| is_monotone_expression(subtree, variable, nonnegative))
+        }
+        _ => !tree.subtrees.iter().any(contains_variable),
+    }
+}
+
+/// Check that the given rule uses the given variable,
+/// which is bound to the value of a recursive aggregate, in a monotone way.
+///
+/// Returns a description of the non-monotone use otherwise.
+fn check_monotone_use(
+    rule: &ChaseRule,
+    variable: &Variable,
+    decreasing: bool,
+) -> Result<(), String> {
+    let occurrences = rule
+        .positive_body()
+        .iter()
+        .flat_map(|atom| atom.terms())
+        .filter(|term| is_variable(term, variable))
+        .count();
+    if occurrences > 1 {
+        return Err(format!("variable {variable} is used in a join"));
+    }
+
+    if rule
+        .negative_body()
+        .iter()
+        .flat_map(|atom| atom.terms())
+        .any(|term| is_variable(term, variable))
+    {
+        return Err(format!("variable {variable} is used in a negated atom"));
+    }
+
+    for filter in rule.all_filters() {
+        if filter.lhs != *variable && !is_variable(&filter.rhs, variable) {
+            continue;
+        }
+
+        let monotone_operation = if decreasing {
+            matches!(
+                filter.operation,
+                FilterOperation::LessThan | FilterOperation::LessThanEq
+            )
+        } else {
+            matches!(
+                filter.operation,
+                FilterOperation::GreaterThan | FilterOperation::GreaterThanEq
+            )
+        };
+
+        if filter.lhs != *variable || !filter.rhs.is_ground() || !monotone_operation {
+            return Err(format!(
+                "variable {variable} is used in the comparison {} {:?} {}",
+                filter.lhs, filter.operation, filter.rhs
+            ));
+        }
+    }
+
+    let nonnegative = rule
+        .all_filters()
+        .filter(|filter| {
+            matches!(
+                filter.operation,
+                FilterOperation::GreaterThan | FilterOperation::GreaterThanEq
+            ) && is_nonnegative_term(&filter.rhs, &HashSet::new())
+        })
+        .map(|filter| &filter.lhs)
+        .collect::<HashSet<_>>();
+
+    for (head_variable, tree) in rule.constructors() {
+        if !is_monotone_expression(&tree.0, variable, &nonnegative) {
+            return Err(format!(
+                "variable {variable} is used in a non-monotone expression for {head_variable}"
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+impl ChaseProgram {
+    /// Assign each predicate the index of its strongly connected component
+    /// in the graph of dependencies between the predicates of positive body atoms and head atoms.
+    fn predicate_components(&self) -> HashMap<Identifier, usize> {
+        let mut graph = Graph::<Identifier, (), Directed>::new();
+        let mut nodes = HashMap::new();
+
+        for rule in self.rules() {
+            for atom in rule.all_atoms() {
+                nodes
+                    .entry(atom.predicate())
+                    .or_insert_with(|| graph.add_node(atom.predicate()));
+            }
+
+            for body_atom in rule.positive_body() {
+                for head_atom in rule.head() {
+                    graph.add_edge(
+                        nodes[&body_atom.predicate()],
+                        nodes[&head_atom.predicate()],
+                        (),
+                    );
+                }
+            }
+        }
+
+        tarjan_scc(&graph)
+            .into_iter()
+            .enumerate()
+            .flat_map(|(component, predicates)| {
+                predicates.into_iter().map(move |node| (node, component))
+            })
+            .map(|(node, component)| (graph[node].clone(), component))
+            .collect()
+    }
+
+    /// Check that all aggregates that are part of recursion are monotone
+    /// and that their values are only used in a monotone way within the recursion.
+    ///
+    /// Minima may only decrease and maxima and counts may only increase while the chase proceeds.
+    /// Hence, facts derived from previous values of the aggregate must be subsumed
+    /// by the facts derived from its final value,
+    /// i.e. recursive rules may only compare the value with constants in the right direction
+    /// and use it in sums, as the minuend of differences,
+    /// or in products whose other factors are non-negative constants or variables compared to be non-negative.
+    pub fn check_for_nonmonotone_aggregates(&self) -> Result<(), AggregateError> {
+        let components = self.predicate_components();
+
+        for aggregate_rule in self.rules() {
+            let Some(aggregate) = aggregate_rule.aggregate() else {
+                continue;
+            };
+
+            let predicate = aggregate_rule.head()[0].predicate();
+            let input_predicate = aggregate_rule.positive_body()[0].predicate();
+            let component = components[&predicate];
+
+            if components[&input_predicate] != component {
+                continue;
+            }
+
+            let operation = aggregate.operation();
+            if !operation.is_monotone() {
+                return Err(AggregateError::NonMonotoneOperation(predicate, operation));
+            }
+
+            for rule in self.rules() {
+                if !rule
+                    .head()
+                    .iter()
+                    .any(|atom| components[&atom.predicate()] == component)
+                {
+                    continue;
+                }
+
+                for atom in rule.positive_body() {
+                    if atom.predicate() != predicate {
+                        continue;
+                    }
+
+                    let Term::Variable(variable) = &atom.terms()[aggregate.position()] else {
+                        unreachable!("Body atoms only contain variables after normalization");
+                    };
+
+                    check_monotone_use(rule, variable, operation.is_decreasing()).map_err(
+                        |reason| AggregateError::NonMonotoneUse(predicate.clone(), reason),
+                    )?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        error::Error,
+        io::parser::parse_program,
+        model::chase_model::{AggregateError, ChaseProgram},
+    };
+
+    fn check(program: &str) -> Result<(), Error> {
+        let mut program: ChaseProgram = parse_program(program).unwrap().try_into()?;
+        program.normalize();
+
+        Ok(program.check_for_nonmonotone_aggregates()?)
+    }
+
+    #[test]
+    fn monotone_aggregates() {
+        let shortest_path = "cand(a, 0) .\n\
+             cand(?Y, ?D + ?W) :- dist(?X, ?D), edge(?X, ?Y, ?W) .\n\
+             dist(?Y, #min(?D)) :- cand(?Y, ?D) .\n";
+        assert!(check(shortest_path).is_ok());
+
+        let threshold = "trusted(?X) :- endorsements(?X, ?N), ?N >= 2 .\n\
+             endorsements(?Y, #count(?X)) :- trusted(?X), endorses(?X, ?Y) .\n";
+        assert!(check(threshold).is_ok());
+
+        let stratified = "total(?X, #sum(?V)) :- value(?X, ?V) .\n\
+             small(?X) :- total(?X, ?S), ?S < 10 .\n";
+        assert!(check(stratified).is_ok());
+
+        let product = "cand(?Y, ?D * ?W) :- dist(?X, ?D), edge(?X, ?Y, ?W), ?D >= 0, ?W >= 0.5 .\n\
+             dist(?Y, #max(?D)) :- cand(?Y, ?D) .\n";
+        assert!(check(product).is_ok());
+    }
+
+    #[test]
+    fn nonmonotone_aggregates() {
+        let sum = "cand(?Y, ?D + ?W) :- dist(?X, ?D), edge(?X, ?Y, ?W) .\n\
+             dist(?Y, #sum(?D)) :- cand(?Y, ?D) .\n";
+        assert!(matches!(
+            check(sum),
+            Err(Error::AggregateError(AggregateError::NonMonotoneOperation(
+                ..
+            )))
+        ));
+
+        let comparison = "cand(?Y, ?D) :- dist(?X, ?D), edge(?X, ?Y), ?D > 2 .\n\
+             dist(?Y, #min(?D)) :- cand(?Y, ?D) .\n";
+        assert!(matches!(
+            check(comparison),
+            Err(Error::AggregateError(AggregateError::NonMonotoneUse(..)))
+        ));
+
+        let product = "cand(?Y, ?D * ?W) :- dist(?X, ?D), edge(?X, ?Y, ?W) .\n\
+             dist(?Y, #max(?D)) :- cand(?Y, ?D) .\n";
+        assert!(matches!(
+            check(product),
+            Err(Error::AggregateError(AggregateError::NonMonotoneUse(..)))
+        ));
+
+        let negation = "cand(?Y, ?N) :- count(?X, ?N), edge(?X, ?Y), ~blocked(?N) .\n\
+             count(?Y, #count(?X)) :- cand(?Y, ?X) .\n";
+        assert!(matches!(
+            check(negation),
+            Err(Error::AggregateError(AggregateError::NonMonotoneUse(..)))
+        ));
+    }
+}
@@ -381,7 +381,9 @@ impl VariableOrderBuilder<'_> {
 
     fn generate_variable_order_for_rule(&mut self, rule: &ChaseRule) -> VariableOrder {
         let mut variable_order: VariableOrder = VariableOrder::new();
-        let mut remaining_vars = {
+        // Anonymous variables occur only once and are never used,
+        // so they are put last in order to be projected away right after the join
+        let (anonymous_vars, mut remaining_vars) = {
             let remaining_vars_unpermutated: Vec<Variable> = rule
                 .positive_body()
                 .iter()
@@ -398,7 +400,7 @@ impl VariableOrderBuilder<'_> {
                 .get_permutator(remaining_vars_unpermutated.len())
                 .permute(&remaining_vars_unpermutated)
                 .expect("we are checking the length so everything should work out")
-                .collect::<Vec<_>>()
+                .partition::<Vec<_>, _>(Variable::is_anonymous)
         };
 
         while !remaining_vars.is_empty() {
@@ -431,6 +433,11 @@ impl VariableOrderBuilder<'_> {
             self.update_trie_column_orders(&variable_order, HashSet::from([next_var]), rule);
         }
 
+        for var in anonymous_vars {
+            variable_order.push(var.clone());
+            self.update_trie_column_orders(&variable_order, HashSet::from([var]), rule);
+        }
+
         variable_order
     }
 
@@ -1,22 +1,46 @@
 //! Managing of tables
 
-use super::model::{Identifier, PrimitiveType};
+use super::model::{
+    types::primitive_logical_value::{PrimitiveLogicalValueIteratorT, PrimitiveLogicalValueT},
+    Identifier, PrimitiveType, Variable,
+};
 
 use bytesize::ByteSize;
 use nemo_physical::{
-    datatypes::data_value::DataValueIteratorT,
+    datatypes::{data_value::DataValueIteratorT, DataValueT},
     management::{
         database::{ColumnOrder, Dict, TableId, TableSource},
         execution_plan::ExecutionNodeRef,
-        DatabaseInstance, ExecutionPlan,
+        ByteSized, DatabaseInstance, ExecutionPlan, MemoryReport,
+    },
+    tabular::{
+        operations::{triescan_select::SelectEqualClasses, ValueAssignment},
+        table_types::trie::Trie,
+        traits::table_schema::TableSchema,
     },
-    tabular::{table_types::trie::Trie, traits::table_schema::TableSchema},
     util::mapping::permutation::Permutation,
 };
 
 use crate::error::Error;
 
-use std::{cell::Ref, cmp::Ordering, collections::HashMap, hash::Hash, ops::Range};
+mod key_filter;
+pub(crate) use key_filter::KeyFilter;
+
+mod statistics;
+pub(crate) use statistics::TableStatistics;
+
+use std::{
+    cell::{Ref, RefCell},
+    cmp::Ordering,
+    collections::HashMap,
+    hash::Hash,
+    ops::Range,
+    rc::Rc,
+    sync::Arc,
+};
+
+/// Step with which the input facts of a predicate are associated.
+const EDB_STEP: usize = 0;
 
 /// Indicates that the table contains the union of successive tables.
 /// For example assume that for predicate p there were tables derived in steps 2, 4, 7, 10, 11.
@@ -51,7 +75,7 @@ impl PartialOrd for SubtableRange {
     }
 }
 
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone)]
 struct SubtableHandler {
     single: Vec<(usize, TableId)>,
     combined: Vec<(SubtableRange, TableId)>,
@@ -100,9 +124,19 @@ impl SubtableHandler {
         self.single_steps().last().copied()
     }
 
+    /// Return the tables computed in a single step within the given range of steps.
+    pub fn single_tables_in_range(&self, range: &Range<usize>) -> Vec<TableId> {
+        let normalized_range = self.normalize_range(range);
+
+        self.single[normalized_range.start..normalized_range.start + normalized_range.len]
+            .iter()
+            .map(|(_, id)| *id)
+            .collect()
+    }
+
     pub fn subtable(&self, step: usize) -> Option<TableId> {
-        let postion = *self.single_steps().find(|&&s| s == step)?;
-        Some(self.single[postion].1)
+        let position = self.single_steps().position(|&s| s == step)?;
+        Some(self.single[position].1)
     }
 
     /// TODO: Currently only counting of in-memory facts is supported, see <https://github.com/knowsys/nemo/issues/335>
@@ -194,11 +228,36 @@ impl SubtableHandler {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 struct PredicateInfo {
     schema: TableSchema,
 }
 
+/// Information about a single table that is stored under some predicate.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SubtableInfo {
+    /// Name of the table in the database.
+    pub name: String,
+    /// First step of the chase whose results are contained in this table.
+    pub first_step: usize,
+    /// Last step of the chase whose results are contained in this table.
+    /// For tables that were computed in a single step, this is the same as `first_step`.
+    pub last_step: usize,
+    /// Number of rows contained in this table.
+    ///
+    /// As for [`TableManager::predicate_count_rows`], only in-memory facts are counted.
+    pub rows: usize,
+    /// Memory consumed by this table.
+    pub memory: ByteSize,
+}
+
+impl SubtableInfo {
+    /// Return whether this table is the union of tables computed in several steps.
+    pub fn is_combined(&self) -> bool {
+        self.first_step != self.last_step
+    }
+}
+
 /// Identifier of a subtable in a chase sequence.
 #[derive(Debug, Clone)]
 pub struct SubtableIdentifier {
@@ -213,6 +272,28 @@ impl SubtableIdentifier {
     }
 }
 
+/// Table containing the matches of a rule body computed during a rule application,
+/// see [`SubtableExecutionPlan::add_bindings_table`].
+#[derive(Debug, Clone)]
+pub struct RuleBindings {
+    /// Id of the table, which is not associated with any predicate
+    pub table: TableId,
+    /// The variables corresponding to the columns of the table
+    pub variables: Vec<Variable>,
+}
+
+/// Results of executing a [`SubtableExecutionPlan`], see [`TableManager::execute_plan_with_results`].
+#[derive(Debug, Default)]
+pub struct PlanResults {
+    /// Predicates for which new facts were derived
+    pub updated_predicates: Vec<Identifier>,
+    /// Table of the matches of the rule body, if it was recorded and is not empty
+    pub bindings: Option<RuleBindings>,
+    /// Number of facts derived for each predicate before removing the facts that were already known,
+    /// if counted (see [`SubtableExecutionPlan::counting_derived_facts`])
+    pub derived_facts: HashMap<Identifier, usize>,
+}
+
 /// A execution plan that will result in the creation of new chase subtables.
 #[derive(Debug, Default)]
 pub struct SubtableExecutionPlan {
@@ -221,9 +302,91 @@ pub struct SubtableExecutionPlan {
     /// Each tree in the plan that will result in a new permanent table
     /// will have an associated [`SubtableIdentifier`].
     map_subtrees: HashMap<usize, SubtableIdentifier>,
+    /// Whether the bindings of the rule body should be kept, see [`RuleBindings`]
+    record_bindings: bool,
+    /// The tree in the plan that results in the table of body bindings, if any,
+    /// together with the variables corresponding to its columns
+    bindings: Option<(usize, Vec<Variable>)>,
+    /// Whether the number of derived facts should be counted before removing duplicates
+    count_derived_facts: bool,
+    /// The trees in the plan that result in the tables of all derived facts,
+    /// together with their predicates
+    derived_facts: Vec<(usize, Identifier)>,
+    /// Whether the derived facts are stored without removing the facts that were already known
+    keep_duplicates: bool,
 }
 
 impl SubtableExecutionPlan {
+    /// Create a new [`SubtableExecutionPlan`] that keeps the bindings of the rule body.
+    pub fn recording_bindings() -> Self {
+        Self {
+            record_bindings: true,
+            ..Default::default()
+        }
+    }
+
+    /// Return whether the bindings of the rule body should be kept.
+    pub fn records_bindings(&self) -> bool {
+        self.record_bindings
+    }
+
+    /// Count the facts derived for each predicate before removing the facts that were already known,
+    /// e.g. to find out how many duplicates were suppressed.
+    pub fn counting_derived_facts(mut self) -> Self {
+        self.count_derived_facts = true;
+        self
+    }
+
+    /// Return whether the facts derived for each predicate should be counted before removing duplicates.
+    pub fn counts_derived_facts(&self) -> bool {
+        self.count_derived_facts
+    }
+
+    /// Store the derived facts without removing the facts that were already known,
+    /// which saves the time of comparing them to the existing tables.
+    pub fn keeping_duplicates(mut self) -> Self {
+        self.keep_duplicates = true;
+        self
+    }
+
+    /// Return whether the facts that were already known should be removed from the derived facts.
+    pub fn eliminates_duplicates(&self) -> bool {
+        !self.keep_duplicates
+    }
+
+    /// Add a table containing all facts derived for the given predicate to the plan,
+    /// whose number of rows is returned by [`TableManager::execute_plan_with_results`].
+    pub fn add_derived_facts_table(
+        &mut self,
+        node: ExecutionNodeRef,
+        tree_name: &str,
+        predicate: Identifier,
+    ) -> usize {
+        let node_id = self
+            .execution_plan
+            .write_permanent(node, tree_name, "Derived Facts");
+        self.derived_facts.push((node_id, predicate));
+
+        node_id
+    }
+
+    /// Add a table containing the bindings of the rule body to the plan,
+    /// which is returned by [`TableManager::execute_plan_with_results`].
+    /// The columns of the table correspond to the given variables.
+    pub fn add_bindings_table(
+        &mut self,
+        node: ExecutionNodeRef,
+        tree_name: &str,
+        variables: Vec<Variable>,
+    ) -> usize {
+        let node_id = self
+            .execution_plan
+            .write_permanent(node, tree_name, "Rule Bindings");
+        self.bindings = Some((node_id, variables));
+
+        node_id
+    }
+
     /// Add a temporary table to the plan.
     pub fn add_temporary_table(&mut self, node: ExecutionNodeRef, tree_name: &str) -> usize {
         self.execution_plan.write_temporary(node, tree_name)
@@ -241,6 +404,18 @@ impl SubtableExecutionPlan {
             .write_temporary_cut(node, tree_name, cut)
     }
 
+    /// Add a temporary table to the plan, of which only the first row is computed.
+    /// The parameter `cut` indicates how many of the last layers will not be needed.
+    pub fn add_temporary_table_first_match(
+        &mut self,
+        node: ExecutionNodeRef,
+        tree_name: &str,
+        cut: usize,
+    ) -> usize {
+        self.execution_plan
+            .write_temporary_first_match(node, tree_name, cut)
+    }
+
     /// Add a permanent table ot the plan-
     pub fn add_permanent_table(
         &mut self,
@@ -343,6 +518,14 @@ pub struct TableManager {
 
     /// Mapping predicate identifiers to a [`PredicateInfo`] which contains relevant information.
     predicate_to_info: HashMap<Identifier, PredicateInfo>,
+
+    /// [`KeyFilter`] of each table computed in a single step, if key filters are enabled.
+    /// Filters are computed once the table is available in memory.
+    key_filters: Option<HashMap<TableId, KeyFilter>>,
+
+    /// [`TableStatistics`] of each table computed in a single step,
+    /// which are computed when first requested.
+    statistics: HashMap<TableId, TableStatistics>,
 }
 
 impl Default for TableManager {
@@ -354,11 +537,107 @@ impl Default for TableManager {
 impl TableManager {
     /// Create new [`TableManager`].
     pub fn new() -> Self {
+        Self::with_dictionary(Rc::new(RefCell::new(Dict::default())))
+    }
+
+    /// Create new [`TableManager`] whose tables are encoded using the given (possibly shared) dictionary.
+    pub fn with_dictionary(dict: Rc<RefCell<Dict>>) -> Self {
         Self {
-            database: DatabaseInstance::new(),
+            database: DatabaseInstance::with_dictionary(dict),
             predicate_subtables: HashMap::new(),
             predicate_to_info: HashMap::new(),
+            key_filters: None,
+            statistics: HashMap::new(),
+        }
+    }
+
+    /// Create a copy of this manager that shares all of its tables and its dictionary,
+    /// see [`DatabaseInstance::snapshot`].
+    pub fn snapshot(&mut self) -> Result<Self, Error> {
+        Ok(Self {
+            database: self.database.snapshot()?,
+            predicate_subtables: self.predicate_subtables.clone(),
+            predicate_to_info: self.predicate_to_info.clone(),
+            key_filters: self.key_filters.clone(),
+            statistics: self.statistics.clone(),
+        })
+    }
+
+    /// Maintain a [`KeyFilter`] for the first column of each table,
+    /// see [`TableManager::key_filter`].
+    pub fn enable_key_filters(&mut self) {
+        self.key_filters.get_or_insert_with(HashMap::new);
+    }
+
+    /// Return whether key filters are maintained, see [`TableManager::enable_key_filters`].
+    pub fn uses_key_filters(&self) -> bool {
+        self.key_filters.is_some()
+    }
+
+    /// Maintain an inverted index of the words of all strings in the dictionary,
+    /// see [`DatabaseInstance::enable_text_index`].
+    pub fn enable_text_index(&mut self) {
+        self.database.enable_text_index();
+    }
+
+    /// Return whether an inverted index of the words of all strings is maintained,
+    /// see [`TableManager::enable_text_index`].
+    pub fn uses_text_index(&self) -> bool {
+        self.database.uses_text_index()
+    }
+
+    /// Return a [`KeyFilter`] for the values in the first column
+    /// of all subtables of a predicate within a certain range of steps.
+    /// Returns `None` if key filters are disabled
+    /// or if some of the subtables are not available in memory.
+    pub(crate) fn key_filter(
+        &mut self,
+        predicate: &Identifier,
+        range: &Range<usize>,
+    ) -> Option<KeyFilter> {
+        let key_filters = self.key_filters.as_mut()?;
+        let tables = self
+            .predicate_subtables
+            .get(predicate)?
+            .single_tables_in_range(range);
+
+        let mut result: Option<KeyFilter> = None;
+        for id in tables {
+            let filter = match key_filters.get(&id) {
+                Some(filter) => filter,
+                None => {
+                    let trie = self
+                        .database
+                        .get_trie_if_loaded(id, &ColumnOrder::default())?;
+                    key_filters.entry(id).or_insert(KeyFilter::from_trie(trie)?)
+                }
+            };
+
+            result = Some(match result {
+                Some(current) => current.union(filter)?,
+                None => filter.clone(),
+            });
         }
+
+        result
+    }
+
+    /// Return whether all subtables of a predicate are available in memory,
+    /// in which case all of their values occur in the dictionary.
+    /// Returns `false` if the predicate does not exist.
+    pub(crate) fn is_in_memory(&self, predicate: &Identifier) -> bool {
+        self.predicate_subtables
+            .get(predicate)
+            .is_some_and(|handler| {
+                handler
+                    .single_tables_in_range(&(0..usize::MAX))
+                    .into_iter()
+                    .all(|id| {
+                        self.database
+                            .get_trie_if_loaded(id, &ColumnOrder::default())
+                            .is_some()
+                    })
+            })
     }
 
     /// Return the [`TableId`] that is associated with a given subtable.
@@ -384,6 +663,52 @@ impl TableManager {
             .map(|s| s.count_rows(&self.database))
     }
 
+    /// Count the rows of the subtable containing the input facts of a predicate.
+    /// Returns `None` if the predicate has no input facts.
+    ///
+    /// As for [`TableManager::predicate_count_rows`], only in-memory facts are counted.
+    pub fn input_count_rows(&self, predicate: &Identifier) -> Option<usize> {
+        self.subtable_count_rows(predicate.clone(), EDB_STEP)
+    }
+
+    /// Count the rows of the subtable that was derived for a predicate in the given step.
+    /// Returns `None` if there is no such subtable.
+    ///
+    /// As for [`TableManager::predicate_count_rows`], only in-memory facts are counted.
+    pub fn subtable_count_rows(&self, predicate: Identifier, step: usize) -> Option<usize> {
+        let id = self.table_id(&SubtableIdentifier::new(predicate, step))?;
+        Some(self.database.count_rows(&id))
+    }
+
+    /// Return information about all the subtables of a predicate,
+    /// sorted by the steps they cover.
+    /// Returns `None` if the predicate does not exist.
+    pub fn subtables(&self, predicate: &Identifier) -> Option<Vec<SubtableInfo>> {
+        let handler = self.predicate_subtables.get(predicate)?;
+
+        let info = |first_step: usize, last_step: usize, id: TableId| SubtableInfo {
+            name: self.database.table_name(id).to_string(),
+            first_step,
+            last_step,
+            rows: self.database.count_rows(&id),
+            memory: self.database.memory_consumption(id),
+        };
+
+        let mut result: Vec<SubtableInfo> = handler
+            .single
+            .iter()
+            .map(|(step, id)| info(*step, *step, *id))
+            .chain(handler.combined.iter().map(|(range, id)| {
+                let (start, end) = range.start_end();
+                info(handler.single[start].0, handler.single[end].0, *id)
+            }))
+            .collect();
+
+        result.sort_by_key(|subtable| (subtable.first_step, subtable.last_step));
+
+        Some(result)
+    }
+
     /// Get a list of column iterators for the full table (i.e. the expanded trie)
     pub fn table_column_iters(&mut self, id: TableId) -> Result<Vec<DataValueIteratorT>, Error> {
         Ok(self.database.get_table_column_iterators(id)?)
@@ -467,7 +792,6 @@ impl TableManager {
     /// Predicate must be registered before calling this function.
     pub fn add_edb(&mut self, predicate: Identifier, sources: Vec<TableSource>) {
         let edb_order = ColumnOrder::default();
-        const EDB_STEP: usize = 0;
 
         let schema = self
             .predicate_to_info
@@ -544,6 +868,40 @@ impl TableManager {
             .unwrap_or_default()
     }
 
+    /// Return the [`TableStatistics`] of all subtables of a predicate within a certain range of steps,
+    /// loading the subtables into memory if necessary.
+    /// Returns `None` if there are no such subtables.
+    pub(crate) fn statistics(
+        &mut self,
+        predicate: &Identifier,
+        range: &Range<usize>,
+    ) -> Result<Option<TableStatistics>, Error> {
+        let tables = self
+            .predicate_subtables
+            .get(predicate)
+            .map(|handler| handler.single_tables_in_range(range))
+            .unwrap_or_default();
+
+        let mut result: Option<TableStatistics> = None;
+        for id in tables {
+            if !self.statistics.contains_key(&id) {
+                let trie = self
+                    .database
+                    .get_shared_trie_or_load(id, &ColumnOrder::default())?;
+                self.statistics
+                    .insert(id, TableStatistics::from_trie(&trie));
+            }
+            let statistics = &self.statistics[&id];
+
+            result = Some(match result {
+                Some(current) => current.union(statistics),
+                None => statistics.clone(),
+            });
+        }
+
+        Ok(result)
+    }
+
     /// Combine subtables in a certain range into one larger table.
     pub fn combine_tables(
         &mut self,
@@ -586,17 +944,402 @@ impl TableManager {
         &mut self,
         subtable_plan: SubtableExecutionPlan,
     ) -> Result<Vec<Identifier>, Error> {
+        let results = self.execute_plan_with_results(subtable_plan)?;
+        if let Some(bindings) = results.bindings {
+            self.delete_table(bindings.table);
+        }
+
+        Ok(results.updated_predicates)
+    }
+
+    /// Execute a plan and add the results as subtables to the manager.
+    ///
+    /// If the plan contains a table of body bindings (see [`SubtableExecutionPlan::add_bindings_table`])
+    /// that is not empty, it is returned as well.
+    /// It is not associated with any predicate and should be deleted with [`TableManager::delete_table`].
+    /// Tables of derived facts (see [`SubtableExecutionPlan::add_derived_facts_table`])
+    /// are only counted and deleted right away.
+    pub fn execute_plan_with_results(
+        &mut self,
+        subtable_plan: SubtableExecutionPlan,
+    ) -> Result<PlanResults, Error> {
         let result = self.database.execute_plan(subtable_plan.execution_plan)?;
 
-        let mut updated_predicates = Vec::new();
+        let mut results = PlanResults::default();
         for (plan_id, table_id) in result {
+            if let Some((bindings_id, variables)) = &subtable_plan.bindings {
+                if *bindings_id == plan_id {
+                    results.bindings = Some(RuleBindings {
+                        table: table_id,
+                        variables: variables.clone(),
+                    });
+                    continue;
+                }
+            }
+
+            if let Some((_, predicate)) = subtable_plan
+                .derived_facts
+                .iter()
+                .find(|(derived_id, _)| *derived_id == plan_id)
+            {
+                *results.derived_facts.entry(predicate.clone()).or_default() +=
+                    self.database.count_rows(&table_id);
+                self.delete_table(table_id);
+                continue;
+            }
+
             let subtable = subtable_plan.map_subtrees.get(&plan_id).unwrap();
-            updated_predicates.push(subtable.predicate.clone());
+            results.updated_predicates.push(subtable.predicate.clone());
 
             self.add_subtable(subtable.clone(), table_id);
         }
 
-        Ok(updated_predicates)
+        Ok(results)
+    }
+
+    /// Compute the rows of a table that satisfy the given restrictions.
+    /// The result is stored in a new table that is not associated with any predicate.
+    /// Returns `None` if the result is empty.
+    pub fn select_rows(
+        &mut self,
+        id: TableId,
+        equal_classes: SelectEqualClasses,
+        assignments: HashMap<usize, ValueAssignment>,
+    ) -> Result<Option<TableId>, Error> {
+        let mut plan = ExecutionPlan::default();
+
+        let node_table = plan.fetch_existing(id);
+        let node_select_value = plan.select_value(node_table, assignments);
+        let node_select_equal = plan.select_equal(node_select_value, equal_classes);
+        let out_id = plan.write_permanent(node_select_equal, "Select Rows", "Selected Rows");
+
+        let result = self.database.execute_plan(plan)?;
+
+        Ok(result.get(&out_id).copied())
+    }
+
+    /// Compute the rows of a table that are not contained in another table with the same schema.
+    /// The result is stored in a new table that is not associated with any predicate.
+    /// Returns `None` if the result is empty.
+    pub fn subtract_table(
+        &mut self,
+        id: TableId,
+        subtracted_id: TableId,
+    ) -> Result<Option<TableId>, Error> {
+        let mut plan = ExecutionPlan::default();
+
+        let node_table = plan.fetch_existing(id);
+        let node_subtracted = plan.fetch_existing(subtracted_id);
+        let node_minus = plan.minus(node_table, node_subtracted);
+        let out_id = plan.write_permanent(node_minus, "Subtract Table", "Subtracted Rows");
+
+        let result = self.database.execute_plan(plan)?;
+
+        Ok(result.get(&out_id).copied())
+    }
+
+    /// Add a table with the schema of the given predicate that is loaded from the given sources,
+    /// but is not associated with the predicate.
+    /// Predicate must be registered before calling this function.
+    pub fn add_unassociated_table(
+        &mut self,
+        predicate: &Identifier,
+        name: &str,
+        sources: Vec<TableSource>,
+    ) -> TableId {
+        let order = ColumnOrder::default();
+
+        let schema = self
+            .predicate_to_info
+            .get(predicate)
+            .expect("Predicate should be registered before calling this function")
+            .schema
+            .clone();
+        let name = format!("{} ({name}) {order:?}", predicate.name());
+
+        let table_id = self.database.register_table(&name, schema);
+        self.database.add_sources(table_id, order, sources);
+
+        table_id
+    }
+
+    /// Count the number of rows of a table that is not associated with any predicate,
+    /// e.g. one that was created by [`TableManager::select_rows`].
+    pub fn table_count_rows(&self, id: TableId) -> usize {
+        self.database.count_rows(&id)
+    }
+
+    /// Delete a table that is not associated with any predicate,
+    /// e.g. one that was created by [`TableManager::select_rows`].
+    pub fn delete_table(&mut self, id: TableId) {
+        self.database.delete(id);
+    }
+
+    /// Return the rows of the given table as logical values of the given types.
+    pub fn table_rows(
+        &mut self,
+        id: TableId,
+        types: &[PrimitiveType],
+    ) -> Result<Vec<Vec<PrimitiveLogicalValueT>>, Error> {
+        self.table_rows_limited(id, types, usize::MAX)
+    }
+
+    /// Return at most `limit` rows of the given table as logical values of the given types.
+    pub fn table_rows_limited(
+        &mut self,
+        id: TableId,
+        types: &[PrimitiveType],
+        limit: usize,
+    ) -> Result<Vec<Vec<PrimitiveLogicalValueT>>, Error> {
+        let mut iterators: Vec<PrimitiveLogicalValueIteratorT> = self
+            .table_column_iters(id)?
+            .into_iter()
+            .zip(types.iter())
+            .map(|(iter, lt)| lt.primitive_logical_value_iterator(iter))
+            .collect();
+
+        let mut rows = Vec::new();
+        while rows.len() < limit {
+            let row: Vec<PrimitiveLogicalValueT> = iterators
+                .iter_mut()
+                .filter_map(|iter| iter.next())
+                .collect();
+            if row.is_empty() {
+                break;
+            }
+
+            rows.push(row);
+        }
+
+        Ok(rows)
+    }
+
+    /// Return the rows of the subtable that was derived for a predicate in the given step
+    /// as logical values of the given types.
+    /// Returns `None` if there is no such subtable.
+    pub fn subtable_rows(
+        &mut self,
+        predicate: Identifier,
+        step: usize,
+        types: &[PrimitiveType],
+    ) -> Result<Option<Vec<Vec<PrimitiveLogicalValueT>>>, Error> {
+        let Some(id) = self.table_id(&SubtableIdentifier::new(predicate, step)) else {
+            return Ok(None);
+        };
+
+        self.table_rows(id, types).map(Some)
+    }
+
+    /// Return the rows of the subtable that was derived for a predicate in the given step.
+    /// Returns `None` if there is no such subtable.
+    pub fn subtable_values(
+        &mut self,
+        predicate: Identifier,
+        step: usize,
+    ) -> Result<Option<Vec<Vec<DataValueT>>>, Error> {
+        let Some(id) = self.table_id(&SubtableIdentifier::new(predicate, step)) else {
+            return Ok(None);
+        };
+
+        Ok(Some(self.database.table_values(id)?.collect()))
+    }
+
+    /// Return the [`TableId`] of the table containing the facts a predicate was initialized with.
+    /// Returns `None` if the predicate has no input facts.
+    pub fn input_table(&self, predicate: Identifier) -> Option<TableId> {
+        self.table_id(&SubtableIdentifier::new(predicate, EDB_STEP))
+    }
+
+    /// Return the facts a predicate was initialized with,
+    /// i.e. the facts that were not derived in some step of the chase.
+    pub fn input_values(&mut self, predicate: Identifier) -> Result<Vec<Vec<DataValueT>>, Error> {
+        Ok(self
+            .subtable_values(predicate, EDB_STEP)?
+            .unwrap_or_default())
+    }
+
+    /// Replace the facts a predicate was initialized with by the given rows.
+    ///
+    /// This requires that the predicate has no derived subtables,
+    /// see [`TableManager::remove_derived_tables`].
+    pub fn replace_input_values(
+        &mut self,
+        predicate: Identifier,
+        rows: Vec<Vec<DataValueT>>,
+    ) -> Result<(), Error> {
+        self.replace_predicate(predicate, EDB_STEP, rows)
+    }
+
+    /// Add the rows of the given [`Trie`] to the facts a predicate was initialized with.
+    ///
+    /// This requires that the predicate has no derived subtables,
+    /// see [`TableManager::remove_derived_tables`].
+    pub fn add_input_trie(&mut self, predicate: Identifier, trie: Trie) -> Result<(), Error> {
+        let order = ColumnOrder::default();
+        let schema = self
+            .predicate_to_info
+            .get(&predicate)
+            .expect("Predicate should be registered before calling this function")
+            .schema
+            .clone();
+        let name = self.generate_table_name(predicate.clone(), &order, EDB_STEP);
+
+        let added_id =
+            self.database
+                .register_add_trie(&format!("{name} Added"), schema, order, trie);
+
+        let Some(input_id) = self.input_table(predicate.clone()) else {
+            self.add_subtable(SubtableIdentifier::new(predicate, EDB_STEP), added_id);
+            return Ok(());
+        };
+
+        let mut union_plan = ExecutionPlan::default();
+        let fetch_nodes = vec![
+            union_plan.fetch_existing(input_id),
+            union_plan.fetch_existing(added_id),
+        ];
+        let union_node = union_plan.union(fetch_nodes);
+        let plan_id = union_plan.write_permanent(union_node, "Adding Input Facts", &name);
+
+        let execution_result = self.database.execute_plan(union_plan)?;
+        let union_id = *execution_result
+            .get(&plan_id)
+            .expect("The union of a non-empty table should be non-empty.");
+
+        self.predicate_subtables
+            .get_mut(&predicate)
+            .expect("Predicate should be registered before calling this function")
+            .single = vec![(EDB_STEP, union_id)];
+
+        self.delete_subtable(input_id);
+        self.delete_table(added_id);
+
+        Ok(())
+    }
+
+    /// Replace all subtables of a predicate by a single subtable containing the given rows,
+    /// which is associated with the given step.
+    ///
+    /// Unlike the other functions of the manager, this does not preserve previously derived facts
+    /// and is only used for predicates whose facts may be retracted, e.g. aggregates in recursion.
+    pub fn replace_predicate(
+        &mut self,
+        predicate: Identifier,
+        step: usize,
+        rows: Vec<Vec<DataValueT>>,
+    ) -> Result<(), Error> {
+        let handler = std::mem::take(
+            self.predicate_subtables
+                .get_mut(&predicate)
+                .expect("Predicate should be registered before calling this function"),
+        );
+
+        let single_ids = handler.single.into_iter().map(|(_, id)| id);
+        let combined_ids = handler.combined.into_iter().map(|(_, id)| id);
+        for id in single_ids.chain(combined_ids) {
+            self.delete_subtable(id);
+        }
+
+        if rows.is_empty() {
+            return Ok(());
+        }
+
+        let order = ColumnOrder::default();
+        let schema = self
+            .predicate_to_info
+            .get(&predicate)
+            .expect("Predicate should be registered before calling this function")
+            .schema
+            .clone();
+        let name = self.generate_table_name(predicate.clone(), &order, step);
+
+        let table_id = self.database.register_table(&name, schema);
+        self.database
+            .add_sources(table_id, order.clone(), vec![TableSource::RLS(rows)]);
+        self.database.get_trie_or_load(table_id, &order)?;
+
+        self.add_subtable(SubtableIdentifier::new(predicate, step), table_id);
+
+        Ok(())
+    }
+
+    /// Add the facts loaded from the given sources to a predicate as a subtable for the given step,
+    /// leaving out the facts that the predicate already contains.
+    /// Predicate must be registered before calling this function.
+    ///
+    /// Returns `false` if there were no new facts, in which case no subtable is added.
+    pub fn add_new_facts(
+        &mut self,
+        predicate: Identifier,
+        step: usize,
+        sources: Vec<TableSource>,
+    ) -> Result<bool, Error> {
+        let order = ColumnOrder::default();
+        let name = self.generate_table_name(predicate.clone(), &order, step);
+
+        let loaded_id = self.add_unassociated_table(&predicate, "New Facts", sources);
+        self.database.get_trie_or_load(loaded_id, &order)?;
+
+        let new_id = if self.database.count_rows(&loaded_id) == 0 {
+            None
+        } else {
+            match self.combine_predicate(predicate.clone())? {
+                Some(existing_id) => {
+                    let mut plan = ExecutionPlan::default();
+                    let node_loaded = plan.fetch_existing(loaded_id);
+                    let node_existing = plan.fetch_existing(existing_id);
+                    let node_minus = plan.minus(node_loaded, node_existing);
+                    let out_id = plan.write_permanent(node_minus, "Adding New Facts", &name);
+
+                    self.database.execute_plan(plan)?.get(&out_id).copied()
+                }
+                None => Some(loaded_id),
+            }
+        };
+
+        if new_id != Some(loaded_id) {
+            self.delete_table(loaded_id);
+        }
+
+        let Some(new_id) = new_id else {
+            return Ok(false);
+        };
+
+        self.add_subtable(SubtableIdentifier::new(predicate, step), new_id);
+
+        Ok(true)
+    }
+
+    /// Delete all subtables that were derived in some step of the chase,
+    /// such that every predicate only contains the facts it was initialized with.
+    pub fn remove_derived_tables(&mut self) {
+        let mut derived_ids = Vec::new();
+        for handler in self.predicate_subtables.values_mut() {
+            handler.single.retain(|&(step, id)| {
+                let is_derived = step != EDB_STEP;
+                if is_derived {
+                    derived_ids.push(id);
+                }
+
+                !is_derived
+            });
+            derived_ids.extend(handler.combined.drain(..).map(|(_, id)| id));
+        }
+
+        for id in derived_ids {
+            self.delete_subtable(id);
+        }
+    }
+
+    /// Delete a table that was associated with some predicate
+    /// together with the information computed for it.
+    fn delete_subtable(&mut self, id: TableId) {
+        self.database.delete(id);
+        self.statistics.remove(&id);
+
+        if let Some(key_filters) = &mut self.key_filters {
+            key_filters.remove(&id);
+        }
     }
 
     /// Returns a reference to the constants dictionary
@@ -604,6 +1347,44 @@ impl TableManager {
         self.database.get_dict_constants()
     }
 
+    /// Returns a shared handle to the constants dictionary
+    pub fn shared_dict(&self) -> Rc<RefCell<Dict>> {
+        self.database.shared_dict_constants()
+    }
+
+    /// Return the lowest null value that has not been used yet.
+    pub fn next_null(&self) -> u64 {
+        self.database.next_null()
+    }
+
+    /// Make sure that newly created nulls are at least as large as the given value.
+    pub fn reserve_nulls(&mut self, next_null: u64) {
+        self.database.reserve_nulls(next_null)
+    }
+
+    /// Combine all subtables of a predicate into one table
+    /// and return a handle to its [`Trie`], which can be shared with other table managers
+    /// using the same dictionary.
+    /// Returns `None` if the predicate has no subtables.
+    pub fn shared_predicate_trie(
+        &mut self,
+        predicate: Identifier,
+    ) -> Result<Option<Arc<Trie>>, Error> {
+        let Some(table_id) = self.combine_predicate(predicate)? else {
+            return Ok(None);
+        };
+
+        Ok(Some(self.database.get_shared_trie_or_load(
+            table_id,
+            &ColumnOrder::default(),
+        )?))
+    }
+
+    /// Return the memory currently consumed by all tables.
+    pub fn memory_consumption(&self) -> ByteSize {
+        self.database.size_bytes()
+    }
+
     /// Return the current [`MemoryUsage`].
     pub fn memory_usage(&self) -> MemoryUsage {
         let mut result = MemoryUsage::new_block("Chase");
@@ -628,6 +1409,39 @@ impl TableManager {
 
         result
     }
+
+    /// Return a [`MemoryReport`] of the tables of each predicate and of the dictionary.
+    pub fn memory_report(&self) -> MemoryReport {
+        let mut result = MemoryReport::new_block("Chase");
+
+        let mut tables = MemoryReport::new_block("Tables");
+        for (identifier, subtable_handler) in &self.predicate_subtables {
+            let mut predicate_report = MemoryReport::new_block(&identifier.to_string());
+
+            for (step, id) in &subtable_handler.single {
+                predicate_report.add_child(MemoryReport::new(
+                    &format!("Step {}", step),
+                    self.database.memory_footprint(*id),
+                ));
+            }
+            for (steps, id) in &subtable_handler.combined {
+                predicate_report.add_child(MemoryReport::new(
+                    &format!("Steps {}-{}", steps.start, steps.start + steps.len),
+                    self.database.memory_footprint(*id),
+                ));
+            }
+
+            tables.add_child(predicate_report);
+        }
+
+        result.add_child(tables);
+        result.add_child(MemoryReport::new(
+            "Dictionary",
+            self.database.dictionary_footprint(),
+        ));
+
+        result
+    }
 }
 
 #[cfg(test)]
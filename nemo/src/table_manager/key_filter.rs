@@ -0,0 +1,190 @@
+//! Approximate sets of the values in the first column of a table
+
+use nemo_physical::{
+    columnar::traits::column::Column,
+    datatypes::{RandomBits, StorageValueT},
+    tabular::table_types::trie::Trie,
+};
+
+/// Kinds of values whose keys can be compared with each other.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum KeyKind {
+    /// Values of type [`u32`], [`u64`] or [`u128`]
+    Unsigned,
+    /// Values of type [`i64`]
+    Signed,
+    /// Floating point values
+    Float,
+}
+
+/// Approximate set of the values in the first column of a table,
+/// implemented as a Bloom filter with a single hash function.
+///
+/// Filters of tables without common values might not be recognized as disjoint,
+/// but filters of tables with common values are never considered disjoint.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct KeyFilter {
+    kind: KeyKind,
+    /// Bits of the filter, whose number is a power of two
+    words: Vec<u64>,
+}
+
+impl KeyFilter {
+    /// Number of bits reserved for each value
+    const BITS_PER_KEY: usize = 8;
+    /// Maximal number of words of a filter
+    const MAX_WORDS: usize = 1 << 14;
+
+    /// Create a [`KeyFilter`] containing the values of the first column of the given trie.
+    /// Returns `None` if the trie has no columns.
+    pub(crate) fn from_trie(trie: &Trie) -> Option<Self> {
+        let column = trie.columns().first()?;
+        let mut values = column.iter().peekable();
+        let kind = match values.peek() {
+            Some(StorageValueT::U32(_) | StorageValueT::U64(_) | StorageValueT::U128(_)) | None => {
+                KeyKind::Unsigned
+            }
+            Some(StorageValueT::I64(_)) => KeyKind::Signed,
+            Some(StorageValueT::Float(_) | StorageValueT::Double(_)) => KeyKind::Float,
+        };
+
+        let num_words = (column.len() * Self::BITS_PER_KEY / 64)
+            .next_power_of_two()
+            .clamp(1, Self::MAX_WORDS);
+        let mut filter = Self {
+            kind,
+            words: vec![0; num_words],
+        };
+
+        for value in values {
+            filter.insert(Self::hash(value));
+        }
+
+        Some(filter)
+    }
+
+    /// Return the key of a value, such that equal values of the same [`KeyKind`] have equal keys.
+    fn key(value: StorageValueT) -> u64 {
+        let float_key = |value: f64| {
+            // Both zeros are equal, but have different bit patterns
+            if value == 0.0 {
+                0
+            } else {
+                value.to_bits()
+            }
+        };
+
+        match value {
+            StorageValueT::U32(value) => value.into(),
+            StorageValueT::U64(value) => value,
+            StorageValueT::I64(value) => value as u64,
+            StorageValueT::U128(value) => value.to_hash_bits(),
+            StorageValueT::Float(value) => float_key(f32::from(value).into()),
+            StorageValueT::Double(value) => float_key(value.into()),
+        }
+    }
+
+    /// Return a hash of a value, such that equal values of the same [`KeyKind`] have equal hashes.
+    pub(super) fn hash(value: StorageValueT) -> u64 {
+        // Finalizer of the SplitMix64 generator, which spreads similar keys over all bits
+        let mut hash = Self::key(value);
+        hash = (hash ^ (hash >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        hash = (hash ^ (hash >> 27)).wrapping_mul(0x94d049bb133111eb);
+        hash ^ (hash >> 31)
+    }
+
+    fn insert(&mut self, hash: u64) {
+        let position = (hash as usize) & (self.words.len() * 64 - 1);
+        self.words[position / 64] |= 1 << (position % 64);
+    }
+
+    /// Return the words of this filter for a filter of the given smaller size.
+    ///
+    /// Since the size of a filter is a power of two,
+    /// the bit of a key in the smaller filter only depends on its bit in this filter.
+    fn fold(&self, num_words: usize) -> Vec<u64> {
+        let mut result = vec![0; num_words];
+        for (index, word) in self.words.iter().enumerate() {
+            result[index % num_words] |= word;
+        }
+
+        result
+    }
+
+    /// Return a filter containing the values of both filters.
+    /// Returns `None` if the values cannot be compared with each other.
+    pub(crate) fn union(&self, other: &Self) -> Option<Self> {
+        if self.kind != other.kind {
+            return None;
+        }
+
+        let num_words = self.words.len().min(other.words.len());
+        let words = self
+            .fold(num_words)
+            .into_iter()
+            .zip(other.fold(num_words))
+            .map(|(left, right)| left | right)
+            .collect();
+
+        Some(Self {
+            kind: self.kind,
+            words,
+        })
+    }
+
+    /// Return whether the filters certainly do not have any value in common.
+    pub(crate) fn is_disjoint(&self, other: &Self) -> bool {
+        if self.kind != other.kind {
+            return false;
+        }
+
+        let num_words = self.words.len().min(other.words.len());
+        self.fold(num_words)
+            .into_iter()
+            .zip(other.fold(num_words))
+            .all(|(left, right)| left & right == 0)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use nemo_physical::{
+        datatypes::{Double, StorageValueT},
+        tabular::{table_types::trie::Trie, traits::table::Table},
+    };
+
+    use super::KeyFilter;
+
+    fn filter(values: &[u64]) -> KeyFilter {
+        let rows = values
+            .iter()
+            .map(|&value| vec![StorageValueT::U64(value), StorageValueT::U64(0)])
+            .collect::<Vec<_>>();
+
+        KeyFilter::from_trie(&Trie::from_rows(&rows)).unwrap()
+    }
+
+    #[test]
+    fn disjoint_filters() {
+        let small = filter(&[1, 2, 3]);
+        let large = filter(&(100..1100).collect::<Vec<_>>());
+
+        assert!(small.is_disjoint(&filter(&[4, 5])));
+        assert!(!small.is_disjoint(&filter(&[3, 4])));
+        assert!(!small.is_disjoint(&large.union(&filter(&[2])).unwrap()));
+        assert!(!large.is_disjoint(&filter(&[1099])));
+        assert!(!large.union(&small).unwrap().is_disjoint(&filter(&[1])));
+
+        let doubles = KeyFilter::from_trie(&Trie::from_rows(&[vec![StorageValueT::Double(
+            Double::new(-0.0).unwrap(),
+        )]]))
+        .unwrap();
+        let zero = KeyFilter::from_trie(&Trie::from_rows(&[vec![StorageValueT::Double(
+            Double::new(0.0).unwrap(),
+        )]]))
+        .unwrap();
+        assert!(!doubles.is_disjoint(&zero));
+        assert!(!doubles.is_disjoint(&small));
+        assert!(doubles.union(&small).is_none());
+    }
+}
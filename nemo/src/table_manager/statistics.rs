@@ -0,0 +1,155 @@
+//! Statistics about tables used to estimate the size of joins
+
+use nemo_physical::{
+    columnar::traits::column::Column,
+    tabular::{table_types::trie::Trie, traits::table::Table},
+};
+
+use super::KeyFilter;
+
+/// Sketch for estimating the number of distinct values in a column,
+/// implemented as a HyperLogLog counter.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct DistinctSketch {
+    /// For each group of values, the maximal position of the first set bit in their hashes
+    registers: Vec<u8>,
+}
+
+impl DistinctSketch {
+    /// Number of bits of a hash that select its register
+    const INDEX_BITS: u32 = 8;
+
+    /// Create a [`DistinctSketch`] containing no values.
+    fn new() -> Self {
+        Self {
+            registers: vec![0; 1 << Self::INDEX_BITS],
+        }
+    }
+
+    fn insert(&mut self, hash: u64) {
+        let index = (hash >> (64 - Self::INDEX_BITS)) as usize;
+        let rank = (hash << Self::INDEX_BITS)
+            .leading_zeros()
+            .min(64 - Self::INDEX_BITS)
+            + 1;
+
+        self.registers[index] = self.registers[index].max(rank as u8);
+    }
+
+    /// Return a sketch containing the values of both sketches.
+    pub(crate) fn union(&self, other: &Self) -> Self {
+        Self {
+            registers: self
+                .registers
+                .iter()
+                .zip(&other.registers)
+                .map(|(left, right)| *left.max(right))
+                .collect(),
+        }
+    }
+
+    /// Return the estimated number of distinct values.
+    pub(crate) fn estimate(&self) -> f64 {
+        let num_registers = self.registers.len() as f64;
+        let alpha = 0.7213 / (1.0 + 1.079 / num_registers);
+        let sum = self
+            .registers
+            .iter()
+            .map(|&register| 2f64.powi(-i32::from(register)))
+            .sum::<f64>();
+        let estimate = alpha * num_registers * num_registers / sum;
+
+        // Small numbers of values are estimated more precisely by the number of empty registers
+        let empty = self
+            .registers
+            .iter()
+            .filter(|&&register| register == 0)
+            .count();
+        if estimate <= 2.5 * num_registers && empty > 0 {
+            num_registers * (num_registers / empty as f64).ln()
+        } else {
+            estimate
+        }
+    }
+}
+
+/// Number of rows and estimated number of distinct values in each column of a table.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct TableStatistics {
+    /// Number of rows of the table
+    pub(crate) rows: usize,
+    /// For each column, a sketch of its distinct values
+    pub(crate) distinct: Vec<DistinctSketch>,
+}
+
+impl TableStatistics {
+    /// Compute the [`TableStatistics`] of the given trie.
+    pub(crate) fn from_trie(trie: &Trie) -> Self {
+        // Each value of a column occurs in the corresponding column of the trie,
+        // which does not repeat values with the same prefix
+        let distinct = trie
+            .columns()
+            .iter()
+            .map(|column| {
+                let mut sketch = DistinctSketch::new();
+                for value in column.iter() {
+                    sketch.insert(KeyFilter::hash(value));
+                }
+
+                sketch
+            })
+            .collect();
+
+        Self {
+            rows: trie.row_num(),
+            distinct,
+        }
+    }
+
+    /// Return statistics of the union of both tables,
+    /// assuming that they do not have any rows in common.
+    pub(crate) fn union(&self, other: &Self) -> Self {
+        Self {
+            rows: self.rows + other.rows,
+            distinct: self
+                .distinct
+                .iter()
+                .zip(&other.distinct)
+                .map(|(left, right)| left.union(right))
+                .collect(),
+        }
+    }
+
+    /// Return the estimated number of distinct values in the given column.
+    pub(crate) fn distinct_values(&self, column: usize) -> f64 {
+        self.distinct[column]
+            .estimate()
+            .clamp(1.0, self.rows.max(1) as f64)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use nemo_physical::{
+        datatypes::StorageValueT,
+        tabular::{table_types::trie::Trie, traits::table::Table},
+    };
+
+    use super::TableStatistics;
+
+    #[test]
+    fn distinct_values() {
+        let rows = (0..5000u64)
+            .map(|row| vec![StorageValueT::U64(row % 10), StorageValueT::U64(row)])
+            .collect::<Vec<_>>();
+        let statistics = TableStatistics::from_trie(&Trie::from_rows(&rows));
+
+        assert_eq!(statistics.rows, 5000);
+        assert!((statistics.distinct_values(0) - 10.0).abs() < 1.0);
+        assert!((statistics.distinct_values(1) / 5000.0 - 1.0).abs() < 0.2);
+
+        let union = statistics.union(&statistics);
+        assert_eq!(union.rows, 10000);
+        assert_eq!(union.distinct, statistics.distinct);
+    }
+}
@@ -1,3 +1,4 @@
 //! This module collects miscellaneous functionality.
 
 pub mod labeled_graph;
+pub mod stable_hash;
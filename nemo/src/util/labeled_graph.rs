@@ -15,7 +15,7 @@ use petgraph::{EdgeType, Graph};
 ///
 /// A [`NodeIndex`] is invalidated once a node is removed from the graph,
 /// hence the interface only permits adding new nodes.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct LabeledGraph<NodeLabel, EdgeLabel, Type>
 where
     NodeLabel: Debug + Clone + Eq + PartialEq + Hash,
@@ -0,0 +1,67 @@
+//! Hashes that are stable across runs, e.g. to identify results that are persisted on disk.
+
+use std::{hash::Hasher, io::Write};
+
+/// Offset basis of the 64-bit FNV-1a hash
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+/// Prime of the 64-bit FNV-1a hash
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+/// Computes the 64-bit FNV-1a hash of the bytes written to it.
+///
+/// In contrast to the [`DefaultHasher`][std::collections::hash_map::DefaultHasher],
+/// the result only depends on the written bytes and is therefore the same in every run.
+/// Note that this is not the case for values hashed via [`Hash`][std::hash::Hash],
+/// so only raw bytes should be written.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct StableHasher(u64);
+
+impl Default for StableHasher {
+    fn default() -> Self {
+        Self(FNV_OFFSET_BASIS)
+    }
+}
+
+impl Hasher for StableHasher {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= u64::from(*byte);
+            self.0 = self.0.wrapping_mul(FNV_PRIME);
+        }
+    }
+}
+
+impl Write for StableHasher {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        Hasher::write(self, buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::hash::Hasher;
+
+    use super::StableHasher;
+
+    #[test]
+    fn fnv_test_vectors() {
+        assert_eq!(StableHasher::default().finish(), 0xcbf2_9ce4_8422_2325);
+
+        let mut hasher = StableHasher::default();
+        hasher.write(b"a");
+        assert_eq!(hasher.finish(), 0xaf63_dc4c_8601_ec8c);
+
+        let mut hasher = StableHasher::default();
+        hasher.write(b"foo");
+        assert_eq!(hasher.finish(), 0xdcb2_7518_fed9_d577);
+    }
+}